@@ -10,6 +10,8 @@ fn main() {
         let elf = r#"
             pub const ROLLUP_ELF: &[u8] = &[];
             pub const MOCK_DA_ELF: &[u8] = &[];
+            pub const ROLLUP_ID: [u32; 8] = [0; 8];
+            pub const MOCK_DA_ID: [u32; 8] = [0; 8];
         "#;
 
         std::fs::write(methods_path, elf).expect("Failed to write mock rollup elf");
@@ -0,0 +1,288 @@
+//! Finds where the execution of an L2 block diverges between two Citrea nodes, e.g. a full node
+//! whose state root doesn't match the sequencer's.
+//!
+//! Both nodes re-execute the block with the opcode tracer, and the first differing opcode is
+//! printed with its stack and storage. If the opcodes match, the state accesses of the
+//! transactions are compared with the prestate tracer instead.
+
+use clap::Parser;
+use ethereum_rpc::TraceResult;
+use jsonrpsee::core::client::ClientT;
+use jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
+use jsonrpsee::rpc_params;
+use reth_primitives::{BlockNumberOrTag, B256};
+use reth_rpc_types::trace::geth::{
+    DefaultFrame, DiffMode, GethDebugTracingOptions, GethTrace, PreStateFrame, StructLog,
+};
+use reth_rpc_types::{BlockTransactions, RichBlock};
+use serde_json::json;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// RPC endpoint of the node to check, e.g. a full node with a mismatching state root.
+    #[arg(long, default_value = "http://127.0.0.1:12345")]
+    rpc_url: String,
+
+    /// RPC endpoint of the node to compare against, e.g. the sequencer.
+    #[arg(long)]
+    against: String,
+
+    /// Number of the L2 block to re-execute.
+    #[arg(long)]
+    height: u64,
+
+    /// Number of opcodes printed before the first differing one.
+    #[arg(long, default_value_t = 5)]
+    context: usize,
+}
+
+#[tokio::main]
+async fn main() -> Result<(), anyhow::Error> {
+    let args = Args::parse();
+
+    let local = HttpClientBuilder::default().build(&args.rpc_url)?;
+    let remote = HttpClientBuilder::default().build(&args.against)?;
+
+    let local_block = get_block(&local, args.height).await?;
+    let remote_block = get_block(&remote, args.height).await?;
+    let tx_hashes = block_tx_hashes(&local_block);
+    anyhow::ensure!(
+        tx_hashes == block_tx_hashes(&remote_block),
+        "Block {} has different transactions on the two nodes, they didn't execute the same block",
+        args.height
+    );
+    println!(
+        "Block {}: {} transactions, state root {} vs {}",
+        args.height,
+        tx_hashes.len(),
+        local_block.header.state_root,
+        remote_block.header.state_root
+    );
+
+    // the opcode tracer records the stack and the storage by default
+    let opcode_opts = GethDebugTracingOptions::default();
+    let local_traces = trace_block(&local, args.height, &opcode_opts).await?;
+    let remote_traces = trace_block(&remote, args.height, &opcode_opts).await?;
+    for (index, (local_trace, remote_trace)) in local_traces.iter().zip(&remote_traces).enumerate()
+    {
+        let (local_frame, remote_frame) = match (&local_trace.result, &remote_trace.result) {
+            (GethTrace::Default(local_frame), GethTrace::Default(remote_frame)) => {
+                (local_frame, remote_frame)
+            }
+            _ => anyhow::bail!("Transaction {} wasn't traced with the opcode tracer", index),
+        };
+        if let Some(step) = first_struct_log_difference(local_frame, remote_frame) {
+            println!("Transaction {} ({}) diverges", index, local_trace.tx_hash);
+            print_struct_logs(local_frame, remote_frame, step, args.context);
+            return Ok(());
+        }
+    }
+
+    let prestate_opts: GethDebugTracingOptions = serde_json::from_value(json!({
+        "tracer": "prestateTracer",
+        "tracerConfig": { "diffMode": true }
+    }))?;
+    for (index, tx_hash) in tx_hashes.iter().enumerate() {
+        let local_diff = trace_state_diff(&local, *tx_hash, &prestate_opts).await?;
+        let remote_diff = trace_state_diff(&remote, *tx_hash, &prestate_opts).await?;
+        if let Some(difference) = first_state_difference(&local_diff, &remote_diff) {
+            println!("Transaction {} ({}) diverges", index, tx_hash);
+            println!("{}", difference);
+            return Ok(());
+        }
+    }
+
+    match local_block.header.state_root == remote_block.header.state_root {
+        true => println!("No divergence found, the nodes executed block {} identically", args.height),
+        false => println!(
+            "The transactions executed identically, the divergence is outside of the EVM transactions of block {} (e.g. system transactions or hooks)",
+            args.height
+        ),
+    }
+    Ok(())
+}
+
+async fn get_block(client: &HttpClient, height: u64) -> anyhow::Result<RichBlock> {
+    let block: Option<RichBlock> = client
+        .request(
+            "eth_getBlockByNumber",
+            rpc_params![BlockNumberOrTag::Number(height), false],
+        )
+        .await?;
+    block.ok_or_else(|| anyhow::anyhow!("Block {} not found", height))
+}
+
+fn block_tx_hashes(block: &RichBlock) -> Vec<B256> {
+    match &block.transactions {
+        BlockTransactions::Hashes(hashes) => hashes.clone(),
+        BlockTransactions::Full(txs) => txs.iter().map(|tx| tx.hash).collect(),
+        BlockTransactions::Uncle => vec![],
+    }
+}
+
+async fn trace_block(
+    client: &HttpClient,
+    height: u64,
+    opts: &GethDebugTracingOptions,
+) -> anyhow::Result<Vec<TraceResult>> {
+    Ok(client
+        .request(
+            "debug_traceBlockByNumber",
+            rpc_params![BlockNumberOrTag::Number(height), opts],
+        )
+        .await?)
+}
+
+async fn trace_state_diff(
+    client: &HttpClient,
+    tx_hash: B256,
+    opts: &GethDebugTracingOptions,
+) -> anyhow::Result<DiffMode> {
+    let trace: GethTrace = client
+        .request("debug_traceTransaction", rpc_params![tx_hash, opts])
+        .await?;
+    match trace {
+        GethTrace::PreStateTracer(PreStateFrame::Diff(diff)) => Ok(diff),
+        _ => anyhow::bail!(
+            "Transaction {} wasn't traced with the prestate tracer",
+            tx_hash
+        ),
+    }
+}
+
+/// Returns the index of the first opcode that differs between the two traces, if any.
+fn first_struct_log_difference(local: &DefaultFrame, remote: &DefaultFrame) -> Option<usize> {
+    let step = local
+        .struct_logs
+        .iter()
+        .zip(&remote.struct_logs)
+        .position(|(local_log, remote_log)| local_log != remote_log);
+    match step {
+        Some(step) => Some(step),
+        None if local.struct_logs.len() != remote.struct_logs.len() => {
+            Some(local.struct_logs.len().min(remote.struct_logs.len()))
+        }
+        None if local.failed != remote.failed
+            || local.gas != remote.gas
+            || local.return_value != remote.return_value =>
+        {
+            Some(local.struct_logs.len())
+        }
+        None => None,
+    }
+}
+
+/// Returns a description of the first account field or storage slot the two transactions
+/// changed differently, if any.
+fn first_state_difference(local: &DiffMode, remote: &DiffMode) -> Option<String> {
+    for (side, local_states, remote_states) in [
+        ("before", &local.pre, &remote.pre),
+        ("after", &local.post, &remote.post),
+    ] {
+        let addresses = local_states.keys().chain(remote_states.keys());
+        for address in addresses {
+            let (local_state, remote_state) = (
+                local_states.get(address).cloned().unwrap_or_default(),
+                remote_states.get(address).cloned().unwrap_or_default(),
+            );
+            if local_state.balance != remote_state.balance {
+                return Some(format!(
+                    "Balance of {} {} the transaction: {:?} vs {:?}",
+                    address, side, local_state.balance, remote_state.balance
+                ));
+            }
+            if local_state.nonce != remote_state.nonce {
+                return Some(format!(
+                    "Nonce of {} {} the transaction: {:?} vs {:?}",
+                    address, side, local_state.nonce, remote_state.nonce
+                ));
+            }
+            if local_state.code != remote_state.code {
+                return Some(format!(
+                    "Code of {} {} the transaction differs",
+                    address, side
+                ));
+            }
+            let slots = local_state
+                .storage
+                .keys()
+                .chain(remote_state.storage.keys());
+            for slot in slots {
+                let (local_value, remote_value) = (
+                    local_state.storage.get(slot),
+                    remote_state.storage.get(slot),
+                );
+                if local_value != remote_value {
+                    return Some(format!(
+                        "Storage slot {} of {} {} the transaction: {:?} vs {:?}",
+                        slot, address, side, local_value, remote_value
+                    ));
+                }
+            }
+        }
+    }
+    None
+}
+
+fn print_struct_logs(local: &DefaultFrame, remote: &DefaultFrame, step: usize, context: usize) {
+    println!();
+    println!(
+        "{:>8} {:>6} {:>14} {:>10} {:>6}",
+        "step", "pc", "op", "gas", "depth"
+    );
+    for (index, log) in local
+        .struct_logs
+        .iter()
+        .enumerate()
+        .take(step)
+        .skip(step.saturating_sub(context))
+    {
+        print_struct_log(&format!("{}", index), log);
+    }
+
+    println!();
+    match local.struct_logs.get(step) {
+        Some(log) => print_struct_log("local", log),
+        None => println!(
+            "local: no more opcodes, failed: {}, gas used: {}",
+            local.failed, local.gas
+        ),
+    }
+    match remote.struct_logs.get(step) {
+        Some(log) => print_struct_log("remote", log),
+        None => println!(
+            "remote: no more opcodes, failed: {}, gas used: {}",
+            remote.failed, remote.gas
+        ),
+    }
+    for (name, log) in [
+        ("local", local.struct_logs.get(step)),
+        ("remote", remote.struct_logs.get(step)),
+    ] {
+        let Some(log) = log else {
+            continue;
+        };
+        println!();
+        println!(
+            "{} stack (top last): {:?}",
+            name,
+            log.stack.clone().unwrap_or_default()
+        );
+        println!(
+            "{} storage: {:?}",
+            name,
+            log.storage.clone().unwrap_or_default()
+        );
+        if let Some(error) = &log.error {
+            println!("{} error: {}", name, error);
+        }
+    }
+}
+
+fn print_struct_log(label: &str, log: &StructLog) {
+    println!(
+        "{:>8} {:>6} {:>14} {:>10} {:>6}",
+        label, log.pc, log.op, log.gas, log.depth
+    );
+}
@@ -0,0 +1,99 @@
+//! Exports the accounts and storage of a Citrea node at a block as a geth genesis alloc,
+//! to fork the chain, build local test fixtures or audit the state independently.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use clap::{Parser, ValueEnum};
+use ethereum_rpc::StateExport;
+use jsonrpsee::core::client::ClientT;
+use jsonrpsee::http_client::HttpClientBuilder;
+use jsonrpsee::rpc_params;
+use reth_primitives::proofs::genesis_state_root;
+use reth_primitives::{BlockNumberOrTag, U64};
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum Format {
+    /// The `alloc` object of a geth genesis file.
+    GethAlloc,
+}
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// RPC endpoint of the node.
+    #[arg(long, default_value = "http://127.0.0.1:12345")]
+    rpc_url: String,
+
+    /// Block to export the state at: a number, `earliest` or `latest`.
+    #[arg(long, value_parser = parse_block, default_value = "latest")]
+    height: BlockNumberOrTag,
+
+    /// Output format.
+    #[arg(long, value_enum, default_value = "geth-alloc")]
+    format: Format,
+
+    /// File to write the state to.
+    #[arg(long, default_value = "alloc.json")]
+    output: PathBuf,
+}
+
+fn parse_block(s: &str) -> Result<BlockNumberOrTag, String> {
+    match s.parse::<u64>() {
+        Ok(number) => Ok(BlockNumberOrTag::Number(number)),
+        Err(_) => BlockNumberOrTag::from_str(s).map_err(|e| e.to_string()),
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), anyhow::Error> {
+    let args = Args::parse();
+
+    let client = HttpClientBuilder::default().build(&args.rpc_url)?;
+
+    // Pin the height so that all pages are read from the same block
+    let mut page: StateExport = client
+        .request(
+            "citrea_exportState",
+            rpc_params![args.height, Option::<U64>::None, Option::<U64>::None],
+        )
+        .await?;
+    let block_number = page.block_number;
+    let state_root = page.state_root;
+
+    let mut alloc = BTreeMap::new();
+    loop {
+        alloc.append(&mut page.alloc);
+        let Some(offset) = page.next_offset else {
+            break;
+        };
+        page = client
+            .request(
+                "citrea_exportState",
+                rpc_params![
+                    BlockNumberOrTag::Number(block_number.to()),
+                    offset,
+                    Option::<U64>::None
+                ],
+            )
+            .await?;
+    }
+
+    match args.format {
+        Format::GethAlloc => std::fs::write(&args.output, serde_json::to_string_pretty(&alloc)?)?,
+    }
+
+    // Root of the exported state as a Merkle Patricia trie, which a geth node started
+    // from the alloc has. Citrea's own state root is a Jellyfish Merkle tree root.
+    let trie_root = genesis_state_root(&alloc.clone().into_iter().collect::<HashMap<_, _>>());
+    println!(
+        "Exported {} accounts at block {} with state root {} to {}",
+        alloc.len(),
+        block_number,
+        state_root,
+        args.output.display()
+    );
+    println!("Merkle Patricia trie root of the alloc: {}", trie_root);
+    Ok(())
+}
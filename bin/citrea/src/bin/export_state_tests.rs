@@ -0,0 +1,107 @@
+//! Exports the transactions executed by a Citrea node as execution-spec-tests state test fixtures,
+//! one file per transaction, to compare Citrea's EVM against other clients and revm upgrades.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use clap::Parser;
+use ethereum_rpc::StateTestFixture;
+use jsonrpsee::core::client::ClientT;
+use jsonrpsee::http_client::HttpClientBuilder;
+use jsonrpsee::rpc_params;
+use reth_primitives::{BlockNumberOrTag, B256};
+
+/// Max number of blocks exported in a single request.
+const BLOCKS_PER_REQUEST: u64 = 100;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// RPC endpoint of the node.
+    #[arg(long, default_value = "http://127.0.0.1:12345")]
+    rpc_url: String,
+
+    /// First block to export: a number, `earliest` or `latest`.
+    #[arg(long, value_parser = parse_block)]
+    from_block: BlockNumberOrTag,
+
+    /// Last block to export: a number, `earliest` or `latest`.
+    #[arg(long, value_parser = parse_block, default_value = "latest")]
+    to_block: BlockNumberOrTag,
+
+    /// Directory to write the fixtures to.
+    #[arg(long, default_value = "state-tests")]
+    out_dir: PathBuf,
+}
+
+fn parse_block(s: &str) -> Result<BlockNumberOrTag, String> {
+    match s.parse::<u64>() {
+        Ok(number) => Ok(BlockNumberOrTag::Number(number)),
+        Err(_) => BlockNumberOrTag::from_str(s).map_err(|e| e.to_string()),
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), anyhow::Error> {
+    let args = Args::parse();
+
+    let client = HttpClientBuilder::default().build(&args.rpc_url)?;
+    let from_block = block_number(&client, args.from_block).await?;
+    let to_block = block_number(&client, args.to_block).await?;
+    anyhow::ensure!(
+        from_block <= to_block,
+        "From block {from_block} is after to block {to_block}"
+    );
+
+    std::fs::create_dir_all(&args.out_dir)?;
+
+    let mut exported = 0;
+    let mut start = from_block;
+    while start <= to_block {
+        let end = to_block.min(start + BLOCKS_PER_REQUEST - 1);
+        let fixtures: BTreeMap<B256, StateTestFixture> = client
+            .request(
+                "citrea_exportStateTests",
+                rpc_params![
+                    BlockNumberOrTag::Number(start),
+                    BlockNumberOrTag::Number(end)
+                ],
+            )
+            .await?;
+
+        for (tx_hash, fixture) in fixtures {
+            let name = format!("{:#x}", tx_hash);
+            let path = args.out_dir.join(format!("{}.json", name));
+            let file = BTreeMap::from([(name, fixture)]);
+            std::fs::write(path, serde_json::to_string_pretty(&file)?)?;
+            exported += 1;
+        }
+        start = end + 1;
+    }
+
+    println!(
+        "Exported {} transactions of blocks {}..={} to {}",
+        exported,
+        from_block,
+        to_block,
+        args.out_dir.display()
+    );
+    Ok(())
+}
+
+/// Resolves a block tag to a block number.
+async fn block_number(
+    client: &jsonrpsee::http_client::HttpClient,
+    block: BlockNumberOrTag,
+) -> Result<u64, anyhow::Error> {
+    match block {
+        BlockNumberOrTag::Number(number) => Ok(number),
+        BlockNumberOrTag::Earliest => Ok(0),
+        _ => {
+            let number: reth_primitives::U64 =
+                client.request("eth_blockNumber", rpc_params![]).await?;
+            Ok(number.to())
+        }
+    }
+}
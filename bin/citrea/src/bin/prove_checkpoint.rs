@@ -0,0 +1,40 @@
+//! Proves the remaining segments of a proving checkpoint of the prover.
+//!
+//! The prover runs it in a fresh process when proving nears the memory limit of the host,
+//! so that a large commitment is not OOM-killed along with hours of proving work. It exits
+//! with `INTERRUPTED_EXIT_CODE` when it nears the limit in turn, and the prover runs it again.
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::Parser;
+use sov_risc0_adapter::checkpoint::{prove_segments, MemoryWatchdog, INTERRUPTED_EXIT_CODE};
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// Checkpoint directory of the proving job.
+    checkpoint_dir: PathBuf,
+
+    /// Percentage of the host memory above which proving stops.
+    #[arg(long, default_value_t = 85)]
+    max_memory_usage_percent: u8,
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+
+    let watchdog = MemoryWatchdog::new(args.max_memory_usage_percent);
+    match prove_segments(&args.checkpoint_dir, &watchdog, true) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(INTERRUPTED_EXIT_CODE as u8),
+        Err(e) => {
+            eprintln!(
+                "Failed to prove checkpoint {}: {:?}",
+                args.checkpoint_dir.display(),
+                e
+            );
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,93 @@
+//! Prints the storage rent report of a Citrea node: the state diff bytes written to DA,
+//! and the L1 fees paid for them, attributed to senders and contracts over a block range.
+
+use std::str::FromStr;
+
+use clap::Parser;
+use ethereum_rpc::{StorageRentEntry, StorageRentReport};
+use jsonrpsee::core::client::ClientT;
+use jsonrpsee::http_client::HttpClientBuilder;
+use jsonrpsee::rpc_params;
+use reth_primitives::BlockNumberOrTag;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    /// RPC endpoint of the node.
+    #[arg(long, default_value = "http://127.0.0.1:12345")]
+    rpc_url: String,
+
+    /// First block of the window: a number, `earliest` or `latest`.
+    #[arg(long, value_parser = parse_block)]
+    from_block: BlockNumberOrTag,
+
+    /// Last block of the window: a number, `earliest` or `latest`.
+    #[arg(long, value_parser = parse_block, default_value = "latest")]
+    to_block: BlockNumberOrTag,
+
+    /// Number of senders and contracts to print.
+    #[arg(long, default_value_t = 20)]
+    top: usize,
+
+    /// Print the report as JSON.
+    #[arg(long)]
+    json: bool,
+}
+
+fn parse_block(s: &str) -> Result<BlockNumberOrTag, String> {
+    match s.parse::<u64>() {
+        Ok(number) => Ok(BlockNumberOrTag::Number(number)),
+        Err(_) => BlockNumberOrTag::from_str(s).map_err(|e| e.to_string()),
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<(), anyhow::Error> {
+    let args = Args::parse();
+
+    let client = HttpClientBuilder::default().build(&args.rpc_url)?;
+    let report: StorageRentReport = client
+        .request(
+            "citrea_getStorageRentReport",
+            rpc_params![args.from_block, args.to_block],
+        )
+        .await?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+
+    println!(
+        "Blocks {}..={} (timestamps {}..={})",
+        report.from_block, report.to_block, report.from_timestamp, report.to_timestamp
+    );
+    println!(
+        "{} transactions, {} diff bytes, {} wei L1 fee",
+        report.tx_count, report.total_diff_size, report.total_l1_fee
+    );
+    print_entries("Senders", &report.by_sender, args.top);
+    print_entries("Contracts", &report.by_contract, args.top);
+
+    Ok(())
+}
+
+fn print_entries(title: &str, entries: &[StorageRentEntry], top: usize) {
+    println!();
+    println!(
+        "{:<42} {:>10} {:>14} {:>24}",
+        title, "txs", "diff bytes", "L1 fee (wei)"
+    );
+    for entry in entries.iter().take(top) {
+        println!(
+            "{:<42} {:>10} {:>14} {:>24}",
+            entry.address.to_string(),
+            entry.tx_count,
+            entry.diff_size,
+            entry.l1_fee
+        );
+    }
+    if entries.len() > top {
+        println!("... and {} more", entries.len() - top);
+    }
+}
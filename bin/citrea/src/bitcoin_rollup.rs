@@ -1,11 +1,13 @@
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use bitcoin_da::service::{BitcoinService, DaServiceConfig};
-use bitcoin_da::spec::{BitcoinSpec, RollupParams};
+use bitcoin_da::spec::BitcoinSpec;
 use bitcoin_da::verifier::BitcoinVerifier;
 use citrea_stf::genesis_config::StorageConfig;
 use citrea_stf::runtime::Runtime;
 use const_rollup_config::{DA_TX_ID_LEADING_ZEROS, ROLLUP_NAME};
-use sequencer_client::SequencerClient;
+use sequencer_client::{LocalMempool, SequencerClient};
 use sov_db::ledger_db::LedgerDB;
 use sov_modules_api::default_context::{DefaultContext, ZkDefaultContext};
 use sov_modules_api::{Address, Spec};
@@ -14,10 +16,13 @@ use sov_modules_stf_blueprint::kernels::basic::BasicKernel;
 use sov_modules_stf_blueprint::StfBlueprint;
 use sov_prover_storage_manager::ProverStorageManager;
 use sov_risc0_adapter::host::Risc0Host;
+use sov_risc0_adapter::Risc0MethodId;
 use sov_rollup_interface::da::DaVerifier;
 use sov_rollup_interface::zk::ZkvmHost;
 use sov_state::{DefaultStorageSpec, Storage, ZkStorage};
-use sov_stf_runner::{ParallelProverService, RollupConfig, RollupProverConfig};
+use sov_stf_runner::{
+    HttpProofMarket, ParallelProverService, RollupConfig, RollupProverConfig, RpcConfig,
+};
 
 /// Rollup with BitcoinDa
 pub struct BitcoinRollup {}
@@ -64,6 +69,8 @@ impl RollupBlueprint for BitcoinRollup {
         ledger_db: &LedgerDB,
         da_service: &Self::DaService,
         sequencer_client: Option<SequencerClient>,
+        local_mempool: Option<LocalMempool>,
+        rpc_config: &RpcConfig,
     ) -> Result<jsonrpsee::RpcModule<()>, anyhow::Error> {
         // unused inside register RPC
         let sov_sequencer = Address::new([0; 32]);
@@ -78,10 +85,15 @@ impl RollupBlueprint for BitcoinRollup {
         crate::eth::register_ethereum::<Self::DaService>(
             da_service.clone(),
             storage.clone(),
+            ledger_db,
             &mut rpc_methods,
             sequencer_client,
+            local_mempool,
+            rpc_config,
         )?;
 
+        crate::compact_relay::register_compact_relay::<Self::DaSpec>(ledger_db, &mut rpc_methods)?;
+
         Ok(rpc_methods)
     }
 
@@ -99,14 +111,11 @@ impl RollupBlueprint for BitcoinRollup {
         &self,
         rollup_config: &RollupConfig<Self::DaConfig>,
     ) -> Self::DaService {
-        BitcoinService::new(
-            rollup_config.da.clone(),
-            RollupParams {
-                rollup_name: ROLLUP_NAME.to_string(),
-                reveal_tx_id_prefix: DA_TX_ID_LEADING_ZEROS.to_vec(),
-            },
-        )
-        .await
+        let rollup_params = rollup_config
+            .da
+            .rollup_params(ROLLUP_NAME, DA_TX_ID_LEADING_ZEROS)
+            .expect("Invalid bitcoin DA config");
+        BitcoinService::new(rollup_config.da.clone(), rollup_params).await
     }
 
     async fn create_prover_service(
@@ -115,22 +124,34 @@ impl RollupBlueprint for BitcoinRollup {
         rollup_config: &RollupConfig<Self::DaConfig>,
         _da_service: &Self::DaService,
     ) -> Self::ProverService {
-        let vm = Risc0Host::new(risc0::ROLLUP_ELF);
+        let vm = crate::create_risc0_host(risc0::ROLLUP_ELF, &rollup_config.prover_service);
         let zk_stf = StfBlueprint::new();
         let zk_storage = ZkStorage::new();
 
-        let da_verifier = BitcoinVerifier::new(RollupParams {
-            rollup_name: ROLLUP_NAME.to_string(),
-            reveal_tx_id_prefix: DA_TX_ID_LEADING_ZEROS.to_vec(),
-        });
+        let da_verifier = BitcoinVerifier::new(
+            rollup_config
+                .da
+                .rollup_params(ROLLUP_NAME, DA_TX_ID_LEADING_ZEROS)
+                .expect("Invalid bitcoin DA config"),
+        );
 
-        ParallelProverService::new_with_default_workers(
+        let prover_service = ParallelProverService::new_with_default_workers(
             vm,
             zk_stf,
             da_verifier,
             prover_config,
             zk_storage,
-            rollup_config.prover_service,
+            rollup_config.prover_service.clone(),
         )
+        .with_self_check(Risc0MethodId::new(risc0::ROLLUP_ID));
+
+        match &rollup_config.prover_service.proof_market {
+            Some(market_config) => prover_service.with_proof_market(
+                Arc::new(HttpProofMarket::new(market_config.clone())),
+                market_config,
+                Risc0MethodId::new(risc0::ROLLUP_ID),
+            ),
+            None => prover_service,
+        }
     }
 }
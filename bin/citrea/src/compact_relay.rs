@@ -0,0 +1,91 @@
+use std::collections::HashSet;
+
+use anyhow::Context as _;
+use borsh::BorshDeserialize;
+use citrea_stf::runtime::{Runtime, RuntimeCall};
+use jsonrpsee::types::ErrorObjectOwned;
+use reth_primitives::{keccak256, Bytes, B256};
+use sequencer_client::{CompactSoftBatch, CompactTx};
+use sov_db::ledger_db::LedgerDB;
+use sov_modules_api::default_context::DefaultContext;
+use sov_modules_api::transaction::Transaction;
+use sov_modules_api::utils::to_jsonrpsee_error_object;
+use sov_modules_api::DispatchCall;
+use sov_rollup_interface::da::DaSpec;
+use sov_rollup_interface::rpc::{LedgerRpcProvider, SoftBatchResponse};
+
+const COMPACT_RELAY_RPC_ERROR: &str = "COMPACT_RELAY_RPC_ERROR";
+
+// register the methods serving soft batches in compact form to downstream full nodes.
+pub(crate) fn register_compact_relay<Da: DaSpec>(
+    ledger_db: &LedgerDB,
+    methods: &mut jsonrpsee::RpcModule<()>,
+) -> Result<(), anyhow::Error> {
+    let mut rpc = jsonrpsee::RpcModule::new(ledger_db.clone());
+
+    rpc.register_method(
+        "citrea_getCompactSoftBatchByNumber",
+        |params, ledger| -> Result<Option<CompactSoftBatch<SoftBatchResponse>>, ErrorObjectOwned> {
+            let number: u64 = params.one()?;
+            let Some(mut soft_batch) = get_soft_batch(ledger, number)? else {
+                return Ok(None);
+            };
+            let compact_txs = soft_batch
+                .txs
+                .take()
+                .unwrap_or_default()
+                .iter()
+                .map(|tx| CompactTx::new(&tx.tx, &evm_tx_bodies::<Da>(&tx.tx)))
+                .collect();
+            Ok(Some(CompactSoftBatch {
+                soft_batch,
+                compact_txs,
+            }))
+        },
+    )?;
+
+    rpc.register_method(
+        "citrea_getSoftBatchTxBodies",
+        |params, ledger| -> Result<Vec<Bytes>, ErrorObjectOwned> {
+            let (number, hashes): (u64, HashSet<B256>) = params.parse()?;
+            let Some(soft_batch) = get_soft_batch(ledger, number)? else {
+                return Ok(vec![]);
+            };
+            Ok(soft_batch
+                .txs
+                .unwrap_or_default()
+                .iter()
+                .flat_map(|tx| evm_tx_bodies::<Da>(&tx.tx))
+                .filter(|body| hashes.contains(&keccak256(body)))
+                .map(Bytes::from)
+                .collect())
+        },
+    )?;
+
+    methods
+        .merge(rpc)
+        .context("Failed to merge compact relay RPC modules")
+}
+
+fn get_soft_batch(
+    ledger: &LedgerDB,
+    number: u64,
+) -> Result<Option<SoftBatchResponse>, ErrorObjectOwned> {
+    ledger
+        .get_soft_batch_by_number::<()>(number)
+        .map_err(|e| to_jsonrpsee_error_object(e, COMPACT_RELAY_RPC_ERROR))
+}
+
+/// Raw EVM transactions inside a sovereign transaction of a soft batch,
+/// in the order they are encoded.
+fn evm_tx_bodies<Da: DaSpec>(tx: &[u8]) -> Vec<Vec<u8>> {
+    let Ok(tx) = Transaction::<DefaultContext>::try_from_slice(tx) else {
+        return vec![];
+    };
+    match Runtime::<DefaultContext, Da>::decode_call(tx.runtime_msg()) {
+        Ok(RuntimeCall::evm(call_message)) => {
+            call_message.txs.into_iter().map(|tx| tx.rlp).collect()
+        }
+        _ => vec![],
+    }
+}
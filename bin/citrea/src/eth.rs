@@ -1,40 +1,106 @@
 use std::str::FromStr;
+use std::sync::Arc;
 
 use anyhow::Context as _;
-use ethereum_rpc::{EthRpcConfig, FeeHistoryCacheConfig, GasPriceOracleConfig};
-use sequencer_client::SequencerClient;
+use ethereum_rpc::{
+    AbiRegistry, ContractVerificationStore, EthRpcConfig, FeeHistoryCacheConfig,
+    GasPriceOracleConfig, ResponseCacheConfig,
+};
+use reth_primitives::U256;
+use sequencer_client::{LocalMempool, SequencerClient};
+use sov_db::ledger_db::LedgerDB;
 use sov_modules_api::default_context::DefaultContext;
 use sov_prover_storage_manager::SnapshotManager;
 use sov_rollup_interface::services::da::DaService;
 use sov_state::ProverStorage;
+use sov_stf_runner::RpcConfig;
+use tracing::info;
 
 // register ethereum methods.
 pub(crate) fn register_ethereum<Da: DaService>(
     da_service: Da,
     storage: ProverStorage<sov_state::DefaultStorageSpec, SnapshotManager>,
+    ledger_db: &LedgerDB,
     methods: &mut jsonrpsee::RpcModule<()>,
     sequencer_client: Option<SequencerClient>,
+    local_mempool: Option<LocalMempool>,
+    rpc_config: &RpcConfig,
 ) -> Result<(), anyhow::Error> {
+    let abi_registry = match &rpc_config.abi_registry_path {
+        Some(path) => {
+            let registry = AbiRegistry::from_dir(path)?;
+            info!(
+                "Loaded {} custom errors from ABI registry at {}",
+                registry.len(),
+                path.display()
+            );
+            Some(Arc::new(registry))
+        }
+        None => None,
+    };
+
+    let contract_verification_store = match &rpc_config.contract_verification_path {
+        Some(path) => {
+            let store = ContractVerificationStore::open(path)?;
+            info!("Serving verified contracts from {}", path.display());
+            Some(Arc::new(store))
+        }
+        None => None,
+    };
+
     let eth_rpc_config = {
         let eth_signer = eth_dev_signer();
         EthRpcConfig {
             eth_signer,
-            gas_price_oracle_config: GasPriceOracleConfig::default(),
+            gas_price_oracle_config: gas_price_oracle_config(rpc_config),
             fee_history_cache_config: FeeHistoryCacheConfig::default(),
+            response_cache_config: ResponseCacheConfig::default(),
+            state_history_blocks: (!rpc_config.archive).then_some(rpc_config.state_history_blocks),
+            abi_registry,
+            contract_verification_store,
+            index_transactions_by_address: rpc_config.index_transactions_by_address,
         }
     };
 
+    if sequencer_client
+        .as_ref()
+        .is_some_and(SequencerClient::streams_receipts)
+    {
+        for method in ethereum_rpc::RECEIPT_STREAMING_METHODS {
+            methods.remove_method(method);
+        }
+    }
+
     let ethereum_rpc = ethereum_rpc::get_ethereum_rpc::<DefaultContext, Da>(
         da_service,
         eth_rpc_config,
         storage,
         sequencer_client,
+        ledger_db.subscribe_soft_batches(),
+        ledger_db.clone(),
+        local_mempool,
     );
     methods
         .merge(ethereum_rpc)
         .context("Failed to merge Ethereum RPC modules")
 }
 
+/// Returns the config of the gas price oracle set in the RPC config, or the default config.
+fn gas_price_oracle_config(rpc_config: &RpcConfig) -> GasPriceOracleConfig {
+    let Some(oracle) = &rpc_config.gas_price_oracle else {
+        return GasPriceOracleConfig::default();
+    };
+    let mut config = GasPriceOracleConfig::new(
+        oracle.blocks,
+        oracle.ignore_price,
+        oracle.max_price,
+        oracle.percentile,
+    );
+    config.default = oracle.default_tip.map(U256::from);
+    config.sample_mempool = oracle.sample_mempool;
+    config
+}
+
 // TODO: #840
 fn eth_dev_signer() -> ethereum_rpc::DevSigner {
     ethereum_rpc::DevSigner::new(vec![secp256k1::SecretKey::from_str(
@@ -0,0 +1,237 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context as _};
+use bitcoin_da::network::{BitcoinNetwork, SequencerAddressType};
+use clap::{Args, Subcommand, ValueEnum};
+use reth_primitives::{hex, public_key_to_address};
+use secp256k1::{PublicKey, SecretKey, SECP256K1};
+use serde::Serialize;
+
+/// Kind of the key material.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum KeyKind {
+    /// Key of the sequencer or the prover signing their blobs on Bitcoin DA.
+    Da,
+    /// Key of an EVM account, like the governance key or the system signer.
+    Evm,
+}
+
+/// Generates and inspects the keys set in the node configs.
+#[derive(Subcommand, Debug)]
+pub(crate) enum KeysCommand {
+    /// Generates a new key and prints its public key and addresses.
+    Generate {
+        #[command(flatten)]
+        key: KeyArgs,
+        /// Directory to write the key to as an encrypted keystore file. The private key is
+        /// printed if not set.
+        #[arg(long, requires = "password_file")]
+        keystore_dir: Option<PathBuf>,
+    },
+    /// Prints the public key and addresses of a key.
+    Inspect {
+        #[command(flatten)]
+        key: KeyArgs,
+        /// Hex encoded private key.
+        #[arg(
+            long,
+            required_unless_present = "keystore",
+            conflicts_with = "keystore"
+        )]
+        private_key: Option<String>,
+        /// Encrypted keystore file of the key.
+        #[arg(long, requires = "password_file")]
+        keystore: Option<PathBuf>,
+        /// Also prints the private key, e.g. to set it in a config file.
+        #[arg(long)]
+        show_private_key: bool,
+    },
+}
+
+#[derive(Args, Debug)]
+pub(crate) struct KeyArgs {
+    /// The kind of the key.
+    #[arg(long)]
+    kind: KeyKind,
+    /// The Bitcoin network of the addresses of DA keys.
+    #[arg(long, default_value = "regtest")]
+    network: BitcoinNetwork,
+    /// File holding the password of the keystore file on its first line.
+    #[arg(long)]
+    password_file: Option<PathBuf>,
+}
+
+/// Public material of a key, in the format of the node configs.
+#[derive(Debug, PartialEq, Serialize)]
+struct KeyDetails {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    private_key: Option<String>,
+    /// Compressed public key, the `sequencer_da_pub_key` or `prover_da_pub_key` of DA keys.
+    public_key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    p2wpkh_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    p2tr_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    keystore: Option<PathBuf>,
+}
+
+/// Runs a `citrea keys` command, printing the key details as JSON.
+pub(crate) fn run(command: KeysCommand) -> anyhow::Result<()> {
+    let details = match command {
+        KeysCommand::Generate { key, keystore_dir } => {
+            let secret_key = SecretKey::new(&mut secp256k1::rand::thread_rng());
+            let mut details = key_details(&secret_key, key.kind, key.network);
+            if let Some(dir) = keystore_dir {
+                let password = read_password(key.password_file.as_deref())?;
+                let name = format!("citrea-{}-{}", key.kind_name(), &details.public_key[..16]);
+                details.keystore = Some(write_keystore(&dir, &name, &secret_key, &password)?);
+            } else {
+                details.private_key = Some(hex::encode(secret_key.secret_bytes()));
+            }
+            details
+        }
+        KeysCommand::Inspect {
+            key,
+            private_key,
+            keystore,
+            show_private_key,
+        } => {
+            let secret_key = match (private_key, keystore) {
+                (Some(private_key), _) => parse_private_key(&private_key)?,
+                (None, Some(keystore)) => {
+                    let password = read_password(key.password_file.as_deref())?;
+                    read_keystore(&keystore, &password)?
+                }
+                (None, None) => unreachable!("Either the private key or the keystore is set"),
+            };
+            let mut details = key_details(&secret_key, key.kind, key.network);
+            if show_private_key {
+                details.private_key = Some(hex::encode(secret_key.secret_bytes()));
+            }
+            details
+        }
+    };
+
+    println!("{}", serde_json::to_string_pretty(&details)?);
+    Ok(())
+}
+
+impl KeyArgs {
+    fn kind_name(&self) -> &'static str {
+        match self.kind {
+            KeyKind::Da => "da",
+            KeyKind::Evm => "evm",
+        }
+    }
+}
+
+fn key_details(secret_key: &SecretKey, kind: KeyKind, network: BitcoinNetwork) -> KeyDetails {
+    let public_key = PublicKey::from_secret_key(SECP256K1, secret_key);
+    let mut details = KeyDetails {
+        private_key: None,
+        public_key: hex::encode(public_key.serialize()),
+        address: None,
+        p2wpkh_address: None,
+        p2tr_address: None,
+        keystore: None,
+    };
+    match kind {
+        KeyKind::Da => {
+            let public_key = bitcoin::secp256k1::PublicKey::from_slice(&public_key.serialize())
+                .expect("Public keys are valid across secp256k1 versions");
+            let address = |address_type: SequencerAddressType| {
+                address_type
+                    .derive_address(&public_key, network)
+                    .to_string()
+            };
+            details.p2wpkh_address = Some(address(SequencerAddressType::P2wpkh));
+            details.p2tr_address = Some(address(SequencerAddressType::P2tr));
+        }
+        KeyKind::Evm => {
+            details.address = Some(public_key_to_address(public_key).to_checksum(None));
+        }
+    }
+    details
+}
+
+fn parse_private_key(private_key: &str) -> anyhow::Result<SecretKey> {
+    let bytes = hex::decode(private_key.trim()).context("Private key is not hex encoded")?;
+    SecretKey::from_slice(&bytes).map_err(|e| anyhow!("Invalid private key: {}", e))
+}
+
+fn read_password(password_file: Option<&Path>) -> anyhow::Result<String> {
+    let password_file = password_file.context("A password file is required for keystores")?;
+    let password = std::fs::read_to_string(password_file)
+        .with_context(|| format!("Failed to read password file {}", password_file.display()))?;
+    Ok(password.lines().next().unwrap_or_default().to_string())
+}
+
+/// Writes the key to `dir` as a Web3 Secret Storage keystore file, returning its path.
+fn write_keystore(
+    dir: &Path,
+    name: &str,
+    secret_key: &SecretKey,
+    password: &str,
+) -> anyhow::Result<PathBuf> {
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create keystore directory {}", dir.display()))?;
+    eth_keystore::encrypt_key(
+        dir,
+        &mut secp256k1::rand::thread_rng(),
+        secret_key.secret_bytes(),
+        password,
+        Some(name),
+    )
+    .map_err(|e| anyhow!("Failed to write keystore: {}", e))?;
+    Ok(dir.join(name))
+}
+
+fn read_keystore(path: &Path, password: &str) -> anyhow::Result<SecretKey> {
+    let bytes = eth_keystore::decrypt_key(path, password)
+        .map_err(|e| anyhow!("Failed to decrypt keystore {}: {}", path.display(), e))?;
+    SecretKey::from_slice(&bytes).map_err(|e| anyhow!("Invalid private key in keystore: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derives_public_material() {
+        let secret_key =
+            parse_private_key("0000000000000000000000000000000000000000000000000000000000000001")
+                .unwrap();
+
+        let details = key_details(&secret_key, KeyKind::Da, BitcoinNetwork::Mainnet);
+        assert_eq!(
+            details.public_key,
+            "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"
+        );
+        assert_eq!(
+            details.p2wpkh_address.as_deref(),
+            Some("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4")
+        );
+        assert!(details.p2tr_address.unwrap().starts_with("bc1p"));
+        assert_eq!(details.address, None);
+
+        let details = key_details(&secret_key, KeyKind::Evm, BitcoinNetwork::Regtest);
+        assert_eq!(
+            details.address.as_deref(),
+            Some("0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf")
+        );
+        assert_eq!(details.p2wpkh_address, None);
+    }
+
+    #[test]
+    fn keystore_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let secret_key = SecretKey::new(&mut secp256k1::rand::thread_rng());
+
+        let path = write_keystore(dir.path(), "citrea-da-test", &secret_key, "password").unwrap();
+        assert_eq!(read_keystore(&path, "password").unwrap(), secret_key);
+        assert!(read_keystore(&path, "wrong password").is_err());
+    }
+}
@@ -4,27 +4,58 @@
 use std::env;
 use std::str::FromStr;
 
+use sov_risc0_adapter::checkpoint::CheckpointConfig;
+use sov_risc0_adapter::host::Risc0Host;
+use sov_stf_runner::ProverServiceConfig;
+
 mod mock_rollup;
 pub use mock_rollup::*;
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
-use tracing_subscriber::{fmt, EnvFilter};
+use tracing_subscriber::{fmt, EnvFilter, Layer};
 
+mod compact_relay;
 mod eth;
 
 mod bitcoin_rollup;
 pub use bitcoin_rollup::*;
 
+mod node;
+pub use node::*;
+
 /// Default initialization of logging
 pub fn initialize_logging() {
-    tracing_subscriber::registry()
-        .with(fmt::layer())
-        .with(
-            EnvFilter::from_str(
-                &env::var("RUST_LOG")
-                    .unwrap_or_else(|_| "debug,hyper=info,risc0_zkvm=info".to_string()),
-            )
-            .unwrap(),
-        )
-        .init();
+    let env_filter = EnvFilter::from_str(
+        &env::var("RUST_LOG").unwrap_or_else(|_| "debug,hyper=info,risc0_zkvm=info".to_string()),
+    )
+    .unwrap();
+    let registry = tracing_subscriber::registry().with(fmt::layer().with_filter(env_filter));
+
+    // Serves the async tasks of the node to tokio-console, on 127.0.0.1:6669 unless set otherwise
+    // by `TOKIO_CONSOLE_BIND`. Needs to be built with `RUSTFLAGS="--cfg tokio_unstable"`.
+    #[cfg(feature = "tokio-console")]
+    let registry = registry.with(console_subscriber::spawn());
+
+    registry.init();
+}
+
+/// Creates the host proving the guest binary, with checkpoints if the memory watchdog is set.
+fn create_risc0_host(
+    elf: &'static [u8],
+    prover_service_config: &ProverServiceConfig,
+) -> Risc0Host<'static> {
+    let host = Risc0Host::new(elf);
+    let Some(watchdog) = &prover_service_config.memory_watchdog else {
+        return host;
+    };
+    let continuation_bin = watchdog.continuation_bin.clone().unwrap_or_else(|| {
+        env::current_exe()
+            .expect("Failed to get the path of the node binary")
+            .with_file_name("prove-checkpoint")
+    });
+    host.with_checkpoints(CheckpointConfig {
+        dir: watchdog.checkpoint_dir.clone(),
+        max_memory_usage_percent: watchdog.max_memory_usage_percent,
+        continuation_bin,
+    })
 }
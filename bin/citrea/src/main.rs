@@ -1,8 +1,8 @@
 use core::fmt::Debug as DebugTrait;
 
-use anyhow::{anyhow, Context as _};
+use anyhow::Context as _;
 use bitcoin_da::service::DaServiceConfig;
-use citrea::{initialize_logging, BitcoinRollup, MockDemoRollup};
+use citrea::{initialize_logging, BitcoinRollup, CitreaNodeBuilder, MockDemoRollup, NodeMode};
 use citrea_sequencer::SequencerConfig;
 use citrea_stf::genesis_config::GenesisPaths;
 use clap::Parser;
@@ -16,6 +16,7 @@ use sov_modules_stf_blueprint::kernels::basic::{
 use sov_state::storage::NativeStorage;
 use sov_stf_runner::{from_toml_path, RollupConfig, RollupProverConfig};
 
+mod keys;
 #[cfg(test)]
 mod test_rpc;
 
@@ -24,12 +25,15 @@ mod test_rpc;
 /// is the one you want. You can run it `cargo run --bin sov-cli`.
 
 #[derive(Parser, Debug)]
-#[command(author, version, about, long_about = None)]
+#[command(author, version, about, long_about = None, subcommand_negates_reqs = true)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// Path to the genesis configuration.
     /// Defines the genesis of module states like evm.
-    #[arg(long)]
-    genesis_paths: String,
+    #[arg(long, required = true)]
+    genesis_paths: Option<String>,
 
     /// The data layer type.
     #[arg(long, default_value = "mock")]
@@ -49,6 +53,13 @@ struct Args {
     prover: bool,
 }
 
+#[derive(clap::Subcommand, Debug)]
+enum Command {
+    /// Generates and inspects DA and EVM keys.
+    #[command(subcommand)]
+    Keys(keys::KeysCommand),
+}
+
 #[derive(clap::ValueEnum, Clone, Debug)]
 enum SupportedDaLayer {
     Mock,
@@ -57,9 +68,16 @@ enum SupportedDaLayer {
 
 #[tokio::main]
 async fn main() -> Result<(), anyhow::Error> {
+    let args = Args::parse();
+    if let Some(Command::Keys(command)) = args.command {
+        return keys::run(command);
+    }
+
     initialize_logging();
 
-    let args = Args::parse();
+    let genesis_paths = args
+        .genesis_paths
+        .expect("Genesis paths are required without a subcommand");
     let rollup_config_path = args.rollup_config_path.as_str();
 
     let sequencer_config: Option<SequencerConfig> =
@@ -73,7 +91,7 @@ async fn main() -> Result<(), anyhow::Error> {
     match args.da_layer {
         SupportedDaLayer::Mock => {
             let kernel_genesis_paths = &BasicKernelGenesisPaths {
-                chain_state: (args.genesis_paths.clone() + "/chain_state.json").into(),
+                chain_state: (genesis_paths.clone() + "/chain_state.json").into(),
             };
 
             let kernel_genesis = BasicKernelGenesisConfig {
@@ -84,7 +102,7 @@ async fn main() -> Result<(), anyhow::Error> {
             };
 
             start_rollup::<MockDemoRollup, MockDaConfig>(
-                &GenesisPaths::from_dir(&args.genesis_paths),
+                &GenesisPaths::from_dir(&genesis_paths),
                 kernel_genesis,
                 rollup_config_path,
                 RollupProverConfig::Execute,
@@ -95,7 +113,7 @@ async fn main() -> Result<(), anyhow::Error> {
         }
         SupportedDaLayer::Bitcoin => {
             let kernel_genesis_paths = &BasicKernelGenesisPaths {
-                chain_state: (args.genesis_paths.clone() + "/chain_state.json").into(),
+                chain_state: (genesis_paths.clone() + "/chain_state.json").into(),
             };
 
             let kernel_genesis = BasicKernelGenesisConfig {
@@ -106,7 +124,7 @@ async fn main() -> Result<(), anyhow::Error> {
             };
 
             start_rollup::<BitcoinRollup, DaServiceConfig>(
-                &GenesisPaths::from_dir(&args.genesis_paths),
+                &GenesisPaths::from_dir(&genesis_paths),
                 kernel_genesis,
                 rollup_config_path,
                 RollupProverConfig::Execute,
@@ -143,40 +161,21 @@ where
     S: RollupBlueprint<DaConfig = DaC>,
     <<S as RollupBlueprint>::NativeContext as Spec>::Storage: NativeStorage,
 {
-    let mut rollup_config: RollupConfig<DaC> = from_toml_path(rollup_config_path)
+    let rollup_config: RollupConfig<DaC> = from_toml_path(rollup_config_path)
         .context("Failed to read rollup configuration")
         .unwrap();
-    let rollup_blueprint = S::new();
-
-    if let Some(sequencer_config) = sequencer_config {
-        rollup_config.sequencer_client = None;
 
-        let sequencer_rollup = rollup_blueprint
-            .create_new_sequencer(
-                rt_genesis_paths,
-                kernel_genesis,
-                rollup_config.clone(),
-                sequencer_config,
-            )
-            .await
-            .unwrap();
-        sequencer_rollup.run().await?;
-    } else {
-        if rollup_config.sequencer_client.is_none() {
-            return Err(anyhow!("Must have sequencer client for full nodes!"));
-        }
-        let rollup = rollup_blueprint
-            .create_new_rollup(
-                rt_genesis_paths,
-                kernel_genesis,
-                rollup_config,
-                prover_config,
-                is_prover,
-            )
-            .await
-            .unwrap();
-        rollup.run().await?;
-    }
+    let mode = match sequencer_config {
+        Some(sequencer_config) => NodeMode::Sequencer(sequencer_config),
+        None if is_prover => NodeMode::Prover(prover_config),
+        None => NodeMode::FullNode,
+    };
+
+    let node = CitreaNodeBuilder::<S>::new(rollup_config)
+        .with_mode(mode)
+        .build(rt_genesis_paths, kernel_genesis)
+        .await?;
+    node.run().await?;
 
     Ok(())
 }
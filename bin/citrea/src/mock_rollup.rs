@@ -1,7 +1,9 @@
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use citrea_stf::genesis_config::StorageConfig;
 use citrea_stf::runtime::Runtime;
-use sequencer_client::SequencerClient;
+use sequencer_client::{LocalMempool, SequencerClient};
 use sov_db::ledger_db::LedgerDB;
 use sov_mock_da::{MockDaConfig, MockDaService, MockDaSpec};
 use sov_modules_api::default_context::{DefaultContext, ZkDefaultContext};
@@ -11,9 +13,12 @@ use sov_modules_stf_blueprint::kernels::basic::BasicKernel;
 use sov_modules_stf_blueprint::StfBlueprint;
 use sov_prover_storage_manager::ProverStorageManager;
 use sov_risc0_adapter::host::Risc0Host;
+use sov_risc0_adapter::Risc0MethodId;
 use sov_rollup_interface::zk::ZkvmHost;
 use sov_state::{DefaultStorageSpec, Storage, ZkStorage};
-use sov_stf_runner::{ParallelProverService, RollupConfig, RollupProverConfig};
+use sov_stf_runner::{
+    HttpProofMarket, ParallelProverService, RollupConfig, RollupProverConfig, RpcConfig,
+};
 
 /// Rollup with MockDa
 pub struct MockDemoRollup {}
@@ -60,6 +65,8 @@ impl RollupBlueprint for MockDemoRollup {
         ledger_db: &LedgerDB,
         da_service: &Self::DaService,
         sequencer_client: Option<SequencerClient>,
+        local_mempool: Option<LocalMempool>,
+        rpc_config: &RpcConfig,
     ) -> Result<jsonrpsee::RpcModule<()>, anyhow::Error> {
         // TODO set the sequencer address
         let sequencer = Address::new([0; 32]);
@@ -74,10 +81,15 @@ impl RollupBlueprint for MockDemoRollup {
         crate::eth::register_ethereum::<Self::DaService>(
             da_service.clone(),
             storage.clone(),
+            ledger_db,
             &mut rpc_methods,
             sequencer_client,
+            local_mempool,
+            rpc_config,
         )?;
 
+        crate::compact_relay::register_compact_relay::<Self::DaSpec>(ledger_db, &mut rpc_methods)?;
+
         Ok(rpc_methods)
     }
 
@@ -94,19 +106,29 @@ impl RollupBlueprint for MockDemoRollup {
         rollup_config: &RollupConfig<Self::DaConfig>,
         _da_service: &Self::DaService,
     ) -> Self::ProverService {
-        let vm = Risc0Host::new(risc0::MOCK_DA_ELF);
+        let vm = crate::create_risc0_host(risc0::MOCK_DA_ELF, &rollup_config.prover_service);
         let zk_stf = StfBlueprint::new();
         let zk_storage = ZkStorage::new();
         let da_verifier = Default::default();
 
-        ParallelProverService::new_with_default_workers(
+        let prover_service = ParallelProverService::new_with_default_workers(
             vm,
             zk_stf,
             da_verifier,
             prover_config,
             zk_storage,
-            rollup_config.prover_service,
+            rollup_config.prover_service.clone(),
         )
+        .with_self_check(Risc0MethodId::new(risc0::MOCK_DA_ID));
+
+        match &rollup_config.prover_service.proof_market {
+            Some(market_config) => prover_service.with_proof_market(
+                Arc::new(HttpProofMarket::new(market_config.clone())),
+                market_config,
+                Risc0MethodId::new(risc0::MOCK_DA_ID),
+            ),
+            None => prover_service,
+        }
     }
 
     fn create_storage_manager(
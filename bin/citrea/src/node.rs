@@ -0,0 +1,195 @@
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+use anyhow::anyhow;
+use citrea_sequencer::SequencerConfig;
+use sov_modules_api::runtime::capabilities::Kernel;
+use sov_modules_api::Spec;
+use sov_modules_rollup_blueprint::{Rollup, RollupBlueprint, Sequencer};
+use sov_modules_stf_blueprint::Runtime as RuntimeTrait;
+use sov_state::storage::NativeStorage;
+use sov_stf_runner::{RollupConfig, RollupProverConfig};
+use tokio::sync::oneshot;
+use tracing::info;
+
+/// The mode a Citrea node runs in.
+pub enum NodeMode {
+    /// Produces soft confirmations from its own mempool.
+    Sequencer(SequencerConfig),
+    /// Follows the sequencer and verifies its commitments.
+    FullNode,
+    /// Follows the sequencer and proves the state transitions.
+    Prover(RollupProverConfig),
+}
+
+/// Builds a Citrea node from a [`RollupBlueprint`].
+///
+/// Services that are not injected are created by the blueprint from the rollup config,
+/// which is what the `citrea` binary does.
+pub struct CitreaNodeBuilder<S: RollupBlueprint> {
+    blueprint: S,
+    rollup_config: RollupConfig<S::DaConfig>,
+    mode: NodeMode,
+    da_service: Option<S::DaService>,
+    prover_service: Option<S::ProverService>,
+    rpc_methods: Vec<jsonrpsee::RpcModule<()>>,
+}
+
+impl<S: RollupBlueprint> CitreaNodeBuilder<S> {
+    /// Creates a builder for a full node with the given rollup config.
+    pub fn new(rollup_config: RollupConfig<S::DaConfig>) -> Self {
+        Self {
+            blueprint: S::new(),
+            rollup_config,
+            mode: NodeMode::FullNode,
+            da_service: None,
+            prover_service: None,
+            rpc_methods: vec![],
+        }
+    }
+
+    /// Sets the mode of the node.
+    pub fn with_mode(mut self, mode: NodeMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Overrides the path of the node's databases.
+    pub fn with_storage_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.rollup_config.storage.path = path.into();
+        self
+    }
+
+    /// Uses the given DA service instead of creating one from the rollup config.
+    pub fn with_da_service(mut self, da_service: S::DaService) -> Self {
+        self.da_service = Some(da_service);
+        self
+    }
+
+    /// Uses the given prover service instead of creating one from the rollup config.
+    /// Only used in prover mode.
+    pub fn with_prover_service(mut self, prover_service: S::ProverService) -> Self {
+        self.prover_service = Some(prover_service);
+        self
+    }
+
+    /// Registers additional RPC methods served next to the node's own methods.
+    pub fn with_rpc_methods(mut self, rpc_methods: jsonrpsee::RpcModule<()>) -> Self {
+        self.rpc_methods.push(rpc_methods);
+        self
+    }
+
+    /// Builds the node. The node is started with [`CitreaNode::run`].
+    pub async fn build(
+        self,
+        rt_genesis_paths: &<S::NativeRuntime as RuntimeTrait<S::NativeContext, S::DaSpec>>::GenesisPaths,
+        kernel_genesis: <S::NativeKernel as Kernel<S::NativeContext, S::DaSpec>>::GenesisConfig,
+    ) -> Result<CitreaNode<S>, anyhow::Error>
+    where
+        <S::NativeContext as Spec>::Storage: NativeStorage,
+    {
+        let Self {
+            blueprint,
+            mut rollup_config,
+            mode,
+            da_service,
+            prover_service,
+            rpc_methods,
+        } = self;
+
+        let rpc_config = &rollup_config.runner.rpc_config;
+        if !rpc_config.archive {
+            info!(
+                "Serving the state of the last {} blocks",
+                rpc_config.state_history_blocks
+            );
+        }
+        if rpc_config.index_transactions_by_address {
+            info!("Indexing transactions by address");
+        }
+
+        let da_service = match da_service {
+            Some(da_service) => da_service,
+            None => blueprint.create_da_service(&rollup_config).await,
+        };
+
+        let mut node = match mode {
+            NodeMode::Sequencer(sequencer_config) => {
+                rollup_config.sequencer_client = None;
+
+                let sequencer = blueprint.create_new_sequencer_with_da_service(
+                    da_service,
+                    rt_genesis_paths,
+                    kernel_genesis,
+                    rollup_config,
+                    sequencer_config,
+                )?;
+                CitreaNode::Sequencer(sequencer)
+            }
+            mode => {
+                if rollup_config.sequencer_client.is_none() {
+                    return Err(anyhow!("Must have sequencer client for full nodes!"));
+                }
+
+                let prover_service = match mode {
+                    NodeMode::Prover(prover_config) => Some(match prover_service {
+                        Some(prover_service) => prover_service,
+                        None => {
+                            blueprint
+                                .create_prover_service(prover_config, &rollup_config, &da_service)
+                                .await
+                        }
+                    }),
+                    _ => None,
+                };
+
+                CitreaNode::Rollup(blueprint.create_new_rollup_with_services(
+                    da_service,
+                    prover_service,
+                    rt_genesis_paths,
+                    kernel_genesis,
+                    rollup_config,
+                )?)
+            }
+        };
+
+        for methods in rpc_methods {
+            node.rpc_methods_mut().merge(methods)?;
+        }
+
+        Ok(node)
+    }
+}
+
+/// A Citrea node ready to be run.
+pub enum CitreaNode<S: RollupBlueprint> {
+    /// Sequencer node.
+    Sequencer(Sequencer<S>),
+    /// Full node or prover node.
+    Rollup(Rollup<S>),
+}
+
+impl<S: RollupBlueprint> CitreaNode<S> {
+    /// Runs the node.
+    pub async fn run(self) -> Result<(), anyhow::Error> {
+        self.run_and_report_rpc_port(None).await
+    }
+
+    /// Runs the node. Reports rpc port to the caller using the provided channel.
+    pub async fn run_and_report_rpc_port(
+        self,
+        channel: Option<oneshot::Sender<SocketAddr>>,
+    ) -> Result<(), anyhow::Error> {
+        match self {
+            CitreaNode::Sequencer(sequencer) => sequencer.run_and_report_rpc_port(channel).await,
+            CitreaNode::Rollup(rollup) => rollup.run_and_report_rpc_port(channel).await,
+        }
+    }
+
+    fn rpc_methods_mut(&mut self) -> &mut jsonrpsee::RpcModule<()> {
+        match self {
+            CitreaNode::Sequencer(sequencer) => &mut sequencer.rpc_methods,
+            CitreaNode::Rollup(rollup) => &mut rollup.rpc_methods,
+        }
+    }
+}
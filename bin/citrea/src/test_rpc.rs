@@ -95,6 +95,18 @@ fn test_helper(
             bind_host: "127.0.0.1".to_string(),
             bind_port: addr.port(),
             max_connections: 100,
+            abi_registry_path: None,
+            contract_verification_path: None,
+            response_etags: false,
+            allowed_methods: None,
+            gas_price_oracle: None,
+            archive: true,
+            state_history_blocks: 128,
+            batch_requests_limit: 100,
+            batch_weight_limit: 1000,
+            method_weights: Default::default(),
+            index_transactions_by_address: false,
+            accept_pushed_soft_batches: false,
         };
 
         queries_test_runner(test_queries, rpc_config).await;
@@ -372,6 +384,16 @@ fn test_get_soft_batch_status() {
     regular_test_helper(payload, &expected);
 }
 
+#[test]
+fn test_get_soft_batches_by_status() {
+    let payload = jsonrpc_req!("ledger_getSoftBatchesByStatus", ["Trusted", 1, 10]);
+    let expected = jsonrpc_result!([1, 2]);
+    regular_test_helper(payload, &expected);
+    let payload = jsonrpc_req!("ledger_getSoftBatchesByStatus", ["Finalized", 1, 10]);
+    let expected = jsonrpc_result!([]);
+    regular_test_helper(payload, &expected);
+}
+
 #[test]
 fn test_get_events() {
     let payload = jsonrpc_req!("ledger_getEvents", [1]);
@@ -2,7 +2,10 @@
 // mod bank;
 mod e2e;
 mod evm;
+mod guest_cycles;
 mod mempool;
+mod regression;
+mod scenario;
 mod sequencer_commitments;
 mod soft_confirmation_rule_enforcer;
 mod test_client;
@@ -5,6 +5,7 @@ use std::time::Duration;
 
 use citrea_evm::smart_contracts::SimpleStorageContract;
 use citrea_evm::system_contracts::L1BlockHashList;
+use citrea_sequencer::{Misbehavior, ScriptedMisbehavior};
 use citrea_stf::genesis_config::GenesisPaths;
 use ethereum_types::H256;
 use ethers::abi::Address;
@@ -19,6 +20,7 @@ use tokio::task::JoinHandle;
 use tokio::time::sleep;
 
 use crate::evm::{init_test_rollup, make_test_client};
+use crate::scenario::ScenarioBuilder;
 use crate::test_client::TestClient;
 use crate::test_helpers::{start_rollup, NodeMode};
 use crate::DEFAULT_MIN_SOFT_CONFIRMATIONS_PER_COMMITMENT;
@@ -963,12 +965,66 @@ async fn test_soft_confirmations_status_one_l1() -> Result<(), anyhow::Error> {
         assert_eq!(SoftConfirmationStatus::Finalized, status_node.unwrap());
     }
 
+    // the full node saw the commitment of the sequencer on the DA layer
+    let da_activity = full_node_test_client
+        .citrea_get_sequencer_da_activity()
+        .await;
+    assert!(da_activity.commitment_count >= 1);
+    assert_eq!(da_activity.malformed_blob_count, 0);
+    assert!(
+        da_activity.last_commitment.unwrap().da_slot_height
+            <= da_activity.last_scanned_da_height.unwrap()
+    );
+
     seq_task.abort();
     full_node_task.abort();
 
     Ok(())
 }
 
+#[tokio::test]
+async fn test_chained_full_nodes_finalize_soft_confirmations() -> Result<(), anyhow::Error> {
+    // citrea::initialize_logging();
+
+    let scenario = ScenarioBuilder::new()
+        .min_soft_confirmations_per_commitment(3)
+        .sequencer("sequencer")
+        .full_node("full_node", "sequencer")
+        .full_node_without_tx_bodies("downstream_full_node", "full_node")
+        .start()
+        .await;
+
+    scenario.publish_batches(4).await;
+    scenario.wait_for_l2_height("downstream_full_node", 4).await;
+    scenario
+        .expect_status(
+            "downstream_full_node",
+            1..=4,
+            SoftConfirmationStatus::Trusted,
+        )
+        .await;
+
+    scenario.publish_da_block().await;
+    scenario.publish_batches(2).await;
+
+    scenario.wait_for_l2_height("downstream_full_node", 6).await;
+    scenario
+        .expect_same_head(&["sequencer", "full_node", "downstream_full_node"])
+        .await;
+    scenario
+        .expect_status("full_node", 1..=4, SoftConfirmationStatus::Finalized)
+        .await;
+    scenario
+        .expect_status(
+            "downstream_full_node",
+            1..=4,
+            SoftConfirmationStatus::Finalized,
+        )
+        .await;
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_soft_confirmations_status_two_l1() -> Result<(), anyhow::Error> {
     // citrea::initialize_logging();
@@ -1037,6 +1093,12 @@ async fn test_soft_confirmations_status_two_l1() -> Result<(), anyhow::Error> {
         assert_eq!(SoftConfirmationStatus::Finalized, status_node.unwrap());
     }
 
+    let finalized_heights = full_node_test_client
+        .ledger_get_soft_batches_by_status(SoftConfirmationStatus::Finalized, 1, 6)
+        .await
+        .unwrap();
+    assert_eq!(finalized_heights, (1..=6).collect::<Vec<_>>());
+
     let status_node = full_node_test_client
         .ledger_get_soft_confirmation_status(410)
         .await;
@@ -1433,6 +1495,19 @@ async fn test_system_transactons() -> Result<(), anyhow::Error> {
             .await;
 
         assert_eq!(block.other.get("l1Hash"), Some(&hash_on_chain.into()));
+
+        let l1_block_info = full_node_test_client
+            .citrea_get_l1_block_info(BlockNumberOrTag::Number((i - 3) * 5 + 1))
+            .await
+            .unwrap();
+        assert_eq!(l1_block_info.l1_height.to::<u64>(), i);
+        assert_eq!(l1_block_info.l1_hash.0, da_block.header.hash.0);
+        assert_eq!(
+            l1_block_info
+                .l1_timestamp
+                .map(|timestamp| timestamp.to::<u64>()),
+            Some(da_block.header.time.secs() as u64)
+        );
     }
 
     let seq_last_block = seq_test_client
@@ -1449,3 +1524,64 @@ async fn test_system_transactons() -> Result<(), anyhow::Error> {
 
     Ok(())
 }
+
+/// Starts a sequencer misbehaving as scripted and a full node syncing from it, and returns
+/// the panic message of the full node once it rejects the tampered soft confirmation.
+async fn full_node_rejection(misbehavior: ScriptedMisbehavior) -> String {
+    let mut scenario = ScenarioBuilder::new()
+        .malicious_sequencer("sequencer", misbehavior)
+        .full_node("full_node", "sequencer")
+        .start()
+        .await;
+
+    scenario.publish_batches(misbehavior.l2_height + 2).await;
+
+    // the blocks before the tampered one are synced
+    sleep(Duration::from_secs(2)).await;
+    scenario
+        .expect_l2_height("full_node", misbehavior.l2_height - 1)
+        .await;
+
+    scenario.expect_rejection("full_node").await
+}
+
+#[tokio::test]
+async fn test_full_node_rejects_invalid_state_root() -> Result<(), anyhow::Error> {
+    let message = full_node_rejection(ScriptedMisbehavior {
+        l2_height: 3,
+        misbehavior: Misbehavior::InvalidStateRoot,
+    })
+    .await;
+
+    assert!(message.contains("Post state root mismatch at height: 3"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_full_node_rejects_invalid_signature() -> Result<(), anyhow::Error> {
+    let message = full_node_rejection(ScriptedMisbehavior {
+        l2_height: 3,
+        misbehavior: Misbehavior::InvalidSignature,
+    })
+    .await;
+
+    assert!(message.contains("Signature verification must succeed"));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_full_node_rejects_conflicting_batch() -> Result<(), anyhow::Error> {
+    // the conflicting soft confirmation is validly signed,
+    // but doesn't lead to the state root the sequencer published
+    let message = full_node_rejection(ScriptedMisbehavior {
+        l2_height: 3,
+        misbehavior: Misbehavior::ConflictingBatch,
+    })
+    .await;
+
+    assert!(message.contains("Post state root mismatch at height: 3"));
+
+    Ok(())
+}
@@ -3,14 +3,16 @@ use std::net::SocketAddr;
 use citrea_evm::smart_contracts::SimpleStorageContract;
 use citrea_stf::genesis_config::GenesisPaths;
 use ethers_core::rand::thread_rng;
-use ethers_core::types::U256;
+use ethers_core::types::{Address, U256};
 use ethers_core::utils::Units::Ether;
 use ethers_signers::{LocalWallet, Signer};
+use reth_primitives::constants::GWEI_TO_WEI;
 use reth_primitives::BlockNumberOrTag;
 use sov_modules_stf_blueprint::kernels::basic::BasicKernelGenesisPaths;
 use sov_stf_runner::RollupProverConfig;
 
 use crate::evm::init_test_rollup;
+use crate::scenario::ScenarioBuilder;
 use crate::test_client::TestClient;
 use crate::test_helpers::{start_rollup, NodeMode};
 use crate::DEFAULT_MIN_SOFT_CONFIRMATIONS_PER_COMMITMENT;
@@ -175,3 +177,40 @@ async fn execute(
 
     Ok(())
 }
+
+#[tokio::test]
+async fn test_max_priority_fee_samples_mempool() -> Result<(), anyhow::Error> {
+    let scenario = ScenarioBuilder::new()
+        .sequencer("sequencer")
+        .full_node("full-node", "sequencer")
+        .start()
+        .await;
+    let sequencer = scenario.client("sequencer");
+    let full_node = scenario.client("full-node");
+
+    // there is nothing to sample before the first block, the default tip is suggested
+    assert_eq!(
+        full_node.eth_max_priority_fee_per_gas().await,
+        U256::from(GWEI_TO_WEI)
+    );
+
+    for i in 1..=5 {
+        sequencer
+            .send_eth(
+                Address::from_low_u64_be(i),
+                Some(50 * GWEI_TO_WEI),
+                Some(100 * GWEI_TO_WEI),
+                None,
+                1,
+            )
+            .await?;
+    }
+
+    // the full node samples the tips waiting in the mempool of the sequencer
+    assert_eq!(
+        full_node.eth_max_priority_fee_per_gas().await,
+        U256::from(50 * GWEI_TO_WEI)
+    );
+
+    Ok(())
+}
@@ -7,13 +7,15 @@ use citrea_evm::smart_contracts::{
 };
 use citrea_stf::genesis_config::GenesisPaths;
 use ethers_core::abi::Address;
-use ethers_core::types::{BlockId, Bytes, U256};
+use ethers_core::types::transaction::eip2718::TypedTransaction;
+use ethers_core::types::{BlockId, Bytes, Eip1559TransactionRequest, U256};
 use ethers_signers::{LocalWallet, Signer};
 use reth_primitives::BlockNumberOrTag;
 // use sov_demo_rollup::initialize_logging;
 use sov_modules_stf_blueprint::kernels::basic::BasicKernelGenesisPaths;
 use sov_stf_runner::RollupProverConfig;
 
+use crate::scenario::ScenarioBuilder;
 use crate::test_client::TestClient;
 use crate::test_helpers::{start_rollup, NodeMode};
 use crate::DEFAULT_MIN_SOFT_CONFIRMATIONS_PER_COMMITMENT;
@@ -140,6 +142,189 @@ async fn test_eth_get_logs() -> Result<(), anyhow::Error> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_filters() -> Result<(), Box<dyn std::error::Error>> {
+    let (port_tx, port_rx) = tokio::sync::oneshot::channel();
+
+    let rollup_task = tokio::spawn(async {
+        start_rollup(
+            port_tx,
+            GenesisPaths::from_dir("../test-data/genesis/integration-tests"),
+            BasicKernelGenesisPaths {
+                chain_state: "../test-data/genesis/integration-tests/chain_state.json".into(),
+            },
+            RollupProverConfig::Skip,
+            NodeMode::SequencerNode,
+            None,
+            DEFAULT_MIN_SOFT_CONFIRMATIONS_PER_COMMITMENT,
+            true,
+        )
+        .await;
+    });
+
+    let port = port_rx.await.unwrap();
+    let client = init_test_rollup(port).await;
+
+    let contract = LogsContract::default();
+    let deploy_contract_req = client.deploy_contract(contract.byte_code(), None).await?;
+    client.send_publish_batch_request().await;
+    let contract_address = deploy_contract_req
+        .await?
+        .unwrap()
+        .contract_address
+        .unwrap();
+
+    let block_filter = client.eth_new_block_filter().await;
+    let log_filter = client
+        .eth_new_filter(serde_json::json!({ "address": contract_address }))
+        .await;
+    let pending_filter = client.eth_new_pending_transaction_filter().await;
+
+    let tx_hash = client
+        .contract_transaction(
+            contract_address,
+            contract.publish_event("hello".to_string()),
+            None,
+        )
+        .await
+        .tx_hash();
+    client.send_publish_batch_request().await;
+
+    let pending = client.eth_get_filter_changes(&pending_filter).await?;
+    assert_eq!(pending, vec![serde_json::json!(tx_hash)]);
+
+    let blocks = client.eth_get_filter_changes(&block_filter).await?;
+    let latest = client
+        .eth_get_block_by_number(Some(BlockNumberOrTag::Latest))
+        .await;
+    assert_eq!(blocks, vec![serde_json::json!(latest.hash.unwrap())]);
+
+    let logs = client.eth_get_filter_changes(&log_filter).await?;
+    assert_eq!(logs.len(), 2);
+    assert!(logs
+        .iter()
+        .all(|log| log["transactionHash"] == serde_json::json!(tx_hash)));
+
+    // changes are only returned once
+    assert!(client.eth_get_filter_changes(&log_filter).await?.is_empty());
+    assert!(client
+        .eth_get_filter_changes(&block_filter)
+        .await?
+        .is_empty());
+
+    assert!(client.eth_uninstall_filter(&block_filter).await);
+    assert!(!client.eth_uninstall_filter(&block_filter).await);
+    assert!(client.eth_get_filter_changes(&block_filter).await.is_err());
+
+    rollup_task.abort();
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_subscribe_new_heads() -> Result<(), Box<dyn std::error::Error>> {
+    let scenario = ScenarioBuilder::new()
+        .sequencer("sequencer")
+        .full_node("full_node", "sequencer")
+        .start()
+        .await;
+
+    let mut sequencer_heads = scenario
+        .client("sequencer")
+        .eth_subscribe("newHeads")
+        .await?;
+    let mut full_node_heads = scenario
+        .client("full_node")
+        .eth_subscribe("newHeads")
+        .await?;
+    assert!(scenario
+        .client("sequencer")
+        .eth_subscribe("newPendingTransactions")
+        .await
+        .is_err());
+
+    scenario.publish_batches(2).await;
+    scenario.wait_for_l2_height("full_node", 2).await;
+
+    for number in 1..=2 {
+        let block = scenario
+            .client("sequencer")
+            .eth_get_block_by_number(Some(BlockNumberOrTag::Number(number)))
+            .await;
+        for heads in [&mut sequencer_heads, &mut full_node_heads] {
+            let head = tokio::time::timeout(std::time::Duration::from_secs(10), heads.next())
+                .await?
+                .unwrap()?;
+            assert_eq!(head["number"], serde_json::json!(block.number.unwrap()));
+            assert_eq!(head["hash"], serde_json::json!(block.hash.unwrap()));
+            assert!(head.get("transactions").is_none());
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_subscribe_logs() -> Result<(), Box<dyn std::error::Error>> {
+    let scenario = ScenarioBuilder::new()
+        .sequencer("sequencer")
+        .full_node("full_node", "sequencer")
+        .start()
+        .await;
+    let client = scenario.client("sequencer");
+
+    let contract = LogsContract::default();
+    let deploy_contract_req = client.deploy_contract(contract.byte_code(), None).await?;
+    scenario.publish_batches(1).await;
+    let contract_address = deploy_contract_req
+        .await?
+        .unwrap()
+        .contract_address
+        .unwrap();
+
+    // only the first of the two logs of publish_event has this topic
+    let filter = serde_json::json!({
+        "address": contract_address,
+        "topics": ["0xa9943ee9804b5d456d8ad7b3b1b975a5aefa607e16d13936959976e776c4bec7"]
+    });
+    let mut sequencer_logs = client.eth_subscribe_logs(filter.clone()).await?;
+    let mut full_node_logs = scenario
+        .client("full_node")
+        .eth_subscribe_logs(filter)
+        .await?;
+
+    let tx_hash = client
+        .contract_transaction(
+            contract_address,
+            contract.publish_event("hello".to_string()),
+            None,
+        )
+        .await
+        .tx_hash();
+    scenario.publish_batches(1).await;
+    scenario.wait_for_l2_height("full_node", 2).await;
+
+    for logs in [&mut sequencer_logs, &mut full_node_logs] {
+        let log = tokio::time::timeout(std::time::Duration::from_secs(10), logs.next())
+            .await?
+            .unwrap()?;
+        assert_eq!(log["transactionHash"], serde_json::json!(tx_hash));
+        assert_eq!(log["blockNumber"], serde_json::json!("0x2"));
+        assert_eq!(
+            log["topics"][0],
+            serde_json::json!("0xa9943ee9804b5d456d8ad7b3b1b975a5aefa607e16d13936959976e776c4bec7")
+        );
+
+        // the other log doesn't match the filter
+        assert!(
+            tokio::time::timeout(std::time::Duration::from_secs(1), logs.next())
+                .await
+                .is_err()
+        );
+    }
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_genesis_contract_call() -> Result<(), Box<dyn std::error::Error>> {
     let (seq_port_tx, seq_port_rx) = tokio::sync::oneshot::channel();
@@ -413,6 +598,33 @@ async fn execute(client: &Box<TestClient>) -> Result<(), Box<dyn std::error::Err
         .unwrap();
     assert_eq!(storage_value, ethereum_types::U256::from(set_arg));
 
+    // The access list of a call setting the value has the storage slot of the contract
+    let set_value_tx = TypedTransaction::Eip1559(
+        Eip1559TransactionRequest::new()
+            .from(client.from_addr)
+            .to(contract_address)
+            .chain_id(client.chain_id)
+            .data(contract.set_call_data(set_arg + 1)),
+    );
+    let access_list = client
+        .eth_create_access_list(set_value_tx.clone(), None)
+        .await?;
+    assert_eq!(access_list.access_list.0.len(), 1);
+    assert_eq!(access_list.access_list.0[0].address, contract_address);
+    assert_eq!(
+        access_list.access_list.0[0].storage_keys,
+        vec![ethereum_types::H256::zero()]
+    );
+    assert!(access_list.gas_used > U256::from(21000));
+
+    // Passing the generated list back returns the same list
+    let mut set_value_tx_with_list = set_value_tx;
+    set_value_tx_with_list.set_access_list(access_list.access_list.clone());
+    let same_access_list = client
+        .eth_create_access_list(set_value_tx_with_list, None)
+        .await?;
+    assert_eq!(same_access_list, access_list);
+
     // Check that the second block has published
     // None should return the latest block
     // It should have a single transaction, setting the value
@@ -4,11 +4,11 @@ use std::str::FromStr;
 use citrea_evm::smart_contracts::{CallerContract, SimpleStorageContract};
 use citrea_stf::genesis_config::GenesisPaths;
 use ethers::abi::Address;
-use reth_primitives::BlockNumberOrTag;
+use reth_primitives::{BlockNumberOrTag, B256, U256};
 use reth_rpc_types::trace::geth::GethTrace::{self, CallTracer, FourByteTracer};
 use reth_rpc_types::trace::geth::{
     CallConfig, CallFrame, FourByteFrame, GethDebugBuiltInTracerType, GethDebugTracerType,
-    GethDebugTracingOptions,
+    GethDebugTracingOptions, PreStateFrame,
 };
 use serde_json::{self, json};
 use sov_modules_stf_blueprint::kernels::basic::BasicKernelGenesisPaths;
@@ -44,7 +44,7 @@ async fn tracing_tests() -> Result<(), Box<dyn std::error::Error>> {
     let test_client = make_test_client(port).await;
 
     // ss is short for simple storage in this context
-    let (caller_contract_address, caller_contract, ss_contract_address, _ss_contract) = {
+    let (caller_contract_address, caller_contract, ss_contract_address, ss_contract) = {
         // caller contract has methods to call simple_storage contract
         // can call get with address and set with address and value
         let ss_contract = SimpleStorageContract::default();
@@ -132,6 +132,33 @@ async fn tracing_tests() -> Result<(), Box<dyn std::error::Error>> {
 
     assert_eq!(json_res, CallTracer(reth_json));
 
+    // the set call changes the value in the first slot of the simple storage contract from 0 to 3
+    let prestate_opts = serde_json::from_value::<GethDebugTracingOptions>(json![{
+        "tracer": "prestateTracer",
+        "tracerConfig": { "diffMode": true }
+    }])
+    .unwrap();
+    let prestate_trace = test_client
+        .debug_trace_transaction(tx_hash, Some(prestate_opts))
+        .await;
+    let GethTrace::PreStateTracer(PreStateFrame::Diff(diff)) = prestate_trace else {
+        panic!("Expected a prestate diff, got {:?}", prestate_trace);
+    };
+    let ss_address = reth_primitives::Address::from_slice(ss_contract_address.as_ref());
+    assert_eq!(
+        diff.pre[&ss_address].storage.get(&B256::ZERO),
+        Some(&B256::ZERO)
+    );
+    assert_eq!(
+        diff.post[&ss_address].storage.get(&B256::ZERO),
+        Some(&B256::from(U256::from(3)))
+    );
+    assert!(diff
+        .post
+        .contains_key(&reth_primitives::Address::from_slice(
+            test_client.from_addr.as_ref()
+        )));
+
     // Create multiple txs in the same block to test the if tracing works with cache enabled
     let call_get_value_req = test_client
         .contract_transaction(
@@ -214,8 +241,14 @@ async fn tracing_tests() -> Result<(), Box<dyn std::error::Error>> {
         )
         .await;
     assert_eq!(traces.len(), 2);
-    assert_eq!(traces[1], CallTracer(expected_send_eth_trace.clone()));
-    assert_eq!(traces[0], CallTracer(expected_call_get_trace.clone()));
+    assert_eq!(
+        traces[1].result,
+        CallTracer(expected_send_eth_trace.clone())
+    );
+    assert_eq!(
+        traces[0].result,
+        CallTracer(expected_call_get_trace.clone())
+    );
 
     let block_hash = test_client
         .eth_get_block_by_number(Some(BlockNumberOrTag::Number(3)))
@@ -233,8 +266,34 @@ async fn tracing_tests() -> Result<(), Box<dyn std::error::Error>> {
         .await;
 
     assert_eq!(traces.len(), 2);
-    assert_eq!(traces[1], CallTracer(expected_send_eth_trace.clone()));
-    assert_eq!(traces[0], CallTracer(expected_call_get_trace));
+    assert_eq!(
+        traces[1].result,
+        CallTracer(expected_send_eth_trace.clone())
+    );
+    assert_eq!(traces[0].result, CallTracer(expected_call_get_trace));
+
+    // traces are returned with the hashes of their transactions
+    assert_eq!(traces[0].tx_hash, B256::from_slice(call_tx_hash.as_bytes()));
+    assert_eq!(
+        traces[1].tx_hash,
+        B256::from_slice(send_eth_tx_hash.as_bytes())
+    );
+
+    // prestate traces are not derived from the cached call traces
+    let prestate_traces = test_client
+        .debug_trace_block_by_hash(
+            block_hash,
+            Some(GethDebugTracingOptions::default().with_tracer(
+                GethDebugTracerType::BuiltInTracer(GethDebugBuiltInTracerType::PreStateTracer),
+            )),
+        )
+        .await;
+    assert_eq!(prestate_traces.len(), 2);
+    assert_eq!(prestate_traces[0].tx_hash, traces[0].tx_hash);
+    assert!(prestate_traces.iter().all(|trace| matches!(
+        trace.result,
+        GethTrace::PreStateTracer(PreStateFrame::Default(_))
+    )));
 
     let four_byte_traces = test_client
         .debug_trace_block_by_hash(
@@ -252,11 +311,11 @@ async fn tracing_tests() -> Result<(), Box<dyn std::error::Error>> {
 
     assert_eq!(four_byte_traces.len(), 2);
     assert_eq!(
-        four_byte_traces[0],
+        four_byte_traces[0].result,
         FourByteTracer(expected_call_get_4byte_trace)
     );
     assert_eq!(
-        four_byte_traces[1],
+        four_byte_traces[1].result,
         FourByteTracer(expected_send_eth_4byte_trace)
     );
 
@@ -287,12 +346,88 @@ async fn tracing_tests() -> Result<(), Box<dyn std::error::Error>> {
 
     println!("{:?}", traces_top_call_only);
     assert_eq!(traces_top_call_only.len(), 2);
-    assert_eq!(traces_top_call_only[1], CallTracer(expected_send_eth_trace));
     assert_eq!(
-        traces_top_call_only[0],
+        traces_top_call_only[1].result,
+        CallTracer(expected_send_eth_trace)
+    );
+    assert_eq!(
+        traces_top_call_only[0].result,
         CallTracer(expected_top_call_only_call_get_trace)
     );
 
+    // trace a call to get the value of the simple storage contract, which is 3
+    let get_value_request = json!({
+        "from": test_client.from_addr,
+        "to": ss_contract_address,
+        "data": ss_contract.get_call_data(),
+    });
+    let call_trace = test_client
+        .debug_trace_call(
+            get_value_request.clone(),
+            Some(BlockNumberOrTag::Latest),
+            Some(json!({ "tracer": "callTracer" })),
+        )
+        .await?;
+    let GethTrace::CallTracer(call_frame) = call_trace else {
+        panic!("Expected a call trace, got {:?}", call_trace);
+    };
+    assert_eq!(
+        call_frame.output,
+        Some(U256::from(3).to_be_bytes_vec().into())
+    );
+
+    // override the value with a state diff
+    let call_trace = test_client
+        .debug_trace_call(
+            get_value_request,
+            Some(BlockNumberOrTag::Latest),
+            Some(json!({
+                "tracer": "callTracer",
+                "stateOverrides": {
+                    ss_address.to_string(): {
+                        "stateDiff": { B256::ZERO.to_string(): B256::from(U256::from(5)) }
+                    }
+                }
+            })),
+        )
+        .await?;
+    let GethTrace::CallTracer(call_frame) = call_trace else {
+        panic!("Expected a call trace, got {:?}", call_trace);
+    };
+    assert_eq!(
+        call_frame.output,
+        Some(U256::from(5).to_be_bytes_vec().into())
+    );
+
+    // the set call is traced as the call to the caller contract and its call to simple storage
+    let parity_traces = test_client.trace_transaction(tx_hash).await.unwrap();
+    assert_eq!(parity_traces.len(), 2);
+    assert_eq!(parity_traces[0].trace.subtraces, 1);
+    assert_eq!(parity_traces[1].trace.trace_address, vec![0]);
+    assert!(parity_traces
+        .iter()
+        .all(|trace| trace.transaction_hash == Some(B256::from_slice(tx_hash.as_bytes()))));
+
+    // the caller contract called simple storage in the set and get transactions
+    let caller_traces = test_client
+        .trace_filter(json!({
+            "fromBlock": "earliest",
+            "toBlock": "latest",
+            "fromAddress": [caller_contract_address],
+        }))
+        .await;
+    assert_eq!(caller_traces.len(), 2);
+    assert_eq!(caller_traces[0], parity_traces[1]);
+    let caller_traces = test_client
+        .trace_filter(json!({
+            "fromBlock": "earliest",
+            "toBlock": "latest",
+            "fromAddress": [caller_contract_address],
+            "after": 1,
+        }))
+        .await;
+    assert_eq!(caller_traces.len(), 1);
+
     rollup_task.abort();
     Ok(())
 }
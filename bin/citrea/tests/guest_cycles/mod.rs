@@ -0,0 +1,224 @@
+//! Cycle budgets of the guest, so that changes of the state transition function that blow up
+//! the proving cost are caught in review.
+//!
+//! The prover executes the guest on representative commitments without proving them, and the
+//! cycle count of each execution must stay within the budget of its scenario. When a change
+//! legitimately needs more cycles, raise the budget in the same change so that the increase
+//! is reviewed.
+//!
+//! The guest is only built without `SKIP_GUEST_BUILD`, so the test is ignored in the default
+//! test run and has its own CI job: `cargo test -p citrea --test all_tests -- --ignored guest_cycles`.
+
+use std::collections::HashSet;
+use std::time::{Duration, Instant};
+
+use citrea_evm::smart_contracts::SimpleStorageContract;
+use citrea_stf::genesis_config::GenesisPaths;
+use ethers::abi::Address;
+use sov_mock_da::{MockAddress, MockDaService};
+use sov_modules_stf_blueprint::kernels::basic::BasicKernelGenesisPaths;
+use sov_rollup_interface::services::da::DaService;
+use sov_stf_runner::RollupProverConfig;
+use tokio::time::sleep;
+
+use crate::evm::make_test_client;
+use crate::test_client::TestClient;
+use crate::test_helpers::{start_rollup, NodeMode};
+
+/// Soft confirmations per commitment, each commitment is executed by the guest at once.
+const SOFT_CONFIRMATIONS_PER_COMMITMENT: u64 = 4;
+
+/// Transactions per soft confirmation of the scenarios with transactions.
+const TXS_PER_SOFT_CONFIRMATION: u64 = 10;
+
+/// Cycles of a commitment of empty soft confirmations.
+const EMPTY_SOFT_CONFIRMATIONS_CYCLE_BUDGET: u64 = 64 * 1024 * 1024;
+
+/// Cycles of a commitment of soft confirmations of ether transfers.
+const TRANSFERS_CYCLE_BUDGET: u64 = 256 * 1024 * 1024;
+
+/// Cycles of a commitment of soft confirmations of contract deployments and calls.
+const CONTRACT_CALLS_CYCLE_BUDGET: u64 = 512 * 1024 * 1024;
+
+/// How long the prover has to execute a commitment.
+const PROVER_TIMEOUT: Duration = Duration::from_secs(300);
+
+#[tokio::test]
+#[ignore = "needs the guest, built without SKIP_GUEST_BUILD"]
+async fn test_guest_cycle_budgets() -> Result<(), anyhow::Error> {
+    // citrea::initialize_logging();
+    assert!(
+        !risc0::MOCK_DA_ELF.is_empty(),
+        "The guest is not built, unset SKIP_GUEST_BUILD to measure its cycles"
+    );
+
+    let da_service = MockDaService::new(MockAddress::default());
+
+    let (seq_port_tx, seq_port_rx) = tokio::sync::oneshot::channel();
+    let seq_task = tokio::spawn(async move {
+        start_rollup(
+            seq_port_tx,
+            GenesisPaths::from_dir("../test-data/genesis/integration-tests"),
+            BasicKernelGenesisPaths {
+                chain_state: "../test-data/genesis/integration-tests/chain_state.json".into(),
+            },
+            RollupProverConfig::Execute,
+            NodeMode::SequencerNode,
+            None,
+            SOFT_CONFIRMATIONS_PER_COMMITMENT,
+            true,
+        )
+        .await;
+    });
+    let seq_port = seq_port_rx.await.unwrap();
+    let seq_test_client = make_test_client(seq_port).await;
+
+    let (prover_node_port_tx, prover_node_port_rx) = tokio::sync::oneshot::channel();
+    let prover_node_task = tokio::spawn(async move {
+        start_rollup(
+            prover_node_port_tx,
+            GenesisPaths::from_dir("../test-data/genesis/integration-tests"),
+            BasicKernelGenesisPaths {
+                chain_state: "../test-data/genesis/integration-tests/chain_state.json".into(),
+            },
+            RollupProverConfig::Execute,
+            NodeMode::Prover(seq_port),
+            None,
+            SOFT_CONFIRMATIONS_PER_COMMITMENT,
+            true,
+        )
+        .await;
+    });
+    let prover_node_port = prover_node_port_rx.await.unwrap();
+    let prover_node_test_client = make_test_client(prover_node_port).await;
+
+    let mut proven_slots = HashSet::new();
+
+    for _ in 0..SOFT_CONFIRMATIONS_PER_COMMITMENT {
+        seq_test_client.send_publish_batch_request().await;
+    }
+    let cycles = execute_commitment(
+        &da_service,
+        &seq_test_client,
+        &prover_node_test_client,
+        &mut proven_slots,
+    )
+    .await;
+    assert_within_budget(
+        "empty soft confirmations",
+        cycles,
+        EMPTY_SOFT_CONFIRMATIONS_CYCLE_BUDGET,
+    );
+
+    for round in 0..SOFT_CONFIRMATIONS_PER_COMMITMENT {
+        for i in 0..TXS_PER_SOFT_CONFIRMATION {
+            let to = Address::from_low_u64_be(round * TXS_PER_SOFT_CONFIRMATION + i + 1);
+            seq_test_client
+                .send_eth(to, None, None, None, 1)
+                .await
+                .unwrap();
+        }
+        seq_test_client.send_publish_batch_request().await;
+    }
+    let cycles = execute_commitment(
+        &da_service,
+        &seq_test_client,
+        &prover_node_test_client,
+        &mut proven_slots,
+    )
+    .await;
+    assert_within_budget("ether transfers", cycles, TRANSFERS_CYCLE_BUDGET);
+
+    let contract = SimpleStorageContract::default();
+    let deploy_contract_req = seq_test_client
+        .deploy_contract(contract.byte_code(), None)
+        .await
+        .unwrap();
+    seq_test_client.send_publish_batch_request().await;
+    let contract_address = deploy_contract_req
+        .await?
+        .unwrap()
+        .contract_address
+        .unwrap();
+    for round in 1..SOFT_CONFIRMATIONS_PER_COMMITMENT {
+        for i in 0..TXS_PER_SOFT_CONFIRMATION {
+            let value = (round * TXS_PER_SOFT_CONFIRMATION + i) as u32;
+            seq_test_client
+                .contract_transaction(contract_address, contract.set_call_data(value), None)
+                .await;
+        }
+        seq_test_client.send_publish_batch_request().await;
+    }
+    let cycles = execute_commitment(
+        &da_service,
+        &seq_test_client,
+        &prover_node_test_client,
+        &mut proven_slots,
+    )
+    .await;
+    assert_within_budget("contract calls", cycles, CONTRACT_CALLS_CYCLE_BUDGET);
+
+    seq_task.abort();
+    prover_node_task.abort();
+    Ok(())
+}
+
+/// Makes the sequencer commit the soft confirmations published since its last commitment, then
+/// waits for the prover to execute the new DA slots. Returns the cycles of the executions.
+async fn execute_commitment(
+    da_service: &MockDaService,
+    seq_test_client: &TestClient,
+    prover_node_test_client: &TestClient,
+    proven_slots: &mut HashSet<[u8; 32]>,
+) -> u64 {
+    da_service.publish_test_block().await.unwrap();
+    // the commitment is sent when the sequencer sees the new DA block
+    seq_test_client.send_publish_batch_request().await;
+    da_service.publish_test_block().await.unwrap();
+
+    let start = Instant::now();
+    loop {
+        let head_height = da_service.get_head_block_header().await.unwrap().height;
+        let mut cycles = None;
+        for height in 1..=head_height {
+            let hash = da_service.get_block_at(height).await.unwrap().header.hash.0;
+            if proven_slots.contains(&hash) {
+                continue;
+            }
+            let Some(details) = prover_node_test_client.prover_get_proof_details(hash).await else {
+                continue;
+            };
+            assert_eq!(details.error, None, "Guest execution failed");
+            let cycle_count = details
+                .cycle_count
+                .expect("The zkVM must report the cycles of executions");
+            proven_slots.insert(hash);
+            *cycles.get_or_insert(0) += cycle_count;
+        }
+        if let Some(cycles) = cycles {
+            return cycles;
+        }
+
+        assert!(
+            start.elapsed() < PROVER_TIMEOUT,
+            "The prover didn't execute the commitment in time"
+        );
+        sleep(Duration::from_secs(1)).await;
+    }
+}
+
+fn assert_within_budget(scenario: &str, cycles: u64, budget: u64) {
+    println!(
+        "Guest cycles of {}: {} ({}% of the budget)",
+        scenario,
+        cycles,
+        cycles * 100 / budget
+    );
+    assert!(
+        cycles <= budget,
+        "Guest cycles of {} are over budget: {} > {}",
+        scenario,
+        cycles,
+        budget
+    );
+}
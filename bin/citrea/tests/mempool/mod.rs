@@ -9,6 +9,7 @@ use sov_stf_runner::RollupProverConfig;
 use tokio::task::JoinHandle;
 
 use crate::evm::make_test_client;
+use crate::scenario::ScenarioBuilder;
 use crate::test_client::{TestClient, MAX_FEE_PER_GAS};
 use crate::test_helpers::{start_rollup, NodeMode};
 use crate::DEFAULT_MIN_SOFT_CONFIRMATIONS_PER_COMMITMENT;
@@ -421,6 +422,28 @@ async fn test_same_nonce_tx_replacement() {
 
     assert_ne!(tx_hash_25_bump.tx_hash(), tx_hash_ultra_bump.tx_hash());
 
+    // the replaced transactions point to the transaction replacing them
+    for (replaced, replaced_by) in [
+        (&tx_hash, &tx_hash_11_bump),
+        (&tx_hash_11_bump, &tx_hash_25_bump),
+        (&tx_hash_25_bump, &tx_hash_ultra_bump),
+    ] {
+        let tx = test_client
+            .eth_get_transaction_by_hash(replaced.tx_hash(), Some(true))
+            .await
+            .unwrap();
+        assert_eq!(tx.hash, replaced.tx_hash());
+        assert_eq!(
+            tx.other.get("replacedBy"),
+            Some(&serde_json::json!(replaced_by.tx_hash()))
+        );
+    }
+    let tx = test_client
+        .eth_get_transaction_by_hash(tx_hash_ultra_bump.tx_hash(), Some(true))
+        .await
+        .unwrap();
+    assert!(tx.other.get("replacedBy").is_none());
+
     test_client.send_publish_batch_request().await;
 
     let block = test_client
@@ -434,3 +457,103 @@ async fn test_same_nonce_tx_replacement() {
 
     seq_task.abort();
 }
+
+/// The pending nonce counts the transactions of the sender in the mempool up to the first
+/// nonce gap, on the sequencer and on the full nodes proxying it.
+#[tokio::test]
+async fn test_pending_nonce_counts_queued_txs() {
+    // citrea::initialize_logging();
+
+    let scenario = ScenarioBuilder::new()
+        .sequencer("sequencer")
+        .full_node("full_node", "sequencer")
+        .start()
+        .await;
+    let seq_client = scenario.client("sequencer");
+    let full_node_client = scenario.client("full_node");
+
+    let addr = Address::from_str("0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266").unwrap();
+    let sender = seq_client.from_addr;
+
+    for nonce in [0, 1, 3] {
+        seq_client
+            .send_eth(addr, None, None, Some(nonce), 0u128)
+            .await
+            .unwrap();
+    }
+
+    for client in [seq_client, full_node_client] {
+        assert_eq!(
+            client
+                .eth_get_transaction_count(sender, Some(BlockNumberOrTag::Pending))
+                .await
+                .unwrap(),
+            2
+        );
+        assert_eq!(
+            client
+                .eth_get_transaction_count(sender, Some(BlockNumberOrTag::Latest))
+                .await
+                .unwrap(),
+            0
+        );
+    }
+
+    // filling the gap makes the queued transaction count
+    seq_client
+        .send_eth(addr, None, None, Some(2), 0u128)
+        .await
+        .unwrap();
+    assert_eq!(
+        full_node_client
+            .eth_get_transaction_count(sender, Some(BlockNumberOrTag::Pending))
+            .await
+            .unwrap(),
+        4
+    );
+}
+
+/// The txpool namespace shows the pending and queued transactions of the sequencer's mempool,
+/// on the sequencer and on the full nodes forwarding to it.
+#[tokio::test]
+async fn test_txpool_namespace() {
+    // citrea::initialize_logging();
+
+    let scenario = ScenarioBuilder::new()
+        .sequencer("sequencer")
+        .full_node("full_node", "sequencer")
+        .start()
+        .await;
+    let seq_client = scenario.client("sequencer");
+    let full_node_client = scenario.client("full_node");
+
+    let addr = Address::from_str("0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266").unwrap();
+    let sender = reth_primitives::Address::from_slice(seq_client.from_addr.as_bytes());
+
+    // nonce 3 is queued behind the gap at nonce 2
+    for nonce in [0, 1, 3] {
+        seq_client
+            .send_eth(addr, None, None, Some(nonce), 0u128)
+            .await
+            .unwrap();
+    }
+
+    for client in [seq_client, full_node_client] {
+        let status = client.txpool_status().await;
+        assert_eq!(status.pending, reth_primitives::U64::from(2));
+        assert_eq!(status.queued, reth_primitives::U64::from(1));
+
+        let content = client.txpool_content().await;
+        let pending_nonces: Vec<&String> = content.pending[&sender].keys().collect();
+        assert_eq!(pending_nonces, vec!["0", "1"]);
+        assert!(content.queued[&sender].contains_key("3"));
+
+        let inspect = client.txpool_inspect().await;
+        let summary = &inspect.queued[&sender]["3"];
+        assert_eq!(
+            summary.to,
+            Some(reth_primitives::Address::from_slice(addr.as_bytes()))
+        );
+        assert_eq!(summary.value, reth_primitives::U256::ZERO);
+    }
+}
@@ -0,0 +1,240 @@
+//! State root regression tests.
+//!
+//! Replays the soft batch histories recorded under `bin/test-data/regression` through the
+//! current state transition function and checks that every soft batch still produces the state
+//! root it was recorded with. A mismatch means that a change, typically a revm, reth or
+//! sovereign-sdk upgrade, changed consensus and would fork a live network.
+//!
+//! Histories are recorded with
+//! `cargo test --test all_tests regression::record_history -- --ignored`
+//! and only need to be recorded again when a consensus change is intended.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use citrea_evm::smart_contracts::SimpleStorageContract;
+use citrea_stf::genesis_config::{GenesisPaths, StorageConfig};
+use citrea_stf::runtime::Runtime;
+use ethers_core::types::Address;
+use sequencer_client::GetSoftBatchResponse;
+use serde::{Deserialize, Serialize};
+use sov_mock_da::{MockAddress, MockBlockHeader, MockDaService, MockDaSpec, MockValidityCond};
+use sov_modules_api::default_context::DefaultContext;
+use sov_modules_stf_blueprint::kernels::basic::{BasicKernel, BasicKernelGenesisConfig};
+use sov_modules_stf_blueprint::{GenesisParams, Runtime as RuntimeTrait, StfBlueprint};
+use sov_prover_storage_manager::ProverStorageManager;
+use sov_risc0_adapter::host::Risc0Host;
+use sov_rollup_interface::da::BlockHeaderTrait;
+use sov_rollup_interface::services::da::{DaService, SlotData};
+use sov_rollup_interface::stf::StateTransitionFunction;
+use sov_rollup_interface::storage::HierarchicalStorageManager;
+use sov_state::DefaultStorageSpec;
+
+use crate::scenario::ScenarioBuilder;
+use crate::test_helpers::SEQUENCER_PUBLIC_KEY;
+
+/// Directory of the recorded histories, relative to the crate.
+const HISTORIES_DIR: &str = "../test-data/regression";
+
+/// Genesis the histories are recorded from, relative to `bin/test-data/genesis`.
+const GENESIS: &str = "integration-tests";
+
+type NativeStf = StfBlueprint<
+    DefaultContext,
+    MockDaSpec,
+    Risc0Host<'static>,
+    Runtime<DefaultContext, MockDaSpec>,
+    BasicKernel<DefaultContext, MockDaSpec>,
+>;
+
+/// Soft batches published by a sequencer from genesis, with the state roots they produced.
+#[derive(Debug, Serialize, Deserialize)]
+struct RecordedHistory {
+    /// Genesis directory the history starts from, relative to `bin/test-data/genesis`
+    genesis: String,
+    #[serde(with = "hex::serde")]
+    sequencer_public_key: Vec<u8>,
+    /// Headers of the DA blocks the soft batches were built on
+    da_headers: Vec<MockBlockHeader>,
+    /// Soft batches from L2 height 1, in order
+    soft_batches: Vec<GetSoftBatchResponse>,
+}
+
+fn recorded_histories() -> Vec<PathBuf> {
+    let entries = fs::read_dir(HISTORIES_DIR)
+        .unwrap_or_else(|e| panic!("Failed to read {}: {}", HISTORIES_DIR, e));
+    let mut paths: Vec<PathBuf> = entries
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "json")
+        })
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// Applies the soft batches of a history on a fresh state and checks the state root after each.
+fn replay(name: &str, history: &RecordedHistory) {
+    let storage_dir = tempfile::tempdir().unwrap();
+    let mut storage_manager =
+        ProverStorageManager::<MockDaSpec, DefaultStorageSpec>::new(StorageConfig {
+            path: storage_dir.path().to_path_buf(),
+        })
+        .unwrap();
+    let stf = NativeStf::new();
+
+    let genesis_dir = Path::new("../test-data/genesis").join(&history.genesis);
+    let genesis_params = GenesisParams {
+        runtime: <Runtime<DefaultContext, MockDaSpec> as RuntimeTrait<_, _>>::genesis_config(
+            &GenesisPaths::from_dir(&genesis_dir),
+        )
+        .unwrap(),
+        kernel: BasicKernelGenesisConfig {
+            chain_state: serde_json::from_str(
+                &fs::read_to_string(genesis_dir.join("chain_state.json")).unwrap(),
+            )
+            .unwrap(),
+        },
+    };
+    let genesis_state = storage_manager.create_storage_on_l2_height(0).unwrap();
+    let (mut state_root, change_set) = stf.init_chain(genesis_state, genesis_params);
+    storage_manager.save_change_set_l2(0, change_set).unwrap();
+    storage_manager.finalize_l2(0).unwrap();
+
+    for (index, soft_batch) in history.soft_batches.iter().enumerate() {
+        let height = index as u64 + 1;
+        assert_eq!(
+            hex::encode(state_root.as_ref()),
+            hex::encode(&soft_batch.pre_state_root),
+            "{}: pre-state root of soft batch {} changed",
+            name,
+            height
+        );
+
+        let da_header = history
+            .da_headers
+            .iter()
+            .find(|header| header.height() == soft_batch.da_slot_height)
+            .unwrap_or_else(|| {
+                panic!(
+                    "{}: DA block {} of soft batch {} was not recorded",
+                    name, soft_batch.da_slot_height, height
+                )
+            });
+
+        let pre_state = storage_manager.create_storage_on_l2_height(height).unwrap();
+        let result = stf.apply_soft_batch(
+            &history.sequencer_public_key,
+            &state_root,
+            pre_state,
+            Default::default(),
+            da_header,
+            &MockValidityCond::default(),
+            // the recorded DA blocks carry no governance actions
+            &mut [],
+            &mut soft_batch.clone().into(),
+        );
+        storage_manager
+            .save_change_set_l2(height, result.change_set)
+            .unwrap();
+        storage_manager.finalize_l2(height).unwrap();
+
+        state_root = result.state_root;
+        assert_eq!(
+            hex::encode(state_root.as_ref()),
+            hex::encode(&soft_batch.post_state_root),
+            "{}: state root of soft batch {} changed",
+            name,
+            height
+        );
+    }
+}
+
+#[test]
+fn test_recorded_state_roots() {
+    let histories = recorded_histories();
+    // without a history the test would pass without checking anything
+    assert!(
+        !histories.is_empty(),
+        "No recorded history in {}, record one with regression::record_history",
+        HISTORIES_DIR
+    );
+    for path in histories {
+        let name = path.file_name().unwrap().to_string_lossy().into_owned();
+        let history: RecordedHistory =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        replay(&name, &history);
+    }
+}
+
+/// Records a devnet history exercising contract deployments, storage writes and transfers
+/// over several DA blocks, and writes it to `bin/test-data/regression/devnet-history.json`.
+#[tokio::test]
+#[ignore = "records the history replayed by test_recorded_state_roots"]
+async fn record_history() -> Result<(), anyhow::Error> {
+    let scenario = ScenarioBuilder::new().sequencer("sequencer").start().await;
+    let client = scenario.client("sequencer");
+
+    let contract = SimpleStorageContract::default();
+    let deploy_contract_req = client.deploy_contract(contract.byte_code(), None).await?;
+    scenario.publish_batches(1).await;
+    let contract_address = deploy_contract_req
+        .await?
+        .unwrap()
+        .contract_address
+        .unwrap();
+
+    for value in 1..=3u32 {
+        scenario.publish_da_block().await;
+        let _set = client
+            .contract_transaction(contract_address, contract.set_call_data(value), None)
+            .await;
+        let _transfer = client
+            .send_eth(
+                Address::from_low_u64_be(value as u64),
+                None,
+                None,
+                None,
+                value as u128 * 1_000_000,
+            )
+            .await?;
+        scenario.publish_batches(2).await;
+    }
+
+    let head = client.eth_block_number().await;
+    let mut soft_batches = vec![];
+    for height in 1..=head {
+        soft_batches.push(
+            client
+                .ledger_get_soft_batch_by_number::<MockDaSpec>(height)
+                .await
+                .unwrap(),
+        );
+    }
+
+    let da_service = MockDaService::new(MockAddress::from([0; 32]));
+    let da_heights: BTreeSet<u64> = soft_batches
+        .iter()
+        .map(|soft_batch| soft_batch.da_slot_height)
+        .collect();
+    let mut da_headers = vec![];
+    for height in da_heights {
+        da_headers.push(da_service.get_block_at(height).await?.header().clone());
+    }
+
+    let history = RecordedHistory {
+        genesis: GENESIS.to_string(),
+        sequencer_public_key: SEQUENCER_PUBLIC_KEY.to_vec(),
+        da_headers,
+        soft_batches,
+    };
+    fs::create_dir_all(HISTORIES_DIR)?;
+    fs::write(
+        Path::new(HISTORIES_DIR).join("devnet-history.json"),
+        serde_json::to_string_pretty(&history)?,
+    )?;
+
+    Ok(())
+}
@@ -0,0 +1,331 @@
+//! A small DSL for multi-node e2e tests.
+//!
+//! A scenario declares its topology with [`ScenarioBuilder`], then drives it step by step
+//! with [`Scenario`]:
+//!
+//! ```ignore
+//! let scenario = ScenarioBuilder::new()
+//!     .sequencer("sequencer")
+//!     .full_node("full_node", "sequencer")
+//!     .start()
+//!     .await;
+//!
+//! scenario.publish_batches(6).await;
+//! scenario.publish_da_block().await;
+//! scenario.wait_for_l2_height("full_node", 6).await;
+//! scenario
+//!     .expect_status("full_node", 1..=6, SoftConfirmationStatus::Trusted)
+//!     .await;
+//! ```
+//!
+//! Nodes are stopped when the scenario is dropped.
+
+use std::ops::RangeInclusive;
+use std::time::Duration;
+
+use citrea_sequencer::ScriptedMisbehavior;
+use citrea_stf::genesis_config::GenesisPaths;
+use reth_primitives::BlockNumberOrTag;
+use sov_mock_da::{MockAddress, MockDaService};
+use sov_modules_stf_blueprint::kernels::basic::BasicKernelGenesisPaths;
+use sov_rollup_interface::rpc::SoftConfirmationStatus;
+use sov_rollup_interface::services::da::DaService;
+use sov_stf_runner::RollupProverConfig;
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, Instant};
+
+use crate::evm::make_test_client;
+use crate::test_client::TestClient;
+use crate::test_helpers::{start_rollup, NodeMode};
+use crate::DEFAULT_MIN_SOFT_CONFIRMATIONS_PER_COMMITMENT;
+
+/// How long to wait for a node to reach a state before failing the scenario.
+const STEP_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy)]
+enum Role {
+    Sequencer(Option<ScriptedMisbehavior>),
+    FullNode { upstream: &'static str },
+}
+
+#[derive(Debug, Clone, Copy)]
+struct NodeSpec {
+    name: &'static str,
+    role: Role,
+    include_tx_body: bool,
+}
+
+/// Declares the nodes of a scenario, started in declaration order.
+/// A node syncs from an upstream node declared before it.
+#[derive(Debug, Clone)]
+pub(crate) struct ScenarioBuilder {
+    min_soft_confirmations_per_commitment: u64,
+    nodes: Vec<NodeSpec>,
+}
+
+impl ScenarioBuilder {
+    pub(crate) fn new() -> Self {
+        Self {
+            min_soft_confirmations_per_commitment: DEFAULT_MIN_SOFT_CONFIRMATIONS_PER_COMMITMENT,
+            nodes: vec![],
+        }
+    }
+
+    /// Soft confirmations the sequencer commits to at once.
+    pub(crate) fn min_soft_confirmations_per_commitment(mut self, count: u64) -> Self {
+        self.min_soft_confirmations_per_commitment = count;
+        self
+    }
+
+    pub(crate) fn sequencer(self, name: &'static str) -> Self {
+        self.node(name, Role::Sequencer(None), true)
+    }
+
+    /// A sequencer tampering with the soft confirmation it publishes at a height.
+    pub(crate) fn malicious_sequencer(
+        self,
+        name: &'static str,
+        misbehavior: ScriptedMisbehavior,
+    ) -> Self {
+        self.node(name, Role::Sequencer(Some(misbehavior)), true)
+    }
+
+    pub(crate) fn full_node(self, name: &'static str, upstream: &'static str) -> Self {
+        self.node(name, Role::FullNode { upstream }, true)
+    }
+
+    /// A full node that doesn't store the bodies of the transactions.
+    pub(crate) fn full_node_without_tx_bodies(
+        self,
+        name: &'static str,
+        upstream: &'static str,
+    ) -> Self {
+        self.node(name, Role::FullNode { upstream }, false)
+    }
+
+    fn node(mut self, name: &'static str, role: Role, include_tx_body: bool) -> Self {
+        assert!(
+            self.nodes.iter().all(|node| node.name != name),
+            "Node {} declared twice",
+            name
+        );
+        self.nodes.push(NodeSpec {
+            name,
+            role,
+            include_tx_body,
+        });
+        self
+    }
+
+    /// Starts the nodes and waits for their RPC servers.
+    pub(crate) async fn start(self) -> Scenario {
+        let mut scenario = Scenario {
+            nodes: vec![],
+            da_service: MockDaService::new(MockAddress::from([0; 32])),
+        };
+
+        for spec in self.nodes {
+            let node_mode = match spec.role {
+                Role::Sequencer(None) => NodeMode::SequencerNode,
+                Role::Sequencer(Some(misbehavior)) => NodeMode::MaliciousSequencer(misbehavior),
+                Role::FullNode { upstream } => {
+                    NodeMode::FullNode(scenario.node(upstream).client.rpc_addr)
+                }
+            };
+
+            let (port_tx, port_rx) = tokio::sync::oneshot::channel();
+            let min_soft_confirmations = self.min_soft_confirmations_per_commitment;
+            let task = tokio::spawn(async move {
+                start_rollup(
+                    port_tx,
+                    GenesisPaths::from_dir("../test-data/genesis/integration-tests"),
+                    BasicKernelGenesisPaths {
+                        chain_state: "../test-data/genesis/integration-tests/chain_state.json"
+                            .into(),
+                    },
+                    RollupProverConfig::Execute,
+                    node_mode,
+                    None,
+                    min_soft_confirmations,
+                    spec.include_tx_body,
+                )
+                .await;
+            });
+
+            let port = port_rx.await.unwrap();
+            scenario.nodes.push(ScenarioNode {
+                spec,
+                client: make_test_client(port).await,
+                task: Some(task),
+            });
+        }
+
+        scenario
+    }
+}
+
+struct ScenarioNode {
+    spec: NodeSpec,
+    client: Box<TestClient>,
+    task: Option<JoinHandle<()>>,
+}
+
+/// Running nodes of a scenario, and the steps to drive them.
+pub(crate) struct Scenario {
+    nodes: Vec<ScenarioNode>,
+    da_service: MockDaService,
+}
+
+impl Scenario {
+    fn node(&self, name: &str) -> &ScenarioNode {
+        self.nodes
+            .iter()
+            .find(|node| node.spec.name == name)
+            .unwrap_or_else(|| panic!("Unknown node {}", name))
+    }
+
+    /// RPC client of a node, for the steps the DSL doesn't cover.
+    pub(crate) fn client(&self, name: &str) -> &TestClient {
+        &self.node(name).client
+    }
+
+    fn sequencer(&self) -> &TestClient {
+        &self
+            .nodes
+            .iter()
+            .find(|node| matches!(node.spec.role, Role::Sequencer(_)))
+            .expect("Scenario has no sequencer")
+            .client
+    }
+
+    /// Makes the sequencer publish `count` soft batches.
+    pub(crate) async fn publish_batches(&self, count: u64) {
+        for _ in 0..count {
+            self.sequencer().send_publish_batch_request().await;
+        }
+    }
+
+    /// Publishes a new block on the mock DA layer.
+    pub(crate) async fn publish_da_block(&self) {
+        self.da_service.publish_test_block().await.unwrap();
+    }
+
+    /// Waits until the latest L2 block of a node is at least `height`.
+    pub(crate) async fn wait_for_l2_height(&self, name: &str, height: u64) {
+        let client = self.client(name);
+        let deadline = Instant::now() + STEP_TIMEOUT;
+        loop {
+            let latest = client.eth_block_number().await;
+            if latest >= height {
+                return;
+            }
+            assert!(
+                Instant::now() < deadline,
+                "Node {} stuck at L2 height {}, expected {}",
+                name,
+                latest,
+                height
+            );
+            sleep(Duration::from_millis(200)).await;
+        }
+    }
+
+    /// Asserts the latest L2 block of a node is exactly `height`.
+    pub(crate) async fn expect_l2_height(&self, name: &str, height: u64) {
+        assert_eq!(
+            self.client(name).eth_block_number().await,
+            height,
+            "Unexpected L2 height of node {}",
+            name
+        );
+    }
+
+    /// Waits until a node reports `status` for the soft confirmations at `heights`.
+    pub(crate) async fn expect_status(
+        &self,
+        name: &str,
+        heights: RangeInclusive<u64>,
+        status: SoftConfirmationStatus,
+    ) {
+        let client = self.client(name);
+        let deadline = Instant::now() + STEP_TIMEOUT;
+        for height in heights {
+            loop {
+                let current = client
+                    .ledger_get_soft_confirmation_status(height)
+                    .await
+                    .ok()
+                    .flatten();
+                if current.as_ref() == Some(&status) {
+                    break;
+                }
+                assert!(
+                    Instant::now() < deadline,
+                    "Soft confirmation {} of node {} is {:?}, expected {:?}",
+                    height,
+                    name,
+                    current,
+                    status
+                );
+                sleep(Duration::from_millis(200)).await;
+            }
+        }
+    }
+
+    /// Asserts the nodes agree on the hash and state root of their latest block.
+    pub(crate) async fn expect_same_head(&self, names: &[&str]) {
+        let mut heads = vec![];
+        for name in names {
+            let block = self
+                .client(name)
+                .eth_get_block_by_number(Some(BlockNumberOrTag::Latest))
+                .await;
+            heads.push((name, block.number, block.hash, block.state_root));
+        }
+        for head in &heads[1..] {
+            assert_eq!(
+                (head.1, head.2, head.3),
+                (heads[0].1, heads[0].2, heads[0].3),
+                "Nodes {} and {} have different heads",
+                heads[0].0,
+                head.0
+            );
+        }
+    }
+
+    /// Waits for a node to stop on a panic, and returns the panic message.
+    pub(crate) async fn expect_rejection(&mut self, name: &str) -> String {
+        let task = self
+            .nodes
+            .iter_mut()
+            .find(|node| node.spec.name == name)
+            .unwrap_or_else(|| panic!("Unknown node {}", name))
+            .task
+            .take()
+            .expect("Node already stopped");
+
+        let panic = tokio::time::timeout(STEP_TIMEOUT, task)
+            .await
+            .unwrap_or_else(|_| panic!("Node {} didn't stop", name))
+            .expect_err("Node stopped without rejecting anything")
+            .into_panic();
+
+        match panic.downcast::<String>() {
+            Ok(message) => *message,
+            Err(panic) => panic
+                .downcast::<&str>()
+                .map(|message| message.to_string())
+                .unwrap(),
+        }
+    }
+}
+
+impl Drop for Scenario {
+    fn drop(&mut self) {
+        for node in &self.nodes {
+            if let Some(task) = &node.task {
+                task.abort();
+            }
+        }
+    }
+}
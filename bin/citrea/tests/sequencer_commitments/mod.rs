@@ -114,6 +114,91 @@ async fn sequencer_sends_commitments_to_da_layer() {
     seq_task.abort();
 }
 
+#[tokio::test]
+async fn sequencer_previews_next_commitment() {
+    // citrea::initialize_logging();
+
+    let (seq_port_tx, seq_port_rx) = tokio::sync::oneshot::channel();
+
+    let seq_task = tokio::spawn(async {
+        start_rollup(
+            seq_port_tx,
+            GenesisPaths::from_dir("../test-data/genesis/integration-tests"),
+            BasicKernelGenesisPaths {
+                chain_state:
+                    "../test-data/genesis/integration-tests-low-limiting-number/chain_state.json"
+                        .into(),
+            },
+            RollupProverConfig::Execute,
+            NodeMode::SequencerNode,
+            None,
+            4,
+            true,
+        )
+        .await;
+    });
+
+    let seq_port = seq_port_rx.await.unwrap();
+    let test_client = make_test_client(seq_port).await;
+    let da_service = MockDaService::new(MockAddress::from([0; 32]));
+
+    for _ in 0..3 {
+        test_client.send_publish_batch_request().await;
+    }
+
+    let preview = test_client.citrea_preview_commitment(None).await.unwrap();
+    assert_eq!(preview["l2StartHeight"], 1);
+    assert_eq!(preview["l2EndHeight"], 3);
+    assert_eq!(preview["ready"], false);
+    assert_eq!(preview["minSoftConfirmationsPerCommitment"], 4);
+
+    test_client.send_publish_batch_request().await;
+
+    let preview = test_client.citrea_preview_commitment(None).await.unwrap();
+    assert_eq!(preview["l2EndHeight"], 4);
+    assert_eq!(preview["ready"], true);
+    assert!(preview["daSize"].as_u64().unwrap() > 0);
+
+    // a higher minimum only changes readiness
+    let with_higher_minimum = test_client
+        .citrea_preview_commitment(Some(10))
+        .await
+        .unwrap();
+    assert_eq!(with_higher_minimum["ready"], false);
+    assert_eq!(with_higher_minimum["merkleRoot"], preview["merkleRoot"]);
+
+    // the commitment sent matches the preview
+    da_service.publish_test_block().await.unwrap();
+    test_client.send_publish_batch_request().await;
+
+    let last_finalized_height = da_service
+        .get_last_finalized_block_header()
+        .await
+        .unwrap()
+        .height;
+    let block = da_service
+        .get_block_at(last_finalized_height)
+        .await
+        .unwrap();
+    let mut blobs = da_service.extract_relevant_blobs(&block);
+    assert_eq!(blobs.len(), 1);
+    let DaData::SequencerCommitment(commitment) =
+        DaData::try_from_slice(blobs.pop().unwrap().full_data()).unwrap()
+    else {
+        panic!("Expected SequencerCommitment");
+    };
+    assert_eq!(
+        preview["merkleRoot"],
+        format!("0x{}", hex::encode(commitment.merkle_root))
+    );
+
+    // the next preview starts after the committed range
+    let preview = test_client.citrea_preview_commitment(None).await.unwrap();
+    assert_eq!(preview["l2StartHeight"], 5);
+
+    seq_task.abort();
+}
+
 async fn check_sequencer_commitment(
     test_client: &TestClient,
     da_service: &MockDaService,
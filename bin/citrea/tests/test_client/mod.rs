@@ -2,22 +2,26 @@ use std::str::FromStr;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::time::Duration;
 
-use citrea_evm::LogResponse;
+use citrea_evm::{L1BlockInfo, LogResponse, TraceResult};
 use ethereum_types::H160;
 use ethers_core::abi::Address;
 use ethers_core::k256::ecdsa::SigningKey;
 use ethers_core::types::transaction::eip2718::TypedTransaction;
+use ethers_core::types::transaction::eip2930::AccessListWithGasUsed;
 use ethers_core::types::{Block, BlockId, Bytes, Eip1559TransactionRequest, Transaction, TxHash};
 use ethers_middleware::SignerMiddleware;
 use ethers_providers::{Http, Middleware, PendingTransaction, Provider};
 use ethers_signers::Wallet;
-use jsonrpsee::core::client::ClientT;
+use jsonrpsee::core::client::{ClientT, Subscription, SubscriptionClientT};
 use jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
 use jsonrpsee::rpc_params;
+use jsonrpsee::ws_client::{WsClient, WsClientBuilder};
 use reth_primitives::BlockNumberOrTag;
 use reth_rpc_types::trace::geth::{GethDebugTracingOptions, GethTrace};
+use reth_rpc_types::trace::parity::LocalizedTransactionTrace;
+use reth_rpc_types::txpool::{TxpoolContent, TxpoolInspect, TxpoolStatus};
 use sequencer_client::GetSoftBatchResponse;
-use sov_rollup_interface::rpc::SoftConfirmationStatus;
+use sov_rollup_interface::rpc::{ProofDetails, SequencerDaActivity, SoftConfirmationStatus};
 
 pub const MAX_FEE_PER_GAS: u64 = 1000000001;
 
@@ -26,6 +30,7 @@ pub struct TestClient {
     pub(crate) from_addr: Address,
     client: SignerMiddleware<Provider<Http>, Wallet<SigningKey>>,
     http_client: HttpClient,
+    ws_client: WsClient,
     current_nonce: AtomicU64,
     pub(crate) rpc_addr: std::net::SocketAddr,
 }
@@ -45,12 +50,17 @@ impl TestClient {
             .unwrap();
 
         let http_client = HttpClientBuilder::default().build(host).unwrap();
+        let ws_client = WsClientBuilder::default()
+            .build(format!("ws://localhost:{}", rpc_addr.port()))
+            .await
+            .unwrap();
 
         let client = Self {
             chain_id,
             from_addr,
             client,
             http_client,
+            ws_client,
             current_nonce: AtomicU64::new(0),
             rpc_addr,
         };
@@ -391,6 +401,13 @@ impl TestClient {
             .unwrap()
     }
 
+    pub(crate) async fn eth_max_priority_fee_per_gas(&self) -> ethereum_types::U256 {
+        self.http_client
+            .request("eth_maxPriorityFeePerGas", rpc_params![])
+            .await
+            .unwrap()
+    }
+
     pub(crate) async fn eth_fee_history(
         &self,
         block_count: String,
@@ -513,6 +530,17 @@ impl TestClient {
         gas.as_u64()
     }
 
+    pub(crate) async fn eth_create_access_list(
+        &self,
+        tx: TypedTransaction,
+        block_number: Option<BlockNumberOrTag>,
+    ) -> Result<AccessListWithGasUsed, Box<dyn std::error::Error>> {
+        self.http_client
+            .request("eth_createAccessList", rpc_params![tx, block_number])
+            .await
+            .map_err(|e| e.into())
+    }
+
     /// params is a tuple of (fromBlock, toBlock, address, topics, blockHash)
     /// any of these params are optional
     pub(crate) async fn eth_get_logs<P>(&self, params: P) -> Vec<LogResponse>
@@ -528,6 +556,69 @@ impl TestClient {
         eth_logs
     }
 
+    pub(crate) async fn eth_new_filter<P>(&self, params: P) -> serde_json::Value
+    where
+        P: serde::Serialize,
+    {
+        self.http_client
+            .request("eth_newFilter", rpc_params![params])
+            .await
+            .unwrap()
+    }
+
+    pub(crate) async fn eth_new_block_filter(&self) -> serde_json::Value {
+        self.http_client
+            .request("eth_newBlockFilter", rpc_params![])
+            .await
+            .unwrap()
+    }
+
+    pub(crate) async fn eth_new_pending_transaction_filter(&self) -> serde_json::Value {
+        self.http_client
+            .request("eth_newPendingTransactionFilter", rpc_params![])
+            .await
+            .unwrap()
+    }
+
+    pub(crate) async fn eth_get_filter_changes(
+        &self,
+        id: &serde_json::Value,
+    ) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>> {
+        Ok(self
+            .http_client
+            .request("eth_getFilterChanges", rpc_params![id])
+            .await?)
+    }
+
+    pub(crate) async fn eth_uninstall_filter(&self, id: &serde_json::Value) -> bool {
+        self.http_client
+            .request("eth_uninstallFilter", rpc_params![id])
+            .await
+            .unwrap()
+    }
+
+    pub(crate) async fn eth_subscribe(
+        &self,
+        kind: &str,
+    ) -> Result<Subscription<serde_json::Value>, jsonrpsee::core::Error> {
+        self.ws_client
+            .subscribe("eth_subscribe", rpc_params![kind], "eth_unsubscribe")
+            .await
+    }
+
+    pub(crate) async fn eth_subscribe_logs(
+        &self,
+        filter: serde_json::Value,
+    ) -> Result<Subscription<serde_json::Value>, jsonrpsee::core::Error> {
+        self.ws_client
+            .subscribe(
+                "eth_subscribe",
+                rpc_params!["logs", filter],
+                "eth_unsubscribe",
+            )
+            .await
+    }
+
     #[allow(clippy::extra_unused_type_parameters)]
     pub(crate) async fn ledger_get_soft_batch_by_number<
         DaSpec: sov_rollup_interface::da::DaSpec,
@@ -554,6 +645,64 @@ impl TestClient {
             .map_err(|e| e.into())
     }
 
+    pub(crate) async fn ledger_get_soft_batches_by_status(
+        &self,
+        status: SoftConfirmationStatus,
+        start: u64,
+        end: u64,
+    ) -> Result<Vec<u64>, Box<dyn std::error::Error>> {
+        self.http_client
+            .request(
+                "ledger_getSoftBatchesByStatus",
+                rpc_params![status, start, end],
+            )
+            .await
+            .map_err(|e| e.into())
+    }
+
+    pub(crate) async fn prover_get_proof_details(
+        &self,
+        da_slot_hash: [u8; 32],
+    ) -> Option<ProofDetails> {
+        self.http_client
+            .request(
+                "prover_getProofDetails",
+                rpc_params![format!("0x{}", hex::encode(da_slot_hash))],
+            )
+            .await
+            .unwrap()
+    }
+
+    pub(crate) async fn citrea_get_l1_block_info(
+        &self,
+        block_number: BlockNumberOrTag,
+    ) -> Option<L1BlockInfo> {
+        self.http_client
+            .request("citrea_getL1BlockInfo", rpc_params![block_number])
+            .await
+            .unwrap()
+    }
+
+    pub(crate) async fn citrea_get_sequencer_da_activity(&self) -> SequencerDaActivity {
+        self.http_client
+            .request("citrea_getSequencerDaActivity", rpc_params![])
+            .await
+            .unwrap()
+    }
+
+    pub(crate) async fn citrea_preview_commitment(
+        &self,
+        min_soft_confirmations_per_commitment: Option<u64>,
+    ) -> Option<serde_json::Value> {
+        self.http_client
+            .request(
+                "citrea_previewCommitment",
+                rpc_params![min_soft_confirmations_per_commitment],
+            )
+            .await
+            .unwrap()
+    }
+
     pub(crate) async fn get_limiting_number(&self) -> u64 {
         self.http_client
             .request(
@@ -564,6 +713,27 @@ impl TestClient {
             .unwrap()
     }
 
+    pub(crate) async fn txpool_content(&self) -> TxpoolContent {
+        self.http_client
+            .request("txpool_content", rpc_params![])
+            .await
+            .unwrap()
+    }
+
+    pub(crate) async fn txpool_status(&self) -> TxpoolStatus {
+        self.http_client
+            .request("txpool_status", rpc_params![])
+            .await
+            .unwrap()
+    }
+
+    pub(crate) async fn txpool_inspect(&self) -> TxpoolInspect {
+        self.http_client
+            .request("txpool_inspect", rpc_params![])
+            .await
+            .unwrap()
+    }
+
     pub(crate) async fn debug_trace_transaction(
         &self,
         tx_hash: TxHash,
@@ -575,11 +745,43 @@ impl TestClient {
             .unwrap()
     }
 
+    pub(crate) async fn debug_trace_call(
+        &self,
+        request: serde_json::Value,
+        block_number: Option<BlockNumberOrTag>,
+        opts: Option<serde_json::Value>,
+    ) -> Result<GethTrace, Box<dyn std::error::Error>> {
+        self.http_client
+            .request("debug_traceCall", rpc_params![request, block_number, opts])
+            .await
+            .map_err(|e| e.into())
+    }
+
+    pub(crate) async fn trace_transaction(
+        &self,
+        tx_hash: TxHash,
+    ) -> Option<Vec<LocalizedTransactionTrace>> {
+        self.http_client
+            .request("trace_transaction", rpc_params![tx_hash])
+            .await
+            .unwrap()
+    }
+
+    pub(crate) async fn trace_filter(
+        &self,
+        filter: serde_json::Value,
+    ) -> Vec<LocalizedTransactionTrace> {
+        self.http_client
+            .request("trace_filter", rpc_params![filter])
+            .await
+            .unwrap()
+    }
+
     pub(crate) async fn debug_trace_block_by_number(
         &self,
         block_number: BlockNumberOrTag,
         opts: Option<GethDebugTracingOptions>,
-    ) -> Vec<GethTrace> {
+    ) -> Vec<TraceResult> {
         self.http_client
             .request("debug_traceBlockByNumber", rpc_params![block_number, opts])
             .await
@@ -590,7 +792,7 @@ impl TestClient {
         &self,
         block_hash: ethereum_types::H256,
         opts: Option<GethDebugTracingOptions>,
-    ) -> Vec<GethTrace> {
+    ) -> Vec<TraceResult> {
         self.http_client
             .request("debug_traceBlockByHash", rpc_params![block_hash, opts])
             .await
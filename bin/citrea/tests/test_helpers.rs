@@ -2,7 +2,7 @@ use std::net::SocketAddr;
 use std::path::Path;
 
 use citrea::MockDemoRollup;
-use citrea_sequencer::SequencerConfig;
+use citrea_sequencer::{ScriptedMisbehavior, SequencerConfig};
 use citrea_stf::genesis_config::GenesisPaths;
 use const_rollup_config::TEST_PRIVATE_KEY;
 use sov_mock_da::{MockAddress, MockDaConfig};
@@ -19,12 +19,20 @@ use sov_stf_runner::{
 use tokio::sync::oneshot;
 use tracing::warn;
 
+/// Public key of the sequencer of the test rollups.
+pub const SEQUENCER_PUBLIC_KEY: [u8; 32] = [
+    32, 64, 64, 227, 100, 193, 15, 43, 236, 156, 31, 229, 0, 161, 205, 76, 36, 124, 137, 214, 80,
+    160, 30, 215, 232, 44, 171, 168, 103, 135, 124, 33,
+];
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum NodeMode {
     FullNode(SocketAddr),
     SequencerNode,
     #[allow(dead_code)]
     Prover(SocketAddr),
+    /// A sequencer tampering with the soft confirmation it publishes at a height.
+    MaliciousSequencer(ScriptedMisbehavior),
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -47,12 +55,11 @@ pub async fn start_rollup(
     }
 
     let rollup_config = RollupConfig {
-        sequencer_public_key: vec![
-            32, 64, 64, 227, 100, 193, 15, 43, 236, 156, 31, 229, 0, 161, 205, 76, 36, 124, 137,
-            214, 80, 160, 30, 215, 232, 44, 171, 168, 103, 135, 124, 33,
-        ],
+        sequencer_public_key: SEQUENCER_PUBLIC_KEY.to_vec(),
         storage: StorageConfig {
             path: path.unwrap().to_path_buf(),
+            min_free_disk_space_mb: None,
+            check_interval_secs: 60,
         },
         runner: RunnerConfig {
             start_height: 1,
@@ -60,6 +67,18 @@ pub async fn start_rollup(
                 bind_host: "127.0.0.1".into(),
                 bind_port: 0,
                 max_connections: 100,
+                abi_registry_path: None,
+                contract_verification_path: None,
+                response_etags: false,
+                allowed_methods: None,
+                gas_price_oracle: None,
+                archive: true,
+                state_history_blocks: 128,
+                batch_requests_limit: 100,
+                batch_weight_limit: 1000,
+                method_weights: Default::default(),
+                index_transactions_by_address: false,
+                accept_pushed_soft_batches: false,
             },
         },
         da: MockDaConfig {
@@ -67,23 +86,42 @@ pub async fn start_rollup(
         },
         prover_service: ProverServiceConfig {
             aggregated_proof_block_jump: 1,
+            wrap_proofs: false,
+            proof_market: None,
+            memory_watchdog: None,
         },
         sequencer_client: match node_mode {
             NodeMode::FullNode(socket_addr) | NodeMode::Prover(socket_addr) => {
                 Some(SequencerClientRpcConfig {
                     url: format!("http://localhost:{}", socket_addr.port()),
+                    compact_relay: false,
+                    stream_receipts: false,
+                    delegate_unsynced_reads: false,
                 })
             }
-            NodeMode::SequencerNode => None,
+            NodeMode::SequencerNode | NodeMode::MaliciousSequencer(_) => None,
         },
         sequencer_da_pub_key: vec![0; 32],
         prover_da_pub_key: vec![],
         include_tx_body,
+        telemetry: None,
+        profiling: None,
+        light_node: None,
+        proof_code_commitment: None,
     };
 
     let sequencer_config = SequencerConfig {
         min_soft_confirmations_per_commitment,
         mempool_conf: Default::default(),
+        validation_gas_policy: Default::default(),
+        memory_budget_mb: None,
+        rate_limits: Default::default(),
+        block_production_interval_ms: None,
+        push_soft_batches_to: vec![],
+        test_misbehaviors: match node_mode {
+            NodeMode::MaliciousSequencer(misbehavior) => vec![misbehavior],
+            _ => vec![],
+        },
     };
 
     let mock_demo_rollup = MockDemoRollup {};
@@ -129,7 +167,7 @@ pub async fn start_rollup(
                 .await
                 .unwrap();
         }
-        NodeMode::SequencerNode => {
+        NodeMode::SequencerNode | NodeMode::MaliciousSequencer(_) => {
             warn!(
                 "Starting sequencer node pub key: {:?}",
                 DefaultPrivateKey::from_hex(TEST_PRIVATE_KEY)
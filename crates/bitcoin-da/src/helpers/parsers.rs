@@ -64,6 +64,25 @@ pub fn parse_transaction(
     parse_relevant_inscriptions(&mut instructions, rollup_name)
 }
 
+/// Returns false if the transaction certainly has no envelope for the rollup,
+/// without parsing the script. This is a byte scan of the tapscript of the first input
+/// for the `OP_IF` opening the envelope and for the pushed rollup name,
+/// which are contained in any script [`parse_transaction`] accepts.
+pub fn may_contain_envelope(tx: &Transaction, rollup_name: &str) -> bool {
+    let Ok(script) = get_script(tx) else {
+        return false;
+    };
+    let script = script.as_bytes();
+    script.contains(&OP_IF.to_u8()) && contains(script, rollup_name.as_bytes())
+}
+
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    needle.is_empty()
+        || haystack
+            .windows(needle.len())
+            .any(|window| window == needle)
+}
+
 // Returns the script from the first input of the transaction
 fn get_script(tx: &Transaction) -> Result<&Script, ParserError> {
     tx.input[0]
@@ -197,15 +216,17 @@ pub fn parse_hex_transaction(
 }
 #[cfg(test)]
 mod tests {
+    use bitcoin::absolute::LockTime;
     use bitcoin::key::XOnlyPublicKey;
     use bitcoin::opcodes::all::{OP_CHECKSIG, OP_ENDIF, OP_IF};
     use bitcoin::opcodes::{OP_FALSE, OP_TRUE};
     use bitcoin::script::{self, PushBytesBuf};
-    use bitcoin::Transaction;
+    use bitcoin::transaction::Version;
+    use bitcoin::{Transaction, TxIn, Witness};
 
     use super::{
-        parse_relevant_inscriptions, BODY_TAG, PUBLICKEY_TAG, RANDOM_TAG, ROLLUP_NAME_TAG,
-        SIGNATURE_TAG,
+        may_contain_envelope, parse_relevant_inscriptions, BODY_TAG, PUBLICKEY_TAG, RANDOM_TAG,
+        ROLLUP_NAME_TAG, SIGNATURE_TAG,
     };
     use crate::helpers::parsers::{parse_transaction, ParserError};
 
@@ -532,4 +553,49 @@ mod tests {
         assert_eq!(result.signature, vec![0u8; 64]);
         assert_eq!(result.public_key, vec![0u8; 64]);
     }
+
+    #[test]
+    fn prefilter() {
+        let reveal_script = script::Builder::new()
+            .push_x_only_key(&XOnlyPublicKey::from_slice(&[1; 32]).unwrap())
+            .push_opcode(OP_CHECKSIG)
+            .push_opcode(OP_FALSE)
+            .push_opcode(OP_IF)
+            .push_slice(PushBytesBuf::try_from(ROLLUP_NAME_TAG.to_vec()).unwrap())
+            .push_slice(PushBytesBuf::try_from("sov-btc".as_bytes().to_vec()).unwrap())
+            .push_slice(PushBytesBuf::try_from(SIGNATURE_TAG.to_vec()).unwrap())
+            .push_slice(PushBytesBuf::try_from(vec![0u8; 64]).unwrap())
+            .push_slice(PushBytesBuf::try_from(PUBLICKEY_TAG.to_vec()).unwrap())
+            .push_slice(PushBytesBuf::try_from(vec![0u8; 64]).unwrap())
+            .push_slice(PushBytesBuf::try_from(RANDOM_TAG.to_vec()).unwrap())
+            .push_int(0)
+            .push_slice(PushBytesBuf::try_from(BODY_TAG.to_vec()).unwrap())
+            .push_slice(PushBytesBuf::try_from(vec![0u8; 128]).unwrap())
+            .push_opcode(OP_ENDIF)
+            .into_script();
+
+        let mut witness = Witness::new();
+        witness.push([0u8; 64]);
+        witness.push(reveal_script.as_bytes());
+        witness.push([0xc0; 33]);
+        let tx = Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![TxIn {
+                witness,
+                ..Default::default()
+            }],
+            output: vec![],
+        };
+
+        assert!(may_contain_envelope(&tx, "sov-btc"));
+        assert!(!may_contain_envelope(&tx, "other-rollup"));
+
+        // not a tapscript spend
+        let hex_tx = "020000000001013a66019bfcc719ba12586a83ebbb0b3debdc945f563cd64fd44c8044e3d3a1790100000000fdffffff028fa2aa060000000017a9147ba15d4e0d8334de3a68cf3687594e2d1ee5b00d879179e0090000000016001493c93ad222e57d65438545e048822ede2d418a3d0247304402202432e6c422b93705fbc57b350ea43e4ef9441c0907988eff051eaac807fc8cf2022046c92b540b5f04f8da11febb5d2a478aed1b8bc088e769da8b78fffcae8c9a9a012103e2991b47d9c788f55379f9ef519b642d79d7dfe0e7555ec5575ee934b2dca1223f5d0c00";
+        let tx: Transaction =
+            bitcoin::consensus::deserialize(&hex::decode(hex_tx).unwrap()).unwrap();
+
+        assert!(!may_contain_envelope(&tx, "sov-btc"));
+    }
 }
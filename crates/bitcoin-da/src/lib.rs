@@ -1,5 +1,9 @@
 mod helpers;
 #[cfg(feature = "native")]
+mod metrics;
+#[cfg(feature = "native")]
+pub mod network;
+#[cfg(feature = "native")]
 mod rpc;
 pub mod spec;
 
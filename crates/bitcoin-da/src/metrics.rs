@@ -0,0 +1,40 @@
+use once_cell::sync::Lazy;
+use prometheus::{
+    exponential_buckets, register_histogram, register_int_counter_vec, Histogram, IntCounterVec,
+};
+
+/// Transactions scanned for relevant blobs, by how the scan ended:
+/// `skipped_txid_prefix`, `skipped_prefilter`, `parse_failed`, `invalid_signature` or `relevant`.
+pub static DA_SCANNED_TRANSACTIONS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        // metric name
+        "bitcoin_da_scanned_transactions_total",
+        // metric description
+        "Transactions scanned for relevant blobs",
+        // metric labels (dimensions)
+        &["result"]
+    )
+    .unwrap()
+});
+
+pub static DA_BLOCK_SCAN_LATENCY_SECONDS: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        // metric name
+        "bitcoin_da_block_scan_latency_seconds",
+        // metric description
+        "Latency of extracting relevant blobs from a block in seconds",
+        exponential_buckets(/*start=*/ 1e-5, /*factor=*/ 2.0, /*count=*/ 22).unwrap(),
+    )
+    .unwrap()
+});
+
+pub static DA_INSCRIPTION_PARSE_LATENCY_SECONDS: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        // metric name
+        "bitcoin_da_inscription_parse_latency_seconds",
+        // metric description
+        "Latency of parsing the inscription of a transaction in seconds",
+        exponential_buckets(/*start=*/ 1e-6, /*factor=*/ 2.0, /*count=*/ 22).unwrap(),
+    )
+    .unwrap()
+});
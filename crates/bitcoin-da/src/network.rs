@@ -0,0 +1,166 @@
+use core::fmt::Display;
+use core::str::FromStr;
+
+use anyhow::anyhow;
+use bitcoin::secp256k1::{PublicKey, Secp256k1};
+use bitcoin::{Address, AddressType};
+use serde::{Deserialize, Serialize};
+
+// Longest reveal tx id prefix, every extra byte multiplies the reveal tx nonce search by 256
+pub const MAX_REVEAL_TX_ID_PREFIX_LEN: usize = 4;
+
+/// Bitcoin network the DA service posts to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BitcoinNetwork {
+    #[serde(alias = "bitcoin", alias = "main")]
+    Mainnet,
+    #[serde(alias = "test")]
+    Testnet,
+    Testnet4,
+    Signet,
+    Regtest,
+}
+
+impl BitcoinNetwork {
+    /// The network used for address encoding and validation.
+    /// testnet4 shares its address format with testnet3.
+    pub fn address_network(&self) -> bitcoin::Network {
+        match self {
+            BitcoinNetwork::Mainnet => bitcoin::Network::Bitcoin,
+            BitcoinNetwork::Testnet | BitcoinNetwork::Testnet4 => bitcoin::Network::Testnet,
+            BitcoinNetwork::Signet => bitcoin::Network::Signet,
+            BitcoinNetwork::Regtest => bitcoin::Network::Regtest,
+        }
+    }
+
+    /// The chain name reported by `getblockchaininfo` on this network
+    pub fn chain_name(&self) -> &'static str {
+        match self {
+            BitcoinNetwork::Mainnet => "main",
+            BitcoinNetwork::Testnet => "test",
+            BitcoinNetwork::Testnet4 => "testnet4",
+            BitcoinNetwork::Signet => "signet",
+            BitcoinNetwork::Regtest => "regtest",
+        }
+    }
+
+    /// Fails if the chain of the connected bitcoind is not this network
+    pub fn check_chain(&self, chain: &str) -> Result<(), anyhow::Error> {
+        if chain != self.chain_name() {
+            return Err(anyhow!(
+                "Bitcoin node is on chain {} but the DA service is configured for {}",
+                chain,
+                self
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Display for BitcoinNetwork {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let name = match self {
+            BitcoinNetwork::Mainnet => "mainnet",
+            BitcoinNetwork::Testnet => "testnet",
+            BitcoinNetwork::Testnet4 => "testnet4",
+            BitcoinNetwork::Signet => "signet",
+            BitcoinNetwork::Regtest => "regtest",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for BitcoinNetwork {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_value(serde_json::Value::String(s.to_string()))
+            .map_err(|_| anyhow!("Invalid bitcoin network name: {}", s))
+    }
+}
+
+/// Address types the sequencer can hold its funds at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SequencerAddressType {
+    P2wpkh,
+    P2tr,
+}
+
+impl SequencerAddressType {
+    pub fn address_type(&self) -> AddressType {
+        match self {
+            SequencerAddressType::P2wpkh => AddressType::P2wpkh,
+            SequencerAddressType::P2tr => AddressType::P2tr,
+        }
+    }
+
+    /// Derives the address of this type paying to the public key on the network.
+    /// P2TR addresses use the key as internal key, without a script tree.
+    pub fn derive_address(&self, public_key: &PublicKey, network: BitcoinNetwork) -> Address {
+        let network = network.address_network();
+        match self {
+            SequencerAddressType::P2wpkh => {
+                Address::p2wpkh(&bitcoin::PublicKey::new(*public_key), network)
+                    .expect("Compressed public keys always have a P2WPKH address")
+            }
+            SequencerAddressType::P2tr => Address::p2tr(
+                &Secp256k1::new(),
+                public_key.x_only_public_key().0,
+                None,
+                network,
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_network_names() {
+        assert_eq!(
+            BitcoinNetwork::from_str("bitcoin").unwrap(),
+            BitcoinNetwork::Mainnet
+        );
+        assert_eq!(
+            BitcoinNetwork::from_str("testnet4").unwrap(),
+            BitcoinNetwork::Testnet4
+        );
+        assert_eq!(
+            BitcoinNetwork::from_str("signet").unwrap(),
+            BitcoinNetwork::Signet
+        );
+        assert!(BitcoinNetwork::from_str("testnet5").is_err());
+
+        assert_eq!(
+            BitcoinNetwork::Testnet4.address_network(),
+            bitcoin::Network::Testnet
+        );
+    }
+
+    #[test]
+    fn check_bitcoind_chain() {
+        assert!(BitcoinNetwork::Regtest.check_chain("regtest").is_ok());
+        assert!(BitcoinNetwork::Testnet4.check_chain("testnet4").is_ok());
+        assert!(BitcoinNetwork::Testnet4.check_chain("test").is_err());
+        assert!(BitcoinNetwork::Mainnet.check_chain("signet").is_err());
+    }
+
+    #[test]
+    fn derive_addresses() {
+        let secret_key = bitcoin::secp256k1::SecretKey::from_slice(&[7u8; 32]).unwrap();
+        let public_key = secret_key.public_key(&Secp256k1::new());
+
+        for address_type in [SequencerAddressType::P2wpkh, SequencerAddressType::P2tr] {
+            let address = address_type.derive_address(&public_key, BitcoinNetwork::Regtest);
+            assert_eq!(address.address_type(), Some(address_type.address_type()));
+            assert!(address.to_string().starts_with("bcrt1"));
+
+            let address = address_type.derive_address(&public_key, BitcoinNetwork::Testnet4);
+            assert!(address.is_valid_for_network(bitcoin::Network::Testnet));
+        }
+    }
+}
@@ -135,6 +135,18 @@ impl BitcoinNode {
         self.call::<u64>("getblockcount", vec![]).await
     }
 
+    // get_chain returns the name of the chain the node is on, as in getblockchaininfo
+    pub async fn get_chain(&self) -> Result<String, anyhow::Error> {
+        let info = self
+            .call::<serde_json::Value>("getblockchaininfo", vec![])
+            .await?;
+
+        info["chain"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| anyhow!("No chain in getblockchaininfo response"))
+    }
+
     // get_block_hash returns the block hash of the block at the given height
     pub async fn get_block_hash(&self, height: u64) -> Result<String, anyhow::Error> {
         self.call::<String>("getblockhash", vec![to_value(height)?])
@@ -3,6 +3,7 @@ use core::str::FromStr;
 use core::time::Duration;
 
 // use std::sync::Arc;
+use anyhow::anyhow;
 use async_trait::async_trait;
 use bitcoin::address::NetworkUnchecked;
 use bitcoin::consensus::encode;
@@ -20,7 +21,11 @@ use crate::helpers::builders::{
     compress_blob, create_inscription_transactions, decompress_blob, sign_blob_with_private_key,
     write_reveal_tx,
 };
-use crate::helpers::parsers::parse_transaction;
+use crate::helpers::parsers::{may_contain_envelope, parse_transaction};
+use crate::metrics::{
+    DA_BLOCK_SCAN_LATENCY_SECONDS, DA_INSCRIPTION_PARSE_LATENCY_SECONDS, DA_SCANNED_TRANSACTIONS,
+};
+use crate::network::{BitcoinNetwork, SequencerAddressType, MAX_REVEAL_TX_ID_PREFIX_LEN};
 use crate::rpc::{BitcoinNode, RPCError};
 use crate::spec::blob::BlobWithSender;
 use crate::spec::block::BitcoinBlock;
@@ -50,13 +55,22 @@ pub struct DaServiceConfig {
     pub node_username: String,
     pub node_password: String,
 
-    // network of the bitcoin node
-    pub network: String,
+    // network of the bitcoin node, checked against the chain of the node on startup
+    pub network: BitcoinNetwork,
 
-    // taproot address that holds the funds of the sequencer
+    // address that holds the funds of the sequencer
     // will be used as the change address for the inscribe transaction
     pub address: String,
 
+    // type the address must be of, any type is accepted if not set
+    #[serde(default)]
+    pub address_type: Option<SequencerAddressType>,
+
+    // hex prefix of the reveal tx ids, overriding the one of the rollup
+    // must be the same on every node of the rollup
+    #[serde(default)]
+    pub reveal_tx_id_prefix: Option<String>,
+
     // da private key of the sequencer
     pub sequencer_da_private_key: Option<String>,
 
@@ -64,24 +78,68 @@ pub struct DaServiceConfig {
     pub fee_rates_to_avg: Option<usize>,
 }
 
+impl DaServiceConfig {
+    /// Returns the sequencer address, checked against the network and address type
+    pub fn checked_address(&self) -> Result<Address, anyhow::Error> {
+        let address = Address::from_str(&self.address)
+            .map_err(|e| anyhow!("Invalid bitcoin address {}: {}", self.address, e))?
+            .require_network(self.network.address_network())
+            .map_err(|e| anyhow!("Address is not a {} address: {}", self.network, e))?;
+
+        if let Some(address_type) = self.address_type {
+            if address.address_type() != Some(address_type.address_type()) {
+                return Err(anyhow!(
+                    "Address {} is not a {:?} address",
+                    address,
+                    address_type
+                ));
+            }
+        }
+
+        Ok(address)
+    }
+
+    /// Returns the rollup params with the configured reveal tx id prefix, if any
+    pub fn rollup_params(
+        &self,
+        rollup_name: &str,
+        default_reveal_tx_id_prefix: &[u8],
+    ) -> Result<RollupParams, anyhow::Error> {
+        let reveal_tx_id_prefix = match &self.reveal_tx_id_prefix {
+            Some(prefix) => hex::decode(prefix.trim_start_matches("0x"))
+                .map_err(|e| anyhow!("Invalid reveal tx id prefix {}: {}", prefix, e))?,
+            None => default_reveal_tx_id_prefix.to_vec(),
+        };
+        if reveal_tx_id_prefix.len() > MAX_REVEAL_TX_ID_PREFIX_LEN {
+            return Err(anyhow!(
+                "Reveal tx id prefix is longer than {} bytes",
+                MAX_REVEAL_TX_ID_PREFIX_LEN
+            ));
+        }
+
+        Ok(RollupParams {
+            rollup_name: rollup_name.to_string(),
+            reveal_tx_id_prefix,
+        })
+    }
+}
+
 const FINALITY_DEPTH: u64 = 4; // blocks
 const POLLING_INTERVAL: u64 = 10; // seconds
 
 impl BitcoinService {
     // Create a new instance of the DA service from the given configuration.
     pub async fn new(config: DaServiceConfig, chain_params: RollupParams) -> Self {
-        let network =
-            bitcoin::Network::from_str(&config.network).expect("Invalid bitcoin network name");
+        let network = config.network;
+        let address = config.checked_address().expect("Invalid sequencer address");
 
         let client = BitcoinNode::new(
             config.node_url,
             config.node_username,
             config.node_password,
-            network,
+            network.address_network(),
         );
 
-        let address = Address::from_str(&config.address).expect("Invalid bitcoin address");
-
         let private_key = config
             .sequencer_da_private_key
             .map(|pk| SecretKey::from_str(&pk).expect("Invalid private key"));
@@ -90,7 +148,7 @@ impl BitcoinService {
             client,
             chain_params.rollup_name,
             network,
-            address,
+            address.as_unchecked().clone(),
             private_key,
             chain_params.reveal_tx_id_prefix,
         )
@@ -99,8 +157,12 @@ impl BitcoinService {
 
     #[cfg(test)]
     pub async fn new_without_client(config: DaServiceConfig, chain_params: RollupParams) -> Self {
-        let network =
-            bitcoin::Network::from_str(&config.network).expect("Invalid bitcoin network name");
+        let network = config.network.address_network();
+        let address = config
+            .checked_address()
+            .expect("Invalid sequencer address")
+            .as_unchecked()
+            .clone();
 
         let client = BitcoinNode::new(
             config.node_url,
@@ -109,8 +171,6 @@ impl BitcoinService {
             network,
         );
 
-        let address = Address::from_str(&config.address).expect("Invalid bitcoin address");
-
         let private_key = config
             .sequencer_da_private_key
             .map(|pk| SecretKey::from_str(&pk).expect("Invalid private key"));
@@ -128,7 +188,7 @@ impl BitcoinService {
     pub async fn with_client(
         client: BitcoinNode,
         rollup_name: String,
-        network: bitcoin::Network,
+        network: BitcoinNetwork,
         address: Address<NetworkUnchecked>,
         sequencer_da_private_key: Option<SecretKey>,
         reveal_tx_id_prefix: Vec<u8>,
@@ -136,9 +196,18 @@ impl BitcoinService {
         // We can't store address with the network check because it's not serializable
         address
             .clone()
-            .require_network(network)
+            .require_network(network.address_network())
             .expect("Invalid address for network!");
 
+        // Fail fast instead of posting to the wrong network
+        let chain = client
+            .get_chain()
+            .await
+            .expect("Failed to get the chain of the bitcoin node");
+        network
+            .check_chain(&chain)
+            .expect("Bitcoin node is on another network");
+
         let wallets = client
             .list_wallets()
             .await
@@ -151,7 +220,7 @@ impl BitcoinService {
         Self {
             client,
             rollup_name,
-            network,
+            network: network.address_network(),
             address,
             sequencer_da_private_key,
             reveal_tx_id_prefix,
@@ -331,6 +400,8 @@ impl DaService for BitcoinService {
             block.header.block_hash()
         );
 
+        let _timer = DA_BLOCK_SCAN_LATENCY_SECONDS.start_timer();
+
         // iterate over all transactions in the block
         for tx in block.txdata.iter() {
             if !tx
@@ -339,26 +410,54 @@ impl DaService for BitcoinService {
                 .as_slice()
                 .starts_with(self.reveal_tx_id_prefix.as_slice())
             {
+                DA_SCANNED_TRANSACTIONS
+                    .with_label_values(&["skipped_txid_prefix"])
+                    .inc();
                 continue;
             }
 
-            // check if the inscription in script is relevant to the rollup
-            let parsed_inscription = parse_transaction(tx, &self.rollup_name);
+            // skip transactions without an envelope for the rollup before parsing the script
+            if !may_contain_envelope(tx, &self.rollup_name) {
+                DA_SCANNED_TRANSACTIONS
+                    .with_label_values(&["skipped_prefilter"])
+                    .inc();
+                continue;
+            }
 
-            if let Ok(inscription) = parsed_inscription {
-                if inscription.get_sig_verified_hash().is_some() {
-                    // Decompress the blob
-                    let decompressed_blob = decompress_blob(&inscription.body);
+            // check if the inscription in script is relevant to the rollup
+            let parsed_inscription = {
+                let _timer = DA_INSCRIPTION_PARSE_LATENCY_SECONDS.start_timer();
+                parse_transaction(tx, &self.rollup_name)
+            };
 
-                    let relevant_tx = BlobWithSender::new(
-                        decompressed_blob,
-                        inscription.public_key,
-                        sha256d::Hash::hash(&inscription.body).to_byte_array(),
-                    );
+            let Ok(inscription) = parsed_inscription else {
+                DA_SCANNED_TRANSACTIONS
+                    .with_label_values(&["parse_failed"])
+                    .inc();
+                continue;
+            };
 
-                    txs.push(relevant_tx);
-                }
+            if inscription.get_sig_verified_hash().is_none() {
+                DA_SCANNED_TRANSACTIONS
+                    .with_label_values(&["invalid_signature"])
+                    .inc();
+                continue;
             }
+
+            DA_SCANNED_TRANSACTIONS
+                .with_label_values(&["relevant"])
+                .inc();
+
+            // Decompress the blob
+            let decompressed_blob = decompress_blob(&inscription.body);
+
+            let relevant_tx = BlobWithSender::new(
+                decompressed_blob,
+                inscription.public_key,
+                sha256d::Hash::hash(&inscription.body).to_byte_array(),
+            );
+
+            txs.push(relevant_tx);
         }
         txs
     }
@@ -496,27 +595,32 @@ mod tests {
     use super::BitcoinService;
     use crate::helpers::parsers::parse_hex_transaction;
     use crate::helpers::test_utils::{get_mock_data, get_mock_txs};
+    use crate::network::{BitcoinNetwork, SequencerAddressType};
     use crate::service::DaServiceConfig;
     use crate::spec::block::BitcoinBlock;
     use crate::spec::header::HeaderWrapper;
     use crate::spec::RollupParams;
     use crate::verifier::BitcoinVerifier;
 
-    async fn get_service() -> BitcoinService {
-        let runtime_config = DaServiceConfig {
+    fn get_config() -> DaServiceConfig {
+        DaServiceConfig {
             node_url: "http://localhost:38332".to_string(),
             node_username: "chainway".to_string(),
             node_password: "topsecret".to_string(),
-            network: "regtest".to_string(),
+            network: BitcoinNetwork::Regtest,
             address: "bcrt1qy85zdv5se9d9ceg9nvay36t6j86z95fny4rdzu".to_string(),
+            address_type: None,
+            reveal_tx_id_prefix: None,
             sequencer_da_private_key: Some(
                 "E9873D79C6D87DC0FB6A5778633389F4453213303DA61F20BD67FC233AA33262".to_string(), // Test key, safe to publish
             ),
             fee_rates_to_avg: Some(2), // small to speed up tests
-        };
+        }
+    }
 
+    async fn get_service() -> BitcoinService {
         BitcoinService::new_without_client(
-            runtime_config,
+            get_config(),
             RollupParams {
                 rollup_name: "sov-btc".to_string(),
                 reveal_tx_id_prefix: vec![0, 0],
@@ -525,6 +629,43 @@ mod tests {
         .await
     }
 
+    #[test]
+    fn check_network_config() {
+        let config = get_config();
+        assert!(config.checked_address().is_ok());
+
+        let wrong_network = DaServiceConfig {
+            network: BitcoinNetwork::Testnet4,
+            ..get_config()
+        };
+        assert!(wrong_network.checked_address().is_err());
+
+        let wrong_address_type = DaServiceConfig {
+            address_type: Some(SequencerAddressType::P2tr),
+            ..get_config()
+        };
+        assert!(wrong_address_type.checked_address().is_err());
+        let address_type = DaServiceConfig {
+            address_type: Some(SequencerAddressType::P2wpkh),
+            ..get_config()
+        };
+        assert!(address_type.checked_address().is_ok());
+
+        let params = config.rollup_params("sov-btc", &[0, 0]).unwrap();
+        assert_eq!(params.reveal_tx_id_prefix, vec![0, 0]);
+        let prefix = DaServiceConfig {
+            reveal_tx_id_prefix: Some("0x0a".to_string()),
+            ..get_config()
+        };
+        let params = prefix.rollup_params("sov-btc", &[0, 0]).unwrap();
+        assert_eq!(params.reveal_tx_id_prefix, vec![10]);
+        let long_prefix = DaServiceConfig {
+            reveal_tx_id_prefix: Some("0000000000".to_string()),
+            ..get_config()
+        };
+        assert!(long_prefix.rollup_params("sov-btc", &[0, 0]).is_err());
+    }
+
     // #[tokio::test]
     // async fn get_finalized_at() {
     //     let da_service = get_service().await;
@@ -623,8 +764,10 @@ mod tests {
             node_url: "http://localhost:38332".to_string(),
             node_username: "chainway".to_string(),
             node_password: "topsecret".to_string(),
-            network: "regtest".to_string(),
+            network: BitcoinNetwork::Regtest,
             address: "bcrt1qy85zdv5se9d9ceg9nvay36t6j86z95fny4rdzu".to_string(),
+            address_type: None,
+            reveal_tx_id_prefix: None,
             sequencer_da_private_key: Some(
                 "E9873D79C6D87DC0FB6A5778633389F4453213303DA61F20BD67FC233AA33261".to_string(), // Test key, safe to publish
             ),
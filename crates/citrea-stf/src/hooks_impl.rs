@@ -82,6 +82,8 @@ impl<C: Context, Da: DaSpec> ApplySoftConfirmationHooks<Da> for Runtime<C, Da> {
             soft_batch.timestamp(),
             working_set,
         );
+        self.evm
+            .apply_da_governance_actions(&soft_batch.da_slot_governance, working_set);
 
         Ok(())
     }
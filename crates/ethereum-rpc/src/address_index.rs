@@ -0,0 +1,173 @@
+use std::sync::Arc;
+
+use citrea_evm::Evm;
+use jsonrpsee::types::ErrorObjectOwned;
+use reth_primitives::{Address, BlockNumberOrTag, B256, U64};
+use reth_rpc_types::{BlockTransactions, Transaction};
+use serde::{Deserialize, Serialize};
+use sov_db::ledger_db::LedgerDB;
+use sov_db::schema::types::BatchNumber;
+use sov_modules_api::utils::to_jsonrpsee_error_object;
+use sov_modules_api::WorkingSet;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::warn;
+
+use crate::ETH_RPC_ERROR;
+
+/// The maximum number of transactions returned by a single `citrea_getTransactionsByAddress` request.
+pub const MAX_ADDRESS_TXS_PER_REQUEST: usize = 100;
+
+/// Position of a transaction in the chain, the cursor of `citrea_getTransactionsByAddress`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressTxCursor {
+    /// Number of the block of the transaction.
+    pub block_number: U64,
+    /// Index of the transaction in the block.
+    pub transaction_index: U64,
+}
+
+/// A page of the transactions of an address.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressTransactions {
+    /// Transactions sent by or to the address, newest first.
+    pub transactions: Vec<Transaction>,
+    /// Cursor of the next page, None on the last page.
+    pub next_cursor: Option<AddressTxCursor>,
+}
+
+/// Index of the transactions of the blocks synced by the node by the addresses that sent or
+/// received them, kept in the ledger DB so that explorers can list the history of an address
+/// without indexing the chain themselves.
+///
+/// Blocks are indexed as their soft batches are committed. The blocks synced before the index
+/// was enabled are indexed on startup.
+pub(crate) struct AddressIndex<C: sov_modules_api::Context> {
+    ledger_db: LedgerDB,
+    storage: C::Storage,
+}
+
+impl<C: sov_modules_api::Context> AddressIndex<C> {
+    pub(crate) fn new(ledger_db: LedgerDB, storage: C::Storage) -> Self {
+        Self { ledger_db, storage }
+    }
+
+    /// Indexes the blocks not indexed yet, then the blocks of the committed soft batches.
+    pub(crate) async fn run(self: Arc<Self>, mut soft_batches: broadcast::Receiver<u64>) {
+        self.index_new_blocks();
+
+        loop {
+            match soft_batches.recv().await {
+                Ok(_) | Err(RecvError::Lagged(_)) => self.index_new_blocks(),
+                Err(RecvError::Closed) => return,
+            }
+        }
+    }
+
+    fn index_new_blocks(&self) {
+        let evm = Evm::<C>::default();
+        let mut working_set = WorkingSet::<C>::new(self.storage.clone());
+        let Some(latest) = evm.block_number_for_id(&BlockNumberOrTag::Latest, &mut working_set)
+        else {
+            return;
+        };
+        let indexed = match self.ledger_db.get_address_index_head() {
+            Ok(head) => head.map_or(0, |head| head.0),
+            Err(e) => {
+                warn!("Failed to read the head of the address index: {:?}", e);
+                return;
+            }
+        };
+
+        for number in indexed + 1..=latest {
+            let txs = match evm.get_block_by_number(
+                Some(BlockNumberOrTag::Number(number)),
+                Some(true),
+                &mut working_set,
+            ) {
+                Ok(Some(block)) => match block.inner.transactions {
+                    BlockTransactions::Full(txs) => txs,
+                    _ => vec![],
+                },
+                Ok(None) => return,
+                Err(e) => {
+                    warn!("Failed to read block {} to index it: {:?}", number, e);
+                    return;
+                }
+            };
+            if let Err(e) = self
+                .ledger_db
+                .put_address_txs(BatchNumber(number), &index_entries(&txs))
+            {
+                warn!(
+                    "Failed to index the transactions of block {}: {:?}",
+                    number, e
+                );
+                return;
+            }
+        }
+    }
+
+    /// Returns up to `limit` transactions of `address` before `cursor`, newest first.
+    pub(crate) fn transactions(
+        &self,
+        address: Address,
+        cursor: Option<AddressTxCursor>,
+        limit: usize,
+    ) -> Result<AddressTransactions, ErrorObjectOwned> {
+        let before = cursor.map(|cursor| {
+            (
+                cursor.block_number.to::<u64>(),
+                cursor.transaction_index.saturating_to::<u32>(),
+            )
+        });
+        let entries = self
+            .ledger_db
+            .get_address_txs(address.into_array(), before, limit)
+            .map_err(|e| to_jsonrpsee_error_object(e, ETH_RPC_ERROR))?;
+        let next_cursor = match entries.last() {
+            Some(((block_number, index), _)) if entries.len() == limit => Some(AddressTxCursor {
+                block_number: U64::from(*block_number),
+                transaction_index: U64::from(*index),
+            }),
+            _ => None,
+        };
+
+        let evm = Evm::<C>::default();
+        let mut working_set = WorkingSet::<C>::new(self.storage.clone());
+        let mut transactions = Vec::with_capacity(entries.len());
+        for ((block_number, _), hash) in entries {
+            // entries of rolled back blocks may point to other transactions
+            let Some(tx) = evm.get_transaction_by_hash(B256::from(hash), &mut working_set)? else {
+                continue;
+            };
+            let in_block = tx
+                .block_number
+                .is_some_and(|number| number.to::<u64>() == block_number);
+            if in_block && (tx.from == address || tx.to == Some(address)) {
+                transactions.push(tx);
+            }
+        }
+
+        Ok(AddressTransactions {
+            transactions,
+            next_cursor,
+        })
+    }
+}
+
+/// Returns the entries of the transactions of a block in the address index,
+/// one for the sender and one for the recipient of each transaction.
+fn index_entries(txs: &[Transaction]) -> Vec<([u8; 20], u32, [u8; 32])> {
+    txs.iter()
+        .enumerate()
+        .flat_map(|(index, tx)| {
+            let recipient = tx.to.filter(|to| *to != tx.from);
+            std::iter::once(tx.from)
+                .chain(recipient)
+                .map(move |address| (address.into_array(), index as u32, tx.hash.0))
+        })
+        .collect()
+}
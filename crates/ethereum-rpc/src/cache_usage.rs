@@ -0,0 +1,62 @@
+//! Approximate memory used by the RPC caches, counted by the memory budget of the sequencer.
+//!
+//! Sizes are estimated from the inline size of the cached values and the lengths of their
+//! variable parts (transactions, inputs, logs), allocator overhead isn't counted.
+
+use std::mem::{size_of, size_of_val};
+use std::sync::Arc;
+
+use reth_primitives::B256;
+use reth_rpc_types::{Block, BlockTransactions, Rich, Transaction, TransactionReceipt};
+use sequencer_client::RpcCachesUsageSink;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::gas_price::gas_oracle::GasPriceOracle;
+use crate::response_cache::ResponseCache;
+
+/// Approximate size of a cached block, in bytes.
+pub(crate) fn block_size(block: &Rich<Block>) -> u64 {
+    let transactions = if let BlockTransactions::Full(transactions) = &block.transactions {
+        transactions.iter().map(transaction_size).sum()
+    } else if let BlockTransactions::Hashes(hashes) = &block.transactions {
+        (hashes.len() * size_of::<B256>()) as u64
+    } else {
+        0
+    };
+    size_of::<Rich<Block>>() as u64 + transactions
+}
+
+/// Approximate size of a cached transaction, in bytes.
+pub(crate) fn transaction_size(transaction: &Transaction) -> u64 {
+    (size_of::<Transaction>() + transaction.input.len()) as u64
+}
+
+/// Approximate size of a cached receipt, in bytes.
+pub(crate) fn receipt_size(receipt: &TransactionReceipt) -> u64 {
+    let logs: usize = receipt
+        .logs
+        .iter()
+        .map(|log| size_of_val(log) + log.topics.len() * size_of::<B256>() + log.data.len())
+        .sum();
+    (size_of::<TransactionReceipt>() + logs) as u64
+}
+
+/// Measures the RPC caches after every committed soft batch, and reports their size to the
+/// memory budget of the sequencer.
+pub(crate) async fn report_caches_usage<C: sov_modules_api::Context>(
+    response_cache: Arc<ResponseCache<C>>,
+    gas_price_oracle: Arc<GasPriceOracle<C>>,
+    mut soft_batches: broadcast::Receiver<u64>,
+    report: RpcCachesUsageSink,
+) {
+    loop {
+        match soft_batches.recv().await {
+            // the caches are measured as they are, missed soft batches don't matter
+            Ok(_) | Err(RecvError::Lagged(_)) => {}
+            Err(RecvError::Closed) => return,
+        }
+        let bytes = response_cache.size_in_bytes() + gas_price_oracle.size_in_bytes().await;
+        report(bytes);
+    }
+}
@@ -0,0 +1,245 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+use reth_primitives::{keccak256, Address, B256};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// CBOR encoding of the `ipfs` key followed by the header of its 34 bytes value,
+/// as appended by solc to the runtime bytecode.
+const IPFS_METADATA_PREFIX: [u8; 7] = [0x64, b'i', b'p', b'f', b's', 0x58, 0x22];
+
+/// Multihash header of a sha2-256 digest.
+const SHA256_MULTIHASH_PREFIX: [u8; 2] = [0x12, 0x20];
+
+/// A contract whose compiler metadata and sources match its on-chain code.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifiedContract {
+    /// Address of the contract.
+    pub address: Address,
+    /// Name of the contract, from the compilation target of the metadata.
+    pub name: String,
+    /// Version of the compiler, from the metadata.
+    pub compiler_version: String,
+    /// Compiler metadata file (`metadata.json`) as uploaded.
+    pub metadata: String,
+    /// Source files keyed by their path in the metadata.
+    pub sources: BTreeMap<String, String>,
+}
+
+/// Subset of the solc metadata file needed to verify a contract.
+#[derive(Deserialize)]
+struct Metadata {
+    compiler: MetadataCompiler,
+    settings: MetadataSettings,
+    sources: BTreeMap<String, MetadataSource>,
+}
+
+#[derive(Deserialize)]
+struct MetadataCompiler {
+    version: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MetadataSettings {
+    compilation_target: BTreeMap<String, String>,
+}
+
+#[derive(Deserialize)]
+struct MetadataSource {
+    keccak256: B256,
+    content: Option<String>,
+}
+
+/// Verified contract metadata uploaded to the node, persisted as one file per contract.
+///
+/// A contract is verified when the IPFS hash of its metadata embedded by solc at the end of
+/// the on-chain code matches the uploaded metadata, and every source matches the hash listed
+/// in the metadata. As the metadata commits to the sources and the compiler settings,
+/// this doesn't need to recompile the contract.
+#[derive(Debug)]
+pub struct ContractVerificationStore {
+    dir: PathBuf,
+    contracts: RwLock<HashMap<Address, VerifiedContract>>,
+}
+
+impl ContractVerificationStore {
+    /// Opens the store in the given directory, loading the contracts verified before.
+    pub fn open(dir: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        std::fs::create_dir_all(&dir)?;
+
+        let mut contracts = HashMap::new();
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            match serde_json::from_slice::<VerifiedContract>(&std::fs::read(&path)?) {
+                Ok(contract) => {
+                    contracts.insert(contract.address, contract);
+                }
+                Err(e) => tracing::warn!(
+                    "Skipping invalid verified contract file {}: {}",
+                    path.display(),
+                    e
+                ),
+            }
+        }
+
+        Ok(Self {
+            dir,
+            contracts: RwLock::new(contracts),
+        })
+    }
+
+    /// Verifies the metadata and sources of a contract against its on-chain code and stores them.
+    /// Sources embedded in the metadata don't need to be uploaded.
+    pub fn verify(
+        &self,
+        address: Address,
+        code: &[u8],
+        metadata: String,
+        mut sources: BTreeMap<String, String>,
+    ) -> anyhow::Result<VerifiedContract> {
+        anyhow::ensure!(!code.is_empty(), "No contract deployed at {}", address);
+        let metadata_hash = metadata_hash(code)
+            .ok_or_else(|| anyhow::anyhow!("Contract code has no IPFS metadata hash"))?;
+        anyhow::ensure!(
+            Sha256::digest(metadata.as_bytes()).as_slice() == metadata_hash.as_slice(),
+            "Metadata doesn't match the metadata hash of the contract code"
+        );
+
+        let parsed: Metadata = serde_json::from_str(&metadata)?;
+        for (path, source) in parsed.sources {
+            let content = match sources.remove(&path) {
+                Some(content) => content,
+                None => source
+                    .content
+                    .ok_or_else(|| anyhow::anyhow!("Missing source {}", path))?,
+            };
+            anyhow::ensure!(
+                keccak256(content.as_bytes()) == source.keccak256,
+                "Source {} doesn't match its hash in the metadata",
+                path
+            );
+            sources.insert(path, content);
+        }
+        let name = parsed
+            .settings
+            .compilation_target
+            .into_values()
+            .next()
+            .ok_or_else(|| anyhow::anyhow!("Metadata has no compilation target"))?;
+
+        let contract = VerifiedContract {
+            address,
+            name,
+            compiler_version: parsed.compiler.version,
+            metadata,
+            sources,
+        };
+        std::fs::write(
+            self.dir.join(format!("{:#x}.json", address)),
+            serde_json::to_vec(&contract)?,
+        )?;
+        self.contracts
+            .write()
+            .unwrap()
+            .insert(address, contract.clone());
+        Ok(contract)
+    }
+
+    /// Returns the verified metadata of a contract.
+    pub fn get(&self, address: &Address) -> Option<VerifiedContract> {
+        self.contracts.read().unwrap().get(address).cloned()
+    }
+}
+
+/// Returns the sha2-256 digest of the metadata file embedded by solc in the CBOR encoded
+/// trailer of the runtime bytecode, whose length is stored in the last two bytes.
+fn metadata_hash(code: &[u8]) -> Option<[u8; 32]> {
+    let (code, len) = code.split_at(code.len().checked_sub(2)?);
+    let len = u16::from_be_bytes([len[0], len[1]]) as usize;
+    let cbor = &code[code.len().checked_sub(len)?..];
+
+    let start = cbor
+        .windows(IPFS_METADATA_PREFIX.len())
+        .position(|window| window == IPFS_METADATA_PREFIX)?
+        + IPFS_METADATA_PREFIX.len();
+    let multihash = cbor.get(start..start + 34)?;
+    if multihash[..2] != SHA256_MULTIHASH_PREFIX {
+        return None;
+    }
+    multihash[2..].try_into().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn code_with_metadata(metadata: &str) -> Vec<u8> {
+        let mut cbor = vec![0xa2];
+        cbor.extend_from_slice(&IPFS_METADATA_PREFIX);
+        cbor.extend_from_slice(&SHA256_MULTIHASH_PREFIX);
+        cbor.extend_from_slice(&Sha256::digest(metadata.as_bytes()));
+        cbor.extend_from_slice(&[0x64, b's', b'o', b'l', b'c', 0x43, 0x00, 0x08, 0x19]);
+
+        let mut code = vec![0x60, 0x80, 0x60, 0x40, 0x52, 0xfe];
+        code.extend_from_slice(&cbor);
+        code.extend_from_slice(&(cbor.len() as u16).to_be_bytes());
+        code
+    }
+
+    #[test]
+    fn verifies_metadata_against_code() {
+        let source = "contract Counter {}";
+        let metadata = json!({
+            "compiler": { "version": "0.8.25+commit.b61c2a91" },
+            "settings": { "compilationTarget": { "src/Counter.sol": "Counter" } },
+            "sources": {
+                "src/Counter.sol": { "keccak256": keccak256(source.as_bytes()) }
+            },
+        })
+        .to_string();
+        let code = code_with_metadata(&metadata);
+        let address = Address::with_last_byte(1);
+
+        let dir = tempfile::tempdir().unwrap();
+        let store = ContractVerificationStore::open(dir.path()).unwrap();
+        let sources = BTreeMap::from([("src/Counter.sol".to_string(), source.to_string())]);
+
+        assert!(store
+            .verify(
+                address,
+                &code,
+                metadata.replace("0.8.25", "0.8.24"),
+                sources.clone()
+            )
+            .is_err());
+        assert!(store
+            .verify(
+                address,
+                &code,
+                metadata.clone(),
+                BTreeMap::from([(
+                    "src/Counter.sol".to_string(),
+                    "contract Other {}".to_string()
+                )])
+            )
+            .is_err());
+        assert_eq!(store.get(&address), None);
+
+        let verified = store.verify(address, &code, metadata, sources).unwrap();
+        assert_eq!(verified.name, "Counter");
+        assert_eq!(verified.compiler_version, "0.8.25+commit.b61c2a91");
+
+        let reopened = ContractVerificationStore::open(dir.path()).unwrap();
+        assert_eq!(reopened.get(&address), Some(verified));
+    }
+}
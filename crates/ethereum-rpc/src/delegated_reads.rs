@@ -0,0 +1,75 @@
+use jsonrpsee::types::ErrorObjectOwned;
+use sequencer_client::SequencerClient;
+use serde::Serialize;
+use serde_json::Value;
+use sov_modules_api::utils::to_jsonrpsee_error_object;
+
+use crate::ETH_RPC_ERROR;
+
+/// Response of a read served by the node, or by the sequencer when the node has not
+/// synced the data yet.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(untagged)]
+pub(crate) enum ReadResponse<T> {
+    Local(Option<T>),
+    Sequencer(Value),
+}
+
+/// Returns the local result of a read if there is one. Otherwise asks the sequencer if the
+/// node delegates reads of the data it has not synced yet, marking its response.
+pub(crate) async fn read_or_delegate<T>(
+    local: Option<T>,
+    sequencer_client: Option<&SequencerClient>,
+    method: &str,
+    params: Vec<Value>,
+) -> Result<ReadResponse<T>, ErrorObjectOwned> {
+    let sequencer_client = match (&local, sequencer_client) {
+        (None, Some(sequencer_client)) if sequencer_client.delegates_reads() => sequencer_client,
+        _ => return Ok(ReadResponse::Local(local)),
+    };
+
+    let response = sequencer_client
+        .delegated_read(method, params)
+        .await
+        .map_err(|e| to_jsonrpsee_error_object(e, ETH_RPC_ERROR))?;
+    Ok(ReadResponse::Sequencer(mark_sequencer_source(response)))
+}
+
+/// Adds `"source": "sequencer"` to the objects of a delegated response
+fn mark_sequencer_source(response: Value) -> Value {
+    match response {
+        Value::Object(mut object) => {
+            object.insert("source".to_string(), Value::from("sequencer"));
+            Value::Object(object)
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(mark_sequencer_source).collect()),
+        response => response,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn marks_delegated_responses() {
+        assert_eq!(
+            mark_sequencer_source(json!({ "number": "0x10" })),
+            json!({ "number": "0x10", "source": "sequencer" })
+        );
+        assert_eq!(
+            mark_sequencer_source(json!([{ "status": "0x1" }, { "status": "0x0" }])),
+            json!([
+                { "status": "0x1", "source": "sequencer" },
+                { "status": "0x0", "source": "sequencer" }
+            ])
+        );
+        // blocks the sequencer doesn't have either stay null
+        assert_eq!(mark_sequencer_source(Value::Null), Value::Null);
+
+        let local = serde_json::to_value(ReadResponse::Local(Some(1u64))).unwrap();
+        assert_eq!(local, json!(1));
+    }
+}
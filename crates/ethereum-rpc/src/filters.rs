@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use citrea_evm::{Evm, Filter, FilterBlockOption, LogResponse};
+use jsonrpsee::types::error::INVALID_PARAMS_CODE;
+use jsonrpsee::types::ErrorObjectOwned;
+use reth_primitives::{BlockNumberOrTag, B256, U128};
+use serde::Serialize;
+use sov_modules_api::WorkingSet;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::TryRecvError;
+
+/// Id of an installed filter.
+pub type FilterId = U128;
+
+/// Filters not polled for this long are uninstalled, as geth does.
+const FILTER_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Max number of pending transaction hashes kept for a filter between two polls,
+/// older hashes are dropped.
+const PENDING_TRANSACTIONS_CAPACITY: usize = 4096;
+
+/// Changes of a filter since its previous poll.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum FilterChanges {
+    /// Hashes of new blocks or pending transactions.
+    Hashes(Vec<B256>),
+    /// Logs of new blocks matching a log filter.
+    Logs(Vec<LogResponse>),
+}
+
+enum FilterKind {
+    Block,
+    Log(Box<Filter>),
+    PendingTransaction(broadcast::Receiver<B256>),
+}
+
+struct ActiveFilter {
+    kind: FilterKind,
+    /// Last block whose changes were returned.
+    last_block: u64,
+    last_poll: Instant,
+}
+
+/// Filters installed by `eth_newFilter`, `eth_newBlockFilter` and
+/// `eth_newPendingTransactionFilter`, polled by `eth_getFilterChanges`.
+///
+/// Block and log filters read the blocks committed to the state since their previous poll,
+/// so they follow the blocks produced by the sequencer and synced by full nodes alike.
+/// Filters are identified by random ids and uninstalled once not polled for [FILTER_TIMEOUT].
+pub struct FilterManager<C: sov_modules_api::Context> {
+    filters: Mutex<HashMap<FilterId, ActiveFilter>>,
+    provider: Evm<C>,
+    /// Hashes of the transactions entering the mempool, received by the pending transaction
+    /// filters. The sequencer sends the transactions of its mempool, full nodes only know
+    /// about the transactions relayed through them.
+    pending_transactions: broadcast::Sender<B256>,
+}
+
+impl<C: sov_modules_api::Context> FilterManager<C> {
+    pub fn new(provider: Evm<C>) -> Self {
+        Self {
+            filters: Mutex::new(HashMap::new()),
+            provider,
+            pending_transactions: broadcast::channel(PENDING_TRANSACTIONS_CAPACITY).0,
+        }
+    }
+
+    /// Returns the sender of the hashes of the transactions entering the mempool of the node.
+    pub fn pending_transactions_sender(&self) -> broadcast::Sender<B256> {
+        self.pending_transactions.clone()
+    }
+
+    /// Installs a filter of the logs of the blocks committed from now on.
+    pub fn new_log_filter(&self, filter: Filter, working_set: &mut WorkingSet<C>) -> FilterId {
+        self.install(FilterKind::Log(Box::new(filter)), working_set)
+    }
+
+    /// Installs a filter of the blocks committed from now on.
+    pub fn new_block_filter(&self, working_set: &mut WorkingSet<C>) -> FilterId {
+        self.install(FilterKind::Block, working_set)
+    }
+
+    /// Installs a filter of the transactions entering the mempool from now on.
+    pub fn new_pending_transaction_filter(&self, working_set: &mut WorkingSet<C>) -> FilterId {
+        let receiver = self.pending_transactions.subscribe();
+        self.install(FilterKind::PendingTransaction(receiver), working_set)
+    }
+
+    /// Uninstalls a filter, returns false if it wasn't installed.
+    pub fn uninstall(&self, id: FilterId) -> bool {
+        self.filters.lock().unwrap().remove(&id).is_some()
+    }
+
+    /// Returns the changes of a filter since its previous poll.
+    pub fn changes(
+        &self,
+        id: FilterId,
+        working_set: &mut WorkingSet<C>,
+    ) -> Result<FilterChanges, ErrorObjectOwned> {
+        let head = self.head(working_set);
+        let mut filters = self.filters.lock().unwrap();
+        evict_expired(&mut filters);
+        let active = filters.get_mut(&id).ok_or_else(filter_not_found)?;
+        active.last_poll = Instant::now();
+
+        let from_block = active.last_block + 1;
+        let changes = match &mut active.kind {
+            FilterKind::Block => {
+                let mut hashes = vec![];
+                for number in from_block..=head {
+                    let block = self.provider.get_block_by_number(
+                        Some(BlockNumberOrTag::Number(number)),
+                        Some(false),
+                        working_set,
+                    )?;
+                    hashes.extend(block.and_then(|block| block.header.hash));
+                }
+                FilterChanges::Hashes(hashes)
+            }
+            FilterKind::Log(filter) => {
+                let to_block = match filter.block_option.get_to_block() {
+                    Some(BlockNumberOrTag::Number(to_block)) => head.min(*to_block),
+                    _ => head,
+                };
+                let logs = match from_block <= to_block {
+                    true => self.provider.eth_get_logs(
+                        Filter {
+                            block_option: FilterBlockOption::Range {
+                                from_block: Some(BlockNumberOrTag::Number(from_block)),
+                                to_block: Some(BlockNumberOrTag::Number(to_block)),
+                            },
+                            address: filter.address.clone(),
+                            topics: filter.topics.clone(),
+                        },
+                        working_set,
+                    )?,
+                    false => vec![],
+                };
+                FilterChanges::Logs(logs)
+            }
+            FilterKind::PendingTransaction(receiver) => {
+                let mut hashes = vec![];
+                loop {
+                    match receiver.try_recv() {
+                        Ok(hash) => hashes.push(hash),
+                        Err(TryRecvError::Lagged(_)) => continue,
+                        Err(TryRecvError::Empty | TryRecvError::Closed) => break,
+                    }
+                }
+                FilterChanges::Hashes(hashes)
+            }
+        };
+        active.last_block = head;
+        Ok(changes)
+    }
+
+    /// Returns all the logs matching a log filter, as `eth_getLogs` would.
+    pub fn logs(
+        &self,
+        id: FilterId,
+        working_set: &mut WorkingSet<C>,
+    ) -> Result<Vec<LogResponse>, ErrorObjectOwned> {
+        let filter = {
+            let mut filters = self.filters.lock().unwrap();
+            evict_expired(&mut filters);
+            let active = filters.get_mut(&id).ok_or_else(filter_not_found)?;
+            active.last_poll = Instant::now();
+            match &active.kind {
+                FilterKind::Log(filter) => filter.clone(),
+                _ => return Err(filter_not_found()),
+            }
+        };
+        self.provider.eth_get_logs(*filter, working_set)
+    }
+
+    fn install(&self, kind: FilterKind, working_set: &mut WorkingSet<C>) -> FilterId {
+        let filter = ActiveFilter {
+            kind,
+            last_block: self.head(working_set),
+            last_poll: Instant::now(),
+        };
+        let id = FilterId::from(rand::random::<u128>());
+        let mut filters = self.filters.lock().unwrap();
+        evict_expired(&mut filters);
+        filters.insert(id, filter);
+        id
+    }
+
+    fn head(&self, working_set: &mut WorkingSet<C>) -> u64 {
+        self.provider
+            .block_number(working_set)
+            .map(|number| number.saturating_to())
+            .unwrap_or_default()
+    }
+}
+
+fn evict_expired(filters: &mut HashMap<FilterId, ActiveFilter>) {
+    filters.retain(|_, filter| filter.last_poll.elapsed() < FILTER_TIMEOUT);
+}
+
+fn filter_not_found() -> ErrorObjectOwned {
+    ErrorObjectOwned::owned(INVALID_PARAMS_CODE, "filter not found", None::<()>)
+}
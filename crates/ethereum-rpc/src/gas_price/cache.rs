@@ -1,3 +1,4 @@
+use std::mem::size_of;
 use std::sync::Mutex;
 
 use citrea_evm::EthResult;
@@ -7,6 +8,7 @@ use schnellru::{ByLength, LruMap};
 use sov_modules_api::WorkingSet;
 
 use super::gas_oracle::convert_u256_to_u64;
+use crate::cache_usage::block_size;
 
 /// Cache for gas oracle
 pub struct BlockCache<C: sov_modules_api::Context> {
@@ -25,6 +27,20 @@ impl<C: sov_modules_api::Context> BlockCache<C> {
         }
     }
 
+    /// Approximate memory used by the cached blocks, in bytes.
+    pub fn size_in_bytes(&self) -> u64 {
+        // the maps are locked one after the other, the getters lock them in both orders
+        let numbers = self.number_to_hash.lock().unwrap().len() * size_of::<(u64, B256)>();
+        let blocks: u64 = self
+            .cache
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, block)| block_size(block))
+            .sum();
+        numbers as u64 + blocks
+    }
+
     /// Gets block from cache or from provider
     pub fn get_block(
         &self,
@@ -1,5 +1,6 @@
 //! Consist of types adjacent to the fee history cache and its configs
 use std::fmt::Debug;
+use std::mem::size_of;
 use std::sync::{Arc, Mutex};
 
 use citrea_evm::EthApiError;
@@ -68,6 +69,19 @@ impl<C: sov_modules_api::Context> FeeHistoryCache<C> {
         &self.config
     }
 
+    /// Approximate memory used by the cached entries, in bytes.
+    pub fn size_in_bytes(&self) -> u64 {
+        self.entries
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, entry)| {
+                (size_of::<(u64, FeeHistoryEntry)>() + entry.rewards.len() * size_of::<U256>())
+                    as u64
+            })
+            .sum()
+    }
+
     /// Returns the configured resolution for percentile approximation.
     #[inline]
     pub fn resolution(&self) -> u64 {
@@ -1,5 +1,5 @@
 //! An implementation of the eth gas price oracle, used for providing gas price estimates based on
-//! previous blocks.
+//! previous blocks and the transactions waiting in the mempool.
 
 // Adopted from: https://github.com/paradigmxyz/reth/blob/main/crates/rpc/rpc/src/eth/gas_oracle.rs
 
@@ -7,10 +7,9 @@ use std::array::TryFromSliceError;
 use std::sync::Arc;
 
 use citrea_evm::{EthApiError, EthResult, Evm, RpcInvalidTransactionError};
-use reth_primitives::basefee::calculate_next_block_base_fee;
 use reth_primitives::constants::GWEI_TO_WEI;
 use reth_primitives::{BlockNumberOrTag, B256, U256, U64};
-use reth_rpc_types::{BlockTransactions, FeeHistory};
+use reth_rpc_types::{BlockTransactions, FeeHistory, Header};
 use serde::{Deserialize, Serialize};
 use sov_modules_api::WorkingSet;
 use tokio::sync::Mutex;
@@ -55,6 +54,10 @@ pub struct GasPriceOracleConfig {
 
     /// The minimum gas price, under which the sample will be ignored
     pub ignore_price: Option<U256>,
+
+    /// Whether the tips of the executable transactions of the mempool are sampled
+    /// along with the tips of recent blocks
+    pub sample_mempool: bool,
 }
 
 impl Default for GasPriceOracleConfig {
@@ -67,6 +70,7 @@ impl Default for GasPriceOracleConfig {
             default: None,
             max_price: Some(DEFAULT_MAX_PRICE),
             ignore_price: Some(DEFAULT_IGNORE_PRICE),
+            sample_mempool: true,
         }
     }
 }
@@ -87,11 +91,12 @@ impl GasPriceOracleConfig {
             default: None,
             max_price: max_price.map(U256::from).or(Some(DEFAULT_MAX_PRICE)),
             ignore_price: ignore_price.map(U256::from).or(Some(DEFAULT_IGNORE_PRICE)),
+            sample_mempool: true,
         }
     }
 }
 
-/// Calculates a gas price depending on recent blocks.
+/// Calculates a gas price depending on recent blocks and the mempool.
 pub struct GasPriceOracle<C: sov_modules_api::Context> {
     /// The type used to get block and tx info
     provider: Evm<C>,
@@ -128,8 +133,11 @@ impl<C: sov_modules_api::Context> GasPriceOracle<C> {
 
         Self {
             provider: provider.clone(),
+            last_price: Mutex::new(GasPriceOracleResult {
+                price: oracle_config.default.unwrap_or(U256::from(GWEI_TO_WEI)),
+                ..Default::default()
+            }),
             oracle_config,
-            last_price: Default::default(),
             fee_history_cache: Mutex::new(fee_history_cache),
             cache: arc_cache,
         }
@@ -140,6 +148,11 @@ impl<C: sov_modules_api::Context> GasPriceOracle<C> {
         &self.oracle_config
     }
 
+    /// Approximate memory used by the fee history and block caches, in bytes.
+    pub async fn size_in_bytes(&self) -> u64 {
+        self.fee_history_cache.lock().await.size_in_bytes() + self.cache.size_in_bytes()
+    }
+
     /// Reports the fee history
     pub async fn fee_history(
         &self,
@@ -222,11 +235,11 @@ impl<C: sov_modules_api::Context> GasPriceOracle<C> {
             }
         }
         let last_entry = fee_entries.last().expect("is not empty");
-        base_fee_per_gas.push(U256::from(calculate_next_block_base_fee(
+        base_fee_per_gas.push(U256::from(self.provider.next_base_fee(
             last_entry.gas_used,
             last_entry.gas_limit,
             last_entry.base_fee_per_gas,
-            self.provider.get_chain_config(working_set).base_fee_params,
+            working_set,
         )));
 
         Ok(FeeHistory {
@@ -239,22 +252,63 @@ impl<C: sov_modules_api::Context> GasPriceOracle<C> {
         })
     }
 
-    /// Suggests a gas price estimate based on recent blocks, using the configured percentile.
-    pub async fn suggest_tip_cap(&self, working_set: &mut WorkingSet<C>) -> EthResult<U256> {
-        let header = &self
-            .provider
-            .get_block_by_number(None, None, working_set)
-            .unwrap()
-            .unwrap()
-            .header;
+    /// Suggests a gas price estimate based on recent blocks and the given tips of the
+    /// transactions waiting in the mempool, using the configured percentile.
+    /// The configured default price is suggested until there are samples.
+    pub async fn suggest_tip_cap(
+        &self,
+        mempool_tips: &[U256],
+        working_set: &mut WorkingSet<C>,
+    ) -> EthResult<U256> {
+        let header = &head_header(&self.provider, working_set)?;
 
         let mut last_price = self.last_price.lock().await;
 
-        // if we have stored a last price, then we check whether or not it was for the same head
-        if last_price.block_hash == header.hash.unwrap() {
-            return Ok(last_price.price);
+        // the blocks are only sampled again when the head changes
+        if last_price.block_hash != header.hash.unwrap() {
+            let block_samples = self
+                .sample_blocks(header, last_price.price, working_set)
+                .await?;
+            last_price.block_hash = header.hash.unwrap();
+            last_price.block_samples = block_samples;
         }
 
+        let mut results = last_price.block_samples.clone();
+        results.extend(mempool_tips.iter().copied().filter(|tip| {
+            self.oracle_config
+                .ignore_price
+                .map_or(true, |ignore_under| *tip >= ignore_under)
+        }));
+
+        // sort results then take the configured percentile result
+        let mut price = last_price.price;
+        if !results.is_empty() {
+            results.sort_unstable();
+            price = *results
+                .get((results.len() - 1) * self.oracle_config.percentile as usize / 100)
+                .expect("gas price index is a percent of nonzero array length, so a value always exists; qed");
+        }
+
+        // constrain to the max price
+        if let Some(max_price) = self.oracle_config.max_price {
+            if price > max_price {
+                price = max_price;
+            }
+        }
+
+        last_price.price = price;
+
+        Ok(price)
+    }
+
+    /// Collects the tips of the recent blocks up to `header`, sampling `last_price` for
+    /// the empty ones.
+    async fn sample_blocks(
+        &self,
+        header: &Header,
+        last_price: U256,
+        working_set: &mut WorkingSet<C>,
+    ) -> EthResult<Vec<U256>> {
         // if all responses are empty, then we can return a maximum of 2*check_block blocks' worth
         // of prices
         //
@@ -280,7 +334,7 @@ impl<C: sov_modules_api::Context> GasPriceOracle<C> {
                 .ok_or(EthApiError::UnknownBlockNumber)?;
 
             if block_values.is_empty() {
-                results.push(U256::from(last_price.price));
+                results.push(last_price);
             } else {
                 results.extend(block_values);
                 populated_blocks += 1;
@@ -294,28 +348,7 @@ impl<C: sov_modules_api::Context> GasPriceOracle<C> {
             current_hash = parent_hash;
         }
 
-        // sort results then take the configured percentile result
-        let mut price = last_price.price;
-        if !results.is_empty() {
-            results.sort_unstable();
-            price = *results
-                .get((results.len() - 1) * self.oracle_config.percentile as usize / 100)
-                .expect("gas price index is a percent of nonzero array length, so a value always exists; qed");
-        }
-
-        // constrain to the max price
-        if let Some(max_price) = self.oracle_config.max_price {
-            if price > max_price {
-                price = max_price;
-            }
-        }
-
-        *last_price = GasPriceOracleResult {
-            block_hash: header.hash.unwrap(),
-            price,
-        };
-
-        Ok(price)
+        Ok(results)
     }
 
     /// Get the `limit` lowest effective tip values for the given block. If the oracle has a
@@ -406,6 +439,8 @@ pub struct GasPriceOracleResult {
     pub block_hash: B256,
     /// The price that the oracle calculated
     pub price: U256,
+    /// The tips sampled from the blocks up to `block_hash`
+    pub block_samples: Vec<U256>,
 }
 
 impl Default for GasPriceOracleResult {
@@ -413,10 +448,23 @@ impl Default for GasPriceOracleResult {
         Self {
             block_hash: B256::ZERO,
             price: U256::from(GWEI_TO_WEI),
+            block_samples: Vec::new(),
         }
     }
 }
 
+/// Returns the header of the head block.
+pub(crate) fn head_header<C: sov_modules_api::Context>(
+    provider: &Evm<C>,
+    working_set: &mut WorkingSet<C>,
+) -> EthResult<Header> {
+    provider
+        .get_block_by_number(None, None, working_set)
+        .map_err(|_| EthApiError::InternalEthError)?
+        .map(|block| block.inner.header)
+        .ok_or(EthApiError::UnknownBlockNumber)
+}
+
 // Adopted from: https://github.com/paradigmxyz/reth/blob/main/crates/primitives/src/transaction/mod.rs#L297
 pub(crate) fn effective_gas_tip(
     transaction: &reth_rpc_types::Transaction,
@@ -437,7 +485,7 @@ pub(crate) fn effective_gas_tip(
         let max_fee_per_gas = U256::from(match transaction.transaction_type {
             Some(tx_type) => {
                 if tx_type == U64::from(2) {
-                    transaction.max_priority_fee_per_gas.unwrap()
+                    transaction.max_fee_per_gas.unwrap()
                 } else {
                     transaction.gas_price.unwrap()
                 }
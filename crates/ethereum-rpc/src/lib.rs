@@ -1,43 +1,95 @@
+mod address_index;
+mod cache_usage;
+mod contract_verification;
+mod delegated_reads;
+mod filters;
 mod gas_price;
+mod relayed_txs;
+mod response_cache;
+mod streamed_receipts;
+mod subscriptions;
+mod syncing;
+mod tx_timeline;
 
 use std::collections::BTreeMap;
 use std::process::Command;
-use std::sync::Mutex;
+use std::sync::{Arc, Mutex};
 
+use address_index::AddressIndex;
+pub use address_index::{AddressTransactions, AddressTxCursor, MAX_ADDRESS_TXS_PER_REQUEST};
 #[cfg(feature = "local")]
 pub use citrea_evm::DevSigner;
-use citrea_evm::{EthApiError, Evm};
+pub use citrea_evm::{
+    AbiRegistry, BlockGasUsage, BlockOrderingInfo, ContractGasUsage, GasUsageWindow, L1BlockInfo,
+    StateExport, StateTestFixture, StorageRentEntry, StorageRentReport, TraceResult,
+    TxOrderingInfo,
+};
+use citrea_evm::{
+    AccountProof, EthApiError, EthResult, Evm, QueryContext, SimulatePayload, TraceFilter,
+};
+pub use contract_verification::{ContractVerificationStore, VerifiedContract};
+use delegated_reads::{read_or_delegate, ReadResponse};
 use ethers::types::Bytes;
+use filters::FilterManager;
+pub use filters::{FilterChanges, FilterId};
 pub use gas_price::fee_history::FeeHistoryCacheConfig;
 use gas_price::gas_oracle::GasPriceOracle;
 pub use gas_price::gas_oracle::GasPriceOracleConfig;
 use jsonrpsee::types::ErrorObjectOwned;
 use jsonrpsee::RpcModule;
-use reth_primitives::{keccak256, BlockNumberOrTag, B256, U256};
+use relayed_txs::RelayedTxJournal;
+use response_cache::ResponseCache;
+pub use response_cache::ResponseCacheConfig;
+use reth_primitives::{keccak256, Address, BlockNumberOrTag, B256, U256, U64};
 use reth_rpc_types::trace::geth::{
     CallConfig, CallFrame, FourByteFrame, GethDebugBuiltInTracerType, GethDebugTracerConfig,
-    GethDebugTracerType, GethDebugTracingOptions, GethTrace, NoopFrame,
+    GethDebugTracerType, GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace,
+    NoopFrame,
 };
-use reth_rpc_types::FeeHistory;
+use reth_rpc_types::{BlockId, BlockTransactions, FeeHistory};
 use rustc_version_runtime::version;
 use schnellru::{ByLength, LruMap};
-use sequencer_client::SequencerClient;
+use sequencer_client::{BlockReceipts, LocalMempool, SequencerClient};
 use serde_json::json;
+use sov_db::ledger_db::LedgerDB;
 use sov_modules_api::utils::to_jsonrpsee_error_object;
 use sov_modules_api::WorkingSet;
-use sov_rollup_interface::services::da::DaService;
-use tracing::info;
-
-use crate::gas_price::gas_oracle::convert_u256_to_u64;
+use sov_rollup_interface::da::BlockHeaderTrait;
+use sov_rollup_interface::services::da::{DaService, SlotData};
+use sov_state::storage::NativeStorage;
+use streamed_receipts::{StreamedReceipts, MAX_STREAMED_BLOCKS_PER_REQUEST};
+use subscriptions::SubscriptionKind;
+use syncing::SyncingResponse;
+use tokio::sync::broadcast;
+use tracing::{info, warn};
+pub use tx_timeline::TransactionTimeline;
+use tx_timeline::{transaction_timeline, SubmissionLatencyTracker};
+
+use crate::gas_price::gas_oracle::{convert_u256_to_u64, effective_gas_tip, head_header};
 
 const ETH_RPC_ERROR: &str = "ETH_RPC_ERROR";
 
 const MAX_TRACE_BLOCK: u32 = 1000;
 
+/// Methods of the EVM module served by the ethereum RPC module on full nodes streaming receipts
+/// from the sequencer instead, so that the logs of the soft batches not executed yet are served.
+/// They must be removed from the methods of the EVM module before merging the ethereum RPC
+/// module into them.
+pub const RECEIPT_STREAMING_METHODS: [&str; 1] = ["eth_getLogs"];
+
 #[derive(Clone)]
 pub struct EthRpcConfig {
     pub gas_price_oracle_config: GasPriceOracleConfig,
     pub fee_history_cache_config: FeeHistoryCacheConfig,
+    pub response_cache_config: ResponseCacheConfig,
+    /// Number of blocks before the head whose state is served, every block if not set.
+    pub state_history_blocks: Option<u64>,
+    /// Registry decoding the custom errors of reverts, none are decoded if not set.
+    pub abi_registry: Option<Arc<AbiRegistry>>,
+    /// Store serving the contract verification methods, which are disabled if not set.
+    pub contract_verification_store: Option<Arc<ContractVerificationStore>>,
+    /// Indexes the transactions by address, serving `citrea_getTransactionsByAddress`.
+    pub index_transactions_by_address: bool,
     #[cfg(feature = "local")]
     pub eth_signer: DevSigner,
 }
@@ -47,41 +99,136 @@ pub fn get_ethereum_rpc<C: sov_modules_api::Context, Da: DaService>(
     eth_rpc_config: EthRpcConfig,
     storage: C::Storage,
     sequencer_client: Option<SequencerClient>,
-) -> RpcModule<Ethereum<C, Da>> {
+    soft_batches: broadcast::Receiver<u64>,
+    ledger_db: LedgerDB,
+    local_mempool: Option<LocalMempool>,
+) -> RpcModule<Ethereum<C, Da>>
+where
+    C::Storage: NativeStorage,
+{
     // Unpack config
     let EthRpcConfig {
         #[cfg(feature = "local")]
         eth_signer,
         gas_price_oracle_config,
         fee_history_cache_config,
+        response_cache_config,
+        state_history_blocks,
+        abi_registry,
+        contract_verification_store,
+        index_transactions_by_address,
     } = eth_rpc_config;
 
     // If the node does not have a sequencer client, then it is the sequencer.
     let is_sequencer = sequencer_client.is_none();
 
+    // Full nodes journal the transactions they relay, and re-broadcast the ones
+    // not included yet after a restart
+    let relayed_txs = sequencer_client.clone().map(|sequencer_client| {
+        let journal = Arc::new(RelayedTxJournal::new(ledger_db.clone(), storage.clone()));
+        tokio::spawn(
+            journal
+                .clone()
+                .run(sequencer_client, soft_batches.resubscribe()),
+        );
+        journal
+    });
+
+    // Full nodes trusting the sequencer serve its receipts until they execute the soft batches
+    let streamed_receipts = sequencer_client
+        .clone()
+        .filter(SequencerClient::streams_receipts)
+        .map(|sequencer_client| {
+            let streamed_receipts = Arc::new(StreamedReceipts::new(storage.clone()));
+            tokio::spawn(
+                streamed_receipts
+                    .clone()
+                    .run(sequencer_client, soft_batches.resubscribe()),
+            );
+            streamed_receipts
+        });
+    let stream_receipts = streamed_receipts.is_some();
+
+    // Nodes indexing transactions by address index the blocks of the committed soft batches
+    let address_index = index_transactions_by_address.then(|| {
+        let address_index = Arc::new(AddressIndex::new(ledger_db.clone(), storage.clone()));
+        tokio::spawn(address_index.clone().run(soft_batches.resubscribe()));
+        address_index
+    });
+
+    // The latency of the transactions submitted to the node is observed as their blocks are committed
+    tokio::spawn(
+        SubmissionLatencyTracker::<C>::new(ledger_db.clone(), storage.clone())
+            .run(soft_batches.resubscribe()),
+    );
+
+    // Full nodes know which soft confirmations are committed to and proven on DA
+    let mut query_context = QueryContext::default();
+    if !is_sequencer {
+        let ledger_db = ledger_db.clone();
+        query_context = query_context.with_finality_resolver(Arc::new(move |tag| match tag {
+            BlockNumberOrTag::Safe => ledger_db.get_last_finalized_l2_height(),
+            BlockNumberOrTag::Finalized => ledger_db.get_last_proven_l2_height(),
+            _ => None,
+        }));
+    }
+    if let Some(blocks) = state_history_blocks {
+        query_context = query_context.with_state_history_blocks(blocks);
+    }
+    if let Some(registry) = abi_registry {
+        query_context = query_context.with_abi_registry(registry);
+    }
+
     // If the running node is a full node rpc context should also have sequencer client so that it can send txs to sequencer
     let mut rpc = RpcModule::new(Ethereum::new(
         da_service,
         gas_price_oracle_config,
         fee_history_cache_config,
+        response_cache_config,
         #[cfg(feature = "local")]
         eth_signer,
         storage,
+        ledger_db,
         sequencer_client,
+        soft_batches,
+        relayed_txs,
+        streamed_receipts,
+        address_index,
+        query_context,
+        local_mempool,
+        contract_verification_store,
     ));
 
-    register_rpc_methods(&mut rpc, is_sequencer).expect("Failed to register ethereum RPC methods");
+    register_rpc_methods(&mut rpc, is_sequencer, stream_receipts)
+        .expect("Failed to register ethereum RPC methods");
     rpc
 }
 
 pub struct Ethereum<C: sov_modules_api::Context, Da: DaService> {
-    #[allow(dead_code)]
     da_service: Da,
-    gas_price_oracle: GasPriceOracle<C>,
+    gas_price_oracle: Arc<GasPriceOracle<C>>,
+    response_cache: Arc<ResponseCache<C>>,
+    filters: FilterManager<C>,
     #[cfg(feature = "local")]
     eth_signer: DevSigner,
     storage: C::Storage,
+    /// Ledger of the node, holding the sync status recorded by the runner.
+    ledger_db: LedgerDB,
     sequencer_client: Option<SequencerClient>,
+    /// Receives the numbers of the committed soft batches, resubscribed by every subscription.
+    soft_batches: broadcast::Receiver<u64>,
+    /// Journal of the transactions relayed to the sequencer, only on full nodes.
+    relayed_txs: Option<Arc<RelayedTxJournal<C>>>,
+    /// Receipts streamed from the sequencer, only on full nodes opting in.
+    streamed_receipts: Option<Arc<StreamedReceipts<C>>>,
+    /// Index of the transactions by address, only on nodes opting in.
+    address_index: Option<Arc<AddressIndex<C>>>,
+    /// Node-scoped context of the EVM queries served by the node.
+    query_context: QueryContext,
+    /// Mempool of the node, only on the sequencer.
+    local_mempool: Option<LocalMempool>,
+    /// Store of the verified contracts, only on nodes opting in.
+    contract_verification_store: Option<Arc<ContractVerificationStore>>,
     web3_client_version: String,
     trace_cache: Mutex<LruMap<u64, Vec<GethTrace>, ByLength>>,
 }
@@ -91,13 +238,49 @@ impl<C: sov_modules_api::Context, Da: DaService> Ethereum<C, Da> {
         da_service: Da,
         gas_price_oracle_config: GasPriceOracleConfig,
         fee_history_cache_config: FeeHistoryCacheConfig,
+        response_cache_config: ResponseCacheConfig,
         #[cfg(feature = "local")] eth_signer: DevSigner,
         storage: C::Storage,
+        ledger_db: LedgerDB,
         sequencer_client: Option<SequencerClient>,
+        soft_batches: broadcast::Receiver<u64>,
+        relayed_txs: Option<Arc<RelayedTxJournal<C>>>,
+        streamed_receipts: Option<Arc<StreamedReceipts<C>>>,
+        address_index: Option<Arc<AddressIndex<C>>>,
+        query_context: QueryContext,
+        local_mempool: Option<LocalMempool>,
+        contract_verification_store: Option<Arc<ContractVerificationStore>>,
     ) -> Self {
         let evm = Evm::<C>::default();
-        let gas_price_oracle =
-            GasPriceOracle::new(evm, gas_price_oracle_config, fee_history_cache_config);
+        let gas_price_oracle = Arc::new(GasPriceOracle::new(
+            evm,
+            gas_price_oracle_config,
+            fee_history_cache_config,
+        ));
+        let response_cache = Arc::new(ResponseCache::new(
+            response_cache_config,
+            Evm::<C>::default(),
+            ledger_db.clone(),
+        ));
+        let filters = FilterManager::new(Evm::<C>::default());
+        // The sequencer feeds the pending transaction filters with the transactions of its mempool
+        if let Some(local_mempool) = &local_mempool {
+            let mut listener = (local_mempool.pending_transactions)();
+            let sender = filters.pending_transactions_sender();
+            tokio::spawn(async move {
+                while let Some(hash) = listener.recv().await {
+                    // no receiver until a pending transaction filter is installed
+                    let _ = sender.send(hash);
+                }
+            });
+            // The sequencer counts the RPC caches in its memory budget
+            tokio::spawn(cache_usage::report_caches_usage(
+                response_cache.clone(),
+                gas_price_oracle.clone(),
+                soft_batches.resubscribe(),
+                local_mempool.rpc_caches_usage.clone(),
+            ));
+        }
 
         let rollup = "citrea";
         let arch = std::env::consts::ARCH;
@@ -118,16 +301,159 @@ impl<C: sov_modules_api::Context, Da: DaService> Ethereum<C, Da> {
         Self {
             da_service,
             gas_price_oracle,
+            response_cache,
+            filters,
             #[cfg(feature = "local")]
             eth_signer,
             storage,
+            ledger_db,
             sequencer_client,
+            soft_batches,
+            relayed_txs,
+            streamed_receipts,
+            address_index,
+            query_context,
+            local_mempool,
+            contract_verification_store,
             web3_client_version: current_version,
             trace_cache,
         }
     }
 }
 
+impl<C: sov_modules_api::Context, Da: DaService> Ethereum<C, Da> {
+    /// Suggests a priority fee from the tips paid in recent blocks and, if enabled,
+    /// the tips offered by the executable transactions of the sequencer's mempool.
+    async fn suggest_tip_cap(&self, working_set: &mut WorkingSet<C>) -> EthResult<U256> {
+        let mempool_tips = if self.gas_price_oracle.config().sample_mempool {
+            let base_fee = head_header(&Evm::<C>::default(), working_set)?
+                .base_fee_per_gas
+                .unwrap_or_default();
+            self.mempool_tips(base_fee).await
+        } else {
+            vec![]
+        };
+
+        self.gas_price_oracle
+            .suggest_tip_cap(&mempool_tips, working_set)
+            .await
+    }
+
+    /// Returns the effective tips of the executable transactions of the mempool, read from
+    /// the sequencer on full nodes. Failures only leave the mempool out of the estimate.
+    async fn mempool_tips(&self, base_fee: U256) -> Vec<U256> {
+        let Some(sequencer_client) = &self.sequencer_client else {
+            let Some(local_mempool) = &self.local_mempool else {
+                return vec![];
+            };
+            return (local_mempool.tips)(convert_u256_to_u64(base_fee))
+                .into_iter()
+                .map(U256::from)
+                .collect();
+        };
+
+        match sequencer_client.txpool_content().await {
+            Ok(content) => content
+                .pending
+                .values()
+                .flat_map(|txs| txs.values())
+                .filter_map(|tx| effective_gas_tip(tx, Some(base_fee)))
+                .collect(),
+            Err(e) => {
+                warn!("Failed to sample the mempool of the sequencer: {}", e);
+                vec![]
+            }
+        }
+    }
+
+    /// Traces all the transactions of a block with the requested tracer, returning the trace
+    /// of each transaction with its hash.
+    fn trace_block(
+        &self,
+        block_number: u64,
+        opts: Option<GethDebugTracingOptions>,
+        working_set: &mut WorkingSet<C>,
+    ) -> Result<Vec<TraceResult>, ErrorObjectOwned> {
+        let evm = Evm::<C>::default();
+        let tx_hashes = match evm
+            .get_block_by_number(
+                Some(BlockNumberOrTag::Number(block_number)),
+                Some(false),
+                working_set,
+            )?
+            .map(|block| block.inner.transactions)
+        {
+            Some(BlockTransactions::Hashes(hashes)) => hashes,
+            _ => {
+                return Err(to_jsonrpsee_error_object(
+                    EthApiError::UnknownBlockNumber,
+                    ETH_RPC_ERROR,
+                ))
+            }
+        };
+
+        let traces = self.trace_block_transactions(&evm, block_number, opts, working_set)?;
+        Ok(tx_hashes
+            .into_iter()
+            .zip(traces)
+            .map(|(tx_hash, result)| TraceResult { tx_hash, result })
+            .collect())
+    }
+
+    fn trace_block_transactions(
+        &self,
+        evm: &Evm<C>,
+        block_number: u64,
+        opts: Option<GethDebugTracingOptions>,
+        working_set: &mut WorkingSet<C>,
+    ) -> Result<Vec<GethTrace>, ErrorObjectOwned> {
+        // If the traces of the requested tracer are not cached, then do not check cache or insert cache, just perform the operation
+        if !is_cached_tracer(opts.as_ref()) {
+            return evm
+                .trace_block_transactions_by_number(
+                    &self.query_context,
+                    block_number,
+                    opts,
+                    None,
+                    working_set,
+                )
+                .map_err(|e| to_jsonrpsee_error_object(e, ETH_RPC_ERROR));
+        }
+        let requested_opts = opts.unwrap();
+
+        if let Some(traces) = self.trace_cache.lock().unwrap().get(&block_number) {
+            // If traces are found in cache convert them to specified opts and then return
+            return Ok(get_traces_with_reuqested_tracer_and_config(
+                traces.clone(),
+                requested_opts.tracer.unwrap(),
+                requested_opts.tracer_config,
+            )?);
+        }
+
+        let cache_options = create_trace_cache_opts();
+        let traces = evm
+            .trace_block_transactions_by_number(
+                &self.query_context,
+                block_number,
+                Some(cache_options),
+                None,
+                working_set,
+            )
+            .map_err(|e| to_jsonrpsee_error_object(e, ETH_RPC_ERROR))?;
+        self.trace_cache
+            .lock()
+            .unwrap()
+            .insert(block_number, traces.clone());
+
+        // Convert the traces to the requested tracer and config
+        Ok(get_traces_with_reuqested_tracer_and_config(
+            traces,
+            requested_opts.tracer.unwrap(),
+            requested_opts.tracer_config,
+        )?)
+    }
+}
+
 // impl<C: sov_modules_api::Context, Da: DaService> Ethereum<C, Da> {
 //     fn make_raw_tx(
 //         &self,
@@ -148,13 +474,30 @@ fn register_rpc_methods<C: sov_modules_api::Context, Da: DaService>(
     rpc: &mut RpcModule<Ethereum<C, Da>>,
     // Checks wether the running node is a sequencer or not, if it is not a sequencer it should also have methods like eth_sendRawTransaction here.
     is_sequencer: bool,
-) -> Result<(), jsonrpsee::core::Error> {
+    // Whether the full node serves the receipts streamed from the sequencer
+    stream_receipts: bool,
+) -> Result<(), jsonrpsee::core::Error>
+where
+    C::Storage: NativeStorage,
+{
     rpc.register_async_method("web3_clientVersion", |_, ethereum| async move {
         info!("eth module: web3_clientVersion");
 
         Ok::<_, ErrorObjectOwned>(ethereum.web3_client_version.clone())
     })?;
 
+    rpc.register_async_method("eth_syncing", |_, ethereum| async move {
+        info!("eth module: eth_syncing");
+
+        // The sequencer produces the chain, it is never syncing
+        let status = match ethereum.sequencer_client {
+            Some(_) => ethereum.ledger_db.get_sync_status(),
+            None => None,
+        };
+
+        Ok::<_, ErrorObjectOwned>(SyncingResponse::from(status))
+    })?;
+
     rpc.register_async_method("web3_sha3", |params, _| async move {
         info!("eth module: web3_sha3");
         let data: Bytes = params.one()?;
@@ -169,11 +512,7 @@ fn register_rpc_methods<C: sov_modules_api::Context, Da: DaService>(
         let price = {
             let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
 
-            let suggested_tip = ethereum
-                .gas_price_oracle
-                .suggest_tip_cap(&mut working_set)
-                .await
-                .unwrap();
+            let suggested_tip = ethereum.suggest_tip_cap(&mut working_set).await.unwrap();
 
             let evm = Evm::<C>::default();
             let base_fee = evm
@@ -194,27 +533,32 @@ fn register_rpc_methods<C: sov_modules_api::Context, Da: DaService>(
         let max_fee_per_gas = {
             let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
 
-            ethereum
-                .gas_price_oracle
-                .suggest_tip_cap(&mut working_set)
-                .await
-                .unwrap()
+            ethereum.suggest_tip_cap(&mut working_set).await.unwrap()
         };
 
         Ok::<U256, ErrorObjectOwned>(max_fee_per_gas)
     })?;
 
+    rpc.register_async_method("eth_maxPriorityFeePerGas", |_, ethereum| async move {
+        info!("eth module: eth_maxPriorityFeePerGas");
+        let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
+
+        let max_priority_fee_per_gas = ethereum.suggest_tip_cap(&mut working_set).await?;
+
+        Ok::<U256, ErrorObjectOwned>(max_priority_fee_per_gas)
+    })?;
+
     rpc.register_async_method("eth_feeHistory", |params, ethereum| async move {
         info!("eth module: eth_feeHistory");
         let mut params = params.sequence();
 
-        let block_count: String = params.next().unwrap();
-        let newest_block: BlockNumberOrTag = params.next().unwrap();
+        let block_count: serde_json::Value = params.next()?;
+        let newest_block: BlockNumberOrTag = params.next()?;
         let reward_percentiles: Option<Vec<f64>> = params.optional_next()?;
 
-        // convert block count to u64 from hex
-        let block_count = u64::from_str_radix(&block_count[2..], 16)
-            .map_err(|e| to_jsonrpsee_error_object(e, ETH_RPC_ERROR))?;
+        let block_count = parse_block_count(&block_count)
+            .ok_or_else(|| to_jsonrpsee_error_object(block_count, ETH_RPC_ERROR))?;
+        let newest_block = ethereum.query_context.resolve_finality_tag(newest_block)?;
 
         let fee_history = {
             let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
@@ -227,13 +571,647 @@ fn register_rpc_methods<C: sov_modules_api::Context, Da: DaService>(
                     reward_percentiles,
                     &mut working_set,
                 )
-                .await
-                .unwrap()
+                .await?
         };
 
         Ok::<FeeHistory, ErrorObjectOwned>(fee_history)
     })?;
 
+    rpc.register_async_method("eth_getBlockByHash", |parameters, ethereum| async move {
+        info!("eth module: eth_getBlockByHash");
+        let mut params = parameters.sequence();
+        let block_hash: B256 = params.next()?;
+        let details: Option<bool> = params.optional_next()?;
+
+        let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
+        let block = ethereum.response_cache.get_block_by_hash(
+            block_hash,
+            details.unwrap_or_default(),
+            &mut working_set,
+        )?;
+
+        // the block may not be synced yet
+        read_or_delegate(
+            block,
+            ethereum.sequencer_client.as_ref(),
+            "eth_getBlockByHash",
+            vec![json!(block_hash), json!(details.unwrap_or_default())],
+        )
+        .await
+    })?;
+
+    rpc.register_async_method("eth_getBlockByNumber", |parameters, ethereum| async move {
+        info!("eth module: eth_getBlockByNumber");
+        let mut params = parameters.sequence();
+        let block_number: Option<BlockNumberOrTag> = params.optional_next()?;
+        let details: Option<bool> = params.optional_next()?;
+
+        let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
+        match block_number {
+            // Blocks addressed by number do not change, so they are served from the cache
+            Some(BlockNumberOrTag::Number(block_number)) => {
+                let block = ethereum.response_cache.get_block_by_number(
+                    block_number,
+                    details.unwrap_or_default(),
+                    &mut working_set,
+                )?;
+
+                // the block may not be synced yet
+                read_or_delegate(
+                    block,
+                    ethereum.sequencer_client.as_ref(),
+                    "eth_getBlockByNumber",
+                    vec![
+                        json!(BlockNumberOrTag::Number(block_number)),
+                        json!(details.unwrap_or_default()),
+                    ],
+                )
+                .await
+            }
+            _ => {
+                let block_number = block_number
+                    .map(|block_number| ethereum.query_context.resolve_finality_tag(block_number))
+                    .transpose()?;
+                Evm::<C>::default()
+                    .get_block_by_number(block_number, details, &mut working_set)
+                    .map(ReadResponse::Local)
+            }
+        }
+    })?;
+
+    rpc.register_async_method("eth_getBlockReceipts", |parameters, ethereum| async move {
+        info!("eth module: eth_getBlockReceipts");
+        let block_id: BlockId = parameters.one()?;
+
+        let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
+        let receipts = match block_id {
+            BlockId::Hash(block_hash) => ethereum
+                .response_cache
+                .get_block_receipts_by_hash(block_hash.block_hash, &mut working_set)?,
+            BlockId::Number(BlockNumberOrTag::Number(block_number)) => ethereum
+                .response_cache
+                .get_block_receipts(block_number, &mut working_set)?,
+            BlockId::Number(block_number) => {
+                let block_number = ethereum.query_context.resolve_finality_tag(block_number)?;
+                Evm::<C>::default().get_block_receipts(block_number.into(), &mut working_set)?
+            }
+        };
+
+        // the block may not be executed yet
+        let receipts = match (receipts, &ethereum.streamed_receipts) {
+            (None, Some(streamed_receipts)) => match block_id {
+                BlockId::Hash(block_hash) => {
+                    streamed_receipts.block_receipts_by_hash(block_hash.block_hash)
+                }
+                BlockId::Number(BlockNumberOrTag::Number(block_number)) => {
+                    streamed_receipts.block_receipts(block_number)
+                }
+                _ => None,
+            },
+            (receipts, _) => receipts,
+        };
+
+        // or not synced yet
+        read_or_delegate(
+            receipts,
+            ethereum.sequencer_client.as_ref(),
+            "eth_getBlockReceipts",
+            vec![json!(block_id)],
+        )
+        .await
+    })?;
+
+    rpc.register_async_method(
+        "eth_getTransactionReceipt",
+        |parameters, ethereum| async move {
+            info!("eth module: eth_getTransactionReceipt");
+            let tx_hash: B256 = parameters.one()?;
+
+            let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
+            let receipt = ethereum
+                .response_cache
+                .get_transaction_receipt(tx_hash, &mut working_set)?;
+
+            // the transaction may be in a block not executed yet
+            let receipt = match (receipt, &ethereum.streamed_receipts) {
+                (None, Some(streamed_receipts)) => streamed_receipts.transaction_receipt(tx_hash),
+                (receipt, _) => receipt,
+            };
+
+            // or not synced yet
+            read_or_delegate(
+                receipt,
+                ethereum.sequencer_client.as_ref(),
+                "eth_getTransactionReceipt",
+                vec![json!(tx_hash)],
+            )
+            .await
+        },
+    )?;
+
+    rpc.register_async_method(
+        "citrea_getBlockReceiptsRange",
+        |parameters, ethereum| async move {
+            info!("eth module: citrea_getBlockReceiptsRange");
+            let mut params = parameters.sequence();
+            let from: u64 = params.next()?;
+            let to: u64 = params.next()?;
+
+            let to = to.min(from.saturating_add(MAX_STREAMED_BLOCKS_PER_REQUEST - 1));
+            let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
+            let mut blocks = vec![];
+            for number in from..=to {
+                let Some(hash) =
+                    Evm::<C>::default().block_hash_from_number(number, &mut working_set)
+                else {
+                    break;
+                };
+                let receipts = ethereum
+                    .response_cache
+                    .get_block_receipts(number, &mut working_set)?
+                    .unwrap_or_default();
+                blocks.push(BlockReceipts {
+                    number,
+                    hash,
+                    receipts,
+                });
+            }
+            Ok::<_, ErrorObjectOwned>(blocks)
+        },
+    )?;
+
+    if stream_receipts {
+        rpc.register_async_method("eth_getLogs", |parameters, ethereum| async move {
+            info!("eth module: eth_getLogs");
+            let filter: citrea_evm::Filter = parameters.one()?;
+            let streamed_receipts = ethereum
+                .streamed_receipts
+                .as_ref()
+                .expect("Registered when streaming receipts");
+
+            let evm = Evm::<C>::default();
+            let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
+            let executed_head = evm
+                .block_number_for_id(&BlockNumberOrTag::Latest, &mut working_set)
+                .unwrap_or_default();
+
+            // the logs of the blocks not executed yet are streamed
+            let mut streamed_logs = streamed_receipts.logs(&filter);
+            streamed_logs.retain(|log| {
+                log.block_number
+                    .is_some_and(|number| number > U256::from(executed_head))
+            });
+            let only_streamed = match filter.block_option {
+                citrea_evm::FilterBlockOption::Range {
+                    from_block: Some(BlockNumberOrTag::Number(from)),
+                    ..
+                } => from > executed_head,
+                _ => false,
+            };
+            if only_streamed {
+                return Ok::<_, ErrorObjectOwned>(streamed_logs);
+            }
+
+            match evm.eth_get_logs(filter, &mut working_set) {
+                Ok(mut logs) => {
+                    logs.extend(streamed_logs);
+                    Ok(logs)
+                }
+                // a block hash is either executed or streamed
+                Err(_) if !streamed_logs.is_empty() => Ok(streamed_logs),
+                Err(e) => Err(e),
+            }
+        })?;
+    }
+
+    rpc.register_async_method("citrea_getL1BlockInfo", |parameters, ethereum| async move {
+        info!("eth module: citrea_getL1BlockInfo");
+        let block_number: BlockNumberOrTag = parameters.one()?;
+
+        let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
+        let Some(mut l1_block_info) = Evm::<C>::default().get_l1_block_info(
+            &ethereum.query_context,
+            block_number,
+            &mut working_set,
+        )?
+        else {
+            return Ok::<Option<L1BlockInfo>, ErrorObjectOwned>(None);
+        };
+
+        // The EVM only records the hash of L1 blocks, their timestamp is read from the DA layer
+        let l1_block = ethereum
+            .da_service
+            .get_block_at(l1_block_info.l1_height.to())
+            .await
+            .map_err(|e| to_jsonrpsee_error_object(e, ETH_RPC_ERROR))?;
+        if l1_block.hash() != l1_block_info.l1_hash.0 {
+            return Err(to_jsonrpsee_error_object(
+                format!(
+                    "L1 block {} on the DA layer doesn't match the recorded one",
+                    l1_block_info.l1_height
+                ),
+                ETH_RPC_ERROR,
+            ));
+        }
+        l1_block_info.l1_timestamp = Some(U64::from(l1_block.header().time().secs() as u64));
+
+        Ok(Some(l1_block_info))
+    })?;
+
+    rpc.register_async_method(
+        "citrea_getTransactionsByAddress",
+        |parameters, ethereum| async move {
+            info!("eth module: citrea_getTransactionsByAddress");
+            let mut params = parameters.sequence();
+            let address: Address = params.next()?;
+            let limit: Option<usize> = params.optional_next()?;
+            let cursor: Option<AddressTxCursor> = params.optional_next()?;
+
+            let address_index = ethereum.address_index.as_ref().ok_or_else(|| {
+                to_jsonrpsee_error_object("Transaction index by address is disabled", ETH_RPC_ERROR)
+            })?;
+            let limit = limit
+                .unwrap_or(MAX_ADDRESS_TXS_PER_REQUEST)
+                .clamp(1, MAX_ADDRESS_TXS_PER_REQUEST);
+            address_index.transactions(address, cursor, limit)
+        },
+    )?;
+
+    rpc.register_async_method(
+        "citrea_getTransactionTimeline",
+        |parameters, ethereum| async move {
+            info!("eth module: citrea_getTransactionTimeline");
+            let hash: B256 = parameters.one()?;
+            transaction_timeline::<C>(&ethereum.ledger_db, ethereum.storage.clone(), hash)
+        },
+    )?;
+
+    rpc.register_async_method("citrea_verifyContract", |parameters, ethereum| async move {
+        info!("eth module: citrea_verifyContract");
+        let mut params = parameters.sequence();
+        let address: Address = params.next()?;
+        let metadata: String = params.next()?;
+        let sources: Option<BTreeMap<String, String>> = params.optional_next()?;
+
+        let store = ethereum
+            .contract_verification_store
+            .as_ref()
+            .ok_or_else(|| {
+                to_jsonrpsee_error_object("Contract verification is disabled", ETH_RPC_ERROR)
+            })?;
+        let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
+        let code = Evm::<C>::default().get_code(
+            &ethereum.query_context,
+            address,
+            None,
+            &mut working_set,
+        )?;
+
+        store
+            .verify(address, &code, metadata, sources.unwrap_or_default())
+            .map_err(|e| to_jsonrpsee_error_object(e, ETH_RPC_ERROR))
+    })?;
+
+    rpc.register_async_method(
+        "citrea_getVerifiedContract",
+        |parameters, ethereum| async move {
+            info!("eth module: citrea_getVerifiedContract");
+            let address: Address = parameters.one()?;
+
+            let store = ethereum
+                .contract_verification_store
+                .as_ref()
+                .ok_or_else(|| {
+                    to_jsonrpsee_error_object("Contract verification is disabled", ETH_RPC_ERROR)
+                })?;
+            Ok::<Option<VerifiedContract>, ErrorObjectOwned>(store.get(&address))
+        },
+    )?;
+
+    rpc.register_async_method("eth_getProof", |parameters, ethereum| async move {
+        info!("eth module: eth_getProof");
+        let mut params = parameters.sequence();
+        let address: Address = params.next()?;
+        let storage_keys: Vec<B256> = params.next()?;
+        let block_number: Option<BlockId> = params.optional_next()?;
+
+        let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
+        let proof = Evm::<C>::default().get_proof(
+            &ethereum.query_context,
+            address,
+            storage_keys,
+            block_number,
+            &mut working_set,
+        )?;
+
+        Ok::<AccountProof, ErrorObjectOwned>(proof)
+    })?;
+
+    rpc.register_async_method("eth_getBalance", |parameters, ethereum| async move {
+        info!("eth module: eth_getBalance");
+        let mut params = parameters.sequence();
+        let address: Address = params.next()?;
+        let block_id: Option<BlockId> = params.optional_next()?;
+
+        let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
+        Evm::<C>::default().get_balance(
+            &ethereum.query_context,
+            address,
+            block_id,
+            &mut working_set,
+        )
+    })?;
+
+    rpc.register_async_method("eth_getStorageAt", |parameters, ethereum| async move {
+        info!("eth module: eth_getStorageAt");
+        let mut params = parameters.sequence();
+        let address: Address = params.next()?;
+        let index: U256 = params.next()?;
+        let block_id: Option<BlockId> = params.optional_next()?;
+
+        let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
+        Evm::<C>::default().get_storage_at(
+            &ethereum.query_context,
+            address,
+            index,
+            block_id,
+            &mut working_set,
+        )
+    })?;
+
+    rpc.register_async_method(
+        "eth_getTransactionCount",
+        |parameters, ethereum| async move {
+            let mut params = parameters.sequence();
+            let address: Address = params.next()?;
+            let block_id: Option<BlockId> = params.optional_next()?;
+            info!(
+                "eth module: eth_getTransactionCount({}, {:?})",
+                address, block_id
+            );
+
+            // The mempool is on the sequencer, full nodes ask it for the pending nonce so that
+            // wallets don't reuse the nonces of the transactions they already sent
+            if let (Some(sequencer_client), Some(BlockId::Number(BlockNumberOrTag::Pending))) =
+                (&ethereum.sequencer_client, block_id)
+            {
+                return sequencer_client
+                    .get_pending_nonce(address)
+                    .await
+                    .map_err(|e| to_jsonrpsee_error_object(e, ETH_RPC_ERROR));
+            }
+
+            let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
+            Evm::<C>::default().get_transaction_count(
+                &ethereum.query_context,
+                address,
+                block_id,
+                &mut working_set,
+            )
+        },
+    )?;
+
+    rpc.register_async_method("eth_getCode", |parameters, ethereum| async move {
+        info!("eth module: eth_getCode");
+        let mut params = parameters.sequence();
+        let address: Address = params.next()?;
+        let block_id: Option<BlockId> = params.optional_next()?;
+
+        let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
+        Evm::<C>::default().get_code(&ethereum.query_context, address, block_id, &mut working_set)
+    })?;
+
+    rpc.register_async_method(
+        "citrea_getBaseFeeAccrued",
+        |parameters, ethereum| async move {
+            info!("eth module: citrea_getBaseFeeAccrued");
+            let block_number: Option<BlockNumberOrTag> = parameters.sequence().optional_next()?;
+
+            let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
+            Evm::<C>::default().get_base_fee_accrued(
+                &ethereum.query_context,
+                block_number,
+                &mut working_set,
+            )
+        },
+    )?;
+
+    rpc.register_async_method(
+        "eth_getTransactionByBlockNumberAndIndex",
+        |parameters, ethereum| async move {
+            info!("eth module: eth_getTransactionByBlockNumberAndIndex");
+            let mut params = parameters.sequence();
+            let block_number: BlockNumberOrTag = params.next()?;
+            let index: U64 = params.next()?;
+
+            let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
+            Evm::<C>::default().get_transaction_by_block_number_and_index(
+                &ethereum.query_context,
+                block_number,
+                index,
+                &mut working_set,
+            )
+        },
+    )?;
+
+    rpc.register_async_method("eth_call", |parameters, ethereum| async move {
+        info!("eth module: eth_call");
+        let mut params = parameters.sequence();
+        let request: reth_rpc_types::TransactionRequest = params.next()?;
+        let block_id: Option<BlockId> = params.optional_next()?;
+        let state_overrides: Option<reth_rpc_types::state::StateOverride> =
+            params.optional_next()?;
+        let block_overrides: Option<Box<reth_rpc_types::BlockOverrides>> =
+            params.optional_next()?;
+
+        let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
+        Evm::<C>::default().get_call(
+            &ethereum.query_context,
+            request,
+            block_id,
+            state_overrides,
+            block_overrides,
+            &mut working_set,
+        )
+    })?;
+
+    rpc.register_async_method("eth_simulateV1", |parameters, ethereum| async move {
+        info!("eth module: eth_simulateV1");
+        let mut params = parameters.sequence();
+        let payload: SimulatePayload = params.next()?;
+        let block_id: Option<BlockId> = params.optional_next()?;
+
+        let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
+        Evm::<C>::default().simulate_v1(
+            &ethereum.query_context,
+            payload,
+            block_id,
+            &mut working_set,
+        )
+    })?;
+
+    rpc.register_async_method("eth_createAccessList", |parameters, ethereum| async move {
+        info!("eth module: eth_createAccessList");
+        let mut params = parameters.sequence();
+        let request: reth_rpc_types::TransactionRequest = params.next()?;
+        let block_id: Option<BlockId> = params.optional_next()?;
+
+        let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
+        Evm::<C>::default().create_access_list(
+            &ethereum.query_context,
+            request,
+            block_id,
+            &mut working_set,
+        )
+    })?;
+
+    rpc.register_async_method("eth_estimateGas", |parameters, ethereum| async move {
+        info!("eth module: eth_estimateGas");
+        let mut params = parameters.sequence();
+        let request: reth_rpc_types::TransactionRequest = params.next()?;
+        let block_id: Option<BlockId> = params.optional_next()?;
+        let state_overrides: Option<reth_rpc_types::state::StateOverride> =
+            params.optional_next()?;
+
+        let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
+        Evm::<C>::default().eth_estimate_gas(
+            &ethereum.query_context,
+            request,
+            block_id,
+            state_overrides,
+            &mut working_set,
+        )
+    })?;
+
+    rpc.register_async_method(
+        "eth_getBlockTransactionCountByNumber",
+        |parameters, ethereum| async move {
+            info!("eth module: eth_getBlockTransactionCountByNumber");
+            let block_number: BlockNumberOrTag = parameters.one()?;
+
+            let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
+            Evm::<C>::default().eth_get_block_transaction_count_by_number(
+                &ethereum.query_context,
+                block_number,
+                &mut working_set,
+            )
+        },
+    )?;
+
+    rpc.register_async_method(
+        "citrea_getBlockOrderingInfo",
+        |parameters, ethereum| async move {
+            info!("eth module: citrea_getBlockOrderingInfo");
+            let block_number: BlockNumberOrTag = parameters.one()?;
+
+            let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
+            Evm::<C>::default().get_block_ordering_info(
+                &ethereum.query_context,
+                block_number,
+                &mut working_set,
+            )
+        },
+    )?;
+
+    rpc.register_async_method(
+        "citrea_getStorageHistory",
+        |parameters, ethereum| async move {
+            info!("eth module: citrea_getStorageHistory");
+            let mut params = parameters.sequence();
+            let address: Address = params.next()?;
+            let index: U256 = params.next()?;
+            let from_block: BlockNumberOrTag = params.next()?;
+            let to_block: BlockNumberOrTag = params.next()?;
+
+            let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
+            Evm::<C>::default().get_storage_history(
+                &ethereum.query_context,
+                address,
+                index,
+                from_block,
+                to_block,
+                &mut working_set,
+            )
+        },
+    )?;
+
+    rpc.register_async_method(
+        "citrea_exportStateTests",
+        |parameters, ethereum| async move {
+            info!("eth module: citrea_exportStateTests");
+            let mut params = parameters.sequence();
+            let from_block: BlockNumberOrTag = params.next()?;
+            let to_block: BlockNumberOrTag = params.next()?;
+
+            let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
+            Evm::<C>::default().export_state_tests(
+                &ethereum.query_context,
+                from_block,
+                to_block,
+                &mut working_set,
+            )
+        },
+    )?;
+
+    rpc.register_async_method("citrea_exportState", |parameters, ethereum| async move {
+        info!("eth module: citrea_exportState");
+        let mut params = parameters.sequence();
+        let block_number: BlockNumberOrTag = params.next()?;
+        let offset: Option<U64> = params.optional_next()?;
+        let limit: Option<U64> = params.optional_next()?;
+
+        let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
+        Evm::<C>::default().export_state(
+            &ethereum.query_context,
+            block_number,
+            offset,
+            limit,
+            &mut working_set,
+        )
+    })?;
+
+    rpc.register_async_method("trace_block", |parameters, ethereum| async move {
+        info!("eth module: trace_block");
+        let block_number: BlockNumberOrTag = parameters.one()?;
+
+        let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
+        Evm::<C>::default().trace_block(&ethereum.query_context, block_number, &mut working_set)
+    })?;
+
+    rpc.register_async_method("trace_transaction", |parameters, ethereum| async move {
+        info!("eth module: trace_transaction");
+        let tx_hash: B256 = parameters.one()?;
+
+        let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
+        Evm::<C>::default().trace_transaction(&ethereum.query_context, tx_hash, &mut working_set)
+    })?;
+
+    rpc.register_async_method("trace_filter", |parameters, ethereum| async move {
+        info!("eth module: trace_filter");
+        let filter: TraceFilter = parameters.one()?;
+
+        let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
+        Evm::<C>::default().trace_filter(&ethereum.query_context, filter, &mut working_set)
+    })?;
+
+    rpc.register_async_method(
+        "ots_getTransactionBySenderAndNonce",
+        |parameters, ethereum| async move {
+            info!("eth module: ots_getTransactionBySenderAndNonce");
+            let mut params = parameters.sequence();
+            let sender: Address = params.next()?;
+            let nonce: u64 = params.next()?;
+
+            let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
+            Evm::<C>::default().ots_get_transaction_by_sender_and_nonce(
+                &ethereum.query_context,
+                sender,
+                nonce,
+                &mut working_set,
+            )
+        },
+    )?;
+
     #[cfg(feature = "local")]
     rpc.register_async_method("eth_accounts", |_, ethereum| async move {
         info!("eth module: eth_accounts");
@@ -488,52 +1466,7 @@ fn register_rpc_methods<C: sov_modules_api::Context, Da: DaService>(
                     }
                 };
 
-            // If opts is None or if opts.tracer is None, then do not check cache or insert cache, just perform the operation
-            if opts.as_ref().map_or(true, |o| o.tracer.is_none()) {
-                return evm
-                    .trace_block_transactions_by_number(
-                        block_number,
-                        opts.clone(),
-                        None,
-                        &mut working_set,
-                    )
-                    .map_err(|e| to_jsonrpsee_error_object(e, ETH_RPC_ERROR));
-            }
-
-            if let Some(traces) = ethereum.trace_cache.lock().unwrap().get(&block_number) {
-                // If traces are found in cache convert them to specified opts and then return
-                let requested_opts = opts.clone().unwrap();
-                let traces = get_traces_with_reuqested_tracer_and_config(
-                    traces.clone(),
-                    requested_opts.tracer.unwrap(),
-                    requested_opts.tracer_config,
-                )?;
-                return Ok::<Vec<GethTrace>, ErrorObjectOwned>(traces);
-            }
-            let cache_options = create_trace_cache_opts();
-            let traces = evm
-                .trace_block_transactions_by_number(
-                    block_number,
-                    Some(cache_options),
-                    None,
-                    &mut working_set,
-                )
-                .map_err(|e| to_jsonrpsee_error_object(e, ETH_RPC_ERROR))?;
-            ethereum
-                .trace_cache
-                .lock()
-                .unwrap()
-                .insert(block_number, traces.clone());
-            // Convert the traces to the requested tracer and config
-            let requested_opts = opts.clone().unwrap();
-            let tracer_config = requested_opts.tracer_config;
-            let traces = get_traces_with_reuqested_tracer_and_config(
-                traces.clone(),
-                requested_opts.tracer.unwrap(),
-                tracer_config,
-            )?;
-
-            Ok::<Vec<GethTrace>, ErrorObjectOwned>(traces)
+            ethereum.trace_block(block_number, opts, &mut working_set)
         },
     )?;
 
@@ -560,43 +1493,31 @@ fn register_rpc_methods<C: sov_modules_api::Context, Da: DaService>(
                 }
             };
 
-            // If opts is None or if opts.tracer is None, then do not check cache or insert cache, just perform the operation
-            if opts.as_ref().map_or(true, |o| o.tracer.is_none()) {
-                return evm.trace_block_transactions_by_number(block_number, opts.clone(), None, &mut working_set)
-                        .map_err(|e| to_jsonrpsee_error_object(e, ETH_RPC_ERROR));
-            }
+            ethereum.trace_block(block_number, opts, &mut working_set)
+        },
+    )?;
 
-            if let Some(traces) = ethereum.trace_cache.lock().unwrap().get(&block_number) {
-                // If traces are found in cache convert them to specified opts and then return
-                let requested_opts = opts.clone().unwrap();
-                let tracer_config = requested_opts.tracer_config;
-                let traces = get_traces_with_reuqested_tracer_and_config(traces.clone(), requested_opts.tracer.unwrap(), tracer_config)?;
-                return Ok::<Vec<GethTrace>, ErrorObjectOwned>(traces);
-            }
+    rpc.register_async_method("debug_traceCall", |parameters, ethereum| async move {
+        info!("eth module: debug_traceCall");
 
-            let cache_options = create_trace_cache_opts();
-            let traces = evm
-                .trace_block_transactions_by_number(
-                    block_number,
-                    Some(cache_options),
-                    None,
-                    &mut working_set,
-                )
-                .map_err(|e| to_jsonrpsee_error_object(e, ETH_RPC_ERROR))?;
-                ethereum
-                    .trace_cache
-                    .lock()
-                    .unwrap()
-                    .insert(block_number, traces.clone());
+        let mut params = parameters.sequence();
 
-            // Convert the traces to the requested tracer and config
-            let requested_opts = opts.clone().unwrap();
-            let tracer_config = requested_opts.tracer_config;
-            let traces = get_traces_with_reuqested_tracer_and_config(traces.clone(), requested_opts.tracer.unwrap(), tracer_config)?;
+        let request: reth_rpc_types::TransactionRequest = params.next()?;
+        let block_number: Option<BlockId> = params.optional_next()?;
+        let opts: Option<GethDebugTracingCallOptions> = params.optional_next()?;
 
-            Ok::<Vec<GethTrace>, ErrorObjectOwned>(traces)
-        },
-    )?;
+        let evm = Evm::<C>::default();
+        let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
+
+        evm.trace_call(
+            &ethereum.query_context,
+            request,
+            block_number,
+            opts,
+            &mut working_set,
+        )
+        .map_err(|e| to_jsonrpsee_error_object(e, ETH_RPC_ERROR))
+    })?;
 
     rpc.register_async_method(
         "debug_traceTransaction",
@@ -632,11 +1553,12 @@ fn register_rpc_methods<C: sov_modules_api::Context, Da: DaService>(
 
             let opts: Option<GethDebugTracingOptions> = params.optional_next().unwrap();
 
-            // If opts is None or if opts.tracer is None, then do not check cache or insert cache, just perform the operation
+            // If the traces of the requested tracer are not cached, then do not check cache or insert cache, just perform the operation
             // also since this is not cached we need to stop at somewhere, so we add param stop_at
-            if opts.as_ref().map_or(true, |o| o.tracer.is_none()) {
+            if !is_cached_tracer(opts.as_ref()) {
                 return Ok::<GethTrace, ErrorObjectOwned>(
                     evm.trace_block_transactions_by_number(
+                        &ethereum.query_context,
                         block_number,
                         opts.clone(),
                         Some(trace_index as usize),
@@ -663,6 +1585,7 @@ fn register_rpc_methods<C: sov_modules_api::Context, Da: DaService>(
             let cache_options = create_trace_cache_opts();
             let traces = evm
                 .trace_block_transactions_by_number(
+                    &ethereum.query_context,
                     block_number,
                     Some(cache_options),
                     None,
@@ -687,6 +1610,101 @@ fn register_rpc_methods<C: sov_modules_api::Context, Da: DaService>(
         },
     )?;
 
+    rpc.register_async_method("eth_newFilter", |parameters, ethereum| async move {
+        info!("eth module: eth_newFilter");
+        let filter: citrea_evm::Filter = parameters.one()?;
+        let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
+        Ok::<FilterId, ErrorObjectOwned>(ethereum.filters.new_log_filter(filter, &mut working_set))
+    })?;
+
+    rpc.register_async_method("eth_newBlockFilter", |_, ethereum| async move {
+        info!("eth module: eth_newBlockFilter");
+        let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
+        Ok::<FilterId, ErrorObjectOwned>(ethereum.filters.new_block_filter(&mut working_set))
+    })?;
+
+    rpc.register_async_method(
+        "eth_newPendingTransactionFilter",
+        |_, ethereum| async move {
+            info!("eth module: eth_newPendingTransactionFilter");
+            let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
+            Ok::<FilterId, ErrorObjectOwned>(
+                ethereum
+                    .filters
+                    .new_pending_transaction_filter(&mut working_set),
+            )
+        },
+    )?;
+
+    rpc.register_async_method("eth_getFilterChanges", |parameters, ethereum| async move {
+        info!("eth module: eth_getFilterChanges");
+        let id: FilterId = parameters.one()?;
+        let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
+        ethereum.filters.changes(id, &mut working_set)
+    })?;
+
+    rpc.register_async_method("eth_getFilterLogs", |parameters, ethereum| async move {
+        info!("eth module: eth_getFilterLogs");
+        let id: FilterId = parameters.one()?;
+        let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
+        ethereum.filters.logs(id, &mut working_set)
+    })?;
+
+    rpc.register_async_method("eth_uninstallFilter", |parameters, ethereum| async move {
+        info!("eth module: eth_uninstallFilter");
+        let id: FilterId = parameters.one()?;
+        Ok::<bool, ErrorObjectOwned>(ethereum.filters.uninstall(id))
+    })?;
+
+    rpc.register_subscription(
+        "eth_subscribe",
+        "eth_subscription",
+        "eth_unsubscribe",
+        |parameters, pending, ethereum| async move {
+            info!("eth module: eth_subscribe");
+            match SubscriptionKind::parse(parameters) {
+                Ok(SubscriptionKind::NewHeads) => {
+                    subscriptions::new_heads::<C>(
+                        pending,
+                        ethereum.soft_batches.resubscribe(),
+                        ethereum.storage.clone(),
+                    )
+                    .await
+                }
+                Ok(SubscriptionKind::Logs(filter)) => {
+                    subscriptions::logs::<C>(
+                        pending,
+                        ethereum.soft_batches.resubscribe(),
+                        ethereum.storage.clone(),
+                        *filter,
+                    )
+                    .await
+                }
+                Ok(SubscriptionKind::ReplacedTransactions(filter)) => {
+                    // only the sequencer knows about the replacements in its mempool
+                    let Some(local_mempool) = &ethereum.local_mempool else {
+                        pending
+                            .reject(EthApiError::Unsupported(
+                                "replacedTransactions is only served by the sequencer",
+                            ))
+                            .await;
+                        return Ok(());
+                    };
+                    subscriptions::replaced_transactions(
+                        pending,
+                        (local_mempool.replaced_transactions)(),
+                        filter,
+                    )
+                    .await
+                }
+                Err(e) => {
+                    pending.reject(e).await;
+                    Ok(())
+                }
+            }
+        },
+    )?;
+
     rpc.register_async_method("txpool_content", |_, _| async move {
         info!("eth module: txpool_content");
 
@@ -715,7 +1733,51 @@ fn register_rpc_methods<C: sov_modules_api::Context, Da: DaService>(
         },
     )?;
 
+    // There are no uncles on the rollup
+    rpc.register_async_method("eth_getUncleByBlockNumberAndIndex", |_, _| async move {
+        info!("eth module: eth_getUncleByBlockNumberAndIndex");
+        Ok::<_, ErrorObjectOwned>(json!(null))
+    })?;
+
+    rpc.register_async_method("eth_getUncleCountByBlockHash", |_, _| async move {
+        info!("eth module: eth_getUncleCountByBlockHash");
+        Ok::<U256, ErrorObjectOwned>(U256::ZERO)
+    })?;
+
+    rpc.register_async_method("eth_getUncleCountByBlockNumber", |_, _| async move {
+        info!("eth module: eth_getUncleCountByBlockNumber");
+        Ok::<U256, ErrorObjectOwned>(U256::ZERO)
+    })?;
+
+    // Blocks are produced by the sequencer, not mined
+    rpc.register_async_method("eth_mining", |_, _| async move {
+        info!("eth module: eth_mining");
+        Ok::<bool, ErrorObjectOwned>(false)
+    })?;
+
+    rpc.register_async_method("eth_hashrate", |_, _| async move {
+        info!("eth module: eth_hashrate");
+        Ok::<U256, ErrorObjectOwned>(U256::ZERO)
+    })?;
+
+    // Answering means the RPC server is listening
+    rpc.register_async_method("net_listening", |_, _| async move {
+        info!("eth module: net_listening");
+        Ok::<bool, ErrorObjectOwned>(true)
+    })?;
+
     if !is_sequencer {
+        // The peer of a full node is the sequencer it syncs from, the sequencer counts
+        // the full nodes syncing from it
+        rpc.register_async_method("net_peerCount", |_, ethereum| async move {
+            info!("Full Node: net_peerCount");
+            let connected = ethereum
+                .sequencer_client
+                .as_ref()
+                .is_some_and(|sequencer_client| sequencer_client.is_connected());
+            Ok::<U64, ErrorObjectOwned>(U64::from(connected as u8))
+        })?;
+
         rpc.register_async_method(
             "eth_sendRawTransaction",
             |parameters, ethereum| async move {
@@ -727,13 +1789,68 @@ fn register_rpc_methods<C: sov_modules_api::Context, Da: DaService>(
                     .sequencer_client
                     .as_ref()
                     .unwrap()
-                    .send_raw_tx(data)
+                    .send_raw_tx(data.clone())
                     .await;
 
-                tx_hash.map_err(|e| to_jsonrpsee_error_object(e, ETH_RPC_ERROR))
+                // Forward the error of the sequencer as is, so that clients get the
+                // geth compatible codes and messages it returns, e.g. "nonce too low"
+                let tx_hash =
+                    tx_hash.map_err(|e| match e.downcast::<jsonrpsee::core::Error>() {
+                        Ok(jsonrpsee::core::Error::Call(error_object)) => error_object,
+                        Ok(e) => to_jsonrpsee_error_object(e, ETH_RPC_ERROR),
+                        Err(e) => to_jsonrpsee_error_object(e, ETH_RPC_ERROR),
+                    })?;
+
+                if let Some(relayed_txs) = &ethereum.relayed_txs {
+                    relayed_txs.record(B256::from(tx_hash.0), &data);
+                }
+                if let Err(e) = ethereum.ledger_db.put_tx_submission(tx_hash.0) {
+                    warn!("Failed to record the submission of {:?}: {:?}", tx_hash, e);
+                }
+
+                // no receiver until a pending transaction filter is installed
+                let _ = ethereum
+                    .filters
+                    .pending_transactions_sender()
+                    .send(B256::from(tx_hash.0));
+                Ok::<_, ErrorObjectOwned>(tx_hash)
             },
         )?;
 
+        // The mempool is on the sequencer
+        rpc.register_async_method("txpool_content", |_, ethereum| async move {
+            info!("Full Node: txpool_content");
+            ethereum
+                .sequencer_client
+                .as_ref()
+                .unwrap()
+                .txpool_content()
+                .await
+                .map_err(|e| to_jsonrpsee_error_object(e, ETH_RPC_ERROR))
+        })?;
+
+        rpc.register_async_method("txpool_status", |_, ethereum| async move {
+            info!("Full Node: txpool_status");
+            ethereum
+                .sequencer_client
+                .as_ref()
+                .unwrap()
+                .txpool_status()
+                .await
+                .map_err(|e| to_jsonrpsee_error_object(e, ETH_RPC_ERROR))
+        })?;
+
+        rpc.register_async_method("txpool_inspect", |_, ethereum| async move {
+            info!("Full Node: txpool_inspect");
+            ethereum
+                .sequencer_client
+                .as_ref()
+                .unwrap()
+                .txpool_inspect()
+                .await
+                .map_err(|e| to_jsonrpsee_error_object(e, ETH_RPC_ERROR))
+        })?;
+
         rpc.register_async_method(
             "eth_getTransactionByHash",
             |parameters, ethereum| async move {
@@ -761,9 +1878,11 @@ fn register_rpc_methods<C: sov_modules_api::Context, Da: DaService>(
                     }
                     _ => {
                         // if mempool_only is not true ask evm first then sequencer
-                        let evm = Evm::<C>::default();
                         let mut working_set = WorkingSet::<C>::new(ethereum.storage.clone());
-                        match evm.get_transaction_by_hash(hash, &mut working_set) {
+                        match ethereum
+                            .response_cache
+                            .get_transaction_by_hash(hash, &mut working_set)
+                        {
                             Ok(Some(tx)) => Ok::<
                                 Option<reth_rpc_types::Transaction>,
                                 ErrorObjectOwned,
@@ -862,6 +1981,18 @@ pub fn get_latest_git_tag() -> Result<String, ErrorObjectOwned> {
         .to_string())
 }
 
+/// Parses the block count of `eth_feeHistory`, which tools send either as a hex string or a number.
+fn parse_block_count(block_count: &serde_json::Value) -> Option<u64> {
+    match block_count {
+        serde_json::Value::Number(block_count) => block_count.as_u64(),
+        serde_json::Value::String(block_count) => match block_count.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16).ok(),
+            None => block_count.parse().ok(),
+        },
+        _ => None,
+    }
+}
+
 fn apply_call_config(call_frame: CallFrame, call_config: CallConfig) -> CallFrame {
     // let only_top_call = call_config.only_top_call.unwrap_or();
     let mut new_call_frame = call_frame.clone();
@@ -974,6 +2105,18 @@ fn convert_call_trace_into_4byte_map(
     four_byte_map
 }
 
+/// Returns whether the traces of the requested tracer can be derived from the cached call traces.
+/// Opcode and prestate traces are not cached.
+fn is_cached_tracer(opts: Option<&GethDebugTracingOptions>) -> bool {
+    match opts.and_then(|opts| opts.tracer.as_ref()) {
+        None => false,
+        Some(GethDebugTracerType::BuiltInTracer(GethDebugBuiltInTracerType::PreStateTracer)) => {
+            false
+        }
+        Some(_) => true,
+    }
+}
+
 fn create_trace_cache_opts() -> GethDebugTracingOptions {
     // Get the traces with call tracer onlytopcall false and withlog true and always cache this way
     let mut call_config_map = serde_json::Map::new();
@@ -988,3 +2131,18 @@ fn create_trace_cache_opts() -> GethDebugTracingOptions {
         ..Default::default()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_fee_history_block_count() {
+        assert_eq!(parse_block_count(&json!("0x14")), Some(20));
+        assert_eq!(parse_block_count(&json!("20")), Some(20));
+        assert_eq!(parse_block_count(&json!(20)), Some(20));
+        assert_eq!(parse_block_count(&json!("0xzz")), None);
+        assert_eq!(parse_block_count(&json!(-1)), None);
+        assert_eq!(parse_block_count(&json!(null)), None);
+    }
+}
@@ -0,0 +1,143 @@
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+use citrea_evm::Evm;
+use ethers::types::Bytes;
+use reth_primitives::B256;
+use sequencer_client::SequencerClient;
+use sov_db::ledger_db::LedgerDB;
+use sov_modules_api::WorkingSet;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{info, warn};
+
+/// Journal of the transactions a full node relayed to the sequencer, persisted in the ledger DB
+/// until they are included in a soft batch.
+///
+/// A transaction accepted by the sequencer just before it or the full node crashed may never
+/// make it to a soft batch. After a restart, the full node re-broadcasts the journaled
+/// transactions that are still not included, so that they aren't silently lost.
+pub(crate) struct RelayedTxJournal<C: sov_modules_api::Context> {
+    ledger_db: LedgerDB,
+    /// Hashes of the journaled transactions, so that soft batches are checked without
+    /// reading the journal.
+    pending: Mutex<HashSet<B256>>,
+    storage: C::Storage,
+}
+
+impl<C: sov_modules_api::Context> RelayedTxJournal<C> {
+    pub(crate) fn new(ledger_db: LedgerDB, storage: C::Storage) -> Self {
+        Self {
+            ledger_db,
+            pending: Mutex::new(HashSet::new()),
+            storage,
+        }
+    }
+
+    /// Journals a transaction accepted by the sequencer.
+    pub(crate) fn record(&self, hash: B256, raw_tx: &Bytes) {
+        if let Err(e) = self.ledger_db.put_relayed_tx(hash.0, raw_tx.to_vec()) {
+            warn!("Failed to journal relayed transaction {}: {:?}", hash, e);
+            return;
+        }
+        self.pending.lock().unwrap().insert(hash);
+    }
+
+    /// Re-broadcasts the transactions journaled before the restart that are not included yet,
+    /// then removes the journaled transactions from the journal as soft batches include them.
+    pub(crate) async fn run(
+        self: Arc<Self>,
+        sequencer_client: SequencerClient,
+        mut soft_batches: broadcast::Receiver<u64>,
+    ) {
+        self.rebroadcast(&sequencer_client).await;
+
+        loop {
+            match soft_batches.recv().await {
+                Ok(_) | Err(RecvError::Lagged(_)) => self.prune_included(),
+                Err(RecvError::Closed) => return,
+            }
+        }
+    }
+
+    async fn rebroadcast(&self, sequencer_client: &SequencerClient) {
+        let journaled = match self.ledger_db.get_relayed_txs() {
+            Ok(journaled) => journaled,
+            Err(e) => {
+                warn!(
+                    "Failed to read the journal of relayed transactions: {:?}",
+                    e
+                );
+                return;
+            }
+        };
+
+        let mut dropped = vec![];
+        for (hash, raw_tx) in journaled {
+            let hash = B256::from(hash);
+            if self.is_included(hash) {
+                dropped.push(hash.0);
+                continue;
+            }
+
+            match sequencer_client.send_raw_tx(Bytes::from(raw_tx)).await {
+                Ok(_) => {
+                    info!("Re-broadcast relayed transaction {} to the sequencer", hash);
+                    self.pending.lock().unwrap().insert(hash);
+                }
+                // the sequencer still has it in its mempool
+                Err(e) if e.to_string().contains("already known") => {
+                    self.pending.lock().unwrap().insert(hash);
+                }
+                // e.g. the nonce was used by another transaction in the meantime
+                Err(e) => {
+                    warn!(
+                        "Sequencer rejected re-broadcast transaction {}, dropping it: {:?}",
+                        hash, e
+                    );
+                    dropped.push(hash.0);
+                }
+            }
+        }
+
+        if let Err(e) = self.ledger_db.delete_relayed_txs(&dropped) {
+            warn!(
+                "Failed to prune the journal of relayed transactions: {:?}",
+                e
+            );
+        }
+    }
+
+    fn prune_included(&self) {
+        let pending: Vec<B256> = self.pending.lock().unwrap().iter().copied().collect();
+        let included: Vec<B256> = pending
+            .into_iter()
+            .filter(|hash| self.is_included(*hash))
+            .collect();
+        if included.is_empty() {
+            return;
+        }
+
+        let hashes: Vec<[u8; 32]> = included.iter().map(|hash| hash.0).collect();
+        if let Err(e) = self.ledger_db.delete_relayed_txs(&hashes) {
+            warn!(
+                "Failed to prune the journal of relayed transactions: {:?}",
+                e
+            );
+            return;
+        }
+        let mut pending = self.pending.lock().unwrap();
+        for hash in included {
+            pending.remove(&hash);
+        }
+    }
+
+    /// Whether the transaction is in a soft batch synced by the node.
+    fn is_included(&self, hash: B256) -> bool {
+        let mut working_set = WorkingSet::<C>::new(self.storage.clone());
+        matches!(
+            Evm::<C>::default().get_transaction_by_hash(hash, &mut working_set),
+            Ok(Some(_))
+        )
+    }
+}
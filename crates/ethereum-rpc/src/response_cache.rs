@@ -0,0 +1,343 @@
+use std::mem::size_of;
+use std::sync::Mutex;
+
+use citrea_evm::Evm;
+use jsonrpsee::core::RpcResult;
+use reth_primitives::{BlockNumberOrTag, B256};
+use reth_rpc_types::{RichBlock, Transaction, TransactionReceipt};
+use schnellru::{ByLength, LruMap};
+use serde::{Deserialize, Serialize};
+use sov_db::ledger_db::LedgerDB;
+use sov_modules_api::WorkingSet;
+
+use crate::cache_usage::{block_size, receipt_size, transaction_size};
+use crate::gas_price::gas_oracle::convert_u256_to_u64;
+
+/// Settings for the [ResponseCache].
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponseCacheConfig {
+    /// Max number of cached blocks, and of cached block receipt lists.
+    pub max_blocks: u32,
+    /// Max number of cached transaction receipts.
+    pub max_receipts: u32,
+    /// Max number of cached transactions.
+    pub max_transactions: u32,
+}
+
+impl Default for ResponseCacheConfig {
+    fn default() -> Self {
+        ResponseCacheConfig {
+            max_blocks: 1_000,
+            max_receipts: 10_000,
+            max_transactions: 10_000,
+        }
+    }
+}
+
+/// Cache for responses about blocks addressed by number or hash, and about the transactions
+/// of the blocks, which do not change once committed.
+///
+/// Blocks up to the last L2 height finalized on DA are served from the cache as they are.
+/// Hits in the non-final head region are revalidated by looking up the hash of the cached block
+/// in the block hash index, and the cached head region is dropped from the block that no longer
+/// matches, so blocks dropped by a rollback of the ledger are never served.
+pub struct ResponseCache<C: sov_modules_api::Context> {
+    /// (Block number, with transaction details) -> block
+    blocks: Mutex<LruMap<(u64, bool), RichBlock, ByLength>>,
+    /// Block number -> (block hash, receipts)
+    block_receipts: Mutex<LruMap<u64, (B256, Vec<TransactionReceipt>), ByLength>>,
+    /// Transaction hash -> receipt
+    receipts: Mutex<LruMap<B256, TransactionReceipt, ByLength>>,
+    /// Transaction hash -> transaction
+    transactions: Mutex<LruMap<B256, Transaction, ByLength>>,
+    provider: Evm<C>,
+    /// Ledger of the node, holding the last L2 height finalized on DA.
+    ledger_db: LedgerDB,
+}
+
+impl<C: sov_modules_api::Context> ResponseCache<C> {
+    pub fn new(config: ResponseCacheConfig, provider: Evm<C>, ledger_db: LedgerDB) -> Self {
+        Self {
+            blocks: Mutex::new(LruMap::new(ByLength::new(config.max_blocks))),
+            block_receipts: Mutex::new(LruMap::new(ByLength::new(config.max_blocks))),
+            receipts: Mutex::new(LruMap::new(ByLength::new(config.max_receipts))),
+            transactions: Mutex::new(LruMap::new(ByLength::new(config.max_transactions))),
+            provider,
+            ledger_db,
+        }
+    }
+
+    /// Approximate memory used by the cached responses, in bytes.
+    pub fn size_in_bytes(&self) -> u64 {
+        let blocks: u64 = self
+            .blocks
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, block)| block_size(block))
+            .sum();
+        let block_receipts: u64 = self
+            .block_receipts
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, (_, receipts))| {
+                size_of::<B256>() as u64 + receipts.iter().map(receipt_size).sum::<u64>()
+            })
+            .sum();
+        let receipts: u64 = self
+            .receipts
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, receipt)| receipt_size(receipt))
+            .sum();
+        let transactions: u64 = self
+            .transactions
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(_, transaction)| transaction_size(transaction))
+            .sum();
+        blocks + block_receipts + receipts + transactions
+    }
+
+    /// Gets block from cache or from provider by block number
+    pub fn get_block_by_number(
+        &self,
+        block_number: u64,
+        details: bool,
+        working_set: &mut WorkingSet<C>,
+    ) -> RpcResult<Option<RichBlock>> {
+        let cached = self
+            .blocks
+            .lock()
+            .unwrap()
+            .get(&(block_number, details))
+            .cloned();
+        if let Some(block) = cached {
+            if let Some(hash) = block.header.hash {
+                if self.is_canonical(block_number, hash, working_set) {
+                    return Ok(Some(block));
+                }
+            }
+        }
+
+        let block = self.provider.get_block_by_number(
+            Some(BlockNumberOrTag::Number(block_number)),
+            Some(details),
+            working_set,
+        )?;
+        if let Some(block) = &block {
+            self.blocks
+                .lock()
+                .unwrap()
+                .insert((block_number, details), block.clone());
+        }
+        Ok(block)
+    }
+
+    /// Gets block from cache or from provider by block hash
+    pub fn get_block_by_hash(
+        &self,
+        block_hash: B256,
+        details: bool,
+        working_set: &mut WorkingSet<C>,
+    ) -> RpcResult<Option<RichBlock>> {
+        match self
+            .provider
+            .get_block_number_by_block_hash(block_hash, working_set)
+        {
+            Some(block_number) => self.get_block_by_number(block_number, details, working_set),
+            None => Ok(None),
+        }
+    }
+
+    /// Gets the receipts of a block from cache or from provider by block number
+    pub fn get_block_receipts(
+        &self,
+        block_number: u64,
+        working_set: &mut WorkingSet<C>,
+    ) -> RpcResult<Option<Vec<TransactionReceipt>>> {
+        let cached = self
+            .block_receipts
+            .lock()
+            .unwrap()
+            .get(&block_number)
+            .cloned();
+        if let Some((hash, receipts)) = cached {
+            if self.is_canonical(block_number, hash, working_set) {
+                return Ok(Some(receipts));
+            }
+        }
+
+        let Some(hash) = self
+            .provider
+            .block_hash_from_number(block_number, working_set)
+        else {
+            return Ok(None);
+        };
+        let receipts = self
+            .provider
+            .get_block_receipts(BlockNumberOrTag::Number(block_number).into(), working_set)?;
+        if let Some(receipts) = &receipts {
+            self.block_receipts
+                .lock()
+                .unwrap()
+                .insert(block_number, (hash, receipts.clone()));
+        }
+        Ok(receipts)
+    }
+
+    /// Gets the receipts of a block from cache or from provider by block hash
+    pub fn get_block_receipts_by_hash(
+        &self,
+        block_hash: B256,
+        working_set: &mut WorkingSet<C>,
+    ) -> RpcResult<Option<Vec<TransactionReceipt>>> {
+        match self
+            .provider
+            .get_block_number_by_block_hash(block_hash, working_set)
+        {
+            Some(block_number) => self.get_block_receipts(block_number, working_set),
+            None => Ok(None),
+        }
+    }
+
+    /// Gets transaction receipt from cache or from provider
+    pub fn get_transaction_receipt(
+        &self,
+        tx_hash: B256,
+        working_set: &mut WorkingSet<C>,
+    ) -> RpcResult<Option<TransactionReceipt>> {
+        let cached = self.receipts.lock().unwrap().get(&tx_hash).cloned();
+        if let Some(receipt) = cached {
+            if let (Some(hash), Some(number)) = (receipt.block_hash, receipt.block_number) {
+                if self.is_canonical(convert_u256_to_u64(number), hash, working_set) {
+                    return Ok(Some(receipt));
+                }
+            }
+        }
+
+        let receipt = self
+            .provider
+            .get_transaction_receipt(tx_hash, working_set)?;
+        if let Some(receipt) = &receipt {
+            self.receipts
+                .lock()
+                .unwrap()
+                .insert(tx_hash, receipt.clone());
+        }
+        Ok(receipt)
+    }
+
+    /// Gets transaction from cache or from provider
+    pub fn get_transaction_by_hash(
+        &self,
+        tx_hash: B256,
+        working_set: &mut WorkingSet<C>,
+    ) -> RpcResult<Option<Transaction>> {
+        let cached = self.transactions.lock().unwrap().get(&tx_hash).cloned();
+        if let Some(tx) = cached {
+            if let (Some(hash), Some(number)) = (tx.block_hash, tx.block_number) {
+                if self.is_canonical(convert_u256_to_u64(number), hash, working_set) {
+                    return Ok(Some(tx));
+                }
+            }
+        }
+
+        let tx = self
+            .provider
+            .get_transaction_by_hash(tx_hash, working_set)?;
+        if let Some(tx) = &tx {
+            self.transactions
+                .lock()
+                .unwrap()
+                .insert(tx_hash, tx.clone());
+        }
+        Ok(tx)
+    }
+
+    /// Checks that the block with the given hash is still the block at the given number.
+    /// Blocks finalized on DA are not looked up, and the cached blocks from a block that
+    /// no longer matches are invalidated.
+    fn is_canonical(
+        &self,
+        block_number: u64,
+        block_hash: B256,
+        working_set: &mut WorkingSet<C>,
+    ) -> bool {
+        if self
+            .ledger_db
+            .get_last_finalized_l2_height()
+            .is_some_and(|finalized| block_number <= finalized)
+        {
+            return true;
+        }
+
+        let canonical = self
+            .provider
+            .get_block_number_by_block_hash(block_hash, working_set)
+            == Some(block_number);
+        if !canonical {
+            self.invalidate_from(block_number);
+        }
+        canonical
+    }
+
+    /// Drops the cached responses about the blocks from `block_number` on.
+    fn invalidate_from(&self, block_number: u64) {
+        let is_stale = |number: Option<reth_primitives::U256>| {
+            number.map_or(true, |number| convert_u256_to_u64(number) >= block_number)
+        };
+        remove_keys(&mut self.blocks.lock().unwrap(), |(number, _), _| {
+            *number >= block_number
+        });
+        remove_keys(&mut self.block_receipts.lock().unwrap(), |number, _| {
+            *number >= block_number
+        });
+        remove_keys(&mut self.receipts.lock().unwrap(), |_, receipt| {
+            is_stale(receipt.block_number)
+        });
+        remove_keys(&mut self.transactions.lock().unwrap(), |_, tx| {
+            is_stale(tx.block_number)
+        });
+    }
+}
+
+/// Removes the entries matching `predicate` from the cache.
+fn remove_keys<K, V>(cache: &mut LruMap<K, V, ByLength>, predicate: impl Fn(&K, &V) -> bool)
+where
+    K: std::hash::Hash + PartialEq + Clone,
+{
+    let keys: Vec<K> = cache
+        .iter()
+        .filter(|(key, value)| predicate(key, value))
+        .map(|(key, _)| key.clone())
+        .collect();
+    for key in keys {
+        cache.remove(&key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removes_matching_keys() {
+        let mut cache = LruMap::new(ByLength::new(10));
+        for number in 0..5u64 {
+            cache.insert((number, false), number * 10);
+            cache.insert((number, true), number * 10 + 1);
+        }
+
+        remove_keys(&mut cache, |(number, _), _| *number >= 3);
+
+        assert_eq!(cache.len(), 6);
+        assert_eq!(cache.get(&(2, true)).copied(), Some(21));
+        assert!(cache.get(&(3, false)).is_none());
+        assert!(cache.get(&(4, true)).is_none());
+    }
+}
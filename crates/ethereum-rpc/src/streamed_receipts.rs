@@ -0,0 +1,271 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use citrea_evm::{Filter, FilterBlockOption, LogResponse};
+use reth_primitives::B256;
+use reth_rpc_types::TransactionReceipt;
+use sequencer_client::{BlockReceipts, SequencerClient};
+use sov_modules_api::WorkingSet;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, warn};
+
+/// Maximum number of blocks the sequencer returns receipts of in one request.
+pub(crate) const MAX_STREAMED_BLOCKS_PER_REQUEST: u64 = 100;
+
+/// Maximum number of blocks ahead of the executed head whose receipts are kept.
+const MAX_STREAMED_BLOCKS_AHEAD: u64 = 1000;
+
+/// Interval between two requests of the receipts of the new blocks of the sequencer.
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Receipts computed by the sequencer for the soft batches the full node has not executed yet.
+///
+/// They let the full node answer receipt and log queries as soon as the sequencer publishes
+/// a soft batch, while it executes the soft batch on its own. Once the full node executes a
+/// block, its own receipts are served and the streamed ones are dropped, after checking that
+/// the sequencer streamed the block the full node executed.
+pub(crate) struct StreamedReceipts<C: sov_modules_api::Context> {
+    /// Streamed blocks by number, all above the executed head.
+    blocks: Mutex<BTreeMap<u64, BlockReceipts>>,
+    storage: C::Storage,
+}
+
+impl<C: sov_modules_api::Context> StreamedReceipts<C> {
+    pub(crate) fn new(storage: C::Storage) -> Self {
+        Self {
+            blocks: Mutex::new(BTreeMap::new()),
+            storage,
+        }
+    }
+
+    /// Streams the receipts of the new blocks of the sequencer, and drops the streamed blocks
+    /// as soft batches are executed.
+    pub(crate) async fn run(
+        self: Arc<Self>,
+        sequencer_client: SequencerClient,
+        mut soft_batches: broadcast::Receiver<u64>,
+    ) {
+        let mut interval = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            tokio::select! {
+                soft_batch = soft_batches.recv() => match soft_batch {
+                    Ok(height) => self.drop_executed(height),
+                    Err(RecvError::Lagged(_)) => self.drop_executed(self.executed_head()),
+                    Err(RecvError::Closed) => return,
+                },
+                _ = interval.tick() => {
+                    if let Err(e) = self.stream(&sequencer_client).await {
+                        debug!("Failed to stream receipts from the sequencer: {:?}", e);
+                    }
+                }
+            }
+        }
+    }
+
+    fn executed_head(&self) -> u64 {
+        let mut working_set = WorkingSet::<C>::new(self.storage.clone());
+        citrea_evm::Evm::<C>::default()
+            .block_number_for_id(&reth_primitives::BlockNumberOrTag::Latest, &mut working_set)
+            .unwrap_or_default()
+    }
+
+    async fn stream(&self, sequencer_client: &SequencerClient) -> anyhow::Result<()> {
+        let executed_head = self.executed_head();
+        let sequencer_head = sequencer_client.block_number().await?;
+
+        let from = {
+            let blocks = self.blocks.lock().unwrap();
+            blocks
+                .keys()
+                .next_back()
+                .map_or(executed_head, |last| executed_head.max(*last))
+                + 1
+        };
+        let to = sequencer_head
+            .min(executed_head + MAX_STREAMED_BLOCKS_AHEAD)
+            .min(from + MAX_STREAMED_BLOCKS_PER_REQUEST - 1);
+        if from > to {
+            return Ok(());
+        }
+
+        let streamed = sequencer_client.get_block_receipts_range(from, to).await?;
+        let mut blocks = self.blocks.lock().unwrap();
+        for block in streamed {
+            if block.number > executed_head {
+                blocks.insert(block.number, block);
+            }
+        }
+        Ok(())
+    }
+
+    /// Drops the streamed blocks up to `height`, once executed, warning if the sequencer
+    /// streamed another block than the executed one.
+    fn drop_executed(&self, height: u64) {
+        let executed = {
+            let mut blocks = self.blocks.lock().unwrap();
+            let pending = blocks.split_off(&(height + 1));
+            std::mem::replace(&mut *blocks, pending)
+        };
+        if executed.is_empty() {
+            return;
+        }
+
+        let evm = citrea_evm::Evm::<C>::default();
+        let mut working_set = WorkingSet::<C>::new(self.storage.clone());
+        for (number, block) in executed {
+            match evm.block_hash_from_number(number, &mut working_set) {
+                Some(hash) if hash == block.hash => {}
+                Some(hash) => warn!(
+                    "Receipts streamed by the sequencer for block {} were for block {}, executed block {}",
+                    number, block.hash, hash
+                ),
+                None => {}
+            }
+        }
+    }
+
+    /// Returns the streamed receipt of a transaction.
+    pub(crate) fn transaction_receipt(&self, hash: B256) -> Option<TransactionReceipt> {
+        self.blocks
+            .lock()
+            .unwrap()
+            .values()
+            .flat_map(|block| &block.receipts)
+            .find(|receipt| receipt.transaction_hash == Some(hash))
+            .cloned()
+    }
+
+    /// Returns the streamed receipts of a block.
+    pub(crate) fn block_receipts(&self, number: u64) -> Option<Vec<TransactionReceipt>> {
+        self.blocks
+            .lock()
+            .unwrap()
+            .get(&number)
+            .map(|block| block.receipts.clone())
+    }
+
+    /// Returns the streamed receipts of a block by its hash.
+    pub(crate) fn block_receipts_by_hash(&self, hash: B256) -> Option<Vec<TransactionReceipt>> {
+        self.blocks
+            .lock()
+            .unwrap()
+            .values()
+            .find(|block| block.hash == hash)
+            .map(|block| block.receipts.clone())
+    }
+
+    /// Returns the streamed logs matching the filter.
+    pub(crate) fn logs(&self, filter: &Filter) -> Vec<LogResponse> {
+        matching_logs(self.blocks.lock().unwrap().values(), filter)
+    }
+}
+
+/// Returns the logs of the blocks matching the filter, in order.
+fn matching_logs<'a>(
+    blocks: impl Iterator<Item = &'a BlockReceipts>,
+    filter: &Filter,
+) -> Vec<LogResponse> {
+    let mut logs = vec![];
+    for block in blocks {
+        let block_matches = match filter.block_option {
+            FilterBlockOption::AtBlockHash(hash) => hash == block.hash,
+            FilterBlockOption::Range { .. } => filter.filter_block_range(&block.number),
+        };
+        if !block_matches {
+            continue;
+        }
+
+        for log in block.receipts.iter().flat_map(|receipt| &receipt.logs) {
+            let primitive_log = reth_primitives::Log {
+                address: log.address,
+                topics: log.topics.clone(),
+                data: log.data.clone(),
+            };
+            if filter.filter_address(&primitive_log, &filter.address)
+                && filter.filter_topics(&primitive_log, &filter.topics)
+            {
+                logs.push(LogResponse {
+                    address: log.address,
+                    topics: log.topics.clone(),
+                    data: log.data.clone(),
+                    block_hash: log.block_hash,
+                    block_number: log.block_number,
+                    transaction_hash: log.transaction_hash,
+                    transaction_index: log.transaction_index,
+                    log_index: log.log_index,
+                    removed: false,
+                });
+            }
+        }
+    }
+    logs
+}
+
+#[cfg(test)]
+mod tests {
+    use reth_primitives::{Address, BlockNumberOrTag, Bytes, U256};
+    use reth_rpc_types::Log;
+
+    use super::*;
+
+    fn block(number: u64, addresses: &[Address]) -> BlockReceipts {
+        let hash = B256::with_last_byte(number as u8);
+        let logs = addresses
+            .iter()
+            .enumerate()
+            .map(|(index, address)| Log {
+                address: *address,
+                topics: vec![B256::with_last_byte(index as u8)],
+                data: Bytes::default(),
+                block_hash: Some(hash),
+                block_number: Some(U256::from(number)),
+                transaction_hash: Some(B256::repeat_byte(number as u8)),
+                transaction_index: Some(U256::ZERO),
+                log_index: Some(U256::from(index)),
+                removed: false,
+            })
+            .collect();
+        BlockReceipts {
+            number,
+            hash,
+            receipts: vec![TransactionReceipt {
+                logs,
+                ..Default::default()
+            }],
+        }
+    }
+
+    #[test]
+    fn filters_streamed_logs() {
+        let first = Address::with_last_byte(1);
+        let second = Address::with_last_byte(2);
+        let blocks = [block(5, &[first, second]), block(6, &[second])];
+
+        let filter = Filter {
+            block_option: FilterBlockOption::Range {
+                from_block: Some(BlockNumberOrTag::Number(6)),
+                to_block: Some(BlockNumberOrTag::Latest),
+            },
+            ..Default::default()
+        };
+        let logs = matching_logs(blocks.iter(), &filter);
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].block_number, Some(U256::from(6)));
+
+        let filter = Filter {
+            address: first.into(),
+            ..Default::default()
+        };
+        let logs = matching_logs(blocks.iter(), &filter);
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].address, first);
+
+        let filter = Filter {
+            block_option: FilterBlockOption::AtBlockHash(blocks[0].hash),
+            ..Default::default()
+        };
+        assert_eq!(matching_logs(blocks.iter(), &filter).len(), 2);
+    }
+}
@@ -0,0 +1,245 @@
+use citrea_evm::{Evm, Filter, FilterBlockOption};
+use jsonrpsee::core::SubscriptionResult;
+use jsonrpsee::types::error::INVALID_PARAMS_CODE;
+use jsonrpsee::types::{ErrorObjectOwned, Params};
+use jsonrpsee::{PendingSubscriptionSink, SubscriptionMessage, SubscriptionSink};
+use reth_primitives::{Address, BlockNumberOrTag, B256};
+use sequencer_client::ReplacedTransaction;
+use serde::{Deserialize, Serialize};
+use sov_modules_api::WorkingSet;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+/// Filter of a `replacedTransactions` subscription: a replacement is notified if the replaced
+/// transaction was sent by one of `from` or is one of `hashes`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ReplacementFilter {
+    #[serde(default)]
+    from: Vec<Address>,
+    #[serde(default)]
+    hashes: Vec<B256>,
+}
+
+impl ReplacementFilter {
+    fn matches(&self, replacement: &ReplacedTransaction) -> bool {
+        self.from.contains(&replacement.from) || self.hashes.contains(&replacement.hash)
+    }
+}
+
+/// Kinds of `eth_subscribe` subscriptions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum SubscriptionKind {
+    /// Headers of the new blocks, produced by the sequencer or synced by full nodes.
+    NewHeads,
+    /// Logs of the new blocks matching the address and topics of a filter.
+    /// The block range of the filter is ignored, as geth does.
+    Logs(Box<Filter>),
+    /// Transactions replaced in the mempool, so that wallets stop showing them as pending.
+    /// Only the replacements of the senders or transactions of the filter are notified.
+    ReplacedTransactions(ReplacementFilter),
+}
+
+impl SubscriptionKind {
+    /// Parses the `eth_subscribe` parameters: the kind, then the filter of `logs` and
+    /// `replacedTransactions` subscriptions.
+    pub(crate) fn parse(params: Params) -> Result<Self, ErrorObjectOwned> {
+        let mut params = params.sequence();
+        let kind: String = params.next()?;
+        match kind.as_str() {
+            "newHeads" => Ok(Self::NewHeads),
+            "logs" => {
+                let filter: Option<Filter> = params.optional_next()?;
+                Ok(Self::Logs(Box::new(filter.unwrap_or_default())))
+            }
+            "replacedTransactions" => {
+                let filter: ReplacementFilter = params.next()?;
+                if filter.from.is_empty() && filter.hashes.is_empty() {
+                    return Err(ErrorObjectOwned::owned(
+                        INVALID_PARAMS_CODE,
+                        "replacedTransactions subscriptions must filter by sender or hash",
+                        None::<()>,
+                    ));
+                }
+                Ok(Self::ReplacedTransactions(filter))
+            }
+            _ => Err(ErrorObjectOwned::owned(
+                INVALID_PARAMS_CODE,
+                format!("unsupported subscription: {}", kind),
+                None::<()>,
+            )),
+        }
+    }
+}
+
+/// Sends the header of every block committed from now on, until the subscriber unsubscribes.
+pub(crate) async fn new_heads<C: sov_modules_api::Context>(
+    pending: PendingSubscriptionSink,
+    soft_batches: broadcast::Receiver<u64>,
+    storage: C::Storage,
+) -> SubscriptionResult {
+    let evm = Evm::<C>::default();
+    notify_blocks::<C, _, _>(pending, soft_batches, storage, |number, working_set| {
+        let block = evm.get_block_by_number(
+            Some(BlockNumberOrTag::Number(number)),
+            Some(false),
+            working_set,
+        )?;
+        Ok(block.map(|block| vec![block.header]))
+    })
+    .await
+}
+
+/// Sends every log matching `filter` emitted by the blocks committed from now on, one log per
+/// notification, until the subscriber unsubscribes.
+pub(crate) async fn logs<C: sov_modules_api::Context>(
+    pending: PendingSubscriptionSink,
+    soft_batches: broadcast::Receiver<u64>,
+    storage: C::Storage,
+    filter: Filter,
+) -> SubscriptionResult {
+    let evm = Evm::<C>::default();
+    notify_blocks::<C, _, _>(pending, soft_batches, storage, |number, working_set| {
+        let logs = evm.eth_get_logs(
+            Filter {
+                block_option: FilterBlockOption::Range {
+                    from_block: Some(BlockNumberOrTag::Number(number)),
+                    to_block: Some(BlockNumberOrTag::Number(number)),
+                },
+                address: filter.address.clone(),
+                topics: filter.topics.clone(),
+            },
+            working_set,
+        )?;
+        Ok(Some(logs))
+    })
+    .await
+}
+
+/// Sends every transaction matching `filter` replaced in the mempool from now on, until the
+/// subscriber unsubscribes. Replacements missed by a lagging subscriber are skipped.
+pub(crate) async fn replaced_transactions(
+    pending: PendingSubscriptionSink,
+    mut replacements: broadcast::Receiver<ReplacedTransaction>,
+    filter: ReplacementFilter,
+) -> SubscriptionResult {
+    let sink = pending.accept().await?;
+
+    loop {
+        let replacement = tokio::select! {
+            _ = sink.closed() => return Ok(()),
+            replacement = replacements.recv() => match replacement {
+                Ok(replacement) => replacement,
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return Ok(()),
+            },
+        };
+        if !filter.matches(&replacement) {
+            continue;
+        }
+        if !send(&sink, &replacement).await? {
+            return Ok(());
+        }
+    }
+}
+
+/// Sends the notifications of every block committed from now on, until the subscriber
+/// unsubscribes. `notifications` returns the notifications of a block, None if the block isn't
+/// available.
+///
+/// `soft_batches` receives the numbers of the committed soft batches, which are the numbers of
+/// their blocks. Blocks missed by a lagging receiver are notified with the next block,
+/// so that subscribers see every block in order.
+async fn notify_blocks<C, T, F>(
+    pending: PendingSubscriptionSink,
+    mut soft_batches: broadcast::Receiver<u64>,
+    storage: C::Storage,
+    mut notifications: F,
+) -> SubscriptionResult
+where
+    C: sov_modules_api::Context,
+    T: Serialize,
+    F: FnMut(u64, &mut WorkingSet<C>) -> Result<Option<Vec<T>>, ErrorObjectOwned>,
+{
+    let sink = pending.accept().await?;
+    let mut last_sent = None;
+
+    loop {
+        let number = tokio::select! {
+            _ = sink.closed() => return Ok(()),
+            number = soft_batches.recv() => match number {
+                Ok(number) => number,
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return Ok(()),
+            },
+        };
+
+        let from = last_sent.map_or(number, |last: u64| last + 1);
+        for number in from..=number {
+            let items = {
+                let mut working_set = WorkingSet::<C>::new(storage.clone());
+                notifications(number, &mut working_set)?
+            };
+            let Some(items) = items else {
+                continue;
+            };
+            for item in &items {
+                if !send(&sink, item).await? {
+                    return Ok(());
+                }
+            }
+            last_sent = Some(number);
+        }
+    }
+}
+
+/// Sends a message to the subscriber, returns false if the subscriber is gone.
+async fn send<T: Serialize>(sink: &SubscriptionSink, item: &T) -> Result<bool, serde_json::Error> {
+    let message = SubscriptionMessage::from_json(item)?;
+    Ok(sink.send(message).await.is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replaced_transactions_are_filtered_by_sender_or_hash() {
+        let sender = Address::repeat_byte(1);
+        let hash = B256::repeat_byte(2);
+        let replacement = ReplacedTransaction {
+            hash,
+            replaced_by: B256::repeat_byte(3),
+            from: sender,
+        };
+
+        let params = format!(r#"["replacedTransactions", {{"from": ["{sender}"]}}]"#);
+        let Ok(SubscriptionKind::ReplacedTransactions(by_sender)) =
+            SubscriptionKind::parse(Params::new(Some(&params)))
+        else {
+            panic!("replacedTransactions filtered by sender must be accepted");
+        };
+        assert!(by_sender.matches(&replacement));
+
+        let params = format!(r#"["replacedTransactions", {{"hashes": ["{hash}"]}}]"#);
+        let Ok(SubscriptionKind::ReplacedTransactions(by_hash)) =
+            SubscriptionKind::parse(Params::new(Some(&params)))
+        else {
+            panic!("replacedTransactions filtered by hash must be accepted");
+        };
+        assert!(by_hash.matches(&replacement));
+        assert!(!by_hash.matches(&ReplacedTransaction {
+            hash: B256::repeat_byte(4),
+            from: Address::repeat_byte(5),
+            ..replacement
+        }));
+
+        // unfiltered subscriptions would leak the replacements of every sender
+        for params in [
+            r#"["replacedTransactions"]"#,
+            r#"["replacedTransactions", {}]"#,
+        ] {
+            assert!(SubscriptionKind::parse(Params::new(Some(params))).is_err());
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use reth_primitives::U64;
+use serde::Serialize;
+use sov_rollup_interface::rpc::SyncStatus;
+
+/// Response of `eth_syncing`: `false` when the node is synced, its progress otherwise.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(untagged)]
+pub(crate) enum SyncingResponse {
+    NotSyncing(bool),
+    Syncing(SyncInfo),
+}
+
+/// Sync progress of the node, with the DA heights it syncs against along the usual fields.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SyncInfo {
+    starting_block: U64,
+    current_block: U64,
+    highest_block: U64,
+    current_l1_block: U64,
+    highest_l1_block: U64,
+}
+
+impl From<Option<SyncStatus>> for SyncingResponse {
+    fn from(sync_status: Option<SyncStatus>) -> Self {
+        match sync_status {
+            Some(sync_status) if sync_status.is_syncing() => Self::Syncing(SyncInfo {
+                starting_block: U64::from(sync_status.starting_l2_height),
+                current_block: U64::from(sync_status.current_l2_height),
+                highest_block: U64::from(sync_status.highest_l2_height),
+                current_l1_block: U64::from(sync_status.current_l1_height),
+                highest_l1_block: U64::from(sync_status.highest_l1_height),
+            }),
+            _ => Self::NotSyncing(false),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn reports_sync_progress() {
+        let mut sync_status = SyncStatus {
+            starting_l2_height: 10,
+            current_l2_height: 20,
+            highest_l2_height: 30,
+            current_l1_height: 5,
+            highest_l1_height: 8,
+        };
+        assert_eq!(
+            serde_json::to_value(SyncingResponse::from(Some(sync_status))).unwrap(),
+            json!({
+                "startingBlock": "0xa",
+                "currentBlock": "0x14",
+                "highestBlock": "0x1e",
+                "currentL1Block": "0x5",
+                "highestL1Block": "0x8",
+            })
+        );
+
+        sync_status.current_l2_height = 30;
+        assert_eq!(
+            serde_json::to_value(SyncingResponse::from(Some(sync_status))).unwrap(),
+            json!(false)
+        );
+        assert_eq!(
+            serde_json::to_value(SyncingResponse::from(None)).unwrap(),
+            json!(false)
+        );
+    }
+}
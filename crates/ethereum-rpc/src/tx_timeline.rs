@@ -0,0 +1,160 @@
+use citrea_evm::Evm;
+use jsonrpsee::types::ErrorObjectOwned;
+use once_cell::sync::Lazy;
+use prometheus::{exponential_buckets, register_histogram, Histogram};
+use reth_primitives::{BlockNumberOrTag, B256, U64};
+use reth_rpc_types::BlockTransactions;
+use serde::{Deserialize, Serialize};
+use sov_db::ledger_db::LedgerDB;
+use sov_db::schema::types::BatchNumber;
+use sov_modules_api::utils::to_jsonrpsee_error_object;
+use sov_modules_api::WorkingSet;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::warn;
+
+use crate::ETH_RPC_ERROR;
+
+static TRANSACTION_SOFT_CONFIRMATION_LATENCY_SECONDS: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        // metric name
+        "transaction_soft_confirmation_latency_seconds",
+        // metric description
+        "Time from the submission of transactions to the node to their soft confirmation, in seconds",
+        exponential_buckets(/*start=*/ 0.05, /*factor=*/ 2.0, /*count=*/ 16).unwrap(),
+    )
+    .unwrap()
+});
+
+/// When a transaction went through each stage of its lifecycle, as seen by the node.
+/// Times are Unix timestamps in milliseconds of the clock of the node.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionTimeline {
+    /// Hash of the transaction.
+    pub transaction_hash: B256,
+    /// Number of the block of the transaction, None until it is soft confirmed.
+    pub block_number: Option<U64>,
+    /// Height of the DA slot of the soft confirmation of the transaction.
+    pub l1_height: Option<U64>,
+    /// When the transaction was submitted. Only known by the node it was submitted to.
+    pub submitted_at: Option<U64>,
+    /// When the node committed the soft confirmation of the transaction.
+    pub soft_confirmed_at: Option<U64>,
+    /// When the node saw the soft confirmation finalized by a verified sequencer commitment.
+    pub finalized_at: Option<U64>,
+    /// When the node saw the soft confirmation proven.
+    pub proven_at: Option<U64>,
+}
+
+/// Returns the timeline of a transaction submitted to or included in the chain of the node.
+pub(crate) fn transaction_timeline<C: sov_modules_api::Context>(
+    ledger_db: &LedgerDB,
+    storage: C::Storage,
+    hash: B256,
+) -> Result<Option<TransactionTimeline>, ErrorObjectOwned> {
+    let mut working_set = WorkingSet::<C>::new(storage);
+    let block_number = Evm::<C>::default()
+        .get_transaction_by_hash(hash, &mut working_set)?
+        .and_then(|tx| tx.block_number);
+    let submitted_at = ledger_db
+        .get_tx_submission(hash.0)
+        .map_err(|e| to_jsonrpsee_error_object(e, ETH_RPC_ERROR))?;
+    if block_number.is_none() && submitted_at.is_none() {
+        return Ok(None);
+    }
+
+    let soft_batch = match block_number {
+        Some(number) => ledger_db
+            .get_soft_batch_timeline(BatchNumber(number.to()))
+            .map_err(|e| to_jsonrpsee_error_object(e, ETH_RPC_ERROR))?,
+        None => None,
+    };
+    Ok(Some(TransactionTimeline {
+        transaction_hash: hash,
+        block_number: block_number.map(|number| U64::from(number.to::<u64>())),
+        l1_height: soft_batch.map(|soft_batch| U64::from(soft_batch.da_slot_height)),
+        submitted_at: submitted_at.map(U64::from),
+        soft_confirmed_at: soft_batch.and_then(|timeline| timeline.committed_at.map(U64::from)),
+        finalized_at: soft_batch.and_then(|timeline| timeline.finalized_at.map(U64::from)),
+        proven_at: soft_batch.and_then(|timeline| timeline.proven_at.map(U64::from)),
+    }))
+}
+
+/// Observes the latency from submission to soft confirmation of the transactions submitted to
+/// the node, as their blocks are committed.
+pub(crate) struct SubmissionLatencyTracker<C: sov_modules_api::Context> {
+    ledger_db: LedgerDB,
+    storage: C::Storage,
+}
+
+impl<C: sov_modules_api::Context> SubmissionLatencyTracker<C> {
+    pub(crate) fn new(ledger_db: LedgerDB, storage: C::Storage) -> Self {
+        Self { ledger_db, storage }
+    }
+
+    /// Observes the transactions of the blocks committed after the node started.
+    pub(crate) async fn run(self, mut soft_batches: broadcast::Receiver<u64>) {
+        let mut observed = self.latest_block().unwrap_or(0);
+        loop {
+            match soft_batches.recv().await {
+                Ok(_) | Err(RecvError::Lagged(_)) => {
+                    let Some(latest) = self.latest_block() else {
+                        continue;
+                    };
+                    for number in observed + 1..=latest {
+                        self.observe_block(number);
+                    }
+                    observed = observed.max(latest);
+                }
+                Err(RecvError::Closed) => return,
+            }
+        }
+    }
+
+    fn latest_block(&self) -> Option<u64> {
+        let mut working_set = WorkingSet::<C>::new(self.storage.clone());
+        Evm::<C>::default().block_number_for_id(&BlockNumberOrTag::Latest, &mut working_set)
+    }
+
+    fn observe_block(&self, number: u64) {
+        let committed_at = match self.ledger_db.get_soft_batch_timeline(BatchNumber(number)) {
+            Ok(Some(timeline)) => timeline.committed_at,
+            Ok(None) => None,
+            Err(e) => {
+                warn!(
+                    "Failed to read the timeline of soft batch {}: {:?}",
+                    number, e
+                );
+                return;
+            }
+        };
+        let Some(committed_at) = committed_at else {
+            return;
+        };
+
+        let mut working_set = WorkingSet::<C>::new(self.storage.clone());
+        let hashes = match Evm::<C>::default().get_block_by_number(
+            Some(BlockNumberOrTag::Number(number)),
+            Some(false),
+            &mut working_set,
+        ) {
+            Ok(Some(block)) => match block.inner.transactions {
+                BlockTransactions::Hashes(hashes) => hashes,
+                _ => vec![],
+            },
+            Ok(_) => return,
+            Err(e) => {
+                warn!("Failed to read block {}: {:?}", number, e);
+                return;
+            }
+        };
+
+        for hash in hashes {
+            if let Ok(Some(submitted_at)) = self.ledger_db.get_tx_submission(hash.0) {
+                TRANSACTION_SOFT_CONFIRMATION_LATENCY_SECONDS
+                    .observe(committed_at.saturating_sub(submitted_at) as f64 / 1000.0);
+            }
+        }
+    }
+}
@@ -0,0 +1,189 @@
+//! Benchmarks `eth_getLogs` over a range of mostly empty blocks.
+//!
+//! The filter of the contract emitting the logs only loads the blocks whose logs bloom matches,
+//! skipping whole sections of the logs bloom index, while the filter matching any log loads
+//! every block of the range like a linear scan.
+
+extern crate criterion;
+
+use citrea_evm::smart_contracts::{LogsContract, TestContract};
+use citrea_evm::{
+    AccountData, CallMessage, DevSigner, Evm, EvmConfig, Filter, FilterBlockOption, FilterSet,
+    RlpEvmTransaction, SpecId,
+};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use reth_primitives::{
+    Address, BlockNumberOrTag, Bytes, Transaction, TransactionKind, TxEip1559, U256,
+};
+use revm::primitives::KECCAK_EMPTY;
+use secp256k1::{PublicKey, SecretKey};
+use sov_modules_api::default_context::DefaultContext;
+use sov_modules_api::utils::generate_address;
+use sov_modules_api::{Context, Module, WorkingSet};
+use sov_prover_storage_manager::{new_orphan_storage, SnapshotManager};
+use sov_state::{DefaultStorageSpec, ProverStorage, Storage};
+
+type C = DefaultContext;
+type S = ProverStorage<DefaultStorageSpec, SnapshotManager>;
+
+/// Number of blocks of the benchmarked range.
+const BLOCKS: u64 = 10_000;
+
+/// The only block with logs.
+const LOGS_BLOCK: u64 = BLOCKS / 2;
+
+fn sign_transaction(
+    signer: &DevSigner,
+    address: Address,
+    to: TransactionKind,
+    input: Vec<u8>,
+    nonce: u64,
+) -> RlpEvmTransaction {
+    let transaction = Transaction::Eip1559(TxEip1559 {
+        to,
+        input: Bytes::from(input),
+        nonce,
+        chain_id: 1,
+        gas_limit: 1_000_000,
+        max_fee_per_gas: 100_000_000_000,
+        ..Default::default()
+    });
+    let signed = signer.sign_transaction(transaction, address).unwrap();
+    RlpEvmTransaction {
+        rlp: signed.envelope_encoded().to_vec(),
+    }
+}
+
+fn commit(working_set: WorkingSet<C>, storage: &S) -> [u8; 32] {
+    let mut checkpoint = working_set.checkpoint();
+    let (cache_log, witness) = checkpoint.freeze();
+    let (root, authenticated_node_batch) = storage
+        .compute_state_update(cache_log, &witness)
+        .expect("jellyfish merkle tree update must succeed");
+    let working_set = checkpoint.to_revertable();
+    let accessory_log = working_set.checkpoint().freeze_non_provable();
+    storage.commit(&authenticated_node_batch, &accessory_log);
+    root.0
+}
+
+/// Creates a chain of [`BLOCKS`] blocks where only [`LOGS_BLOCK`] has logs, emitted by the
+/// returned contract.
+fn setup(path: &std::path::Path) -> (Evm<C>, S, Address) {
+    let secret_key = SecretKey::from_slice(&[1u8; 32]).unwrap();
+    let address = reth_primitives::public_key_to_address(PublicKey::from_secret_key(
+        secp256k1::SECP256K1,
+        &secret_key,
+    ));
+    let signer = DevSigner::new(vec![secret_key]);
+    let contract_address = address.create(0);
+
+    let config = EvmConfig {
+        data: vec![AccountData {
+            address,
+            balance: U256::from(10u128.pow(20)),
+            code_hash: KECCAK_EMPTY,
+            code: Default::default(),
+            nonce: 0,
+            storage: Default::default(),
+        }],
+        spec: vec![(0, SpecId::SHANGHAI)].into_iter().collect(),
+        ..Default::default()
+    };
+
+    let storage = new_orphan_storage(path).unwrap();
+    let evm = Evm::<C>::default();
+    let mut working_set = WorkingSet::new(storage.clone());
+    evm.genesis(&config, &mut working_set).unwrap();
+    let mut root = commit(working_set, &storage);
+
+    let context = C::new(
+        generate_address::<C>("sender"),
+        generate_address::<C>("sequencer"),
+        1,
+    );
+    for number in 1..=BLOCKS {
+        let mut working_set = WorkingSet::new(storage.clone());
+        evm.finalize_hook(&root.into(), &mut working_set.accessory_state());
+        evm.begin_soft_confirmation_hook([5u8; 32], 1, [42u8; 32], &root, 1, 0, &mut working_set);
+        if number == LOGS_BLOCK {
+            let contract = LogsContract::default();
+            let txs = vec![
+                sign_transaction(
+                    &signer,
+                    address,
+                    TransactionKind::Create,
+                    contract.byte_code().to_vec(),
+                    0,
+                ),
+                sign_transaction(
+                    &signer,
+                    address,
+                    TransactionKind::Call(contract_address),
+                    contract.publish_event("hello".to_string()).to_vec(),
+                    1,
+                ),
+            ];
+            evm.call(
+                CallMessage {
+                    txs,
+                    encrypted: None,
+                },
+                &context,
+                &mut working_set,
+            )
+            .unwrap();
+        }
+        evm.end_soft_confirmation_hook(&mut working_set);
+        root = commit(working_set, &storage);
+    }
+    let mut working_set = WorkingSet::new(storage.clone());
+    evm.finalize_hook(&root.into(), &mut working_set.accessory_state());
+    commit(working_set, &storage);
+
+    (evm, storage, contract_address)
+}
+
+fn range_filter(address: FilterSet<Address>) -> Filter {
+    Filter {
+        block_option: FilterBlockOption::Range {
+            from_block: Some(BlockNumberOrTag::Number(1)),
+            to_block: Some(BlockNumberOrTag::Number(BLOCKS)),
+        },
+        address,
+        topics: Default::default(),
+    }
+}
+
+fn get_logs_benchmark(c: &mut Criterion) {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let (evm, storage, contract_address) = setup(tmpdir.path());
+
+    let mut group = c.benchmark_group("eth_getLogs");
+    group.bench_function("logs bloom index", |b| {
+        b.iter(|| {
+            let mut working_set = WorkingSet::new(storage.clone());
+            let logs = evm
+                .eth_get_logs(
+                    range_filter(FilterSet::from(contract_address)),
+                    &mut working_set,
+                )
+                .unwrap();
+            assert_eq!(logs.len(), 2);
+            black_box(logs)
+        })
+    });
+    group.bench_function("linear scan", |b| {
+        b.iter(|| {
+            let mut working_set = WorkingSet::new(storage.clone());
+            let logs = evm
+                .eth_get_logs(range_filter(FilterSet::default()), &mut working_set)
+                .unwrap();
+            assert_eq!(logs.len(), 2);
+            black_box(logs)
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, get_logs_benchmark);
+criterion_main!(benches);
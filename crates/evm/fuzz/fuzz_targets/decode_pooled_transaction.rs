@@ -0,0 +1,27 @@
+#![no_main]
+#[macro_use]
+extern crate libfuzzer_sys;
+
+use reth_primitives::{IntoRecoveredTransaction, PooledTransactionsElement};
+use reth_transaction_pool::{EthPooledTransaction, PoolTransaction};
+
+// Raw transactions received by `eth_sendRawTransaction` go through these steps
+// before being validated by the mempool of the sequencer.
+fuzz_target!(|input: (&[u8], u64)| {
+    let (mut data, base_fee) = input;
+    let Ok(tx) = PooledTransactionsElement::decode_enveloped(&mut data) else {
+        return;
+    };
+    let Ok(recovered) = tx.try_into_ecrecovered() else {
+        return;
+    };
+
+    let pool_tx = EthPooledTransaction::from_recovered_pooled_transaction(recovered);
+    pool_tx.cost();
+    pool_tx.size();
+    pool_tx.effective_tip_per_gas(base_fee);
+    pool_tx.priority_fee_or_price();
+
+    let tx = pool_tx.to_recovered_transaction();
+    assert_eq!(&tx.hash(), pool_tx.hash());
+});
@@ -0,0 +1,22 @@
+#![no_main]
+#[macro_use]
+extern crate libfuzzer_sys;
+
+use citrea_evm::RlpEvmTransaction;
+use reth_primitives::TransactionSignedEcRecovered;
+
+// Transactions of soft batches are decoded by the EVM module before execution,
+// a decoding panic would halt the sequencer and every full node.
+fuzz_target!(|data: &[u8]| {
+    let rlp = RlpEvmTransaction { rlp: data.to_vec() };
+    if let Ok(tx) = TransactionSignedEcRecovered::try_from(rlp) {
+        // a decoded transaction decodes to the same transaction once encoded again
+        let rlp = RlpEvmTransaction {
+            rlp: tx.envelope_encoded().to_vec(),
+        };
+        let decoded = TransactionSignedEcRecovered::try_from(rlp)
+            .expect("Re-encoded transaction must decode");
+        assert_eq!(decoded.transaction, tx.transaction);
+        assert_eq!(decoded.signer(), tx.signer());
+    }
+});
@@ -0,0 +1,123 @@
+#![no_main]
+
+use std::str::FromStr;
+
+use citrea_evm::{AccountData, CallMessage, DevSigner, Evm, EvmConfig, RlpEvmTransaction};
+use libfuzzer_sys::arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use reth_primitives::constants::ETHEREUM_BLOCK_GAS_LIMIT;
+use reth_primitives::{
+    Address, Bytes, Transaction, TransactionKind, TxEip1559, KECCAK_EMPTY, U256,
+};
+use secp256k1::SecretKey;
+use sov_modules_api::default_context::DefaultContext;
+use sov_modules_api::{Context, Module, WorkingSet};
+use sov_prover_storage_manager::new_orphan_storage;
+use sov_state::Storage;
+
+type C = DefaultContext;
+
+/// Key of the only funded account, so that some of the generated transactions execute.
+const SIGNER_KEY: &str = "ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+#[derive(Debug, Arbitrary)]
+struct FuzzTx {
+    nonce: u8,
+    gas_limit: u32,
+    max_fee_per_gas: u64,
+    max_priority_fee_per_gas: u64,
+    to: Option<[u8; 20]>,
+    value: u64,
+    input: Vec<u8>,
+}
+
+#[derive(Debug, Arbitrary)]
+enum FuzzInput {
+    /// Arbitrary bytes, mostly rejected when decoding.
+    Raw(Vec<u8>),
+    /// A transaction signed by the funded account, reaching the admission checks
+    /// and the execution of the module.
+    Signed(FuzzTx),
+}
+
+fn sign(signer: &DevSigner, chain_id: u64, tx: FuzzTx) -> Vec<u8> {
+    let transaction = Transaction::Eip1559(TxEip1559 {
+        chain_id,
+        nonce: tx.nonce as u64,
+        gas_limit: tx.gas_limit as u64,
+        max_fee_per_gas: tx.max_fee_per_gas as u128,
+        max_priority_fee_per_gas: tx.max_priority_fee_per_gas as u128,
+        to: match tx.to {
+            Some(to) => TransactionKind::Call(Address::from(to)),
+            None => TransactionKind::Create,
+        },
+        value: U256::from(tx.value),
+        input: Bytes::from(tx.input),
+        ..Default::default()
+    });
+    signer
+        .sign_transaction(transaction, signer.signers()[0])
+        .unwrap()
+        .envelope_encoded()
+        .to_vec()
+}
+
+// Invalid transactions of a soft batch are skipped by the EVM module, whatever they contain
+// they must never make it panic.
+fuzz_target!(|inputs: Vec<FuzzInput>| {
+    let signer = DevSigner::new(vec![SecretKey::from_str(SIGNER_KEY).unwrap()]);
+    let config = EvmConfig {
+        data: vec![AccountData {
+            address: signer.signers()[0],
+            balance: U256::from(u64::MAX),
+            code_hash: KECCAK_EMPTY,
+            code: Default::default(),
+            nonce: 0,
+            storage: Default::default(),
+        }],
+        block_gas_limit: ETHEREUM_BLOCK_GAS_LIMIT,
+        ..Default::default()
+    };
+
+    let tmpdir = tempfile::tempdir().unwrap();
+    let storage = new_orphan_storage(tmpdir.path()).unwrap();
+    let mut working_set = WorkingSet::new(storage.clone());
+    let evm = Evm::<C>::default();
+    evm.genesis(&config, &mut working_set).unwrap();
+
+    // commit the genesis so that the first block can start on top of it
+    let mut checkpoint = working_set.checkpoint();
+    let (cache_log, witness) = checkpoint.freeze();
+    let (root, node_batch) = storage
+        .compute_state_update(cache_log, &witness)
+        .expect("jellyfish merkle tree update must succeed");
+    let mut working_set = checkpoint.to_revertable();
+    let accessory_log = working_set.checkpoint().freeze_non_provable();
+    storage.commit(&node_batch, &accessory_log);
+
+    let mut working_set = WorkingSet::new(storage);
+    evm.finalize_hook(&root, &mut working_set.accessory_state());
+
+    let txs = inputs
+        .into_iter()
+        .map(|input| match input {
+            FuzzInput::Raw(rlp) => RlpEvmTransaction { rlp },
+            FuzzInput::Signed(tx) => RlpEvmTransaction {
+                rlp: sign(&signer, config.chain_id, tx),
+            },
+        })
+        .collect();
+
+    evm.begin_soft_confirmation_hook([1; 32], 1, [2; 32], &root.0, 1, 0, &mut working_set);
+    let context = C::new(Default::default(), Default::default(), 1);
+    evm.call(
+        CallMessage {
+            txs,
+            encrypted: None,
+        },
+        &context,
+        &mut working_set,
+    )
+    .unwrap();
+    evm.end_soft_confirmation_hook(&mut working_set);
+});
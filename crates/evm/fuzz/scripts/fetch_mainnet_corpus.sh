@@ -0,0 +1,30 @@
+#!/usr/bin/env bash
+# Seeds the corpus of the decoding targets with the raw transactions of recent mainnet blocks,
+# covering the legacy, EIP-2930, EIP-1559 and EIP-4844 envelopes found in the wild.
+#
+# Usage: ETH_RPC_URL=<mainnet rpc url> ./scripts/fetch_mainnet_corpus.sh [block count]
+set -euo pipefail
+
+: "${ETH_RPC_URL:?ETH_RPC_URL must be set to a mainnet RPC url}"
+BLOCKS="${1:-5}"
+cd "$(dirname "$0")/.."
+
+rpc() {
+  curl -sf -X POST -H 'Content-Type: application/json' \
+    --data "{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"$1\",\"params\":$2}" "$ETH_RPC_URL"
+}
+
+mkdir -p corpus/decode_rlp_transaction corpus/decode_pooled_transaction
+
+latest=$(( $(rpc eth_blockNumber '[]' | jq -r .result) ))
+for block in $(seq $((latest - BLOCKS + 1)) "$latest"); do
+  hashes=$(rpc eth_getBlockByNumber "[\"$(printf '0x%x' "$block")\", false]" | jq -r '.result.transactions[]')
+  for hash in $hashes; do
+    raw=$(rpc eth_getRawTransactionByHash "[\"$hash\"]" | jq -r '.result // empty')
+    [ -z "$raw" ] && continue
+    for target in decode_rlp_transaction decode_pooled_transaction; do
+      echo -n "${raw#0x}" | xxd -r -p > "corpus/$target/$hash"
+    done
+  done
+  echo "Fetched the transactions of block $block"
+done
@@ -0,0 +1,66 @@
+use reth_primitives::basefee::calculate_next_block_base_fee;
+use reth_primitives::Header;
+use sov_modules_api::{StateValueAccessor, WorkingSet};
+
+use crate::Evm;
+
+impl<C: sov_modules_api::Context> Evm<C> {
+    /// Returns the base fee of the block following `parent`, or `None` if `parent` has no base fee.
+    pub fn next_block_base_fee(
+        &self,
+        parent: &Header,
+        working_set: &mut WorkingSet<C>,
+    ) -> Option<u64> {
+        parent.base_fee_per_gas.map(|base_fee| {
+            self.next_base_fee(parent.gas_used, parent.gas_limit, base_fee, working_set)
+        })
+    }
+
+    /// Returns the base fee of the block following a block with the given gas usage and base fee.
+    /// The base fee moves towards the block gas target set in genesis, or towards
+    /// `gas_limit / base_fee_params.elasticity_multiplier` if it isn't set.
+    pub fn next_base_fee(
+        &self,
+        gas_used: u64,
+        gas_limit: u64,
+        base_fee: u64,
+        working_set: &mut WorkingSet<C>,
+    ) -> u64 {
+        let base_fee_params = self
+            .cfg
+            .get(working_set)
+            .expect("EVM chain config should be set")
+            .base_fee_params;
+        match self.block_gas_target.get(working_set) {
+            Some(gas_target) => calculate_base_fee_with_target(
+                gas_used,
+                gas_target,
+                base_fee,
+                base_fee_params.max_change_denominator as u128,
+            ),
+            None => calculate_next_block_base_fee(gas_used, gas_limit, base_fee, base_fee_params),
+        }
+    }
+}
+
+/// EIP-1559 base fee update with an explicit gas target instead of one derived from the gas limit.
+/// The gas target is checked to be positive in genesis.
+pub(crate) fn calculate_base_fee_with_target(
+    gas_used: u64,
+    gas_target: u64,
+    base_fee: u64,
+    max_change_denominator: u128,
+) -> u64 {
+    let denominator = gas_target as u128 * max_change_denominator;
+    match gas_used.cmp(&gas_target) {
+        std::cmp::Ordering::Equal => base_fee,
+        std::cmp::Ordering::Greater => {
+            let delta = base_fee as u128 * (gas_used - gas_target) as u128 / denominator;
+            base_fee.saturating_add(delta.max(1) as u64)
+        }
+        std::cmp::Ordering::Less => {
+            let delta = base_fee as u128 * (gas_target - gas_used) as u128 / denominator;
+            base_fee.saturating_sub(delta as u64)
+        }
+    }
+}
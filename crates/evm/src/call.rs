@@ -1,18 +1,31 @@
 use core::panic;
+use std::collections::{HashMap, HashSet};
 
-use anyhow::Result;
-use reth_primitives::TransactionSignedEcRecovered;
+use anyhow::{bail, Result};
+use borsh::BorshDeserialize;
+use reth_primitives::constants::KECCAK_EMPTY;
+use reth_primitives::{Address, TransactionSignedEcRecovered, B256, U256};
 use revm::primitives::{CfgEnvWithHandlerCfg, EVMError, SpecId};
+use secp256k1::{PublicKey, SecretKey, SECP256K1};
 use sov_modules_api::prelude::*;
 use sov_modules_api::{CallResponse, WorkingSet};
 
 use crate::evm::db::EvmDb;
 use crate::evm::executor::{self};
 use crate::evm::handler::{CitreaExternal, CitreaExternalExt};
-use crate::evm::primitive_types::{BlockEnv, Receipt, TransactionSignedAndRecovered};
-use crate::evm::{EvmChainConfig, RlpEvmTransaction};
-use crate::system_contracts::L1BlockHashList;
-use crate::system_events::{create_system_transactions, SYSTEM_SIGNER};
+use crate::evm::primitive_types::{
+    BlockEnv, ContractCreation, Receipt, TransactionSignedAndRecovered,
+};
+use crate::evm::{
+    epoch_public_key, epoch_secret_key, AccountFreeze, AppliedGovernanceAction, DepositKey,
+    EncryptedBatch, EncryptedMempoolConfig, EncryptedQueueEntry, EpochKeyAnnouncement,
+    EpochKeyRelease, EvmChainConfig, GovernanceAction, RlpEvmTransaction, SignedGovernanceAction,
+    MAX_BRIDGE_OPERATORS,
+};
+use crate::system_contracts::{Bridge, L1BlockHashList, StateRootAnchor};
+use crate::system_events::{
+    create_system_transactions, system_event_gas_limit, DEFAULT_SYSTEM_TX_GAS_LIMIT, SYSTEM_SIGNER,
+};
 use crate::{Evm, PendingTransaction, SystemEvent};
 
 #[cfg_attr(
@@ -22,17 +35,57 @@ use crate::{Evm, PendingTransaction, SystemEvent};
 )]
 
 /// EVM call message.
-#[derive(borsh::BorshDeserialize, borsh::BorshSerialize, Debug, PartialEq, Clone)]
+#[derive(Debug, PartialEq, Clone)]
 pub struct CallMessage {
     /// RLP encoded transaction.
     pub txs: Vec<RlpEvmTransaction>,
+    /// Encrypted mempool part of the block, only allowed if the encrypted mempool is enabled.
+    pub encrypted: Option<EncryptedBatch>,
+}
+
+// The encrypted batch is appended only when present, so that call messages
+// encoded before the encrypted mempool existed keep the same encoding.
+impl borsh::BorshSerialize for CallMessage {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        self.txs.serialize(writer)?;
+        if let Some(encrypted) = &self.encrypted {
+            1u8.serialize(writer)?;
+            encrypted.serialize(writer)?;
+        }
+        Ok(())
+    }
+}
+
+impl borsh::BorshDeserialize for CallMessage {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let txs = Vec::<RlpEvmTransaction>::deserialize_reader(reader)?;
+        let mut tag = [0u8; 1];
+        let encrypted = match reader.read(&mut tag)? {
+            0 => None,
+            _ => match tag[0] {
+                0 => None,
+                1 => Some(EncryptedBatch::deserialize_reader(reader)?),
+                tag => {
+                    return Err(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!("Invalid encrypted batch tag: {tag}"),
+                    ))
+                }
+            },
+        };
+        Ok(Self { txs, encrypted })
+    }
 }
 
 impl<C: sov_modules_api::Context> Evm<C> {
     /// Executes system events for the current block and push tx to pending_transactions.
+    ///
+    /// The events of missing system contracts are dropped, then the events not fitting in the
+    /// system transaction gas limit are deferred. The deferred events are always recorded,
+    /// even when no event is left to execute in this block.
     pub(crate) fn execute_system_events(
         &self,
-        system_events: Vec<SystemEvent>,
+        mut system_events: Vec<SystemEvent>,
         working_set: &mut WorkingSet<C>,
     ) {
         let block_env = self
@@ -54,7 +107,69 @@ impl<C: sov_modules_api::Context> Evm<C> {
             .is_some();
         if !l1_block_hash_exists {
             tracing::error!("System contract not found: L1BlockHashList");
-            return;
+            system_events.clear();
+        }
+
+        let state_root_anchor_exists = self
+            .accounts
+            .get(&StateRootAnchor::address(), working_set)
+            .is_some();
+        if !state_root_anchor_exists {
+            let events_len = system_events.len();
+            system_events.retain(|event| !matches!(event, SystemEvent::StateRootAnchor(..)));
+            if system_events.len() != events_len {
+                tracing::error!("System contract not found: StateRootAnchor");
+            }
+        }
+
+        if !self.bridge_has_code(working_set) {
+            let events_len = system_events.len();
+            system_events.retain(|event| {
+                !matches!(
+                    event,
+                    SystemEvent::BridgeSetWithdrawalRateLimit(..)
+                        | SystemEvent::BridgeSetOperators(..)
+                )
+            });
+            if system_events.len() != events_len {
+                tracing::error!("System contract not found: Bridge");
+            }
+        }
+
+        let events_len = system_events.len();
+        system_events.retain(|event| match event {
+            SystemEvent::UpgradeSystemContract(proxy, ..) => {
+                self.accounts.get(proxy, working_set).is_some()
+            }
+            _ => true,
+        });
+        if system_events.len() != events_len {
+            tracing::error!("System contract proxy not found, skipping its upgrade");
+        }
+
+        // the events whose transactions don't fit in the system transaction gas limit of the
+        // block are deferred to the next block, in order, so that no event is ever lost
+        let system_tx_gas_limit = self
+            .system_tx_gas_limit
+            .get(working_set)
+            .unwrap_or(DEFAULT_SYSTEM_TX_GAS_LIMIT);
+        let mut gas_limit = 0;
+        let fitting = system_events
+            .iter()
+            .take_while(|event| {
+                gas_limit += system_event_gas_limit(event);
+                gas_limit <= system_tx_gas_limit
+            })
+            .count();
+        let deferred = system_events.split_off(fitting);
+        if deferred.is_empty() {
+            self.deferred_system_events.delete(working_set);
+        } else {
+            tracing::warn!(
+                "System transactions exceed the system gas limit, deferring {} system events to the next block",
+                deferred.len()
+            );
+            self.deferred_system_events.set(&deferred, working_set);
         }
 
         let system_nonce = self
@@ -63,10 +178,13 @@ impl<C: sov_modules_api::Context> Evm<C> {
             .map(|acc| acc.info.nonce)
             .unwrap_or(0);
 
+        let gas_schedule = self.gas_schedule.get(working_set).unwrap_or_default();
+
         let db: EvmDb<'_, C> = self.get_db(working_set);
         let system_txs = create_system_transactions(system_events, system_nonce, cfg_env.chain_id);
 
-        let mut citrea_handler_ext = CitreaExternal::new(l1_fee_rate);
+        let mut citrea_handler_ext =
+            CitreaExternal::new(l1_fee_rate).with_gas_schedule(gas_schedule);
         let block_number = block_env.number;
         let tx_results = executor::execute_system_txs(
             db,
@@ -105,6 +223,13 @@ impl<C: sov_modules_api::Context> Evm<C> {
                 error: None,
             };
             log_index_start += logs_len;
+            self.record_contract_creations(
+                &tx_info.created_contracts,
+                tx_hash,
+                tx.signer(),
+                block_number,
+                working_set,
+            );
 
             let pending_transaction = PendingTransaction {
                 transaction: TransactionSignedAndRecovered {
@@ -123,9 +248,19 @@ impl<C: sov_modules_api::Context> Evm<C> {
     pub(crate) fn execute_call(
         &self,
         txs: Vec<RlpEvmTransaction>,
+        encrypted: Option<EncryptedBatch>,
         _context: &C,
         working_set: &mut WorkingSet<C>,
     ) -> Result<CallResponse> {
+        let txs = match encrypted {
+            Some(batch) => {
+                let mut decrypted_txs = self.process_encrypted_batch(batch, working_set)?;
+                decrypted_txs.extend(txs);
+                decrypted_txs
+            }
+            None => txs,
+        };
+
         let users_txs: Vec<TransactionSignedEcRecovered> = txs
             .into_iter()
             .filter_map(|tx| match tx.try_into() {
@@ -139,6 +274,49 @@ impl<C: sov_modules_api::Context> Evm<C> {
             .get(working_set)
             .expect("Pending block must be set");
 
+        // transactions sent by or to frozen accounts are dropped, their inner calls aren't checked
+        let users_txs: Vec<TransactionSignedEcRecovered> = users_txs
+            .into_iter()
+            .filter(|tx| {
+                let frozen = self.is_frozen(&tx.signer(), block_env.number, working_set)
+                    || tx
+                        .to()
+                        .is_some_and(|to| self.is_frozen(&to, block_env.number, working_set));
+                if frozen {
+                    tracing::warn!(
+                        "evm: Dropping transaction {} of a frozen account",
+                        tx.hash()
+                    );
+                }
+                !frozen
+            })
+            .collect();
+
+        // deposits minted before, or by an earlier transaction of the block, are dropped
+        let mut block_deposits = HashSet::new();
+        let mut deposit_keys = Vec::with_capacity(users_txs.len());
+        let users_txs: Vec<TransactionSignedEcRecovered> = users_txs
+            .into_iter()
+            .filter(|tx| {
+                let deposit_key = self.deposit_key(tx);
+                if let Some(key) = deposit_key {
+                    if self.processed_deposits.get(&key, working_set).is_some()
+                        || !block_deposits.insert(key)
+                    {
+                        tracing::warn!(
+                            "evm: Dropping transaction {} minting the deposit {}:{} again",
+                            tx.hash(),
+                            key.txid,
+                            key.vout
+                        );
+                        return false;
+                    }
+                }
+                deposit_keys.push(deposit_key);
+                true
+            })
+            .collect();
+
         let cfg = self.cfg.get(working_set).expect("Evm config must be set");
         let cfg_env: CfgEnvWithHandlerCfg = get_cfg_env(&block_env, cfg, None);
 
@@ -146,15 +324,27 @@ impl<C: sov_modules_api::Context> Evm<C> {
             .l1_fee_rate
             .get(working_set)
             .expect("L1 fee rate must be set");
-        let mut citrea_handler_ext = CitreaExternal::new(l1_fee_rate);
+        let gas_schedule = self.gas_schedule.get(working_set).unwrap_or_default();
+        let mut citrea_handler_ext =
+            CitreaExternal::new(l1_fee_rate).with_gas_schedule(gas_schedule);
 
         let block_number = block_env.number;
+        let base_fee = U256::from(block_env.basefee);
+        let mut base_fee_paid = U256::ZERO;
         let mut cumulative_gas_used = 0;
         let mut log_index_start = 0;
         if let Some(tx) = self.pending_transactions.last(working_set) {
             cumulative_gas_used = tx.receipt.receipt.cumulative_gas_used;
             log_index_start = tx.receipt.log_index_start + tx.receipt.receipt.logs.len() as u64;
         }
+        // system transactions are at the beginning of the block and don't consume
+        // the block gas limit of user transactions
+        let system_gas_used: u64 = self
+            .pending_transactions
+            .iter(working_set)
+            .take_while(|tx| tx.transaction.signer == SYSTEM_SIGNER)
+            .map(|tx| tx.receipt.gas_used)
+            .sum();
 
         let evm_db: EvmDb<'_, C> = self.get_db(working_set);
 
@@ -164,13 +354,17 @@ impl<C: sov_modules_api::Context> Evm<C> {
             &users_txs,
             cfg_env,
             &mut citrea_handler_ext,
-            cumulative_gas_used,
+            cumulative_gas_used - system_gas_used,
         );
 
         // Iterate each evm_txs_recovered and results pair
         // Create a PendingTransaction for each pair
         // Push each PendingTransaction to pending_transactions
-        for (evm_tx_recovered, result) in users_txs.into_iter().zip(results.into_iter()) {
+        for ((evm_tx_recovered, result), deposit_key) in users_txs
+            .into_iter()
+            .zip(results.into_iter())
+            .zip(deposit_keys)
+        {
             match result {
                 Ok(result) => {
                     // take ownership of result.log() and use into()
@@ -179,6 +373,7 @@ impl<C: sov_modules_api::Context> Evm<C> {
 
                     let gas_used = result.gas_used();
                     cumulative_gas_used += gas_used;
+                    base_fee_paid += base_fee * U256::from(gas_used);
                     let tx_hash = evm_tx_recovered.hash();
                     let tx_info = citrea_handler_ext.get_tx_info(tx_hash).unwrap_or_else(|| {
                         panic!("evm: Could not get associated info for tx: {tx_hash}")
@@ -197,6 +392,17 @@ impl<C: sov_modules_api::Context> Evm<C> {
                         error: None,
                     };
                     log_index_start += logs_len;
+                    self.record_contract_creations(
+                        &tx_info.created_contracts,
+                        tx_hash,
+                        evm_tx_recovered.signer(),
+                        block_number,
+                        working_set,
+                    );
+                    if let Some(key) = deposit_key.filter(|_| receipt.receipt.success) {
+                        self.processed_deposits
+                            .set(&key, &block_number, working_set);
+                    }
 
                     let pending_transaction = PendingTransaction {
                         transaction: TransactionSignedAndRecovered {
@@ -230,10 +436,492 @@ impl<C: sov_modules_api::Context> Evm<C> {
                 },
             }
         }
+
+        if base_fee_paid > U256::ZERO {
+            let base_fee_accrued = self.base_fee_accrued.get(working_set).unwrap_or_default();
+            self.base_fee_accrued
+                .set(&(base_fee_accrued + base_fee_paid), working_set);
+        }
         Ok(CallResponse::default())
     }
 }
 
+impl<C: sov_modules_api::Context> Evm<C> {
+    /// Records the epoch key shares of an encrypted batch, decrypts the queued transactions of
+    /// the ended epochs whose key is complete, drops those of the expired epochs, then queues
+    /// the newly ordered transactions. Invalid entries of the batch are skipped, and the whole
+    /// batch is skipped if the encrypted mempool is disabled.
+    /// Returns the decrypted transactions, to be executed before the other transactions of the block.
+    fn process_encrypted_batch(
+        &self,
+        batch: EncryptedBatch,
+        working_set: &mut WorkingSet<C>,
+    ) -> Result<Vec<RlpEvmTransaction>> {
+        let Some(config) = self.encrypted_mempool_config.get(working_set) else {
+            tracing::error!("evm: Skipping encrypted batch, the encrypted mempool is disabled");
+            return Ok(vec![]);
+        };
+
+        let block_number = self
+            .block_env
+            .get(working_set)
+            .expect("Pending block must be set")
+            .number;
+        let epoch = config.epoch_of(block_number);
+
+        for announcement in batch.announcements {
+            let announced_epoch = announcement.epoch;
+            if let Err(e) =
+                self.apply_epoch_key_announcement(&config, epoch, announcement, working_set)
+            {
+                tracing::error!(
+                    "evm: Skipping key announcement of epoch {}: {:?}",
+                    announced_epoch,
+                    e
+                );
+            }
+        }
+        for release in batch.releases {
+            let released_epoch = release.epoch;
+            if let Err(e) = self.apply_epoch_key_release(&config, epoch, release, working_set) {
+                tracing::error!(
+                    "evm: Skipping key release of epoch {}: {:?}",
+                    released_epoch,
+                    e
+                );
+            }
+        }
+
+        let (mut head, mut tail) = self
+            .encrypted_tx_queue_range
+            .get(working_set)
+            .unwrap_or_default();
+
+        // The queue is in epoch order, the transactions of an epoch wait for the key of the
+        // epoch and for the earlier epochs to be decrypted or expired.
+        let mut decrypted_txs = vec![];
+        let mut epoch_secret_keys = HashMap::new();
+        while let Some(entry) = self.encrypted_tx_queue.get(&head, working_set) {
+            let tx_epoch = entry.transaction.epoch;
+            if tx_epoch >= epoch {
+                break;
+            }
+            let secret_key = epoch_secret_keys
+                .entry(tx_epoch)
+                .or_insert_with(|| self.epoch_secret_key(&config, tx_epoch, working_set));
+            match secret_key {
+                Ok(secret_key) => match entry.transaction.decrypt(secret_key) {
+                    Some(rlp) => decrypted_txs.push(RlpEvmTransaction { rlp }),
+                    None => tracing::warn!(
+                        "evm: Dropping encrypted transaction {} with an invalid ephemeral key or tag",
+                        entry.transaction.hash()
+                    ),
+                },
+                Err(missing_keypers) if block_number > config.release_deadline(tx_epoch) => {
+                    if self.expired_epochs.get(&tx_epoch, working_set).is_none() {
+                        tracing::warn!(
+                            "evm: Dropping the encrypted transactions of epoch {} missing keys of {:?}",
+                            tx_epoch,
+                            missing_keypers
+                        );
+                        self.expired_epochs
+                            .set(&tx_epoch, missing_keypers, working_set);
+                    }
+                }
+                Err(_) => break,
+            }
+            self.encrypted_tx_queue.remove(&head, working_set);
+            head += 1;
+        }
+
+        let epoch_key_announced = self.epoch_public_key(&config, epoch, working_set).is_some();
+        for transaction in batch.ordered {
+            if transaction.epoch != epoch {
+                tracing::error!(
+                    "evm: Skipping encrypted transaction {} of epoch {} ordered in epoch {}",
+                    transaction.hash(),
+                    transaction.epoch,
+                    epoch
+                );
+                continue;
+            }
+            if !epoch_key_announced {
+                tracing::error!(
+                    "evm: Skipping encrypted transaction {}, the key of epoch {} was not announced by every keyper",
+                    transaction.hash(),
+                    epoch
+                );
+                continue;
+            }
+            let entry = EncryptedQueueEntry {
+                transaction,
+                ordered_at: block_number,
+            };
+            self.encrypted_tx_queue.set(&tail, &entry, working_set);
+            tail += 1;
+        }
+
+        self.encrypted_tx_queue_range
+            .set(&(head, tail), working_set);
+
+        Ok(decrypted_txs)
+    }
+
+    /// Verifies and records a keyper's public key share of an epoch after `current_epoch`.
+    fn apply_epoch_key_announcement(
+        &self,
+        config: &EncryptedMempoolConfig,
+        current_epoch: u64,
+        announcement: EpochKeyAnnouncement,
+        working_set: &mut WorkingSet<C>,
+    ) -> Result<()> {
+        let chain_id = self
+            .cfg
+            .get(working_set)
+            .expect("Evm config must be set")
+            .chain_id;
+        let Some(keyper) = announcement
+            .signer(chain_id)
+            .filter(|signer| config.keypers.contains(signer))
+        else {
+            bail!("Key announcement is not signed by a keyper");
+        };
+        if announcement.epoch <= current_epoch {
+            bail!(
+                "Key of epoch {} announced after it started",
+                announcement.epoch
+            );
+        }
+        let key = (announcement.epoch, keyper);
+        if self
+            .epoch_public_key_shares
+            .get(&key, working_set)
+            .is_some()
+        {
+            bail!(
+                "Keyper {} already announced its key of epoch {}",
+                keyper,
+                announcement.epoch
+            );
+        }
+        if PublicKey::from_slice(&announcement.public_key).is_err() {
+            bail!("Invalid public key share");
+        }
+        if !announcement.verify_proof_of_possession(chain_id, keyper) {
+            bail!("Invalid proof of possession of the public key share");
+        }
+        self.epoch_public_key_shares
+            .set(&key, &announcement.public_key, working_set);
+        Ok(())
+    }
+
+    /// Verifies a keyper's secret key share of an epoch before `current_epoch` against its
+    /// announced public key share, and records it.
+    fn apply_epoch_key_release(
+        &self,
+        config: &EncryptedMempoolConfig,
+        current_epoch: u64,
+        release: EpochKeyRelease,
+        working_set: &mut WorkingSet<C>,
+    ) -> Result<()> {
+        if release.epoch >= current_epoch {
+            bail!("Key of epoch {} released before it ended", release.epoch);
+        }
+        if !config.keypers.contains(&release.keyper) {
+            bail!("{} is not a keyper", release.keyper);
+        }
+        let key = (release.epoch, release.keyper);
+        let Some(public_key) = self.epoch_public_key_shares.get(&key, working_set) else {
+            bail!(
+                "Keyper {} did not announce its key of epoch {}",
+                release.keyper,
+                release.epoch
+            );
+        };
+        if self
+            .epoch_secret_key_shares
+            .get(&key, working_set)
+            .is_some()
+        {
+            bail!(
+                "Keyper {} already released its key of epoch {}",
+                release.keyper,
+                release.epoch
+            );
+        }
+        let secret_key = SecretKey::from_slice(release.secret_key.as_slice())?;
+        if PublicKey::from_secret_key(SECP256K1, &secret_key).serialize()[..] != public_key[..] {
+            bail!("Secret key share doesn't match the announced public key share");
+        }
+        self.epoch_secret_key_shares
+            .set(&key, &release.secret_key, working_set);
+        Ok(())
+    }
+
+    /// Returns the public key of an epoch, None if not every keyper announced its share.
+    pub(crate) fn epoch_public_key(
+        &self,
+        config: &EncryptedMempoolConfig,
+        epoch: u64,
+        working_set: &mut WorkingSet<C>,
+    ) -> Option<PublicKey> {
+        let shares = config
+            .keypers
+            .iter()
+            .map(|keyper| {
+                let share = self
+                    .epoch_public_key_shares
+                    .get(&(epoch, *keyper), working_set)?;
+                PublicKey::from_slice(&share).ok()
+            })
+            .collect::<Option<Vec<_>>>()?;
+        epoch_public_key(&shares)
+    }
+
+    /// Returns the secret key of an epoch, or the keypers that did not release their share.
+    fn epoch_secret_key(
+        &self,
+        config: &EncryptedMempoolConfig,
+        epoch: u64,
+        working_set: &mut WorkingSet<C>,
+    ) -> std::result::Result<SecretKey, Vec<Address>> {
+        let mut shares = vec![];
+        let mut missing_keypers = vec![];
+        for keyper in &config.keypers {
+            match self
+                .epoch_secret_key_shares
+                .get(&(epoch, *keyper), working_set)
+                .and_then(|share| SecretKey::from_slice(share.as_slice()).ok())
+            {
+                Some(share) => shares.push(share),
+                None => missing_keypers.push(*keyper),
+            }
+        }
+        if !missing_keypers.is_empty() {
+            return Err(missing_keypers);
+        }
+        epoch_secret_key(&shares).ok_or_else(Vec::new)
+    }
+}
+
+impl<C: sov_modules_api::Context> Evm<C> {
+    /// Applies the governance actions posted to the DA block of the pending block, before its
+    /// transactions. The actions are taken from the DA block by the STF, so the sequencer
+    /// can't omit them. Every soft confirmation on the DA block gets the same actions,
+    /// the ones already applied are skipped by their nonce.
+    pub fn apply_da_governance_actions(
+        &self,
+        actions: &[Vec<u8>],
+        working_set: &mut WorkingSet<C>,
+    ) {
+        if actions.is_empty() || self.governance_config.get(working_set).is_none() {
+            return;
+        }
+        let next_nonce = self.governance_nonce.get(working_set).unwrap_or_default();
+        let mut actions: Vec<SignedGovernanceAction> = actions
+            .iter()
+            .filter_map(|action| SignedGovernanceAction::try_from_slice(action).ok())
+            .filter(|action| action.nonce >= next_nonce)
+            .collect();
+        actions.sort_by_key(|action| action.nonce);
+
+        for action in actions {
+            let nonce = action.nonce;
+            if let Err(e) = self.apply_governance_action(action, working_set) {
+                tracing::warn!("evm: Skipping governance action {}: {:?}", nonce, e);
+            }
+        }
+    }
+
+    /// Verifies a governance action and applies it to the blocklist.
+    fn apply_governance_action(
+        &self,
+        signed: SignedGovernanceAction,
+        working_set: &mut WorkingSet<C>,
+    ) -> Result<()> {
+        let Some(config) = self.governance_config.get(working_set) else {
+            bail!("Governance is disabled");
+        };
+        let chain_id = self
+            .cfg
+            .get(working_set)
+            .expect("Evm config must be set")
+            .chain_id;
+        if signed.signer(chain_id) != Some(config.address) {
+            bail!("Governance action is not signed by the governance key");
+        }
+        let nonce = self.governance_nonce.get(working_set).unwrap_or_default();
+        if signed.nonce != nonce {
+            bail!(
+                "Governance action nonce {} is not the next one: {}",
+                signed.nonce,
+                nonce
+            );
+        }
+
+        let block_number = self
+            .block_env
+            .get(working_set)
+            .expect("Pending block must be set")
+            .number;
+        match &signed.action {
+            GovernanceAction::FreezeAccounts {
+                accounts,
+                until_block,
+            } => {
+                if *until_block <= block_number
+                    || *until_block - block_number > config.max_freeze_blocks
+                {
+                    bail!(
+                        "Freeze until block {} is not within {} blocks after block {}",
+                        until_block,
+                        config.max_freeze_blocks,
+                        block_number
+                    );
+                }
+                let freeze = AccountFreeze {
+                    until_block: *until_block,
+                    governance_nonce: nonce,
+                };
+                for account in accounts {
+                    self.frozen_accounts.set(account, &freeze, working_set);
+                }
+            }
+            GovernanceAction::UnfreezeAccounts { accounts } => {
+                for account in accounts {
+                    self.frozen_accounts.remove(account, working_set);
+                }
+            }
+            GovernanceAction::SetWithdrawalRateLimit { limit } => {
+                if limit.is_some_and(|limit| limit.window_l1_blocks == 0) {
+                    bail!("Withdrawal rate limit window cannot be empty");
+                }
+                if limit.is_some_and(|limit| limit.max_volume == 0) {
+                    bail!("Withdrawal rate limit cannot be zero");
+                }
+                // rejected without consuming the nonce, so that the action can be posted again
+                // once the bridge is deployed
+                if !self.bridge_has_code(working_set) {
+                    bail!("Bridge has no code, the withdrawal rate limit cannot be set");
+                }
+                // the bridge keeps the accounting, the system signer sets the limit
+                // at the beginning of the next block
+                let mut system_events = self
+                    .deferred_system_events
+                    .get(working_set)
+                    .unwrap_or_default();
+                system_events.push(SystemEvent::BridgeSetWithdrawalRateLimit(
+                    limit.map_or(0, |limit| limit.max_volume),
+                    limit.map_or(0, |limit| limit.window_l1_blocks),
+                ));
+                self.deferred_system_events.set(&system_events, working_set);
+            }
+            GovernanceAction::SetBridgeOperators {
+                operators,
+                threshold,
+            } => {
+                if operators.len() > MAX_BRIDGE_OPERATORS {
+                    bail!(
+                        "Bridge operators {} are more than {}",
+                        operators.len(),
+                        MAX_BRIDGE_OPERATORS
+                    );
+                }
+                if *threshold == 0 || *threshold > operators.len() as u64 {
+                    bail!(
+                        "Bridge operator threshold {} is not within 1 and {}",
+                        threshold,
+                        operators.len()
+                    );
+                }
+                if operators.contains(&Address::ZERO) {
+                    bail!("Bridge operator cannot be the zero address");
+                }
+                if operators.iter().collect::<HashSet<_>>().len() != operators.len() {
+                    bail!("Bridge operators contain duplicates");
+                }
+                // rejected without consuming the nonce, so that the action can be posted again
+                // once the bridge is deployed
+                if !self.bridge_has_code(working_set) {
+                    bail!("Bridge has no code, the bridge operators cannot be set");
+                }
+                // the system signer sets the operators at the beginning of the next block
+                let mut system_events = self
+                    .deferred_system_events
+                    .get(working_set)
+                    .unwrap_or_default();
+                system_events.push(SystemEvent::BridgeSetOperators(
+                    operators.clone(),
+                    *threshold,
+                ));
+                self.deferred_system_events.set(&system_events, working_set);
+            }
+        }
+
+        self.governance_actions.set(
+            &nonce,
+            &AppliedGovernanceAction {
+                action: signed.action,
+                block_number,
+            },
+            working_set,
+        );
+        self.governance_nonce.set(&(nonce + 1), working_set);
+        Ok(())
+    }
+
+    /// Returns true if the Bridge system contract is deployed, its system transactions
+    /// would otherwise succeed without effect.
+    fn bridge_has_code(&self, working_set: &mut WorkingSet<C>) -> bool {
+        self.accounts
+            .get(&Bridge::address(), working_set)
+            .is_some_and(|bridge| bridge.info.code_hash != KECCAK_EMPTY)
+    }
+
+    /// Indexes the contracts created by a transaction for `citrea_getContractCreation`.
+    fn record_contract_creations(
+        &self,
+        created_contracts: &[Address],
+        transaction_hash: B256,
+        creator: Address,
+        block_number: u64,
+        working_set: &mut WorkingSet<C>,
+    ) {
+        let creation = ContractCreation {
+            transaction_hash,
+            block_number,
+            creator,
+        };
+        for address in created_contracts {
+            self.contract_creations
+                .set(address, &creation, &mut working_set.accessory_state());
+        }
+    }
+
+    /// Returns the idempotency key of the deposit minted by a transaction calling `deposit`
+    /// of the bridge, None for other transactions.
+    fn deposit_key(&self, tx: &TransactionSignedEcRecovered) -> Option<DepositKey> {
+        if tx.to() != Some(Bridge::address()) {
+            return None;
+        }
+        Bridge::default()
+            .decode_deposit(tx.input())
+            .map(|params| params.key())
+    }
+
+    /// Whether the account is frozen in the L2 block `block_number`.
+    pub(crate) fn is_frozen(
+        &self,
+        address: &Address,
+        block_number: u64,
+        working_set: &mut WorkingSet<C>,
+    ) -> bool {
+        self.frozen_accounts
+            .get(address, working_set)
+            .is_some_and(|freeze| freeze.until_block > block_number)
+    }
+}
+
 /// Get cfg env for a given block number
 /// Returns correct config depending on spec for given block number
 /// Copies context dependent values from template_cfg or default if not provided
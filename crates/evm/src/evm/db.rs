@@ -12,6 +12,8 @@ pub(crate) struct EvmDb<'a, C: sov_modules_api::Context> {
     pub(crate) accounts: sov_modules_api::StateMap<Address, DbAccount, BcsCodec>,
     pub(crate) code: sov_modules_api::StateMap<B256, Bytecode, BcsCodec>,
     pub(crate) last_block_hashes: sov_modules_api::StateMap<U256, B256, BcsCodec>,
+    pub(crate) account_list: sov_modules_api::AccessoryStateVec<Address, BcsCodec>,
+    pub(crate) storage_changes: sov_modules_api::AccessoryStateVec<(Address, U256), BcsCodec>,
     pub(crate) working_set: &'a mut WorkingSet<C>,
 }
 
@@ -20,12 +22,16 @@ impl<'a, C: sov_modules_api::Context> EvmDb<'a, C> {
         accounts: sov_modules_api::StateMap<Address, DbAccount, BcsCodec>,
         code: sov_modules_api::StateMap<B256, Bytecode, BcsCodec>,
         last_block_hashes: sov_modules_api::StateMap<U256, B256, BcsCodec>,
+        account_list: sov_modules_api::AccessoryStateVec<Address, BcsCodec>,
+        storage_changes: sov_modules_api::AccessoryStateVec<(Address, U256), BcsCodec>,
         working_set: &'a mut WorkingSet<C>,
     ) -> Self {
         Self {
             accounts,
             code,
             last_block_hashes,
+            account_list,
+            storage_changes,
             working_set,
         }
     }
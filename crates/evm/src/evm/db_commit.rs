@@ -16,10 +16,14 @@ impl<'a, C: sov_modules_api::Context> DatabaseCommit for EvmDb<'a, C> {
             }
             let accounts_prefix = self.accounts.prefix();
 
-            let mut db_account = self
-                .accounts
-                .get(&address, self.working_set)
-                .unwrap_or_else(|| DbAccount::new(accounts_prefix, address));
+            let mut db_account = match self.accounts.get(&address, self.working_set) {
+                Some(db_account) => db_account,
+                None => {
+                    self.account_list
+                        .push(&address, &mut self.working_set.accessory_state());
+                    DbAccount::new(accounts_prefix, address)
+                }
+            };
 
             // https://github.com/Sovereign-Labs/sovereign-sdk/issues/425
             if account.is_selfdestructed() {
@@ -33,6 +37,8 @@ impl<'a, C: sov_modules_api::Context> DatabaseCommit for EvmDb<'a, C> {
                 let keys_to_remove: Vec<U256> = db_account.keys.iter(self.working_set).collect();
                 for key in keys_to_remove {
                     db_account.storage.delete(&key, self.working_set);
+                    self.storage_changes
+                        .push(&(address, key), &mut self.working_set.accessory_state());
                 }
                 db_account.keys.clear(self.working_set);
                 self.accounts.set(&address, &db_account, self.working_set);
@@ -54,6 +60,10 @@ impl<'a, C: sov_modules_api::Context> DatabaseCommit for EvmDb<'a, C> {
             let storage_slots = account.storage.into_iter().collect::<BTreeMap<_, _>>();
             // insert to StateVec keys must sorted -- or else nodes will have different state roots
             for (key, value) in storage_slots.into_iter() {
+                if value.is_changed() {
+                    self.storage_changes
+                        .push(&(address, key), &mut self.working_set.accessory_state());
+                }
                 let value = value.present_value();
                 if db_account.storage.get(&key, self.working_set).is_none() {
                     db_account.keys.push(&key, self.working_set);
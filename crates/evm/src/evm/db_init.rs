@@ -2,7 +2,7 @@ use reth_primitives::U256;
 #[cfg(test)]
 use revm::db::{CacheDB, EmptyDB};
 use revm::primitives::{Address, Bytecode, B256};
-use sov_modules_api::StateMapAccessor;
+use sov_modules_api::{StateMapAccessor, StateVecAccessor};
 
 use super::db::EvmDb;
 use super::{AccountInfo, DbAccount};
@@ -19,6 +19,8 @@ impl<'a, C: sov_modules_api::Context> InitEvmDb for EvmDb<'a, C> {
         let parent_prefix = self.accounts.prefix();
         let db_account = DbAccount::new_with_info(parent_prefix, sender, info);
 
+        self.account_list
+            .push(&sender, &mut self.working_set.accessory_state());
         self.accounts.set(&sender, &db_account, self.working_set);
     }
 
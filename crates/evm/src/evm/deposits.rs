@@ -0,0 +1,68 @@
+use reth_primitives::{B256, U256};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Index of the output paying the bridge in deposit transactions on Bitcoin,
+/// the first output by the convention of the bridge.
+pub const DEPOSIT_OUTPUT_INDEX: u32 = 0;
+
+/// Idempotency key of a bridge deposit, the outpoint of the deposit on Bitcoin.
+///
+/// Deposits minted on chain are recorded by their key and deposit transactions of a key minted
+/// before are dropped from the blocks, so a deposit re-scanned after a restart or an L1 reorg
+/// can never be minted twice. Unlike the wtxid the bridge contract checks, the key doesn't
+/// change with the witness of the deposit transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DepositKey {
+    /// Txid of the deposit transaction, in internal byte order.
+    pub txid: B256,
+    /// Index of the output paying the bridge.
+    pub vout: u32,
+}
+
+/// Parameters of a `deposit` call of the bridge: the deposit transaction on Bitcoin
+/// and the proof of its inclusion in an L1 block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DepositParams {
+    /// Version of the transaction.
+    pub version: [u8; 4],
+    /// Segwit marker and flag of the transaction.
+    pub flag: [u8; 2],
+    /// Inputs of the transaction, prefixed with their count.
+    pub vin: Vec<u8>,
+    /// Outputs of the transaction, prefixed with their count.
+    pub vout: Vec<u8>,
+    /// Witnesses of the inputs of the transaction.
+    pub witness: Vec<u8>,
+    /// Locktime of the transaction.
+    pub locktime: [u8; 4],
+    /// Merkle proof of the wtxid in the witness tree of the L1 block.
+    pub intermediate_nodes: Vec<u8>,
+    /// Height of the L1 block.
+    pub block_height: U256,
+    /// Index of the transaction in the L1 block.
+    pub index: U256,
+}
+
+impl DepositParams {
+    /// Returns the txid of the deposit transaction, the double SHA-256 of the transaction
+    /// serialized without its witnesses.
+    pub fn txid(&self) -> B256 {
+        let first = Sha256::new()
+            .chain_update(self.version)
+            .chain_update(&self.vin)
+            .chain_update(&self.vout)
+            .chain_update(self.locktime)
+            .finalize();
+        B256::from_slice(&Sha256::digest(first))
+    }
+
+    /// Returns the idempotency key of the deposit.
+    pub fn key(&self) -> DepositKey {
+        DepositKey {
+            txid: self.txid(),
+            vout: DEPOSIT_OUTPUT_INDEX,
+        }
+    }
+}
@@ -0,0 +1,360 @@
+use reth_primitives::{keccak256, sign_message, Address, Signature, B256, U256};
+use secp256k1::ecdh::shared_secret_point;
+use secp256k1::{schnorr, KeyPair, Message, PublicKey, Scalar, SecretKey, SECP256K1};
+use serde::{Deserialize, Serialize};
+
+/// Parameters of the encrypted mempool. The encrypted mempool is disabled if not set in genesis.
+///
+/// Transactions are encrypted to the key of an epoch, a range of L2 blocks. The epoch key is
+/// shared by a committee of keypers: each keyper announces its share of the epoch public key
+/// before the epoch starts, with a proof that it knows the matching secret key share, and releases
+/// its share of the epoch secret key once the epoch ended.
+/// The sequencer orders encrypted transactions during their epoch, and the EVM module decrypts
+/// them in the block releasing the last share, so the sequencer commits to an order before
+/// anyone can see the transactions, unless all the keypers collude. Every keyper must release
+/// its share for the transactions of an epoch to be decrypted, there is no threshold.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct EncryptedMempoolConfig {
+    /// Number of L2 blocks of an epoch. Epoch `n` starts at block `n * epoch_blocks`.
+    pub epoch_blocks: u64,
+    /// Number of L2 blocks after the end of an epoch its keys can still be released in.
+    /// The transactions of an epoch whose keys are not all released by then are dropped,
+    /// so that a missing keyper doesn't block the transactions of the later epochs.
+    pub expiry: u64,
+    /// Addresses the keypers sign their epoch public key shares with.
+    pub keypers: Vec<Address>,
+}
+
+impl EncryptedMempoolConfig {
+    /// Returns the epoch of an L2 block.
+    pub fn epoch_of(&self, block_number: u64) -> u64 {
+        block_number / self.epoch_blocks
+    }
+
+    /// Returns the last L2 block the keys of `epoch` can be released in.
+    pub fn release_deadline(&self, epoch: u64) -> u64 {
+        (epoch + 1) * self.epoch_blocks + self.expiry
+    }
+}
+
+/// A transaction encrypted to the key of an epoch.
+#[derive(
+    borsh::BorshDeserialize,
+    borsh::BorshSerialize,
+    Debug,
+    PartialEq,
+    Eq,
+    Clone,
+    Deserialize,
+    Serialize,
+)]
+pub struct EncryptedTransaction {
+    /// Epoch the transaction is ordered in and whose key decrypts it.
+    pub epoch: u64,
+    /// Compressed one-time public key of the sender, used to derive the transaction key
+    /// with the epoch key.
+    pub ephemeral_key: Vec<u8>,
+    /// The RLP encoded transaction, encrypted.
+    pub ciphertext: Vec<u8>,
+    /// Authentication tag of the epoch, the ephemeral key and the ciphertext.
+    pub tag: B256,
+}
+
+impl EncryptedTransaction {
+    /// Encrypts an RLP encoded transaction to the public key of `epoch` with a one-time secret key.
+    /// The secret key must be random and never reused.
+    pub fn encrypt(
+        epoch: u64,
+        epoch_public_key: &PublicKey,
+        ephemeral_secret_key: &SecretKey,
+        rlp: &[u8],
+    ) -> Self {
+        let (encryption_key, mac_key) =
+            derive_keys(&shared_secret_point(epoch_public_key, ephemeral_secret_key));
+        let ephemeral_key = PublicKey::from_secret_key(SECP256K1, ephemeral_secret_key)
+            .serialize()
+            .to_vec();
+        let ciphertext = apply_keystream(&encryption_key, rlp);
+        let tag = authentication_tag(&mac_key, epoch, &ephemeral_key, &ciphertext);
+        Self {
+            epoch,
+            ephemeral_key,
+            ciphertext,
+            tag,
+        }
+    }
+
+    /// Decrypts the transaction with the secret key of its epoch,
+    /// None if the ephemeral key is invalid or the authentication tag doesn't match.
+    pub fn decrypt(&self, epoch_secret_key: &SecretKey) -> Option<Vec<u8>> {
+        let ephemeral_key = PublicKey::from_slice(&self.ephemeral_key).ok()?;
+        let (encryption_key, mac_key) =
+            derive_keys(&shared_secret_point(&ephemeral_key, epoch_secret_key));
+        if authentication_tag(&mac_key, self.epoch, &self.ephemeral_key, &self.ciphertext)
+            != self.tag
+        {
+            return None;
+        }
+        Some(apply_keystream(&encryption_key, &self.ciphertext))
+    }
+
+    /// Returns the hash identifying the transaction before it is decrypted.
+    pub fn hash(&self) -> B256 {
+        keccak256(
+            [
+                &self.epoch.to_be_bytes()[..],
+                &self.ephemeral_key,
+                &self.ciphertext,
+                self.tag.as_slice(),
+            ]
+            .concat(),
+        )
+    }
+}
+
+/// An encrypted transaction ordered on chain and waiting for the key of its epoch.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct EncryptedQueueEntry {
+    /// The encrypted transaction.
+    pub transaction: EncryptedTransaction,
+    /// Number of the L2 block that ordered the transaction.
+    pub ordered_at: u64,
+}
+
+/// A keyper's share of the public key of an epoch, signed with the keyper's address.
+/// The share signs the keyper's address to prove that the keyper knows its secret key, so that
+/// a keyper can't announce a share derived from the shares of the others to control the epoch key.
+#[derive(
+    borsh::BorshDeserialize,
+    borsh::BorshSerialize,
+    Debug,
+    PartialEq,
+    Eq,
+    Clone,
+    Deserialize,
+    Serialize,
+)]
+pub struct EpochKeyAnnouncement {
+    /// The epoch.
+    pub epoch: u64,
+    /// Compressed secp256k1 public key share.
+    pub public_key: Vec<u8>,
+    /// Secp256k1 signature of [`EpochKeyAnnouncement::signing_hash`], `r || s || v`.
+    pub signature: Vec<u8>,
+    /// BIP-340 Schnorr signature of [`EpochKeyAnnouncement::proof_of_possession_hash`]
+    /// with the secret key share.
+    pub proof_of_possession: Vec<u8>,
+}
+
+impl EpochKeyAnnouncement {
+    /// Signs the public key of the secret key `share` of `epoch` with the keyper's secret key,
+    /// for the chain `chain_id`.
+    pub fn sign(
+        secret_key: B256,
+        chain_id: u64,
+        epoch: u64,
+        share: &SecretKey,
+    ) -> Result<Self, secp256k1::Error> {
+        let keyper_public_key =
+            PublicKey::from_secret_key(SECP256K1, &SecretKey::from_slice(secret_key.as_slice())?);
+        let keyper =
+            Address::from_slice(&keccak256(&keyper_public_key.serialize_uncompressed()[1..])[12..]);
+        let public_key = PublicKey::from_secret_key(SECP256K1, share)
+            .serialize()
+            .to_vec();
+        let proof_of_possession = SECP256K1.sign_schnorr_no_aux_rand(
+            &Message::from_slice(
+                Self::proof_of_possession_hash(chain_id, epoch, keyper, &public_key).as_slice(),
+            )?,
+            &KeyPair::from_secret_key(SECP256K1, share),
+        );
+        let signature = sign_message(secret_key, Self::signing_hash(chain_id, epoch, &public_key))?;
+        let mut signature_bytes = Vec::with_capacity(65);
+        signature_bytes.extend_from_slice(&signature.r.to_be_bytes::<32>());
+        signature_bytes.extend_from_slice(&signature.s.to_be_bytes::<32>());
+        signature_bytes.push(signature.odd_y_parity as u8);
+        Ok(Self {
+            epoch,
+            public_key,
+            signature: signature_bytes,
+            proof_of_possession: proof_of_possession[..].to_vec(),
+        })
+    }
+
+    /// Returns the hash signed by the keyper, the keccak hash of the chain id, the epoch
+    /// and the public key share.
+    pub fn signing_hash(chain_id: u64, epoch: u64, public_key: &[u8]) -> B256 {
+        keccak256(
+            [
+                &chain_id.to_be_bytes()[..],
+                &epoch.to_be_bytes(),
+                public_key,
+            ]
+            .concat(),
+        )
+    }
+
+    /// Returns the hash signed by the secret key share, the keccak hash of the chain id, the epoch,
+    /// the keyper's address and the public key share.
+    pub fn proof_of_possession_hash(
+        chain_id: u64,
+        epoch: u64,
+        keyper: Address,
+        public_key: &[u8],
+    ) -> B256 {
+        keccak256(
+            [
+                &chain_id.to_be_bytes()[..],
+                &epoch.to_be_bytes(),
+                keyper.as_slice(),
+                public_key,
+            ]
+            .concat(),
+        )
+    }
+
+    /// Returns true if the proof of possession is a signature of `keyper`'s announcement
+    /// for `chain_id` with the secret key of the public key share.
+    pub fn verify_proof_of_possession(&self, chain_id: u64, keyper: Address) -> bool {
+        let (Ok(public_key), Ok(proof_of_possession)) = (
+            PublicKey::from_slice(&self.public_key),
+            schnorr::Signature::from_slice(&self.proof_of_possession),
+        ) else {
+            return false;
+        };
+        let hash = Self::proof_of_possession_hash(chain_id, self.epoch, keyper, &self.public_key);
+        let Ok(message) = Message::from_slice(hash.as_slice()) else {
+            return false;
+        };
+        SECP256K1
+            .verify_schnorr(
+                &proof_of_possession,
+                &message,
+                &public_key.x_only_public_key().0,
+            )
+            .is_ok()
+    }
+
+    /// Recovers the address that signed the announcement for `chain_id`,
+    /// None if the signature is invalid.
+    pub fn signer(&self, chain_id: u64) -> Option<Address> {
+        if self.signature.len() != 65 {
+            return None;
+        }
+        let odd_y_parity = match self.signature[64] {
+            0 | 27 => false,
+            1 | 28 => true,
+            _ => return None,
+        };
+        let signature = Signature {
+            r: U256::from_be_slice(&self.signature[..32]),
+            s: U256::from_be_slice(&self.signature[32..64]),
+            odd_y_parity,
+        };
+        signature.recover_signer(Self::signing_hash(chain_id, self.epoch, &self.public_key))
+    }
+}
+
+/// A keyper's share of the secret key of an ended epoch. It is verified against the
+/// announced public key share, so it needs no signature.
+#[derive(
+    borsh::BorshDeserialize,
+    borsh::BorshSerialize,
+    Debug,
+    PartialEq,
+    Eq,
+    Clone,
+    Deserialize,
+    Serialize,
+)]
+pub struct EpochKeyRelease {
+    /// The epoch.
+    pub epoch: u64,
+    /// The keyper releasing its share.
+    pub keyper: Address,
+    /// Secp256k1 secret key share.
+    pub secret_key: B256,
+}
+
+/// Returns the public key of an epoch, the sum of the public key shares of the keypers.
+pub fn epoch_public_key(shares: &[PublicKey]) -> Option<PublicKey> {
+    PublicKey::combine_keys(&shares.iter().collect::<Vec<_>>()).ok()
+}
+
+/// Returns the secret key of an epoch, the sum of the secret key shares of the keypers.
+pub fn epoch_secret_key(shares: &[SecretKey]) -> Option<SecretKey> {
+    let (first, rest) = shares.split_first()?;
+    rest.iter().try_fold(*first, |sum, share| {
+        sum.add_tweak(&Scalar::from(*share)).ok()
+    })
+}
+
+/// Encrypted mempool part of a block: new encrypted transactions to order, and epoch key shares
+/// to publish. The transactions of an ended epoch are decrypted by the block publishing the last
+/// secret key share of the epoch.
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize),
+    derive(serde::Deserialize)
+)]
+#[derive(borsh::BorshDeserialize, borsh::BorshSerialize, Debug, Default, PartialEq, Eq, Clone)]
+pub struct EncryptedBatch {
+    /// Encrypted transactions of the current epoch ordered by the block, appended to the queue.
+    pub ordered: Vec<EncryptedTransaction>,
+    /// Public key shares of later epochs.
+    pub announcements: Vec<EpochKeyAnnouncement>,
+    /// Secret key shares of ended epochs.
+    pub releases: Vec<EpochKeyRelease>,
+}
+
+impl EncryptedBatch {
+    /// Returns true if the batch neither orders transactions nor publishes key shares.
+    pub fn is_empty(&self) -> bool {
+        self.ordered.is_empty() && self.announcements.is_empty() && self.releases.is_empty()
+    }
+}
+
+/// Derives the encryption key and the authentication key of a transaction from the shared
+/// secret point of the ephemeral key and the epoch key.
+fn derive_keys(shared_secret_point: &[u8]) -> ([u8; 32], [u8; 32]) {
+    let derive = |domain: u8| keccak256([shared_secret_point, &[domain]].concat()).0;
+    (derive(0), derive(1))
+}
+
+/// Keyed keccak hash authenticating an encrypted transaction, keccak being safe to use as a MAC
+/// by prefixing the key.
+fn authentication_tag(
+    mac_key: &[u8; 32],
+    epoch: u64,
+    ephemeral_key: &[u8],
+    ciphertext: &[u8],
+) -> B256 {
+    keccak256(
+        [
+            &mac_key[..],
+            &epoch.to_be_bytes(),
+            ephemeral_key,
+            ciphertext,
+        ]
+        .concat(),
+    )
+}
+
+/// XORs `data` with the keystream `keccak256(key || counter)`, counter being a big endian u64
+/// incremented every 32 bytes. Encryption and decryption are the same operation.
+fn apply_keystream(key: &[u8; 32], data: &[u8]) -> Vec<u8> {
+    let mut preimage = [0u8; 40];
+    preimage[..32].copy_from_slice(key);
+    data.chunks(32)
+        .enumerate()
+        .flat_map(|(counter, chunk)| {
+            preimage[32..].copy_from_slice(&(counter as u64).to_be_bytes());
+            let keystream = keccak256(preimage);
+            chunk
+                .iter()
+                .zip(keystream.0)
+                .map(|(byte, key_byte)| byte ^ key_byte)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
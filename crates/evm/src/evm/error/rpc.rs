@@ -8,7 +8,7 @@ use jsonrpsee::core::Error as RpcError;
 use jsonrpsee::types::error::CALL_EXECUTION_FAILED_CODE;
 use jsonrpsee::types::ErrorObject;
 use reth_interfaces::RethError;
-use reth_primitives::{Address, Bytes, U256};
+use reth_primitives::{Address, Bytes, B256, U256};
 use reth_rpc_types::error::EthRpcErrorCode;
 use reth_rpc_types::request::TransactionInputError;
 use reth_rpc_types::BlockError;
@@ -19,6 +19,7 @@ use super::pool::{
     PoolTransactionError,
 };
 use super::result::{internal_rpc_err, invalid_params_rpc_err, rpc_err, rpc_error_with_code};
+use crate::rpc_helpers::QueryContext;
 
 /// Result alias
 pub type EthResult<T> = Result<T, EthApiError>;
@@ -48,6 +49,12 @@ pub enum EthApiError {
     /// Thrown when an unknown block or transaction index is encountered
     #[error("unknown block or tx index")]
     UnknownBlockOrTxIndex,
+    /// When the block of an EIP-1898 block hash parameter is unknown
+    #[error("block {0} not found")]
+    UnknownBlockHash(B256),
+    /// When the node doesn't keep the state of the block, only archive nodes keep all of it
+    #[error("state of block {block} is not available, this node keeps the state from block {oldest} on, query an archive node")]
+    StateHistoryUnavailable { block: u64, oldest: u64 },
     /// When an invalid block range is provided
     #[error("invalid block range")]
     InvalidBlockRange,
@@ -154,7 +161,10 @@ impl From<EthApiError> for ErrorObject<'static> {
             | EthApiError::InvalidBlockData(_)
             | EthApiError::Internal(_)
             | EthApiError::TransactionNotFound => internal_rpc_err(error.to_string()),
-            EthApiError::UnknownBlockNumber | EthApiError::UnknownBlockOrTxIndex => {
+            EthApiError::UnknownBlockNumber
+            | EthApiError::UnknownBlockOrTxIndex
+            | EthApiError::UnknownBlockHash(_)
+            | EthApiError::StateHistoryUnavailable { .. } => {
                 rpc_error_with_code(EthRpcErrorCode::ResourceNotFound.code(), error.to_string())
             }
             EthApiError::UnknownSafeOrFinalizedBlock => {
@@ -543,6 +553,8 @@ pub struct RevertError {
     ///
     /// Note: this is `None` if output was empty
     output: Option<Bytes>,
+    /// The custom error the output decodes to, if known to the ABI registry of the node
+    custom_error: Option<String>,
 }
 
 // === impl RevertError ==
@@ -553,14 +565,27 @@ impl RevertError {
     /// Note: this is intended to wrap an revm output
     pub fn new(output: Bytes) -> Self {
         if output.is_empty() {
-            Self { output: None }
+            Self {
+                output: None,
+                custom_error: None,
+            }
         } else {
             Self {
                 output: Some(output),
+                custom_error: None,
             }
         }
     }
 
+    /// Decodes the output with the ABI registry of the query context
+    pub(crate) fn with_query_context(mut self, ctx: &QueryContext) -> Self {
+        self.custom_error = self
+            .output
+            .as_ref()
+            .and_then(|bytes| ctx.decode_custom_error(bytes));
+        self
+    }
+
     fn error_code(&self) -> i32 {
         EthRpcErrorCode::ExecutionError.code()
     }
@@ -569,11 +594,11 @@ impl RevertError {
 impl std::fmt::Display for RevertError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str("execution reverted")?;
-        if let Some(reason) = self
-            .output
-            .as_ref()
-            .and_then(|bytes| decode_revert_reason(bytes))
-        {
+        if let Some(reason) = self.custom_error.clone().or_else(|| {
+            self.output
+                .as_ref()
+                .and_then(|bytes| decode_revert_reason(bytes))
+        }) {
             write!(f, ": {reason}")?;
         }
         Ok(())
@@ -714,12 +739,13 @@ impl From<SignError> for ErrorObject<'static> {
 
 /// Converts the evm [ExecutionResult] into a result where `Ok` variant is the output bytes if it is
 /// [ExecutionResult::Success].
-pub(crate) fn ensure_success(result: ExecutionResult) -> EthResult<Bytes> {
+pub(crate) fn ensure_success(ctx: &QueryContext, result: ExecutionResult) -> EthResult<Bytes> {
     match result {
         ExecutionResult::Success { output, .. } => Ok(output.into_data()),
-        ExecutionResult::Revert { output, .. } => {
-            Err(RpcInvalidTransactionError::Revert(RevertError::new(output)).into())
-        }
+        ExecutionResult::Revert { output, .. } => Err(RpcInvalidTransactionError::Revert(
+            RevertError::new(output).with_query_context(ctx),
+        )
+        .into()),
         ExecutionResult::Halt { reason, gas_used } => {
             Err(RpcInvalidTransactionError::halt(reason, gas_used).into())
         }
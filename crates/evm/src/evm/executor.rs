@@ -22,8 +22,9 @@ where
     fn new(db: DB, block_env: BlockEnv, config_env: CfgEnvWithHandlerCfg, ext: EXT) -> Self {
         let evm_env = Env::boxed(config_env.cfg_env, block_env.into(), Default::default());
         let evm_context = EvmContext::new_with_env(db, evm_env);
+        let opcode_surcharges = ext.gas_schedule().opcode_surcharges.clone();
         let context = Context::new(evm_context, ext);
-        let handler = citrea_handler(config_env.handler_cfg);
+        let handler = citrea_handler(config_env.handler_cfg, opcode_surcharges);
         let evm = revm::Evm::new(context, handler);
         Self { evm }
     }
@@ -95,6 +96,8 @@ pub(crate) fn execute_multiple_tx<
     tx_results
 }
 
+/// Executes the system transactions of a block. They don't consume the block gas limit of
+/// user transactions, the caller only passes the ones fitting in the system gas limit.
 pub(crate) fn execute_system_txs<
     DB: Database<Error = Infallible> + DatabaseCommit,
     EXT: CitreaExternalExt,
@@ -0,0 +1,54 @@
+use std::collections::BTreeMap;
+
+use reth_primitives::Address;
+use serde::{Deserialize, Serialize};
+
+/// Gas cost of a precompile call, computed as `base + per_word * ceil(input_len / 32)`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PrecompileGasCost {
+    /// Flat cost charged for every call.
+    pub base: u64,
+    /// Cost charged for every 32-byte word of input.
+    #[serde(default)]
+    pub per_word: u64,
+}
+
+impl PrecompileGasCost {
+    /// Returns the gas cost of calling the precompile with an input of `input_len` bytes.
+    pub fn cost(&self, input_len: usize) -> u64 {
+        let words = (input_len as u64).div_ceil(32);
+        self.base
+            .saturating_add(self.per_word.saturating_mul(words))
+    }
+}
+
+/// Gas cost overrides applied on top of the spec's default gas schedule.
+/// Set in genesis and stored in the module state so that native and zk execution
+/// always charge the same amounts.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct GasSchedule {
+    /// Precompile address => gas cost.
+    /// Replaces the gas charged by the precompile, e.g. for Schnorr, P-256 or
+    /// Bitcoin header verification precompiles.
+    #[serde(default)]
+    pub precompiles: BTreeMap<Address, PrecompileGasCost>,
+    /// Opcode => gas charged before the instruction, on top of the spec's cost.
+    /// Opcodes can only be made more expensive, as lowering their cost would let a block
+    /// exceed what is affordable to prove.
+    #[serde(default)]
+    pub opcode_surcharges: BTreeMap<u8, u64>,
+}
+
+impl GasSchedule {
+    /// Returns the overridden gas cost of a precompile call, if the address is repriced.
+    pub fn precompile_cost(&self, address: &Address, input_len: usize) -> Option<u64> {
+        self.precompiles
+            .get(address)
+            .map(|cost| cost.cost(input_len))
+    }
+
+    /// Returns true if no overrides are set.
+    pub fn is_empty(&self) -> bool {
+        self.precompiles.is_empty() && self.opcode_surcharges.is_empty()
+    }
+}
@@ -0,0 +1,188 @@
+use borsh::BorshSerialize;
+use reth_primitives::{keccak256, sign_message, Address, Signature, B256, U256};
+use serde::{Deserialize, Serialize};
+
+/// Parameters of the emergency account blocklist. Governance actions are rejected if not set
+/// in genesis.
+///
+/// Accounts are frozen by governance actions signed by the governance key and posted to DA.
+/// The STF takes them from the DA block every soft confirmation is given for, and the EVM
+/// module verifies them, so every node enforces the same blocklist and the sequencer can
+/// neither censor transactions on its own nor omit the actions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GovernanceConfig {
+    /// Address of the governance key signing the actions.
+    pub address: Address,
+    /// Maximum number of L2 blocks an account can be frozen for by a single action,
+    /// so that every freeze expires even if governance is unavailable.
+    pub max_freeze_blocks: u64,
+}
+
+/// Action of the governance.
+#[derive(
+    borsh::BorshDeserialize,
+    borsh::BorshSerialize,
+    Debug,
+    PartialEq,
+    Eq,
+    Clone,
+    Deserialize,
+    Serialize,
+)]
+#[serde(rename_all = "camelCase", rename_all_fields = "camelCase")]
+pub enum GovernanceAction {
+    /// Freezes accounts until the L2 block `until_block`, exclusive. Transactions sent by or to
+    /// a frozen account are dropped from the blocks. Freezing a frozen account changes its expiry.
+    ///
+    /// Only the sender and the recipient of a transaction are checked, not its inner calls.
+    /// A contract called by another account can still move the funds of a frozen account,
+    /// for example the tokens it approved before the freeze.
+    FreezeAccounts {
+        /// The accounts to freeze.
+        accounts: Vec<Address>,
+        /// First L2 block the accounts are not frozen in anymore.
+        until_block: u64,
+    },
+    /// Unfreezes accounts before their freeze expires.
+    UnfreezeAccounts {
+        /// The accounts to unfreeze.
+        accounts: Vec<Address>,
+    },
+    /// Limits the volume withdrawn from the bridge per window of L1 blocks, or removes the
+    /// limit. The system signer sets it in the bridge contract at the beginning of the next
+    /// L2 block.
+    SetWithdrawalRateLimit {
+        /// The new limit, None to remove it.
+        limit: Option<WithdrawalRateLimit>,
+    },
+    /// Replaces the operators signing off the withdrawals processed on Bitcoin, and the number
+    /// of their signatures required. The system signer sets them in the bridge contract at the
+    /// beginning of the next L2 block.
+    SetBridgeOperators {
+        /// The new operators, at most [`MAX_BRIDGE_OPERATORS`].
+        operators: Vec<Address>,
+        /// Number of operator signatures required to process withdrawals.
+        threshold: u64,
+    },
+}
+
+/// Maximum number of bridge operators set by a governance action, so that the system
+/// transaction setting them fits in its gas limit.
+pub const MAX_BRIDGE_OPERATORS: usize = 32;
+
+/// Limit of the volume withdrawn from the bridge per window of L1 blocks.
+///
+/// A defense in depth against a bug in the proof system draining the bridge at once:
+/// the bridge contract keeps the volume withdrawn in the current window and reverts the
+/// withdrawals over the limit, which can be sent again in a later window.
+#[derive(
+    borsh::BorshDeserialize,
+    borsh::BorshSerialize,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Deserialize,
+    Serialize,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawalRateLimit {
+    /// Maximum volume withdrawn in a window, in wei.
+    pub max_volume: u128,
+    /// Number of L1 blocks of a window. Windows start at the multiples of it.
+    pub window_l1_blocks: u64,
+}
+
+/// A governance action signed by the governance key, as posted to DA.
+#[derive(
+    borsh::BorshDeserialize,
+    borsh::BorshSerialize,
+    Debug,
+    PartialEq,
+    Eq,
+    Clone,
+    Deserialize,
+    Serialize,
+)]
+pub struct SignedGovernanceAction {
+    /// Sequence number of the action, actions are applied in order without gaps.
+    pub nonce: u64,
+    /// The action.
+    pub action: GovernanceAction,
+    /// Secp256k1 signature of [`SignedGovernanceAction::signing_hash`], `r || s || v`.
+    pub signature: Vec<u8>,
+}
+
+impl SignedGovernanceAction {
+    /// Signs the action with the governance secret key, for the chain `chain_id`.
+    pub fn sign(
+        secret_key: B256,
+        chain_id: u64,
+        nonce: u64,
+        action: GovernanceAction,
+    ) -> Result<Self, secp256k1::Error> {
+        let signature = sign_message(secret_key, Self::signing_hash(chain_id, nonce, &action))?;
+        let mut signature_bytes = Vec::with_capacity(65);
+        signature_bytes.extend_from_slice(&signature.r.to_be_bytes::<32>());
+        signature_bytes.extend_from_slice(&signature.s.to_be_bytes::<32>());
+        signature_bytes.push(signature.odd_y_parity as u8);
+        Ok(Self {
+            nonce,
+            action,
+            signature: signature_bytes,
+        })
+    }
+
+    /// Returns the hash signed by the governance key, the keccak hash of the chain id,
+    /// the nonce and the borsh encoded action.
+    pub fn signing_hash(chain_id: u64, nonce: u64, action: &GovernanceAction) -> B256 {
+        let mut preimage = Vec::new();
+        preimage.extend_from_slice(&chain_id.to_be_bytes());
+        preimage.extend_from_slice(&nonce.to_be_bytes());
+        action
+            .serialize(&mut preimage)
+            .expect("Serialization to a vector never fails");
+        keccak256(preimage)
+    }
+
+    /// Recovers the address that signed the action for `chain_id`,
+    /// None if the signature is invalid.
+    pub fn signer(&self, chain_id: u64) -> Option<Address> {
+        if self.signature.len() != 65 {
+            return None;
+        }
+        let odd_y_parity = match self.signature[64] {
+            0 | 27 => false,
+            1 | 28 => true,
+            _ => return None,
+        };
+        let signature = Signature {
+            r: U256::from_be_slice(&self.signature[..32]),
+            s: U256::from_be_slice(&self.signature[32..64]),
+            odd_y_parity,
+        };
+        signature.recover_signer(Self::signing_hash(chain_id, self.nonce, &self.action))
+    }
+}
+
+/// Freeze of an account, recorded on chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountFreeze {
+    /// First L2 block the account is not frozen in anymore.
+    pub until_block: u64,
+    /// Nonce of the governance action that froze the account.
+    pub governance_nonce: u64,
+}
+
+/// A governance action applied on chain, kept as a public record of the blocklist changes.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppliedGovernanceAction {
+    /// The action.
+    pub action: GovernanceAction,
+    /// Number of the L2 block the action was applied in.
+    pub block_number: u64,
+}
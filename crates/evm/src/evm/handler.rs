@@ -1,23 +1,32 @@
 use std::cmp::Ordering;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::mem::size_of;
 use std::sync::Arc;
 
-use revm::handler::register::{EvmHandler, HandleRegisters};
+use revm::handler::register::{EvmHandler, HandleRegisterBox, HandleRegisters};
+use revm::interpreter::opcode::{BoxedInstruction, BoxedInstructionTable, InstructionTables};
 use revm::interpreter::{
     CallInputs, CallOutcome, CreateInputs, CreateOutcome, Gas, InstructionResult, Interpreter,
+    InterpreterResult,
 };
+use revm::precompile::{PrecompileSpecId, Precompiles};
 use revm::primitives::{
-    spec_to_generic, Address, EVMError, Env, HandlerCfg, InvalidTransaction, Log, ResultAndState,
-    Spec, SpecId, B256, U256,
+    spec_to_generic, Address, Bytes, EVMError, Env, HandlerCfg, InvalidTransaction, Log,
+    ResultAndState, Spec, SpecId, B256, U256,
+};
+use revm::{
+    Context, Database, EvmContext, FrameOrResult, FrameResult, InnerEvmContext, Inspector,
+    JournalEntry,
 };
-use revm::{Context, Database, EvmContext, FrameResult, InnerEvmContext, Inspector, JournalEntry};
 
+use super::gas_schedule::GasSchedule;
 use crate::system_events::SYSTEM_SIGNER;
 
-#[derive(Copy, Clone)]
+#[derive(Clone)]
 pub struct TxInfo {
     pub diff_size: u64,
+    /// Contracts created by the transaction, including the ones created by other contracts.
+    pub created_contracts: Vec<Address>,
 }
 
 /// An external context appended to the EVM.
@@ -31,6 +40,8 @@ pub(crate) trait CitreaExternalExt {
     fn set_tx_info(&mut self, info: TxInfo);
     /// Get tx info for the given tx by its hash.
     fn get_tx_info(&self, tx_hash: B256) -> Option<TxInfo>;
+    /// Get gas cost overrides set in genesis.
+    fn gas_schedule(&self) -> &GasSchedule;
 }
 
 // Blanked impl for &mut T: CitreaExternalExt
@@ -47,6 +58,9 @@ impl<T: CitreaExternalExt> CitreaExternalExt for &mut T {
     fn get_tx_info(&self, tx_hash: B256) -> Option<TxInfo> {
         (**self).get_tx_info(tx_hash)
     }
+    fn gas_schedule(&self) -> &GasSchedule {
+        (**self).gas_schedule()
+    }
 }
 
 /// This is an external context to be passed to the EVM.
@@ -56,6 +70,7 @@ pub(crate) struct CitreaExternal {
     l1_fee_rate: u64,
     current_tx_hash: Option<B256>,
     tx_infos: HashMap<B256, TxInfo>,
+    gas_schedule: GasSchedule,
 }
 
 impl CitreaExternal {
@@ -65,6 +80,12 @@ impl CitreaExternal {
             ..Default::default()
         }
     }
+
+    /// Sets the gas cost overrides to be applied during execution.
+    pub(crate) fn with_gas_schedule(mut self, gas_schedule: GasSchedule) -> Self {
+        self.gas_schedule = gas_schedule;
+        self
+    }
 }
 
 impl CitreaExternalExt for CitreaExternal {
@@ -83,7 +104,10 @@ impl CitreaExternalExt for CitreaExternal {
         }
     }
     fn get_tx_info(&self, tx_hash: B256) -> Option<TxInfo> {
-        self.tx_infos.get(&tx_hash).copied()
+        self.tx_infos.get(&tx_hash).cloned()
+    }
+    fn gas_schedule(&self) -> &GasSchedule {
+        &self.gas_schedule
     }
 }
 
@@ -99,9 +123,9 @@ where
     DB: Database,
     I: Inspector<DB>,
 {
-    pub(crate) fn new(inspector: I, l1_fee_rate: u64) -> Self {
+    pub(crate) fn new(inspector: I, l1_fee_rate: u64, gas_schedule: GasSchedule) -> Self {
         Self {
-            ext: CitreaExternal::new(l1_fee_rate),
+            ext: CitreaExternal::new(l1_fee_rate).with_gas_schedule(gas_schedule),
             inspector,
             _ph: Default::default(),
         }
@@ -122,6 +146,9 @@ impl<I, DB> CitreaExternalExt for TracingCitreaExternal<I, DB> {
     fn get_tx_info(&self, tx_hash: B256) -> Option<TxInfo> {
         self.ext.get_tx_info(tx_hash)
     }
+    fn gas_schedule(&self) -> &GasSchedule {
+        self.ext.gas_schedule()
+    }
 }
 
 // Pass all methods to self.inspector
@@ -195,16 +222,75 @@ impl<EXT, DB: Database> CitreaEnv for &'_ mut Context<EXT, DB> {
     }
 }
 
-pub(crate) fn citrea_handler<'a, DB, EXT>(cfg: HandlerCfg) -> EvmHandler<'a, EXT, DB>
+pub(crate) fn citrea_handler<'a, DB, EXT>(
+    cfg: HandlerCfg,
+    opcode_surcharges: BTreeMap<u8, u64>,
+) -> EvmHandler<'a, EXT, DB>
 where
-    DB: Database,
-    EXT: CitreaExternalExt,
+    DB: Database + 'a,
+    EXT: CitreaExternalExt + 'a,
 {
     let mut handler = EvmHandler::mainnet_with_spec(cfg.spec_id);
     handler.append_handler_register(HandleRegisters::Plain(citrea_handle_register));
+    if !opcode_surcharges.is_empty() {
+        handler.append_handler_register(HandleRegisters::Box(surcharge_opcodes_register(
+            opcode_surcharges,
+        )));
+    }
     handler
 }
 
+/// Returns a handle register charging the opcode surcharges of the gas schedule before
+/// their instructions are executed.
+pub(crate) fn surcharge_opcodes_register<'a, DB, EXT>(
+    opcode_surcharges: BTreeMap<u8, u64>,
+) -> HandleRegisterBox<'a, EXT, DB>
+where
+    DB: Database + 'a,
+    EXT: 'a,
+{
+    Box::new(move |handler: &mut EvmHandler<'a, EXT, DB>| {
+        surcharge_opcodes_handle_register(handler, &opcode_surcharges)
+    })
+}
+
+/// Wraps the instructions of the surcharged opcodes, the others are kept as is.
+fn surcharge_opcodes_handle_register<'a, DB, EXT>(
+    handler: &mut EvmHandler<'a, EXT, DB>,
+    opcode_surcharges: &BTreeMap<u8, u64>,
+) where
+    DB: Database + 'a,
+    EXT: 'a,
+{
+    let table = handler
+        .instruction_table
+        .take()
+        .expect("Handler must have instruction table");
+    let mut table: Vec<BoxedInstruction<'a, revm::Evm<'a, EXT, DB>>> = match table {
+        InstructionTables::Plain(table) => table
+            .into_iter()
+            .map(|instruction| Box::new(instruction) as BoxedInstruction<'a, _>)
+            .collect(),
+        InstructionTables::Boxed(table) => table.into_iter().collect(),
+    };
+    for (&opcode, &surcharge) in opcode_surcharges {
+        let instruction = std::mem::replace(&mut table[opcode as usize], Box::new(|_, _| {}));
+        table[opcode as usize] = Box::new(
+            move |interpreter: &mut Interpreter, host: &mut revm::Evm<'a, EXT, DB>| {
+                if !interpreter.gas.record_cost(surcharge) {
+                    interpreter.instruction_result = InstructionResult::OutOfGas;
+                    return;
+                }
+                instruction(interpreter, host)
+            },
+        );
+    }
+    let table: BoxedInstructionTable<'a, revm::Evm<'a, EXT, DB>> = table
+        .try_into()
+        .unwrap_or_else(|_| unreachable!("The instruction table has 256 instructions"));
+    handler.instruction_table = Some(InstructionTables::Boxed(table));
+}
+
 pub(crate) fn citrea_handle_register<DB, EXT>(handler: &mut EvmHandler<'_, EXT, DB>)
 where
     DB: Database,
@@ -213,7 +299,7 @@ where
     spec_to_generic!(handler.cfg.spec_id, {
         let validation = &mut handler.validation;
         let pre_execution = &mut handler.pre_execution;
-        // let execution = &mut handler.execution;
+        let execution = &mut handler.execution;
         let post_execution = &mut handler.post_execution;
         // validation.initial_tx_gas = can be overloaded too
         // validation.env =
@@ -223,7 +309,7 @@ where
         // pre_execution.load_accounts =
         pre_execution.deduct_caller = Arc::new(CitreaHandler::<SPEC, EXT, DB>::deduct_caller);
         // execution.last_frame_return =
-        // execution.call =
+        execution.call = Arc::new(CitreaHandler::<SPEC, EXT, DB>::call);
         // execution.call_return =
         // execution.insert_call_outcome =
         // execution.create =
@@ -290,6 +376,42 @@ impl<SPEC: Spec, EXT: CitreaExternalExt, DB: Database> CitreaHandler<SPEC, EXT,
         }
         revm::handler::mainnet::deduct_caller::<SPEC, EXT, DB>(context)
     }
+    fn call(
+        context: &mut Context<EXT, DB>,
+        mut inputs: Box<CallInputs>,
+    ) -> Result<FrameOrResult, EVMError<DB::Error>> {
+        let repriced_cost = context
+            .external
+            .gas_schedule()
+            .precompile_cost(&inputs.contract, inputs.input.len())
+            .filter(|_| is_precompile::<SPEC>(&inputs.contract));
+        let Some(cost) = repriced_cost else {
+            return revm::handler::mainnet::call::<SPEC, EXT, DB>(context, inputs);
+        };
+
+        // The repriced cost is charged before the precompile runs, so that the precompile
+        // never runs out of gas by its default cost.
+        let gas_limit = inputs.gas_limit;
+        if cost > gas_limit {
+            let mut gas = Gas::new(gas_limit);
+            gas.record_cost(gas_limit);
+            return Ok(FrameOrResult::new_call_result(
+                InterpreterResult {
+                    result: InstructionResult::PrecompileOOG,
+                    gas,
+                    output: Bytes::new(),
+                },
+                inputs.return_memory_offset.clone(),
+            ));
+        }
+        inputs.gas_limit = u64::MAX;
+        let mut frame_or_result = revm::handler::mainnet::call::<SPEC, EXT, DB>(context, inputs)?;
+        // Precompile calls are resolved immediately, other calls create a new frame.
+        if let FrameOrResult::Result(FrameResult::Call(outcome)) = &mut frame_or_result {
+            reprice_precompile_outcome(outcome, gas_limit, cost);
+        }
+        Ok(frame_or_result)
+    }
     fn reimburse_caller(
         context: &mut Context<EXT, DB>,
         gas: &Gas,
@@ -336,9 +458,16 @@ impl<SPEC: Spec, EXT: CitreaExternalExt, DB: Database> CitreaHandler<SPEC, EXT,
         result: FrameResult,
     ) -> Result<ResultAndState, EVMError<<DB as Database>::Error>> {
         let diff_size = calc_diff_size(context).map_err(EVMError::Database)? as u64;
-        let l1_fee_rate = U256::from(context.external.l1_fee_rate());
-        let l1_fee = U256::from(diff_size) * l1_fee_rate;
-        context.external.set_tx_info(TxInfo { diff_size });
+        let l1_fee = calc_l1_fee(diff_size, context.external.l1_fee_rate());
+        let created_contracts = if result.interpreter_result().is_ok() {
+            created_contracts(context)
+        } else {
+            vec![]
+        };
+        context.external.set_tx_info(TxInfo {
+            diff_size,
+            created_contracts,
+        });
         if result.interpreter_result().is_ok() {
             // Deduct L1 fee only if tx is successful.
             if context.is_system_caller() {
@@ -358,6 +487,51 @@ impl<SPEC: Spec, EXT: CitreaExternalExt, DB: Database> CitreaHandler<SPEC, EXT,
     }
 }
 
+/// Returns true if `address` is a precompile of `SPEC`.
+fn is_precompile<SPEC: Spec>(address: &Address) -> bool {
+    Precompiles::new(PrecompileSpecId::from_spec_id(SPEC::SPEC_ID))
+        .addresses()
+        .any(|precompile| precompile == address)
+}
+
+/// Replaces the gas charged by a precompile run without a gas limit with the cost from
+/// the gas schedule, within the gas limit of the call.
+/// Failed calls consume the whole gas limit, same as a regular precompile.
+fn reprice_precompile_outcome(outcome: &mut CallOutcome, gas_limit: u64, cost: u64) {
+    let result = &mut outcome.result;
+    let mut gas = Gas::new(gas_limit);
+    gas.record_cost(if result.is_ok() { cost } else { gas_limit });
+    result.gas = gas;
+}
+
+/// Calculates the fee paid for publishing the state diff of a transaction on DA.
+pub(crate) fn calc_l1_fee(diff_size: u64, l1_fee_rate: u64) -> U256 {
+    U256::from(diff_size) * U256::from(l1_fee_rate)
+}
+
+/// Returns the contracts created by the transaction, sorted by address.
+/// Creations reverted by their frame and contracts destroyed in the same transaction are left out.
+fn created_contracts<EXT, DB: Database>(context: &Context<EXT, DB>) -> Vec<Address> {
+    let mut created: Vec<Address> = context
+        .evm
+        .journaled_state
+        .state
+        .iter()
+        .filter(|(_, account)| {
+            account.is_created()
+                && !account.is_selfdestructed()
+                && account
+                    .info
+                    .code
+                    .as_ref()
+                    .is_some_and(|code| !code.is_empty())
+        })
+        .map(|(address, _)| *address)
+        .collect();
+    created.sort_unstable();
+    created
+}
+
 /// Calculates the diff of the modified state.
 fn calc_diff_size<EXT, DB: Database>(
     context: &mut Context<EXT, DB>,
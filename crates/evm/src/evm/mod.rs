@@ -9,10 +9,16 @@ pub(crate) mod conversions;
 pub(crate) mod db;
 mod db_commit;
 pub(crate) mod db_init;
+mod deposits;
+mod encrypted_mempool;
 pub(crate) mod error;
 pub(crate) mod executor;
+mod gas_schedule;
+mod governance;
 pub(crate) mod handler;
+mod ordering_policy;
 pub(crate) mod primitive_types;
+mod state_diff;
 /// System contracts used for system transactions
 pub mod system_contracts;
 pub(crate) mod system_events;
@@ -20,8 +26,25 @@ pub(crate) mod system_events;
 mod tests;
 
 pub(crate) use call::prepare_call_env;
-pub use primitive_types::RlpEvmTransaction;
+pub use deposits::{DepositKey, DepositParams, DEPOSIT_OUTPUT_INDEX};
+pub use encrypted_mempool::{
+    epoch_public_key, epoch_secret_key, EncryptedBatch, EncryptedMempoolConfig,
+    EncryptedQueueEntry, EncryptedTransaction, EpochKeyAnnouncement, EpochKeyRelease,
+};
+pub use gas_schedule::{GasSchedule, PrecompileGasCost};
+pub use governance::{
+    AccountFreeze, AppliedGovernanceAction, GovernanceAction, GovernanceConfig,
+    SignedGovernanceAction, WithdrawalRateLimit, MAX_BRIDGE_OPERATORS,
+};
+pub use ordering_policy::{
+    ordering_policy_extra_data, ordering_policy_version, LATEST_ORDERING_POLICY_VERSION,
+    ORDERING_POLICY_EXTRA_DATA_PREFIX,
+};
+pub use primitive_types::{ContractCreation, RlpEvmTransaction};
 use sov_state::codec::BcsCodec;
+#[cfg(test)]
+pub use state_diff::{decode_state_diff, StateDiffDecodeError};
+pub use state_diff::{encode_state_diff, AccountDiff};
 
 #[cfg(test)]
 use crate::tests::DEFAULT_CHAIN_ID;
@@ -75,7 +98,7 @@ impl DbAccount {
         }
     }
 
-    fn create_storage_prefix(parent_prefix: &Prefix, address: Address) -> Prefix {
+    pub(crate) fn create_storage_prefix(parent_prefix: &Prefix, address: Address) -> Prefix {
         let mut prefix = parent_prefix.as_aligned_vec().clone().into_inner();
         prefix.extend_from_slice(address.as_ref());
         Prefix::new(prefix)
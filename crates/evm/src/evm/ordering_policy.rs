@@ -0,0 +1,26 @@
+use reth_primitives::Bytes;
+
+/// Prefix of the extra data of blocks built under a versioned ordering policy,
+/// followed by a single byte with the version.
+pub const ORDERING_POLICY_EXTRA_DATA_PREFIX: &[u8] = b"citrea-ordering-v";
+
+/// Latest version of the transaction ordering policy of the sequencer.
+///
+/// Version 1: system transactions first, then transactions by effective priority fee,
+/// highest first, in nonce order for the transactions of a sender.
+pub const LATEST_ORDERING_POLICY_VERSION: u8 = 1;
+
+/// Extra data of the blocks built under the given ordering policy version.
+pub fn ordering_policy_extra_data(version: u8) -> Bytes {
+    [ORDERING_POLICY_EXTRA_DATA_PREFIX, &[version]]
+        .concat()
+        .into()
+}
+
+/// Ordering policy version recorded in the extra data of a block, if any.
+pub fn ordering_policy_version(extra_data: &[u8]) -> Option<u8> {
+    match extra_data.strip_prefix(ORDERING_POLICY_EXTRA_DATA_PREFIX)? {
+        [version] => Some(*version),
+        _ => None,
+    }
+}
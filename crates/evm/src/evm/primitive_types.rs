@@ -54,6 +54,18 @@ pub struct RlpEvmTransaction {
     pub rlp: Vec<u8>,
 }
 
+/// Transaction creating a contract, directly or through other contracts.
+#[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractCreation {
+    /// Hash of the transaction.
+    pub transaction_hash: B256,
+    /// L2 block the transaction was added to.
+    pub block_number: u64,
+    /// Sender of the transaction, not the factory contract for nested creations.
+    pub creator: Address,
+}
+
 #[cfg_attr(
     feature = "native",
     derive(serde::Serialize),
@@ -0,0 +1,294 @@
+//! Compact encoding of EVM state diffs for the DA layer.
+//!
+//! Most writes of ERC-20 and AMM contracts hit a few accounts over and over, and contiguous
+//! storage slots, e.g. the fields of a struct or the reserves of a pair. The encoding takes
+//! advantage of both:
+//! - addresses are written once in a dictionary and referenced by index,
+//! - storage slots are sorted and grouped in runs of consecutive slots, each run written as
+//!   the distance from the end of the previous run and its length,
+//! - integers are written without their leading zeros.
+//!
+//! The batch proofs still publish the raw writes of the state tree to DA, so neither the prover
+//! nor the guest uses this encoding yet. The decoder only exists in test builds, to check that
+//! the encoding round-trips.
+
+use std::collections::BTreeMap;
+#[cfg(test)]
+use std::mem::size_of;
+
+use reth_primitives::{Address, B256, U256};
+use serde::{Deserialize, Serialize};
+
+const NONCE_FLAG: u8 = 1;
+const BALANCE_FLAG: u8 = 1 << 1;
+const CODE_HASH_FLAG: u8 = 1 << 2;
+const STORAGE_FLAG: u8 = 1 << 3;
+
+/// A run starting at a distance from the end of the previous run that fits in a `u64`.
+const RUN_DELTA_TAG: u8 = 0;
+/// A run starting at a slot written in full.
+const RUN_FULL_TAG: u8 = 1;
+
+/// Changes to an account in a state diff.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountDiff {
+    /// The changed account.
+    pub address: Address,
+    /// New nonce, if changed.
+    pub nonce: Option<u64>,
+    /// New balance, if changed.
+    pub balance: Option<U256>,
+    /// New code hash, if changed.
+    pub code_hash: Option<B256>,
+    /// New values of the changed storage slots.
+    pub storage: BTreeMap<U256, U256>,
+}
+
+/// Errors of [`decode_state_diff`].
+#[cfg(test)]
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum StateDiffDecodeError {
+    /// The encoding ended before the end of a field.
+    #[error("Unexpected end of state diff")]
+    UnexpectedEnd,
+    /// An account references an address missing from the dictionary.
+    #[error("Unknown address index {0}")]
+    UnknownAddress(u64),
+    /// A field has an invalid value.
+    #[error("Invalid state diff: {0}")]
+    Invalid(&'static str),
+}
+
+/// Encodes account diffs, in order. The same account can appear more than once,
+/// e.g. in the diffs of consecutive blocks.
+pub fn encode_state_diff(diffs: &[AccountDiff]) -> Vec<u8> {
+    let mut addresses: Vec<Address> = vec![];
+    let mut indices: BTreeMap<Address, u64> = BTreeMap::new();
+    for diff in diffs {
+        indices.entry(diff.address).or_insert_with(|| {
+            addresses.push(diff.address);
+            addresses.len() as u64 - 1
+        });
+    }
+
+    let mut out = vec![];
+    write_varint(&mut out, addresses.len() as u64);
+    for address in &addresses {
+        out.extend_from_slice(address.as_slice());
+    }
+
+    write_varint(&mut out, diffs.len() as u64);
+    for diff in diffs {
+        write_varint(&mut out, indices[&diff.address]);
+
+        let mut flags = 0;
+        if diff.nonce.is_some() {
+            flags |= NONCE_FLAG;
+        }
+        if diff.balance.is_some() {
+            flags |= BALANCE_FLAG;
+        }
+        if diff.code_hash.is_some() {
+            flags |= CODE_HASH_FLAG;
+        }
+        if !diff.storage.is_empty() {
+            flags |= STORAGE_FLAG;
+        }
+        out.push(flags);
+
+        if let Some(nonce) = diff.nonce {
+            write_varint(&mut out, nonce);
+        }
+        if let Some(balance) = diff.balance {
+            write_u256(&mut out, balance);
+        }
+        if let Some(code_hash) = diff.code_hash {
+            out.extend_from_slice(code_hash.as_slice());
+        }
+        if !diff.storage.is_empty() {
+            write_storage(&mut out, &diff.storage);
+        }
+    }
+    out
+}
+
+/// Decodes account diffs encoded by [`encode_state_diff`].
+#[cfg(test)]
+pub fn decode_state_diff(mut bytes: &[u8]) -> Result<Vec<AccountDiff>, StateDiffDecodeError> {
+    let input = &mut bytes;
+
+    let address_count = read_varint(input)?;
+    let mut addresses = vec![];
+    for _ in 0..address_count {
+        addresses.push(Address::from_slice(read_bytes(
+            input,
+            size_of::<Address>(),
+        )?));
+    }
+
+    let diff_count = read_varint(input)?;
+    let mut diffs = vec![];
+    for _ in 0..diff_count {
+        let index = read_varint(input)?;
+        let address = *addresses
+            .get(index as usize)
+            .ok_or(StateDiffDecodeError::UnknownAddress(index))?;
+
+        let flags = read_bytes(input, 1)?[0];
+        if flags & !(NONCE_FLAG | BALANCE_FLAG | CODE_HASH_FLAG | STORAGE_FLAG) != 0 {
+            return Err(StateDiffDecodeError::Invalid("unknown account flags"));
+        }
+
+        let nonce = (flags & NONCE_FLAG != 0)
+            .then(|| read_varint(input))
+            .transpose()?;
+        let balance = (flags & BALANCE_FLAG != 0)
+            .then(|| read_u256(input))
+            .transpose()?;
+        let code_hash = (flags & CODE_HASH_FLAG != 0)
+            .then(|| read_bytes(input, size_of::<B256>()).map(B256::from_slice))
+            .transpose()?;
+        let storage = if flags & STORAGE_FLAG != 0 {
+            read_storage(input)?
+        } else {
+            BTreeMap::new()
+        };
+
+        diffs.push(AccountDiff {
+            address,
+            nonce,
+            balance,
+            code_hash,
+            storage,
+        });
+    }
+
+    if !input.is_empty() {
+        return Err(StateDiffDecodeError::Invalid("trailing bytes"));
+    }
+    Ok(diffs)
+}
+
+fn write_storage(out: &mut Vec<u8>, storage: &BTreeMap<U256, U256>) {
+    // Runs of consecutive slots, as (first slot, values)
+    let mut runs: Vec<(U256, Vec<U256>)> = vec![];
+    for (slot, value) in storage {
+        match runs.last_mut() {
+            Some((start, values)) if start.checked_add(U256::from(values.len())) == Some(*slot) => {
+                values.push(*value)
+            }
+            _ => runs.push((*slot, vec![*value])),
+        }
+    }
+
+    write_varint(out, runs.len() as u64);
+    // Slots right after the end of the previous run are at distance zero
+    let mut next_slot = Some(U256::ZERO);
+    for (start, values) in runs {
+        match next_slot.map(|next_slot| start - next_slot) {
+            Some(delta) if delta <= U256::from(u64::MAX) => {
+                out.push(RUN_DELTA_TAG);
+                write_varint(out, delta.to::<u64>());
+            }
+            _ => {
+                out.push(RUN_FULL_TAG);
+                out.extend_from_slice(&start.to_be_bytes::<32>());
+            }
+        }
+        write_varint(out, values.len() as u64);
+        for value in &values {
+            write_u256(out, *value);
+        }
+        next_slot = start.checked_add(U256::from(values.len()));
+    }
+}
+
+#[cfg(test)]
+fn read_storage(input: &mut &[u8]) -> Result<BTreeMap<U256, U256>, StateDiffDecodeError> {
+    let mut storage = BTreeMap::new();
+    let run_count = read_varint(input)?;
+    let mut next_slot = Some(U256::ZERO);
+    for _ in 0..run_count {
+        let start = match read_bytes(input, 1)?[0] {
+            RUN_DELTA_TAG => {
+                let delta = U256::from(read_varint(input)?);
+                next_slot
+                    .and_then(|next_slot| next_slot.checked_add(delta))
+                    .ok_or(StateDiffDecodeError::Invalid("storage run out of range"))?
+            }
+            RUN_FULL_TAG => U256::from_be_slice(read_bytes(input, size_of::<U256>())?),
+            _ => return Err(StateDiffDecodeError::Invalid("unknown storage run tag")),
+        };
+        let len = read_varint(input)?;
+        if len == 0 {
+            return Err(StateDiffDecodeError::Invalid("empty storage run"));
+        }
+        let mut slot = start;
+        for i in 0..len {
+            if storage.insert(slot, read_u256(input)?).is_some() {
+                return Err(StateDiffDecodeError::Invalid("overlapping storage runs"));
+            }
+            if i + 1 < len {
+                slot = slot
+                    .checked_add(U256::from(1))
+                    .ok_or(StateDiffDecodeError::Invalid("storage run out of range"))?;
+            }
+        }
+        next_slot = slot.checked_add(U256::from(1));
+    }
+    Ok(storage)
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+#[cfg(test)]
+fn read_varint(input: &mut &[u8]) -> Result<u64, StateDiffDecodeError> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = read_bytes(input, 1)?[0];
+        let bits = u64::from(byte & 0x7f);
+        if shift == 63 && bits > 1 {
+            return Err(StateDiffDecodeError::Invalid("varint overflow"));
+        }
+        value |= bits << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(StateDiffDecodeError::Invalid("varint overflow"))
+}
+
+/// Writes the length of the integer without its leading zeros, then its big endian bytes.
+fn write_u256(out: &mut Vec<u8>, value: U256) {
+    let bytes = value.to_be_bytes::<32>();
+    let len = 32 - value.leading_zeros() / 8;
+    out.push(len as u8);
+    out.extend_from_slice(&bytes[32 - len..]);
+}
+
+#[cfg(test)]
+fn read_u256(input: &mut &[u8]) -> Result<U256, StateDiffDecodeError> {
+    let len = read_bytes(input, 1)?[0] as usize;
+    if len > 32 {
+        return Err(StateDiffDecodeError::Invalid(
+            "integer longer than 32 bytes",
+        ));
+    }
+    Ok(U256::from_be_slice(read_bytes(input, len)?))
+}
+
+#[cfg(test)]
+fn read_bytes<'a>(input: &mut &'a [u8], len: usize) -> Result<&'a [u8], StateDiffDecodeError> {
+    if input.len() < len {
+        return Err(StateDiffDecodeError::UnexpectedEnd);
+    }
+    let (bytes, rest) = input.split_at(len);
+    *input = rest;
+    Ok(bytes)
+}
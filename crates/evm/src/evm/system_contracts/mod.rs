@@ -1,7 +1,9 @@
-use alloy_primitives::{address, Address};
+use alloy_primitives::{address, b256, keccak256, Address, B256, U256};
 use ethers_contract::BaseContract;
-use ethers_core::abi::Abi;
-use ethers_core::types::Bytes;
+use ethers_core::abi::{parse_abi, Abi, Token};
+use ethers_core::types::{Bytes, H160};
+
+use super::DepositParams;
 
 fn make_contract_from_abi(abi_json: &str) -> BaseContract {
     let j: serde_json::Value = serde_json::from_str(abi_json).unwrap();
@@ -25,7 +27,12 @@ impl Default for L1BlockHashList {
 }
 
 impl L1BlockHashList {
-    pub(crate) fn address() -> Address {
+    /// Storage slot of `blockNumber`, after the `owner`, `pendingOwner`, `blockHashes`
+    /// and `witnessRoots` slots.
+    pub(crate) const BLOCK_NUMBER_SLOT: u64 = 4;
+
+    /// Address the contract is deployed at in genesis.
+    pub fn address() -> Address {
         address!("3100000000000000000000000000000000000001")
     }
 
@@ -51,11 +58,274 @@ impl L1BlockHashList {
             .expect("ABI for system contract should be correct")
     }
 
-    #[allow(dead_code)]
-    pub(crate) fn get_witness_root_by_number(&self, block_number: u64) -> Bytes {
+    /// Return input data to query the witness root of a block by its number
+    pub fn get_witness_root_by_number(&self, block_number: u64) -> Bytes {
         let args = ethereum_types::U256::from(block_number);
         self.base_contract
             .encode("getWitnessRootByNumber", args)
             .expect("ABI for system contract should be correct")
     }
+
+    /// Return input data to query the witness root of a block by its hash
+    pub fn get_witness_root_by_hash(&self, block_hash: [u8; 32]) -> Bytes {
+        self.base_contract
+            .encode("getWitnessRootByHash", block_hash)
+            .expect("ABI for system contract should be correct")
+    }
+
+    /// Return input data to query the number of the next L1 block to be recorded,
+    /// one more than the number of the latest recorded L1 block
+    pub fn block_number(&self) -> Bytes {
+        self.base_contract
+            .encode("blockNumber", ())
+            .expect("ABI for system contract should be correct")
+    }
+}
+
+/// Bridge wrapper, covering deposits and the calls that process withdrawals with operator signatures.
+pub struct Bridge {
+    base_contract: BaseContract,
+}
+
+impl Default for Bridge {
+    fn default() -> Self {
+        let abi = parse_abi(&[
+            "function deposit((bytes4,bytes2,bytes,bytes,bytes,bytes4,bytes,uint256,uint256) p)",
+            "function setOperators(address[] _operators, uint256 _threshold)",
+            "function processWithdrawals(uint256 _processedWithdrawalCount, bytes32 _bitcoinTxId, bytes[] _signatures)",
+            "function processedWithdrawalCount() view returns (uint256)",
+            "function setWithdrawalRateLimit(uint256 _limit, uint256 _windowL1Blocks)",
+        ])
+        .expect("ABI for system contract should be correct");
+        Self {
+            base_contract: BaseContract::from(abi),
+        }
+    }
+}
+
+impl Bridge {
+    /// Address the contract is deployed at.
+    pub fn address() -> Address {
+        address!("3100000000000000000000000000000000000002")
+    }
+
+    /// Return input data to mint the deposit of `params`
+    pub fn deposit(&self, params: &DepositParams) -> Bytes {
+        let args = Token::Tuple(vec![
+            Token::FixedBytes(params.version.to_vec()),
+            Token::FixedBytes(params.flag.to_vec()),
+            Token::Bytes(params.vin.clone()),
+            Token::Bytes(params.vout.clone()),
+            Token::Bytes(params.witness.clone()),
+            Token::FixedBytes(params.locktime.to_vec()),
+            Token::Bytes(params.intermediate_nodes.clone()),
+            Token::Uint(ethereum_types::U256(params.block_height.into_limbs())),
+            Token::Uint(ethereum_types::U256(params.index.into_limbs())),
+        ]);
+        self.base_contract
+            .encode("deposit", args)
+            .expect("ABI for system contract should be correct")
+    }
+
+    /// Decodes the parameters of a `deposit` call from its input data,
+    /// None if the input is not a well formed `deposit` call.
+    pub(crate) fn decode_deposit(&self, input: &[u8]) -> Option<DepositParams> {
+        let Token::Tuple(fields) = self
+            .base_contract
+            .decode::<Token, _>("deposit", input)
+            .ok()?
+        else {
+            return None;
+        };
+        let fields: [Token; 9] = fields.try_into().ok()?;
+        let [version, flag, vin, vout, witness, locktime, intermediate_nodes, block_height, index] =
+            fields;
+        Some(DepositParams {
+            version: version.into_fixed_bytes()?.try_into().ok()?,
+            flag: flag.into_fixed_bytes()?.try_into().ok()?,
+            vin: vin.into_bytes()?,
+            vout: vout.into_bytes()?,
+            witness: witness.into_bytes()?,
+            locktime: locktime.into_fixed_bytes()?.try_into().ok()?,
+            intermediate_nodes: intermediate_nodes.into_bytes()?,
+            block_height: U256::from_limbs(block_height.into_uint()?.0),
+            index: U256::from_limbs(index.into_uint()?.0),
+        })
+    }
+
+    /// Return input data to replace the operator set and the number of signatures required from it
+    pub fn set_operators(&self, operators: &[Address], threshold: u64) -> Bytes {
+        let operators: Vec<H160> = operators
+            .iter()
+            .map(|operator| H160::from(operator.into_array()))
+            .collect();
+        let args = (operators, ethereum_types::U256::from(threshold));
+        self.base_contract
+            .encode("setOperators", args)
+            .expect("ABI for system contract should be correct")
+    }
+
+    /// Return input data to mark the withdrawals before `processed_withdrawal_count` as paid out
+    /// by `bitcoin_tx_id`. Signatures must be sorted by signer address in ascending order.
+    pub fn process_withdrawals(
+        &self,
+        processed_withdrawal_count: u64,
+        bitcoin_tx_id: [u8; 32],
+        signatures: Vec<Bytes>,
+    ) -> Bytes {
+        let args = (
+            ethereum_types::U256::from(processed_withdrawal_count),
+            bitcoin_tx_id,
+            signatures,
+        );
+        self.base_contract
+            .encode("processWithdrawals", args)
+            .expect("ABI for system contract should be correct")
+    }
+
+    pub(crate) fn set_withdrawal_rate_limit(&self, limit: u128, window_l1_blocks: u64) -> Bytes {
+        let args = (
+            ethereum_types::U256::from(limit),
+            ethereum_types::U256::from(window_l1_blocks),
+        );
+        self.base_contract
+            .encode("setWithdrawalRateLimit", args)
+            .expect("ABI for system contract should be correct")
+    }
+
+    /// Return input data to query the number of processed withdrawals
+    pub fn processed_withdrawal_count(&self) -> Bytes {
+        self.base_contract
+            .encode("processedWithdrawalCount", ())
+            .expect("ABI for system contract should be correct")
+    }
+
+    /// Returns the digest operators sign to process withdrawals,
+    /// the same as `withdrawalProcessingDigest` of the bridge deployed at `bridge` on chain `chain_id`.
+    pub fn withdrawal_processing_digest(
+        chain_id: u64,
+        bridge: Address,
+        processed_withdrawal_count: u64,
+        bitcoin_tx_id: [u8; 32],
+    ) -> B256 {
+        let encoded = ethers_core::abi::encode(&[
+            Token::Uint(ethereum_types::U256::from(chain_id)),
+            Token::Address(H160::from(bridge.into_array())),
+            Token::Uint(ethereum_types::U256::from(processed_withdrawal_count)),
+            Token::FixedBytes(bitcoin_tx_id.to_vec()),
+        ]);
+        keccak256(encoded)
+    }
+}
+
+/// StateRootAnchor wrapper.
+pub struct StateRootAnchor {
+    base_contract: BaseContract,
+}
+
+impl Default for StateRootAnchor {
+    fn default() -> Self {
+        let abi = parse_abi(&[
+            "function anchor(uint256 _blockNumber, bytes32 _stateRoot, uint256 _timestamp)",
+            "function getStateRoot(uint256 _blockNumber) view returns (bytes32)",
+            "function isRecentStateRoot(bytes32 _stateRoot, uint256 _maxAge) view returns (bool)",
+        ])
+        .expect("ABI for system contract should be correct");
+        Self {
+            base_contract: BaseContract::from(abi),
+        }
+    }
+}
+
+impl StateRootAnchor {
+    /// Address the contract is deployed at in genesis.
+    pub fn address() -> Address {
+        address!("3100000000000000000000000000000000000003")
+    }
+
+    pub(crate) fn anchor(&self, block_number: u64, state_root: [u8; 32], timestamp: u64) -> Bytes {
+        let args = (
+            ethereum_types::U256::from(block_number),
+            state_root,
+            ethereum_types::U256::from(timestamp),
+        );
+        self.base_contract
+            .encode("anchor", args)
+            .expect("ABI for system contract should be correct")
+    }
+
+    /// Return input data to query the anchored state root of a block
+    pub fn get_state_root(&self, block_number: u64) -> Bytes {
+        let args = ethereum_types::U256::from(block_number);
+        self.base_contract
+            .encode("getStateRoot", args)
+            .expect("ABI for system contract should be correct")
+    }
+
+    /// Return input data to check that a state root was anchored at most `max_age` blocks ago
+    pub fn is_recent_state_root(&self, state_root: [u8; 32], max_age: u64) -> Bytes {
+        let args = (state_root, ethereum_types::U256::from(max_age));
+        self.base_contract
+            .encode("isRecentStateRoot", args)
+            .expect("ABI for system contract should be correct")
+    }
+}
+
+/// SystemContractProxy wrapper, the upgradeable proxy system contracts are deployed behind.
+///
+/// Only the system signer can upgrade a proxy, so upgrades only happen through the system
+/// transactions of the [`SystemContractUpgrade`]s scheduled in genesis.
+pub struct SystemContractProxy {
+    base_contract: BaseContract,
+}
+
+impl Default for SystemContractProxy {
+    fn default() -> Self {
+        let abi = parse_abi(&[
+            "function upgradeToAndCall(address _implementation, bytes _data) payable",
+            "function implementation() view returns (address)",
+        ])
+        .expect("ABI for system contract should be correct");
+        Self {
+            base_contract: BaseContract::from(abi),
+        }
+    }
+}
+
+impl SystemContractProxy {
+    /// EIP-1967 storage slot of the implementation address,
+    /// `keccak256("eip1967.proxy.implementation") - 1`.
+    pub const IMPLEMENTATION_SLOT: B256 =
+        b256!("360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc");
+
+    pub(crate) fn upgrade_to_and_call(&self, implementation: Address, data: Bytes) -> Bytes {
+        let args = (H160::from(implementation.into_array()), data);
+        self.base_contract
+            .encode("upgradeToAndCall", args)
+            .expect("ABI for system contract should be correct")
+    }
+
+    /// Return input data to query the address of the current implementation
+    pub fn implementation(&self) -> Bytes {
+        self.base_contract
+            .encode("implementation", ())
+            .expect("ABI for system contract should be correct")
+    }
+}
+
+/// Upgrade of a system contract deployed behind a [`SystemContractProxy`], scheduled in genesis.
+///
+/// At the beginning of the L2 block `block_number`, the system signer deploys the new
+/// implementation with `code` and upgrades the proxy to it, calling it with `call_data` if not empty.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct SystemContractUpgrade {
+    /// Number of the L2 block the upgrade happens in, the fork height.
+    pub block_number: u64,
+    /// Address of the proxy to upgrade.
+    pub proxy: Address,
+    /// Init code of the new implementation.
+    pub code: reth_primitives::Bytes,
+    /// Call data of the initialization of the new implementation, made through the proxy.
+    #[serde(default)]
+    pub call_data: reth_primitives::Bytes,
 }
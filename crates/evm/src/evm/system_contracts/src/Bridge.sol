@@ -38,12 +38,25 @@ contract Bridge is Ownable, MerkleTree {
     
     mapping(bytes32 => bool) public blockHashes;
     mapping(bytes32 => bool) public spentWtxIds;
+
+    address[] public operators;
+    mapping(address => bool) public isOperator;
+    uint256 public operatorThreshold;
+    uint256 public processedWithdrawalCount;
+
+    uint256 public withdrawalLimit;
+    uint256 public withdrawalWindowL1Blocks;
+    uint256 public withdrawalWindowStart;
+    uint256 public withdrawalWindowVolume;
     
 
     event Deposit(bytes32 wtxId, uint256 timestamp);
     event Withdrawal(bytes32  bitcoin_address, uint32 indexed leafIndex, uint256 timestamp);
     event DepositScriptUpdate(bytes depositScript, bytes scriptSuffix, uint256 requiredSigsCount);
     event OperatorUpdated(address oldOperator, address newOperator);
+    event OperatorsUpdated(address[] operators, uint256 threshold);
+    event WithdrawalsProcessed(uint256 processedWithdrawalCount, bytes32 bitcoinTxId);
+    event WithdrawalRateLimitUpdated(uint256 limit, uint256 windowL1Blocks);
 
     modifier onlyOperator() {
         require(msg.sender == operator, "caller is not the operator");
@@ -131,6 +144,7 @@ contract Bridge is Ownable, MerkleTree {
     /// @param bitcoin_address The Bitcoin address of the receiver
     function withdraw(bytes32 bitcoin_address) external payable {
         require(msg.value == DEPOSIT_AMOUNT, "Invalid withdraw amount");
+        spendWithdrawalAllowance(msg.value);
         insertWithdrawalTree(bitcoin_address);
         emit Withdrawal(bitcoin_address, nextIndex, block.timestamp);
     }
@@ -140,6 +154,7 @@ contract Bridge is Ownable, MerkleTree {
     /// @param bitcoin_addresses The Bitcoin addresses of the receivers
     function batchWithdraw(bytes32[] calldata bitcoin_addresses) external payable {
         require(msg.value == DEPOSIT_AMOUNT * bitcoin_addresses.length, "Invalid withdraw amount");
+        spendWithdrawalAllowance(msg.value);
         for (uint i = 0; i < bitcoin_addresses.length; i++) {
             insertWithdrawalTree(bitcoin_addresses[i]);
             emit Withdrawal(bitcoin_addresses[i], nextIndex, block.timestamp);
@@ -153,6 +168,107 @@ contract Bridge is Ownable, MerkleTree {
         emit OperatorUpdated(operator, _operator);
     }
     
+    /// @notice Sets the operator set that signs off withdrawal processing on Bitcoin, and the number of signatures required
+    /// @param _operators Addresses of the operators
+    /// @param _threshold Number of operator signatures required to process withdrawals
+    function setOperators(address[] calldata _operators, uint256 _threshold) external onlyOwner {
+        require(_threshold != 0 && _threshold <= _operators.length, "Invalid operator threshold");
+
+        for (uint i = 0; i < operators.length; i++) {
+            isOperator[operators[i]] = false;
+        }
+        for (uint i = 0; i < _operators.length; i++) {
+            require(_operators[i] != address(0), "Operator cannot be zero address");
+            require(!isOperator[_operators[i]], "Duplicate operator");
+            isOperator[_operators[i]] = true;
+        }
+        operators = _operators;
+        operatorThreshold = _threshold;
+
+        emit OperatorsUpdated(_operators, _threshold);
+    }
+
+    /// @notice Limits the volume withdrawn per window of L1 blocks, a defense in depth against a bug draining the bridge at once
+    /// @dev Set by the system signer on governance actions. Windows start at the multiples of `_windowL1Blocks`,
+    /// the volume withdrawn in the current window still counts against the new limit
+    /// @param _limit Maximum volume withdrawn in a window, 0 to remove the limit
+    /// @param _windowL1Blocks Number of L1 blocks of a window
+    function setWithdrawalRateLimit(uint256 _limit, uint256 _windowL1Blocks) external onlyOwner {
+        require(_limit == 0 || _windowL1Blocks != 0, "Withdrawal window cannot be empty");
+
+        withdrawalLimit = _limit;
+        withdrawalWindowL1Blocks = _windowL1Blocks;
+
+        emit WithdrawalRateLimitUpdated(_limit, _windowL1Blocks);
+    }
+
+    /// @notice Marks the withdrawals before `_processedWithdrawalCount` as paid out on Bitcoin
+    /// @dev Requires `operatorThreshold` signatures of distinct operators over `withdrawalProcessingDigest`, sorted by signer address
+    /// @param _processedWithdrawalCount The number of withdrawals processed so far, including the ones paid out by `_bitcoinTxId`
+    /// @param _bitcoinTxId The Bitcoin transaction paying out the withdrawals
+    /// @param _signatures 65 byte `r, s, v` signatures of the operators
+    function processWithdrawals(uint256 _processedWithdrawalCount, bytes32 _bitcoinTxId, bytes[] calldata _signatures) external {
+        require(_processedWithdrawalCount > processedWithdrawalCount, "Withdrawals are already processed");
+        require(_processedWithdrawalCount <= nextIndex, "Withdrawals are not requested yet");
+        verifyOperatorSignatures(withdrawalProcessingDigest(_processedWithdrawalCount, _bitcoinTxId), _signatures);
+
+        processedWithdrawalCount = _processedWithdrawalCount;
+
+        emit WithdrawalsProcessed(_processedWithdrawalCount, _bitcoinTxId);
+    }
+
+    /// @notice Returns the digest operators sign to process withdrawals
+    /// @param _processedWithdrawalCount The number of withdrawals processed so far
+    /// @param _bitcoinTxId The Bitcoin transaction paying out the withdrawals
+    function withdrawalProcessingDigest(uint256 _processedWithdrawalCount, bytes32 _bitcoinTxId) public view returns (bytes32) {
+        return keccak256(abi.encode(block.chainid, address(this), _processedWithdrawalCount, _bitcoinTxId));
+    }
+
+    /// @notice Checks that `_digest` is signed by at least `operatorThreshold` distinct operators
+    /// @dev Signatures must be sorted by signer address in ascending order, which rules out counting a signer twice
+    /// @param _digest The signed digest
+    /// @param _signatures 65 byte `r, s, v` signatures of the operators
+    function verifyOperatorSignatures(bytes32 _digest, bytes[] calldata _signatures) internal view {
+        require(operatorThreshold != 0, "Operators are not set");
+        require(_signatures.length >= operatorThreshold, "Not enough operator signatures");
+
+        address lastSigner = address(0);
+        for (uint i = 0; i < _signatures.length; i++) {
+            address signer = recoverSigner(_digest, _signatures[i]);
+            require(isOperator[signer], "Signer is not an operator");
+            require(signer > lastSigner, "Signers are not sorted");
+            lastSigner = signer;
+        }
+    }
+
+    /// @notice Counts `_volume` in the volume withdrawn in the current window, reverts if it exceeds the limit
+    /// @dev The current L1 block is the last one recorded in the block hash list
+    /// @param _volume The volume withdrawn
+    function spendWithdrawalAllowance(uint256 _volume) internal {
+        uint256 _limit = withdrawalLimit;
+        if (_limit == 0) {
+            return;
+        }
+
+        uint256 _l1Block = BLOCK_HASH_LIST.blockNumber() - 1;
+        uint256 _windowStart = _l1Block - _l1Block % withdrawalWindowL1Blocks;
+        uint256 _windowVolume = withdrawalWindowVolume;
+        if (_windowStart != withdrawalWindowStart) {
+            withdrawalWindowStart = _windowStart;
+            _windowVolume = 0;
+        }
+        require(_windowVolume + _volume <= _limit, "Withdrawal rate limit exceeded");
+        withdrawalWindowVolume = _windowVolume + _volume;
+    }
+
+    function recoverSigner(bytes32 _digest, bytes calldata _signature) internal pure returns (address) {
+        require(_signature.length == 65, "Invalid signature length");
+        bytes32 r = bytes32(_signature[0:32]);
+        bytes32 s = bytes32(_signature[32:64]);
+        uint8 v = uint8(_signature[64]);
+        return ecrecover(_digest, v, r, s);
+    }
+
     /// @notice Checks if two byte sequences are equal
     /// @dev This is not efficient, and a better approach would be doing a hash based comparison but as this is ran in a zkEVM, hashing is inefficient
     /// @param a First byte sequence
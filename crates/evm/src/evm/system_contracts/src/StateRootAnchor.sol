@@ -0,0 +1,59 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.13;
+
+import "../lib/Ownable.sol";
+
+/// @title A system contract that stores recent state roots of Citrea itself
+/// @author Citrea
+
+//  WARNING: Integrators must be aware of the following points:
+// - State roots are anchored periodically, not on every block, so most block numbers have no state root recorded
+// - The state root of a block is only known in the next block, so the latest anchored block is always behind the current block
+
+contract StateRootAnchor is Ownable {
+    struct Anchor {
+        bytes32 stateRoot;
+        uint256 timestamp;
+    }
+
+    mapping(uint256 => Anchor) public anchors;
+    mapping(bytes32 => uint256) public anchoredBlockNumbers;
+    uint256 public latestAnchoredBlockNumber;
+
+    event StateRootAnchored(uint256 blockNumber, bytes32 stateRoot, uint256 timestamp);
+    constructor() Ownable(){ }
+
+    /// @notice Anchors the state root of an L2 block
+    /// @dev Block numbers must increase, as no anchor should be overwritten, so the genesis block cannot be anchored
+    /// @param _blockNumber Number of the L2 block
+    /// @param _stateRoot State root after the L2 block
+    /// @param _timestamp Timestamp of the L2 block
+    function anchor(uint256 _blockNumber, bytes32 _stateRoot, uint256 _timestamp) external onlyOwner {
+        require(_blockNumber > latestAnchoredBlockNumber, "Block already anchored");
+        anchors[_blockNumber] = Anchor(_stateRoot, _timestamp);
+        anchoredBlockNumbers[_stateRoot] = _blockNumber;
+        latestAnchoredBlockNumber = _blockNumber;
+        emit StateRootAnchored(_blockNumber, _stateRoot, _timestamp);
+    }
+
+    /// @param _blockNumber Number of the L2 block to get the state root for
+    /// @return State root of the given block, 0 if the block is not anchored
+    function getStateRoot(uint256 _blockNumber) external view returns (bytes32) {
+        return anchors[_blockNumber].stateRoot;
+    }
+
+    /// @param _blockNumber Number of the L2 block to get the timestamp for
+    /// @return Timestamp of the given block, 0 if the block is not anchored
+    function getTimestamp(uint256 _blockNumber) external view returns (uint256) {
+        return anchors[_blockNumber].timestamp;
+    }
+
+    /// @notice Checks that a state root was anchored at most `_maxAge` blocks before the latest anchored block
+    /// @param _stateRoot State root to check
+    /// @param _maxAge Maximum number of L2 blocks between the anchored block and the latest anchored block
+    /// @return If the state root is anchored and recent
+    function isRecentStateRoot(bytes32 _stateRoot, uint256 _maxAge) external view returns (bool) {
+        uint256 _blockNumber = anchoredBlockNumbers[_stateRoot];
+        return _blockNumber != 0 && latestAnchoredBlockNumber - _blockNumber <= _maxAge;
+    }
+}
@@ -0,0 +1,74 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.13;
+
+/// @title Upgradeable proxy system contracts are deployed behind
+/// @author Citrea
+
+//  WARNING: Integrators must be aware of the following points:
+// - The admin is the system signer, so upgrades only happen through system transactions scheduled in genesis
+// - Implementations must not declare `upgradeToAndCall(address,bytes)`, calls to it are never forwarded to them
+
+contract SystemContractProxy {
+    /// @dev EIP-1967 implementation slot, `bytes32(uint256(keccak256("eip1967.proxy.implementation")) - 1)`
+    bytes32 internal constant IMPLEMENTATION_SLOT = 0x360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc;
+    address public constant SYSTEM_SIGNER = 0xdeaDDeADDEaDdeaDdEAddEADDEAdDeadDEADDEaD;
+
+    event Upgraded(address indexed implementation);
+
+    constructor(address _implementation) {
+        _setImplementation(_implementation);
+    }
+
+    /// @notice Replaces the implementation and calls it with `_data`, skipped if `_data` is empty
+    /// @param _implementation Address of the new implementation
+    /// @param _data Call data of the initialization of the new implementation
+    function upgradeToAndCall(address _implementation, bytes calldata _data) external payable {
+        require(msg.sender == SYSTEM_SIGNER, "Caller is not system signer");
+        _setImplementation(_implementation);
+        if (_data.length > 0) {
+            (bool success, bytes memory result) = _implementation.delegatecall(_data);
+            if (!success) {
+                assembly {
+                    revert(add(result, 32), mload(result))
+                }
+            }
+        }
+    }
+
+    /// @return Address of the current implementation
+    function implementation() external view returns (address) {
+        return _implementation();
+    }
+
+    fallback() external payable {
+        address _impl = _implementation();
+        assembly {
+            calldatacopy(0, 0, calldatasize())
+            let result := delegatecall(gas(), _impl, 0, calldatasize(), 0, 0)
+            returndatacopy(0, 0, returndatasize())
+            switch result
+            case 0 { revert(0, returndatasize()) }
+            default { return(0, returndatasize()) }
+        }
+    }
+
+    receive() external payable {
+        revert("Proxy does not accept plain transfers");
+    }
+
+    function _implementation() internal view returns (address _impl) {
+        bytes32 slot = IMPLEMENTATION_SLOT;
+        assembly {
+            _impl := sload(slot)
+        }
+    }
+
+    function _setImplementation(address _impl) internal {
+        require(_impl.code.length > 0, "Implementation is not a contract");
+        bytes32 slot = IMPLEMENTATION_SLOT;
+        assembly {
+            sstore(slot, _impl)
+        }
+        emit Upgraded(_impl);
+    }
+}
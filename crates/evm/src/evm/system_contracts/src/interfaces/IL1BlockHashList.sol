@@ -2,6 +2,7 @@
 pragma solidity ^0.8.13;
 
 interface IL1BlockHashList {
+    function blockNumber() external view returns (uint256);
     function initializeBlockNumber(uint256) external;
     function setBlockInfo(bytes32, bytes32) external;
     function getBlockHash(uint256) external view returns (bytes32);
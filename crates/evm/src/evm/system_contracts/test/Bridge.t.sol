@@ -246,10 +246,179 @@ contract BridgeTest is Test {
         assertEq(5, bridge.requiredSigsCount());
     }
 
+    function testProcessWithdrawals() public {
+        (, uint256[] memory keys) = setUpOperators(3, 2);
+        doWithdrawals(2);
+
+        bytes32 bitcoinTxId = keccak256("CITREA_WITHDRAWAL");
+        bytes[] memory signatures = signWithdrawalProcessing(keys, 2, bitcoinTxId, 2);
+        bridge.processWithdrawals(2, bitcoinTxId, signatures);
+        assertEq(bridge.processedWithdrawalCount(), 2);
+
+        vm.expectRevert("Withdrawals are already processed");
+        bridge.processWithdrawals(2, bitcoinTxId, signatures);
+    }
+
+    function testCannotProcessWithdrawalsWithoutThreshold() public {
+        (, uint256[] memory keys) = setUpOperators(3, 2);
+        doWithdrawals(1);
+
+        bytes32 bitcoinTxId = keccak256("CITREA_WITHDRAWAL");
+        bytes[] memory signatures = signWithdrawalProcessing(keys, 1, bitcoinTxId, 1);
+        vm.expectRevert("Not enough operator signatures");
+        bridge.processWithdrawals(1, bitcoinTxId, signatures);
+    }
+
+    function testCannotProcessWithdrawalsWithDuplicateSignatures() public {
+        (, uint256[] memory keys) = setUpOperators(3, 2);
+        doWithdrawals(1);
+
+        bytes32 bitcoinTxId = keccak256("CITREA_WITHDRAWAL");
+        bytes[] memory signatures = signWithdrawalProcessing(keys, 1, bitcoinTxId, 1);
+        bytes[] memory duplicated = new bytes[](2);
+        duplicated[0] = signatures[0];
+        duplicated[1] = signatures[0];
+        vm.expectRevert("Signers are not sorted");
+        bridge.processWithdrawals(1, bitcoinTxId, duplicated);
+    }
+
+    function testCannotProcessWithdrawalsSignedByNonOperator() public {
+        setUpOperators(3, 1);
+        doWithdrawals(1);
+
+        (, uint256 key) = makeAddrAndKey("citrea_non_operator");
+        uint256[] memory keys = new uint256[](1);
+        keys[0] = key;
+        bytes32 bitcoinTxId = keccak256("CITREA_WITHDRAWAL");
+        bytes[] memory signatures = signWithdrawalProcessing(keys, 1, bitcoinTxId, 1);
+        vm.expectRevert("Signer is not an operator");
+        bridge.processWithdrawals(1, bitcoinTxId, signatures);
+    }
+
+    function testCannotProcessUnrequestedWithdrawals() public {
+        (, uint256[] memory keys) = setUpOperators(3, 2);
+        doWithdrawals(1);
+
+        bytes32 bitcoinTxId = keccak256("CITREA_WITHDRAWAL");
+        bytes[] memory signatures = signWithdrawalProcessing(keys, 2, bitcoinTxId, 2);
+        vm.expectRevert("Withdrawals are not requested yet");
+        bridge.processWithdrawals(2, bitcoinTxId, signatures);
+    }
+
+    function testSetOperatorsReplacesOperatorSet() public {
+        (address[] memory signers, ) = setUpOperators(3, 2);
+        (address[] memory newSigners, ) = setUpOperators(2, 2);
+
+        for (uint i = 0; i < signers.length; i++) {
+            assertFalse(bridge.isOperator(signers[i]));
+        }
+        for (uint i = 0; i < newSigners.length; i++) {
+            assertTrue(bridge.isOperator(newSigners[i]));
+        }
+        assertEq(bridge.operatorThreshold(), 2);
+    }
+
+    function testCannotSetOperatorsWithInvalidThreshold() public {
+        address[] memory signers = new address[](2);
+        signers[0] = makeAddr("citrea_operator_0");
+        signers[1] = makeAddr("citrea_operator_1");
+        vm.expectRevert("Invalid operator threshold");
+        bridge.setOperators(signers, 3);
+        vm.expectRevert("Invalid operator threshold");
+        bridge.setOperators(signers, 0);
+    }
+
+    function testCannotSetOperatorsIfNotOwner() public {
+        address[] memory signers = new address[](1);
+        signers[0] = operator;
+        vm.startPrank(user);
+        vm.expectRevert("Caller is not owner");
+        bridge.setOperators(signers, 1);
+    }
+
+    function testWithdrawalRateLimit() public {
+        bridge.setWithdrawalRateLimit(2 * DEPOSIT_AMOUNT, 10);
+        doWithdrawals(2);
+        assertEq(bridge.withdrawalWindowVolume(), 2 * DEPOSIT_AMOUNT);
+
+        vm.deal(user, 2 * DEPOSIT_AMOUNT);
+        vm.startPrank(user);
+        vm.expectRevert("Withdrawal rate limit exceeded");
+        bridge.withdraw{value: DEPOSIT_AMOUNT}(bytes32(uint256(2)));
+        bytes32[] memory btc_addresses = new bytes32[](2);
+        vm.expectRevert("Withdrawal rate limit exceeded");
+        bridge.batchWithdraw{value: 2 * DEPOSIT_AMOUNT}(btc_addresses);
+        vm.stopPrank();
+
+        // The next window starts with the L1 block INITIAL_BLOCK_NUMBER + 10
+        L1BlockHashList l1BlockHashList = bridge.BLOCK_HASH_LIST();
+        for (uint i = 0; i < 10; i++) {
+            l1BlockHashList.setBlockInfo(keccak256(abi.encodePacked(i)), witnessRoot);
+        }
+        vm.startPrank(user);
+        bridge.batchWithdraw{value: 2 * DEPOSIT_AMOUNT}(btc_addresses);
+        vm.stopPrank();
+        assertEq(bridge.withdrawalWindowStart(), INITIAL_BLOCK_NUMBER + 10);
+        assertEq(bridge.withdrawalWindowVolume(), 2 * DEPOSIT_AMOUNT);
+    }
+
+    function testRemoveWithdrawalRateLimit() public {
+        bridge.setWithdrawalRateLimit(DEPOSIT_AMOUNT, 10);
+        doWithdrawals(1);
+        bridge.setWithdrawalRateLimit(0, 0);
+        doWithdrawals(2);
+        assertEq(user.balance, 0);
+    }
+
+    function testCannotSetWithdrawalRateLimitWithEmptyWindow() public {
+        vm.expectRevert("Withdrawal window cannot be empty");
+        bridge.setWithdrawalRateLimit(DEPOSIT_AMOUNT, 0);
+    }
+
+    function testCannotSetWithdrawalRateLimitIfNotOwner() public {
+        vm.startPrank(user);
+        vm.expectRevert("Caller is not owner");
+        bridge.setWithdrawalRateLimit(DEPOSIT_AMOUNT, 10);
+    }
+
     function isKeccakEqual(bytes memory a, bytes memory b) public pure returns (bool result) {
         result = keccak256(abi.encodePacked(a)) == keccak256(abi.encodePacked(b));
     }
 
+    /// Sets `count` operators sorted by address, returning their addresses and private keys
+    function setUpOperators(uint256 count, uint256 threshold) public returns (address[] memory signers, uint256[] memory keys) {
+        signers = new address[](count);
+        keys = new uint256[](count);
+        for (uint i = 0; i < count; i++) {
+            (signers[i], keys[i]) = makeAddrAndKey(string(abi.encodePacked("citrea_operator_", vm.toString(i), "_of_", vm.toString(count))));
+        }
+        for (uint i = 1; i < count; i++) {
+            for (uint j = i; j > 0 && signers[j - 1] > signers[j]; j--) {
+                (signers[j - 1], signers[j]) = (signers[j], signers[j - 1]);
+                (keys[j - 1], keys[j]) = (keys[j], keys[j - 1]);
+            }
+        }
+        bridge.setOperators(signers, threshold);
+    }
+
+    function signWithdrawalProcessing(uint256[] memory keys, uint256 count, bytes32 bitcoinTxId, uint256 signatureCount) public view returns (bytes[] memory signatures) {
+        bytes32 digest = bridge.withdrawalProcessingDigest(count, bitcoinTxId);
+        signatures = new bytes[](signatureCount);
+        for (uint i = 0; i < signatureCount; i++) {
+            (uint8 v, bytes32 r, bytes32 s) = vm.sign(keys[i], digest);
+            signatures[i] = abi.encodePacked(r, s, v);
+        }
+    }
+
+    function doWithdrawals(uint256 count) public {
+        vm.deal(user, count * DEPOSIT_AMOUNT);
+        vm.startPrank(user);
+        for (uint i = 0; i < count; i++) {
+            bridge.withdraw{value: DEPOSIT_AMOUNT}(bytes32(i));
+        }
+        vm.stopPrank();
+    }
+
     function doDeposit() public {
         Bridge.DepositParams memory depositParams = Bridge.DepositParams(version, flag, vin, vout, witness, locktime, intermediate_nodes, INITIAL_BLOCK_NUMBER, index);
         bridge.deposit(depositParams);
@@ -0,0 +1,54 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.13;
+
+import "forge-std/Test.sol";
+import "../src/StateRootAnchor.sol";
+
+contract StateRootAnchorTest is Test {
+    StateRootAnchor stateRootAnchor;
+    bytes32 mockStateRoot = bytes32(keccak256("CITREA_TEST"));
+    bytes32 otherStateRoot = bytes32(keccak256("CITREA"));
+    uint256 constant BLOCK_NUMBER = 100;
+    uint256 constant TIMESTAMP = 1700000000;
+
+    function setUp() public {
+        stateRootAnchor = new StateRootAnchor();
+    }
+
+    function testAnchor() public {
+        stateRootAnchor.anchor(BLOCK_NUMBER, mockStateRoot, TIMESTAMP);
+        assertEq(stateRootAnchor.getStateRoot(BLOCK_NUMBER), mockStateRoot);
+        assertEq(stateRootAnchor.getTimestamp(BLOCK_NUMBER), TIMESTAMP);
+        assertEq(stateRootAnchor.anchoredBlockNumbers(mockStateRoot), BLOCK_NUMBER);
+        assertEq(stateRootAnchor.latestAnchoredBlockNumber(), BLOCK_NUMBER);
+        assertEq(stateRootAnchor.getStateRoot(BLOCK_NUMBER + 1), bytes32(0));
+    }
+
+    function testCannotAnchorSameOrOlderBlock() public {
+        stateRootAnchor.anchor(BLOCK_NUMBER, mockStateRoot, TIMESTAMP);
+        vm.expectRevert("Block already anchored");
+        stateRootAnchor.anchor(BLOCK_NUMBER, otherStateRoot, TIMESTAMP);
+        vm.expectRevert("Block already anchored");
+        stateRootAnchor.anchor(BLOCK_NUMBER - 1, otherStateRoot, TIMESTAMP);
+    }
+
+    function testCannotAnchorGenesis() public {
+        vm.expectRevert("Block already anchored");
+        stateRootAnchor.anchor(0, mockStateRoot, TIMESTAMP);
+    }
+
+    function testNonOwnerCannotAnchor() public {
+        vm.startPrank(address(0x1));
+        vm.expectRevert("Caller is not owner");
+        stateRootAnchor.anchor(BLOCK_NUMBER, mockStateRoot, TIMESTAMP);
+    }
+
+    function testIsRecentStateRoot() public {
+        stateRootAnchor.anchor(BLOCK_NUMBER, mockStateRoot, TIMESTAMP);
+        stateRootAnchor.anchor(BLOCK_NUMBER + 10, otherStateRoot, TIMESTAMP + 20);
+        assertTrue(stateRootAnchor.isRecentStateRoot(mockStateRoot, 10));
+        assertFalse(stateRootAnchor.isRecentStateRoot(mockStateRoot, 9));
+        assertTrue(stateRootAnchor.isRecentStateRoot(otherStateRoot, 0));
+        assertFalse(stateRootAnchor.isRecentStateRoot(bytes32(keccak256("UNKNOWN")), type(uint256).max));
+    }
+}
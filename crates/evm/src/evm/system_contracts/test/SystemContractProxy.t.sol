@@ -0,0 +1,69 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.13;
+
+import "forge-std/Test.sol";
+import "../src/StateRootAnchor.sol";
+import "../src/SystemContractProxy.sol";
+
+contract StateRootAnchorV2 is StateRootAnchor {
+    function version() external pure returns (uint256) {
+        return 2;
+    }
+}
+
+contract SystemContractProxyTest is Test {
+    address constant SYSTEM_SIGNER = 0xdeaDDeADDEaDdeaDdEAddEADDEAdDeadDEADDEaD;
+    SystemContractProxy proxy;
+    StateRootAnchor anchor;
+    bytes32 mockStateRoot = bytes32(keccak256("CITREA_TEST"));
+
+    function setUp() public {
+        vm.startPrank(SYSTEM_SIGNER);
+        proxy = new SystemContractProxy(address(new StateRootAnchor()));
+        anchor = StateRootAnchor(address(proxy));
+        // The owner of the implementation is stored in the proxy, as set in genesis
+        vm.store(address(proxy), bytes32(0), bytes32(uint256(uint160(SYSTEM_SIGNER))));
+    }
+
+    function testForwardsCalls() public {
+        anchor.anchor(100, mockStateRoot, 1700000000);
+        assertEq(anchor.getStateRoot(100), mockStateRoot);
+        assertEq(anchor.owner(), SYSTEM_SIGNER);
+    }
+
+    function testUpgradeKeepsState() public {
+        anchor.anchor(100, mockStateRoot, 1700000000);
+        address implementation = address(new StateRootAnchorV2());
+        proxy.upgradeToAndCall(implementation, "");
+        assertEq(proxy.implementation(), implementation);
+        assertEq(StateRootAnchorV2(address(proxy)).version(), 2);
+        assertEq(anchor.getStateRoot(100), mockStateRoot);
+    }
+
+    function testUpgradeAndCall() public {
+        address implementation = address(new StateRootAnchorV2());
+        proxy.upgradeToAndCall(
+            implementation, abi.encodeCall(StateRootAnchor.anchor, (100, mockStateRoot, 1700000000))
+        );
+        assertEq(anchor.getStateRoot(100), mockStateRoot);
+    }
+
+    function testFailedCallRevertsUpgrade() public {
+        address implementation = address(new StateRootAnchorV2());
+        vm.expectRevert("Block already anchored");
+        proxy.upgradeToAndCall(implementation, abi.encodeCall(StateRootAnchor.anchor, (0, mockStateRoot, 0)));
+    }
+
+    function testCannotUpgradeToNonContract() public {
+        vm.expectRevert("Implementation is not a contract");
+        proxy.upgradeToAndCall(address(0x1), "");
+    }
+
+    function testOnlySystemSignerCanUpgrade() public {
+        address implementation = address(new StateRootAnchorV2());
+        vm.stopPrank();
+        vm.startPrank(address(0x1));
+        vm.expectRevert("Caller is not system signer");
+        proxy.upgradeToAndCall(implementation, "");
+    }
+}
@@ -3,7 +3,7 @@ use reth_primitives::{
     TransactionSigned, TransactionSignedEcRecovered, TransactionSignedNoHash, TxEip1559, U256,
 };
 
-use super::system_contracts::L1BlockHashList;
+use super::system_contracts::{Bridge, L1BlockHashList, StateRootAnchor, SystemContractProxy};
 
 /// This is a special signature to force tx.signer to be set to SYSTEM_SIGNER
 pub const SYSTEM_SIGNATURE: Signature = Signature {
@@ -15,15 +15,42 @@ pub const SYSTEM_SIGNATURE: Signature = Signature {
 /// This is a special system address to indicate a tx is called by system not by a user/contract.
 pub const SYSTEM_SIGNER: Address = address!("deaddeaddeaddeaddeaddeaddeaddeaddeaddead");
 
+/// Gas limit of the system transactions of a block if not set in genesis.
+///
+/// System transactions don't consume the block gas limit of user transactions, they have their
+/// own budget so that blocks updating the L1 block hash list keep the full user throughput.
+/// System events whose transactions don't fit in the budget are deferred to the next blocks.
+pub const DEFAULT_SYSTEM_TX_GAS_LIMIT: u64 = 30_000_000;
+
 /// A system event is an event that is emitted on special conditions by the EVM.
 /// There events will be transformed into Evm transactions and put in the begining of the block.
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, Eq, PartialEq)]
 pub(crate) enum SystemEvent {
     L1BlockHashInitialize(/*block number*/ u64),
     L1BlockHashSetBlockInfo(/*hash*/ [u8; 32], /*merkle root*/ [u8; 32]),
+    StateRootAnchor(
+        /*block number*/ u64,
+        /*state root*/ [u8; 32],
+        /*timestamp*/ u64,
+    ),
+    UpgradeSystemContract(
+        /*proxy*/ Address,
+        /*implementation init code*/ RethBytes,
+        /*call data*/ RethBytes,
+    ),
+    BridgeSetWithdrawalRateLimit(/*limit*/ u128, /*window L1 blocks*/ u64),
+    BridgeSetOperators(/*operators*/ Vec<Address>, /*threshold*/ u64),
 }
 
-fn system_event_to_transaction(event: SystemEvent, nonce: u64, chain_id: u64) -> Transaction {
+/// Gas limit of the deployment of a new system contract implementation.
+const SYSTEM_CONTRACT_DEPLOYMENT_GAS_LIMIT: u64 = 10_000_000;
+
+/// Gas limit of the replacement of the bridge operators, enough to clear and set
+/// [`MAX_BRIDGE_OPERATORS`](crate::MAX_BRIDGE_OPERATORS) operators.
+const BRIDGE_SET_OPERATORS_GAS_LIMIT: u64 = 5_000_000;
+
+/// Returns the transactions of a system event, the first one being sent with `nonce`.
+fn system_event_to_transactions(event: SystemEvent, nonce: u64, chain_id: u64) -> Vec<Transaction> {
     let sys_block_hash = L1BlockHashList::default();
     let body: TxEip1559 = match event {
         SystemEvent::L1BlockHashInitialize(block_number) => TxEip1559 {
@@ -50,16 +77,93 @@ fn system_event_to_transaction(event: SystemEvent, nonce: u64, chain_id: u64) ->
             max_fee_per_gas: u64::MAX as u128,
             ..Default::default()
         },
+        SystemEvent::StateRootAnchor(block_number, state_root, timestamp) => TxEip1559 {
+            to: TransactionKind::Call(StateRootAnchor::address()),
+            input: RethBytes::from(
+                StateRootAnchor::default()
+                    .anchor(block_number, state_root, timestamp)
+                    .to_vec(),
+            ),
+            nonce,
+            chain_id,
+            value: U256::ZERO,
+            gas_limit: 1_000_000u64,
+            max_fee_per_gas: u64::MAX as u128,
+            ..Default::default()
+        },
+        SystemEvent::BridgeSetWithdrawalRateLimit(limit, window_l1_blocks) => TxEip1559 {
+            to: TransactionKind::Call(Bridge::address()),
+            input: RethBytes::from(
+                Bridge::default()
+                    .set_withdrawal_rate_limit(limit, window_l1_blocks)
+                    .to_vec(),
+            ),
+            nonce,
+            chain_id,
+            value: U256::ZERO,
+            gas_limit: 1_000_000u64,
+            max_fee_per_gas: u64::MAX as u128,
+            ..Default::default()
+        },
+        SystemEvent::BridgeSetOperators(operators, threshold) => TxEip1559 {
+            to: TransactionKind::Call(Bridge::address()),
+            input: RethBytes::from(
+                Bridge::default()
+                    .set_operators(&operators, threshold)
+                    .to_vec(),
+            ),
+            nonce,
+            chain_id,
+            value: U256::ZERO,
+            gas_limit: BRIDGE_SET_OPERATORS_GAS_LIMIT,
+            max_fee_per_gas: u64::MAX as u128,
+            ..Default::default()
+        },
+        SystemEvent::UpgradeSystemContract(proxy, code, call_data) => {
+            // deploy the new implementation, then point the proxy to it
+            let deployment = TxEip1559 {
+                to: TransactionKind::Create,
+                input: code,
+                nonce,
+                chain_id,
+                value: U256::ZERO,
+                gas_limit: SYSTEM_CONTRACT_DEPLOYMENT_GAS_LIMIT,
+                max_fee_per_gas: u64::MAX as u128,
+                ..Default::default()
+            };
+            let implementation = SYSTEM_SIGNER.create(nonce);
+            let upgrade = TxEip1559 {
+                to: TransactionKind::Call(proxy),
+                input: RethBytes::from(
+                    SystemContractProxy::default()
+                        .upgrade_to_and_call(implementation, call_data.to_vec().into())
+                        .to_vec(),
+                ),
+                nonce: nonce + 1,
+                chain_id,
+                value: U256::ZERO,
+                gas_limit: 1_000_000u64,
+                max_fee_per_gas: u64::MAX as u128,
+                ..Default::default()
+            };
+            return vec![
+                Transaction::Eip1559(deployment),
+                Transaction::Eip1559(upgrade),
+            ];
+        }
     };
-    Transaction::Eip1559(body)
+    vec![Transaction::Eip1559(body)]
 }
 
-fn signed_system_transaction(
-    event: SystemEvent,
-    nonce: u64,
-    chain_id: u64,
-) -> TransactionSignedEcRecovered {
-    let transaction = system_event_to_transaction(event, nonce, chain_id);
+/// Returns the total gas limit of the transactions of a system event.
+pub(crate) fn system_event_gas_limit(event: &SystemEvent) -> u64 {
+    system_event_to_transactions(event.clone(), 0, 0)
+        .iter()
+        .map(|tx| tx.gas_limit())
+        .sum()
+}
+
+fn signed_system_transaction(transaction: Transaction) -> TransactionSignedEcRecovered {
     let signed_no_hash = TransactionSignedNoHash {
         signature: SYSTEM_SIGNATURE,
         transaction,
@@ -75,10 +179,11 @@ pub(crate) fn create_system_transactions<I: IntoIterator<Item = SystemEvent>>(
 ) -> Vec<TransactionSignedEcRecovered> {
     events
         .into_iter()
-        .map(|event| {
-            let tx = signed_system_transaction(event, nonce, chain_id);
-            nonce += 1;
-            tx
+        .flat_map(|event| {
+            let txs = system_event_to_transactions(event, nonce, chain_id);
+            nonce += txs.len() as u64;
+            txs
         })
+        .map(signed_system_transaction)
         .collect()
 }
@@ -10,7 +10,11 @@ use sov_modules_api::WorkingSet;
 
 use crate::evm::db_init::InitEvmDb;
 use crate::evm::primitive_types::Block;
-use crate::evm::{AccountInfo, EvmChainConfig};
+use crate::evm::system_contracts::SystemContractUpgrade;
+use crate::evm::system_events::{system_event_gas_limit, SystemEvent, DEFAULT_SYSTEM_TX_GAS_LIMIT};
+use crate::evm::{
+    AccountInfo, EncryptedMempoolConfig, EvmChainConfig, GasSchedule, GovernanceConfig,
+};
 #[cfg(test)]
 use crate::tests::DEFAULT_CHAIN_ID;
 use crate::Evm;
@@ -127,6 +131,41 @@ pub struct EvmConfig {
     pub nonce: u64,
     /// Difficulty of the genesis block.
     pub difficulty: U256,
+    /// Gas cost overrides for precompiles.
+    #[serde(default)]
+    pub gas_schedule: GasSchedule,
+    /// Number of L2 blocks between state roots anchored in the StateRootAnchor system contract.
+    /// Anchoring is disabled if zero.
+    #[serde(default)]
+    pub state_root_anchor_interval: u64,
+    /// Gas limit of the system transactions of a block, which don't consume `block_gas_limit`.
+    /// System events not fitting in it are deferred to the next blocks, so it must fit the
+    /// transactions of any single system event.
+    /// [`DEFAULT_SYSTEM_TX_GAS_LIMIT`](crate::DEFAULT_SYSTEM_TX_GAS_LIMIT) if not set.
+    #[serde(default)]
+    pub system_tx_gas_limit: Option<u64>,
+    /// Gas used by a block at which the base fee of the next block stays the same.
+    /// `block_gas_limit / base_fee_params.elasticity_multiplier` if not set.
+    #[serde(default)]
+    pub block_gas_target: Option<u64>,
+    /// Upgrades of system contracts deployed behind a proxy, applied by system transactions
+    /// at the beginning of their block.
+    #[serde(default)]
+    pub system_contract_upgrades: Vec<SystemContractUpgrade>,
+    /// Encrypted mempool parameters. The encrypted mempool is disabled if not set.
+    #[serde(default)]
+    pub encrypted_mempool: Option<EncryptedMempoolConfig>,
+    /// Verifying key of the SNARKs the rollup proofs are wrapped into, committed in genesis
+    /// so that on-chain verifiers can check proofs against it.
+    #[serde(default)]
+    pub proof_verifying_key: Option<Bytes>,
+    /// Version of the transaction ordering policy of the sequencer, recorded in the extra data
+    /// of every block. Not recorded if not set.
+    #[serde(default)]
+    pub ordering_policy_version: Option<u8>,
+    /// Emergency account blocklist parameters. Governance actions are rejected if not set.
+    #[serde(default)]
+    pub governance: Option<GovernanceConfig>,
 }
 
 #[cfg(test)]
@@ -145,6 +184,15 @@ impl Default for EvmConfig {
             extra_data: Bytes::default(),
             nonce: 0,
             difficulty: U256::ZERO,
+            gas_schedule: GasSchedule::default(),
+            state_root_anchor_interval: 0,
+            system_tx_gas_limit: None,
+            block_gas_target: None,
+            system_contract_upgrades: vec![],
+            encrypted_mempool: None,
+            proof_verifying_key: None,
+            ordering_policy_version: None,
+            governance: None,
         }
     }
 }
@@ -178,6 +226,18 @@ impl<C: sov_modules_api::Context> Evm<C> {
             }
         }
 
+        // Genesis storage is not added to the `keys` of the accounts, so it is indexed
+        // separately for the state export
+        for acc in config.data.iter().filter(|acc| acc.code.len() > 0) {
+            let mut keys: Vec<U256> = acc.storage.keys().copied().collect();
+            if keys.is_empty() {
+                continue;
+            }
+            keys.sort();
+            self.genesis_storage_keys
+                .set(&acc.address, &keys, &mut working_set.accessory_state());
+        }
+
         let mut spec = config
             .spec
             .iter()
@@ -210,6 +270,100 @@ impl<C: sov_modules_api::Context> Evm<C> {
 
         self.cfg.set(&chain_cfg, working_set);
 
+        if let Some(block_gas_target) = config.block_gas_target {
+            anyhow::ensure!(
+                block_gas_target > 0 && block_gas_target <= config.block_gas_limit,
+                "Block gas target {} must be positive and at most the block gas limit {}",
+                block_gas_target,
+                config.block_gas_limit
+            );
+            self.block_gas_target.set(&block_gas_target, working_set);
+        }
+
+        if !config.gas_schedule.is_empty() {
+            self.gas_schedule.set(&config.gas_schedule, working_set);
+        }
+
+        if config.state_root_anchor_interval != 0 {
+            self.state_root_anchor_interval
+                .set(&config.state_root_anchor_interval, working_set);
+        }
+
+        if let Some(system_tx_gas_limit) = config.system_tx_gas_limit {
+            self.system_tx_gas_limit
+                .set(&system_tx_gas_limit, working_set);
+        }
+        // deferred system events must fit in the gas limit of a block on their own,
+        // otherwise they would be deferred forever
+        let system_tx_gas_limit = config
+            .system_tx_gas_limit
+            .unwrap_or(DEFAULT_SYSTEM_TX_GAS_LIMIT);
+        let upgrades = config.system_contract_upgrades.iter().map(|upgrade| {
+            SystemEvent::UpgradeSystemContract(
+                upgrade.proxy,
+                upgrade.code.clone(),
+                upgrade.call_data.clone(),
+            )
+        });
+        for event in [
+            SystemEvent::L1BlockHashInitialize(0),
+            SystemEvent::L1BlockHashSetBlockInfo([0; 32], [0; 32]),
+            SystemEvent::StateRootAnchor(0, [0; 32], 0),
+        ]
+        .into_iter()
+        .chain(upgrades)
+        {
+            anyhow::ensure!(
+                system_event_gas_limit(&event) <= system_tx_gas_limit,
+                "System transaction gas limit {} is below the gas limit {} of a system event",
+                system_tx_gas_limit,
+                system_event_gas_limit(&event)
+            );
+        }
+
+        for upgrade in &config.system_contract_upgrades {
+            // the genesis block has no system transactions
+            anyhow::ensure!(
+                upgrade.block_number > 0,
+                "System contract upgrade of {} scheduled in the genesis block",
+                upgrade.proxy
+            );
+            let mut upgrades = self
+                .system_contract_upgrades
+                .get(&upgrade.block_number, working_set)
+                .unwrap_or_default();
+            upgrades.push(upgrade.clone());
+            self.system_contract_upgrades
+                .set(&upgrade.block_number, &upgrades, working_set);
+        }
+
+        if let Some(encrypted_mempool) = &config.encrypted_mempool {
+            anyhow::ensure!(
+                encrypted_mempool.epoch_blocks > 0,
+                "Encrypted mempool epochs cannot be empty"
+            );
+            anyhow::ensure!(
+                !encrypted_mempool.keypers.is_empty(),
+                "Encrypted mempool needs at least one keyper"
+            );
+            self.encrypted_mempool_config
+                .set(encrypted_mempool, working_set);
+        }
+
+        if let Some(proof_verifying_key) = &config.proof_verifying_key {
+            self.proof_verifying_key
+                .set(proof_verifying_key, working_set);
+        }
+
+        if let Some(ordering_policy_version) = config.ordering_policy_version {
+            self.ordering_policy_version
+                .set(&ordering_policy_version, working_set);
+        }
+
+        if let Some(governance) = &config.governance {
+            self.governance_config.set(governance, working_set);
+        }
+
         let header = reth_primitives::Header {
             parent_hash: B256::default(),
             ommers_hash: EMPTY_OMMER_ROOT_HASH,
@@ -1,12 +1,15 @@
+use std::collections::BTreeSet;
+
 use alloy_primitives::B256;
-use reth_primitives::{Bloom, Bytes, U256};
+use reth_primitives::{Address, Bloom, U256};
 use sov_modules_api::prelude::*;
 use sov_modules_api::{AccessoryWorkingSet, Spec, WorkingSet};
 use sov_state::Storage;
 
+use crate::evm::ordering_policy_extra_data;
 use crate::evm::primitive_types::{Block, BlockEnv};
 use crate::evm::system_events::SystemEvent;
-use crate::{Evm, PendingTransaction};
+use crate::{Evm, PendingTransaction, LOG_BLOOM_SECTION_SIZE};
 
 impl<C: sov_modules_api::Context> Evm<C>
 where
@@ -42,8 +45,26 @@ where
             working_set,
         );
 
-        // populate system events
-        let mut system_events = vec![];
+        // populate system events, starting with the ones deferred by the system
+        // transaction gas limit of the previous blocks
+        let mut system_events = self
+            .deferred_system_events
+            .get(working_set)
+            .unwrap_or_default();
+        // upgrade system contracts first, so the other system transactions of the block
+        // already call the new implementations
+        for upgrade in self
+            .system_contract_upgrades
+            .get(&(parent_block.header.number + 1), working_set)
+            .unwrap_or_default()
+        {
+            system_events.push(SystemEvent::UpgradeSystemContract(
+                upgrade.proxy,
+                upgrade.code,
+                upgrade.call_data,
+            ));
+        }
+
         if let Some(last_l1_hash) = self.last_l1_hash.get(working_set) {
             if last_l1_hash != da_slot_hash {
                 // That's a new L1 block
@@ -61,6 +82,18 @@ where
             ));
         }
 
+        // anchor the state root of the parent block every `state_root_anchor_interval` blocks
+        if let Some(interval) = self.state_root_anchor_interval.get(working_set) {
+            let parent_number = parent_block.header.number;
+            if parent_number > 0 && parent_number % interval == 0 {
+                system_events.push(SystemEvent::StateRootAnchor(
+                    parent_number,
+                    parent_block.header.state_root.0,
+                    parent_block.header.timestamp,
+                ));
+            }
+        }
+
         let cfg = self
             .cfg
             .get(working_set)
@@ -70,9 +103,8 @@ where
             coinbase: cfg.coinbase,
             timestamp,
             prevrandao: da_slot_hash.into(),
-            basefee: parent_block
-                .header
-                .next_block_base_fee(cfg.base_fee_params)
+            basefee: self
+                .next_block_base_fee(&parent_block.header, working_set)
                 .unwrap(),
             gas_limit: cfg.block_gas_limit,
         };
@@ -100,11 +132,6 @@ where
     /// Logic executed at the end of the slot. Here, we generate an authenticated block and set it as the new head of the chain.
     /// It's important to note that the state root hash is not known at this moment, so we postpone setting this field until the begin_slot_hook of the next slot.
     pub fn end_soft_confirmation_hook(&self, working_set: &mut WorkingSet<C>) {
-        let cfg = self
-            .cfg
-            .get(working_set)
-            .expect("EVM chain config should be set");
-
         let block_env = self
             .block_env
             .get(working_set)
@@ -175,8 +202,12 @@ where
             gas_used,
             mix_hash: block_env.prevrandao,
             nonce: 0,
-            base_fee_per_gas: parent_block.header.next_block_base_fee(cfg.base_fee_params),
-            extra_data: Bytes::default(),
+            base_fee_per_gas: self.next_block_base_fee(&parent_block.header, working_set),
+            extra_data: self
+                .ordering_policy_version
+                .get(working_set)
+                .map(ordering_policy_extra_data)
+                .unwrap_or_default(),
             // EIP-4844 related fields
             // https://github.com/Sovereign-Labs/sovereign-sdk/issues/912
             blob_gas_used: None,
@@ -259,5 +290,36 @@ where
             accessory_working_set,
         );
         self.pending_head.delete(accessory_working_set);
+
+        let number = sealed_block.header.number;
+        let logs_bloom = sealed_block.header.logs_bloom;
+        self.block_log_blooms
+            .set(&number, &logs_bloom, accessory_working_set);
+        let section = number / LOG_BLOOM_SECTION_SIZE;
+        let section_bloom = self
+            .log_bloom_sections
+            .get(&section, accessory_working_set)
+            .unwrap_or_default();
+        self.log_bloom_sections.set(
+            &section,
+            &(section_bloom | logs_bloom),
+            accessory_working_set,
+        );
+
+        let changed_slots: BTreeSet<(Address, U256)> = self
+            .pending_storage_changes
+            .iter(accessory_working_set)
+            .collect();
+        self.pending_storage_changes.clear(accessory_working_set);
+        for (address, key) in changed_slots {
+            let count = self
+                .storage_change_counts
+                .get(&(address, key), accessory_working_set)
+                .unwrap_or(0);
+            self.storage_change_blocks
+                .set(&(address, key, count), &number, accessory_working_set);
+            self.storage_change_counts
+                .set(&(address, key), &(count + 1), accessory_working_set);
+        }
     }
 }
@@ -1,5 +1,6 @@
 #![deny(missing_docs)]
 #![doc = include_str!("../README.md")]
+mod base_fee;
 mod call;
 mod evm;
 mod genesis;
@@ -19,22 +20,23 @@ mod signer;
 pub use signer::DevSigner;
 #[cfg(feature = "smart_contracts")]
 pub mod smart_contracts;
-pub use system_events::SYSTEM_SIGNER;
+pub use system_events::{DEFAULT_SYSTEM_TX_GAS_LIMIT, SYSTEM_SIGNER};
 
 #[cfg(test)]
 mod tests;
 
 use evm::db::EvmDb;
 use evm::DbAccount;
-use reth_primitives::{Address, B256};
+use reth_primitives::{Address, Bloom, B256};
 pub use revm::primitives::SpecId;
 use revm::primitives::U256;
 use sov_modules_api::{Error, ModuleInfo, WorkingSet};
 use sov_state::codec::BcsCodec;
 
 use crate::evm::primitive_types::{
-    Block, BlockEnv, Receipt, SealedBlock, TransactionSignedAndRecovered,
+    Block, BlockEnv, ContractCreation, Receipt, SealedBlock, TransactionSignedAndRecovered,
 };
+use crate::evm::system_contracts::SystemContractUpgrade;
 use crate::evm::system_events::SystemEvent;
 pub use crate::EvmConfig;
 
@@ -103,6 +105,102 @@ pub struct Evm<C: sov_modules_api::Context> {
     #[state]
     pub(crate) l1_fee_rate: sov_modules_api::StateValue<u64, BcsCodec>,
 
+    /// Total base fee paid by transactions since genesis.
+    /// Base fees are accrued to the coinbase instead of being burned, this keeps track of the accrued amount.
+    /// The base fee itself moves towards `block_gas_target`.
+    #[state]
+    pub(crate) base_fee_accrued: sov_modules_api::StateValue<U256, BcsCodec>,
+
+    /// Gas cost overrides for precompiles. This field is set in genesis and is empty when not set.
+    #[state]
+    pub(crate) gas_schedule: sov_modules_api::StateValue<GasSchedule, BcsCodec>,
+
+    /// Number of L2 blocks between state roots anchored in the StateRootAnchor system contract.
+    /// This field is set in genesis and anchoring is disabled when not set.
+    #[state]
+    pub(crate) state_root_anchor_interval: sov_modules_api::StateValue<u64, BcsCodec>,
+
+    /// Gas limit of the system transactions of a block, separate from the block gas limit of
+    /// user transactions. This field is set in genesis and [`DEFAULT_SYSTEM_TX_GAS_LIMIT`]
+    /// is used when not set.
+    #[state]
+    pub(crate) system_tx_gas_limit: sov_modules_api::StateValue<u64, BcsCodec>,
+
+    /// Gas used by a block at which the base fee of the next block stays the same. This field
+    /// is set in genesis and the EIP-1559 target derived from `base_fee_params` is used when not set.
+    #[state]
+    pub(crate) block_gas_target: sov_modules_api::StateValue<u64, BcsCodec>,
+
+    /// System events that didn't fit in the system transaction gas limit of their block,
+    /// executed first in the next blocks.
+    #[state]
+    pub(crate) deferred_system_events: sov_modules_api::StateValue<Vec<SystemEvent>, BcsCodec>,
+
+    /// Upgrades of system contracts scheduled at each L2 block number. This field is set in genesis.
+    #[state]
+    pub(crate) system_contract_upgrades:
+        sov_modules_api::StateMap<u64, Vec<SystemContractUpgrade>, BcsCodec>,
+
+    /// Encrypted mempool parameters. This field is set in genesis and the encrypted mempool
+    /// is disabled when not set.
+    #[state]
+    pub(crate) encrypted_mempool_config:
+        sov_modules_api::StateValue<EncryptedMempoolConfig, BcsCodec>,
+
+    /// Encrypted transactions ordered and not decrypted yet, by queue index.
+    #[state]
+    pub(crate) encrypted_tx_queue: sov_modules_api::StateMap<u64, EncryptedQueueEntry, BcsCodec>,
+
+    /// Queue index of the oldest encrypted transaction and the next queue index.
+    #[state]
+    pub(crate) encrypted_tx_queue_range: sov_modules_api::StateValue<(u64, u64), BcsCodec>,
+
+    /// Compressed public key shares of the epochs by epoch and keyper.
+    #[state]
+    pub(crate) epoch_public_key_shares:
+        sov_modules_api::StateMap<(u64, Address), Vec<u8>, BcsCodec>,
+
+    /// Secret key shares released for the epochs by epoch and keyper.
+    #[state]
+    pub(crate) epoch_secret_key_shares: sov_modules_api::StateMap<(u64, Address), B256, BcsCodec>,
+
+    /// Keypers that did not release their share of an epoch key in time, by epoch.
+    /// The encrypted transactions of these epochs were dropped.
+    #[state]
+    pub(crate) expired_epochs: sov_modules_api::StateMap<u64, Vec<Address>, BcsCodec>,
+
+    /// Verifying key of the SNARKs the rollup proofs are wrapped into. This field is set in genesis.
+    #[state]
+    pub(crate) proof_verifying_key: sov_modules_api::StateValue<reth_primitives::Bytes, BcsCodec>,
+
+    /// Version of the transaction ordering policy of the sequencer, recorded in the extra data
+    /// of every block. This field is set in genesis and no version is recorded when not set.
+    #[state]
+    pub(crate) ordering_policy_version: sov_modules_api::StateValue<u8, BcsCodec>,
+
+    /// Emergency account blocklist parameters. This field is set in genesis and governance
+    /// actions are rejected when not set.
+    #[state]
+    pub(crate) governance_config: sov_modules_api::StateValue<GovernanceConfig, BcsCodec>,
+
+    /// Nonce of the next governance action.
+    #[state]
+    pub(crate) governance_nonce: sov_modules_api::StateValue<u64, BcsCodec>,
+
+    /// Governance actions applied on chain, by nonce.
+    #[state]
+    pub(crate) governance_actions:
+        sov_modules_api::StateMap<u64, AppliedGovernanceAction, BcsCodec>,
+
+    /// Accounts frozen by governance actions. Expired freezes are kept until the account
+    /// is frozen or unfrozen again.
+    #[state]
+    pub(crate) frozen_accounts: sov_modules_api::StateMap<Address, AccountFreeze, BcsCodec>,
+
+    /// L2 block numbers bridge deposits were minted in, by idempotency key.
+    #[state]
+    pub(crate) processed_deposits: sov_modules_api::StateMap<DepositKey, u64, BcsCodec>,
+
     /// Used only by the RPC: This represents the head of the chain and is set in two distinct stages:
     /// 1. `end_slot_hook`: the pending head is populated with data from pending_transactions.
     /// 2. `finalize_hook` the `root_hash` is populated.
@@ -114,6 +212,16 @@ pub struct Evm<C: sov_modules_api::Context> {
     #[state]
     pub(crate) blocks: sov_modules_api::AccessoryStateVec<SealedBlock, BcsCodec>,
 
+    /// Used only by the RPC: block_number => logs bloom of the block, so that `eth_getLogs` can
+    /// skip blocks without loading them. Set in `finalize_hook`.
+    #[state]
+    pub(crate) block_log_blooms: sov_modules_api::AccessoryStateMap<u64, Bloom, BcsCodec>,
+
+    /// Used only by the RPC: section => union of the logs blooms of the blocks of the section,
+    /// sections being [`LOG_BLOOM_SECTION_SIZE`] blocks long. Set in `finalize_hook`.
+    #[state]
+    pub(crate) log_bloom_sections: sov_modules_api::AccessoryStateMap<u64, Bloom, BcsCodec>,
+
     /// Used only by the RPC: block_hash => block_number mapping,
     #[state]
     pub(crate) block_hashes:
@@ -132,6 +240,38 @@ pub struct Evm<C: sov_modules_api::Context> {
     /// Used only by the RPC: Receipts.
     #[state]
     pub(crate) receipts: sov_modules_api::AccessoryStateVec<Receipt, BcsCodec>,
+
+    /// Used only by the RPC: Addresses of the accounts in creation order, to export the state.
+    #[state]
+    pub(crate) account_list: sov_modules_api::AccessoryStateVec<Address, BcsCodec>,
+
+    /// Used only by the RPC: contract address => transaction creating it. A contract created again
+    /// at the same address after being destroyed maps to its latest creation.
+    #[state]
+    pub(crate) contract_creations:
+        sov_modules_api::AccessoryStateMap<Address, ContractCreation, BcsCodec>,
+
+    /// Used only by the RPC: Storage keys set at genesis, which are not in the `keys` of the accounts.
+    #[state]
+    pub(crate) genesis_storage_keys:
+        sov_modules_api::AccessoryStateMap<Address, Vec<U256>, BcsCodec>,
+
+    /// Used only by the RPC: Storage slots changed by the transactions of the pending block.
+    /// Moved to the storage history index in `finalize_hook`.
+    #[state]
+    pub(crate) pending_storage_changes:
+        sov_modules_api::AccessoryStateVec<(Address, U256), BcsCodec>,
+
+    /// Used only by the RPC: (address, storage key) => number of blocks that changed the slot.
+    #[state]
+    pub(crate) storage_change_counts:
+        sov_modules_api::AccessoryStateMap<(Address, U256), u64, BcsCodec>,
+
+    /// Used only by the RPC: (address, storage key, i) => number of the i-th block that changed
+    /// the slot, so that `citrea_getStorageHistory` only reads the state at those blocks.
+    #[state]
+    pub(crate) storage_change_blocks:
+        sov_modules_api::AccessoryStateMap<(Address, U256, u64), u64, BcsCodec>,
 }
 
 impl<C: sov_modules_api::Context> sov_modules_api::Module for Evm<C> {
@@ -153,7 +293,7 @@ impl<C: sov_modules_api::Context> sov_modules_api::Module for Evm<C> {
         context: &Self::Context,
         working_set: &mut WorkingSet<C>,
     ) -> Result<sov_modules_api::CallResponse, Error> {
-        Ok(self.execute_call(msg.txs, context, working_set)?)
+        Ok(self.execute_call(msg.txs, msg.encrypted, context, working_set)?)
     }
 }
 
@@ -163,6 +303,8 @@ impl<C: sov_modules_api::Context> Evm<C> {
             self.accounts.clone(),
             self.code.clone(),
             self.latest_block_hashes.clone(),
+            self.account_list.clone(),
+            self.pending_storage_changes.clone(),
             working_set,
         )
     }
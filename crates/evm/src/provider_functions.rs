@@ -1,7 +1,7 @@
-use reth_primitives::{Account, Address, SealedHeader};
-use sov_modules_api::{StateMapAccessor, StateVecAccessor, WorkingSet};
+use reth_primitives::{Account, Address, Bytes, SealedHeader};
+use sov_modules_api::{StateMapAccessor, StateValueAccessor, StateVecAccessor, WorkingSet};
 
-use crate::{DbAccount, Evm};
+use crate::{DbAccount, EncryptedMempoolConfig, EncryptedQueueEntry, Evm, GovernanceConfig};
 
 impl<C: sov_modules_api::Context> Evm<C> {
     /// Returns the account at the given address.
@@ -30,4 +30,80 @@ impl<C: sov_modules_api::Context> Evm<C> {
             .unwrap()
             .header
     }
+
+    /// Returns the encrypted mempool parameters, or None if the encrypted mempool is disabled.
+    pub fn encrypted_mempool_config(
+        &self,
+        working_set: &mut WorkingSet<C>,
+    ) -> Option<EncryptedMempoolConfig> {
+        self.encrypted_mempool_config.get(working_set)
+    }
+
+    /// Returns the emergency account blocklist parameters, or None if governance is disabled.
+    pub fn governance_config(&self, working_set: &mut WorkingSet<C>) -> Option<GovernanceConfig> {
+        self.governance_config.get(working_set)
+    }
+
+    /// Returns the nonce of the next governance action.
+    pub fn governance_nonce(&self, working_set: &mut WorkingSet<C>) -> u64 {
+        self.governance_nonce.get(working_set).unwrap_or_default()
+    }
+
+    /// Whether the account is frozen in the next block, so that its transactions
+    /// would be dropped.
+    pub fn is_account_frozen(&self, address: &Address, working_set: &mut WorkingSet<C>) -> bool {
+        let next_block_number = self.last_sealed_header(working_set).number + 1;
+        self.is_frozen(address, next_block_number, working_set)
+    }
+
+    /// Returns the verifying key of the SNARKs the rollup proofs are wrapped into,
+    /// if committed in genesis.
+    pub fn proof_verifying_key(&self, working_set: &mut WorkingSet<C>) -> Option<Bytes> {
+        self.proof_verifying_key.get(working_set)
+    }
+
+    /// Returns the compressed public key of an epoch of the encrypted mempool,
+    /// None if not every keyper announced its share yet.
+    pub fn encrypted_mempool_epoch_key(
+        &self,
+        epoch: u64,
+        working_set: &mut WorkingSet<C>,
+    ) -> Option<Bytes> {
+        let config = self.encrypted_mempool_config.get(working_set)?;
+        self.epoch_public_key(&config, epoch, working_set)
+            .map(|key| Bytes::from(key.serialize().to_vec()))
+    }
+
+    /// Returns whether `keyper` announced its share of the public key of an epoch.
+    pub fn is_epoch_key_announced(
+        &self,
+        epoch: u64,
+        keyper: Address,
+        working_set: &mut WorkingSet<C>,
+    ) -> bool {
+        self.epoch_public_key_shares
+            .get(&(epoch, keyper), working_set)
+            .is_some()
+    }
+
+    /// Returns the keypers that did not release their share of the key of an expired epoch,
+    /// None if the epoch did not expire.
+    pub fn expired_epoch_keypers(
+        &self,
+        epoch: u64,
+        working_set: &mut WorkingSet<C>,
+    ) -> Option<Vec<Address>> {
+        self.expired_epochs.get(&epoch, working_set)
+    }
+
+    /// Returns the encrypted transactions ordered and not decrypted yet, oldest first.
+    pub fn encrypted_tx_queue(&self, working_set: &mut WorkingSet<C>) -> Vec<EncryptedQueueEntry> {
+        let (head, tail) = self
+            .encrypted_tx_queue_range
+            .get(working_set)
+            .unwrap_or_default();
+        (head..tail)
+            .filter_map(|index| self.encrypted_tx_queue.get(&index, working_set))
+            .collect()
+    }
 }
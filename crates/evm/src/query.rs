@@ -9,33 +9,42 @@ use reth_interfaces::provider::ProviderError;
 use reth_primitives::revm::env::tx_env_with_recovered;
 use reth_primitives::TransactionKind::{Call, Create};
 use reth_primitives::{
-    Block, BlockId, BlockNumberOrTag, SealedHeader, TransactionSignedEcRecovered, U128, U256, U64,
+    Address, Block, BlockId, BlockNumberOrTag, GenesisAccount, SealedHeader,
+    TransactionSignedEcRecovered, B256, U128, U256, U64,
 };
 use reth_revm::access_list::AccessListInspector;
 use reth_revm::tracing::{TracingInspector, TracingInspectorConfig};
 use reth_rpc_types::other::OtherFields;
-use reth_rpc_types::trace::geth::{GethDebugTracingOptions, GethTrace};
-use reth_rpc_types::AccessListWithGasUsed;
+use reth_rpc_types::trace::geth::{
+    GethDebugTracingCallOptions, GethDebugTracingOptions, GethTrace,
+};
+use reth_rpc_types::trace::parity::LocalizedTransactionTrace;
+use reth_rpc_types::{AccessListWithGasUsed, TransactionInfo};
 use reth_rpc_types_compat::block::from_primitive_with_hash;
 use revm::primitives::{
-    CfgEnvWithHandlerCfg, EVMError, ExecutionResult, HaltReason, InvalidTransaction, TransactTo,
-    TxEnv, KECCAK_EMPTY,
+    CfgEnvWithHandlerCfg, EVMError, ExecutionResult, HaltReason, InvalidTransaction,
+    ResultAndState, TransactTo, TxEnv, KECCAK_EMPTY,
 };
 use revm::{Database, DatabaseCommit};
 use sov_modules_api::macros::rpc_gen;
 use sov_modules_api::prelude::*;
 use sov_modules_api::WorkingSet;
+use sov_state::codec::{BcsCodec, StateCodec};
+use sov_state::storage::{NativeStorage, StorageKey};
 use tracing::info;
 
 use crate::call::get_cfg_env;
 use crate::error::rpc::{ensure_success, EthApiError, RevertError, RpcInvalidTransactionError};
 use crate::evm::db::EvmDb;
-use crate::evm::prepare_call_env;
+use crate::evm::handler::{calc_l1_fee, CitreaExternal, CitreaExternalExt};
 use crate::evm::primitive_types::{BlockEnv, Receipt, SealedBlock, TransactionSignedAndRecovered};
+use crate::evm::system_contracts::L1BlockHashList;
+use crate::evm::{executor, ordering_policy_version, prepare_call_env, DbAccount, GasSchedule};
 use crate::rpc_helpers::*;
 use crate::{
-    BloomFilter, EthResult, Evm, EvmChainConfig, FilterBlockOption, FilterError,
-    ESTIMATE_GAS_ERROR_RATIO, MIN_TRANSACTION_GAS,
+    AccountFreeze, AppliedGovernanceAction, BloomFilter, ContractCreation, EthResult, Evm,
+    EvmChainConfig, FilterBlockOption, FilterError, ESTIMATE_GAS_ERROR_RATIO, MIN_TRANSACTION_GAS,
+    SYSTEM_SIGNER,
 };
 
 #[rpc_gen(client, server)]
@@ -72,7 +81,7 @@ impl<C: sov_modules_api::Context> Evm<C> {
     }
 
     /// Handler for `eth_getBlockByHash`
-    #[rpc_method(name = "eth_getBlockByHash")]
+    /// RPC method is moved to ethereum-rpc module
     pub fn get_block_by_hash(
         &self,
         block_hash: reth_primitives::B256,
@@ -98,7 +107,8 @@ impl<C: sov_modules_api::Context> Evm<C> {
     }
 
     /// Handler for: `eth_getBlockByNumber`
-    #[rpc_method(name = "eth_getBlockByNumber")]
+    /// RPC method is moved to ethereum-rpc module
+    /// The `safe` and `finalized` tags must be resolved with the [`QueryContext`] of the node first.
     pub fn get_block_by_number(
         &self,
         block_number: Option<BlockNumberOrTag>,
@@ -107,6 +117,13 @@ impl<C: sov_modules_api::Context> Evm<C> {
     ) -> RpcResult<Option<reth_rpc_types::RichBlock>> {
         info!("evm module: eth_getBlockByNumber");
 
+        if matches!(
+            block_number,
+            Some(BlockNumberOrTag::Safe | BlockNumberOrTag::Finalized)
+        ) {
+            return Err(EthApiError::UnknownSafeOrFinalizedBlock.into());
+        }
+
         let sealed_block = match self.get_sealed_block_by_number(block_number, working_set) {
             Some(sealed_block) => sealed_block,
             None => return Ok(None), // if block doesn't exist return null
@@ -189,7 +206,7 @@ impl<C: sov_modules_api::Context> Evm<C> {
     }
 
     /// Handler for: `eth_getBlockReceipts`
-    #[rpc_method(name = "eth_getBlockReceipts")]
+    /// RPC method is moved to ethereum-rpc module
     pub fn get_block_receipts(
         &self,
         block_number_or_hash: BlockId,
@@ -242,15 +259,18 @@ impl<C: sov_modules_api::Context> Evm<C> {
     }
 
     /// Handler for: `eth_getBalance`
-    #[rpc_method(name = "eth_getBalance")]
+    /// RPC method is moved to ethereum-rpc module
     pub fn get_balance(
         &self,
+        ctx: &QueryContext,
         address: reth_primitives::Address,
-        block_number: Option<BlockNumberOrTag>,
+        block_id: Option<BlockId>,
         working_set: &mut WorkingSet<C>,
     ) -> RpcResult<reth_primitives::U256> {
         info!("evm module: eth_getBalance");
 
+        let block_number = self.resolve_block_id(ctx, block_id, working_set)?;
+
         let curr_block_number = self
             .blocks
             .last(&mut working_set.accessory_state())
@@ -264,12 +284,12 @@ impl<C: sov_modules_api::Context> Evm<C> {
                 if num > curr_block_number {
                     return Err(EthApiError::UnknownBlockNumber.into());
                 }
-                set_state_to_end_of_evm_block(num, working_set);
+                self.set_state_to_past_evm_block(ctx, num, working_set)?;
             }
             // Working state here is already at the latest state, so no need to anything
             Some(BlockNumberOrTag::Latest) | Some(BlockNumberOrTag::Pending) | None => {}
             Some(BlockNumberOrTag::Earliest) => {
-                set_state_to_end_of_evm_block(0, working_set);
+                self.set_state_to_past_evm_block(ctx, 0, working_set)?;
             }
             _ => {
                 return Err(EthApiError::InvalidParams(
@@ -289,16 +309,19 @@ impl<C: sov_modules_api::Context> Evm<C> {
     }
 
     /// Handler for: `eth_getStorageAt`
-    #[rpc_method(name = "eth_getStorageAt")]
+    /// RPC method is moved to ethereum-rpc module
     pub fn get_storage_at(
         &self,
+        ctx: &QueryContext,
         address: reth_primitives::Address,
         index: reth_primitives::U256,
-        block_number: Option<BlockNumberOrTag>,
+        block_id: Option<BlockId>,
         working_set: &mut WorkingSet<C>,
     ) -> RpcResult<reth_primitives::B256> {
         info!("evm module: eth_getStorageAt");
 
+        let block_number = self.resolve_block_id(ctx, block_id, working_set)?;
+
         let curr_block_number = self
             .blocks
             .last(&mut working_set.accessory_state())
@@ -312,12 +335,12 @@ impl<C: sov_modules_api::Context> Evm<C> {
                 if num > curr_block_number {
                     return Err(EthApiError::UnknownBlockNumber.into());
                 }
-                set_state_to_end_of_evm_block(num, working_set);
+                self.set_state_to_past_evm_block(ctx, num, working_set)?;
             }
             // Working state here is already at the latest state, so no need to anything
             Some(BlockNumberOrTag::Latest) | Some(BlockNumberOrTag::Pending) | None => {}
             Some(BlockNumberOrTag::Earliest) => {
-                set_state_to_end_of_evm_block(0, working_set);
+                self.set_state_to_past_evm_block(ctx, 0, working_set)?;
             }
             _ => {
                 return Err(EthApiError::InvalidParams(
@@ -337,15 +360,18 @@ impl<C: sov_modules_api::Context> Evm<C> {
     }
 
     /// Handler for: `eth_getTransactionCount`
-    #[rpc_method(name = "eth_getTransactionCount")]
+    /// RPC method is moved to ethereum-rpc module
     pub fn get_transaction_count(
         &self,
+        ctx: &QueryContext,
         address: reth_primitives::Address,
-        block_number: Option<BlockNumberOrTag>,
+        block_id: Option<BlockId>,
         working_set: &mut WorkingSet<C>,
     ) -> RpcResult<reth_primitives::U64> {
         info!("evm module: eth_getTransactionCount");
 
+        let block_number = self.resolve_block_id(ctx, block_id, working_set)?;
+
         let curr_block_number = self
             .blocks
             .last(&mut working_set.accessory_state())
@@ -359,12 +385,12 @@ impl<C: sov_modules_api::Context> Evm<C> {
                 if num > curr_block_number {
                     return Err(EthApiError::UnknownBlockNumber.into());
                 }
-                set_state_to_end_of_evm_block(num, working_set);
+                self.set_state_to_past_evm_block(ctx, num, working_set)?;
             }
             // Working state here is already at the latest state, so no need to anything
             Some(BlockNumberOrTag::Latest) | Some(BlockNumberOrTag::Pending) | None => {}
             Some(BlockNumberOrTag::Earliest) => {
-                set_state_to_end_of_evm_block(0, working_set);
+                self.set_state_to_past_evm_block(ctx, 0, working_set)?;
             }
             _ => {
                 return Err(EthApiError::InvalidParams(
@@ -384,15 +410,18 @@ impl<C: sov_modules_api::Context> Evm<C> {
     }
 
     /// Handler for: `eth_getCode`
-    #[rpc_method(name = "eth_getCode")]
+    /// RPC method is moved to ethereum-rpc module
     pub fn get_code(
         &self,
+        ctx: &QueryContext,
         address: reth_primitives::Address,
-        block_number: Option<BlockNumberOrTag>,
+        block_id: Option<BlockId>,
         working_set: &mut WorkingSet<C>,
     ) -> RpcResult<reth_primitives::Bytes> {
         info!("evm module: eth_getCode");
 
+        let block_number = self.resolve_block_id(ctx, block_id, working_set)?;
+
         let curr_block_number = self
             .blocks
             .last(&mut working_set.accessory_state())
@@ -405,12 +434,12 @@ impl<C: sov_modules_api::Context> Evm<C> {
                 if num > curr_block_number {
                     return Err(EthApiError::UnknownBlockNumber.into());
                 }
-                set_state_to_end_of_evm_block(num, working_set);
+                self.set_state_to_past_evm_block(ctx, num, working_set)?;
             }
             // Working state here is already at the latest state, so no need to anything
             Some(BlockNumberOrTag::Latest) | Some(BlockNumberOrTag::Pending) | None => {}
             Some(BlockNumberOrTag::Earliest) => {
-                set_state_to_end_of_evm_block(0, working_set);
+                self.set_state_to_past_evm_block(ctx, 0, working_set)?;
             }
             // Is this the way?
             // Note that reth works for all types of BlockNumberOrTag
@@ -431,6 +460,591 @@ impl<C: sov_modules_api::Context> Evm<C> {
         Ok(code.original_bytes())
     }
 
+    /// Handler for: `citrea_getBaseFeeAccrued`
+    /// Returns the total base fee paid by transactions up to the end of the given block.
+    /// RPC method is moved to ethereum-rpc module
+    pub fn get_base_fee_accrued(
+        &self,
+        ctx: &QueryContext,
+        block_number: Option<BlockNumberOrTag>,
+        working_set: &mut WorkingSet<C>,
+    ) -> RpcResult<reth_primitives::U256> {
+        info!("evm module: citrea_getBaseFeeAccrued");
+
+        let block_number = block_number
+            .map(|block_number| ctx.resolve_finality_tag(block_number))
+            .transpose()?;
+
+        let curr_block_number = self
+            .blocks
+            .last(&mut working_set.accessory_state())
+            .expect("Head block must be set")
+            .header
+            .number;
+
+        match block_number {
+            Some(BlockNumberOrTag::Number(num)) => {
+                if num > curr_block_number {
+                    return Err(EthApiError::UnknownBlockNumber.into());
+                }
+                self.set_state_to_past_evm_block(ctx, num, working_set)?;
+            }
+            // Working state here is already at the latest state, so no need to anything
+            Some(BlockNumberOrTag::Latest) | Some(BlockNumberOrTag::Pending) | None => {}
+            Some(BlockNumberOrTag::Earliest) => {
+                self.set_state_to_past_evm_block(ctx, 0, working_set)?;
+            }
+            _ => {
+                return Err(EthApiError::InvalidParams(
+                    "Please provide a number or earliest/latest/pending tag".to_string(),
+                )
+                .into())
+            }
+        }
+
+        Ok(self.base_fee_accrued.get(working_set).unwrap_or_default())
+    }
+
+    /// Handler for: `citrea_getAccountFreeze`
+    /// Returns the latest freeze of an account by the governance, expired or not.
+    /// The account is frozen in the blocks before `untilBlock`.
+    #[rpc_method(name = "citrea_getAccountFreeze")]
+    pub fn get_account_freeze(
+        &self,
+        address: Address,
+        working_set: &mut WorkingSet<C>,
+    ) -> RpcResult<Option<AccountFreeze>> {
+        info!("evm module: citrea_getAccountFreeze({})", address);
+        Ok(self.frozen_accounts.get(&address, working_set))
+    }
+
+    /// Handler for: `citrea_getContractCreation`
+    /// Returns the transaction creating the contract at the given address, including
+    /// contracts created by other contracts. Contracts set in genesis have no creation.
+    #[rpc_method(name = "citrea_getContractCreation")]
+    pub fn get_contract_creation(
+        &self,
+        address: Address,
+        working_set: &mut WorkingSet<C>,
+    ) -> RpcResult<Option<ContractCreation>> {
+        info!("evm module: citrea_getContractCreation({})", address);
+        Ok(self
+            .contract_creations
+            .get(&address, &mut working_set.accessory_state()))
+    }
+
+    /// Handler for: `citrea_getStorageHistory`
+    /// Returns the value of a storage slot at the end of `from_block` and the values the
+    /// blocks up to `to_block` changed it to. The state is only read at the blocks changing
+    /// the slot, found through the index of storage changes.
+    /// RPC method is moved to ethereum-rpc module
+    pub fn get_storage_history(
+        &self,
+        ctx: &QueryContext,
+        address: Address,
+        index: U256,
+        from_block: BlockNumberOrTag,
+        to_block: BlockNumberOrTag,
+        working_set: &mut WorkingSet<C>,
+    ) -> RpcResult<StorageHistory> {
+        info!(
+            "evm module: citrea_getStorageHistory({}, {})",
+            address, index
+        );
+
+        let from = self
+            .get_sealed_block_for_report(from_block, working_set)?
+            .header
+            .number;
+        let to = self
+            .get_sealed_block_for_report(to_block, working_set)?
+            .header
+            .number;
+        if from > to {
+            return Err(EthApiError::InvalidBlockRange.into());
+        }
+        if to - from >= MAX_STORAGE_HISTORY_BLOCK_RANGE {
+            return Err(EthApiError::InvalidParams(format!(
+                "Block range exceeds the limit of {} blocks",
+                MAX_STORAGE_HISTORY_BLOCK_RANGE
+            ))
+            .into());
+        }
+
+        // the index is read before the state is set to past blocks, which also sets
+        // the accessory state to past blocks
+        let count = self
+            .storage_change_counts
+            .get(&(address, index), &mut working_set.accessory_state())
+            .unwrap_or(0);
+        let (mut low, mut high) = (0, count);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if self.storage_change_block(address, index, mid, working_set) <= from {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        let mut change_blocks = vec![];
+        let mut covered_to = to;
+        for i in low..count {
+            let block_number = self.storage_change_block(address, index, i, working_set);
+            if block_number > to {
+                break;
+            }
+            if change_blocks.len() == MAX_STORAGE_HISTORY_CHANGES {
+                covered_to = block_number - 1;
+                break;
+            }
+            change_blocks.push(block_number);
+        }
+
+        self.set_state_to_past_evm_block(ctx, from, working_set)?;
+        let initial_value = self.storage_value(address, index, working_set);
+        let mut previous = initial_value;
+        let mut changes = Vec::with_capacity(change_blocks.len());
+        for block_number in change_blocks {
+            set_state_to_end_of_evm_block(block_number, working_set);
+            let value = self.storage_value(address, index, working_set);
+            // transactions of a block may set the slot back to its value before the block
+            if value != previous {
+                changes.push(StorageSlotChange {
+                    block_number: U64::from(block_number),
+                    value: value.into(),
+                });
+            }
+            previous = value;
+        }
+
+        Ok(StorageHistory {
+            from_block: U64::from(from),
+            to_block: U64::from(covered_to),
+            initial_value: initial_value.into(),
+            changes,
+        })
+    }
+
+    /// Handler for: `citrea_getGovernanceAction`
+    /// Returns the governance action with the given nonce, if applied.
+    #[rpc_method(name = "citrea_getGovernanceAction")]
+    pub fn get_governance_action(
+        &self,
+        nonce: U64,
+        working_set: &mut WorkingSet<C>,
+    ) -> RpcResult<Option<AppliedGovernanceAction>> {
+        info!("evm module: citrea_getGovernanceAction({})", nonce);
+        Ok(self.governance_actions.get(&nonce.to(), working_set))
+    }
+
+    /// Handler for: `citrea_getStorageRentReport`
+    /// Attributes the state diff bytes written to DA, and the L1 fees paid for them,
+    /// to transaction senders and contracts over the given block range.
+    /// System transactions are not included as they don't pay L1 fees.
+    #[rpc_method(name = "citrea_getStorageRentReport")]
+    pub fn get_storage_rent_report(
+        &self,
+        from_block: BlockNumberOrTag,
+        to_block: BlockNumberOrTag,
+        working_set: &mut WorkingSet<C>,
+    ) -> RpcResult<StorageRentReport> {
+        info!("evm module: citrea_getStorageRentReport");
+
+        let from_block = self.get_sealed_block_for_report(from_block, working_set)?;
+        let to_block = self.get_sealed_block_for_report(to_block, working_set)?;
+        let from = from_block.header.number;
+        let to = to_block.header.number;
+        if from > to {
+            return Err(EthApiError::InvalidBlockRange.into());
+        }
+        if to - from >= MAX_STORAGE_RENT_BLOCK_RANGE {
+            return Err(EthApiError::InvalidParams(format!(
+                "Block range exceeds the limit of {} blocks",
+                MAX_STORAGE_RENT_BLOCK_RANGE
+            ))
+            .into());
+        }
+
+        let mut accumulator = StorageRentAccumulator::default();
+        for block_number in from..=to {
+            let block = self
+                .blocks
+                .get(block_number as usize, &mut working_set.accessory_state())
+                .expect("Block must be set");
+
+            for id in block.transactions.clone() {
+                let tx = self
+                    .transactions
+                    .get(id as usize, &mut working_set.accessory_state())
+                    .expect("Transaction must be set");
+                if tx.signer == SYSTEM_SIGNER {
+                    continue;
+                }
+                let receipt = self
+                    .receipts
+                    .get(id as usize, &mut working_set.accessory_state())
+                    .expect("Receipt for known transaction must be set");
+
+                let contract = match tx.signed_transaction.kind() {
+                    Call(to) => *to,
+                    Create => tx.signer.create(tx.signed_transaction.nonce()),
+                };
+                accumulator.record(
+                    tx.signer,
+                    contract,
+                    receipt.diff_size,
+                    block.l1_fee_rate,
+                    receipt.receipt.success,
+                );
+            }
+        }
+
+        Ok(accumulator.into_report(
+            from,
+            to,
+            from_block.header.timestamp,
+            to_block.header.timestamp,
+        ))
+    }
+
+    /// Handler for: `citrea_getBlockGasUsage`
+    /// Aggregates the gas used by the blocks of the given range, as a whole and in windows
+    /// of `window_size` blocks, with the contracts using the most gas in each window.
+    /// The whole range is a single window if no window size is given.
+    #[rpc_method(name = "citrea_getBlockGasUsage")]
+    pub fn get_block_gas_usage(
+        &self,
+        from_block: BlockNumberOrTag,
+        to_block: BlockNumberOrTag,
+        window_size: Option<u64>,
+        working_set: &mut WorkingSet<C>,
+    ) -> RpcResult<BlockGasUsage> {
+        info!("evm module: citrea_getBlockGasUsage");
+
+        let from_block = self.get_sealed_block_for_report(from_block, working_set)?;
+        let to_block = self.get_sealed_block_for_report(to_block, working_set)?;
+        let from = from_block.header.number;
+        let to = to_block.header.number;
+        if from > to {
+            return Err(EthApiError::InvalidBlockRange.into());
+        }
+        if to - from >= MAX_GAS_USAGE_BLOCK_RANGE {
+            return Err(EthApiError::InvalidParams(format!(
+                "Block range exceeds the limit of {} blocks",
+                MAX_GAS_USAGE_BLOCK_RANGE
+            ))
+            .into());
+        }
+        let window_size = match window_size {
+            Some(0) => {
+                return Err(
+                    EthApiError::InvalidParams("Window size must be positive".to_string()).into(),
+                )
+            }
+            Some(window_size) => window_size,
+            None => to - from + 1,
+        };
+
+        let mut total = GasUsageAccumulator::new(from, from_block.header.timestamp);
+        let mut windows = vec![];
+        let mut window: Option<GasUsageAccumulator> = None;
+        for block_number in from..=to {
+            let block = self
+                .blocks
+                .get(block_number as usize, &mut working_set.accessory_state())
+                .expect("Block must be set");
+
+            let window = window.get_or_insert_with(|| {
+                GasUsageAccumulator::new(block_number, block.header.timestamp)
+            });
+            window.record_block(
+                block_number,
+                block.header.timestamp,
+                block.header.gas_used,
+                block.header.gas_limit,
+                block.transactions.end - block.transactions.start,
+            );
+
+            for id in block.transactions.clone() {
+                let tx = self
+                    .transactions
+                    .get(id as usize, &mut working_set.accessory_state())
+                    .expect("Transaction must be set");
+                if tx.signer == SYSTEM_SIGNER {
+                    continue;
+                }
+                let receipt = self
+                    .receipts
+                    .get(id as usize, &mut working_set.accessory_state())
+                    .expect("Receipt for known transaction must be set");
+
+                let contract = match tx.signed_transaction.kind() {
+                    Call(to) => *to,
+                    Create => tx.signer.create(tx.signed_transaction.nonce()),
+                };
+                window.record_transaction(contract, receipt.gas_used);
+            }
+
+            if (block_number - from + 1) % window_size == 0 || block_number == to {
+                let window = window.take().expect("Window was just set");
+                total.merge(&window);
+                windows.push(window.into_window());
+            }
+        }
+
+        Ok(BlockGasUsage {
+            total: total.into_window(),
+            windows,
+        })
+    }
+
+    /// Handler for: `citrea_exportStateTests`
+    /// Re-executes the transactions of the given block range and exports every transaction
+    /// except system transactions as an execution-spec-tests state test fixture,
+    /// keyed by transaction hash.
+    /// RPC method is moved to ethereum-rpc module
+    pub fn export_state_tests(
+        &self,
+        ctx: &QueryContext,
+        from_block: BlockNumberOrTag,
+        to_block: BlockNumberOrTag,
+        working_set: &mut WorkingSet<C>,
+    ) -> RpcResult<BTreeMap<B256, StateTestFixture>> {
+        info!("evm module: citrea_exportStateTests");
+
+        let from = self
+            .get_sealed_block_for_report(from_block, working_set)?
+            .header
+            .number;
+        let to = self
+            .get_sealed_block_for_report(to_block, working_set)?
+            .header
+            .number;
+        if from > to {
+            return Err(EthApiError::InvalidBlockRange.into());
+        }
+        if to - from >= MAX_STATE_TEST_BLOCK_RANGE {
+            return Err(EthApiError::InvalidParams(format!(
+                "Block range exceeds the limit of {} blocks",
+                MAX_STATE_TEST_BLOCK_RANGE
+            ))
+            .into());
+        }
+
+        let cfg = self
+            .cfg
+            .get(working_set)
+            .expect("EVM chain config should be set");
+        let gas_schedule = self.gas_schedule.get(working_set).unwrap_or_default();
+
+        let mut fixtures = BTreeMap::new();
+        // genesis block has no transactions
+        for block_number in from.max(1)..=to {
+            let block = self
+                .blocks
+                .get(block_number as usize, &mut working_set.accessory_state())
+                .expect("Block must be set");
+            let block_txs: Vec<TransactionSignedEcRecovered> = block
+                .transactions
+                .clone()
+                .map(|id| {
+                    self.transactions
+                        .get(id as usize, &mut working_set.accessory_state())
+                        .expect("Transaction must be set")
+                        .into()
+                })
+                .collect();
+
+            // set state to end of the previous block
+            self.set_state_to_past_evm_block(ctx, block_number - 1, working_set)?;
+
+            let block_env = BlockEnv::from(&block);
+            let cfg_env = get_cfg_env(&block_env, cfg.clone(), None);
+            let env = StateTestEnv {
+                current_coinbase: block_env.coinbase,
+                current_gas_limit: U256::from(block_env.gas_limit),
+                current_number: U256::from(block_env.number),
+                current_timestamp: U256::from(block_env.timestamp),
+                current_random: block_env.prevrandao,
+                current_difficulty: U256::ZERO,
+                current_base_fee: U256::from(block_env.basefee),
+            };
+
+            let mut evm_db = self.get_db(working_set);
+            for tx in block_txs {
+                let ResultAndState { result, state } = execute_citrea_tx(
+                    &mut evm_db,
+                    cfg_env.clone(),
+                    block_env.clone().into(),
+                    &tx,
+                    block.l1_fee_rate,
+                    gas_schedule.clone(),
+                )?;
+
+                if tx.signer() != SYSTEM_SIGNER {
+                    let mut pre = BTreeMap::new();
+                    let mut post = BTreeMap::new();
+                    for (address, account) in &state {
+                        let pre_info = evm_db.basic(*address).unwrap();
+                        if let Some(info) = &pre_info {
+                            let code = evm_db.code_by_hash(info.code_hash).unwrap();
+                            let storage = account
+                                .storage
+                                .iter()
+                                .map(|(key, slot)| (*key, slot.original_value()))
+                                .collect();
+                            pre.insert(
+                                *address,
+                                StateTestAccount::new(info, code.original_bytes(), storage),
+                            );
+                        }
+
+                        // Touched empty accounts are removed, untouched ones are kept as they were
+                        let is_removed =
+                            account.info.is_empty() && (account.is_touched() || pre_info.is_none());
+                        if account.is_selfdestructed() || is_removed {
+                            continue;
+                        }
+                        let code = match &account.info.code {
+                            Some(code) => code.clone(),
+                            None => evm_db.code_by_hash(account.info.code_hash).unwrap(),
+                        };
+                        let storage = account
+                            .storage
+                            .iter()
+                            .map(|(key, slot)| (*key, slot.present_value()))
+                            .collect();
+                        post.insert(
+                            *address,
+                            StateTestAccount::new(&account.info, code.original_bytes(), storage),
+                        );
+                    }
+
+                    let logs: Vec<reth_primitives::Log> =
+                        result.logs().iter().cloned().map(Into::into).collect();
+                    fixtures.insert(
+                        tx.hash(),
+                        StateTestFixture::new(
+                            block.header.hash(),
+                            block.l1_fee_rate,
+                            cfg_env.handler_cfg.spec_id,
+                            env.clone(),
+                            pre,
+                            &tx,
+                            &logs,
+                            post,
+                        ),
+                    );
+                }
+
+                evm_db.commit(state);
+            }
+        }
+
+        Ok(fixtures)
+    }
+
+    /// Handler for: `citrea_exportState`
+    /// Exports the accounts and their non-zero storage at the end of the given block
+    /// in the genesis alloc format of geth. Accounts are paged in creation order,
+    /// up to `limit` accounts starting at `offset`.
+    /// RPC method is moved to ethereum-rpc module
+    pub fn export_state(
+        &self,
+        ctx: &QueryContext,
+        block_number: BlockNumberOrTag,
+        offset: Option<U64>,
+        limit: Option<U64>,
+        working_set: &mut WorkingSet<C>,
+    ) -> RpcResult<StateExport> {
+        info!("evm module: citrea_exportState");
+
+        let block = self.get_sealed_block_for_report(block_number, working_set)?;
+        let offset = offset.map(|offset| offset.to::<u64>()).unwrap_or_default();
+        let limit = limit
+            .map(|limit| limit.to::<u64>())
+            .unwrap_or(MAX_STATE_EXPORT_ACCOUNTS);
+        if limit == 0 || limit > MAX_STATE_EXPORT_ACCOUNTS {
+            return Err(EthApiError::InvalidParams(format!(
+                "Limit must be between 1 and {}",
+                MAX_STATE_EXPORT_ACCOUNTS
+            ))
+            .into());
+        }
+
+        // The index is read at the head, accounts created after the block are skipped below
+        let account_count = self.account_list.len(&mut working_set.accessory_state()) as u64;
+        let end = account_count.min(offset.saturating_add(limit));
+        let accounts: Vec<(Address, Vec<U256>)> = (offset..end)
+            .map(|index| {
+                let mut accessory_state = working_set.accessory_state();
+                let address = self
+                    .account_list
+                    .get(index as usize, &mut accessory_state)
+                    .expect("Account must be set");
+                let genesis_keys = self
+                    .genesis_storage_keys
+                    .get(&address, &mut accessory_state)
+                    .unwrap_or_default();
+                (address, genesis_keys)
+            })
+            .collect();
+
+        let head_number = self
+            .blocks
+            .last(&mut working_set.accessory_state())
+            .expect("Head block must be set")
+            .header
+            .number;
+        // Working state is already at the end of the head block
+        if block.header.number != head_number {
+            self.set_state_to_past_evm_block(ctx, block.header.number, working_set)?;
+        }
+
+        let mut alloc = BTreeMap::new();
+        for (address, genesis_keys) in accounts {
+            let Some(account) = self.accounts.get(&address, working_set) else {
+                continue;
+            };
+
+            let code = if account.info.code_hash == KECCAK_EMPTY {
+                None
+            } else {
+                self.code
+                    .get(&account.info.code_hash, working_set)
+                    .map(|code| code.original_bytes())
+            };
+
+            let mut keys = genesis_keys;
+            keys.extend(account.keys.iter(working_set));
+            keys.sort();
+            keys.dedup();
+            let storage: Vec<(B256, B256)> = keys
+                .into_iter()
+                .filter_map(|key| {
+                    let value = account.storage.get(&key, working_set)?;
+                    (value != U256::ZERO).then(|| (B256::from(key), B256::from(value)))
+                })
+                .collect();
+
+            alloc.insert(
+                address,
+                GenesisAccount::default()
+                    .with_nonce(Some(account.info.nonce))
+                    .with_balance(account.info.balance)
+                    .with_code(code)
+                    .with_storage((!storage.is_empty()).then(|| storage.into_iter().collect())),
+            );
+        }
+
+        Ok(StateExport {
+            block_number: U64::from(block.header.number),
+            state_root: block.header.state_root,
+            alloc,
+            next_offset: (end < account_count).then(|| U64::from(end)),
+        })
+    }
+
     /// Handler for: `eth_getTransactionByBlockHashAndIndex`
     #[rpc_method(name = "eth_getTransactionByBlockHashAndIndex")]
     pub fn get_transaction_by_block_hash_and_index(
@@ -482,15 +1096,17 @@ impl<C: sov_modules_api::Context> Evm<C> {
     }
 
     /// Handler for: `eth_getTransactionByBlockNumberAndIndex`
-    #[rpc_method(name = "eth_getTransactionByBlockNumberAndIndex")]
+    /// RPC method is moved to ethereum-rpc module
     pub fn get_transaction_by_block_number_and_index(
         &self,
+        ctx: &QueryContext,
         block_number: BlockNumberOrTag,
         index: reth_primitives::U64,
         working_set: &mut WorkingSet<C>,
     ) -> RpcResult<Option<reth_rpc_types::Transaction>> {
         info!("evm module: eth_getTransactionByBlockNumberAndIndex");
 
+        let block_number = ctx.resolve_finality_tag(block_number)?;
         let block_number = match self.block_number_for_id(&block_number, working_set) {
             Some(block_number) => block_number,
             None => return Ok(None),
@@ -530,8 +1146,8 @@ impl<C: sov_modules_api::Context> Evm<C> {
     }
 
     /// Handler for: `eth_getTransactionReceipt`
+    /// RPC method is moved to ethereum-rpc module
     // TODO https://github.com/Sovereign-Labs/sovereign-sdk/issues/502
-    #[rpc_method(name = "eth_getTransactionReceipt")]
     pub fn get_transaction_receipt(
         &self,
         hash: reth_primitives::B256,
@@ -566,17 +1182,19 @@ impl<C: sov_modules_api::Context> Evm<C> {
     /// Handler for: `eth_call`
     //https://github.com/paradigmxyz/reth/blob/f577e147807a783438a3f16aad968b4396274483/crates/rpc/rpc/src/eth/api/transactions.rs#L502
     //https://github.com/paradigmxyz/reth/blob/main/crates/rpc/rpc-types/src/eth/call.rs#L7
-    #[rpc_method(name = "eth_call")]
+    /// RPC method is moved to ethereum-rpc module
     pub fn get_call(
         &self,
+        ctx: &QueryContext,
         request: reth_rpc_types::TransactionRequest,
-        block_number: Option<BlockNumberOrTag>,
-        _state_overrides: Option<reth_rpc_types::state::StateOverride>,
-        _block_overrides: Option<Box<reth_rpc_types::BlockOverrides>>,
+        block_id: Option<BlockId>,
+        state_overrides: Option<reth_rpc_types::state::StateOverride>,
+        block_overrides: Option<Box<reth_rpc_types::BlockOverrides>>,
         working_set: &mut WorkingSet<C>,
     ) -> RpcResult<reth_primitives::Bytes> {
         info!("evm module: eth_call");
-        let block_env = match block_number {
+        let block_number = self.resolve_block_id(ctx, block_id, working_set)?;
+        let mut block_env = match block_number {
             None | Some(BlockNumberOrTag::Pending | BlockNumberOrTag::Latest) => {
                 // so we don't unnecessarily set archival version
                 self.block_env.get(working_set).unwrap_or_default().clone()
@@ -587,12 +1205,16 @@ impl<C: sov_modules_api::Context> Evm<C> {
                     None => return Err(EthApiError::UnknownBlockNumber.into()),
                 };
 
-                set_state_to_end_of_evm_block(block.header.number, working_set);
+                self.set_state_to_past_evm_block(ctx, block.header.number, working_set)?;
 
                 BlockEnv::from(&block)
             }
         };
 
+        if let Some(block_overrides) = block_overrides {
+            apply_block_overrides(&mut block_env, *block_overrides)?;
+        }
+
         let mut tx_env = prepare_call_env(&block_env, request.clone())?;
 
         // https://github.com/paradigmxyz/reth/issues/6574
@@ -604,7 +1226,10 @@ impl<C: sov_modules_api::Context> Evm<C> {
             .expect("EVM chain config should be set");
         let cfg_env = get_cfg_env(&block_env, cfg, Some(get_cfg_env_template()));
 
-        let evm_db: EvmDb<'_, C> = self.get_db(working_set);
+        let mut evm_db: EvmDb<'_, C> = self.get_db(working_set);
+        if let Some(state_overrides) = state_overrides {
+            apply_state_overrides(&mut evm_db, state_overrides)?;
+        }
 
         let result = match inspect(
             evm_db,
@@ -619,40 +1244,48 @@ impl<C: sov_modules_api::Context> Evm<C> {
             }
         };
 
-        Ok(ensure_success(result)?)
-    }
-
-    /// Handler for: `eth_blockNumber`
-    #[rpc_method(name = "eth_blockNumber")]
-    pub fn block_number(
-        &self,
-        working_set: &mut WorkingSet<C>,
-    ) -> RpcResult<reth_primitives::U256> {
-        info!("evm module: eth_blockNumber");
-
-        let block_number = U256::from(
-            self.blocks
-                .len(&mut working_set.accessory_state())
-                .saturating_sub(1),
-        );
-        Ok(block_number)
+        Ok(ensure_success(ctx, result)?)
     }
 
-    /// Handler for `eth_createAccessList`
-    #[rpc_method(name = "eth_createAccessList")]
-    pub fn create_access_list(
+    /// Handler for: `eth_simulateV1`
+    /// Simulates blocks of calls on top of the given block, each call seeing the state left
+    /// by the previous ones. Nothing is persisted.
+    /// RPC method is moved to ethereum-rpc module
+    pub fn simulate_v1(
         &self,
-        request: reth_rpc_types::TransactionRequest,
-        block_number: Option<BlockNumberOrTag>,
+        ctx: &QueryContext,
+        payload: SimulatePayload,
+        block_id: Option<BlockId>,
         working_set: &mut WorkingSet<C>,
-    ) -> RpcResult<AccessListWithGasUsed> {
-        info!("evm module: eth_createAccessList");
-
-        let mut request = request.clone();
+    ) -> RpcResult<Vec<SimulatedBlock>> {
+        info!("evm module: eth_simulateV1");
+
+        if payload.block_state_calls.len() > MAX_SIMULATE_BLOCKS {
+            return Err(EthApiError::InvalidParams(format!(
+                "Too many blocks to simulate, at most {} are allowed",
+                MAX_SIMULATE_BLOCKS
+            ))
+            .into());
+        }
+        let call_count: usize = payload
+            .block_state_calls
+            .iter()
+            .map(|block| block.calls.len())
+            .sum();
+        if call_count > MAX_SIMULATE_CALLS {
+            return Err(EthApiError::InvalidParams(format!(
+                "Too many calls to simulate, at most {} are allowed",
+                MAX_SIMULATE_CALLS
+            ))
+            .into());
+        }
+        if payload.trace_transfers {
+            return Err(EthApiError::Unsupported("traceTransfers is not supported").into());
+        }
 
-        let block_env = match block_number {
+        let block_number = self.resolve_block_id(ctx, block_id, working_set)?;
+        let mut parent_env = match block_number {
             None | Some(BlockNumberOrTag::Pending | BlockNumberOrTag::Latest) => {
-                // so we don't unnecessarily set archival version
                 self.block_env.get(working_set).unwrap_or_default().clone()
             }
             _ => {
@@ -661,27 +1294,168 @@ impl<C: sov_modules_api::Context> Evm<C> {
                     None => return Err(EthApiError::UnknownBlockNumber.into()),
                 };
 
-                set_state_to_end_of_evm_block(block.header.number, working_set);
+                self.set_state_to_past_evm_block(ctx, block.header.number, working_set)?;
+
                 BlockEnv::from(&block)
             }
         };
 
-        let mut tx_env = prepare_call_env(&block_env, request.clone())?;
-
         let cfg = self
             .cfg
             .get(working_set)
             .expect("EVM chain config should be set");
-        let mut cfg_env = get_cfg_env(&block_env, cfg, Some(get_cfg_env_template()));
-        cfg_env.disable_block_gas_limit = true;
-        cfg_env.disable_base_fee = true;
 
-        let mut evm_db = self.get_db(working_set);
+        let mut evm_db: EvmDb<'_, C> = self.get_db(working_set);
+        let mut simulated_blocks = Vec::with_capacity(payload.block_state_calls.len());
+        for sim_block in payload.block_state_calls {
+            let mut block_env = BlockEnv {
+                number: parent_env.number + 1,
+                timestamp: parent_env.timestamp + 1,
+                basefee: if payload.validation {
+                    parent_env.basefee
+                } else {
+                    0
+                },
+                ..parent_env.clone()
+            };
+            if let Some(block_overrides) = sim_block.block_overrides {
+                apply_block_overrides(&mut block_env, block_overrides)?;
+            }
+            if block_env.number <= parent_env.number || block_env.timestamp <= parent_env.timestamp
+            {
+                return Err(EthApiError::InvalidParams(
+                    "Simulated blocks must increase in number and timestamp".into(),
+                )
+                .into());
+            }
+            if let Some(state_overrides) = sim_block.state_overrides {
+                apply_state_overrides(&mut evm_db, state_overrides)?;
+            }
 
-        if request.gas.is_none() && tx_env.gas_price > U256::ZERO {
-            // if gas price is set, we need to cap the gas limit
-            cap_tx_gas_limit_with_caller_allowance(&mut evm_db, &mut tx_env)?;
-        }
+            let mut cfg_env = get_cfg_env(&block_env, cfg.clone(), Some(get_cfg_env_template()));
+            cfg_env.disable_base_fee = !payload.validation;
+
+            let mut gas_used = 0u64;
+            let mut log_count = 0u64;
+            let mut calls = Vec::with_capacity(sim_block.calls.len());
+            for (index, mut request) in sim_block.calls.into_iter().enumerate() {
+                let remaining_gas = block_env.gas_limit.saturating_sub(gas_used);
+                if request.gas.is_none() {
+                    request.gas = Some(U256::from(remaining_gas));
+                }
+
+                let mut tx_env = prepare_call_env(&block_env, request)?;
+                if !payload.validation {
+                    tx_env.nonce = None;
+                }
+                if tx_env.gas_limit > remaining_gas {
+                    return Err(EthApiError::InvalidParams(format!(
+                        "Call {} of block {} exceeds the block gas limit",
+                        index, block_env.number
+                    ))
+                    .into());
+                }
+                let gas_limit = tx_env.gas_limit;
+
+                let ResultAndState { result, state } = inspect_no_tracing(
+                    &mut evm_db,
+                    cfg_env.clone(),
+                    block_env.clone().into(),
+                    tx_env,
+                )
+                .map_err(EthApiError::from)?;
+                evm_db.commit(state);
+
+                gas_used += result.gas_used();
+                let call = SimulatedCall::from_execution(
+                    ctx,
+                    result,
+                    gas_limit,
+                    block_env.number,
+                    index as u64,
+                    log_count,
+                );
+                log_count += call.logs.len() as u64;
+                calls.push(call);
+            }
+
+            simulated_blocks.push(SimulatedBlock {
+                number: U64::from(block_env.number),
+                timestamp: U64::from(block_env.timestamp),
+                gas_limit: U64::from(block_env.gas_limit),
+                gas_used: U64::from(gas_used),
+                fee_recipient: block_env.coinbase,
+                base_fee_per_gas: U256::from(block_env.basefee),
+                calls,
+            });
+            parent_env = block_env;
+        }
+
+        Ok(simulated_blocks)
+    }
+
+    /// Handler for: `eth_blockNumber`
+    #[rpc_method(name = "eth_blockNumber")]
+    pub fn block_number(
+        &self,
+        working_set: &mut WorkingSet<C>,
+    ) -> RpcResult<reth_primitives::U256> {
+        info!("evm module: eth_blockNumber");
+
+        let block_number = U256::from(
+            self.blocks
+                .len(&mut working_set.accessory_state())
+                .saturating_sub(1),
+        );
+        Ok(block_number)
+    }
+
+    /// Handler for `eth_createAccessList`
+    /// RPC method is moved to ethereum-rpc module
+    pub fn create_access_list(
+        &self,
+        ctx: &QueryContext,
+        request: reth_rpc_types::TransactionRequest,
+        block_id: Option<BlockId>,
+        working_set: &mut WorkingSet<C>,
+    ) -> RpcResult<AccessListWithGasUsed> {
+        info!("evm module: eth_createAccessList");
+        let block_number = self.resolve_block_id(ctx, block_id, working_set)?;
+
+        let mut request = request.clone();
+
+        let block_env = match block_number {
+            None | Some(BlockNumberOrTag::Pending | BlockNumberOrTag::Latest) => {
+                // so we don't unnecessarily set archival version
+                self.block_env.get(working_set).unwrap_or_default().clone()
+            }
+            _ => {
+                let block = match self.get_sealed_block_by_number(block_number, working_set) {
+                    Some(block) => block,
+                    None => return Err(EthApiError::UnknownBlockNumber.into()),
+                };
+
+                self.set_state_to_past_evm_block(ctx, block.header.number, working_set)?;
+                BlockEnv::from(&block)
+            }
+        };
+
+        let mut tx_env = prepare_call_env(&block_env, request.clone())?;
+
+        let cfg = self
+            .cfg
+            .get(working_set)
+            .expect("EVM chain config should be set");
+        let mut cfg_env = get_cfg_env(&block_env, cfg, Some(get_cfg_env_template()));
+        cfg_env.disable_block_gas_limit = true;
+        cfg_env.disable_base_fee = true;
+
+        let mut evm_db = self.get_db(working_set);
+
+        if request.gas.is_none() && tx_env.gas_price > U256::ZERO {
+            // if gas price is set, we need to cap the gas limit
+            cap_tx_gas_limit_with_caller_allowance(&mut evm_db, &mut tx_env)?;
+        }
 
         let from = request.from.unwrap_or_default();
         let to = if let Some(to) = request.to {
@@ -713,9 +1487,9 @@ impl<C: sov_modules_api::Context> Evm<C> {
                 HaltReason::NonceOverflow => RpcInvalidTransactionError::NonceMaxValue,
                 halt => RpcInvalidTransactionError::EvmHalt(halt),
             }),
-            ExecutionResult::Revert { output, .. } => {
-                Err(RpcInvalidTransactionError::Revert(RevertError::new(output)))
-            }
+            ExecutionResult::Revert { output, .. } => Err(RpcInvalidTransactionError::Revert(
+                RevertError::new(output).with_query_context(ctx),
+            )),
             ExecutionResult::Success { .. } => Ok(()),
         }?;
 
@@ -725,6 +1499,7 @@ impl<C: sov_modules_api::Context> Evm<C> {
         tx_env.access_list = access_list.clone().into_flattened();
 
         let gas_used = self.estimate_gas_with_env(
+            ctx,
             request,
             block_env.clone(),
             cfg_env,
@@ -740,14 +1515,17 @@ impl<C: sov_modules_api::Context> Evm<C> {
 
     /// Handler for: `eth_estimateGas`
     // https://github.com/paradigmxyz/reth/blob/main/crates/rpc/rpc/src/eth/api/call.rs#L172
-    #[rpc_method(name = "eth_estimateGas")]
+    /// RPC method is moved to ethereum-rpc module
     pub fn eth_estimate_gas(
         &self,
+        ctx: &QueryContext,
         request: reth_rpc_types::TransactionRequest,
-        block_number: Option<BlockNumberOrTag>,
+        block_id: Option<BlockId>,
+        state_overrides: Option<reth_rpc_types::state::StateOverride>,
         working_set: &mut WorkingSet<C>,
     ) -> RpcResult<reth_primitives::U64> {
         info!("evm module: eth_estimateGas");
+        let block_number = self.resolve_block_id(ctx, block_id, working_set)?;
         let block_env = match block_number {
             None | Some(BlockNumberOrTag::Pending | BlockNumberOrTag::Latest) => {
                 // so we don't unnecessarily set archival version
@@ -759,7 +1537,7 @@ impl<C: sov_modules_api::Context> Evm<C> {
                     None => return Err(EthApiError::UnknownBlockNumber.into()),
                 };
 
-                set_state_to_end_of_evm_block(block.header.number, working_set);
+                self.set_state_to_past_evm_block(ctx, block.header.number, working_set)?;
                 BlockEnv::from(&block)
             }
         };
@@ -772,7 +1550,13 @@ impl<C: sov_modules_api::Context> Evm<C> {
             .expect("EVM chain config should be set");
         let cfg_env = get_cfg_env(&block_env, cfg, Some(get_cfg_env_template()));
 
-        self.estimate_gas_with_env(request, block_env, cfg_env, &mut tx_env, working_set)
+        // the overrides are written to the working set, which is never committed, so that
+        // every execution of the estimation sees them
+        if let Some(state_overrides) = state_overrides {
+            apply_state_overrides(&mut self.get_db(working_set), state_overrides)?;
+        }
+
+        self.estimate_gas_with_env(ctx, request, block_env, cfg_env, &mut tx_env, working_set)
     }
 
     /// Handler for: `eth_getBlockTransactionCountByHash`
@@ -792,9 +1576,122 @@ impl<C: sov_modules_api::Context> Evm<C> {
         }
     }
 
+    /// Handler for: `eth_getBlockTransactionCountByNumber`
+    /// RPC method is moved to ethereum-rpc module
+    pub fn eth_get_block_transaction_count_by_number(
+        &self,
+        ctx: &QueryContext,
+        block_number: BlockNumberOrTag,
+        working_set: &mut WorkingSet<C>,
+    ) -> RpcResult<Option<reth_primitives::U256>> {
+        info!("evm module: eth_getBlockTransactionCountByNumber");
+        let block_number = ctx.resolve_finality_tag(block_number)?;
+        let block = self.get_block_by_number(Some(block_number), None, working_set)?;
+        match block {
+            Some(block) => Ok(Some(U256::from(block.transactions.len()))),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the L1 block the given L2 block was built on, as recorded by the `L1BlockHashList`
+    /// system contract, without its timestamp, which the EVM doesn't know.
+    /// Returns None if the L2 block doesn't exist or was built before any L1 block was recorded.
+    pub fn get_l1_block_info(
+        &self,
+        ctx: &QueryContext,
+        block_number: BlockNumberOrTag,
+        working_set: &mut WorkingSet<C>,
+    ) -> RpcResult<Option<L1BlockInfo>> {
+        let block_number = ctx.resolve_finality_tag(block_number)?;
+        let Some(block) = self.get_sealed_block_by_number(Some(block_number), working_set) else {
+            return Ok(None);
+        };
+
+        let head_number = self
+            .blocks
+            .last(&mut working_set.accessory_state())
+            .expect("Head block must be set")
+            .header
+            .number;
+        // Working state is already at the end of the head block
+        if block.header.number != head_number {
+            self.set_state_to_past_evm_block(ctx, block.header.number, working_set)?;
+        }
+
+        // The contract stores the number of the next L1 block to record
+        let next_l1_height = self
+            .accounts
+            .get(&L1BlockHashList::address(), working_set)
+            .and_then(|account| {
+                account
+                    .storage
+                    .get(&U256::from(L1BlockHashList::BLOCK_NUMBER_SLOT), working_set)
+            })
+            .unwrap_or_default()
+            .saturating_to::<u64>();
+        if next_l1_height == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(L1BlockInfo {
+            l2_height: U64::from(block.header.number),
+            l1_height: U64::from(next_l1_height - 1),
+            l1_hash: block.l1_hash,
+            l1_timestamp: None,
+        }))
+    }
+
+    /// Handler for: `citrea_getBlockOrderingInfo`
+    /// Returns the transactions of the given block in the order the sequencer put them,
+    /// with their effective priority fee and the ordering policy version of the block.
+    /// Returns None if the block doesn't exist.
+    /// RPC method is moved to ethereum-rpc module
+    pub fn get_block_ordering_info(
+        &self,
+        ctx: &QueryContext,
+        block_number: BlockNumberOrTag,
+        working_set: &mut WorkingSet<C>,
+    ) -> RpcResult<Option<BlockOrderingInfo>> {
+        info!("evm module: citrea_getBlockOrderingInfo");
+
+        let block_number = ctx.resolve_finality_tag(block_number)?;
+        let Some(block) = self.get_sealed_block_by_number(Some(block_number), working_set) else {
+            return Ok(None);
+        };
+        let base_fee = block.header.base_fee_per_gas.unwrap_or_default();
+
+        let mut transactions = vec![];
+        for (position, id) in block.transactions.clone().enumerate() {
+            let tx = self
+                .transactions
+                .get(id as usize, &mut working_set.accessory_state())
+                .expect("Transaction must be set");
+            if tx.signer == SYSTEM_SIGNER {
+                continue;
+            }
+            let effective_gas_price = tx.signed_transaction.effective_gas_price(Some(base_fee));
+            transactions.push(TxOrderingInfo {
+                hash: tx.signed_transaction.hash,
+                position: U64::from(position),
+                effective_priority_fee: U256::from(
+                    effective_gas_price.saturating_sub(base_fee as u128),
+                ),
+            });
+        }
+
+        Ok(Some(BlockOrderingInfo {
+            block_number: U64::from(block.header.number),
+            base_fee_per_gas: U64::from(base_fee),
+            ordering_policy_version: ordering_policy_version(&block.header.extra_data)
+                .map(U64::from),
+            transactions,
+        }))
+    }
+
     /// Inner gas estimator
     pub(crate) fn estimate_gas_with_env(
         &self,
+        ctx: &QueryContext,
         request: reth_rpc_types::TransactionRequest,
         block_env: BlockEnv,
         cfg_env: CfgEnvWithHandlerCfg,
@@ -884,7 +1781,9 @@ impl<C: sov_modules_api::Context> Evm<C> {
             // again with the block's gas limit to check if revert is gas related or not
             if request_gas.is_some() || request_gas_price.is_some() {
                 let evm_db = self.get_db(working_set);
-                return Err(map_out_of_gas_err(block_env, tx_env.clone(), cfg_env, evm_db).into());
+                return Err(
+                    map_out_of_gas_err(ctx, block_env, tx_env.clone(), cfg_env, evm_db).into(),
+                );
             }
         }
 
@@ -899,10 +1798,16 @@ impl<C: sov_modules_api::Context> Evm<C> {
                     // again with the block's gas limit to check if revert is gas related or not
                     return if request_gas.is_some() || request_gas_price.is_some() {
                         let evm_db = self.get_db(working_set);
-                        Err(map_out_of_gas_err(block_env, tx_env.clone(), cfg_env, evm_db).into())
+                        Err(
+                            map_out_of_gas_err(ctx, block_env, tx_env.clone(), cfg_env, evm_db)
+                                .into(),
+                        )
                     } else {
                         // the transaction did revert
-                        Err(RpcInvalidTransactionError::Revert(RevertError::new(output)).into())
+                        Err(RpcInvalidTransactionError::Revert(
+                            RevertError::new(output).with_query_context(ctx),
+                        )
+                        .into())
                     };
                 }
             },
@@ -1060,11 +1965,94 @@ impl<C: sov_modules_api::Context> Evm<C> {
     /// Traces the entire block txs and returns the traces
     pub fn trace_block_transactions_by_number(
         &self,
+        ctx: &QueryContext,
         block_number: u64,
         opts: Option<GethDebugTracingOptions>,
         stop_at: Option<usize>,
         working_set: &mut WorkingSet<C>,
     ) -> RpcResult<Vec<GethTrace>> {
+        self.replay_block_transactions(
+            ctx,
+            block_number,
+            stop_at,
+            working_set,
+            |_, tx, cfg_env, block_env, evm_db, l1_fee_rate, gas_schedule| {
+                trace_transaction(
+                    ctx,
+                    opts.clone().unwrap_or_default(),
+                    cfg_env,
+                    block_env,
+                    tx_env_with_recovered(tx),
+                    tx.hash(),
+                    evm_db,
+                    l1_fee_rate,
+                    gas_schedule,
+                )
+            },
+        )
+    }
+
+    /// Traces the entire block txs into flat parity traces, `trace_block` of the `trace` namespace
+    pub fn parity_trace_block_transactions_by_number(
+        &self,
+        ctx: &QueryContext,
+        block_number: u64,
+        stop_at: Option<usize>,
+        working_set: &mut WorkingSet<C>,
+    ) -> RpcResult<Vec<Vec<LocalizedTransactionTrace>>> {
+        let sealed_block = self
+            .get_sealed_block_by_number(Some(BlockNumberOrTag::Number(block_number)), working_set)
+            .ok_or_else(|| EthApiError::UnknownBlockNumber)?;
+        let block_hash = sealed_block.header.hash();
+        let base_fee = sealed_block.header.base_fee_per_gas;
+
+        self.replay_block_transactions(
+            ctx,
+            block_number,
+            stop_at,
+            working_set,
+            |index, tx, cfg_env, block_env, evm_db, l1_fee_rate, gas_schedule| {
+                let tx_info = TransactionInfo {
+                    hash: Some(tx.hash()),
+                    index: Some(index as u64),
+                    block_hash: Some(block_hash),
+                    block_number: Some(block_number),
+                    base_fee,
+                };
+                trace_transaction_parity(
+                    cfg_env,
+                    block_env,
+                    tx_env_with_recovered(tx),
+                    tx_info,
+                    evm_db,
+                    l1_fee_rate,
+                    gas_schedule,
+                )
+            },
+        )
+    }
+
+    /// Replays the txs of a block on top of the state at the end of the previous block, up to
+    /// the tx at `stop_at` if set, and returns the output of `trace` for each tx.
+    fn replay_block_transactions<T, F>(
+        &self,
+        ctx: &QueryContext,
+        block_number: u64,
+        stop_at: Option<usize>,
+        working_set: &mut WorkingSet<C>,
+        mut trace: F,
+    ) -> RpcResult<Vec<T>>
+    where
+        F: FnMut(
+            usize,
+            &TransactionSignedEcRecovered,
+            CfgEnvWithHandlerCfg,
+            revm::primitives::BlockEnv,
+            &mut EvmDb<'_, C>,
+            u64,
+            GasSchedule,
+        ) -> EthResult<(T, revm::primitives::State)>,
+    {
         let sealed_block = self
             .get_sealed_block_by_number(Some(BlockNumberOrTag::Number(block_number)), working_set)
             .ok_or_else(|| EthApiError::UnknownBlockNumber)?;
@@ -1084,12 +2072,13 @@ impl<C: sov_modules_api::Context> Evm<C> {
             .collect();
 
         // set state to end of the previous block
-        set_state_to_end_of_evm_block(block_number - 1, working_set);
+        self.set_state_to_past_evm_block(ctx, block_number - 1, working_set)?;
 
         let block_env = BlockEnv::from(&sealed_block);
         let cfg = self.cfg.get(working_set).unwrap();
         let cfg_env = get_cfg_env(&block_env, cfg, Some(get_cfg_env_template()));
         let l1_fee_rate = sealed_block.l1_fee_rate;
+        let gas_schedule = self.gas_schedule.get(working_set).unwrap_or_default();
 
         // EvmDB is the replacement of revm::CacheDB because cachedb requires immutable state
         // TODO: Move to CacheDB once immutable state is implemented
@@ -1097,17 +2086,17 @@ impl<C: sov_modules_api::Context> Evm<C> {
 
         // TODO: Convert below steps to blocking task like in reth after implementing the semaphores
         let mut traces = Vec::new();
-        let mut transactions = block_txs.into_iter().enumerate().peekable();
+        let mut transactions = block_txs.iter().enumerate().peekable();
         let limit = stop_at.unwrap_or(usize::MAX);
         while let Some((index, tx)) = transactions.next() {
-            let (trace, state_changes) = trace_transaction(
-                opts.clone().unwrap_or_default(),
+            let (trace, state_changes) = trace(
+                index,
+                tx,
                 cfg_env.clone(),
                 block_env.clone().into(),
-                tx_env_with_recovered(&tx),
-                tx.hash(),
                 &mut evm_db,
                 l1_fee_rate,
+                gas_schedule.clone(),
             )?;
             traces.push(trace);
 
@@ -1124,6 +2113,398 @@ impl<C: sov_modules_api::Context> Evm<C> {
         Ok(traces)
     }
 
+    /// Handler for: `trace_block`
+    /// Returns the flat parity traces of all the transactions of a block.
+    /// RPC method is moved to ethereum-rpc module
+    pub fn trace_block(
+        &self,
+        ctx: &QueryContext,
+        block_number: BlockNumberOrTag,
+        working_set: &mut WorkingSet<C>,
+    ) -> RpcResult<Vec<LocalizedTransactionTrace>> {
+        info!("evm module: trace_block");
+
+        let block_number = self
+            .get_sealed_block_for_report(block_number, working_set)?
+            .header
+            .number;
+        Ok(self
+            .parity_trace_block_transactions_by_number(ctx, block_number, None, working_set)?
+            .into_iter()
+            .flatten()
+            .collect())
+    }
+
+    /// Handler for: `trace_transaction`
+    /// Returns the flat parity traces of a transaction, `None` if the transaction is not found.
+    /// RPC method is moved to ethereum-rpc module
+    pub fn trace_transaction(
+        &self,
+        ctx: &QueryContext,
+        tx_hash: B256,
+        working_set: &mut WorkingSet<C>,
+    ) -> RpcResult<Option<Vec<LocalizedTransactionTrace>>> {
+        info!("evm module: trace_transaction");
+
+        let mut accessory_state = working_set.accessory_state();
+        let Some(tx_number) = self.transaction_hashes.get(&tx_hash, &mut accessory_state) else {
+            return Ok(None);
+        };
+        let tx = self
+            .transactions
+            .get(tx_number as usize, &mut accessory_state)
+            .expect("Transaction with known hash must be set");
+        let block = self
+            .blocks
+            .get(tx.block_number as usize, &mut accessory_state)
+            .expect("Block number for known transaction must be set");
+        let index = (tx_number - block.transactions.start) as usize;
+
+        let mut traces = self.parity_trace_block_transactions_by_number(
+            ctx,
+            tx.block_number,
+            Some(index),
+            working_set,
+        )?;
+        Ok(traces.pop())
+    }
+
+    /// Handler for: `trace_filter`
+    /// Returns the flat parity traces of a block range matching the given senders and receivers.
+    /// RPC method is moved to ethereum-rpc module
+    pub fn trace_filter(
+        &self,
+        ctx: &QueryContext,
+        filter: TraceFilter,
+        working_set: &mut WorkingSet<C>,
+    ) -> RpcResult<Vec<LocalizedTransactionTrace>> {
+        info!("evm module: trace_filter");
+
+        let from = self
+            .get_sealed_block_for_report(
+                filter.from_block.unwrap_or(BlockNumberOrTag::Latest),
+                working_set,
+            )?
+            .header
+            .number;
+        let to = self
+            .get_sealed_block_for_report(
+                filter.to_block.unwrap_or(BlockNumberOrTag::Latest),
+                working_set,
+            )?
+            .header
+            .number;
+        if from > to {
+            return Err(EthApiError::InvalidBlockRange.into());
+        }
+        if to - from >= MAX_TRACE_FILTER_BLOCK_RANGE {
+            return Err(EthApiError::InvalidParams(format!(
+                "Block range exceeds the limit of {} blocks",
+                MAX_TRACE_FILTER_BLOCK_RANGE
+            ))
+            .into());
+        }
+
+        let mut traces = vec![];
+        // genesis block has no transactions
+        for block_number in from.max(1)..=to {
+            traces.extend(
+                self.parity_trace_block_transactions_by_number(
+                    ctx,
+                    block_number,
+                    None,
+                    working_set,
+                )?
+                .into_iter()
+                .flatten()
+                .filter(|trace| filter.matches(trace)),
+            );
+        }
+        Ok(traces
+            .into_iter()
+            .skip(filter.after.unwrap_or_default() as usize)
+            .take(filter.count.map_or(usize::MAX, |count| count as usize))
+            .collect())
+    }
+
+    /// Handler for: `ots_getApiLevel`
+    /// Returns the version of the Otterscan API served by the node.
+    #[rpc_method(name = "ots_getApiLevel")]
+    pub fn ots_get_api_level(&self, _working_set: &mut WorkingSet<C>) -> RpcResult<u64> {
+        info!("evm module: ots_getApiLevel");
+        Ok(OTS_API_LEVEL)
+    }
+
+    /// Handler for: `ots_getBlockDetails`
+    /// Returns a block with its transaction count instead of its transactions, and its fees.
+    #[rpc_method(name = "ots_getBlockDetails")]
+    pub fn ots_get_block_details(
+        &self,
+        block_number: u64,
+        working_set: &mut WorkingSet<C>,
+    ) -> RpcResult<Option<OtsBlockDetails>> {
+        info!("evm module: ots_getBlockDetails");
+
+        let Some(sealed_block) = self
+            .blocks
+            .get(block_number as usize, &mut working_set.accessory_state())
+        else {
+            return Ok(None);
+        };
+        let Some(mut block) = self.get_block_by_number(
+            Some(BlockNumberOrTag::Number(block_number)),
+            Some(false),
+            working_set,
+        )?
+        else {
+            return Ok(None);
+        };
+        block.inner.transactions = reth_rpc_types::BlockTransactions::Hashes(vec![]);
+
+        let total_fees = sealed_block
+            .transactions
+            .clone()
+            .map(|id| {
+                let receipt = self
+                    .receipts
+                    .get(id as usize, &mut working_set.accessory_state())
+                    .expect("Receipt for known transaction must be set");
+                let tx = self
+                    .transactions
+                    .get(id as usize, &mut working_set.accessory_state())
+                    .expect("Transaction must be set");
+                let receipt = build_rpc_receipt(&sealed_block, tx, id, receipt);
+                U256::from(receipt.effective_gas_price.to::<u128>())
+                    * receipt.gas_used.unwrap_or_default()
+            })
+            .fold(U256::ZERO, |total, fee| total + fee);
+
+        Ok(Some(OtsBlockDetails {
+            block: OtsBlock {
+                block,
+                transaction_count: U64::from(
+                    sealed_block.transactions.end - sealed_block.transactions.start,
+                ),
+            },
+            issuance: OtsIssuance::default(),
+            total_fees,
+        }))
+    }
+
+    /// Handler for: `ots_getTransactionBySenderAndNonce`
+    /// Returns the hash of the transaction of a sender with the given nonce.
+    /// RPC method is moved to ethereum-rpc module
+    pub fn ots_get_transaction_by_sender_and_nonce(
+        &self,
+        ctx: &QueryContext,
+        sender: Address,
+        nonce: u64,
+        working_set: &mut WorkingSet<C>,
+    ) -> RpcResult<Option<B256>> {
+        info!("evm module: ots_getTransactionBySenderAndNonce");
+
+        let nonce_at_end_of =
+            |block_number: Option<u64>, working_set: &mut WorkingSet<C>| -> EthResult<u64> {
+                if let Some(block_number) = block_number {
+                    self.set_state_to_past_evm_block(ctx, block_number, working_set)?;
+                }
+                let nonce = self
+                    .accounts
+                    .get(&sender, working_set)
+                    .map(|account| account.info.nonce)
+                    .unwrap_or_default();
+                working_set.unset_archival_version();
+                Ok(nonce)
+            };
+
+        if nonce_at_end_of(None, working_set)? <= nonce {
+            return Ok(None);
+        }
+
+        // The nonce only grows, so the transaction is in the first block after which
+        // the nonce of the sender is above it
+        let head_number = self
+            .blocks
+            .last(&mut working_set.accessory_state())
+            .expect("Head block must be set")
+            .header
+            .number;
+        let (mut low, mut high) = (0, head_number);
+        while low < high {
+            let mid = low + (high - low) / 2;
+            if nonce_at_end_of(Some(mid), working_set)? > nonce {
+                high = mid;
+            } else {
+                low = mid + 1;
+            }
+        }
+
+        let block = self
+            .blocks
+            .get(low as usize, &mut working_set.accessory_state())
+            .expect("Block must be set");
+        let hash = block.transactions.clone().find_map(|id| {
+            let tx = self
+                .transactions
+                .get(id as usize, &mut working_set.accessory_state())
+                .expect("Transaction must be set");
+            (tx.signer == sender && tx.signed_transaction.nonce() == nonce)
+                .then_some(tx.signed_transaction.hash)
+        });
+        Ok(hash)
+    }
+
+    /// Handler for: `ots_searchTransactionsBefore`
+    /// Returns the transactions from, to or creating the address in the blocks before the
+    /// given one, or up to the head block if it is 0. Whole blocks are returned, so a page
+    /// can hold more than `page_size` transactions.
+    #[rpc_method(name = "ots_searchTransactionsBefore")]
+    pub fn ots_search_transactions_before(
+        &self,
+        address: Address,
+        block_number: u64,
+        page_size: usize,
+        working_set: &mut WorkingSet<C>,
+    ) -> RpcResult<OtsTransactionsPage> {
+        info!("evm module: ots_searchTransactionsBefore");
+
+        let head_number = self
+            .blocks
+            .last(&mut working_set.accessory_state())
+            .expect("Head block must be set")
+            .header
+            .number;
+        let to = match block_number {
+            0 => head_number,
+            block_number => block_number.saturating_sub(1).min(head_number),
+        };
+        let page_size = page_size.min(MAX_OTS_PAGE_SIZE);
+
+        let mut page = OtsTransactionsPage {
+            first_page: block_number == 0,
+            last_page: true,
+            ..Default::default()
+        };
+        // genesis block has no transactions
+        for number in (1..=to).rev() {
+            if page.txs.len() >= page_size {
+                page.last_page = false;
+                break;
+            }
+            self.search_block_transactions(number, address, &mut page, working_set);
+        }
+        Ok(page)
+    }
+
+    /// Handler for: `ots_searchTransactionsAfter`
+    /// Returns the transactions from, to or creating the address in the blocks after the
+    /// given one, newest first. Whole blocks are returned, so a page can hold more than
+    /// `page_size` transactions.
+    #[rpc_method(name = "ots_searchTransactionsAfter")]
+    pub fn ots_search_transactions_after(
+        &self,
+        address: Address,
+        block_number: u64,
+        page_size: usize,
+        working_set: &mut WorkingSet<C>,
+    ) -> RpcResult<OtsTransactionsPage> {
+        info!("evm module: ots_searchTransactionsAfter");
+
+        let head_number = self
+            .blocks
+            .last(&mut working_set.accessory_state())
+            .expect("Head block must be set")
+            .header
+            .number;
+        let page_size = page_size.min(MAX_OTS_PAGE_SIZE);
+
+        let mut page = OtsTransactionsPage {
+            first_page: true,
+            last_page: block_number == 0,
+            ..Default::default()
+        };
+        for number in (block_number + 1).max(1)..=head_number {
+            if page.txs.len() >= page_size {
+                page.first_page = false;
+                break;
+            }
+            // blocks are searched oldest first, their transactions are added newest first
+            let mut block_page = OtsTransactionsPage::default();
+            self.search_block_transactions(number, address, &mut block_page, working_set);
+            block_page.txs.append(&mut page.txs);
+            block_page.receipts.append(&mut page.receipts);
+            page.txs = block_page.txs;
+            page.receipts = block_page.receipts;
+        }
+        Ok(page)
+    }
+
+    /// Traces a call on top of the state at the end of the given block, with optional state
+    /// and block overrides, without submitting a transaction.
+    pub fn trace_call(
+        &self,
+        ctx: &QueryContext,
+        request: reth_rpc_types::TransactionRequest,
+        block_id: Option<BlockId>,
+        opts: Option<GethDebugTracingCallOptions>,
+        working_set: &mut WorkingSet<C>,
+    ) -> RpcResult<GethTrace> {
+        let block_number = self.resolve_block_id(ctx, block_id, working_set)?;
+        let GethDebugTracingCallOptions {
+            tracing_options,
+            state_overrides,
+            block_overrides,
+        } = opts.unwrap_or_default();
+
+        let mut block_env = match block_number {
+            None | Some(BlockNumberOrTag::Pending | BlockNumberOrTag::Latest) => {
+                self.block_env.get(working_set).unwrap_or_default().clone()
+            }
+            _ => {
+                let block = self
+                    .get_sealed_block_by_number(block_number, working_set)
+                    .ok_or(EthApiError::UnknownBlockNumber)?;
+
+                self.set_state_to_past_evm_block(ctx, block.header.number, working_set)?;
+
+                BlockEnv::from(&block)
+            }
+        };
+
+        if let Some(block_overrides) = block_overrides {
+            apply_block_overrides(&mut block_env, block_overrides)?;
+        }
+
+        let mut tx_env = prepare_call_env(&block_env, request)?;
+        tx_env.nonce = None;
+
+        let cfg = self
+            .cfg
+            .get(working_set)
+            .expect("EVM chain config should be set");
+        let cfg_env = get_cfg_env(&block_env, cfg, Some(get_cfg_env_template()));
+        let gas_schedule = self.gas_schedule.get(working_set).unwrap_or_default();
+
+        let mut evm_db = self.get_db(working_set);
+        if let Some(state_overrides) = state_overrides {
+            apply_state_overrides(&mut evm_db, state_overrides)?;
+        }
+
+        // like eth_call, calls don't pay the L1 fee
+        let (trace, _) = trace_transaction(
+            ctx,
+            tracing_options,
+            cfg_env,
+            block_env.into(),
+            tx_env,
+            B256::ZERO,
+            &mut evm_db,
+            0,
+            gas_schedule,
+        )?;
+        Ok(trace)
+    }
+
     // https://github.com/paradigmxyz/reth/blob/8892d04a88365ba507f28c3314d99a6b54735d3f/crates/rpc/rpc/src/eth/filter.rs#L349
     fn logs_for_filter(
         &self,
@@ -1201,6 +2582,9 @@ impl<C: sov_modules_api::Context> Evm<C> {
         from_block_number: u64,
         to_block_number: u64,
     ) -> Result<Vec<LogResponse>, FilterError> {
+        if from_block_number > to_block_number {
+            return Err(FilterError::InvalidBlockRangeParams);
+        }
         let max_blocks_per_filter: u64 = DEFAULT_MAX_BLOCKS_PER_FILTER;
         if to_block_number - from_block_number >= max_blocks_per_filter {
             return Err(FilterError::QueryExceedsMaxBlocks(max_blocks_per_filter));
@@ -1212,35 +2596,51 @@ impl<C: sov_modules_api::Context> Evm<C> {
         let topics_filter: Vec<BloomFilter> =
             filter.topics.iter().map(|t| t.to_bloom_filter()).collect();
 
-        let max_headers_range = MAX_HEADERS_RANGE;
+        // loop over the sections of the range and skip the sections whose merged logs bloom
+        // doesn't match the filter, then check the logs bloom of each block of the section
+        let mut section_start = from_block_number;
+        while section_start <= to_block_number {
+            let section = section_start / LOG_BLOOM_SECTION_SIZE;
+            let section_end = ((section + 1) * LOG_BLOOM_SECTION_SIZE - 1).min(to_block_number);
+            let range = section_start..=section_end;
+            section_start = section_end + 1;
+
+            if !self.log_bloom_section_matches(
+                section,
+                &address_filter,
+                &topics_filter,
+                working_set,
+            ) {
+                continue;
+            }
 
-        // loop over the range of new blocks and check logs if the filter matches the log's bloom
-        // filter
-        for (from, to) in
-            BlockRangeInclusiveIter::new(from_block_number..=to_block_number, max_headers_range)
-        {
-            for idx in from..=to {
-                let block = match self.blocks.get(
-                    // Index from +1 or just from?
-                    (idx) as usize,
-                    &mut working_set.accessory_state(),
-                ) {
-                    Some(block) => block,
-                    None => {
-                        return Err(FilterError::EthAPIError(
-                            // from and to are checked against last block
-                            // so this should never happen ideally
-                            ProviderError::BlockBodyIndicesNotFound(idx).into(),
-                        ));
+            for idx in range {
+                let block = match self
+                    .block_log_blooms
+                    .get(&idx, &mut working_set.accessory_state())
+                {
+                    Some(logs_bloom)
+                        if !logs_bloom_matches(logs_bloom, &address_filter, &topics_filter) =>
+                    {
+                        continue
                     }
+                    // blocks finalized before the logs bloom index are checked by their header
+                    _ => match self
+                        .blocks
+                        .get(idx as usize, &mut working_set.accessory_state())
+                    {
+                        Some(block) => block,
+                        None => {
+                            return Err(FilterError::EthAPIError(
+                                // from and to are checked against last block
+                                // so this should never happen ideally
+                                ProviderError::BlockBodyIndicesNotFound(idx).into(),
+                            ));
+                        }
+                    },
                 };
 
-                let logs_bloom = block.header.logs_bloom;
-
-                let alloy_logs_bloom = alloy_primitives::Bloom::from(logs_bloom.data());
-                if matches_address(alloy_logs_bloom, &address_filter)
-                    && matches_topics(alloy_logs_bloom, &topics_filter)
-                {
+                if logs_bloom_matches(block.header.logs_bloom, &address_filter, &topics_filter) {
                     self.append_matching_block_logs(working_set, &mut all_logs, filter, block);
                     let max_logs_per_response = DEFAULT_MAX_LOGS_PER_RESPONSE;
                     // size check but only if range is multiple blocks, so we always return all
@@ -1255,6 +2655,33 @@ impl<C: sov_modules_api::Context> Evm<C> {
         Ok(all_logs)
     }
 
+    /// Returns whether the blocks of a section of the logs bloom index may have logs matching
+    /// the filter.
+    fn log_bloom_section_matches(
+        &self,
+        section: u64,
+        address_filter: &BloomFilter,
+        topics_filter: &[BloomFilter],
+        working_set: &mut WorkingSet<C>,
+    ) -> bool {
+        // the merged bloom of a section misses the blocks finalized before the index,
+        // so sections are only skipped if their first block is indexed
+        let first_block = section * LOG_BLOOM_SECTION_SIZE;
+        if self
+            .block_log_blooms
+            .get(&first_block, &mut working_set.accessory_state())
+            .is_none()
+        {
+            return true;
+        }
+
+        self.log_bloom_sections
+            .get(&section, &mut working_set.accessory_state())
+            .map_or(true, |logs_bloom| {
+                logs_bloom_matches(logs_bloom, address_filter, topics_filter)
+            })
+    }
+
     // https://github.com/paradigmxyz/reth/blob/main/crates/rpc/rpc/src/eth/logs_utils.rs#L21
     fn append_matching_block_logs(
         &self,
@@ -1420,6 +2847,7 @@ impl<C: sov_modules_api::Context> Evm<C> {
 
     /// Helper function to check if the block number is valid
     /// If returns None, block doesn't exist
+    /// The `safe` and `finalized` tags must be resolved with the [`QueryContext`] of the node first.
     pub fn block_number_for_id(
         &self,
         block_id: &BlockNumberOrTag,
@@ -1427,7 +2855,7 @@ impl<C: sov_modules_api::Context> Evm<C> {
     ) -> Option<u64> {
         match block_id {
             BlockNumberOrTag::Earliest => Some(0),
-            BlockNumberOrTag::Latest => self
+            BlockNumberOrTag::Latest | BlockNumberOrTag::Pending => self
                 .blocks
                 .last(&mut working_set.accessory_state())
                 .map(|block| block.header.number),
@@ -1438,12 +2866,99 @@ impl<C: sov_modules_api::Context> Evm<C> {
                     None
                 }
             }
-            _ => {
-                todo!();
+            BlockNumberOrTag::Safe | BlockNumberOrTag::Finalized => None,
+        }
+    }
+
+    /// Resolves an EIP-1898 block parameter to a block number or tag, along with the
+    /// `safe` and `finalized` tags.
+    /// Every stored block is canonical, so `requireCanonical` always holds for a known hash.
+    fn resolve_block_id(
+        &self,
+        ctx: &QueryContext,
+        block_id: Option<BlockId>,
+        working_set: &mut WorkingSet<C>,
+    ) -> EthResult<Option<BlockNumberOrTag>> {
+        match block_id {
+            Some(BlockId::Hash(block_hash)) => {
+                let block_number = self
+                    .block_hashes
+                    .get(&block_hash.block_hash, &mut working_set.accessory_state())
+                    .ok_or(EthApiError::UnknownBlockHash(block_hash.block_hash))?;
+                Ok(Some(BlockNumberOrTag::Number(block_number)))
             }
+            Some(BlockId::Number(block_number)) => {
+                Ok(Some(ctx.resolve_finality_tag(block_number)?))
+            }
+            None => Ok(None),
         }
     }
 
+    /// Adds the transactions of a block from, to or creating the address to a page of
+    /// Otterscan search results, newest first.
+    fn search_block_transactions(
+        &self,
+        block_number: u64,
+        address: Address,
+        page: &mut OtsTransactionsPage,
+        working_set: &mut WorkingSet<C>,
+    ) {
+        let block = self
+            .blocks
+            .get(block_number as usize, &mut working_set.accessory_state())
+            .expect("Block must be set");
+        for id in block.transactions.clone().rev() {
+            let tx = self
+                .transactions
+                .get(id as usize, &mut working_set.accessory_state())
+                .expect("Transaction must be set");
+            let receipt = self
+                .receipts
+                .get(id as usize, &mut working_set.accessory_state())
+                .expect("Receipt for known transaction must be set");
+            let receipt = build_rpc_receipt(&block, tx.clone(), id, receipt);
+            if receipt.from != address
+                && receipt.to != Some(address)
+                && receipt.contract_address != Some(address)
+            {
+                continue;
+            }
+
+            page.txs.push(
+                reth_rpc_types_compat::transaction::from_recovered_with_block_context(
+                    tx.into(),
+                    block.header.hash(),
+                    block.header.number,
+                    block.header.base_fee_per_gas,
+                    U256::from(id - block.transactions.start),
+                ),
+            );
+            page.receipts.push(OtsReceipt {
+                receipt,
+                timestamp: U64::from(block.header.timestamp),
+            });
+        }
+    }
+
+    /// Sets the working set to the state at the end of a past block.
+    /// Fails if the node doesn't keep the state of the block.
+    fn set_state_to_past_evm_block(
+        &self,
+        ctx: &QueryContext,
+        block_number: u64,
+        working_set: &mut WorkingSet<C>,
+    ) -> EthResult<()> {
+        let head_number = self
+            .blocks
+            .last(&mut working_set.accessory_state())
+            .expect("Head block must be set")
+            .header
+            .number;
+        ctx.check_state_history(block_number, head_number)?;
+        set_state_to_end_of_evm_block(block_number, working_set);
+        Ok(())
+    }
+
     /// Helper function to get sealed block by number
     /// If returns None, block doesn't exist
     fn get_sealed_block_by_number(
@@ -1451,17 +2966,19 @@ impl<C: sov_modules_api::Context> Evm<C> {
         block_number: Option<BlockNumberOrTag>,
         working_set: &mut WorkingSet<C>,
     ) -> Option<SealedBlock> {
-        // safe, finalized, and pending are not supported
         match block_number {
             Some(BlockNumberOrTag::Number(block_number)) => self
                 .blocks
                 .get(block_number as usize, &mut working_set.accessory_state()),
+            // safe and finalized are resolved by the callers with the query context
+            Some(BlockNumberOrTag::Safe | BlockNumberOrTag::Finalized) => None,
             Some(BlockNumberOrTag::Earliest) => Some(
                 self.blocks
                     .get(0, &mut working_set.accessory_state())
                     .expect("Genesis block must be set"),
             ),
-            Some(BlockNumberOrTag::Latest) => Some(
+            // Pending blocks aren't built ahead of time, tooling asking for one gets the head block
+            Some(BlockNumberOrTag::Latest) | Some(BlockNumberOrTag::Pending) => Some(
                 self.blocks
                     .last(&mut working_set.accessory_state())
                     .expect("Head block must be set"),
@@ -1471,10 +2988,122 @@ impl<C: sov_modules_api::Context> Evm<C> {
                     .last(&mut working_set.accessory_state())
                     .expect("Head block must be set"),
             ),
-            _ => panic!("Unsupported block number type"),
         }
     }
 
+    /// Applies the given transactions to the working set on top of the latest state,
+    /// as if they were included in the next block, with its number and base fee.
+    /// Invalid transactions are skipped.
+    /// Used by the sequencer to serve reads at the `pending` tag and to dry-run blocks,
+    /// the working set must not be committed.
+    pub fn apply_pending_transactions(
+        &self,
+        txs: &[TransactionSignedEcRecovered],
+        working_set: &mut WorkingSet<C>,
+    ) -> Vec<PendingTransactionResult> {
+        let cfg = self
+            .cfg
+            .get(working_set)
+            .expect("EVM chain config should be set");
+        // the block env in the state is the one of the head block
+        let head = self.head.get(working_set).expect("Head block must be set");
+        let block_env = BlockEnv {
+            number: head.header.number + 1,
+            coinbase: cfg.coinbase,
+            timestamp: head.header.timestamp,
+            prevrandao: head.header.mix_hash,
+            basefee: self
+                .next_block_base_fee(&head.header, working_set)
+                .unwrap_or_default(),
+            gas_limit: cfg.block_gas_limit,
+        };
+        let cfg_env = get_cfg_env(&block_env, cfg, None);
+        let l1_fee_rate = self.l1_fee_rate.get(working_set).unwrap_or_default();
+        let gas_schedule = self.gas_schedule.get(working_set).unwrap_or_default();
+        let mut citrea_handler_ext =
+            CitreaExternal::new(l1_fee_rate).with_gas_schedule(gas_schedule);
+
+        let evm_db: EvmDb<'_, C> = self.get_db(working_set);
+        let results = executor::execute_multiple_tx(
+            evm_db,
+            block_env,
+            txs,
+            cfg_env,
+            &mut citrea_handler_ext,
+            0,
+        );
+
+        txs.iter()
+            .zip(results)
+            .map(|(tx, result)| {
+                let hash = tx.hash();
+                let (gas_used, success, error) = match result {
+                    Ok(result) => (result.gas_used(), result.is_success(), None),
+                    Err(e) => (0, false, Some(format!("{:?}", e))),
+                };
+                let diff_size = match error {
+                    None => citrea_handler_ext
+                        .get_tx_info(hash)
+                        .map(|info| info.diff_size)
+                        .unwrap_or_default(),
+                    Some(_) => 0,
+                };
+                PendingTransactionResult {
+                    hash,
+                    from: tx.signer(),
+                    nonce: tx.nonce(),
+                    gas_used,
+                    diff_size,
+                    success,
+                    error,
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the block at a number, earliest or latest tag for `citrea_getStorageRentReport`.
+    fn get_sealed_block_for_report(
+        &self,
+        block_number: BlockNumberOrTag,
+        working_set: &mut WorkingSet<C>,
+    ) -> Result<SealedBlock, EthApiError> {
+        match block_number {
+            BlockNumberOrTag::Number(_) | BlockNumberOrTag::Earliest | BlockNumberOrTag::Latest => {
+                self.get_sealed_block_by_number(Some(block_number), working_set)
+                    .ok_or(EthApiError::UnknownBlockNumber)
+            }
+            _ => Err(EthApiError::InvalidParams(
+                "Please provide a number or earliest/latest tag".to_string(),
+            )),
+        }
+    }
+
+    /// Returns the number of the `i`-th block that changed the storage slot.
+    fn storage_change_block(
+        &self,
+        address: Address,
+        index: U256,
+        i: u64,
+        working_set: &mut WorkingSet<C>,
+    ) -> u64 {
+        self.storage_change_blocks
+            .get(&(address, index, i), &mut working_set.accessory_state())
+            .expect("Storage change must be set")
+    }
+
+    /// Returns the value of the storage slot in the state of the working set.
+    fn storage_value(
+        &self,
+        address: Address,
+        index: U256,
+        working_set: &mut WorkingSet<C>,
+    ) -> U256 {
+        self.accounts
+            .get(&address, working_set)
+            .and_then(|account| account.storage.get(&index, working_set))
+            .unwrap_or_default()
+    }
+
     /// Returns the block number given block hash
     /// If block not found returns None
     pub fn get_block_number_by_block_hash(
@@ -1489,6 +3118,106 @@ impl<C: sov_modules_api::Context> Evm<C> {
     }
 }
 
+impl<C: sov_modules_api::Context> Evm<C>
+where
+    C::Storage: NativeStorage,
+{
+    /// Handler for: `eth_getProof`
+    ///
+    /// Returns the account and the requested storage slots at the end of a block, with JMT proofs
+    /// against the state root of the rollup at that block.
+    pub fn get_proof(
+        &self,
+        ctx: &QueryContext,
+        address: reth_primitives::Address,
+        storage_keys: Vec<B256>,
+        block_id: Option<BlockId>,
+        working_set: &mut WorkingSet<C>,
+    ) -> RpcResult<AccountProof> {
+        info!("evm module: eth_getProof");
+
+        let block_number = self.resolve_block_id(ctx, block_id, working_set)?;
+
+        let curr_block_number = self
+            .blocks
+            .last(&mut working_set.accessory_state())
+            .expect("Head block must be set")
+            .header
+            .number;
+
+        let block_number = match block_number {
+            Some(BlockNumberOrTag::Number(num)) => {
+                if num > curr_block_number {
+                    return Err(EthApiError::UnknownBlockNumber.into());
+                }
+                num
+            }
+            Some(BlockNumberOrTag::Latest) | Some(BlockNumberOrTag::Pending) | None => {
+                curr_block_number
+            }
+            Some(BlockNumberOrTag::Earliest) => 0,
+            _ => {
+                return Err(EthApiError::InvalidParams(
+                    "Please provide a number or earliest/latest/pending tag".to_string(),
+                )
+                .into())
+            }
+        };
+        // genesis is committed at db version 1
+        // so every block is offset by 1
+        let version = block_number + 1;
+        self.set_state_to_past_evm_block(ctx, block_number, working_set)?;
+
+        let state_root = working_set
+            .get_root_hash(version)
+            .map_err(|e| EthApiError::InvalidParams(e.to_string()))?;
+        let account_key = StorageKey::new(
+            self.accounts.prefix(),
+            &address,
+            self.accounts.codec().key_codec(),
+        );
+        let account_proof = working_set
+            .get_with_proof_at_version(account_key, version)
+            .map_err(|e| EthApiError::InvalidParams(e.to_string()))?;
+
+        let account = self.accounts.get(&address, working_set);
+        let info = account
+            .as_ref()
+            .map(|account| account.info.clone())
+            .unwrap_or_default();
+
+        // slots of absent accounts are proven absent under the prefix the account would have
+        let storage_prefix = DbAccount::create_storage_prefix(self.accounts.prefix(), address);
+        let mut storage_proof = Vec::with_capacity(storage_keys.len());
+        for key in storage_keys {
+            let slot = U256::from_be_bytes(key.0);
+            let value = account
+                .as_ref()
+                .and_then(|account| account.storage.get(&slot, working_set))
+                .unwrap_or_default();
+            let slot_key = StorageKey::new(&storage_prefix, &slot, &BcsCodec {});
+            let proof = working_set
+                .get_with_proof_at_version(slot_key, version)
+                .map_err(|e| EthApiError::InvalidParams(e.to_string()))?;
+            storage_proof.push(StorageSlotProof {
+                key,
+                value,
+                proof: StateProof::new(proof),
+            });
+        }
+
+        Ok(AccountProof {
+            address,
+            balance: info.balance,
+            nonce: U64::from(info.nonce),
+            code_hash: info.code_hash,
+            state_root: B256::from(Into::<[u8; 32]>::into(state_root)),
+            account_proof: StateProof::new(account_proof),
+            storage_proof,
+        })
+    }
+}
+
 fn get_cfg_env_template() -> revm::primitives::CfgEnvWithHandlerCfg {
     // https://github.com/Sovereign-Labs/sovereign-sdk/issues/912
     let mut cfg_env = revm::primitives::CfgEnvWithHandlerCfg::new_with_spec_id(
@@ -1520,6 +3249,12 @@ pub(crate) fn build_rpc_receipt(
     let transaction_index = tx_number - block.transactions.start;
     let block_hash = Some(block.header.hash());
     let block_number = Some(U256::from(block.header.number));
+    // the L1 fee is only charged to successful transactions of users
+    let l1_fee = if receipt.receipt.success && transaction.signer() != SYSTEM_SIGNER {
+        calc_l1_fee(receipt.diff_size, block.l1_fee_rate)
+    } else {
+        U256::ZERO
+    };
     let other = OtherFields::new(
         [
             (
@@ -1530,6 +3265,17 @@ pub(crate) fn build_rpc_receipt(
                 "diffSize".into(),
                 format!("{:#x}", receipt.diff_size).into(),
             ),
+            ("l1Fee".into(), format!("{:#x}", l1_fee).into()),
+            (
+                "l1GasUsed".into(),
+                format!("{:#x}", receipt.diff_size).into(),
+            ),
+            (
+                "l1GasPrice".into(),
+                format!("{:#x}", block.l1_fee_rate).into(),
+            ),
+            // the L1 fee is not scaled, every byte of state diff pays the L1 fee rate
+            ("l1FeeScalar".into(), "1".into()),
         ]
         .into_iter()
         .collect(),
@@ -1599,6 +3345,7 @@ fn check_tx_range(transactions_range: &Range<u64>, index: Uint<64, 1>) -> Option
 }
 
 fn map_out_of_gas_err<C: sov_modules_api::Context>(
+    ctx: &QueryContext,
     block_env: BlockEnv,
     mut tx_env: revm::primitives::TxEnv,
     cfg_env: revm::primitives::CfgEnvWithHandlerCfg,
@@ -1616,7 +3363,8 @@ fn map_out_of_gas_err<C: sov_modules_api::Context>(
             }
             ExecutionResult::Revert { output, .. } => {
                 // reverted again after bumping the limit
-                RpcInvalidTransactionError::Revert(RevertError::new(output)).into()
+                RpcInvalidTransactionError::Revert(RevertError::new(output).with_query_context(ctx))
+                    .into()
             }
             ExecutionResult::Halt { reason, .. } => {
                 RpcInvalidTransactionError::EvmHalt(reason).into()
@@ -1626,6 +3374,16 @@ fn map_out_of_gas_err<C: sov_modules_api::Context>(
     }
 }
 
+/// Returns whether a logs bloom may contain logs matching the address and topics filters.
+fn logs_bloom_matches(
+    logs_bloom: reth_primitives::Bloom,
+    address_filter: &BloomFilter,
+    topics_filter: &[BloomFilter],
+) -> bool {
+    let logs_bloom = alloy_primitives::Bloom::from(logs_bloom.data());
+    matches_address(logs_bloom, address_filter) && matches_topics(logs_bloom, topics_filter)
+}
+
 fn convert_u256_to_u64(u256: reth_primitives::U256) -> Result<u64, TryFromSliceError> {
     let bytes: [u8; 32] = u256.to_be_bytes();
     let bytes: [u8; 8] = bytes[24..].try_into()?;
@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use ethers_core::abi::{Abi, AbiError};
+
+use super::QueryContext;
+
+/// Registry of known custom errors, used by the RPC to decode reverts into human readable messages.
+#[derive(Debug, Default)]
+pub struct AbiRegistry {
+    errors: HashMap<[u8; 4], Vec<AbiError>>,
+}
+
+impl AbiRegistry {
+    /// Loads all ABIs found in the `.json` files of the given directory.
+    /// A file can either be a plain ABI, a compiler artifact with an `abi` field
+    /// or a Sourcify metadata file with an `output.abi` field.
+    pub fn from_dir(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let mut registry = Self::default();
+        for entry in std::fs::read_dir(path)? {
+            let path = entry?.path();
+            if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+                continue;
+            }
+            let content: serde_json::Value = serde_json::from_slice(&std::fs::read(&path)?)?;
+            let abi = content
+                .get("abi")
+                .or_else(|| content.get("output").and_then(|output| output.get("abi")))
+                .unwrap_or(&content);
+            match serde_json::from_value::<Abi>(abi.clone()) {
+                Ok(abi) => registry.add_abi(&abi),
+                Err(e) => tracing::warn!("Skipping invalid ABI file {}: {}", path.display(), e),
+            }
+        }
+        Ok(registry)
+    }
+
+    /// Adds the custom errors of the given ABI.
+    pub fn add_abi(&mut self, abi: &Abi) {
+        for error in abi.errors() {
+            let mut selector = [0u8; 4];
+            selector.copy_from_slice(&error.signature()[..4]);
+            let known = self.errors.entry(selector).or_default();
+            if !known.contains(error) {
+                known.push(error.clone());
+            }
+        }
+    }
+
+    /// Decodes revert output of a known custom error into `Name(arg, ..)`.
+    pub fn decode_error(&self, output: &[u8]) -> Option<String> {
+        if output.len() < 4 {
+            return None;
+        }
+        let (selector, data) = output.split_at(4);
+        self.errors.get(selector)?.iter().find_map(|error| {
+            let tokens = error.decode(data).ok()?;
+            let args = tokens
+                .iter()
+                .map(|token| token.to_string())
+                .collect::<Vec<_>>();
+            Some(format!("{}({})", error.name, args.join(", ")))
+        })
+    }
+
+    /// Returns the number of known custom errors.
+    pub fn len(&self) -> usize {
+        self.errors.values().map(Vec::len).sum()
+    }
+
+    /// Returns true if there are no known custom errors.
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+impl QueryContext {
+    /// Decodes revert output with the ABI registry of the node, if any.
+    pub(crate) fn decode_custom_error(&self, output: &[u8]) -> Option<String> {
+        self.abi_registry.as_ref()?.decode_error(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use ethers_core::abi::{encode, Token};
+    use ethers_core::types::U256;
+
+    use super::*;
+    use crate::error::rpc::RevertError;
+
+    #[test]
+    fn decodes_known_custom_error() {
+        let abi: Abi = serde_json::from_str(
+            r#"[{"type":"error","name":"InsufficientBalance","inputs":[{"name":"available","type":"uint256"},{"name":"required","type":"uint256"}]}]"#,
+        )
+        .unwrap();
+        let mut registry = AbiRegistry::default();
+        registry.add_abi(&abi);
+        assert_eq!(registry.len(), 1);
+
+        let selector = ethers_core::utils::id("InsufficientBalance(uint256,uint256)");
+        let mut output = selector.to_vec();
+        output.extend(encode(&[
+            Token::Uint(U256::from(1)),
+            Token::Uint(U256::from(2)),
+        ]));
+
+        assert_eq!(
+            registry.decode_error(&output),
+            Some("InsufficientBalance(1, 2)".to_string())
+        );
+        assert_eq!(registry.decode_error(&[0xde, 0xad, 0xbe, 0xef]), None);
+
+        // reverts are only decoded with the registry of the node
+        let revert = || RevertError::new(output.clone().into());
+        assert_eq!(
+            revert()
+                .with_query_context(&QueryContext::default())
+                .to_string(),
+            "execution reverted"
+        );
+        let ctx = QueryContext::default().with_abi_registry(Arc::new(registry));
+        assert_eq!(
+            revert().with_query_context(&ctx).to_string(),
+            "execution reverted: InsufficientBalance(1, 2)"
+        );
+    }
+}
@@ -0,0 +1,38 @@
+use std::sync::Arc;
+
+use reth_primitives::BlockNumberOrTag;
+
+use super::QueryContext;
+use crate::error::rpc::{EthApiError, EthResult};
+
+/// Returns the L2 height the `safe` or `finalized` tag stands for on the node, if any.
+///
+/// `safe` is the highest soft confirmation finalized by a sequencer commitment on DA,
+/// `finalized` the highest proven one.
+pub type FinalityResolver = Arc<dyn Fn(BlockNumberOrTag) -> Option<u64> + Send + Sync>;
+
+impl QueryContext {
+    /// Returns the height of the block the `safe` or `finalized` tag stands for, if known.
+    pub fn finality_tag_height(&self, tag: BlockNumberOrTag) -> Option<u64> {
+        self.finality_resolver
+            .as_ref()
+            .and_then(|resolver| resolver(tag))
+    }
+
+    /// Replaces the `safe` and `finalized` tags by the number of the block they stand for,
+    /// other block numbers and tags are returned as they are.
+    pub fn resolve_finality_tag(
+        &self,
+        block_number: BlockNumberOrTag,
+    ) -> EthResult<BlockNumberOrTag> {
+        match block_number {
+            tag @ (BlockNumberOrTag::Safe | BlockNumberOrTag::Finalized) => {
+                let number = self
+                    .finality_tag_height(tag)
+                    .ok_or(EthApiError::UnknownSafeOrFinalizedBlock)?;
+                Ok(BlockNumberOrTag::Number(number))
+            }
+            block_number => Ok(block_number),
+        }
+    }
+}
@@ -21,6 +21,9 @@ pub const DEFAULT_MAX_BLOCKS_PER_FILTER: u64 = 100_000;
 pub const DEFAULT_MAX_LOGS_PER_RESPONSE: usize = 20_000;
 /// The maximum number of headers we read at once when handling a range filter.
 pub const MAX_HEADERS_RANGE: u64 = 1_000; // with ~530bytes? per header this is ~500kb?
+/// The number of blocks of a section of the logs bloom index. The logs blooms of the blocks of
+/// a section are merged so that eth_getLogs can skip the whole section at once.
+pub const LOG_BLOOM_SECTION_SIZE: u64 = 256;
 
 /// Helper type to represent a bloom filter used for matching logs.
 #[derive(Default, Debug)]
@@ -731,6 +734,9 @@ pub enum FilterError {
     /// There is a maximum number of logs that can be returned in a single eth_getLogs response.
     #[error("query exceeds max results {0}")]
     QueryExceedsMaxResults(usize),
+    /// The start of the queried block range is after its end.
+    #[error("invalid block range params")]
+    InvalidBlockRangeParams,
     /// Error thrown when the eth api returns an error
     #[error(transparent)]
     EthAPIError(#[from] EthApiError),
@@ -760,6 +766,10 @@ impl From<FilterError> for jsonrpsee::types::error::ErrorObject<'static> {
                 jsonrpsee::types::error::INVALID_PARAMS_CODE,
                 err.to_string(),
             ),
+            err @ FilterError::InvalidBlockRangeParams => rpc_error_with_code(
+                jsonrpsee::types::error::INVALID_PARAMS_CODE,
+                err.to_string(),
+            ),
         }
     }
 }
@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+
+use reth_primitives::{Address, U64};
+use serde::{Deserialize, Serialize};
+
+/// The maximum number of blocks that can be analyzed in a single `citrea_getBlockGasUsage` request.
+pub const MAX_GAS_USAGE_BLOCK_RANGE: u64 = 10_000;
+
+/// Number of contracts reported per window of a [`BlockGasUsage`].
+pub const GAS_USAGE_TOP_CONTRACTS: usize = 10;
+
+/// Gas used by the transactions to a contract.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractGasUsage {
+    /// The called or created contract.
+    pub address: Address,
+    /// Number of transactions to the contract.
+    pub tx_count: u64,
+    /// Total gas used by the transactions.
+    pub gas_used: u64,
+}
+
+/// Gas used per block over a range of blocks.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GasUsageWindow {
+    /// First block of the window.
+    pub from_block: U64,
+    /// Last block of the window.
+    pub to_block: U64,
+    /// Timestamp of the first block.
+    pub from_timestamp: U64,
+    /// Timestamp of the last block.
+    pub to_timestamp: U64,
+    /// Number of transactions, system transactions included.
+    pub tx_count: u64,
+    /// Total gas used by the blocks.
+    pub total_gas_used: u64,
+    /// Average gas used per block.
+    pub avg_gas_used: u64,
+    /// Median gas used per block.
+    pub p50_gas_used: u64,
+    /// 90th percentile of the gas used per block.
+    pub p90_gas_used: u64,
+    /// 99th percentile of the gas used per block.
+    pub p99_gas_used: u64,
+    /// Maximum gas used by a block.
+    pub max_gas_used: u64,
+    /// Average gas limit per block.
+    pub avg_gas_limit: u64,
+    /// Contracts with the largest gas used, system transactions excluded.
+    pub top_contracts: Vec<ContractGasUsage>,
+}
+
+/// Gas analytics of a block range, for the range as a whole and for fixed size windows of it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockGasUsage {
+    /// The whole range.
+    pub total: GasUsageWindow,
+    /// Consecutive windows of the range, oldest first. The last window may be shorter.
+    pub windows: Vec<GasUsageWindow>,
+}
+
+/// Accumulates the blocks and receipts of a window into a [`GasUsageWindow`].
+#[derive(Debug)]
+pub(crate) struct GasUsageAccumulator {
+    from_block: u64,
+    from_timestamp: u64,
+    to_block: u64,
+    to_timestamp: u64,
+    tx_count: u64,
+    gas_used: Vec<u64>,
+    gas_limit: u128,
+    contracts: HashMap<Address, ContractGasUsage>,
+}
+
+impl GasUsageAccumulator {
+    pub(crate) fn new(from_block: u64, from_timestamp: u64) -> Self {
+        Self {
+            from_block,
+            from_timestamp,
+            to_block: from_block,
+            to_timestamp: from_timestamp,
+            tx_count: 0,
+            gas_used: vec![],
+            gas_limit: 0,
+            contracts: HashMap::new(),
+        }
+    }
+
+    /// Records a block with `tx_count` transactions.
+    pub(crate) fn record_block(
+        &mut self,
+        number: u64,
+        timestamp: u64,
+        gas_used: u64,
+        gas_limit: u64,
+        tx_count: u64,
+    ) {
+        self.to_block = number;
+        self.to_timestamp = timestamp;
+        self.tx_count += tx_count;
+        self.gas_used.push(gas_used);
+        self.gas_limit += gas_limit as u128;
+    }
+
+    /// Records a transaction to `contract`.
+    pub(crate) fn record_transaction(&mut self, contract: Address, gas_used: u64) {
+        let entry = self
+            .contracts
+            .entry(contract)
+            .or_insert_with(|| ContractGasUsage {
+                address: contract,
+                tx_count: 0,
+                gas_used: 0,
+            });
+        entry.tx_count += 1;
+        entry.gas_used += gas_used;
+    }
+
+    /// Adds the blocks and transactions of a later window.
+    pub(crate) fn merge(&mut self, other: &GasUsageAccumulator) {
+        self.to_block = other.to_block;
+        self.to_timestamp = other.to_timestamp;
+        self.tx_count += other.tx_count;
+        self.gas_used.extend_from_slice(&other.gas_used);
+        self.gas_limit += other.gas_limit;
+        for (address, usage) in &other.contracts {
+            let entry = self
+                .contracts
+                .entry(*address)
+                .or_insert_with(|| ContractGasUsage {
+                    address: *address,
+                    tx_count: 0,
+                    gas_used: 0,
+                });
+            entry.tx_count += usage.tx_count;
+            entry.gas_used += usage.gas_used;
+        }
+    }
+
+    pub(crate) fn into_window(self) -> GasUsageWindow {
+        let block_count = self.gas_used.len().max(1) as u128;
+        let total_gas_used: u64 = self.gas_used.iter().sum();
+
+        let mut gas_used = self.gas_used;
+        gas_used.sort_unstable();
+
+        let mut top_contracts: Vec<_> = self.contracts.into_values().collect();
+        top_contracts.sort_by(|a, b| {
+            b.gas_used
+                .cmp(&a.gas_used)
+                .then_with(|| a.address.cmp(&b.address))
+        });
+        top_contracts.truncate(GAS_USAGE_TOP_CONTRACTS);
+
+        GasUsageWindow {
+            from_block: U64::from(self.from_block),
+            to_block: U64::from(self.to_block),
+            from_timestamp: U64::from(self.from_timestamp),
+            to_timestamp: U64::from(self.to_timestamp),
+            tx_count: self.tx_count,
+            total_gas_used,
+            avg_gas_used: (total_gas_used as u128 / block_count) as u64,
+            p50_gas_used: percentile(&gas_used, 50),
+            p90_gas_used: percentile(&gas_used, 90),
+            p99_gas_used: percentile(&gas_used, 99),
+            max_gas_used: gas_used.last().copied().unwrap_or_default(),
+            avg_gas_limit: (self.gas_limit / block_count) as u64,
+            top_contracts,
+        }
+    }
+}
+
+/// Returns the nearest-rank percentile of sorted values.
+fn percentile(sorted: &[u64], percentile: usize) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (sorted.len() * percentile).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentiles_use_nearest_rank() {
+        let values: Vec<u64> = (1..=10).collect();
+        assert_eq!(percentile(&values, 50), 5);
+        assert_eq!(percentile(&values, 90), 9);
+        assert_eq!(percentile(&values, 99), 10);
+        assert_eq!(percentile(&[7], 50), 7);
+        assert_eq!(percentile(&[], 50), 0);
+    }
+}
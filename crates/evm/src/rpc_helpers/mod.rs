@@ -1,9 +1,39 @@
+mod abi_registry;
+mod block_tags;
 mod filter;
+mod gas_usage;
 mod log_utils;
+mod ordering_info;
+mod otterscan;
+mod proof;
+mod query_context;
 mod responses;
+mod simulate;
+mod state_export;
+mod state_history;
+mod state_override;
+mod state_test;
+mod storage_history;
+mod storage_rent;
+mod trace_filter;
 mod tracing_utils;
 
+pub use abi_registry::*;
+pub use block_tags::*;
 pub use filter::*;
+pub use gas_usage::*;
 pub use log_utils::*;
+pub use ordering_info::*;
+pub use otterscan::*;
+pub use proof::*;
+pub use query_context::*;
 pub use responses::*;
+pub use simulate::*;
+pub use state_export::*;
+pub use state_history::*;
+pub(crate) use state_override::*;
+pub use state_test::*;
+pub use storage_history::*;
+pub use storage_rent::*;
+pub use trace_filter::*;
 pub(crate) use tracing_utils::*;
@@ -0,0 +1,29 @@
+use reth_primitives::{B256, U256, U64};
+use serde::{Deserialize, Serialize};
+
+/// Position and effective priority fee of a transaction in a block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxOrderingInfo {
+    /// Hash of the transaction.
+    pub hash: B256,
+    /// Index of the transaction in the block, system transactions included.
+    pub position: U64,
+    /// Priority fee per gas paid by the transaction on top of the base fee of the block.
+    pub effective_priority_fee: U256,
+}
+
+/// How the sequencer ordered the transactions of a block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockOrderingInfo {
+    /// Number of the block.
+    pub block_number: U64,
+    /// Base fee per gas of the block.
+    pub base_fee_per_gas: U64,
+    /// Ordering policy version recorded in the extra data of the block,
+    /// `None` if the block wasn't built under a versioned policy.
+    pub ordering_policy_version: Option<U64>,
+    /// Transactions of the block in order, system transactions excluded.
+    pub transactions: Vec<TxOrderingInfo>,
+}
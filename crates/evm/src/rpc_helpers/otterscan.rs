@@ -0,0 +1,71 @@
+use reth_primitives::{U256, U64};
+use reth_rpc_types::{RichBlock, Transaction, TransactionReceipt};
+use serde::{Deserialize, Serialize};
+
+/// Version of the Otterscan API served by `ots_getApiLevel`.
+pub const OTS_API_LEVEL: u64 = 8;
+
+/// The maximum number of transactions requested from `ots_searchTransactionsBefore` and
+/// `ots_searchTransactionsAfter` in a single page.
+pub const MAX_OTS_PAGE_SIZE: usize = 100;
+
+/// Response of `ots_getBlockDetails`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OtsBlockDetails {
+    /// The block without its transactions.
+    pub block: OtsBlock,
+    /// Ether issued by the block, always zero as there are no block rewards.
+    pub issuance: OtsIssuance,
+    /// Total fees paid by the transactions of the block.
+    pub total_fees: U256,
+}
+
+/// Block of `ots_getBlockDetails`, with its transaction count instead of its transactions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OtsBlock {
+    /// The block, with an empty transaction list.
+    #[serde(flatten)]
+    pub block: RichBlock,
+    /// Number of transactions in the block.
+    pub transaction_count: U64,
+}
+
+/// Issuance of a block.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OtsIssuance {
+    /// Reward of the block producer.
+    pub block_reward: U256,
+    /// Reward of the uncle blocks.
+    pub uncle_reward: U256,
+    /// Total issuance.
+    pub issuance: U256,
+}
+
+/// Receipt of a transaction search result, with the timestamp of its block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OtsReceipt {
+    /// The receipt.
+    #[serde(flatten)]
+    pub receipt: TransactionReceipt,
+    /// Timestamp of the block of the transaction.
+    pub timestamp: U64,
+}
+
+/// A page of `ots_searchTransactionsBefore` or `ots_searchTransactionsAfter`,
+/// newest transactions first.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OtsTransactionsPage {
+    /// Transactions of the page.
+    pub txs: Vec<Transaction>,
+    /// Receipts of the transactions, in the same order.
+    pub receipts: Vec<OtsReceipt>,
+    /// Whether the page holds the newest transactions of the address.
+    pub first_page: bool,
+    /// Whether the page holds the oldest transactions of the address.
+    pub last_page: bool,
+}
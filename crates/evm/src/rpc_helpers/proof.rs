@@ -0,0 +1,68 @@
+use borsh::BorshSerialize;
+use reth_primitives::{Address, Bytes, B256, U256, U64};
+use serde::{Deserialize, Serialize};
+use sov_state::storage::StorageProof;
+
+/// Response of `eth_getProof`.
+///
+/// The rollup state is a Jellyfish Merkle Tree, not a Patricia Merkle Trie, so the proofs are
+/// JMT proofs against the state root of the rollup, which is the one committed in soft batches.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountProof {
+    /// Address of the account
+    pub address: Address,
+    /// Balance of the account
+    pub balance: U256,
+    /// Nonce of the account
+    pub nonce: U64,
+    /// Hash of the code of the account
+    pub code_hash: B256,
+    /// Root of the rollup state the proofs are against
+    pub state_root: B256,
+    /// Proof of the account entry of the EVM module
+    pub account_proof: StateProof,
+    /// Proofs of the requested storage slots
+    pub storage_proof: Vec<StorageSlotProof>,
+}
+
+/// Proof of the presence or absence of an entry in the rollup state.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateProof {
+    /// Key of the entry in the state
+    pub key: Bytes,
+    /// Encoded value of the entry, none if the entry is absent
+    pub value: Option<Bytes>,
+    /// Borsh encoded sparse Merkle proof of the entry
+    pub proof: Bytes,
+}
+
+impl StateProof {
+    pub(crate) fn new<P: BorshSerialize>(storage_proof: StorageProof<P>) -> Self {
+        Self {
+            key: Bytes::from(storage_proof.key.as_ref().clone()),
+            value: storage_proof
+                .value
+                .map(|value| Bytes::from(value.value().to_vec())),
+            proof: Bytes::from(
+                storage_proof
+                    .proof
+                    .try_to_vec()
+                    .expect("Serialization to a vector never fails"),
+            ),
+        }
+    }
+}
+
+/// Value and proof of a storage slot of an account.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageSlotProof {
+    /// The storage slot
+    pub key: B256,
+    /// Value of the storage slot
+    pub value: U256,
+    /// Proof of the storage slot entry of the EVM module
+    pub proof: StateProof,
+}
@@ -0,0 +1,37 @@
+use std::sync::Arc;
+
+use super::{AbiRegistry, FinalityResolver};
+
+/// Settings of the queries of the EVM module that depend on the node serving them.
+/// Held by the RPC context of the node and passed to the queries reading blocks by tag
+/// or the state of past blocks.
+///
+/// The default context, used by queries made outside of a node, knows no `safe` or
+/// `finalized` block, serves the state of every block and decodes no custom error.
+#[derive(Clone, Default)]
+pub struct QueryContext {
+    pub(crate) finality_resolver: Option<FinalityResolver>,
+    pub(crate) state_history_blocks: Option<u64>,
+    pub(crate) abi_registry: Option<Arc<AbiRegistry>>,
+}
+
+impl QueryContext {
+    /// Resolves the `safe` and `finalized` tags with the given resolver.
+    pub fn with_finality_resolver(mut self, resolver: FinalityResolver) -> Self {
+        self.finality_resolver = Some(resolver);
+        self
+    }
+
+    /// Limits the state served to the head block and the `blocks` blocks before it,
+    /// on nodes that are not archive nodes.
+    pub fn with_state_history_blocks(mut self, blocks: u64) -> Self {
+        self.state_history_blocks = Some(blocks);
+        self
+    }
+
+    /// Decodes the custom errors of reverts with the given registry.
+    pub fn with_abi_registry(mut self, registry: Arc<AbiRegistry>) -> Self {
+        self.abi_registry = Some(registry);
+        self
+    }
+}
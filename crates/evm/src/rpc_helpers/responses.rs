@@ -1,7 +1,8 @@
 use std::hash::Hash;
 
 use alloy_primitives::Bytes;
-use reth_primitives::{Address, U256};
+use reth_primitives::{Address, U256, U64};
+use reth_rpc_types::trace::geth::GethTrace;
 use revm::primitives::B256;
 
 /// Ethereum Log emitted by a transaction
@@ -28,3 +29,48 @@ pub struct LogResponse {
     #[serde(default)]
     pub removed: bool,
 }
+
+/// Trace of a transaction of a block, as returned by `debug_traceBlockByNumber` and
+/// `debug_traceBlockByHash`.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceResult {
+    /// Transaction hash.
+    pub tx_hash: B256,
+    /// Trace of the transaction with the requested tracer.
+    pub result: GethTrace,
+}
+
+/// Outcome of a transaction applied by [`Evm::apply_pending_transactions`](crate::Evm::apply_pending_transactions).
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingTransactionResult {
+    /// Transaction hash.
+    pub hash: B256,
+    /// Transaction sender.
+    pub from: Address,
+    /// Transaction nonce.
+    pub nonce: u64,
+    /// Gas used by the transaction, zero if it is invalid.
+    pub gas_used: u64,
+    /// Size of the state diff of the transaction, zero if it is invalid.
+    pub diff_size: u64,
+    /// Whether the transaction is valid and executed successfully.
+    pub success: bool,
+    /// The reason the transaction is invalid, if it is.
+    pub error: Option<String>,
+}
+
+/// L1 block an L2 block was built on, as recorded by the `L1BlockHashList` system contract.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1BlockInfo {
+    /// Height of the L2 block.
+    pub l2_height: U64,
+    /// Height of the L1 block.
+    pub l1_height: U64,
+    /// Hash of the L1 block.
+    pub l1_hash: B256,
+    /// Timestamp of the L1 block, set by nodes that read it from the DA layer.
+    pub l1_timestamp: Option<U64>,
+}
@@ -0,0 +1,157 @@
+use reth_primitives::{Address, Bytes, U256, U64};
+use reth_rpc_types::state::StateOverride;
+use reth_rpc_types::{BlockOverrides, Log, TransactionRequest};
+use revm::primitives::ExecutionResult;
+use serde::{Deserialize, Serialize};
+
+use super::QueryContext;
+use crate::error::rpc::{RevertError, RpcInvalidTransactionError};
+
+/// The maximum number of blocks simulated by a single `eth_simulateV1` request.
+pub const MAX_SIMULATE_BLOCKS: usize = 256;
+
+/// The maximum number of calls simulated by a single `eth_simulateV1` request, over all blocks.
+pub const MAX_SIMULATE_CALLS: usize = 1000;
+
+/// Error code of the simulated calls that reverted, the one of `eth_call`.
+const REVERTED_CALL_ERROR_CODE: i32 = 3;
+
+/// Error code of the simulated calls halted by the EVM, the one of geth.
+const HALTED_CALL_ERROR_CODE: i32 = -32015;
+
+/// Request of `eth_simulateV1`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatePayload {
+    /// Blocks to simulate on top of each other.
+    pub block_state_calls: Vec<SimulateBlock>,
+    /// Whether to validate the calls like transactions, checking nonces, balances and fees.
+    #[serde(default)]
+    pub validation: bool,
+    /// Whether to add logs of the ether transfers, not supported.
+    #[serde(default)]
+    pub trace_transfers: bool,
+    /// Whether to return full transactions, not supported.
+    #[serde(default)]
+    pub return_full_transactions: bool,
+}
+
+/// A block of `eth_simulateV1`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulateBlock {
+    /// Overrides of the block, by default the block follows the previous one by a second.
+    #[serde(default)]
+    pub block_overrides: Option<BlockOverrides>,
+    /// Overrides of the state, applied before the calls of the block.
+    #[serde(default)]
+    pub state_overrides: Option<StateOverride>,
+    /// Calls of the block, each one on top of the state left by the previous ones.
+    #[serde(default)]
+    pub calls: Vec<TransactionRequest>,
+}
+
+/// A simulated block in the response of `eth_simulateV1`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedBlock {
+    /// Number of the block.
+    pub number: U64,
+    /// Timestamp of the block.
+    pub timestamp: U64,
+    /// Gas limit of the block.
+    pub gas_limit: U64,
+    /// Gas used by the calls of the block.
+    pub gas_used: U64,
+    /// Coinbase of the block.
+    pub fee_recipient: Address,
+    /// Base fee of the block.
+    pub base_fee_per_gas: U256,
+    /// Results of the calls, in order.
+    pub calls: Vec<SimulatedCall>,
+}
+
+/// Result of a simulated call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulatedCall {
+    /// Output of the call, or its revert data.
+    pub return_data: Bytes,
+    /// Logs emitted by the call.
+    pub logs: Vec<Log>,
+    /// Gas used by the call.
+    pub gas_used: U64,
+    /// 1 if the call succeeded, 0 otherwise.
+    pub status: U64,
+    /// Why the call failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<SimulatedCallError>,
+}
+
+/// Error of a failed simulated call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SimulatedCallError {
+    /// JSON-RPC error code, 3 for reverts.
+    pub code: i32,
+    /// Error message.
+    pub message: String,
+}
+
+impl SimulatedCall {
+    /// Builds the result of a call from its execution, the first log of the call having
+    /// index `log_index_start` in the block.
+    pub(crate) fn from_execution(
+        ctx: &QueryContext,
+        result: ExecutionResult,
+        gas_limit: u64,
+        block_number: u64,
+        call_index: u64,
+        log_index_start: u64,
+    ) -> Self {
+        let gas_used = U64::from(result.gas_used());
+        let logs = result
+            .logs()
+            .iter()
+            .cloned()
+            .map(reth_primitives::Log::from)
+            .enumerate()
+            .map(|(index, log)| Log {
+                address: log.address,
+                topics: log.topics,
+                data: log.data,
+                block_hash: None,
+                block_number: Some(U256::from(block_number)),
+                transaction_hash: None,
+                transaction_index: Some(U256::from(call_index)),
+                log_index: Some(U256::from(log_index_start + index as u64)),
+                removed: false,
+            })
+            .collect();
+
+        let (return_data, error) = match result {
+            ExecutionResult::Success { output, .. } => (output.into_data(), None),
+            ExecutionResult::Revert { output, .. } => (
+                output.clone(),
+                Some(SimulatedCallError {
+                    code: REVERTED_CALL_ERROR_CODE,
+                    message: RevertError::new(output).with_query_context(ctx).to_string(),
+                }),
+            ),
+            ExecutionResult::Halt { reason, .. } => (
+                Bytes::new(),
+                Some(SimulatedCallError {
+                    code: HALTED_CALL_ERROR_CODE,
+                    message: RpcInvalidTransactionError::halt(reason, gas_limit).to_string(),
+                }),
+            ),
+        };
+
+        Self {
+            return_data,
+            logs,
+            gas_used,
+            status: U64::from(error.is_none() as u8),
+            error,
+        }
+    }
+}
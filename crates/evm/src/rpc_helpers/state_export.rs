@@ -0,0 +1,21 @@
+use std::collections::BTreeMap;
+
+use reth_primitives::{Address, GenesisAccount, B256, U64};
+use serde::{Deserialize, Serialize};
+
+/// The maximum number of accounts that can be exported in a single `citrea_exportState` request.
+pub const MAX_STATE_EXPORT_ACCOUNTS: u64 = 1000;
+
+/// A page of the state at the end of a block, in the genesis alloc format of geth.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateExport {
+    /// Number of the exported block.
+    pub block_number: U64,
+    /// State root of the exported block, as reported in its header.
+    pub state_root: B256,
+    /// Accounts of the page with their non-zero storage, keyed by address.
+    pub alloc: BTreeMap<Address, GenesisAccount>,
+    /// Offset of the next page, `None` if this is the last page.
+    pub next_offset: Option<U64>,
+}
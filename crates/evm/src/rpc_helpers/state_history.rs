@@ -0,0 +1,45 @@
+use super::QueryContext;
+use crate::error::rpc::{EthApiError, EthResult};
+
+impl QueryContext {
+    /// Fails if the node doesn't serve the state at the end of the block.
+    pub(crate) fn check_state_history(&self, block_number: u64, head_number: u64) -> EthResult<()> {
+        match self.state_history_blocks {
+            Some(blocks) => check_state_history_with(block_number, head_number, blocks),
+            None => Ok(()),
+        }
+    }
+}
+
+fn check_state_history_with(block_number: u64, head_number: u64, blocks: u64) -> EthResult<()> {
+    let oldest = head_number.saturating_sub(blocks);
+    if block_number < oldest {
+        return Err(EthApiError::StateHistoryUnavailable {
+            block: block_number,
+            oldest,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serves_recent_state_only() {
+        assert!(check_state_history_with(100, 100, 0).is_ok());
+        assert!(check_state_history_with(99, 100, 0).is_err());
+
+        assert!(check_state_history_with(72, 200, 128).is_ok());
+        assert!(matches!(
+            check_state_history_with(71, 200, 128),
+            Err(EthApiError::StateHistoryUnavailable {
+                block: 71,
+                oldest: 72
+            })
+        ));
+        // the node serves the whole chain while it is shorter than its history
+        assert!(check_state_history_with(0, 100, 128).is_ok());
+    }
+}
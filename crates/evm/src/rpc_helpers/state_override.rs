@@ -0,0 +1,120 @@
+use reth_rpc_types::state::StateOverride;
+use reth_rpc_types::BlockOverrides;
+use revm::primitives::{
+    Account, AccountStatus, Bytecode, HashMap, StorageSlot, B256, KECCAK_EMPTY, U256,
+};
+use revm::{Database, DatabaseCommit};
+use sov_modules_api::{StateMapAccessor, StateVecAccessor};
+
+use crate::error::rpc::{EthApiError, EthResult};
+use crate::evm::db::EvmDb;
+use crate::evm::primitive_types::BlockEnv;
+
+/// Applies the state overrides of a call to the working set of `db`, which must not be committed.
+///
+/// `state` replaces the whole storage of an account, `stateDiff` only the given slots.
+pub(crate) fn apply_state_overrides<C: sov_modules_api::Context>(
+    db: &mut EvmDb<'_, C>,
+    overrides: StateOverride,
+) -> EthResult<()> {
+    for (address, account_override) in overrides {
+        let mut info = db.basic(address)?.unwrap_or_default();
+        if let Some(balance) = account_override.balance {
+            info.balance = balance;
+        }
+        if let Some(nonce) = account_override.nonce {
+            info.nonce = nonce.to();
+        }
+        if let Some(code) = account_override.code {
+            let bytecode = Bytecode::new_raw(code);
+            info.code_hash = match bytecode.is_empty() {
+                true => KECCAK_EMPTY,
+                false => bytecode.hash_slow(),
+            };
+            info.code = Some(bytecode);
+        }
+
+        let mut storage = HashMap::new();
+        match (account_override.state, account_override.state_diff) {
+            (Some(_), Some(_)) => {
+                return Err(EthApiError::BothStateAndStateDiffInOverride(address));
+            }
+            (Some(state), None) => {
+                // the slots missing from the override are cleared
+                if let Some(db_account) = db.accounts.get(&address, db.working_set) {
+                    let keys: Vec<U256> = db_account.keys.iter(db.working_set).collect();
+                    for key in keys {
+                        storage.insert(key, StorageSlot::new(U256::ZERO));
+                    }
+                }
+                storage.extend(state.into_iter().map(override_slot));
+            }
+            (None, Some(state_diff)) => storage.extend(state_diff.into_iter().map(override_slot)),
+            (None, None) => {}
+        }
+
+        db.commit(HashMap::from_iter([(
+            address,
+            Account {
+                info,
+                storage,
+                status: AccountStatus::Touched,
+            },
+        )]));
+    }
+    Ok(())
+}
+
+fn override_slot((key, value): (B256, U256)) -> (U256, StorageSlot) {
+    (U256::from_be_bytes(key.0), StorageSlot::new(value))
+}
+
+/// Applies the block overrides of a call to the block env it is executed in.
+///
+/// Difficulty doesn't exist after the merge and block hashes come from the state,
+/// so overriding them is not supported.
+pub(crate) fn apply_block_overrides(
+    block_env: &mut BlockEnv,
+    overrides: BlockOverrides,
+) -> EthResult<()> {
+    let BlockOverrides {
+        number,
+        difficulty,
+        time,
+        gas_limit,
+        coinbase,
+        random,
+        base_fee,
+        block_hash,
+    } = overrides;
+    if difficulty.is_some() {
+        return Err(EthApiError::Unsupported(
+            "Difficulty overrides are not supported",
+        ));
+    }
+    if block_hash.is_some() {
+        return Err(EthApiError::Unsupported(
+            "Block hash overrides are not supported",
+        ));
+    }
+
+    if let Some(number) = number {
+        block_env.number = number.to();
+    }
+    if let Some(time) = time {
+        block_env.timestamp = time.to();
+    }
+    if let Some(gas_limit) = gas_limit {
+        block_env.gas_limit = gas_limit.to();
+    }
+    if let Some(coinbase) = coinbase {
+        block_env.coinbase = coinbase;
+    }
+    if let Some(random) = random {
+        block_env.prevrandao = random;
+    }
+    if let Some(base_fee) = base_fee {
+        block_env.basefee = base_fee.to();
+    }
+    Ok(())
+}
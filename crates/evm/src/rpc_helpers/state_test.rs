@@ -0,0 +1,251 @@
+use std::collections::{BTreeMap, HashMap};
+
+use alloy_rlp::Encodable;
+use reth_primitives::proofs::genesis_state_root;
+use reth_primitives::{
+    keccak256, AccessList, Address, Bytes, GenesisAccount, Log, TransactionKind,
+    TransactionSignedEcRecovered, B256, U256, U64,
+};
+use revm::primitives::{AccountInfo, SpecId};
+use serde::{Deserialize, Serialize};
+
+/// The maximum number of blocks that can be exported in a single `citrea_exportStateTests` request.
+pub const MAX_STATE_TEST_BLOCK_RANGE: u64 = 100;
+
+/// An account in the pre or post state of a state test.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateTestAccount {
+    /// Balance of the account.
+    pub balance: U256,
+    /// Code of the account.
+    pub code: Bytes,
+    /// Nonce of the account.
+    pub nonce: U64,
+    /// Non-zero storage slots of the account read or written by the transaction.
+    pub storage: BTreeMap<U256, U256>,
+}
+
+impl StateTestAccount {
+    pub(crate) fn new(info: &AccountInfo, code: Bytes, storage: BTreeMap<U256, U256>) -> Self {
+        Self {
+            balance: info.balance,
+            code,
+            nonce: U64::from(info.nonce),
+            storage: storage
+                .into_iter()
+                .filter(|(_, value)| *value != U256::ZERO)
+                .collect(),
+        }
+    }
+}
+
+/// The block environment of a state test.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateTestEnv {
+    /// Coinbase of the block.
+    pub current_coinbase: Address,
+    /// Gas limit of the block.
+    pub current_gas_limit: U256,
+    /// Number of the block.
+    pub current_number: U256,
+    /// Timestamp of the block.
+    pub current_timestamp: U256,
+    /// Prevrandao of the block.
+    pub current_random: B256,
+    /// Difficulty of the block.
+    pub current_difficulty: U256,
+    /// Base fee of the block.
+    pub current_base_fee: U256,
+}
+
+/// The transaction of a state test, with a single data, gas and value variant.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateTestTransaction {
+    /// Input data variants.
+    pub data: Vec<Bytes>,
+    /// Gas limit variants.
+    pub gas_limit: Vec<U256>,
+    /// Value variants.
+    pub value: Vec<U256>,
+    /// Nonce of the sender.
+    pub nonce: U256,
+    /// Recipient, empty for contract creations.
+    pub to: String,
+    /// Recovered sender. The secret key is unknown for exported transactions.
+    pub sender: Address,
+    /// Gas price of legacy and access list transactions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gas_price: Option<U256>,
+    /// Max fee per gas of dynamic fee transactions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_fee_per_gas: Option<U256>,
+    /// Max priority fee per gas of dynamic fee transactions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_priority_fee_per_gas: Option<U256>,
+    /// Access list variants of typed transactions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub access_lists: Option<Vec<AccessList>>,
+}
+
+impl From<&TransactionSignedEcRecovered> for StateTestTransaction {
+    fn from(tx: &TransactionSignedEcRecovered) -> Self {
+        let to = match tx.kind() {
+            TransactionKind::Call(to) => format!("{:#x}", to),
+            TransactionKind::Create => String::new(),
+        };
+        let (gas_price, max_fee_per_gas, max_priority_fee_per_gas) =
+            match tx.max_priority_fee_per_gas() {
+                Some(max_priority_fee_per_gas) => (
+                    None,
+                    Some(U256::from(tx.max_fee_per_gas())),
+                    Some(U256::from(max_priority_fee_per_gas)),
+                ),
+                None => (Some(U256::from(tx.max_fee_per_gas())), None, None),
+            };
+        Self {
+            data: vec![tx.input().clone()],
+            gas_limit: vec![U256::from(tx.gas_limit())],
+            value: vec![tx.value()],
+            nonce: U256::from(tx.nonce()),
+            to,
+            sender: tx.signer(),
+            gas_price,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            access_lists: tx
+                .access_list()
+                .map(|access_list| vec![access_list.clone()]),
+        }
+    }
+}
+
+/// Indexes of the transaction variants a post state belongs to.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateTestIndexes {
+    /// Index into the data variants.
+    pub data: u64,
+    /// Index into the gas limit variants.
+    pub gas: u64,
+    /// Index into the value variants.
+    pub value: u64,
+}
+
+/// The expected result of a state test for a fork.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateTestPost {
+    /// Merkle Patricia trie root of the post state.
+    pub hash: B256,
+    /// Hash of the RLP encoded logs.
+    pub logs: B256,
+    /// The signed transaction.
+    pub txbytes: Bytes,
+    /// Transaction variants the result belongs to.
+    pub indexes: StateTestIndexes,
+    /// The post state.
+    pub state: BTreeMap<Address, StateTestAccount>,
+}
+
+/// A transaction executed by Citrea as an execution-spec-tests state test fixture.
+///
+/// Citrea charges L1 fees for state diffs, so clients without them only reproduce
+/// the post state of transactions executed with a zero L1 fee rate.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateTestFixture {
+    /// Information about the origin of the fixture.
+    #[serde(rename = "_info")]
+    pub info: BTreeMap<String, String>,
+    /// Block environment.
+    pub env: StateTestEnv,
+    /// Accounts the transaction read or wrote, before the transaction.
+    pub pre: BTreeMap<Address, StateTestAccount>,
+    /// The transaction.
+    pub transaction: StateTestTransaction,
+    /// Expected results by fork name.
+    pub post: BTreeMap<String, Vec<StateTestPost>>,
+}
+
+impl StateTestFixture {
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        block_hash: B256,
+        l1_fee_rate: u64,
+        spec_id: SpecId,
+        env: StateTestEnv,
+        pre: BTreeMap<Address, StateTestAccount>,
+        tx: &TransactionSignedEcRecovered,
+        logs: &[Log],
+        post_state: BTreeMap<Address, StateTestAccount>,
+    ) -> Self {
+        let info = BTreeMap::from([
+            (
+                "comment".to_string(),
+                format!(
+                    "Exported from Citrea block {:#x}, transaction {:#x}",
+                    block_hash,
+                    tx.hash()
+                ),
+            ),
+            ("l1-fee-rate".to_string(), l1_fee_rate.to_string()),
+        ]);
+
+        let mut encoded_logs = vec![];
+        logs.to_vec().encode(&mut encoded_logs);
+
+        let post = StateTestPost {
+            hash: state_root(&post_state),
+            logs: keccak256(encoded_logs),
+            txbytes: tx.envelope_encoded(),
+            indexes: StateTestIndexes::default(),
+            state: post_state,
+        };
+
+        Self {
+            info,
+            env,
+            pre,
+            transaction: tx.into(),
+            post: BTreeMap::from([(fork_name(spec_id), vec![post])]),
+        }
+    }
+}
+
+/// Returns the execution-spec-tests name of the fork.
+fn fork_name(spec_id: SpecId) -> String {
+    match spec_id {
+        SpecId::FRONTIER => "Frontier".to_string(),
+        SpecId::HOMESTEAD => "Homestead".to_string(),
+        SpecId::BYZANTIUM => "Byzantium".to_string(),
+        SpecId::CONSTANTINOPLE => "Constantinople".to_string(),
+        SpecId::PETERSBURG => "ConstantinopleFix".to_string(),
+        SpecId::ISTANBUL => "Istanbul".to_string(),
+        SpecId::BERLIN => "Berlin".to_string(),
+        SpecId::LONDON => "London".to_string(),
+        SpecId::MERGE => "Paris".to_string(),
+        SpecId::SHANGHAI => "Shanghai".to_string(),
+        SpecId::CANCUN => "Cancun".to_string(),
+        spec_id => format!("{:?}", spec_id),
+    }
+}
+
+/// Computes the Merkle Patricia trie root of a state made of the given accounts only.
+fn state_root(state: &BTreeMap<Address, StateTestAccount>) -> B256 {
+    let accounts: HashMap<Address, GenesisAccount> = state
+        .iter()
+        .map(|(address, account)| {
+            let storage = account
+                .storage
+                .iter()
+                .map(|(key, value)| (B256::from(*key), B256::from(*value)))
+                .collect();
+            let genesis_account = GenesisAccount::default()
+                .with_nonce(Some(account.nonce.to()))
+                .with_balance(account.balance)
+                .with_code((!account.code.is_empty()).then(|| account.code.clone()))
+                .with_storage(Some(storage));
+            (*address, genesis_account)
+        })
+        .collect();
+    genesis_state_root(&accounts)
+}
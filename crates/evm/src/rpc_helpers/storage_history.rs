@@ -0,0 +1,33 @@
+use reth_primitives::{B256, U64};
+use serde::{Deserialize, Serialize};
+
+/// The maximum number of blocks that can be queried in a single `citrea_getStorageHistory` request.
+pub const MAX_STORAGE_HISTORY_BLOCK_RANGE: u64 = 100_000;
+
+/// The maximum number of changes returned by a single `citrea_getStorageHistory` request.
+pub const MAX_STORAGE_HISTORY_CHANGES: usize = 1_000;
+
+/// Value a block set a storage slot to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageSlotChange {
+    /// Number of the block.
+    pub block_number: U64,
+    /// Value of the slot at the end of the block.
+    pub value: B256,
+}
+
+/// History of the values of a storage slot over a block range.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageHistory {
+    /// First block of the range.
+    pub from_block: U64,
+    /// Last block of the range covered by the history. Before the requested last block when
+    /// the changes were truncated, the next request continues from this block.
+    pub to_block: U64,
+    /// Value of the slot at the end of the first block.
+    pub initial_value: B256,
+    /// Blocks after the first block changing the value of the slot, oldest first.
+    pub changes: Vec<StorageSlotChange>,
+}
@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+
+use reth_primitives::{Address, U256, U64};
+use serde::{Deserialize, Serialize};
+
+/// The maximum number of blocks that can be analyzed in a single `citrea_getStorageRentReport` request.
+pub const MAX_STORAGE_RENT_BLOCK_RANGE: u64 = 10_000;
+
+/// State diff bytes and L1 fees attributed to an address.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageRentEntry {
+    /// The sender or contract address.
+    pub address: Address,
+    /// Number of transactions attributed to the address.
+    pub tx_count: u64,
+    /// Total state diff size in bytes written to DA.
+    pub diff_size: u64,
+    /// Total L1 fee paid for the state diff.
+    pub l1_fee: U256,
+}
+
+impl StorageRentEntry {
+    fn new(address: Address) -> Self {
+        Self {
+            address,
+            tx_count: 0,
+            diff_size: 0,
+            l1_fee: U256::ZERO,
+        }
+    }
+}
+
+/// Attribution of the DA byte costs of the state diffs in a block range.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageRentReport {
+    /// First block of the range.
+    pub from_block: U64,
+    /// Last block of the range.
+    pub to_block: U64,
+    /// Timestamp of the first block.
+    pub from_timestamp: U64,
+    /// Timestamp of the last block.
+    pub to_timestamp: U64,
+    /// Number of analyzed transactions.
+    pub tx_count: u64,
+    /// Total state diff size in bytes.
+    pub total_diff_size: u64,
+    /// Total L1 fee paid for the state diffs.
+    pub total_l1_fee: U256,
+    /// Costs per transaction sender, largest diff size first.
+    pub by_sender: Vec<StorageRentEntry>,
+    /// Costs per called or created contract, largest diff size first.
+    pub by_contract: Vec<StorageRentEntry>,
+}
+
+/// Accumulates the state diffs of transactions into a [`StorageRentReport`].
+#[derive(Debug, Default)]
+pub(crate) struct StorageRentAccumulator {
+    senders: HashMap<Address, StorageRentEntry>,
+    contracts: HashMap<Address, StorageRentEntry>,
+    tx_count: u64,
+    total_diff_size: u64,
+    total_l1_fee: U256,
+}
+
+impl StorageRentAccumulator {
+    /// Records a transaction of `sender` to `contract`.
+    /// The L1 fee is only charged to successful transactions.
+    pub(crate) fn record(
+        &mut self,
+        sender: Address,
+        contract: Address,
+        diff_size: u64,
+        l1_fee_rate: u64,
+        success: bool,
+    ) {
+        let l1_fee = if success {
+            U256::from(diff_size) * U256::from(l1_fee_rate)
+        } else {
+            U256::ZERO
+        };
+
+        for entry in [
+            self.senders
+                .entry(sender)
+                .or_insert_with(|| StorageRentEntry::new(sender)),
+            self.contracts
+                .entry(contract)
+                .or_insert_with(|| StorageRentEntry::new(contract)),
+        ] {
+            entry.tx_count += 1;
+            entry.diff_size += diff_size;
+            entry.l1_fee += l1_fee;
+        }
+
+        self.tx_count += 1;
+        self.total_diff_size += diff_size;
+        self.total_l1_fee += l1_fee;
+    }
+
+    pub(crate) fn into_report(
+        self,
+        from_block: u64,
+        to_block: u64,
+        from_timestamp: u64,
+        to_timestamp: u64,
+    ) -> StorageRentReport {
+        StorageRentReport {
+            from_block: U64::from(from_block),
+            to_block: U64::from(to_block),
+            from_timestamp: U64::from(from_timestamp),
+            to_timestamp: U64::from(to_timestamp),
+            tx_count: self.tx_count,
+            total_diff_size: self.total_diff_size,
+            total_l1_fee: self.total_l1_fee,
+            by_sender: sorted_entries(self.senders),
+            by_contract: sorted_entries(self.contracts),
+        }
+    }
+}
+
+fn sorted_entries(entries: HashMap<Address, StorageRentEntry>) -> Vec<StorageRentEntry> {
+    let mut entries: Vec<_> = entries.into_values().collect();
+    entries.sort_by(|a, b| {
+        b.diff_size
+            .cmp(&a.diff_size)
+            .then_with(|| a.address.cmp(&b.address))
+    });
+    entries
+}
@@ -0,0 +1,52 @@
+use reth_primitives::{Address, BlockNumberOrTag};
+use reth_rpc_types::trace::parity::{Action, LocalizedTransactionTrace, TraceOutput};
+use serde::{Deserialize, Serialize};
+
+/// The maximum number of blocks that can be traced in a single `trace_filter` request.
+pub const MAX_TRACE_FILTER_BLOCK_RANGE: u64 = 100;
+
+/// Filter of `trace_filter`, in the format of the OpenEthereum trace API.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceFilter {
+    /// First block of the range, the latest block if not set.
+    pub from_block: Option<BlockNumberOrTag>,
+    /// Last block of the range, the latest block if not set.
+    pub to_block: Option<BlockNumberOrTag>,
+    /// Senders of the traced calls, any sender if empty.
+    #[serde(default)]
+    pub from_address: Vec<Address>,
+    /// Receivers of the traced calls, any receiver if empty.
+    #[serde(default)]
+    pub to_address: Vec<Address>,
+    /// Number of matching traces to skip.
+    pub after: Option<u64>,
+    /// Maximum number of matching traces to return.
+    pub count: Option<u64>,
+}
+
+impl TraceFilter {
+    /// Whether the sender and the receiver of a trace match the filter.
+    /// The receiver of a create is the created contract, of a selfdestruct the refund address.
+    pub(crate) fn matches(&self, trace: &LocalizedTransactionTrace) -> bool {
+        let (from, to) = match &trace.trace.action {
+            Action::Call(call) => (Some(call.from), Some(call.to)),
+            Action::Create(create) => (
+                Some(create.from),
+                match &trace.trace.result {
+                    Some(TraceOutput::Create(output)) => Some(output.address),
+                    _ => None,
+                },
+            ),
+            Action::Selfdestruct(selfdestruct) => (
+                Some(selfdestruct.address),
+                Some(selfdestruct.refund_address),
+            ),
+            Action::Reward(reward) => (None, Some(reward.author)),
+        };
+        let matches_address = |addresses: &[Address], address: Option<Address>| {
+            addresses.is_empty() || address.map_or(false, |address| addresses.contains(&address))
+        };
+        matches_address(&self.from_address, from) && matches_address(&self.to_address, to)
+    }
+}
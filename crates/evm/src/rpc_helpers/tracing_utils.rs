@@ -1,22 +1,38 @@
-use reth_primitives::revm::env::{fill_tx_env, fill_tx_env_with_recovered};
+use std::collections::BTreeMap;
+
+use reth_primitives::revm::env::{fill_tx_env, fill_tx_env_with_recovered, tx_env_with_recovered};
 use reth_primitives::revm_primitives::TxEnv;
-use reth_primitives::{TransactionSigned, TransactionSignedEcRecovered, TxHash, U256};
+use reth_primitives::{
+    TransactionSigned, TransactionSignedEcRecovered, TxHash, B256, KECCAK_EMPTY, U256,
+};
 use reth_revm::tracing::{FourByteInspector, TracingInspector, TracingInspectorConfig};
 use reth_rpc_types::trace::geth::{
-    FourByteFrame, GethDebugBuiltInTracerType, GethDebugTracerType, GethDebugTracingOptions,
-    GethTrace, NoopFrame,
+    AccountState, CallFrame, DiffMode, FourByteFrame, GethDebugBuiltInTracerType,
+    GethDebugTracerType, GethDebugTracingOptions, GethTrace, NoopFrame, PreStateFrame,
+    PreStateMode,
 };
+use reth_rpc_types::trace::parity::LocalizedTransactionTrace;
+use reth_rpc_types::TransactionInfo;
+use revm::inspectors::NoOpInspector;
 use revm::precompile::{PrecompileSpecId, Precompiles};
 use revm::primitives::db::Database;
-use revm::primitives::{Address, BlockEnv, CfgEnvWithHandlerCfg, EVMError, ResultAndState, SpecId};
+use revm::primitives::{
+    Address, BlockEnv, CfgEnvWithHandlerCfg, EVMError, ResultAndState, SpecId, State,
+};
 use revm::{inspector_handle_register, Inspector};
 
+use super::QueryContext;
 use crate::error::rpc::{EthApiError, EthResult};
 use crate::evm::db::EvmDb;
-use crate::handler::{citrea_handle_register, CitreaExternalExt, TracingCitreaExternal};
+use crate::evm::GasSchedule;
+use crate::handler::{
+    citrea_handle_register, surcharge_opcodes_register, CitreaExternalExt, TracingCitreaExternal,
+};
 use crate::RpcInvalidTransactionError;
 
+#[allow(clippy::too_many_arguments)]
 pub(crate) fn trace_transaction<C: sov_modules_api::Context>(
+    ctx: &QueryContext,
     opts: GethDebugTracingOptions,
     config_env: CfgEnvWithHandlerCfg,
     block_env: BlockEnv,
@@ -24,6 +40,7 @@ pub(crate) fn trace_transaction<C: sov_modules_api::Context>(
     tx_hash: TxHash,
     db: &mut EvmDb<'_, C>,
     l1_fee_rate: u64,
+    gas_schedule: GasSchedule,
 ) -> EthResult<(GethTrace, revm::primitives::State)> {
     let GethDebugTracingOptions {
         config,
@@ -37,7 +54,8 @@ pub(crate) fn trace_transaction<C: sov_modules_api::Context>(
             GethDebugTracerType::BuiltInTracer(tracer) => match tracer {
                 GethDebugBuiltInTracerType::FourByteTracer => {
                     let inspector = FourByteInspector::default();
-                    let mut citrea_inspector = TracingCitreaExternal::new(inspector, l1_fee_rate);
+                    let mut citrea_inspector =
+                        TracingCitreaExternal::new(inspector, l1_fee_rate, gas_schedule);
                     let res = inspect_citrea(
                         db,
                         config_env,
@@ -59,7 +77,8 @@ pub(crate) fn trace_transaction<C: sov_modules_api::Context>(
                         TracingInspectorConfig::from_geth_config(&config)
                             .set_record_logs(call_config.with_log.unwrap_or_default()),
                     );
-                    let mut citrea_inspector = TracingCitreaExternal::new(inspector, l1_fee_rate);
+                    let mut citrea_inspector =
+                        TracingCitreaExternal::new(inspector, l1_fee_rate, gas_schedule);
                     let res = inspect_citrea(
                         db,
                         config_env,
@@ -68,22 +87,35 @@ pub(crate) fn trace_transaction<C: sov_modules_api::Context>(
                         tx_hash,
                         &mut citrea_inspector,
                     )?;
-                    let frame = citrea_inspector
+                    let mut frame = citrea_inspector
                         .inspector
                         .into_geth_builder()
                         .geth_call_traces(call_config, res.result.gas_used());
+                    decode_custom_error_reverts(ctx, &mut frame);
                     return Ok((frame.into(), res.state));
                 }
                 GethDebugBuiltInTracerType::PreStateTracer => {
-                    // Requires DatabaseRef trait
-                    // meaning we need a readonly state to implement this
-                    return Err(EthApiError::Unsupported("PreStateTracer"));
+                    let prestate_config = tracer_config
+                        .into_pre_state_config()
+                        .map_err(|_| EthApiError::InvalidTracerConfig)?;
+                    let mut citrea_inspector =
+                        TracingCitreaExternal::new(NoOpInspector, l1_fee_rate, gas_schedule);
+                    let res = inspect_citrea(
+                        &mut *db,
+                        config_env,
+                        block_env,
+                        tx_env,
+                        tx_hash,
+                        &mut citrea_inspector,
+                    )?;
+                    // the state changes are not committed yet, so the db still has the pre state
+                    let frame = prestate_frame(db, &res.state, prestate_config.is_diff_mode())?;
+                    return Ok((frame.into(), res.state));
                 }
                 GethDebugBuiltInTracerType::NoopTracer => {
                     Ok((NoopFrame::default().into(), Default::default()))
                 }
-                // TODO: either implement or return unsupported
-                GethDebugBuiltInTracerType::MuxTracer => todo!("MuxTracer"),
+                GethDebugBuiltInTracerType::MuxTracer => Err(EthApiError::Unsupported("MuxTracer")),
             },
             GethDebugTracerType::JsTracer(_code) => {
                 // This also requires DatabaseRef trait
@@ -97,7 +129,7 @@ pub(crate) fn trace_transaction<C: sov_modules_api::Context>(
     let inspector_config = TracingInspectorConfig::from_geth_config(&config);
 
     let inspector = TracingInspector::new(inspector_config);
-    let mut citrea_inspector = TracingCitreaExternal::new(inspector, l1_fee_rate);
+    let mut citrea_inspector = TracingCitreaExternal::new(inspector, l1_fee_rate, gas_schedule);
 
     let res = inspect_citrea(
         db,
@@ -118,6 +150,148 @@ pub(crate) fn trace_transaction<C: sov_modules_api::Context>(
     Ok((frame.into(), res.state))
 }
 
+/// Traces a transaction into flat parity traces, as returned by the `trace` namespace.
+pub(crate) fn trace_transaction_parity<C: sov_modules_api::Context>(
+    config_env: CfgEnvWithHandlerCfg,
+    block_env: BlockEnv,
+    tx_env: TxEnv,
+    tx_info: TransactionInfo,
+    db: &mut EvmDb<'_, C>,
+    l1_fee_rate: u64,
+    gas_schedule: GasSchedule,
+) -> EthResult<(Vec<LocalizedTransactionTrace>, State)> {
+    let mut citrea_inspector = TracingCitreaExternal::new(
+        TracingInspector::new(TracingInspectorConfig::default_parity()),
+        l1_fee_rate,
+        gas_schedule,
+    );
+    let res = inspect_citrea(
+        &mut *db,
+        config_env,
+        block_env,
+        tx_env,
+        tx_info.hash.unwrap_or_default(),
+        &mut citrea_inspector,
+    )?;
+    let traces = citrea_inspector
+        .inspector
+        .into_parity_builder()
+        .into_localized_transaction_traces(tx_info);
+    Ok((traces, res.state))
+}
+
+/// Builds the frame of the prestate tracer from the state changes of a transaction, reading the
+/// state before the transaction from `db`.
+///
+/// In default mode, the frame has the state before the transaction of every account and storage
+/// slot it accessed. In diff mode, it has the state before and after the transaction of the
+/// accounts it modified, with only the modified fields after it.
+fn prestate_frame<C: sov_modules_api::Context>(
+    db: &mut EvmDb<'_, C>,
+    state: &State,
+    diff_mode: bool,
+) -> EthResult<PreStateFrame> {
+    let mut pre = BTreeMap::new();
+    let mut post = BTreeMap::new();
+    for (address, account) in state {
+        let pre_info = db.basic(*address)?.unwrap_or_default();
+        let changed_storage = account.changed_storage_slots().next().is_some();
+        let changed_info = pre_info.balance != account.info.balance
+            || pre_info.nonce != account.info.nonce
+            || pre_info.code_hash != account.info.code_hash;
+        let changed = changed_info || changed_storage || account.is_selfdestructed();
+        if diff_mode && !changed {
+            continue;
+        }
+
+        let pre_code = match pre_info.code_hash == KECCAK_EMPTY {
+            true => None,
+            false => Some(db.code_by_hash(pre_info.code_hash)?.original_bytes()),
+        };
+        let pre_storage = account
+            .storage
+            .iter()
+            .filter(|(_, slot)| !diff_mode || slot.is_changed())
+            .map(|(key, slot)| (storage_key(*key), storage_key(slot.original_value())))
+            .collect();
+        pre.insert(
+            *address,
+            AccountState {
+                balance: Some(pre_info.balance),
+                nonce: Some(pre_info.nonce),
+                code: pre_code,
+                storage: pre_storage,
+            },
+        );
+
+        if !diff_mode || account.is_selfdestructed() {
+            continue;
+        }
+        let post_code = match pre_info.code_hash != account.info.code_hash {
+            true => Some(match &account.info.code {
+                Some(code) => code.original_bytes(),
+                None => db.code_by_hash(account.info.code_hash)?.original_bytes(),
+            }),
+            false => None,
+        };
+        post.insert(
+            *address,
+            AccountState {
+                balance: (pre_info.balance != account.info.balance).then_some(account.info.balance),
+                nonce: (pre_info.nonce != account.info.nonce).then_some(account.info.nonce),
+                code: post_code,
+                storage: account
+                    .changed_storage_slots()
+                    .map(|(key, slot)| (storage_key(*key), storage_key(slot.present_value())))
+                    .collect(),
+            },
+        );
+    }
+
+    Ok(match diff_mode {
+        true => PreStateFrame::Diff(DiffMode { pre, post }),
+        false => PreStateFrame::Default(PreStateMode(pre)),
+    })
+}
+
+fn storage_key(value: U256) -> B256 {
+    B256::from(value.to_be_bytes())
+}
+
+/// Executes the transaction with the Citrea handler without committing state changes.
+pub(crate) fn execute_citrea_tx<C: sov_modules_api::Context>(
+    db: &mut EvmDb<'_, C>,
+    config_env: CfgEnvWithHandlerCfg,
+    block_env: BlockEnv,
+    tx: &TransactionSignedEcRecovered,
+    l1_fee_rate: u64,
+    gas_schedule: GasSchedule,
+) -> EthResult<ResultAndState> {
+    let mut citrea_inspector = TracingCitreaExternal::new(NoOpInspector, l1_fee_rate, gas_schedule);
+    Ok(inspect_citrea(
+        db,
+        config_env,
+        block_env,
+        tx_env_with_recovered(tx),
+        tx.hash(),
+        &mut citrea_inspector,
+    )?)
+}
+
+/// Fills the revert reason of reverted calls whose output is a custom error known to the ABI registry.
+fn decode_custom_error_reverts(ctx: &QueryContext, frame: &mut CallFrame) {
+    if frame.revert_reason.is_none() && frame.error.is_some() {
+        frame.revert_reason = frame
+            .output
+            .as_ref()
+            .and_then(|output| ctx.decode_custom_error(output));
+    }
+    frame
+        .calls
+        .iter_mut()
+        .for_each(|call| decode_custom_error_reverts(ctx, call));
+}
+
 /// Executes the [Env] against the given [Database] without committing state changes.
 fn inspect_citrea<DB, I>(
     db: DB,
@@ -133,6 +307,7 @@ where
     I: Inspector<DB>,
     I: CitreaExternalExt,
 {
+    let opcode_surcharges = inspector.gas_schedule().opcode_surcharges.clone();
     let mut evm = revm::Evm::builder()
         .with_db(db)
         .with_external_context(inspector)
@@ -140,6 +315,7 @@ where
         .with_block_env(block_env)
         .with_tx_env(tx_env)
         .append_handler_register(citrea_handle_register)
+        .append_handler_register_box(surcharge_opcodes_register(opcode_surcharges))
         .append_handler_register(inspector_handle_register)
         .build();
     evm.context.external.set_current_tx_hash(tx_hash);
@@ -1,22 +1,33 @@
 use std::str::FromStr;
 
+use borsh::{BorshDeserialize, BorshSerialize};
 use reth_primitives::constants::ETHEREUM_BLOCK_GAS_LIMIT;
-use reth_primitives::{Address, BlockNumberOrTag, Bytes, TransactionKind, U64};
+use reth_primitives::{Address, BlockNumberOrTag, Bytes, TransactionKind, B256, U64};
 use reth_rpc_types::request::{TransactionInput, TransactionRequest};
 use revm::primitives::{SpecId, KECCAK_EMPTY, U256};
+use secp256k1::{PublicKey, SecretKey, SECP256K1};
 use sov_modules_api::default_context::DefaultContext;
 use sov_modules_api::utils::generate_address;
-use sov_modules_api::{Context, Module, StateMapAccessor, StateVecAccessor};
+use sov_modules_api::{
+    CallResponse, Context, Module, StateMapAccessor, StateValueAccessor, StateVecAccessor,
+    WorkingSet,
+};
 
 use crate::call::CallMessage;
 use crate::evm::primitive_types::Receipt;
+use crate::evm::system_contracts::Bridge;
 use crate::smart_contracts::{
     BlockHashContract, LogsContract, SelfDestructorContract, SimpleStorageContract, TestContract,
 };
 use crate::tests::genesis_tests::get_evm;
 use crate::tests::test_signer::TestSigner;
 use crate::tests::DEFAULT_CHAIN_ID;
-use crate::{AccountData, EvmConfig, RlpEvmTransaction};
+use crate::{
+    epoch_public_key, AccountData, AccountFreeze, ContractCreation, DepositKey, DepositParams,
+    EncryptedBatch, EncryptedMempoolConfig, EncryptedQueueEntry, EncryptedTransaction,
+    EpochKeyAnnouncement, EpochKeyRelease, Evm, EvmConfig, GovernanceAction, GovernanceConfig,
+    PrecompileGasCost, QueryContext, RlpEvmTransaction, SignedGovernanceAction,
+};
 
 type C = DefaultContext;
 
@@ -74,7 +85,10 @@ fn call_multiple_test() {
         ];
 
         evm.call(
-            CallMessage { txs: transactions },
+            CallMessage {
+                txs: transactions,
+                encrypted: None,
+            },
             &context,
             &mut working_set,
         )
@@ -180,6 +194,7 @@ fn call_test() {
 
         let call_message = CallMessage {
             txs: rlp_transactions,
+            encrypted: None,
         };
 
         evm.call(call_message, &context, &mut working_set).unwrap();
@@ -255,6 +270,7 @@ fn failed_transaction_test() {
 
         let call_message = CallMessage {
             txs: rlp_transactions,
+            encrypted: None,
         };
         evm.call(call_message, &context, working_set).unwrap();
     }
@@ -317,6 +333,7 @@ fn self_destruct_test() {
         evm.call(
             CallMessage {
                 txs: rlp_transactions,
+                encrypted: None,
             },
             &context,
             &mut working_set,
@@ -369,6 +386,7 @@ fn self_destruct_test() {
                     3,
                     die_to_address,
                 )],
+                encrypted: None,
             },
             &context,
             &mut working_set,
@@ -443,6 +461,7 @@ fn test_block_hash_in_evm() {
         evm.call(
             CallMessage {
                 txs: vec![deploy_message],
+                encrypted: None,
             },
             &context,
             &mut working_set,
@@ -511,7 +530,14 @@ fn test_block_hash_in_evm() {
                 .to_vec()
                 .into(),
         );
-        let resp = evm.get_call(request.clone(), None, None, None, &mut working_set);
+        let resp = evm.get_call(
+            &QueryContext::default(),
+            request.clone(),
+            None,
+            None,
+            None,
+            &mut working_set,
+        );
         if !(260..=515).contains(&i) {
             // Should be 0, there is more than 256 blocks between the last block and the block number
             assert_eq!(resp.unwrap().to_vec(), vec![0u8; 32]);
@@ -571,6 +597,7 @@ fn test_block_gas_limit() {
         evm.call(
             CallMessage {
                 txs: rlp_transactions,
+                encrypted: None,
             },
             &context,
             &mut working_set,
@@ -825,6 +852,7 @@ fn test_l1_fee_success() {
             evm.call(
                 CallMessage {
                     txs: vec![deploy_message],
+                    encrypted: None,
                 },
                 &context,
                 &mut working_set,
@@ -872,6 +900,51 @@ fn test_l1_fee_success() {
     run_tx(1, U256::from(885288), U256::from(gas_fee_paid + 477));
 }
 
+#[test]
+fn test_base_fee_accrued() {
+    let (config, dev_signer, _) =
+        get_evm_config_starting_base_fee(U256::from_str("1000000").unwrap(), None, 1);
+
+    let (evm, mut working_set) = get_evm(&config);
+    assert_eq!(evm.base_fee_accrued.get(&mut working_set), None);
+
+    evm.begin_soft_confirmation_hook(
+        [5u8; 32],
+        1,
+        [42u8; 32],
+        &[10u8; 32],
+        0,
+        0,
+        &mut working_set,
+    );
+    {
+        let sender_address = generate_address::<C>("sender");
+        let sequencer_address = generate_address::<C>("sequencer");
+        let context = C::new(sender_address, sequencer_address, 1);
+
+        let deploy_message =
+            create_contract_message_with_fee(&dev_signer, 0, BlockHashContract::default(), 1);
+
+        evm.call(
+            CallMessage {
+                txs: vec![deploy_message],
+                encrypted: None,
+            },
+            &context,
+            &mut working_set,
+        )
+        .unwrap();
+    }
+    evm.end_soft_confirmation_hook(&mut working_set);
+    evm.finalize_hook(&[99u8; 32].into(), &mut working_set.accessory_state());
+
+    // Base fee is 1 wei, so the whole gas used is accrued
+    assert_eq!(
+        evm.base_fee_accrued.get(&mut working_set),
+        Some(U256::from(114235))
+    );
+}
+
 #[test]
 fn test_l1_fee_not_enough_funds() {
     let (config, dev_signer, _) =
@@ -900,6 +973,7 @@ fn test_l1_fee_not_enough_funds() {
         let call_result = evm.call(
             CallMessage {
                 txs: vec![deploy_message],
+                encrypted: None,
             },
             &context,
             &mut working_set,
@@ -927,3 +1001,814 @@ fn test_l1_fee_not_enough_funds() {
     let db_coinbase = evm.accounts.get(&config.coinbase, &mut working_set);
     assert!(db_coinbase.is_none());
 }
+
+/// Keypers of the encrypted mempool tests and their secret key shares of every epoch.
+fn encrypted_mempool_keypers() -> Vec<(B256, SecretKey)> {
+    vec![
+        (
+            B256::from([1u8; 32]),
+            SecretKey::from_slice(&[3u8; 32]).unwrap(),
+        ),
+        (
+            B256::from([2u8; 32]),
+            SecretKey::from_slice(&[4u8; 32]).unwrap(),
+        ),
+    ]
+}
+
+fn encrypted_mempool_config(keypers: &[(B256, SecretKey)]) -> EncryptedMempoolConfig {
+    EncryptedMempoolConfig {
+        epoch_blocks: 2,
+        expiry: 2,
+        keypers: keypers
+            .iter()
+            .map(|(key, _)| {
+                TestSigner::new(SecretKey::from_slice(key.as_slice()).unwrap()).address()
+            })
+            .collect(),
+    }
+}
+
+fn announce_epoch_key(keyper_key: B256, share: &SecretKey, epoch: u64) -> EpochKeyAnnouncement {
+    EpochKeyAnnouncement::sign(keyper_key, DEFAULT_CHAIN_ID, epoch, share).unwrap()
+}
+
+/// Runs a block with an encrypted batch, returning the block number and the result of the call.
+fn run_encrypted_block(
+    evm: &Evm<C>,
+    batch: EncryptedBatch,
+    working_set: &mut WorkingSet<C>,
+) -> (u64, anyhow::Result<CallResponse>) {
+    let sender_address = generate_address::<C>("sender");
+    let sequencer_address = generate_address::<C>("sequencer");
+    let context = C::new(sender_address, sequencer_address, 1);
+
+    evm.begin_soft_confirmation_hook([5u8; 32], 1, [42u8; 32], &[99u8; 32], 0, 0, working_set);
+    let block_number = evm.block_env.get(working_set).unwrap().number;
+    let result = evm.call(
+        CallMessage {
+            txs: vec![],
+            encrypted: Some(batch),
+        },
+        &context,
+        working_set,
+    );
+    evm.end_soft_confirmation_hook(working_set);
+    evm.finalize_hook(&[99u8; 32].into(), &mut working_set.accessory_state());
+    (block_number, result)
+}
+
+/// Runs empty blocks until the next block is in `epoch`.
+fn run_until_epoch(
+    evm: &Evm<C>,
+    config: &EncryptedMempoolConfig,
+    epoch: u64,
+    working_set: &mut WorkingSet<C>,
+) {
+    while config.epoch_of(evm.head.get(working_set).unwrap().header.number + 1) < epoch {
+        run_encrypted_block(evm, EncryptedBatch::default(), working_set)
+            .1
+            .unwrap();
+    }
+}
+
+#[test]
+fn test_encrypted_mempool() {
+    let (mut config, dev_signer, contract_addr) =
+        get_evm_config(U256::from_str("100000000000000000000").unwrap(), None);
+    let keypers = encrypted_mempool_keypers();
+    let mempool_config = encrypted_mempool_config(&keypers);
+    config.encrypted_mempool = Some(mempool_config.clone());
+
+    let (evm, mut working_set) = get_evm(&config);
+
+    let next_block = evm.head.get(&mut working_set).unwrap().header.number + 1;
+    let epoch = mempool_config.epoch_of(next_block) + 1;
+    let stranger_key = B256::from([9u8; 32]);
+    // The proof of possession of the share is bound to the keyper announcing it
+    let mut stolen_proof = announce_epoch_key(keypers[1].0, &keypers[1].1, epoch);
+    stolen_proof.proof_of_possession =
+        announce_epoch_key(keypers[0].0, &keypers[1].1, epoch).proof_of_possession;
+    let (block_number, result) = run_encrypted_block(
+        &evm,
+        EncryptedBatch {
+            announcements: vec![
+                announce_epoch_key(keypers[0].0, &keypers[0].1, epoch),
+                // Skipped: only the first announcement of a keyper counts
+                announce_epoch_key(keypers[0].0, &keypers[1].1, epoch),
+                // Skipped: not a keyper
+                announce_epoch_key(stranger_key, &keypers[1].1, epoch),
+                // Skipped: the epoch already started
+                announce_epoch_key(keypers[1].0, &keypers[1].1, epoch - 1),
+                // Skipped: invalid proof of possession
+                stolen_proof,
+            ],
+            ..Default::default()
+        },
+        &mut working_set,
+    );
+    result.unwrap();
+    assert_eq!(mempool_config.epoch_of(block_number), epoch - 1);
+    assert!(evm.is_epoch_key_announced(epoch, mempool_config.keypers[0], &mut working_set));
+    assert!(!evm.is_epoch_key_announced(epoch, mempool_config.keypers[1], &mut working_set));
+    assert!(!evm.is_epoch_key_announced(epoch - 1, mempool_config.keypers[1], &mut working_set));
+    assert_eq!(
+        evm.encrypted_mempool_epoch_key(epoch, &mut working_set),
+        None
+    );
+
+    let (_, result) = run_encrypted_block(
+        &evm,
+        EncryptedBatch {
+            announcements: vec![announce_epoch_key(keypers[1].0, &keypers[1].1, epoch)],
+            ..Default::default()
+        },
+        &mut working_set,
+    );
+    result.unwrap();
+    let public_key = epoch_public_key(&[
+        PublicKey::from_secret_key(SECP256K1, &keypers[0].1),
+        PublicKey::from_secret_key(SECP256K1, &keypers[1].1),
+    ])
+    .unwrap();
+    assert_eq!(
+        evm.encrypted_mempool_epoch_key(epoch, &mut working_set),
+        Some(Bytes::from(public_key.serialize().to_vec()))
+    );
+
+    let deploy_message = create_contract_message(&dev_signer, 0, SimpleStorageContract::default());
+    let transaction = EncryptedTransaction::encrypt(
+        epoch,
+        &public_key,
+        &SecretKey::from_slice(&[5u8; 32]).unwrap(),
+        &deploy_message.rlp,
+    );
+    let release = |keyper: usize| EpochKeyRelease {
+        epoch,
+        keyper: mempool_config.keypers[keyper],
+        secret_key: B256::from_slice(&keypers[keyper].1.secret_bytes()),
+    };
+
+    run_until_epoch(&evm, &mempool_config, epoch, &mut working_set);
+    let (block_number, result) = run_encrypted_block(
+        &evm,
+        EncryptedBatch {
+            ordered: vec![transaction.clone()],
+            // Skipped: the epoch didn't end
+            releases: vec![release(0)],
+            ..Default::default()
+        },
+        &mut working_set,
+    );
+    result.unwrap();
+    assert_eq!(
+        evm.encrypted_tx_queue(&mut working_set),
+        vec![EncryptedQueueEntry {
+            transaction: transaction.clone(),
+            ordered_at: block_number,
+        }]
+    );
+    assert!(evm
+        .epoch_secret_key_shares
+        .get(&(epoch, mempool_config.keypers[0]), &mut working_set)
+        .is_none());
+
+    // Transactions of another epoch can't be ordered, and don't fail the rest of the block
+    let mut next_epoch_transaction = transaction.clone();
+    next_epoch_transaction.epoch = epoch + 1;
+    let (_, result) = run_encrypted_block(
+        &evm,
+        EncryptedBatch {
+            ordered: vec![next_epoch_transaction],
+            ..Default::default()
+        },
+        &mut working_set,
+    );
+    result.unwrap();
+    assert_eq!(evm.encrypted_tx_queue(&mut working_set).len(), 1);
+
+    run_until_epoch(&evm, &mempool_config, epoch + 1, &mut working_set);
+    let mut wrong_share = release(1);
+    wrong_share.secret_key = B256::from([6u8; 32]);
+    let (_, result) = run_encrypted_block(
+        &evm,
+        EncryptedBatch {
+            // The share of the second keyper doesn't match its announced public key share
+            releases: vec![release(0), wrong_share],
+            ..Default::default()
+        },
+        &mut working_set,
+    );
+    result.unwrap();
+    assert_eq!(evm.encrypted_tx_queue(&mut working_set).len(), 1);
+    assert!(evm.accounts.get(&contract_addr, &mut working_set).is_none());
+
+    let (_, result) = run_encrypted_block(
+        &evm,
+        EncryptedBatch {
+            releases: vec![release(1)],
+            ..Default::default()
+        },
+        &mut working_set,
+    );
+    result.unwrap();
+    assert!(evm.encrypted_tx_queue(&mut working_set).is_empty());
+    assert!(evm.accounts.get(&contract_addr, &mut working_set).is_some());
+}
+
+#[test]
+fn test_encrypted_mempool_epoch_expiry() {
+    let (mut config, dev_signer, contract_addr) =
+        get_evm_config(U256::from_str("100000000000000000000").unwrap(), None);
+    let keypers = encrypted_mempool_keypers();
+    let mempool_config = encrypted_mempool_config(&keypers);
+    config.encrypted_mempool = Some(mempool_config.clone());
+
+    let (evm, mut working_set) = get_evm(&config);
+
+    let next_block = evm.head.get(&mut working_set).unwrap().header.number + 1;
+    let epoch = mempool_config.epoch_of(next_block) + 1;
+    run_encrypted_block(
+        &evm,
+        EncryptedBatch {
+            announcements: keypers
+                .iter()
+                .map(|(key, share)| announce_epoch_key(*key, share, epoch))
+                .collect(),
+            ..Default::default()
+        },
+        &mut working_set,
+    )
+    .1
+    .unwrap();
+    let public_key = PublicKey::from_slice(
+        &evm.encrypted_mempool_epoch_key(epoch, &mut working_set)
+            .unwrap(),
+    )
+    .unwrap();
+
+    let deploy_message = create_contract_message(&dev_signer, 0, SimpleStorageContract::default());
+    let transaction = EncryptedTransaction::encrypt(
+        epoch,
+        &public_key,
+        &SecretKey::from_slice(&[5u8; 32]).unwrap(),
+        &deploy_message.rlp,
+    );
+    run_until_epoch(&evm, &mempool_config, epoch, &mut working_set);
+    run_encrypted_block(
+        &evm,
+        EncryptedBatch {
+            ordered: vec![transaction],
+            ..Default::default()
+        },
+        &mut working_set,
+    )
+    .1
+    .unwrap();
+
+    // Only the first keyper releases its share
+    run_until_epoch(&evm, &mempool_config, epoch + 1, &mut working_set);
+    run_encrypted_block(
+        &evm,
+        EncryptedBatch {
+            releases: vec![EpochKeyRelease {
+                epoch,
+                keyper: mempool_config.keypers[0],
+                secret_key: B256::from_slice(&keypers[0].1.secret_bytes()),
+            }],
+            ..Default::default()
+        },
+        &mut working_set,
+    )
+    .1
+    .unwrap();
+
+    // The transaction waits for the missing share until the release deadline
+    while evm.head.get(&mut working_set).unwrap().header.number
+        < mempool_config.release_deadline(epoch)
+    {
+        assert_eq!(evm.encrypted_tx_queue(&mut working_set).len(), 1);
+        assert_eq!(evm.expired_epoch_keypers(epoch, &mut working_set), None);
+        run_encrypted_block(&evm, EncryptedBatch::default(), &mut working_set)
+            .1
+            .unwrap();
+    }
+    assert_eq!(evm.encrypted_tx_queue(&mut working_set).len(), 1);
+
+    run_encrypted_block(&evm, EncryptedBatch::default(), &mut working_set)
+        .1
+        .unwrap();
+    assert!(evm.encrypted_tx_queue(&mut working_set).is_empty());
+    assert_eq!(
+        evm.expired_epoch_keypers(epoch, &mut working_set),
+        Some(vec![mempool_config.keypers[1]])
+    );
+    assert!(evm.accounts.get(&contract_addr, &mut working_set).is_none());
+}
+
+#[test]
+fn test_encrypted_mempool_disabled() {
+    let (config, _, _) = get_evm_config(U256::from_str("100000000000000000000").unwrap(), None);
+    let (evm, mut working_set) = get_evm(&config);
+
+    let (_, result) = run_encrypted_block(
+        &evm,
+        EncryptedBatch {
+            ordered: vec![EncryptedTransaction {
+                epoch: 0,
+                ephemeral_key: vec![2u8; 33],
+                ciphertext: vec![1, 2, 3],
+                tag: B256::ZERO,
+            }],
+            ..Default::default()
+        },
+        &mut working_set,
+    );
+    // The batch is skipped, not the block
+    result.unwrap();
+    assert!(evm.encrypted_tx_queue(&mut working_set).is_empty());
+}
+
+#[test]
+fn test_encrypted_transaction_authentication() {
+    let epoch_secret_key = SecretKey::from_slice(&[1u8; 32]).unwrap();
+    let transaction = EncryptedTransaction::encrypt(
+        1,
+        &PublicKey::from_secret_key(SECP256K1, &epoch_secret_key),
+        &SecretKey::from_slice(&[2u8; 32]).unwrap(),
+        &[3u8; 100],
+    );
+    assert_eq!(transaction.decrypt(&epoch_secret_key), Some(vec![3u8; 100]));
+
+    let mut tampered = transaction.clone();
+    tampered.ciphertext[0] ^= 1;
+    assert_eq!(tampered.decrypt(&epoch_secret_key), None);
+
+    let mut tampered = transaction;
+    tampered.epoch = 2;
+    assert_eq!(tampered.decrypt(&epoch_secret_key), None);
+}
+
+#[test]
+fn test_call_message_encoding() {
+    let txs = vec![RlpEvmTransaction { rlp: vec![1, 2, 3] }];
+
+    // Call messages without an encrypted batch keep the encoding they had before it existed
+    let message = CallMessage {
+        txs: txs.clone(),
+        encrypted: None,
+    };
+    let encoded = message.try_to_vec().unwrap();
+    assert_eq!(encoded, txs.try_to_vec().unwrap());
+    assert_eq!(CallMessage::try_from_slice(&encoded).unwrap(), message);
+
+    let message = CallMessage {
+        txs,
+        encrypted: Some(EncryptedBatch {
+            ordered: vec![EncryptedTransaction {
+                epoch: 1,
+                ephemeral_key: vec![2u8; 33],
+                ciphertext: vec![4, 5],
+                tag: B256::from([5u8; 32]),
+            }],
+            announcements: vec![EpochKeyAnnouncement {
+                epoch: 2,
+                public_key: vec![3u8; 33],
+                signature: vec![8u8; 65],
+                proof_of_possession: vec![9u8; 64],
+            }],
+            releases: vec![EpochKeyRelease {
+                epoch: 1,
+                keyper: Address::from([7u8; 20]),
+                secret_key: B256::from([6u8; 32]),
+            }],
+        }),
+    };
+    let encoded = message.try_to_vec().unwrap();
+    assert_eq!(CallMessage::try_from_slice(&encoded).unwrap(), message);
+
+    let mut invalid_tag = encoded.clone();
+    invalid_tag[message.txs.try_to_vec().unwrap().len()] = 2;
+    assert!(CallMessage::try_from_slice(&invalid_tag).is_err());
+}
+
+#[test]
+fn test_governance_freezes_accounts() {
+    let (mut config, dev_signer, contract_addr) =
+        get_evm_config(U256::from_str("100000000000000000000").unwrap(), None);
+    let governance_key = B256::from([1u8; 32]);
+    let governance = TestSigner::new(SecretKey::from_slice(governance_key.as_slice()).unwrap());
+    config.governance = Some(GovernanceConfig {
+        address: governance.address(),
+        max_freeze_blocks: 1,
+    });
+
+    let (evm, mut working_set) = get_evm(&config);
+
+    let sender_address = generate_address::<C>("sender");
+    let sequencer_address = generate_address::<C>("sequencer");
+    let context = C::new(sender_address, sequencer_address, 1);
+
+    let freeze = |key: B256, nonce: u64, until_block: u64| {
+        SignedGovernanceAction::sign(
+            key,
+            DEFAULT_CHAIN_ID,
+            nonce,
+            GovernanceAction::FreezeAccounts {
+                accounts: vec![dev_signer.address()],
+                until_block,
+            },
+        )
+        .unwrap()
+    };
+    let deploy_message = create_contract_message(&dev_signer, 0, SimpleStorageContract::default());
+    let block_number = evm.head.get(&mut working_set).unwrap().header.number + 1;
+
+    evm.begin_soft_confirmation_hook(
+        [5u8; 32],
+        1,
+        [42u8; 32],
+        &[10u8; 32],
+        0,
+        0,
+        &mut working_set,
+    );
+    // posted to the DA block
+    let posted = [
+        // Not signed by the governance key
+        freeze(B256::from([2u8; 32]), 0, block_number + 1),
+        // Longer than the maximum freeze
+        freeze(governance_key, 0, block_number + 2),
+        freeze(governance_key, 0, block_number + 1),
+        // Replayed
+        freeze(governance_key, 0, block_number + 1),
+    ]
+    .map(|action| action.try_to_vec().unwrap());
+    evm.apply_da_governance_actions(&posted, &mut working_set);
+    evm.call(
+        CallMessage {
+            txs: vec![deploy_message.clone()],
+            encrypted: None,
+        },
+        &context,
+        &mut working_set,
+    )
+    .unwrap();
+    evm.end_soft_confirmation_hook(&mut working_set);
+    evm.finalize_hook(&[99u8; 32].into(), &mut working_set.accessory_state());
+
+    assert_eq!(
+        evm.frozen_accounts
+            .get(&dev_signer.address(), &mut working_set),
+        Some(AccountFreeze {
+            until_block: block_number + 1,
+            governance_nonce: 0,
+        })
+    );
+    assert_eq!(evm.governance_nonce(&mut working_set), 1);
+    // The transaction of the frozen account was dropped
+    assert!(evm.accounts.get(&contract_addr, &mut working_set).is_none());
+
+    // The freeze expired, and the actions of the DA block are not applied again by the next
+    // soft confirmation on it
+    evm.begin_soft_confirmation_hook(
+        [5u8; 32],
+        1,
+        [42u8; 32],
+        &[99u8; 32],
+        0,
+        0,
+        &mut working_set,
+    );
+    evm.apply_da_governance_actions(&posted, &mut working_set);
+    evm.call(
+        CallMessage {
+            txs: vec![deploy_message],
+            encrypted: None,
+        },
+        &context,
+        &mut working_set,
+    )
+    .unwrap();
+    evm.end_soft_confirmation_hook(&mut working_set);
+    evm.finalize_hook(&[99u8; 32].into(), &mut working_set.accessory_state());
+
+    assert_eq!(evm.governance_nonce(&mut working_set), 1);
+    assert!(evm.accounts.get(&contract_addr, &mut working_set).is_some());
+}
+
+#[test]
+fn test_contract_creations_are_indexed() {
+    let (config, dev_signer, _) =
+        get_evm_config(U256::from_str("100000000000000000000").unwrap(), None);
+    let (evm, mut working_set) = get_evm(&config);
+
+    // init code of a contract with the runtime code `STOP`
+    let child_init_code = hex::decode("600060005360016000f3").unwrap();
+    // init code deploying the child with CREATE2 and salt 0, then returning the runtime code `STOP`
+    let factory_init_code =
+        hex::decode("69600060005360016000f36000526000600a60166000f55060016000f3").unwrap();
+    let contract_addr = dev_signer.address().create(0);
+    let factory_addr = dev_signer.address().create(1);
+    let child_addr = factory_addr.create2_from_code(B256::ZERO, &child_init_code);
+
+    evm.begin_soft_confirmation_hook(
+        [5u8; 32],
+        1,
+        [42u8; 32],
+        &[10u8; 32],
+        0,
+        0,
+        &mut working_set,
+    );
+    {
+        let sender_address = generate_address::<C>("sender");
+        let sequencer_address = generate_address::<C>("sequencer");
+        let context = C::new(sender_address, sequencer_address, 1);
+
+        let factory_message = dev_signer
+            .sign_default_transaction(TransactionKind::Create, factory_init_code, 1, 0)
+            .unwrap();
+        evm.call(
+            CallMessage {
+                txs: vec![
+                    create_contract_message(&dev_signer, 0, SimpleStorageContract::default()),
+                    factory_message,
+                ],
+                encrypted: None,
+            },
+            &context,
+            &mut working_set,
+        )
+        .unwrap();
+    }
+    evm.end_soft_confirmation_hook(&mut working_set);
+    evm.finalize_hook(&[99u8; 32].into(), &mut working_set.accessory_state());
+
+    let tx_count = evm.transactions.len(&mut working_set.accessory_state());
+    let tx_hash = |index: usize, working_set: &mut WorkingSet<C>| {
+        evm.transactions
+            .get(index, &mut working_set.accessory_state())
+            .unwrap()
+            .signed_transaction
+            .hash
+    };
+    let deploy_hash = tx_hash(tx_count - 2, &mut working_set);
+    let factory_hash = tx_hash(tx_count - 1, &mut working_set);
+    let block_number = evm.head.get(&mut working_set).unwrap().header.number;
+
+    assert_eq!(
+        evm.get_contract_creation(contract_addr, &mut working_set),
+        Ok(Some(ContractCreation {
+            transaction_hash: deploy_hash,
+            block_number,
+            creator: dev_signer.address(),
+        }))
+    );
+    let factory_creation = ContractCreation {
+        transaction_hash: factory_hash,
+        block_number,
+        creator: dev_signer.address(),
+    };
+    assert_eq!(
+        evm.get_contract_creation(factory_addr, &mut working_set),
+        Ok(Some(factory_creation))
+    );
+    assert_eq!(
+        evm.get_contract_creation(child_addr, &mut working_set),
+        Ok(Some(factory_creation))
+    );
+    assert_eq!(
+        evm.get_contract_creation(dev_signer.address(), &mut working_set),
+        Ok(None)
+    );
+}
+
+#[test]
+fn test_deposits_are_minted_once() {
+    let (config, dev_signer, _) =
+        get_evm_config(U256::from_str("100000000000000000000").unwrap(), None);
+    let (evm, mut working_set) = get_evm(&config);
+
+    let sender_address = generate_address::<C>("sender");
+    let sequencer_address = generate_address::<C>("sequencer");
+    let context = C::new(sender_address, sequencer_address, 1);
+
+    // the deposit of the bridge contract tests
+    let params = DepositParams {
+        version: hex::decode("02000000").unwrap().try_into().unwrap(),
+        flag: hex::decode("0001").unwrap().try_into().unwrap(),
+        vin: hex::decode("01d4d6c5c94583a0505dd0c1eb64760ba2a6a391f6da3164094ed8bcac190b7d6c0000000000fdffffff").unwrap(),
+        vout: hex::decode("0378dcf50500000000225120081bb55c845b1b14b8580a0246764d53d4aa579645c67568d8375c71f687a2ce4a01000000000000220020340a847f2a890d208f6c7a21811116134bd2b01cc1d46a999e61da195f6b8a3b4a010000000000002200204ae81572f06e1b88fd5ced7a1a000945432e83e1551e6f721ee9c00b8cc33260").unwrap(),
+        witness: vec![0x01, 0x00],
+        locktime: [0; 4],
+        intermediate_nodes: vec![],
+        block_height: U256::from(505051),
+        index: U256::from(1),
+    };
+    let key = DepositKey {
+        txid: B256::from_str("7e959e984eebdadad61e83ee4efc27b899810c03e34c1ea2541a514e8186411b")
+            .unwrap(),
+        vout: 0,
+    };
+    assert_eq!(params.key(), key);
+
+    // the same deposit transaction with another witness
+    let malleated = DepositParams {
+        witness: vec![0x01, 0x01, 0x00],
+        ..params.clone()
+    };
+    assert_eq!(malleated.key(), key);
+    let deposit_message = |params: &DepositParams, nonce: u64| {
+        dev_signer
+            .sign_default_transaction(
+                TransactionKind::Call(Bridge::address()),
+                Bridge::default().deposit(params).to_vec(),
+                nonce,
+                0,
+            )
+            .unwrap()
+    };
+
+    let mut produce_block = |txs: Vec<RlpEvmTransaction>| {
+        evm.begin_soft_confirmation_hook(
+            [5u8; 32],
+            1,
+            [42u8; 32],
+            &[10u8; 32],
+            0,
+            0,
+            &mut working_set,
+        );
+        evm.call(
+            CallMessage {
+                txs,
+                encrypted: None,
+            },
+            &context,
+            &mut working_set,
+        )
+        .unwrap();
+        evm.end_soft_confirmation_hook(&mut working_set);
+        evm.finalize_hook(&[99u8; 32].into(), &mut working_set.accessory_state());
+        (
+            evm.processed_deposits.get(&key, &mut working_set),
+            evm.accounts
+                .get(&dev_signer.address(), &mut working_set)
+                .unwrap()
+                .info
+                .nonce,
+        )
+    };
+
+    // the second mint of the block is dropped
+    assert_eq!(
+        produce_block(vec![
+            deposit_message(&params, 0),
+            deposit_message(&malleated, 1)
+        ]),
+        (Some(1), 1)
+    );
+    // and so is a mint in a later block
+    assert_eq!(
+        produce_block(vec![deposit_message(&malleated, 1)]),
+        (Some(1), 1)
+    );
+}
+
+/// Executes transactions in the first block of a new chain, returns their receipts.
+fn execute_transactions(config: &EvmConfig, txs: Vec<RlpEvmTransaction>) -> Vec<Receipt> {
+    let (evm, mut working_set) = get_evm(config);
+
+    let sender_address = generate_address::<C>("sender");
+    let sequencer_address = generate_address::<C>("sequencer");
+    let context = C::new(sender_address, sequencer_address, 1);
+
+    let tx_count = txs.len();
+    evm.begin_soft_confirmation_hook(
+        [5u8; 32],
+        1,
+        [42u8; 32],
+        &[10u8; 32],
+        0,
+        0,
+        &mut working_set,
+    );
+    evm.call(
+        CallMessage {
+            txs,
+            encrypted: None,
+        },
+        &context,
+        &mut working_set,
+    )
+    .unwrap();
+    evm.end_soft_confirmation_hook(&mut working_set);
+    evm.finalize_hook(&[99u8; 32].into(), &mut working_set.accessory_state());
+
+    let receipts: Vec<Receipt> = evm
+        .receipts
+        .iter(&mut working_set.accessory_state())
+        .collect();
+    receipts.into_iter().rev().take(tx_count).rev().collect()
+}
+
+#[test]
+fn test_gas_schedule_reprices_precompiles_before_execution() {
+    let (mut config, dev_signer, _) =
+        get_evm_config(U256::from_str("100000000000000000000").unwrap(), None);
+    let ecrecover = Address::with_last_byte(1);
+    let identity = Address::with_last_byte(4);
+    // Cheaper than the 3000 gas ecrecover charges by default
+    config.gas_schedule.precompiles.insert(
+        ecrecover,
+        PrecompileGasCost {
+            base: 100,
+            per_word: 0,
+        },
+    );
+    config.gas_schedule.precompiles.insert(
+        identity,
+        PrecompileGasCost {
+            base: 1000,
+            per_word: 0,
+        },
+    );
+
+    let input = vec![1u8; 32];
+    let intrinsic_gas = 21_000 + 16 * input.len() as u64;
+    let call = |to: Address, nonce: u64, gas_limit: u64| {
+        dev_signer
+            .sign_default_transaction_with_gas_limit(
+                TransactionKind::Call(to),
+                input.clone(),
+                nonce,
+                gas_limit,
+            )
+            .unwrap()
+    };
+
+    let receipts = execute_transactions(
+        &config,
+        vec![
+            call(ecrecover, 0, intrinsic_gas + 100),
+            call(identity, 1, intrinsic_gas + 1000),
+            call(identity, 2, intrinsic_gas + 999),
+        ],
+    );
+    assert_eq!(
+        receipts
+            .iter()
+            .map(|receipt| (receipt.receipt.success, receipt.gas_used))
+            .collect::<Vec<_>>(),
+        vec![
+            // The default cost of ecrecover doesn't fit in the gas limit
+            (true, intrinsic_gas + 100),
+            (true, intrinsic_gas + 1000),
+            // Out of gas consumes the whole gas limit
+            (false, intrinsic_gas + 999),
+        ]
+    );
+}
+
+#[test]
+fn test_gas_schedule_surcharges_opcodes() {
+    let (mut config, dev_signer, _) =
+        get_evm_config(U256::from_str("100000000000000000000").unwrap(), None);
+    let contract = Address::from([0xcc; 20]);
+    // PUSH1 1 PUSH1 0 SSTORE STOP
+    config.data.push(AccountData::new(
+        contract,
+        U256::ZERO,
+        Bytes::from_static(&[0x60, 0x01, 0x60, 0x00, 0x55, 0x00]),
+        Default::default(),
+    ));
+    let call = |nonce: u64, gas_limit: u64| {
+        dev_signer
+            .sign_default_transaction_with_gas_limit(
+                TransactionKind::Call(contract),
+                vec![],
+                nonce,
+                gas_limit,
+            )
+            .unwrap()
+    };
+
+    let receipts = execute_transactions(&config, vec![call(0, 100_000)]);
+    assert!(receipts[0].receipt.success);
+    let gas_used = receipts[0].gas_used;
+
+    // SSTORE
+    config.gas_schedule.opcode_surcharges.insert(0x55, 5000);
+    let receipts = execute_transactions(
+        &config,
+        vec![call(0, gas_used + 4999), call(1, gas_used + 5000)],
+    );
+    assert_eq!(
+        receipts
+            .iter()
+            .map(|receipt| (receipt.receipt.success, receipt.gas_used))
+            .collect::<Vec<_>>(),
+        vec![(false, gas_used + 4999), (true, gas_used + 5000)]
+    );
+}
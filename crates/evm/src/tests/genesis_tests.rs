@@ -15,8 +15,11 @@ use sov_prover_storage_manager::new_orphan_storage;
 
 use super::queries::commit;
 use crate::evm::primitive_types::SealedBlock;
-use crate::evm::{AccountInfo, DbAccount, EvmChainConfig};
-use crate::{AccountData, Evm, EvmConfig};
+use crate::evm::system_contracts::SystemContractUpgrade;
+use crate::evm::{
+    AccountInfo, DbAccount, EncryptedMempoolConfig, EvmChainConfig, PrecompileGasCost,
+};
+use crate::{AccountData, Evm, EvmConfig, QueryContext};
 
 type C = DefaultContext;
 
@@ -63,6 +66,15 @@ lazy_static! {
         difficulty: U256::ZERO,
         extra_data: Bytes::default(),
         nonce: 0,
+        gas_schedule: Default::default(),
+        state_root_anchor_interval: 0,
+        system_tx_gas_limit: None,
+        block_gas_target: None,
+        system_contract_upgrades: vec![],
+        encrypted_mempool: None,
+        proof_verifying_key: None,
+        ordering_policy_version: None,
+        governance: None,
     };
 
     pub(crate) static ref GENESIS_HASH: B256 = B256::from(hex!(
@@ -96,11 +108,18 @@ fn genesis_data() {
         .unwrap();
 
     let contract_storage1 = evm
-        .get_storage_at(contract.address, U256::from(0), None, &mut working_set)
+        .get_storage_at(
+            &QueryContext::default(),
+            contract.address,
+            U256::from(0),
+            None,
+            &mut working_set,
+        )
         .unwrap();
 
     let contract_storage2 = evm
         .get_storage_at(
+            &QueryContext::default(),
             contract.address,
             U256::from_be_slice(
                 &hex::decode("6661e9d6d8b923d5bbaab1b96e1dd51ff6ea2a93520fdc9eb75d059238b8c5e9")
@@ -194,6 +213,175 @@ fn genesis_empty_spec_defaults_to_shanghai() {
     assert_eq!(cfg.spec, vec![(0, SpecId::SHANGHAI)]);
 }
 
+#[test]
+fn genesis_gas_schedule() {
+    let (evm, mut working_set) = get_evm(&TEST_CONFIG);
+    assert_eq!(evm.gas_schedule.get(&mut working_set), None);
+
+    let mut config = TEST_CONFIG.clone();
+    config.gas_schedule.precompiles.insert(
+        Address::from([0xcc; 20]),
+        PrecompileGasCost {
+            base: 3000,
+            per_word: 12,
+        },
+    );
+    let (evm, mut working_set) = get_evm(&config);
+
+    let gas_schedule = evm.gas_schedule.get(&mut working_set).unwrap();
+    assert_eq!(gas_schedule, config.gas_schedule);
+    assert_eq!(
+        gas_schedule.precompile_cost(&Address::from([0xcc; 20]), 33),
+        Some(3000 + 2 * 12)
+    );
+    assert_eq!(
+        gas_schedule.precompile_cost(&Address::from([0xdd; 20]), 33),
+        None
+    );
+}
+
+#[test]
+fn genesis_state_root_anchor_interval() {
+    let (evm, mut working_set) = get_evm(&TEST_CONFIG);
+    assert_eq!(evm.state_root_anchor_interval.get(&mut working_set), None);
+
+    let mut config = TEST_CONFIG.clone();
+    config.state_root_anchor_interval = 10;
+    let (evm, mut working_set) = get_evm(&config);
+
+    assert_eq!(
+        evm.state_root_anchor_interval.get(&mut working_set),
+        Some(10)
+    );
+}
+
+#[test]
+fn genesis_block_gas_target() {
+    let gas_limit = TEST_CONFIG.block_gas_limit;
+    let base_fee = 1_000_000_000;
+
+    // without a target the base fee follows the EIP-1559 target of half the gas limit
+    let (evm, mut working_set) = get_evm(&TEST_CONFIG);
+    assert_eq!(evm.block_gas_target.get(&mut working_set), None);
+    assert_eq!(
+        evm.next_base_fee(gas_limit / 2, gas_limit, base_fee, &mut working_set),
+        base_fee
+    );
+
+    let mut config = TEST_CONFIG.clone();
+    config.block_gas_target = Some(gas_limit / 4);
+    let (evm, mut working_set) = get_evm(&config);
+    assert_eq!(
+        evm.block_gas_target.get(&mut working_set),
+        Some(gas_limit / 4)
+    );
+    assert_eq!(
+        evm.next_base_fee(gas_limit / 4, gas_limit, base_fee, &mut working_set),
+        base_fee
+    );
+    assert!(evm.next_base_fee(gas_limit / 2, gas_limit, base_fee, &mut working_set) > base_fee);
+    assert!(evm.next_base_fee(gas_limit / 8, gas_limit, base_fee, &mut working_set) < base_fee);
+
+    for block_gas_target in [0, gas_limit + 1] {
+        let mut config = TEST_CONFIG.clone();
+        config.block_gas_target = Some(block_gas_target);
+        let tmpdir = tempfile::tempdir().unwrap();
+        let mut working_set = WorkingSet::new(new_orphan_storage(tmpdir.path()).unwrap());
+        assert!(Evm::<C>::default()
+            .genesis(&config, &mut working_set)
+            .is_err());
+    }
+}
+
+#[test]
+fn genesis_system_contract_upgrades() {
+    let upgrade = |block_number, proxy| SystemContractUpgrade {
+        block_number,
+        proxy: Address::from([proxy; 20]),
+        code: Bytes::from_static(&[0x60, 0x00]),
+        call_data: Bytes::default(),
+    };
+    let mut config = TEST_CONFIG.clone();
+    config.system_contract_upgrades = vec![upgrade(10, 1), upgrade(20, 1), upgrade(10, 2)];
+    let (evm, mut working_set) = get_evm(&config);
+
+    assert_eq!(
+        evm.system_contract_upgrades.get(&10, &mut working_set),
+        Some(vec![upgrade(10, 1), upgrade(10, 2)])
+    );
+    assert_eq!(
+        evm.system_contract_upgrades.get(&20, &mut working_set),
+        Some(vec![upgrade(20, 1)])
+    );
+    assert_eq!(
+        evm.system_contract_upgrades.get(&11, &mut working_set),
+        None
+    );
+
+    // the genesis block has no system transactions
+    let mut config = TEST_CONFIG.clone();
+    config.system_contract_upgrades = vec![upgrade(0, 1)];
+    let tmpdir = tempfile::tempdir().unwrap();
+    let mut working_set = WorkingSet::new(new_orphan_storage(tmpdir.path()).unwrap());
+    assert!(Evm::<C>::default()
+        .genesis(&config, &mut working_set)
+        .is_err());
+}
+
+#[test]
+fn genesis_encrypted_mempool() {
+    let (evm, mut working_set) = get_evm(&TEST_CONFIG);
+    assert_eq!(evm.encrypted_mempool_config.get(&mut working_set), None);
+
+    let encrypted_mempool = EncryptedMempoolConfig {
+        epoch_blocks: 2,
+        expiry: 100,
+        keypers: vec![Address::from([1u8; 20])],
+    };
+    let mut config = TEST_CONFIG.clone();
+    config.encrypted_mempool = Some(encrypted_mempool.clone());
+    let (evm, mut working_set) = get_evm(&config);
+
+    assert_eq!(
+        evm.encrypted_mempool_config.get(&mut working_set),
+        Some(encrypted_mempool.clone())
+    );
+
+    // Empty epochs and an empty committee are rejected
+    for invalid in [
+        EncryptedMempoolConfig {
+            epoch_blocks: 0,
+            ..encrypted_mempool.clone()
+        },
+        EncryptedMempoolConfig {
+            keypers: vec![],
+            ..encrypted_mempool
+        },
+    ] {
+        config.encrypted_mempool = Some(invalid);
+        let tmpdir = tempfile::tempdir().unwrap();
+        let mut working_set = WorkingSet::new(new_orphan_storage(tmpdir.path()).unwrap());
+        assert!(Evm::<C>::default()
+            .genesis(&config, &mut working_set)
+            .is_err());
+    }
+}
+
+#[test]
+fn genesis_proof_verifying_key() {
+    let (evm, mut working_set) = get_evm(&TEST_CONFIG);
+    assert_eq!(evm.proof_verifying_key(&mut working_set), None);
+
+    let mut config = TEST_CONFIG.clone();
+    config.proof_verifying_key = Some(Bytes::from_static(&[1, 2, 3]));
+    let (evm, mut working_set) = get_evm(&config);
+
+    assert_eq!(
+        evm.proof_verifying_key(&mut working_set),
+        Some(Bytes::from_static(&[1, 2, 3]))
+    );
+}
+
 #[test]
 #[should_panic(expected = "Cancun is not supported")]
 fn genesis_cfg_cancun() {
@@ -13,7 +13,10 @@ use crate::evm::primitive_types::{
 };
 use crate::tests::genesis_tests::{BENEFICIARY, GENESIS_STATE_ROOT};
 use crate::tests::DEFAULT_CHAIN_ID;
-use crate::PendingTransaction;
+use crate::{
+    ordering_policy_extra_data, ordering_policy_version, PendingTransaction,
+    LATEST_ORDERING_POLICY_VERSION,
+};
 
 lazy_static! {
     pub(crate) static ref DA_ROOT_HASH: B256 = B256::from([5u8; 32]);
@@ -177,6 +180,34 @@ fn end_soft_confirmation_hook_moves_transactions_and_receipts() {
     assert_eq!(evm.pending_transactions.len(&mut working_set), 0);
 }
 
+#[test]
+fn end_soft_confirmation_hook_records_ordering_policy_version() {
+    let mut config = TEST_CONFIG.clone();
+    config.ordering_policy_version = Some(LATEST_ORDERING_POLICY_VERSION);
+    let (evm, mut working_set) = get_evm(&config);
+
+    evm.begin_soft_confirmation_hook(
+        DA_ROOT_HASH.0,
+        1,
+        [42u8; 32],
+        &[10u8; 32],
+        0,
+        54,
+        &mut working_set,
+    );
+    evm.end_soft_confirmation_hook(&mut working_set);
+
+    let head = evm.head.get(&mut working_set).unwrap();
+    assert_eq!(
+        head.header.extra_data,
+        ordering_policy_extra_data(LATEST_ORDERING_POLICY_VERSION)
+    );
+    assert_eq!(
+        ordering_policy_version(&head.header.extra_data),
+        Some(LATEST_ORDERING_POLICY_VERSION)
+    );
+}
+
 fn create_pending_transaction(hash: B256, index: u64) -> PendingTransaction {
     PendingTransaction {
         transaction: TransactionSignedAndRecovered {
@@ -3,6 +3,7 @@ mod cfg_tests;
 mod genesis_tests;
 mod hooks_tests;
 mod queries;
+mod state_diff_tests;
 mod sys_tx_tests;
 pub(crate) mod test_signer;
 mod tx_tests;
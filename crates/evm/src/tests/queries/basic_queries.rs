@@ -1,17 +1,22 @@
 use std::collections::BTreeMap;
 use std::str::FromStr;
+use std::sync::Arc;
 
 use alloy_primitives::FixedBytes;
 use hex::FromHex;
-use reth_primitives::{Address, BlockId, BlockNumberOrTag, U64};
+use reth_primitives::{
+    Address, BlockId, BlockNumberOrTag, TransactionKind, TransactionSignedEcRecovered, U64,
+};
 use reth_rpc_types::request::{TransactionInput, TransactionRequest};
-use reth_rpc_types::{Block, Rich, TransactionReceipt};
+use reth_rpc_types::{Block, BlockTransactions, Rich, RpcBlockHash, TransactionReceipt};
 use revm::primitives::{B256, U256};
 use serde_json::json;
+use sov_modules_api::StateValueAccessor;
 
 use crate::smart_contracts::SimpleStorageContract;
+use crate::tests::call_tests::{create_contract_transaction, set_arg_message};
 use crate::tests::queries::init_evm;
-use crate::EthApiError;
+use crate::{EthApiError, QueryContext};
 
 #[test]
 fn get_block_by_hash_test() {
@@ -63,6 +68,130 @@ fn get_block_by_number_test() {
     check_against_third_block(&block);
 }
 
+#[test]
+fn get_block_by_finality_tag_test() {
+    let (evm, mut working_set, _) = init_evm();
+
+    // the tags are unknown until the node resolves them
+    let result =
+        evm.get_block_by_number(Some(BlockNumberOrTag::Safe), Some(false), &mut working_set);
+    assert_eq!(result, Err(EthApiError::UnknownSafeOrFinalizedBlock.into()));
+
+    let ctx = QueryContext::default().with_finality_resolver(Arc::new(|tag| match tag {
+        BlockNumberOrTag::Safe => Some(2),
+        BlockNumberOrTag::Finalized => Some(1),
+        _ => None,
+    }));
+    assert!(matches!(
+        QueryContext::default().resolve_finality_tag(BlockNumberOrTag::Safe),
+        Err(EthApiError::UnknownSafeOrFinalizedBlock)
+    ));
+
+    let safe = ctx.resolve_finality_tag(BlockNumberOrTag::Safe).unwrap();
+    assert_eq!(safe, BlockNumberOrTag::Number(2));
+    let block = evm
+        .get_block_by_number(Some(safe), Some(false), &mut working_set)
+        .unwrap()
+        .unwrap();
+    check_against_third_block(&block);
+
+    let finalized = ctx
+        .resolve_finality_tag(BlockNumberOrTag::Finalized)
+        .unwrap();
+    let block = evm
+        .get_block_by_number(Some(finalized), Some(false), &mut working_set)
+        .unwrap()
+        .unwrap();
+    assert_eq!(block.header.number, Some(U256::from(1)));
+
+    assert_eq!(
+        ctx.finality_tag_height(BlockNumberOrTag::Finalized),
+        Some(1)
+    );
+    assert_eq!(
+        ctx.resolve_finality_tag(BlockNumberOrTag::Latest).unwrap(),
+        BlockNumberOrTag::Latest
+    );
+}
+
+#[test]
+fn state_history_test() {
+    let (evm, mut working_set, signer) = init_evm();
+
+    // the state of the genesis block is out of the history of the node
+    let ctx = QueryContext::default().with_state_history_blocks(1);
+    let genesis = Some(BlockNumberOrTag::Number(0).into());
+    assert!(evm
+        .get_balance(&ctx, signer.address(), genesis, &mut working_set)
+        .is_err());
+    assert!(evm
+        .get_balance(&ctx, signer.address(), None, &mut working_set)
+        .is_ok());
+
+    // nodes serve the state of every block by default
+    assert!(evm
+        .get_balance(
+            &QueryContext::default(),
+            signer.address(),
+            genesis,
+            &mut working_set
+        )
+        .is_ok());
+}
+
+#[test]
+fn block_hash_param_test() {
+    let (evm, mut working_set, signer) = init_evm();
+
+    let third_block_hash =
+        B256::from_hex("0x2d7962c316685635252886d6801a553139e94e3b7d2b678f8c9d974a54e24ab9")
+            .unwrap();
+
+    let by_number = evm
+        .get_transaction_count(
+            &QueryContext::default(),
+            signer.address(),
+            Some(BlockNumberOrTag::Number(2).into()),
+            &mut working_set,
+        )
+        .unwrap();
+    let by_hash = evm
+        .get_transaction_count(
+            &QueryContext::default(),
+            signer.address(),
+            Some(BlockId::Hash(third_block_hash.into())),
+            &mut working_set,
+        )
+        .unwrap();
+    assert_eq!(by_hash, by_number);
+
+    // every stored block is canonical
+    let by_canonical_hash = evm
+        .get_transaction_count(
+            &QueryContext::default(),
+            signer.address(),
+            Some(BlockId::Hash(RpcBlockHash::from_hash(
+                third_block_hash,
+                Some(true),
+            ))),
+            &mut working_set,
+        )
+        .unwrap();
+    assert_eq!(by_canonical_hash, by_number);
+
+    let unknown_hash = B256::repeat_byte(5);
+    let result = evm.get_balance(
+        &QueryContext::default(),
+        signer.address(),
+        Some(BlockId::Hash(unknown_hash.into())),
+        &mut working_set,
+    );
+    assert_eq!(
+        result,
+        Err(EthApiError::UnknownBlockHash(unknown_hash).into())
+    );
+}
+
 #[test]
 fn get_block_receipts_test() {
     // make a block
@@ -142,6 +271,7 @@ fn get_transaction_by_block_number_and_index_test() {
     let (evm, mut working_set, _) = init_evm();
 
     let result = evm.get_transaction_by_block_number_and_index(
+        &QueryContext::default(),
         BlockNumberOrTag::Number(100),
         U64::from(0),
         &mut working_set,
@@ -151,6 +281,7 @@ fn get_transaction_by_block_number_and_index_test() {
 
     // doesn't exist
     let result = evm.get_transaction_by_block_number_and_index(
+        &QueryContext::default(),
         BlockNumberOrTag::Number(1),
         U64::from(3),
         &mut working_set,
@@ -161,6 +292,7 @@ fn get_transaction_by_block_number_and_index_test() {
     // these should exist
     for i in 0..3 {
         let result = evm.get_transaction_by_block_number_and_index(
+            &QueryContext::default(),
             BlockNumberOrTag::Number(1),
             U64::from(i),
             &mut working_set,
@@ -177,6 +309,7 @@ fn get_transaction_by_block_number_and_index_test() {
     ];
     for (i, tx_hash) in tx_hashes.iter().enumerate() {
         let result = evm.get_transaction_by_block_number_and_index(
+            &QueryContext::default(),
             BlockNumberOrTag::Number(2),
             U64::from(i),
             &mut working_set,
@@ -235,11 +368,53 @@ fn get_block_transaction_count_by_hash_test() {
     assert_eq!(result, Ok(Some(U256::from(2))));
 }
 
+#[test]
+fn get_block_transaction_count_by_number_test() {
+    let (evm, mut working_set, _) = init_evm();
+
+    let result = evm.eth_get_block_transaction_count_by_number(
+        &QueryContext::default(),
+        BlockNumberOrTag::Number(5),
+        &mut working_set,
+    );
+    // Non-existent block number should return None
+    assert_eq!(result, Ok(None));
+
+    let result = evm.eth_get_block_transaction_count_by_number(
+        &QueryContext::default(),
+        BlockNumberOrTag::Number(1),
+        &mut working_set,
+    );
+    assert_eq!(result, Ok(Some(U256::from(3))));
+
+    let result = evm.eth_get_block_transaction_count_by_number(
+        &QueryContext::default(),
+        BlockNumberOrTag::Number(2),
+        &mut working_set,
+    );
+    assert_eq!(result, Ok(Some(U256::from(4))));
+
+    // Latest and pending both return the head block
+    let result = evm.eth_get_block_transaction_count_by_number(
+        &QueryContext::default(),
+        BlockNumberOrTag::Latest,
+        &mut working_set,
+    );
+    assert_eq!(result, Ok(Some(U256::from(2))));
+    let result = evm.eth_get_block_transaction_count_by_number(
+        &QueryContext::default(),
+        BlockNumberOrTag::Pending,
+        &mut working_set,
+    );
+    assert_eq!(result, Ok(Some(U256::from(2))));
+}
+
 #[test]
 fn call_test() {
     let (evm, mut working_set, signer) = init_evm();
 
     let fail_result = evm.get_call(
+        &QueryContext::default(),
         TransactionRequest {
             from: Some(signer.address()),
             to: Some(Address::from_str("0xeeb03d20dae810f52111b853b31c8be6f30f4cd3").unwrap()),
@@ -258,7 +433,7 @@ fn call_test() {
             sidecar: None,
             other: Default::default(),
         },
-        Some(BlockNumberOrTag::Number(100)),
+        Some(BlockNumberOrTag::Number(100).into()),
         None,
         None,
         &mut working_set,
@@ -271,6 +446,7 @@ fn call_test() {
     let call_data = contract.get_call_data().to_string();
 
     let nonce_too_low_result = evm.get_call(
+        &QueryContext::default(),
         TransactionRequest {
             from: Some(signer.address()),
             to: Some(Address::from_str("0xeeb03d20dae810f52111b853b31c8be6f30f4cd3").unwrap()),
@@ -289,7 +465,7 @@ fn call_test() {
             sidecar: None,
             other: Default::default(),
         },
-        Some(BlockNumberOrTag::Number(3)),
+        Some(BlockNumberOrTag::Number(3).into()),
         None,
         None,
         &mut working_set,
@@ -300,6 +476,7 @@ fn call_test() {
 
     let result = evm
         .get_call(
+            &QueryContext::default(),
             TransactionRequest {
                 from: Some(signer.address()),
                 to: Some(Address::from_str("0xeeb03d20dae810f52111b853b31c8be6f30f4cd3").unwrap()),
@@ -321,7 +498,7 @@ fn call_test() {
                 other: Default::default(),
             },
             // How does this work precisely? In the first block, the contract was not there?
-            Some(BlockNumberOrTag::Latest),
+            Some(BlockNumberOrTag::Latest.into()),
             None,
             None,
             &mut working_set,
@@ -336,6 +513,7 @@ fn call_test() {
 
     let result = evm
         .get_call(
+            &QueryContext::default(),
             TransactionRequest {
                 from: Some(signer.address()),
                 to: Some(Address::from_str("0xeeb03d20dae810f52111b853b31c8be6f30f4cd3").unwrap()),
@@ -357,7 +535,7 @@ fn call_test() {
                 other: Default::default(),
             },
             // How does this work precisely? In the first block, the contract was not there?
-            Some(BlockNumberOrTag::Latest),
+            Some(BlockNumberOrTag::Latest.into()),
             None,
             None,
             &mut working_set,
@@ -374,6 +552,493 @@ fn call_test() {
     // https://github.com/chainwayxyz/citrea/issues/134
 }
 
+#[test]
+fn get_block_ordering_info_test() {
+    let (evm, mut working_set, _) = init_evm();
+
+    assert_eq!(
+        evm.get_block_ordering_info(
+            &QueryContext::default(),
+            BlockNumberOrTag::Number(5),
+            &mut working_set
+        ),
+        Ok(None)
+    );
+
+    let block = evm
+        .get_block_by_number(Some(BlockNumberOrTag::Number(2)), None, &mut working_set)
+        .unwrap()
+        .unwrap();
+    let ordering_info = evm
+        .get_block_ordering_info(
+            &QueryContext::default(),
+            BlockNumberOrTag::Number(2),
+            &mut working_set,
+        )
+        .unwrap()
+        .unwrap();
+
+    assert_eq!(ordering_info.block_number, U64::from(2));
+    assert_eq!(
+        U256::from(ordering_info.base_fee_per_gas.to::<u64>()),
+        block.header.base_fee_per_gas.unwrap()
+    );
+    let BlockTransactions::Hashes(hashes) = &block.transactions else {
+        panic!("Block must have transaction hashes only");
+    };
+    // blocks of the test config are not built under a versioned ordering policy
+    assert_eq!(ordering_info.ordering_policy_version, None);
+    assert_eq!(ordering_info.transactions.len(), 4);
+    for (position, (tx, hash)) in ordering_info.transactions.iter().zip(hashes).enumerate() {
+        assert_eq!(tx.hash, *hash);
+        assert_eq!(tx.position, U64::from(position));
+        // transactions of the test signer don't pay a priority fee
+        assert_eq!(tx.effective_priority_fee, U256::ZERO);
+    }
+}
+
+#[test]
+fn export_state_test() {
+    let (evm, mut working_set, signer) = init_evm();
+    let logs_contract = Address::from_str("0x819c5497b157177315e1204f52e588b393771719").unwrap();
+    let storage_contract = Address::from_str("0xeeb03d20dae810f52111b853b31c8be6f30f4cd3").unwrap();
+
+    let export = evm
+        .export_state(
+            &QueryContext::default(),
+            BlockNumberOrTag::Latest,
+            None,
+            None,
+            &mut working_set,
+        )
+        .unwrap();
+    assert_eq!(export.block_number, U64::from(3));
+    assert_eq!(export.next_offset, None);
+    assert_eq!(
+        export.state_root,
+        evm.get_block_by_number(Some(BlockNumberOrTag::Latest), None, &mut working_set)
+            .unwrap()
+            .unwrap()
+            .header
+            .state_root
+    );
+    assert_eq!(export.alloc[&signer.address()].nonce, Some(9));
+    assert!(export.alloc[&logs_contract].code.is_some());
+    assert_eq!(
+        export.alloc[&storage_contract]
+            .storage
+            .as_ref()
+            .unwrap()
+            .get(&B256::ZERO),
+        Some(&B256::from(U256::from(478)))
+    );
+
+    // The genesis account is the first page
+    let page = evm
+        .export_state(
+            &QueryContext::default(),
+            BlockNumberOrTag::Latest,
+            None,
+            Some(U64::from(1)),
+            &mut working_set,
+        )
+        .unwrap();
+    assert_eq!(
+        page.alloc.keys().collect::<Vec<_>>(),
+        vec![&signer.address()]
+    );
+    assert_eq!(page.next_offset, Some(U64::from(1)));
+
+    // Accounts created after the block are not exported
+    let export = evm
+        .export_state(
+            &QueryContext::default(),
+            BlockNumberOrTag::Number(2),
+            None,
+            None,
+            &mut working_set,
+        )
+        .unwrap();
+    assert_eq!(export.alloc[&signer.address()].nonce, Some(7));
+    assert!(export.alloc.contains_key(&logs_contract));
+    assert!(!export.alloc.contains_key(&storage_contract));
+
+    let genesis = evm
+        .export_state(
+            &QueryContext::default(),
+            BlockNumberOrTag::Earliest,
+            None,
+            None,
+            &mut working_set,
+        )
+        .unwrap();
+    assert_eq!(genesis.alloc.len(), 1);
+    assert_eq!(
+        genesis.alloc[&signer.address()].balance,
+        U256::from_str("100000000000000000000").unwrap()
+    );
+
+    assert!(evm
+        .export_state(
+            &QueryContext::default(),
+            BlockNumberOrTag::Latest,
+            None,
+            Some(U64::from(0)),
+            &mut working_set,
+        )
+        .is_err());
+}
+
+#[test]
+fn get_storage_rent_report_test() {
+    let (evm, mut working_set, signer) = init_evm();
+
+    let report = evm
+        .get_storage_rent_report(
+            BlockNumberOrTag::Earliest,
+            BlockNumberOrTag::Latest,
+            &mut working_set,
+        )
+        .unwrap();
+
+    assert_eq!(report.from_block, U64::from(0));
+    assert_eq!(report.to_block, U64::from(3));
+    assert_eq!(report.tx_count, 9);
+    assert!(report.total_diff_size > 0);
+
+    // All transactions are sent by the same signer.
+    assert_eq!(report.by_sender.len(), 1);
+    assert_eq!(report.by_sender[0].address, signer.address());
+    assert_eq!(report.by_sender[0].diff_size, report.total_diff_size);
+    assert_eq!(report.by_sender[0].l1_fee, report.total_l1_fee);
+
+    let contract_diff_size: u64 = report.by_contract.iter().map(|e| e.diff_size).sum();
+    assert_eq!(contract_diff_size, report.total_diff_size);
+    assert!(report
+        .by_contract
+        .windows(2)
+        .all(|pair| pair[0].diff_size >= pair[1].diff_size));
+
+    let genesis_report = evm
+        .get_storage_rent_report(
+            BlockNumberOrTag::Number(0),
+            BlockNumberOrTag::Number(0),
+            &mut working_set,
+        )
+        .unwrap();
+    assert_eq!(genesis_report.tx_count, 0);
+    assert!(genesis_report.by_sender.is_empty());
+
+    assert_eq!(
+        evm.get_storage_rent_report(
+            BlockNumberOrTag::Number(2),
+            BlockNumberOrTag::Number(1),
+            &mut working_set,
+        ),
+        Err(EthApiError::InvalidBlockRange.into())
+    );
+    assert_eq!(
+        evm.get_storage_rent_report(
+            BlockNumberOrTag::Number(0),
+            BlockNumberOrTag::Number(1000),
+            &mut working_set,
+        ),
+        Err(EthApiError::UnknownBlockNumber.into())
+    );
+}
+
+#[test]
+fn get_storage_history_test() {
+    let (evm, mut working_set, _) = init_evm();
+
+    // the contract is deployed and its slot set to 478 in the third block
+    let contract_address = Address::from_str("0xeeb03d20dae810f52111b853b31c8be6f30f4cd3").unwrap();
+
+    let history = evm
+        .get_storage_history(
+            &QueryContext::default(),
+            contract_address,
+            U256::ZERO,
+            BlockNumberOrTag::Earliest,
+            BlockNumberOrTag::Latest,
+            &mut working_set,
+        )
+        .unwrap();
+    assert_eq!(history.from_block, U64::from(0));
+    assert_eq!(history.to_block, U64::from(3));
+    assert_eq!(history.initial_value, B256::ZERO);
+    assert_eq!(history.changes.len(), 1);
+    assert_eq!(history.changes[0].block_number, U64::from(3));
+    assert_eq!(
+        history.changes[0].value,
+        B256::from(U256::from(478).to_be_bytes())
+    );
+
+    let history = evm
+        .get_storage_history(
+            &QueryContext::default(),
+            contract_address,
+            U256::ZERO,
+            BlockNumberOrTag::Number(3),
+            BlockNumberOrTag::Number(3),
+            &mut working_set,
+        )
+        .unwrap();
+    assert_eq!(
+        history.initial_value,
+        B256::from(U256::from(478).to_be_bytes())
+    );
+    assert!(history.changes.is_empty());
+
+    let history = evm
+        .get_storage_history(
+            &QueryContext::default(),
+            contract_address,
+            U256::from(1),
+            BlockNumberOrTag::Earliest,
+            BlockNumberOrTag::Latest,
+            &mut working_set,
+        )
+        .unwrap();
+    assert_eq!(history.initial_value, B256::ZERO);
+    assert!(history.changes.is_empty());
+
+    assert_eq!(
+        evm.get_storage_history(
+            &QueryContext::default(),
+            contract_address,
+            U256::ZERO,
+            BlockNumberOrTag::Number(2),
+            BlockNumberOrTag::Number(1),
+            &mut working_set,
+        ),
+        Err(EthApiError::InvalidBlockRange.into())
+    );
+    assert_eq!(
+        evm.get_storage_history(
+            &QueryContext::default(),
+            contract_address,
+            U256::ZERO,
+            BlockNumberOrTag::Number(0),
+            BlockNumberOrTag::Number(1000),
+            &mut working_set,
+        ),
+        Err(EthApiError::UnknownBlockNumber.into())
+    );
+}
+
+#[test]
+fn get_block_gas_usage_test() {
+    let (evm, mut working_set, _) = init_evm();
+
+    let usage = evm
+        .get_block_gas_usage(
+            BlockNumberOrTag::Earliest,
+            BlockNumberOrTag::Latest,
+            Some(2),
+            &mut working_set,
+        )
+        .unwrap();
+
+    assert_eq!(usage.total.from_block, U64::from(0));
+    assert_eq!(usage.total.to_block, U64::from(3));
+    assert_eq!(usage.windows.len(), 2);
+    assert_eq!(usage.windows[0].to_block, U64::from(1));
+    assert_eq!(usage.windows[1].from_block, U64::from(2));
+
+    let mut total_gas_used = 0;
+    for block_number in 0..=3 {
+        let block = evm
+            .get_block_by_number(
+                Some(BlockNumberOrTag::Number(block_number)),
+                None,
+                &mut working_set,
+            )
+            .unwrap()
+            .unwrap();
+        total_gas_used += block.header.gas_used.to::<u64>();
+    }
+    assert_eq!(usage.total.total_gas_used, total_gas_used);
+    assert_eq!(
+        usage.total.total_gas_used,
+        usage.windows[0].total_gas_used + usage.windows[1].total_gas_used
+    );
+    assert_eq!(usage.total.avg_gas_used, total_gas_used / 4);
+    assert!(usage.total.p50_gas_used <= usage.total.p90_gas_used);
+    assert!(usage.total.p99_gas_used <= usage.total.max_gas_used);
+
+    // Transactions are attributed to contracts, largest gas used first.
+    let contract_gas_used: u64 = usage.total.top_contracts.iter().map(|c| c.gas_used).sum();
+    assert!(contract_gas_used > 0 && contract_gas_used <= total_gas_used);
+    assert!(usage
+        .total
+        .top_contracts
+        .windows(2)
+        .all(|pair| pair[0].gas_used >= pair[1].gas_used));
+
+    // Without a window size the whole range is a single window.
+    let single_window = evm
+        .get_block_gas_usage(
+            BlockNumberOrTag::Earliest,
+            BlockNumberOrTag::Latest,
+            None,
+            &mut working_set,
+        )
+        .unwrap();
+    assert_eq!(single_window.windows, vec![single_window.total.clone()]);
+
+    assert_eq!(
+        evm.get_block_gas_usage(
+            BlockNumberOrTag::Number(0),
+            BlockNumberOrTag::Number(1),
+            Some(0),
+            &mut working_set,
+        ),
+        Err(EthApiError::InvalidParams("Window size must be positive".to_string()).into())
+    );
+    assert_eq!(
+        evm.get_block_gas_usage(
+            BlockNumberOrTag::Number(2),
+            BlockNumberOrTag::Number(1),
+            None,
+            &mut working_set,
+        ),
+        Err(EthApiError::InvalidBlockRange.into())
+    );
+}
+
+#[test]
+fn export_state_tests_test() {
+    let (evm, mut working_set, signer) = init_evm();
+
+    let fixtures = evm
+        .export_state_tests(
+            &QueryContext::default(),
+            BlockNumberOrTag::Number(3),
+            BlockNumberOrTag::Number(3),
+            &mut working_set,
+        )
+        .unwrap();
+    assert_eq!(fixtures.len(), 2);
+
+    for fixture in fixtures.values() {
+        assert_eq!(fixture.env.current_number, U256::from(3));
+        assert_eq!(fixture.transaction.sender, signer.address());
+
+        let post = &fixture.post["Shanghai"][0];
+        let sender_pre = &fixture.pre[&signer.address()];
+        let sender_post = &post.state[&signer.address()];
+        assert_eq!(sender_post.nonce, sender_pre.nonce + U64::from(1));
+        assert!(sender_post.balance < sender_pre.balance);
+    }
+
+    let contract_address = signer.address().create(7);
+    let set_arg = fixtures
+        .values()
+        .find(|fixture| fixture.transaction.to == format!("{:#x}", contract_address))
+        .unwrap();
+    let contract_post = &set_arg.post["Shanghai"][0].state[&contract_address];
+    assert!(!contract_post.code.is_empty());
+    assert_eq!(contract_post.storage[&U256::ZERO], U256::from(478));
+
+    let create = fixtures
+        .values()
+        .find(|fixture| fixture.transaction.to.is_empty())
+        .unwrap();
+    assert!(!create.pre.contains_key(&contract_address));
+
+    assert_eq!(
+        evm.export_state_tests(
+            &QueryContext::default(),
+            BlockNumberOrTag::Number(2),
+            BlockNumberOrTag::Number(1),
+            &mut working_set,
+        ),
+        Err(EthApiError::InvalidBlockRange.into())
+    );
+}
+
+#[test]
+fn apply_pending_transactions_test() {
+    let (evm, mut working_set, signer) = init_evm();
+
+    let contract_address = signer.address().create(9);
+    let txs: Vec<TransactionSignedEcRecovered> = vec![
+        create_contract_transaction(&signer, 9, SimpleStorageContract::default()),
+        set_arg_message(contract_address, &signer, 10, 42),
+    ]
+    .into_iter()
+    .map(|tx| tx.try_into().unwrap())
+    .collect();
+
+    let results = evm.apply_pending_transactions(&txs, &mut working_set);
+    assert_eq!(results.len(), 2);
+    assert!(results.iter().all(|result| result.success));
+    assert_eq!(results[1].nonce, 10);
+    assert!(results[0].diff_size > 0);
+
+    let code = evm
+        .get_code(
+            &QueryContext::default(),
+            contract_address,
+            Some(BlockNumberOrTag::Latest.into()),
+            &mut working_set,
+        )
+        .unwrap();
+    assert!(!code.is_empty());
+    let storage = evm
+        .get_storage_at(
+            &QueryContext::default(),
+            contract_address,
+            U256::ZERO,
+            Some(BlockNumberOrTag::Latest.into()),
+            &mut working_set,
+        )
+        .unwrap();
+    assert_eq!(U256::from_be_bytes(storage.0), U256::from(42));
+}
+
+#[test]
+fn apply_pending_transactions_in_next_block_env_test() {
+    let (evm, mut working_set, signer) = init_evm();
+
+    // NUMBER PUSH1 0 SSTORE BASEFEE PUSH1 1 SSTORE STOP
+    let init_code = vec![0x43, 0x60, 0x00, 0x55, 0x48, 0x60, 0x01, 0x55, 0x00];
+    let created = signer.address().create(9);
+    let tx: TransactionSignedEcRecovered = signer
+        .sign_default_transaction(TransactionKind::Create, init_code, 9, 0)
+        .unwrap()
+        .try_into()
+        .unwrap();
+
+    let results = evm.apply_pending_transactions(&[tx], &mut working_set);
+    assert!(results[0].success);
+
+    let head = evm.head.get(&mut working_set).unwrap();
+    let cfg = evm.cfg.get(&mut working_set).unwrap();
+    let mut storage_at = |slot: u64| {
+        let storage = evm
+            .get_storage_at(
+                &QueryContext::default(),
+                created,
+                U256::from(slot),
+                Some(BlockNumberOrTag::Latest.into()),
+                &mut working_set,
+            )
+            .unwrap();
+        U256::from_be_bytes(storage.0)
+    };
+    assert_eq!(storage_at(0), U256::from(head.header.number + 1));
+    assert_eq!(
+        storage_at(1),
+        U256::from(
+            head.header
+                .next_block_base_fee(cfg.base_fee_params)
+                .unwrap()
+        )
+    );
+}
+
 fn check_against_third_block(block: &Rich<Block>) {
     // details = false
     let mut inner_block = serde_json::from_value::<Block>(json!({
@@ -439,6 +1104,10 @@ fn check_against_third_block_receipts(receipts: Vec<TransactionReceipt>) {
         "to": "0x819c5497b157177315e1204f52e588b393771719",
         "l1FeeRate": "0x1",
         "diffSize": "0x9c",
+        "l1Fee": "0x9c",
+        "l1GasUsed": "0x9c",
+        "l1GasPrice": "0x1",
+        "l1FeeScalar": "1",
         "contractAddress": null,
         "logs": [
             {
@@ -488,6 +1157,10 @@ fn check_against_third_block_receipts(receipts: Vec<TransactionReceipt>) {
         "to": "0x819c5497b157177315e1204f52e588b393771719",
         "l1FeeRate": "0x1",
         "diffSize": "0x9c",
+        "l1Fee": "0x9c",
+        "l1GasUsed": "0x9c",
+        "l1GasPrice": "0x1",
+        "l1FeeScalar": "1",
         "contractAddress": null,
         "logs": [
             {
@@ -537,6 +1210,10 @@ fn check_against_third_block_receipts(receipts: Vec<TransactionReceipt>) {
         "to": "0x819c5497b157177315e1204f52e588b393771719",
         "l1FeeRate": "0x1",
         "diffSize": "0x9c",
+        "l1Fee": "0x9c",
+        "l1GasUsed": "0x9c",
+        "l1GasPrice": "0x1",
+        "l1FeeScalar": "1",
         "contractAddress": null,
         "logs": [
             {
@@ -586,6 +1263,10 @@ fn check_against_third_block_receipts(receipts: Vec<TransactionReceipt>) {
         "to": "0x819c5497b157177315e1204f52e588b393771719",
         "l1FeeRate": "0x1",
         "diffSize": "0x9c",
+        "l1Fee": "0x9c",
+        "l1GasUsed": "0x9c",
+        "l1GasPrice": "0x1",
+        "l1FeeScalar": "1",
         "contractAddress": null,
         "logs": [
             {
@@ -626,3 +1307,160 @@ fn check_against_third_block_receipts(receipts: Vec<TransactionReceipt>) {
 
     assert_eq!(receipts, test_receipts)
 }
+
+#[test]
+fn get_proof_test() {
+    let (evm, mut working_set, signer) = init_evm();
+
+    let contract_address = Address::from_str("0xeeb03d20dae810f52111b853b31c8be6f30f4cd3").unwrap();
+    let slot = B256::ZERO;
+
+    let proof = evm
+        .get_proof(
+            &QueryContext::default(),
+            contract_address,
+            vec![slot],
+            Some(BlockNumberOrTag::Number(3).into()),
+            &mut working_set,
+        )
+        .unwrap();
+    let storage = evm
+        .get_storage_at(
+            &QueryContext::default(),
+            contract_address,
+            U256::ZERO,
+            Some(BlockNumberOrTag::Number(3).into()),
+            &mut working_set,
+        )
+        .unwrap();
+    assert_eq!(proof.address, contract_address);
+    assert!(proof.account_proof.value.is_some());
+    assert_eq!(proof.storage_proof.len(), 1);
+    assert_eq!(proof.storage_proof[0].key, slot);
+    assert_eq!(proof.storage_proof[0].value, U256::from_be_bytes(storage.0));
+    assert!(proof.storage_proof[0].proof.value.is_some());
+
+    let latest = evm
+        .get_proof(
+            &QueryContext::default(),
+            contract_address,
+            vec![slot],
+            None,
+            &mut working_set,
+        )
+        .unwrap();
+    assert_eq!(latest, proof);
+
+    // the contract is deployed in the third block
+    let before_deployment = evm
+        .get_proof(
+            &QueryContext::default(),
+            contract_address,
+            vec![slot],
+            Some(BlockNumberOrTag::Number(2).into()),
+            &mut working_set,
+        )
+        .unwrap();
+    assert_ne!(before_deployment.state_root, proof.state_root);
+    assert!(before_deployment.account_proof.value.is_none());
+    assert_eq!(before_deployment.balance, U256::ZERO);
+    assert_eq!(before_deployment.storage_proof[0].value, U256::ZERO);
+    assert!(before_deployment.storage_proof[0].proof.value.is_none());
+
+    let signer_proof = evm
+        .get_proof(
+            &QueryContext::default(),
+            signer.address(),
+            vec![],
+            None,
+            &mut working_set,
+        )
+        .unwrap();
+    assert_eq!(signer_proof.nonce, U64::from(9));
+    assert!(signer_proof.storage_proof.is_empty());
+
+    assert_eq!(
+        evm.get_proof(
+            &QueryContext::default(),
+            contract_address,
+            vec![],
+            Some(BlockNumberOrTag::Number(4).into()),
+            &mut working_set,
+        ),
+        Err(EthApiError::UnknownBlockNumber.into())
+    );
+}
+
+#[test]
+fn otterscan_test() {
+    let (evm, mut working_set, signer) = init_evm();
+    let storage_contract = Address::from_str("0xeeb03d20dae810f52111b853b31c8be6f30f4cd3").unwrap();
+
+    let details = evm
+        .ots_get_block_details(2, &mut working_set)
+        .unwrap()
+        .unwrap();
+    assert_eq!(details.block.transaction_count, U64::from(4));
+    assert_eq!(
+        details.block.block.transactions,
+        BlockTransactions::Hashes(vec![])
+    );
+    assert_eq!(details.issuance, Default::default());
+    assert!(details.total_fees > U256::ZERO);
+    assert_eq!(evm.ots_get_block_details(4, &mut working_set), Ok(None));
+
+    let tx = evm
+        .get_transaction_by_block_number_and_index(
+            &QueryContext::default(),
+            BlockNumberOrTag::Number(2),
+            U64::from(1),
+            &mut working_set,
+        )
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        evm.ots_get_transaction_by_sender_and_nonce(
+            &QueryContext::default(),
+            signer.address(),
+            4,
+            &mut working_set
+        ),
+        Ok(Some(tx.hash))
+    );
+    assert_eq!(
+        evm.ots_get_transaction_by_sender_and_nonce(
+            &QueryContext::default(),
+            signer.address(),
+            9,
+            &mut working_set
+        ),
+        Ok(None)
+    );
+
+    // pages end with whole blocks
+    let page = evm
+        .ots_search_transactions_before(signer.address(), 0, 1, &mut working_set)
+        .unwrap();
+    assert_eq!(page.txs.len(), 2);
+    assert_eq!(page.receipts.len(), 2);
+    assert!(page.first_page);
+    assert!(!page.last_page);
+    assert_eq!(page.txs[0].nonce, U64::from(8));
+
+    let page = evm
+        .ots_search_transactions_before(signer.address(), 3, 10, &mut working_set)
+        .unwrap();
+    assert_eq!(page.txs.len(), 7);
+    assert!(!page.first_page);
+    assert!(page.last_page);
+
+    // the contract is created and called in the last block
+    let page = evm
+        .ots_search_transactions_after(storage_contract, 0, 10, &mut working_set)
+        .unwrap();
+    assert_eq!(page.txs.len(), 2);
+    assert_eq!(page.txs[0].nonce, U64::from(8));
+    assert_eq!(page.txs[1].nonce, U64::from(7));
+    assert!(page.first_page);
+    assert!(page.last_page);
+}
@@ -15,7 +15,7 @@ use sov_modules_api::WorkingSet;
 use crate::smart_contracts::{CallerContract, SimpleStorageContract};
 use crate::tests::queries::{init_evm, init_evm_single_block, init_evm_with_caller_contract};
 use crate::tests::test_signer::TestSigner;
-use crate::Evm;
+use crate::{Evm, QueryContext, MIN_TRANSACTION_GAS};
 
 type C = DefaultContext;
 
@@ -45,7 +45,13 @@ fn payable_contract_value_test() {
         other: Default::default(),
     };
 
-    let result = evm.eth_estimate_gas(tx_req, Some(BlockNumberOrTag::Latest), &mut working_set);
+    let result = evm.eth_estimate_gas(
+        &QueryContext::default(),
+        tx_req,
+        Some(BlockNumberOrTag::Latest.into()),
+        None,
+        &mut working_set,
+    );
     assert_eq!(result.unwrap(), Uint::from_str("0xab12").unwrap());
 }
 
@@ -76,8 +82,10 @@ fn test_tx_request_fields_gas() {
     };
 
     let result_contract_call = evm.eth_estimate_gas(
+        &QueryContext::default(),
         tx_req_contract_call.clone(),
-        Some(BlockNumberOrTag::Latest),
+        Some(BlockNumberOrTag::Latest.into()),
+        None,
         &mut working_set,
     );
     assert_eq!(
@@ -92,8 +100,10 @@ fn test_tx_request_fields_gas() {
     };
 
     let result_no_sender = evm.eth_estimate_gas(
+        &QueryContext::default(),
         tx_req_no_sender,
-        Some(BlockNumberOrTag::Latest),
+        Some(BlockNumberOrTag::Latest.into()),
+        None,
         &mut working_set,
     );
     assert_eq!(result_no_sender.unwrap(), Uint::from_str("0x6601").unwrap());
@@ -105,8 +115,10 @@ fn test_tx_request_fields_gas() {
     };
 
     let result_no_recipient = evm.eth_estimate_gas(
+        &QueryContext::default(),
         tx_req_no_recipient,
-        Some(BlockNumberOrTag::Latest),
+        Some(BlockNumberOrTag::Latest.into()),
+        None,
         &mut working_set,
     );
     assert_eq!(
@@ -121,8 +133,10 @@ fn test_tx_request_fields_gas() {
     };
 
     let result_no_gas = evm.eth_estimate_gas(
+        &QueryContext::default(),
         tx_req_no_gas,
-        Some(BlockNumberOrTag::Latest),
+        Some(BlockNumberOrTag::Latest.into()),
+        None,
         &mut working_set,
     );
     assert_eq!(result_no_gas.unwrap(), Uint::from_str("0x6601").unwrap());
@@ -134,8 +148,10 @@ fn test_tx_request_fields_gas() {
     };
 
     let result_no_gas_price = evm.eth_estimate_gas(
+        &QueryContext::default(),
         tx_req_no_gas_price,
-        Some(BlockNumberOrTag::Latest),
+        Some(BlockNumberOrTag::Latest.into()),
+        None,
         &mut working_set,
     );
     assert_eq!(
@@ -150,8 +166,10 @@ fn test_tx_request_fields_gas() {
     };
 
     let result_no_chain_id = evm.eth_estimate_gas(
+        &QueryContext::default(),
         tx_req_no_chain_id,
-        Some(BlockNumberOrTag::Latest),
+        Some(BlockNumberOrTag::Latest.into()),
+        None,
         &mut working_set,
     );
     assert_eq!(
@@ -166,8 +184,10 @@ fn test_tx_request_fields_gas() {
     };
 
     let result_invalid_chain_id = evm.eth_estimate_gas(
+        &QueryContext::default(),
         tx_req_invalid_chain_id,
-        Some(BlockNumberOrTag::Latest),
+        Some(BlockNumberOrTag::Latest.into()),
+        None,
         &mut working_set,
     );
     assert_eq!(
@@ -183,8 +203,10 @@ fn test_tx_request_fields_gas() {
     };
 
     let result_no_blob_versioned_hashes = evm.eth_estimate_gas(
+        &QueryContext::default(),
         tx_req_no_blob_versioned_hashes,
-        Some(BlockNumberOrTag::Latest),
+        Some(BlockNumberOrTag::Latest.into()),
+        None,
         &mut working_set,
     );
     assert_eq!(
@@ -199,8 +221,9 @@ fn test_tx_request_fields_gas() {
     };
 
     let create_no_access_list_test = evm.create_access_list(
+        &QueryContext::default(),
         no_access_list_req,
-        Some(BlockNumberOrTag::Latest),
+        Some(BlockNumberOrTag::Latest.into()),
         &mut working_set,
     );
 
@@ -234,8 +257,10 @@ fn test_tx_request_fields_gas() {
     };
 
     let access_list_gas_test = evm.eth_estimate_gas(
+        &QueryContext::default(),
         access_list_req.clone(),
-        Some(BlockNumberOrTag::Latest),
+        Some(BlockNumberOrTag::Latest.into()),
+        None,
         &mut working_set,
     );
 
@@ -246,8 +271,9 @@ fn test_tx_request_fields_gas() {
     );
 
     let already_formed_list = evm.create_access_list(
+        &QueryContext::default(),
         access_list_req,
-        Some(BlockNumberOrTag::Latest),
+        Some(BlockNumberOrTag::Latest.into()),
         &mut working_set,
     );
 
@@ -303,11 +329,21 @@ fn test_access_list() {
         other: Default::default(),
     };
 
-    let no_access_list = evm.eth_estimate_gas(tx_req_contract_call.clone(), None, &mut working_set);
+    let no_access_list = evm.eth_estimate_gas(
+        &QueryContext::default(),
+        tx_req_contract_call.clone(),
+        None,
+        None,
+        &mut working_set,
+    );
     assert_eq!(no_access_list.unwrap(), Uint::from_str("0x788b").unwrap());
 
-    let form_access_list =
-        evm.create_access_list(tx_req_contract_call.clone(), None, &mut working_set);
+    let form_access_list = evm.create_access_list(
+        &QueryContext::default(),
+        tx_req_contract_call.clone(),
+        None,
+        &mut working_set,
+    );
 
     assert_eq!(
         form_access_list.unwrap(),
@@ -338,7 +374,13 @@ fn test_access_list() {
         ..tx_req_contract_call.clone()
     };
 
-    let with_access_list = evm.eth_estimate_gas(tx_req_with_access_list, None, &mut working_set);
+    let with_access_list = evm.eth_estimate_gas(
+        &QueryContext::default(),
+        tx_req_with_access_list,
+        None,
+        None,
+        &mut working_set,
+    );
     assert_eq!(with_access_list.unwrap(), Uint::from_str("0x775d").unwrap());
 }
 
@@ -368,6 +410,41 @@ fn estimate_gas_with_varied_inputs_test() {
     );
 }
 
+#[test]
+fn estimate_gas_with_state_overrides() {
+    let (evm, mut working_set, _) = init_evm_single_block();
+
+    let sender = Address::from_str("0xcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcd").unwrap();
+    let tx_req = TransactionRequest {
+        from: Some(sender),
+        to: Some(Address::from_str("0xabababababababababababababababababababab").unwrap()),
+        value: Some(U256::from(10u128.pow(18))),
+        ..Default::default()
+    };
+
+    let result = evm.eth_estimate_gas(
+        &QueryContext::default(),
+        tx_req.clone(),
+        Some(BlockNumberOrTag::Latest.into()),
+        None,
+        &mut working_set,
+    );
+    assert!(result.is_err());
+
+    let state_overrides = serde_json::from_value(serde_json::json!({
+        sender.to_string(): { "balance": U256::from(10u128.pow(19)) },
+    }))
+    .unwrap();
+    let result = evm.eth_estimate_gas(
+        &QueryContext::default(),
+        tx_req,
+        Some(BlockNumberOrTag::Latest.into()),
+        Some(state_overrides),
+        &mut working_set,
+    );
+    assert_eq!(result.unwrap(), U64::from(MIN_TRANSACTION_GAS));
+}
+
 fn test_estimate_gas_with_input(
     evm: &Evm<C>,
     working_set: &mut WorkingSet<C>,
@@ -385,7 +462,13 @@ fn test_estimate_gas_with_input(
         ..Default::default()
     };
 
-    evm.eth_estimate_gas(tx_req, Some(BlockNumberOrTag::Latest), working_set)
+    evm.eth_estimate_gas(
+        &QueryContext::default(),
+        tx_req,
+        Some(BlockNumberOrTag::Latest.into()),
+        None,
+        working_set,
+    )
 }
 
 fn test_estimate_gas_with_value(
@@ -401,5 +484,11 @@ fn test_estimate_gas_with_value(
         ..Default::default()
     };
 
-    evm.eth_estimate_gas(tx_req, Some(BlockNumberOrTag::Latest), working_set)
+    evm.eth_estimate_gas(
+        &QueryContext::default(),
+        tx_req,
+        Some(BlockNumberOrTag::Latest.into()),
+        None,
+        working_set,
+    )
 }
@@ -2,17 +2,19 @@ use std::str::FromStr;
 
 use hex::FromHex;
 use jsonrpsee::core::RpcResult;
-use reth_primitives::{Address, BlockNumberOrTag, Bytes, U64};
+use reth_primitives::{Address, BlockNumberOrTag, Bytes, B256, U64};
 use reth_rpc::eth::error::RpcInvalidTransactionError;
 use reth_rpc_types::request::{TransactionInput, TransactionRequest};
+use reth_rpc_types::trace::geth::{GethDebugTracingCallOptions, GethTrace};
 use revm::primitives::U256;
 use sov_modules_api::WorkingSet;
 
 use super::C;
+use crate::rpc_helpers::{SimulateBlock, SimulatePayload, MAX_SIMULATE_BLOCKS};
 use crate::smart_contracts::SimpleStorageContract;
 use crate::tests::queries::{init_evm, init_evm_single_block};
 use crate::tests::test_signer::TestSigner;
-use crate::Evm;
+use crate::{Evm, QueryContext};
 
 #[test]
 fn call_contract_without_value() {
@@ -24,6 +26,7 @@ fn call_contract_without_value() {
     let contract_call_data = Bytes::from(contract.set_call_data(5).to_vec());
 
     let call_result = evm.get_call(
+        &QueryContext::default(),
         TransactionRequest {
             from: Some(signer.address()),
             to: Some(contract_address),
@@ -33,7 +36,7 @@ fn call_contract_without_value() {
             input: TransactionInput::new(contract_call_data),
             ..Default::default()
         },
-        Some(BlockNumberOrTag::Latest),
+        Some(BlockNumberOrTag::Latest.into()),
         None,
         None,
         &mut working_set,
@@ -42,6 +45,7 @@ fn call_contract_without_value() {
     assert_eq!(call_result.unwrap(), Bytes::from_str("0x").unwrap());
 
     let call_result = evm.get_call(
+        &QueryContext::default(),
         TransactionRequest {
             from: Some(signer.address()),
             to: Some(contract_address),
@@ -51,7 +55,7 @@ fn call_contract_without_value() {
             input: TransactionInput::new(contract.get_call_data().to_vec().into()),
             ..Default::default()
         },
-        Some(BlockNumberOrTag::Latest),
+        Some(BlockNumberOrTag::Latest.into()),
         None,
         None,
         &mut working_set,
@@ -68,7 +72,12 @@ fn call_contract_without_value() {
 fn test_state_change() {
     let (evm, mut working_set, signer) = init_evm();
 
-    let balance_1 = evm.get_balance(signer.address(), None, &mut working_set);
+    let balance_1 = evm.get_balance(
+        &QueryContext::default(),
+        signer.address(),
+        None,
+        &mut working_set,
+    );
 
     let random_address = Address::from_str("0x000000000000000000000000000000000000dead").unwrap();
 
@@ -83,6 +92,7 @@ fn test_state_change() {
     );
 
     let call_result = evm.get_call(
+        &QueryContext::default(),
         TransactionRequest {
             from: Some(signer.address()),
             to: Some(random_address),
@@ -91,7 +101,7 @@ fn test_state_change() {
             value: Some(U256::from(123134235)),
             ..Default::default()
         },
-        Some(BlockNumberOrTag::Latest),
+        Some(BlockNumberOrTag::Latest.into()),
         None,
         None,
         &mut working_set,
@@ -102,7 +112,12 @@ fn test_state_change() {
     evm.end_soft_confirmation_hook(&mut working_set);
     evm.finalize_hook(&[99u8; 32].into(), &mut working_set.accessory_state());
 
-    let balance_2 = evm.get_balance(signer.address(), None, &mut working_set);
+    let balance_2 = evm.get_balance(
+        &QueryContext::default(),
+        signer.address(),
+        None,
+        &mut working_set,
+    );
     assert_eq!(balance_1, balance_2);
 }
 
@@ -116,6 +131,7 @@ fn call_contract_with_value_transfer() {
     let contract_call_data = Bytes::from(contract.set_call_data(5).to_vec());
 
     let call_result = evm.get_call(
+        &QueryContext::default(),
         TransactionRequest {
             from: Some(signer.address()),
             to: Some(contract_address),
@@ -125,7 +141,7 @@ fn call_contract_with_value_transfer() {
             input: TransactionInput::new(contract_call_data),
             ..Default::default()
         },
-        Some(BlockNumberOrTag::Latest),
+        Some(BlockNumberOrTag::Latest.into()),
         None,
         None,
         &mut working_set,
@@ -146,6 +162,7 @@ fn call_contract_with_invalid_nonce() {
     let invalid_nonce = U64::from(100);
 
     let call_result = evm.get_call(
+        &QueryContext::default(),
         TransactionRequest {
             from: Some(signer.address()),
             to: Some(contract_address),
@@ -155,7 +172,7 @@ fn call_contract_with_invalid_nonce() {
             input: TransactionInput::new(contract_call_data.clone()),
             ..Default::default()
         },
-        Some(BlockNumberOrTag::Latest),
+        Some(BlockNumberOrTag::Latest.into()),
         None,
         None,
         &mut working_set,
@@ -166,6 +183,7 @@ fn call_contract_with_invalid_nonce() {
     let low_nonce = U64::from(2);
 
     let call_result = evm.get_call(
+        &QueryContext::default(),
         TransactionRequest {
             from: Some(signer.address()),
             to: Some(contract_address),
@@ -175,7 +193,7 @@ fn call_contract_with_invalid_nonce() {
             input: TransactionInput::new(contract_call_data),
             ..Default::default()
         },
-        Some(BlockNumberOrTag::Latest),
+        Some(BlockNumberOrTag::Latest.into()),
         None,
         None,
         &mut working_set,
@@ -192,6 +210,7 @@ fn call_to_nonexistent_contract() {
         Address::from_str("0x000000000000000000000000000000000000dead").unwrap();
 
     let call_result = evm.get_call(
+        &QueryContext::default(),
         TransactionRequest {
             from: Some(signer.address()),
             to: Some(nonexistent_contract_address),
@@ -203,7 +222,7 @@ fn call_to_nonexistent_contract() {
             },
             ..Default::default()
         },
-        Some(BlockNumberOrTag::Latest),
+        Some(BlockNumberOrTag::Latest.into()),
         None,
         None,
         &mut working_set,
@@ -224,6 +243,7 @@ fn call_with_high_gas_price() {
     let high_gas_price = U256::from(1000) * U256::from(10_000_000_000_000_000_000_i128); // A very high gas price
 
     let call_result = evm.get_call(
+        &QueryContext::default(),
         TransactionRequest {
             from: Some(signer.address()),
             to: Some(contract_address),
@@ -232,7 +252,7 @@ fn call_with_high_gas_price() {
             input: TransactionInput::new(contract_call_data),
             ..Default::default()
         },
-        Some(BlockNumberOrTag::Latest),
+        Some(BlockNumberOrTag::Latest.into()),
         None,
         None,
         &mut working_set,
@@ -346,8 +366,9 @@ fn eth_call_eip1559(
     };
 
     evm.get_call(
+        &QueryContext::default(),
         tx_req,
-        Some(BlockNumberOrTag::Latest),
+        Some(BlockNumberOrTag::Latest.into()),
         None,
         None,
         working_set,
@@ -382,11 +403,12 @@ fn gas_price_call_test() {
     // Test with low gas limit
     let tx_req_low_gas = base_tx_req();
     let result_low_gas = evm.get_call(
+        &QueryContext::default(),
         TransactionRequest {
             gas: Some(U256::from(21000)),
             ..tx_req_low_gas
         },
-        Some(BlockNumberOrTag::Latest),
+        Some(BlockNumberOrTag::Latest.into()),
         None,
         None,
         &mut working_set,
@@ -400,11 +422,12 @@ fn gas_price_call_test() {
 
     let tx_req_only_gas = base_tx_req();
     let result_only_gas = evm.get_call(
+        &QueryContext::default(),
         TransactionRequest {
             gas: Some(U256::from(250000)),
             ..tx_req_only_gas
         },
-        Some(BlockNumberOrTag::Latest),
+        Some(BlockNumberOrTag::Latest.into()),
         None,
         None,
         &mut working_set,
@@ -416,12 +439,13 @@ fn gas_price_call_test() {
     // Test with gas and gas_price specified - error
     let tx_req_gas_and_gas_price = base_tx_req();
     let result_gas_and_gas_price = evm.get_call(
+        &QueryContext::default(),
         TransactionRequest {
             gas: Some(U256::from(25000)),
             gas_price: Some(U256::from(20e9 as u64)),
             ..tx_req_gas_and_gas_price
         },
-        Some(BlockNumberOrTag::Latest),
+        Some(BlockNumberOrTag::Latest.into()),
         None,
         None,
         &mut working_set,
@@ -436,12 +460,13 @@ fn gas_price_call_test() {
     // Test with gas and gas_price specified - this time successful
     let tx_req_gas_and_gas_price = base_tx_req();
     let result_gas_and_gas_price = evm.get_call(
+        &QueryContext::default(),
         TransactionRequest {
             gas: Some(U256::from(250000)),
             gas_price: Some(U256::from(20e9 as u64)),
             ..tx_req_gas_and_gas_price
         },
-        Some(BlockNumberOrTag::Latest),
+        Some(BlockNumberOrTag::Latest.into()),
         None,
         None,
         &mut working_set,
@@ -453,12 +478,13 @@ fn gas_price_call_test() {
     // Test with max_fee_per_gas and max_priority_fee_per_gas specified
     let tx_req_fees = base_tx_req();
     let result_fees = evm.get_call(
+        &QueryContext::default(),
         TransactionRequest {
             max_fee_per_gas: Some(U256::from(30e9 as u64)),
             max_priority_fee_per_gas: Some(U256::from(10e9 as u64)),
             ..tx_req_fees
         },
-        Some(BlockNumberOrTag::Latest),
+        Some(BlockNumberOrTag::Latest.into()),
         None,
         None,
         &mut working_set,
@@ -470,11 +496,12 @@ fn gas_price_call_test() {
     // Test with extremely high gas price
     let tx_req_high_gas_price = base_tx_req();
     let result_high_gas_price = evm.get_call(
+        &QueryContext::default(),
         TransactionRequest {
             gas_price: Some(U256::from(1e12 as u64)),
             ..tx_req_high_gas_price
         },
-        Some(BlockNumberOrTag::Latest),
+        Some(BlockNumberOrTag::Latest.into()),
         None,
         None,
         &mut working_set,
@@ -486,12 +513,13 @@ fn gas_price_call_test() {
     // Test with extremely high max_fee_per_gas and max_priority_fee_per_gas
     let tx_req_high_fees = base_tx_req();
     let result_high_fees = evm.get_call(
+        &QueryContext::default(),
         TransactionRequest {
             max_fee_per_gas: Some(U256::from(1e12 as u64)),
             max_priority_fee_per_gas: Some(U256::from(500e9 as u64)),
             ..tx_req_high_fees
         },
-        Some(BlockNumberOrTag::Latest),
+        Some(BlockNumberOrTag::Latest.into()),
         None,
         None,
         &mut working_set,
@@ -500,3 +528,255 @@ fn gas_price_call_test() {
     assert!(result_high_fees.is_ok());
     working_set.unset_archival_version();
 }
+
+#[test]
+fn call_with_state_overrides() {
+    let (evm, mut working_set, signer) = init_evm();
+
+    let contract = SimpleStorageContract::default();
+    let contract_address = Address::from_str("0xeeb03d20dae810f52111b853b31c8be6f30f4cd3").unwrap();
+    let request = TransactionRequest {
+        from: Some(signer.address()),
+        to: Some(contract_address),
+        gas: Some(U256::from(100000)),
+        input: TransactionInput::new(contract.get_call_data().to_vec().into()),
+        ..Default::default()
+    };
+    let overrides = |overrides: serde_json::Value| {
+        serde_json::from_value(serde_json::json!({ contract_address.to_string(): overrides }))
+            .unwrap()
+    };
+
+    let stored = evm
+        .get_call(
+            &QueryContext::default(),
+            request.clone(),
+            Some(BlockNumberOrTag::Latest.into()),
+            None,
+            None,
+            &mut working_set,
+        )
+        .unwrap();
+    assert_ne!(stored, Bytes::from(U256::from(7).to_be_bytes_vec()));
+
+    let overridden = evm
+        .get_call(
+            &QueryContext::default(),
+            request.clone(),
+            Some(BlockNumberOrTag::Latest.into()),
+            Some(overrides(
+                serde_json::json!({ "stateDiff": { B256::ZERO.to_string(): "0x7" } }),
+            )),
+            None,
+            &mut working_set,
+        )
+        .unwrap();
+    assert_eq!(overridden, Bytes::from(U256::from(7).to_be_bytes_vec()));
+
+    let cleared = evm
+        .get_call(
+            &QueryContext::default(),
+            request.clone(),
+            Some(BlockNumberOrTag::Latest.into()),
+            Some(overrides(serde_json::json!({ "state": {} }))),
+            None,
+            &mut working_set,
+        )
+        .unwrap();
+    assert_eq!(cleared, Bytes::from(U256::ZERO.to_be_bytes_vec()));
+
+    let result = evm.get_call(
+        &QueryContext::default(),
+        request,
+        Some(BlockNumberOrTag::Latest.into()),
+        Some(overrides(
+            serde_json::json!({ "state": {}, "stateDiff": {} }),
+        )),
+        None,
+        &mut working_set,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn call_with_block_overrides() {
+    let (evm, mut working_set, signer) = init_evm();
+
+    // returns NUMBER, TIMESTAMP and BASEFEE
+    let block_context_reader =
+        Address::from_str("0xcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcdcd").unwrap();
+    let state_overrides = serde_json::from_value(serde_json::json!({
+        block_context_reader.to_string(): { "code": "0x43600052426020524860405260606000f3" },
+    }))
+    .unwrap();
+    let request = TransactionRequest {
+        from: Some(signer.address()),
+        to: Some(block_context_reader),
+        gas: Some(U256::from(100000)),
+        ..Default::default()
+    };
+    let block_overrides = serde_json::from_value(serde_json::json!({
+        "number": "0x64",
+        "time": "0x3e8",
+        "baseFee": "0x7",
+    }))
+    .unwrap();
+
+    let result = evm
+        .get_call(
+            &QueryContext::default(),
+            request.clone(),
+            Some(BlockNumberOrTag::Latest.into()),
+            Some(state_overrides),
+            Some(Box::new(block_overrides)),
+            &mut working_set,
+        )
+        .unwrap();
+    let expected: Vec<u8> = [100u64, 1000, 7]
+        .into_iter()
+        .flat_map(|value| U256::from(value).to_be_bytes_vec())
+        .collect();
+    assert_eq!(result, Bytes::from(expected));
+
+    let result = evm.get_call(
+        &QueryContext::default(),
+        request,
+        Some(BlockNumberOrTag::Latest.into()),
+        None,
+        Some(Box::new(
+            serde_json::from_value(serde_json::json!({ "difficulty": "0x1" })).unwrap(),
+        )),
+        &mut working_set,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn trace_call_with_state_overrides() {
+    let (evm, mut working_set, signer) = init_evm();
+
+    let contract = SimpleStorageContract::default();
+    let contract_address = Address::from_str("0xeeb03d20dae810f52111b853b31c8be6f30f4cd3").unwrap();
+    let request = TransactionRequest {
+        from: Some(signer.address()),
+        to: Some(contract_address),
+        gas: Some(U256::from(100000)),
+        input: TransactionInput::new(contract.get_call_data().to_vec().into()),
+        ..Default::default()
+    };
+    let opts = |overrides: serde_json::Value| {
+        serde_json::from_value::<GethDebugTracingCallOptions>(serde_json::json!({
+            "tracer": "callTracer",
+            "stateOverrides": { contract_address.to_string(): overrides },
+        }))
+        .unwrap()
+    };
+
+    let trace = evm
+        .trace_call(
+            &QueryContext::default(),
+            request.clone(),
+            Some(BlockNumberOrTag::Latest.into()),
+            Some(opts(
+                serde_json::json!({ "stateDiff": { B256::ZERO.to_string(): "0x7" } }),
+            )),
+            &mut working_set,
+        )
+        .unwrap();
+    let GethTrace::CallTracer(call_frame) = trace else {
+        panic!("Expected a call trace, got {:?}", trace);
+    };
+    assert_eq!(
+        call_frame.output,
+        Some(Bytes::from(U256::from(7).to_be_bytes_vec()))
+    );
+
+    let trace = evm.trace_call(
+        &QueryContext::default(),
+        request,
+        Some(BlockNumberOrTag::Latest.into()),
+        Some(opts(serde_json::json!({ "state": {}, "stateDiff": {} }))),
+        &mut working_set,
+    );
+    assert!(trace.is_err());
+}
+
+#[test]
+fn simulate_v1_chains_blocks_without_persisting() {
+    let (evm, mut working_set, signer) = init_evm();
+
+    let contract = SimpleStorageContract::default();
+    let contract_address = Address::from_str("0xeeb03d20dae810f52111b853b31c8be6f30f4cd3").unwrap();
+
+    let call = |input: Vec<u8>| TransactionRequest {
+        from: Some(signer.address()),
+        to: Some(contract_address),
+        gas: Some(U256::from(100000)),
+        input: TransactionInput::new(input.into()),
+        ..Default::default()
+    };
+
+    let payload = SimulatePayload {
+        block_state_calls: vec![
+            SimulateBlock {
+                calls: vec![call(contract.set_call_data(1000).to_vec())],
+                ..Default::default()
+            },
+            SimulateBlock {
+                calls: vec![call(contract.get_call_data().to_vec())],
+                ..Default::default()
+            },
+        ],
+        ..Default::default()
+    };
+
+    let blocks = evm
+        .simulate_v1(
+            &QueryContext::default(),
+            payload,
+            Some(BlockNumberOrTag::Latest.into()),
+            &mut working_set,
+        )
+        .unwrap();
+
+    assert_eq!(blocks.len(), 2);
+    assert_eq!(blocks[1].number, blocks[0].number + U64::from(1));
+    assert!(blocks[1].timestamp > blocks[0].timestamp);
+    assert_eq!(blocks[0].base_fee_per_gas, U256::ZERO);
+    assert!(blocks
+        .iter()
+        .all(|block| block.calls[0].status == U64::from(1)));
+    assert_eq!(
+        blocks[1].calls[0].return_data,
+        Bytes::from_str("0x00000000000000000000000000000000000000000000000000000000000003e8")
+            .unwrap()
+    );
+
+    // the simulated calls are not persisted
+    let call_result = evm.get_call(
+        &QueryContext::default(),
+        call(contract.get_call_data().to_vec()),
+        Some(BlockNumberOrTag::Latest.into()),
+        None,
+        None,
+        &mut working_set,
+    );
+    assert_eq!(
+        call_result.unwrap(),
+        Bytes::from_str("0x00000000000000000000000000000000000000000000000000000000000001de")
+            .unwrap()
+    );
+
+    let too_many_blocks = SimulatePayload {
+        block_state_calls: vec![SimulateBlock::default(); MAX_SIMULATE_BLOCKS + 1],
+        ..Default::default()
+    };
+    assert!(evm
+        .simulate_v1(
+            &QueryContext::default(),
+            too_many_blocks,
+            None,
+            &mut working_set
+        )
+        .is_err());
+}
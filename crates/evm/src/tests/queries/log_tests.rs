@@ -3,18 +3,18 @@ use std::str::FromStr;
 use alloy_primitives::FixedBytes;
 use hex::FromHex;
 use reth_primitives::constants::ETHEREUM_BLOCK_GAS_LIMIT;
-use reth_primitives::BlockNumberOrTag;
+use reth_primitives::{Address, BlockNumberOrTag};
 use revm::primitives::{B256, U256};
 use sov_modules_api::default_context::DefaultContext;
 use sov_modules_api::utils::generate_address;
-use sov_modules_api::{Context, Module, StateVecAccessor};
+use sov_modules_api::{Context, Module, StateMapAccessor, StateVecAccessor, WorkingSet};
 
 use crate::call::CallMessage;
 use crate::smart_contracts::LogsContract;
 use crate::tests::call_tests::{create_contract_message, get_evm_config, publish_event_message};
 use crate::tests::genesis_tests::get_evm;
 use crate::tests::queries::init_evm;
-use crate::{EthApiError, Filter, FilterBlockOption, FilterSet};
+use crate::{EthApiError, Filter, FilterBlockOption, FilterSet, LOG_BLOOM_SECTION_SIZE};
 
 type C = DefaultContext;
 
@@ -96,6 +96,7 @@ fn log_filter_test_at_block_hash() {
         evm.call(
             CallMessage {
                 txs: rlp_transcations,
+                encrypted: None,
             },
             &context,
             &mut working_set,
@@ -298,6 +299,7 @@ fn log_filter_test_with_range() {
         evm.call(
             CallMessage {
                 txs: rlp_transactions,
+                encrypted: None,
             },
             &context,
             &mut working_set,
@@ -349,6 +351,7 @@ fn log_filter_test_with_range() {
                     3,
                     "message".to_string(),
                 )],
+                encrypted: None,
             },
             &context,
             &mut working_set,
@@ -372,6 +375,47 @@ fn log_filter_test_with_range() {
     assert_eq!(rpc_logs.len(), 2);
 }
 
+#[test]
+fn log_filter_test_with_inverted_range() {
+    let (config, _, _) = get_evm_config(U256::from_str("100000000000000000000").unwrap(), None);
+
+    let (evm, mut working_set) = get_evm(&config);
+
+    for _ in 0..2 {
+        evm.begin_soft_confirmation_hook(
+            [5u8; 32],
+            1,
+            [42u8; 32],
+            &[10u8; 32],
+            1,
+            0,
+            &mut working_set,
+        );
+        evm.end_soft_confirmation_hook(&mut working_set);
+        evm.finalize_hook(&[99u8; 32].into(), &mut working_set.accessory_state());
+    }
+
+    let filter = Filter {
+        block_option: crate::FilterBlockOption::Range {
+            from_block: Some(BlockNumberOrTag::Number(2)),
+            to_block: Some(BlockNumberOrTag::Number(1)),
+        },
+        address: FilterSet::default(),
+        topics: [
+            FilterSet::default(),
+            FilterSet::default(),
+            FilterSet::default(),
+            FilterSet::default(),
+        ],
+    };
+
+    let rpc_logs = evm.eth_get_logs(filter, &mut working_set);
+    assert_eq!(
+        rpc_logs.err().unwrap().message(),
+        "invalid block range params".to_string()
+    );
+}
+
 #[test]
 fn test_log_limits() {
     // citrea::initialize_logging();
@@ -435,6 +479,7 @@ fn test_log_limits() {
         evm.call(
             CallMessage {
                 txs: rlp_transactions,
+                encrypted: None,
             },
             &context,
             &mut working_set,
@@ -510,3 +555,100 @@ fn test_log_limits() {
         "query exceeds max block range 100000".to_string()
     );
 }
+
+#[test]
+fn log_filter_test_with_logs_bloom_index() {
+    let (config, dev_signer, contract_addr) =
+        get_evm_config(U256::from_str("100000000000000000000").unwrap(), None);
+
+    let (evm, mut working_set) = get_evm(&config);
+
+    let produce_empty_blocks = |count: u64, working_set: &mut WorkingSet<C>| {
+        for _ in 0..count {
+            evm.begin_soft_confirmation_hook(
+                [5u8; 32],
+                1,
+                [42u8; 32],
+                &[10u8; 32],
+                1,
+                0,
+                working_set,
+            );
+            evm.end_soft_confirmation_hook(working_set);
+            evm.finalize_hook(&[99u8; 32].into(), &mut working_set.accessory_state());
+        }
+    };
+
+    // the logs are in the second section of the index, the first and third ones are empty
+    produce_empty_blocks(LOG_BLOOM_SECTION_SIZE + 10, &mut working_set);
+    evm.begin_soft_confirmation_hook(
+        [5u8; 32],
+        1,
+        [42u8; 32],
+        &[10u8; 32],
+        1,
+        0,
+        &mut working_set,
+    );
+    {
+        let sender_address = generate_address::<C>("sender");
+        let sequencer_address = generate_address::<C>("sequencer");
+        let context = C::new(sender_address, sequencer_address, 1);
+
+        let rlp_transactions = vec![
+            create_contract_message(&dev_signer, 0, LogsContract::default()),
+            publish_event_message(contract_addr, &dev_signer, 1, "hello".to_string()),
+        ];
+
+        evm.call(
+            CallMessage {
+                txs: rlp_transactions,
+                encrypted: None,
+            },
+            &context,
+            &mut working_set,
+        )
+        .unwrap();
+    }
+    evm.end_soft_confirmation_hook(&mut working_set);
+    evm.finalize_hook(&[99u8; 32].into(), &mut working_set.accessory_state());
+    let logs_block = evm.blocks.last(&mut working_set.accessory_state()).unwrap();
+    produce_empty_blocks(LOG_BLOOM_SECTION_SIZE + 10, &mut working_set);
+
+    let filter_by_address = |address: Address| Filter {
+        block_option: crate::FilterBlockOption::Range {
+            from_block: Some(BlockNumberOrTag::Earliest),
+            to_block: Some(BlockNumberOrTag::Latest),
+        },
+        address: FilterSet::from(address),
+        topics: [
+            FilterSet::default(),
+            FilterSet::default(),
+            FilterSet::default(),
+            FilterSet::default(),
+        ],
+    };
+
+    let rpc_logs = evm
+        .eth_get_logs(filter_by_address(contract_addr), &mut working_set)
+        .unwrap();
+    assert_eq!(rpc_logs.len(), 2);
+    assert!(rpc_logs
+        .iter()
+        .all(|log| log.block_hash == Some(logs_block.header.hash())));
+
+    let rpc_logs = evm
+        .eth_get_logs(filter_by_address(Address::random()), &mut working_set)
+        .unwrap();
+    assert!(rpc_logs.is_empty());
+
+    // blocks finalized before the index was added are checked by their header
+    for number in 0..LOG_BLOOM_SECTION_SIZE * 2 {
+        evm.block_log_blooms
+            .delete(&number, &mut working_set.accessory_state());
+    }
+    let rpc_logs = evm
+        .eth_get_logs(filter_by_address(contract_addr), &mut working_set)
+        .unwrap();
+    assert_eq!(rpc_logs.len(), 2);
+}
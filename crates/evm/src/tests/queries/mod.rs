@@ -84,7 +84,10 @@ fn init_evm() -> (Evm<C>, WorkingSet<C>, TestSigner) {
         ];
 
         evm.call(
-            CallMessage { txs: transactions },
+            CallMessage {
+                txs: transactions,
+                encrypted: None,
+            },
             &context,
             &mut working_set,
         )
@@ -121,7 +124,10 @@ fn init_evm() -> (Evm<C>, WorkingSet<C>, TestSigner) {
         ];
 
         evm.call(
-            CallMessage { txs: transactions },
+            CallMessage {
+                txs: transactions,
+                encrypted: None,
+            },
             &context,
             &mut working_set,
         )
@@ -156,7 +162,10 @@ fn init_evm() -> (Evm<C>, WorkingSet<C>, TestSigner) {
         ];
 
         evm.call(
-            CallMessage { txs: transactions },
+            CallMessage {
+                txs: transactions,
+                encrypted: None,
+            },
             &context,
             &mut working_set,
         )
@@ -209,6 +218,7 @@ pub fn init_evm_single_block() -> (Evm<C>, WorkingSet<C>, TestSigner) {
     evm.call(
         CallMessage {
             txs: vec![simple_payable_contract_tx],
+            encrypted: None,
         },
         &context,
         &mut working_set,
@@ -269,7 +279,10 @@ pub fn init_evm_with_caller_contract() -> (Evm<C>, WorkingSet<C>, TestSigner) {
         ];
 
         evm.call(
-            CallMessage { txs: transactions },
+            CallMessage {
+                txs: transactions,
+                encrypted: None,
+            },
             &context,
             &mut working_set,
         )
@@ -297,7 +310,10 @@ pub fn init_evm_with_caller_contract() -> (Evm<C>, WorkingSet<C>, TestSigner) {
         )];
 
         evm.call(
-            CallMessage { txs: transactions },
+            CallMessage {
+                txs: transactions,
+                encrypted: None,
+            },
             &context,
             &mut working_set,
         )
@@ -0,0 +1,89 @@
+use std::collections::BTreeMap;
+
+use reth_primitives::{keccak256, Address, B256, U256};
+
+use crate::{decode_state_diff, encode_state_diff, AccountDiff, StateDiffDecodeError};
+
+/// Diffs of two ERC-20 transfers followed by an AMM swap touching the same token.
+fn token_and_pair_diffs() -> Vec<AccountDiff> {
+    let token = Address::with_last_byte(1);
+    let pair = Address::with_last_byte(2);
+    let sender = Address::with_last_byte(3);
+
+    let balance_slot = |holder: Address| U256::from_be_bytes(keccak256(holder.into_word()).0);
+
+    vec![
+        AccountDiff {
+            address: sender,
+            nonce: Some(7),
+            balance: Some(U256::from(10u64.pow(18))),
+            ..Default::default()
+        },
+        AccountDiff {
+            address: token,
+            storage: BTreeMap::from([
+                (balance_slot(sender), U256::from(500)),
+                (balance_slot(pair), U256::from(1500)),
+            ]),
+            ..Default::default()
+        },
+        AccountDiff {
+            address: pair,
+            // reserves, block timestamp and cumulative prices are in consecutive slots
+            storage: BTreeMap::from([
+                (U256::from(8), U256::from(1500)),
+                (U256::from(9), U256::from(3000)),
+                (U256::from(10), U256::from(1_700_000_000)),
+                (U256::from(12), U256::MAX),
+                (U256::MAX, U256::from(1)),
+            ]),
+            ..Default::default()
+        },
+        AccountDiff {
+            address: token,
+            code_hash: Some(B256::with_last_byte(9)),
+            storage: BTreeMap::from([(balance_slot(sender), U256::ZERO)]),
+            ..Default::default()
+        },
+    ]
+}
+
+#[test]
+fn state_diff_roundtrip() {
+    let diffs = token_and_pair_diffs();
+    let encoded = encode_state_diff(&diffs);
+    assert_eq!(decode_state_diff(&encoded), Ok(diffs.clone()));
+
+    // Naive size: every account with its address, every slot with its full key and value
+    let naive_size: usize = diffs
+        .iter()
+        .map(|diff| {
+            20 + diff.nonce.map_or(0, |_| 8)
+                + diff.balance.map_or(0, |_| 32)
+                + diff.code_hash.map_or(0, |_| 32)
+                + 64 * diff.storage.len()
+        })
+        .sum();
+    assert!(encoded.len() * 2 < naive_size);
+
+    assert_eq!(decode_state_diff(&encode_state_diff(&[])), Ok(vec![]));
+}
+
+#[test]
+fn state_diff_rejects_invalid_encodings() {
+    let encoded = encode_state_diff(&token_and_pair_diffs());
+
+    assert_eq!(
+        decode_state_diff(&encoded[..encoded.len() - 1]),
+        Err(StateDiffDecodeError::UnexpectedEnd)
+    );
+    assert_eq!(
+        decode_state_diff(&[encoded.as_slice(), &[0]].concat()),
+        Err(StateDiffDecodeError::Invalid("trailing bytes"))
+    );
+    // one address in the dictionary, one diff referencing the second address
+    assert_eq!(
+        decode_state_diff(&[&[1u8][..], &[0; 20], &[1, 1, 0]].concat()),
+        Err(StateDiffDecodeError::UnknownAddress(1))
+    );
+}
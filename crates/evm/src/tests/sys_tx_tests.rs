@@ -1,24 +1,74 @@
 use std::str::FromStr;
 
-use reth_primitives::{b256, hex, Log};
+use borsh::BorshSerialize;
+use reth_primitives::{
+    address, b256, hex, Address, BlockNumberOrTag, Log, TransactionKind, B256, U64,
+};
 use reth_rpc_types::{TransactionInput, TransactionRequest};
 use revm::primitives::{Bytes, U256};
+use secp256k1::SecretKey;
 use sov_modules_api::default_context::DefaultContext;
 use sov_modules_api::utils::generate_address;
-use sov_modules_api::{Context, Module, StateMapAccessor, StateVecAccessor};
+use sov_modules_api::{Context, Module, StateMapAccessor, StateValueAccessor, StateVecAccessor};
 
 use crate::call::CallMessage;
 use crate::evm::primitive_types::Receipt;
-use crate::evm::system_contracts::L1BlockHashList;
+use crate::evm::system_contracts::{
+    Bridge, L1BlockHashList, StateRootAnchor, SystemContractProxy, SystemContractUpgrade,
+};
+use crate::evm::system_events::{create_system_transactions, SystemEvent};
 use crate::smart_contracts::BlockHashContract;
 use crate::tests::call_tests::{
     create_contract_message_with_fee, get_evm_config_starting_base_fee,
 };
 use crate::tests::genesis_tests::get_evm;
-use crate::{AccountData, SYSTEM_SIGNER};
+use crate::tests::test_signer::TestSigner;
+use crate::tests::DEFAULT_CHAIN_ID;
+use crate::{
+    AccountData, GovernanceAction, GovernanceConfig, L1BlockInfo, QueryContext,
+    SignedGovernanceAction, WithdrawalRateLimit, MAX_BRIDGE_OPERATORS, SYSTEM_SIGNER,
+};
 
 type C = DefaultContext;
 
+/// The L1BlockHashList system contract, owned by the system signer.
+fn l1_block_hash_list_account() -> AccountData {
+    AccountData::new(
+        L1BlockHashList::address(),
+        U256::ZERO,
+        Bytes::from_static(&hex!("608060405234801561001057600080fd5b50600436106100f55760003560e01c8063715018a611610097578063d269a03e11610066578063d269a03e14610229578063e30c39781461023c578063ee82ac5e1461024f578063f2fde38b1461026f57600080fd5b8063715018a6146101ce57806379ba5097146101d65780638da5cb5b146101de578063a91d8b3d1461020957600080fd5b806334cdf78d116100d357806334cdf78d146101555780634ffd344a1461017557806357e871e71461019857806361b207e2146101a157600080fd5b80630466efc4146100fa5780630e27bc111461012d5780631f57833314610142575b600080fd5b61011a61010836600461076a565b60009081526003602052604090205490565b6040519081526020015b60405180910390f35b61014061013b366004610783565b610282565b005b61014061015036600461076a565b610375565b61011a61016336600461076a565b60026020526000908152604090205481565b6101886101833660046107ee565b6103ea565b6040519015158152602001610124565b61011a60045481565b61011a6101af36600461076a565b6000908152600260209081526040808320548352600390915290205490565b610140610410565b610140610486565b6000546101f1906001600160a01b031681565b6040516001600160a01b039091168152602001610124565b61011a61021736600461076a565b60036020526000908152604090205481565b6101886102373660046107ee565b61054c565b6001546101f1906001600160a01b031681565b61011a61025d36600461076a565b60009081526002602052604090205490565b61014061027d366004610849565b61055b565b6000546001600160a01b031633146102b55760405162461bcd60e51b81526004016102ac90610879565b60405180910390fd5b60045460008190036102fb5760405162461bcd60e51b815260206004820152600f60248201526e139bdd081a5b9a5d1a585b1a5e9959608a1b60448201526064016102ac565b60008181526002602052604090208390556103178160016108a6565b60049081556000848152600360209081526040918290208590559154815190815291820185905281018390527f32eff959e2e8d1609edc4b39ccf75900aa6c1da5719f8432752963fdf008234f9060600160405180910390a1505050565b6000546001600160a01b0316331461039f5760405162461bcd60e51b81526004016102ac90610879565b600454156103e55760405162461bcd60e51b8152602060048201526013602482015272105b1c9958591e481a5b9a5d1a585b1a5e9959606a1b60448201526064016102ac565b600455565b60008581526002602052604081205461040690868686866105e1565b9695505050505050565b6000546001600160a01b0316331461043a5760405162461bcd60e51b81526004016102ac90610879565b600080546001600160a01b03191681556040805182815260208101929092527f8be0079c531659141344cd1fd0a4f28419497f9722a3daafe3b4186f6b6457e0910160405180910390a1565b6001546001600160a01b031633146104e05760405162461bcd60e51b815260206004820152601b60248201527f43616c6c6572206973206e6f742070656e64696e67206f776e6572000000000060448201526064016102ac565b60008054600180546001600160a01b03198084166001600160a01b038084169190911786559116909155604080519190921680825260208201939093527f8be0079c531659141344cd1fd0a4f28419497f9722a3daafe3b4186f6b6457e091015b60405180910390a150565b600061040686868686866105e1565b6000546001600160a01b031633146105855760405162461bcd60e51b81526004016102ac90610879565b600180546001600160a01b0319166001600160a01b038381169182179092556000546040805191909316815260208101919091527fed8889f560326eb138920d842192f0eb3dd22b4f139c87a2c57538e05bae12789101610541565b6000858152600360209081526040808320548151601f870184900484028101840190925285825291610634918891849190899089908190840183828082843760009201919091525089925061063f915050565b979650505050505050565b6000838514801561064e575081155b801561065957508251155b1561066657506001610675565b6106728584868561067d565b90505b949350505050565b60006020845161068d91906108c7565b1561069a57506000610675565b83516000036106ab57506000610675565b818560005b865181101561071a576106c46002846108c7565b6001036106e8576106e16106db8883016020015190565b83610727565b9150610701565b6106fe826106f98984016020015190565b610727565b91505b60019290921c916107136020826108a6565b90506106b0565b5090931495945050505050565b6000610733838361073c565b90505b92915050565b60008260005281602052602060006040600060025afa50602060006020600060025afa505060005192915050565b60006020828403121561077c57600080fd5b5035919050565b6000806040838503121561079657600080fd5b50508035926020909101359150565b60008083601f8401126107b757600080fd5b50813567ffffffffffffffff8111156107cf57600080fd5b6020830191508360208285010111156107e757600080fd5b9250929050565b60008060008060006080868803121561080657600080fd5b8535945060208601359350604086013567ffffffffffffffff81111561082b57600080fd5b610837888289016107a5565b96999598509660600135949350505050565b60006020828403121561085b57600080fd5b81356001600160a01b038116811461087257600080fd5b9392505050565b60208082526013908201527221b0b63632b91034b9903737ba1037bbb732b960691b604082015260600190565b8082018082111561073657634e487b7160e01b600052601160045260246000fd5b6000826108e457634e487b7160e01b600052601260045260246000fd5b50069056fea264697066735822122047f87b69f6257337195f178a4f126d7bc4b6097397b529282027d5c023c891e764736f6c63430008190033")),
+        [
+             (U256::from_be_slice(&hex!("0000000000000000000000000000000000000000000000000000000000000000")), U256::from_be_slice(SYSTEM_SIGNER.into_word().as_slice())),
+        ].into_iter().collect()
+    )
+}
+
+/// The StateRootAnchor system contract, owned by the system signer.
+fn state_root_anchor_account() -> AccountData {
+    AccountData::new(
+        StateRootAnchor::address(),
+        U256::ZERO,
+        Bytes::from_static(&hex!("346104a357600436106104a35760003560e01c80638da5cb5b1461009b578063e30c3978146100a7578063715018a6146100bf578063f2fde38b1461015857806379ba509714610201578063368b733e146102a157806381f16ba3146102cb578063d5f40886146100b3578063dfedea24146102ed578063c38019381461041f578063b633620c146104415780638e4717bc14610466576104a3565b60005460005260206000f35b60015460005260206000f35b60045460005260206000f35b3360005414610120577f08c379a000000000000000000000000000000000000000000000000000000000600052602060045260136024527f43616c6c6572206973206e6f74206f776e65720000000000000000000000000060445260646000fd5b600060005560005460005260006020527f8be0079c531659141344cd1fd0a4f28419497f9722a3daafe3b4186f6b6457e060406000a1005b602436106104a3576004358060a01c6104a35733600054146101cc577f08c379a000000000000000000000000000000000000000000000000000000000600052602060045260136024527f43616c6c6572206973206e6f74206f776e65720000000000000000000000000060445260646000fd5b806001556000546000526020527fed8889f560326eb138920d842192f0eb3dd22b4f139c87a2c57538e05bae127860406000a1005b3360015414610262577f08c379a0000000000000000000000000000000000000000000000000000000006000526020600452601b6024527f43616c6c6572206973206e6f742070656e64696e67206f776e6572000000000060445260646000fd5b60005460015460005560006001556000526001546020527f8be0079c531659141344cd1fd0a4f28419497f9722a3daafe3b4186f6b6457e060406000a1005b602436106104a3576004356000526002602052604060002080546000526001015460205260406000f35b602436106104a357600435600052600360205260406000205460005260206000f35b606436106104a3573360005414610356577f08c379a000000000000000000000000000000000000000000000000000000000600052602060045260136024527f43616c6c6572206973206e6f74206f776e65720000000000000000000000000060445260646000fd5b600454600435116103b9577f08c379a000000000000000000000000000000000000000000000000000000000600052602060045260166024527f426c6f636b20616c726561647920616e63686f7265640000000000000000000060445260646000fd5b60043560005260026020526040600020602435815560443590600101556004356024356000526003602052604060002055600435600455606060046000377f4f8fbc9282f25dd6c66518b88e9d6ad78690a081d485ce8966fba98dcb21245a60606000a1005b602436106104a357600435600052600260205260406000205460005260206000f35b602436106104a357600435600052600260205260406000206001015460005260206000f35b604436106104a35760043560005260036020526040600020548015610498576004540360243581111560005260206000f35b600060005260206000f35b600080fd")),
+        [
+             (U256::from_be_slice(&hex!("0000000000000000000000000000000000000000000000000000000000000000")), U256::from_be_slice(SYSTEM_SIGNER.into_word().as_slice())),
+        ].into_iter().collect()
+    )
+}
+
+/// SystemContractProxy deployed at `proxy` pointing to `implementation`, the system signer
+/// owning the contract behind it.
+fn system_contract_proxy_account(proxy: Address, implementation: Address) -> AccountData {
+    AccountData::new(
+        proxy,
+        U256::ZERO,
+        Bytes::from_static(&hex!("361561025e57600436106102165760003560e01c80634f1ef2861461008c5780635c60da1b146100395780630b68e6131461006957610216565b346102da577f360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc5460005260206000f35b346102da5773deaddeaddeaddeaddeaddeaddeaddeaddeaddead60005260206000f35b604436106102da576004358060a01c6102da576024358067ffffffffffffffff106102da576004018060200136106102da5780358067ffffffffffffffff106102da5780826020010136106102da573373deaddeaddeaddeaddeaddeaddeaddeaddeaddead1461014e577f08c379a0000000000000000000000000000000000000000000000000000000006000526020600452601b6024527f43616c6c6572206973206e6f742073797374656d207369676e6572000000000060445260646000fd5b823b15156101ae577f08c379a000000000000000000000000000000000000000000000000000000000600052602060045260206024527f496d706c656d656e746174696f6e206973206e6f74206120636f6e747261637460445260646000fd5b827f360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc55827fbc7cd75a20ee27fd9adebab32041f755214dbc6bffa90cc0225b39da2e5c2d3b600080a28015610214578091602001600037600080916000845af415610254575b005b36600080376000803660007f360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc545af43d6000803e15610254573d6000f35b3d6000803e3d6000fd5b7f08c379a000000000000000000000000000000000000000000000000000000000600052602060045260256024527f50726f787920646f6573206e6f742061636365707420706c61696e207472616e6044527f736665727300000000000000000000000000000000000000000000000000000060645260846000fd5b600080fd")),
+        [
+             (U256::from_be_slice(&hex!("0000000000000000000000000000000000000000000000000000000000000000")), U256::from_be_slice(SYSTEM_SIGNER.into_word().as_slice())),
+             (U256::from_be_slice(&hex!("360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc")), U256::from_be_slice(implementation.into_word().as_slice())),
+        ].into_iter().collect()
+    )
+}
+
 #[test]
 fn test_sys_l1blockhashlist() {
     let (mut config, dev_signer, _) =
@@ -26,14 +76,7 @@ fn test_sys_l1blockhashlist() {
 
     let l1_block_hash_list = L1BlockHashList::default();
 
-    config.data.push(AccountData::new(
-        L1BlockHashList::address(),
-        U256::ZERO,
-        Bytes::from_static(&hex!("608060405234801561001057600080fd5b50600436106100f55760003560e01c8063715018a611610097578063d269a03e11610066578063d269a03e14610229578063e30c39781461023c578063ee82ac5e1461024f578063f2fde38b1461026f57600080fd5b8063715018a6146101ce57806379ba5097146101d65780638da5cb5b146101de578063a91d8b3d1461020957600080fd5b806334cdf78d116100d357806334cdf78d146101555780634ffd344a1461017557806357e871e71461019857806361b207e2146101a157600080fd5b80630466efc4146100fa5780630e27bc111461012d5780631f57833314610142575b600080fd5b61011a61010836600461076a565b60009081526003602052604090205490565b6040519081526020015b60405180910390f35b61014061013b366004610783565b610282565b005b61014061015036600461076a565b610375565b61011a61016336600461076a565b60026020526000908152604090205481565b6101886101833660046107ee565b6103ea565b6040519015158152602001610124565b61011a60045481565b61011a6101af36600461076a565b6000908152600260209081526040808320548352600390915290205490565b610140610410565b610140610486565b6000546101f1906001600160a01b031681565b6040516001600160a01b039091168152602001610124565b61011a61021736600461076a565b60036020526000908152604090205481565b6101886102373660046107ee565b61054c565b6001546101f1906001600160a01b031681565b61011a61025d36600461076a565b60009081526002602052604090205490565b61014061027d366004610849565b61055b565b6000546001600160a01b031633146102b55760405162461bcd60e51b81526004016102ac90610879565b60405180910390fd5b60045460008190036102fb5760405162461bcd60e51b815260206004820152600f60248201526e139bdd081a5b9a5d1a585b1a5e9959608a1b60448201526064016102ac565b60008181526002602052604090208390556103178160016108a6565b60049081556000848152600360209081526040918290208590559154815190815291820185905281018390527f32eff959e2e8d1609edc4b39ccf75900aa6c1da5719f8432752963fdf008234f9060600160405180910390a1505050565b6000546001600160a01b0316331461039f5760405162461bcd60e51b81526004016102ac90610879565b600454156103e55760405162461bcd60e51b8152602060048201526013602482015272105b1c9958591e481a5b9a5d1a585b1a5e9959606a1b60448201526064016102ac565b600455565b60008581526002602052604081205461040690868686866105e1565b9695505050505050565b6000546001600160a01b0316331461043a5760405162461bcd60e51b81526004016102ac90610879565b600080546001600160a01b03191681556040805182815260208101929092527f8be0079c531659141344cd1fd0a4f28419497f9722a3daafe3b4186f6b6457e0910160405180910390a1565b6001546001600160a01b031633146104e05760405162461bcd60e51b815260206004820152601b60248201527f43616c6c6572206973206e6f742070656e64696e67206f776e6572000000000060448201526064016102ac565b60008054600180546001600160a01b03198084166001600160a01b038084169190911786559116909155604080519190921680825260208201939093527f8be0079c531659141344cd1fd0a4f28419497f9722a3daafe3b4186f6b6457e091015b60405180910390a150565b600061040686868686866105e1565b6000546001600160a01b031633146105855760405162461bcd60e51b81526004016102ac90610879565b600180546001600160a01b0319166001600160a01b038381169182179092556000546040805191909316815260208101919091527fed8889f560326eb138920d842192f0eb3dd22b4f139c87a2c57538e05bae12789101610541565b6000858152600360209081526040808320548151601f870184900484028101840190925285825291610634918891849190899089908190840183828082843760009201919091525089925061063f915050565b979650505050505050565b6000838514801561064e575081155b801561065957508251155b1561066657506001610675565b6106728584868561067d565b90505b949350505050565b60006020845161068d91906108c7565b1561069a57506000610675565b83516000036106ab57506000610675565b818560005b865181101561071a576106c46002846108c7565b6001036106e8576106e16106db8883016020015190565b83610727565b9150610701565b6106fe826106f98984016020015190565b610727565b91505b60019290921c916107136020826108a6565b90506106b0565b5090931495945050505050565b6000610733838361073c565b90505b92915050565b60008260005281602052602060006040600060025afa50602060006020600060025afa505060005192915050565b60006020828403121561077c57600080fd5b5035919050565b6000806040838503121561079657600080fd5b50508035926020909101359150565b60008083601f8401126107b757600080fd5b50813567ffffffffffffffff8111156107cf57600080fd5b6020830191508360208285010111156107e757600080fd5b9250929050565b60008060008060006080868803121561080657600080fd5b8535945060208601359350604086013567ffffffffffffffff81111561082b57600080fd5b610837888289016107a5565b96999598509660600135949350505050565b60006020828403121561085b57600080fd5b81356001600160a01b038116811461087257600080fd5b9392505050565b60208082526013908201527221b0b63632b91034b9903737ba1037bbb732b960691b604082015260600190565b8082018082111561073657634e487b7160e01b600052601160045260246000fd5b6000826108e457634e487b7160e01b600052601260045260246000fd5b50069056fea264697066735822122047f87b69f6257337195f178a4f126d7bc4b6097397b529282027d5c023c891e764736f6c63430008190033")),
-        [
-             (U256::from_be_slice(&hex!("0000000000000000000000000000000000000000000000000000000000000000")), U256::from_be_slice(SYSTEM_SIGNER.into_word().as_slice())),
-        ].into_iter().collect()
-    ));
+    config.data.push(l1_block_hash_list_account());
 
     let (evm, mut working_set) = get_evm(&config);
 
@@ -84,6 +127,7 @@ fn test_sys_l1blockhashlist() {
 
     let hash = evm
         .get_call(
+            &QueryContext::default(),
             TransactionRequest {
                 to: Some(L1BlockHashList::address()),
                 input: TransactionInput::new(l1_block_hash_list.get_block_hash(1).to_vec().into()),
@@ -98,6 +142,7 @@ fn test_sys_l1blockhashlist() {
 
     let merkle_root = evm
         .get_call(
+            &QueryContext::default(),
             TransactionRequest {
                 to: Some(L1BlockHashList::address()),
                 input: TransactionInput::new(
@@ -139,6 +184,7 @@ fn test_sys_l1blockhashlist() {
         evm.call(
             CallMessage {
                 txs: vec![deploy_message],
+                encrypted: None,
             },
             &context,
             &mut working_set,
@@ -203,6 +249,7 @@ fn test_sys_l1blockhashlist() {
 
     let hash = evm
         .get_call(
+            &QueryContext::default(),
             TransactionRequest {
                 to: Some(L1BlockHashList::address()),
                 input: TransactionInput::new(l1_block_hash_list.get_block_hash(2).to_vec().into()),
@@ -217,6 +264,7 @@ fn test_sys_l1blockhashlist() {
 
     let merkle_root = evm
         .get_call(
+            &QueryContext::default(),
             TransactionRequest {
                 to: Some(L1BlockHashList::address()),
                 input: TransactionInput::new(
@@ -236,4 +284,842 @@ fn test_sys_l1blockhashlist() {
 
     assert_eq!(hash.as_ref(), &[2u8; 32]);
     assert_eq!(merkle_root.as_ref(), &[3u8; 32]);
+
+    let next_block_number = evm
+        .get_call(
+            &QueryContext::default(),
+            TransactionRequest {
+                to: Some(L1BlockHashList::address()),
+                input: TransactionInput::new(l1_block_hash_list.block_number().to_vec().into()),
+                ..Default::default()
+            },
+            None,
+            None,
+            None,
+            &mut working_set,
+        )
+        .unwrap();
+    assert_eq!(U256::from_be_slice(&next_block_number), U256::from(3));
+
+    assert_eq!(
+        evm.get_l1_block_info(
+            &QueryContext::default(),
+            BlockNumberOrTag::Latest,
+            &mut working_set
+        ),
+        Ok(Some(L1BlockInfo {
+            l2_height: U64::from(2),
+            l1_height: U64::from(2),
+            l1_hash: B256::from([2u8; 32]),
+            l1_timestamp: None,
+        }))
+    );
+    assert_eq!(
+        evm.get_l1_block_info(
+            &QueryContext::default(),
+            BlockNumberOrTag::Number(3),
+            &mut working_set
+        ),
+        Ok(None)
+    );
+    assert_eq!(
+        evm.get_l1_block_info(
+            &QueryContext::default(),
+            BlockNumberOrTag::Number(1),
+            &mut working_set
+        ),
+        Ok(Some(L1BlockInfo {
+            l2_height: U64::from(1),
+            l1_height: U64::from(1),
+            l1_hash: B256::from([1u8; 32]),
+            l1_timestamp: None,
+        }))
+    );
+    // No L1 block is recorded in genesis
+    assert_eq!(
+        evm.get_l1_block_info(
+            &QueryContext::default(),
+            BlockNumberOrTag::Earliest,
+            &mut working_set
+        ),
+        Ok(None)
+    );
+}
+
+#[test]
+fn test_system_txs_dont_consume_block_gas_limit() {
+    // the block gas limit only fits the user transaction
+    let (mut config, dev_signer, _) =
+        get_evm_config_starting_base_fee(U256::from_str("1000000").unwrap(), Some(1_000_000), 1);
+    config.data.push(l1_block_hash_list_account());
+
+    let (evm, mut working_set) = get_evm(&config);
+
+    // New L1 block, the block starts with a system transaction
+    evm.begin_soft_confirmation_hook(
+        [2u8; 32],
+        2,
+        [3u8; 32],
+        &[10u8; 32],
+        1,
+        42,
+        &mut working_set,
+    );
+    {
+        let sender_address = generate_address::<C>("sender");
+        let sequencer_address = generate_address::<C>("sequencer");
+        let context = C::new(sender_address, sequencer_address, 1);
+
+        let deploy_message =
+            create_contract_message_with_fee(&dev_signer, 0, BlockHashContract::default(), 1);
+
+        evm.call(
+            CallMessage {
+                txs: vec![deploy_message],
+                encrypted: None,
+            },
+            &context,
+            &mut working_set,
+        )
+        .unwrap();
+    }
+    evm.end_soft_confirmation_hook(&mut working_set);
+    evm.finalize_hook(&[99u8; 32].into(), &mut working_set.accessory_state());
+
+    let receipts: Vec<_> = evm
+        .receipts
+        .iter(&mut working_set.accessory_state())
+        .collect();
+    // 2 system transactions of the first block, the system transaction
+    // and the user transaction of the second block
+    assert_eq!(receipts.len(), 4);
+    assert!(receipts[3].receipt.success);
+    assert_eq!(
+        receipts[3].receipt.cumulative_gas_used,
+        receipts[2].gas_used + receipts[3].gas_used
+    );
+}
+
+#[test]
+fn test_system_tx_gas_limit() {
+    let (mut config, _, _) =
+        get_evm_config_starting_base_fee(U256::from_str("1000000").unwrap(), None, 1);
+    config.data.push(l1_block_hash_list_account());
+    // only fits the first system transaction of the first block
+    config.system_tx_gas_limit = Some(1_000_000);
+
+    let (evm, mut working_set) = get_evm(&config);
+
+    assert_eq!(
+        evm.system_tx_gas_limit.get(&mut working_set),
+        Some(1_000_000)
+    );
+    let receipts: Vec<_> = evm
+        .receipts
+        .iter(&mut working_set.accessory_state())
+        .collect();
+    assert_eq!(receipts.len(), 1);
+
+    let system_account = evm.accounts.get(&SYSTEM_SIGNER, &mut working_set).unwrap();
+    assert_eq!(system_account.info.nonce, 1);
+}
+
+#[test]
+fn test_system_events_over_gas_limit_are_deferred() {
+    let (mut config, _, _) =
+        get_evm_config_starting_base_fee(U256::from_str("1000000").unwrap(), None, 1);
+    config.data.push(l1_block_hash_list_account());
+    // only fits one of the two system transactions of the first block
+    config.system_tx_gas_limit = Some(1_000_000);
+
+    let (evm, mut working_set) = get_evm(&config);
+
+    assert_eq!(
+        evm.deferred_system_events.get(&mut working_set),
+        Some(vec![SystemEvent::L1BlockHashSetBlockInfo(
+            [1u8; 32], [2u8; 32]
+        )])
+    );
+    // the L1 block hash list is initialized, but the L1 block isn't recorded yet
+    assert_eq!(next_l1_block_number(&evm, &mut working_set), 1);
+
+    // the L1 block info of the first block is set in the next block, in the same L1 block
+    evm.begin_soft_confirmation_hook(
+        [1u8; 32],
+        1,
+        [2u8; 32],
+        &[10u8; 32],
+        1,
+        42,
+        &mut working_set,
+    );
+    evm.end_soft_confirmation_hook(&mut working_set);
+    evm.finalize_hook(&[99u8; 32].into(), &mut working_set.accessory_state());
+
+    assert_eq!(evm.deferred_system_events.get(&mut working_set), None);
+    let receipts: Vec<_> = evm
+        .receipts
+        .iter(&mut working_set.accessory_state())
+        .collect();
+    assert_eq!(receipts.len(), 2);
+    assert!(receipts.iter().all(|receipt| receipt.receipt.success));
+
+    let system_account = evm.accounts.get(&SYSTEM_SIGNER, &mut working_set).unwrap();
+    assert_eq!(system_account.info.nonce, 2);
+    assert_eq!(next_l1_block_number(&evm, &mut working_set), 2);
+}
+
+#[test]
+fn test_deferred_system_events_of_missing_contracts_are_dropped() {
+    let (mut config, _, _) =
+        get_evm_config_starting_base_fee(U256::from_str("1000000").unwrap(), None, 1);
+    config.data.push(l1_block_hash_list_account());
+
+    let (evm, mut working_set) = get_evm(&config);
+    let system_nonce = |working_set: &mut sov_modules_api::WorkingSet<C>| {
+        let system_account = evm.accounts.get(&SYSTEM_SIGNER, working_set).unwrap();
+        system_account.info.nonce
+    };
+    let nonce = system_nonce(&mut working_set);
+
+    // the deferred rate limit is the only system event of the next block, in the same L1 block,
+    // and there is no Bridge to call
+    evm.deferred_system_events.set(
+        &vec![SystemEvent::BridgeSetWithdrawalRateLimit(1, 1)],
+        &mut working_set,
+    );
+    evm.begin_soft_confirmation_hook(
+        [1u8; 32],
+        1,
+        [2u8; 32],
+        &[10u8; 32],
+        1,
+        42,
+        &mut working_set,
+    );
+    evm.end_soft_confirmation_hook(&mut working_set);
+    evm.finalize_hook(&[99u8; 32].into(), &mut working_set.accessory_state());
+
+    assert_eq!(evm.deferred_system_events.get(&mut working_set), None);
+    assert_eq!(system_nonce(&mut working_set), nonce);
+}
+
+/// Returns the number of the next L1 block the L1BlockHashList system contract records.
+fn next_l1_block_number(
+    evm: &crate::Evm<C>,
+    working_set: &mut sov_modules_api::WorkingSet<C>,
+) -> u64 {
+    evm.accounts
+        .get(&L1BlockHashList::address(), working_set)
+        .and_then(|account| {
+            account
+                .storage
+                .get(&U256::from(L1BlockHashList::BLOCK_NUMBER_SLOT), working_set)
+        })
+        .unwrap_or_default()
+        .saturating_to()
+}
+
+#[test]
+fn test_system_tx_gas_limit_fits_system_events() {
+    let (mut config, _, _) =
+        get_evm_config_starting_base_fee(U256::from_str("1000000").unwrap(), None, 1);
+    config.system_tx_gas_limit = Some(999_999);
+
+    let tmpdir = tempfile::tempdir().unwrap();
+    let storage = sov_prover_storage_manager::new_orphan_storage(tmpdir.path()).unwrap();
+    let mut working_set = sov_modules_api::WorkingSet::<C>::new(storage);
+    assert!(crate::Evm::<C>::default()
+        .genesis(&config, &mut working_set)
+        .is_err());
+}
+
+#[test]
+fn test_system_contract_upgrade_transactions() {
+    let proxy = StateRootAnchor::address();
+    let code = Bytes::from_static(&hex!("6080604052"));
+    let call_data = Bytes::from_static(&hex!("12345678"));
+
+    let txs = create_system_transactions(
+        [
+            SystemEvent::UpgradeSystemContract(proxy, code.clone(), call_data.clone()),
+            SystemEvent::L1BlockHashInitialize(1),
+        ],
+        5,
+        DEFAULT_CHAIN_ID,
+    );
+
+    assert_eq!(txs.len(), 3);
+    assert!(txs.iter().all(|tx| tx.signer() == SYSTEM_SIGNER));
+    assert_eq!(
+        txs.iter().map(|tx| tx.nonce()).collect::<Vec<_>>(),
+        [5, 6, 7]
+    );
+
+    // the new implementation is deployed first
+    assert_eq!(txs[0].kind(), &TransactionKind::Create);
+    assert_eq!(txs[0].input(), &code);
+
+    // then the proxy is upgraded to the address of the deployment
+    assert_eq!(txs[1].kind(), &TransactionKind::Call(proxy));
+    assert_eq!(
+        txs[1].input().to_vec(),
+        SystemContractProxy::default()
+            .upgrade_to_and_call(SYSTEM_SIGNER.create(5), call_data.to_vec().into())
+            .to_vec()
+    );
+
+    assert_eq!(
+        txs[2].kind(),
+        &TransactionKind::Call(L1BlockHashList::address())
+    );
+}
+
+#[test]
+fn test_governance_sets_withdrawal_rate_limit() {
+    let (mut config, _, _) =
+        get_evm_config_starting_base_fee(U256::from_str("1000000").unwrap(), None, 1);
+    config.data.push(l1_block_hash_list_account());
+    // the accounting is tested with the contract, only the system transaction is checked here
+    config.data.push(AccountData::new(
+        Bridge::address(),
+        U256::ZERO,
+        Bytes::from_static(&hex!("00")),
+        Default::default(),
+    ));
+    let governance_key = B256::from([1u8; 32]);
+    let governance = TestSigner::new(SecretKey::from_slice(governance_key.as_slice()).unwrap());
+    config.governance = Some(GovernanceConfig {
+        address: governance.address(),
+        max_freeze_blocks: 1,
+    });
+
+    let (evm, mut working_set) = get_evm(&config);
+
+    let set_limit = |nonce: u64, limit: Option<WithdrawalRateLimit>| {
+        SignedGovernanceAction::sign(
+            governance_key,
+            DEFAULT_CHAIN_ID,
+            nonce,
+            GovernanceAction::SetWithdrawalRateLimit { limit },
+        )
+        .unwrap()
+    };
+    let limit = WithdrawalRateLimit {
+        max_volume: 30_000_000_000_000_000_000,
+        window_l1_blocks: 6,
+    };
+
+    evm.begin_soft_confirmation_hook(
+        [1u8; 32],
+        1,
+        [2u8; 32],
+        &[10u8; 32],
+        1,
+        42,
+        &mut working_set,
+    );
+    evm.apply_da_governance_actions(
+        &[
+            // Empty window
+            set_limit(
+                0,
+                Some(WithdrawalRateLimit {
+                    window_l1_blocks: 0,
+                    ..limit
+                }),
+            ),
+            // Nothing can be withdrawn
+            set_limit(
+                0,
+                Some(WithdrawalRateLimit {
+                    max_volume: 0,
+                    ..limit
+                }),
+            ),
+            set_limit(0, Some(limit)),
+        ]
+        .map(|action| action.try_to_vec().unwrap()),
+        &mut working_set,
+    );
+    evm.end_soft_confirmation_hook(&mut working_set);
+    evm.finalize_hook(&[99u8; 32].into(), &mut working_set.accessory_state());
+
+    assert_eq!(evm.governance_nonce(&mut working_set), 1);
+    assert_eq!(
+        evm.deferred_system_events.get(&mut working_set),
+        Some(vec![SystemEvent::BridgeSetWithdrawalRateLimit(
+            limit.max_volume,
+            limit.window_l1_blocks
+        )])
+    );
+
+    // the system signer sets the limit at the beginning of the next block, and governance
+    // lifts it
+    evm.begin_soft_confirmation_hook(
+        [1u8; 32],
+        1,
+        [2u8; 32],
+        &[99u8; 32],
+        1,
+        42,
+        &mut working_set,
+    );
+    evm.apply_da_governance_actions(
+        &[set_limit(1, None).try_to_vec().unwrap()],
+        &mut working_set,
+    );
+    evm.end_soft_confirmation_hook(&mut working_set);
+    evm.finalize_hook(&[100u8; 32].into(), &mut working_set.accessory_state());
+
+    let transaction = evm
+        .transactions
+        .iter(&mut working_set.accessory_state())
+        .last()
+        .unwrap();
+    assert_eq!(transaction.signer, SYSTEM_SIGNER);
+    assert_eq!(
+        transaction.signed_transaction.kind(),
+        &TransactionKind::Call(Bridge::address())
+    );
+    assert_eq!(
+        transaction.signed_transaction.input().to_vec(),
+        Bridge::default()
+            .set_withdrawal_rate_limit(limit.max_volume, limit.window_l1_blocks)
+            .to_vec()
+    );
+    let receipt = evm
+        .receipts
+        .iter(&mut working_set.accessory_state())
+        .last()
+        .unwrap();
+    assert!(receipt.receipt.success);
+
+    assert_eq!(evm.governance_nonce(&mut working_set), 2);
+    assert_eq!(
+        evm.deferred_system_events.get(&mut working_set),
+        Some(vec![SystemEvent::BridgeSetWithdrawalRateLimit(0, 0)])
+    );
+}
+
+#[test]
+fn test_governance_sets_bridge_operators() {
+    let (mut config, _, _) =
+        get_evm_config_starting_base_fee(U256::from_str("1000000").unwrap(), None, 1);
+    config.data.push(l1_block_hash_list_account());
+    // the operator accounting is tested with the contract, only the system transaction is
+    // checked here
+    config.data.push(AccountData::new(
+        Bridge::address(),
+        U256::ZERO,
+        Bytes::from_static(&hex!("00")),
+        Default::default(),
+    ));
+    let governance_key = B256::from([1u8; 32]);
+    let governance = TestSigner::new(SecretKey::from_slice(governance_key.as_slice()).unwrap());
+    config.governance = Some(GovernanceConfig {
+        address: governance.address(),
+        max_freeze_blocks: 1,
+    });
+
+    let (evm, mut working_set) = get_evm(&config);
+
+    let set_operators = |operators: Vec<Address>, threshold: u64| {
+        SignedGovernanceAction::sign(
+            governance_key,
+            DEFAULT_CHAIN_ID,
+            0,
+            GovernanceAction::SetBridgeOperators {
+                operators,
+                threshold,
+            },
+        )
+        .unwrap()
+    };
+    let operators = vec![Address::repeat_byte(1), Address::repeat_byte(2)];
+
+    evm.begin_soft_confirmation_hook(
+        [1u8; 32],
+        1,
+        [2u8; 32],
+        &[10u8; 32],
+        1,
+        42,
+        &mut working_set,
+    );
+    evm.apply_da_governance_actions(
+        &[
+            // Threshold over the number of operators
+            set_operators(operators.clone(), 3),
+            // Duplicate operator
+            set_operators(vec![operators[0], operators[0]], 1),
+            // Zero address
+            set_operators(vec![operators[0], Address::ZERO], 1),
+            // Too many operators
+            set_operators(
+                (1..=MAX_BRIDGE_OPERATORS as u8 + 1)
+                    .map(Address::with_last_byte)
+                    .collect(),
+                1,
+            ),
+            set_operators(operators.clone(), 2),
+        ]
+        .map(|action| action.try_to_vec().unwrap()),
+        &mut working_set,
+    );
+    evm.end_soft_confirmation_hook(&mut working_set);
+    evm.finalize_hook(&[99u8; 32].into(), &mut working_set.accessory_state());
+
+    assert_eq!(evm.governance_nonce(&mut working_set), 1);
+    assert_eq!(
+        evm.deferred_system_events.get(&mut working_set),
+        Some(vec![SystemEvent::BridgeSetOperators(operators.clone(), 2)])
+    );
+
+    // the system signer sets the operators at the beginning of the next block
+    evm.begin_soft_confirmation_hook(
+        [1u8; 32],
+        1,
+        [2u8; 32],
+        &[99u8; 32],
+        1,
+        42,
+        &mut working_set,
+    );
+    evm.end_soft_confirmation_hook(&mut working_set);
+    evm.finalize_hook(&[100u8; 32].into(), &mut working_set.accessory_state());
+
+    let transaction = evm
+        .transactions
+        .iter(&mut working_set.accessory_state())
+        .last()
+        .unwrap();
+    assert_eq!(transaction.signer, SYSTEM_SIGNER);
+    assert_eq!(
+        transaction.signed_transaction.kind(),
+        &TransactionKind::Call(Bridge::address())
+    );
+    assert_eq!(
+        transaction.signed_transaction.input().to_vec(),
+        Bridge::default().set_operators(&operators, 2).to_vec()
+    );
+    assert_eq!(evm.deferred_system_events.get(&mut working_set), None);
+}
+
+#[test]
+fn test_governance_withdrawal_rate_limit_requires_bridge() {
+    let (mut config, _, _) =
+        get_evm_config_starting_base_fee(U256::from_str("1000000").unwrap(), None, 1);
+    config.data.push(l1_block_hash_list_account());
+    // the Bridge account exists, but its contract isn't deployed
+    config.data.push(AccountData::new(
+        Bridge::address(),
+        U256::from(1),
+        Bytes::new(),
+        Default::default(),
+    ));
+    let governance_key = B256::from([1u8; 32]);
+    let governance = TestSigner::new(SecretKey::from_slice(governance_key.as_slice()).unwrap());
+    config.governance = Some(GovernanceConfig {
+        address: governance.address(),
+        max_freeze_blocks: 1,
+    });
+
+    let (evm, mut working_set) = get_evm(&config);
+
+    evm.begin_soft_confirmation_hook(
+        [1u8; 32],
+        1,
+        [2u8; 32],
+        &[10u8; 32],
+        1,
+        42,
+        &mut working_set,
+    );
+    evm.apply_da_governance_actions(
+        &[SignedGovernanceAction::sign(
+            governance_key,
+            DEFAULT_CHAIN_ID,
+            0,
+            GovernanceAction::SetWithdrawalRateLimit {
+                limit: Some(WithdrawalRateLimit {
+                    max_volume: 30_000_000_000_000_000_000,
+                    window_l1_blocks: 6,
+                }),
+            },
+        )
+        .unwrap()
+        .try_to_vec()
+        .unwrap()],
+        &mut working_set,
+    );
+    evm.end_soft_confirmation_hook(&mut working_set);
+    evm.finalize_hook(&[99u8; 32].into(), &mut working_set.accessory_state());
+
+    assert_eq!(evm.governance_nonce(&mut working_set), 0);
+    assert_eq!(evm.deferred_system_events.get(&mut working_set), None);
+}
+
+#[test]
+fn test_sys_state_root_anchor() {
+    let (mut config, _, _) =
+        get_evm_config_starting_base_fee(U256::from_str("1000000").unwrap(), None, 1);
+    config.data.push(l1_block_hash_list_account());
+    config.data.push(state_root_anchor_account());
+    config.state_root_anchor_interval = 2;
+
+    let (evm, mut working_set) = get_evm(&config);
+
+    let sender_address = generate_address::<C>("sender");
+    let sequencer_address = generate_address::<C>("sequencer");
+    let context = C::new(sender_address, sequencer_address, 1);
+
+    // blocks 2 to 4, the state root of each block being set at the beginning of the next one
+    for (pre_state_root, timestamp) in [([10u8; 32], 42), ([20u8; 32], 43), ([30u8; 32], 44)] {
+        evm.begin_soft_confirmation_hook(
+            [1u8; 32],
+            1,
+            [2u8; 32],
+            &pre_state_root,
+            1,
+            timestamp,
+            &mut working_set,
+        );
+        evm.call(
+            CallMessage {
+                txs: vec![],
+                encrypted: None,
+            },
+            &context,
+            &mut working_set,
+        )
+        .unwrap();
+        evm.end_soft_confirmation_hook(&mut working_set);
+        evm.finalize_hook(&[99u8; 32].into(), &mut working_set.accessory_state());
+    }
+
+    // only block 2 is anchored, at the beginning of block 3
+    let transactions = evm
+        .transactions
+        .iter(&mut working_set.accessory_state())
+        .filter(|tx| {
+            tx.signed_transaction.kind() == &TransactionKind::Call(StateRootAnchor::address())
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(transactions.len(), 1);
+    assert_eq!(transactions[0].signer, SYSTEM_SIGNER);
+    assert_eq!(
+        transactions[0].signed_transaction.input().to_vec(),
+        StateRootAnchor::default()
+            .anchor(2, [20u8; 32], 42)
+            .to_vec()
+    );
+
+    let block = evm
+        .blocks
+        .get(3, &mut working_set.accessory_state())
+        .unwrap();
+    let receipt = evm
+        .receipts
+        .get(
+            block.transactions.start as usize,
+            &mut working_set.accessory_state(),
+        )
+        .unwrap();
+    assert!(receipt.receipt.success);
+    assert_eq!(receipt.receipt.logs.len(), 1);
+    assert_eq!(
+        receipt.receipt.logs[0].data.to_vec(),
+        [
+            U256::from(2).to_be_bytes::<32>(),
+            [20u8; 32],
+            U256::from(42).to_be_bytes::<32>()
+        ]
+        .concat()
+    );
+
+    let state_root_anchor = StateRootAnchor::default();
+    let mut call = |input: Vec<u8>| {
+        evm.get_call(
+            &QueryContext::default(),
+            TransactionRequest {
+                to: Some(StateRootAnchor::address()),
+                input: TransactionInput::new(input.into()),
+                ..Default::default()
+            },
+            None,
+            None,
+            None,
+            &mut working_set,
+        )
+        .unwrap()
+    };
+    assert_eq!(
+        call(state_root_anchor.get_state_root(2).to_vec()).as_ref(),
+        &[20u8; 32]
+    );
+    assert_eq!(
+        call(state_root_anchor.get_state_root(3).to_vec()).as_ref(),
+        &[0u8; 32]
+    );
+    assert_eq!(
+        U256::from_be_slice(&call(
+            state_root_anchor
+                .is_recent_state_root([20u8; 32], 0)
+                .to_vec()
+        )),
+        U256::from(1)
+    );
+    assert_eq!(
+        U256::from_be_slice(&call(
+            state_root_anchor
+                .is_recent_state_root([30u8; 32], 0)
+                .to_vec()
+        )),
+        U256::ZERO
+    );
+}
+
+#[test]
+fn test_system_contract_behind_proxy() {
+    let (mut config, _, _) =
+        get_evm_config_starting_base_fee(U256::from_str("1000000").unwrap(), None, 1);
+    // L1BlockHashList behind a proxy, as in the genesis files
+    let implementation = address!("3200000000000000000000000000000000000001");
+    let l1_block_hash_list_code = l1_block_hash_list_account().code;
+    config.data.push(system_contract_proxy_account(
+        L1BlockHashList::address(),
+        implementation,
+    ));
+    config.data.push(AccountData::new(
+        implementation,
+        U256::ZERO,
+        l1_block_hash_list_code.clone(),
+        Default::default(),
+    ));
+    // init code returning the same runtime code, deployed as the new implementation in block 2
+    let init_code = [
+        &[0x61][..],
+        &(l1_block_hash_list_code.len() as u16).to_be_bytes(),
+        &hex!("80600c6000396000f3"),
+        &l1_block_hash_list_code,
+    ]
+    .concat();
+    config.system_contract_upgrades = vec![SystemContractUpgrade {
+        block_number: 2,
+        proxy: L1BlockHashList::address(),
+        code: init_code.into(),
+        call_data: Default::default(),
+    }];
+
+    let (evm, mut working_set) = get_evm(&config);
+
+    let l1_block_hash_list = L1BlockHashList::default();
+    let proxy = SystemContractProxy::default();
+    let call = |input: Vec<u8>, working_set: &mut sov_modules_api::WorkingSet<C>| {
+        evm.get_call(
+            &QueryContext::default(),
+            TransactionRequest {
+                to: Some(L1BlockHashList::address()),
+                input: TransactionInput::new(input.into()),
+                ..Default::default()
+            },
+            None,
+            None,
+            None,
+            working_set,
+        )
+        .unwrap()
+    };
+
+    // the system transactions of block 1 went through the proxy to the implementation,
+    // the state is kept by the proxy
+    assert!(evm
+        .receipts
+        .iter(&mut working_set.accessory_state())
+        .all(|receipt| receipt.receipt.success));
+    assert_eq!(
+        call(
+            l1_block_hash_list.get_block_hash(1).to_vec(),
+            &mut working_set
+        )
+        .as_ref(),
+        &[1u8; 32]
+    );
+    assert_eq!(
+        call(proxy.implementation().to_vec(), &mut working_set).as_ref(),
+        implementation.into_word().as_slice()
+    );
+    assert_eq!(
+        evm.get_storage_at(
+            &QueryContext::default(),
+            implementation,
+            U256::ZERO,
+            None,
+            &mut working_set
+        )
+        .unwrap(),
+        B256::ZERO
+    );
+
+    let sender_address = generate_address::<C>("sender");
+    let sequencer_address = generate_address::<C>("sequencer");
+    let context = C::new(sender_address, sequencer_address, 1);
+
+    evm.begin_soft_confirmation_hook(
+        [2u8; 32],
+        2,
+        [3u8; 32],
+        &[10u8; 32],
+        1,
+        42,
+        &mut working_set,
+    );
+    evm.call(
+        CallMessage {
+            txs: vec![],
+            encrypted: None,
+        },
+        &context,
+        &mut working_set,
+    )
+    .unwrap();
+    evm.end_soft_confirmation_hook(&mut working_set);
+    evm.finalize_hook(&[99u8; 32].into(), &mut working_set.accessory_state());
+
+    // the upgrade is applied before the block info is set through the new implementation
+    let system_nonce = 2;
+    let new_implementation = SYSTEM_SIGNER.create(system_nonce);
+    let block = evm
+        .blocks
+        .get(2, &mut working_set.accessory_state())
+        .unwrap();
+    assert_eq!(block.transactions.end - block.transactions.start, 3);
+    for index in block.transactions {
+        let receipt = evm
+            .receipts
+            .get(index as usize, &mut working_set.accessory_state())
+            .unwrap();
+        assert!(receipt.receipt.success);
+    }
+    assert_eq!(
+        call(proxy.implementation().to_vec(), &mut working_set).as_ref(),
+        new_implementation.into_word().as_slice()
+    );
+    assert_eq!(
+        call(
+            l1_block_hash_list.get_block_hash(1).to_vec(),
+            &mut working_set
+        )
+        .as_ref(),
+        &[1u8; 32]
+    );
+    assert_eq!(
+        call(
+            l1_block_hash_list.get_block_hash(2).to_vec(),
+            &mut working_set
+        )
+        .as_ref(),
+        &[2u8; 32]
+    );
 }
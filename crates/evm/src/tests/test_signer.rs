@@ -59,6 +59,29 @@ impl TestSigner {
         nonce: u64,
         value: u128,
         max_fee_per_gas: u128,
+    ) -> Result<RlpEvmTransaction, SignError> {
+        self.sign_transaction(to, data, nonce, value, max_fee_per_gas, 1_000_000u64)
+    }
+
+    /// Signs default Eip1559 transaction with to, data, nonce and gas limit overridden.
+    pub(crate) fn sign_default_transaction_with_gas_limit(
+        &self,
+        to: TransactionKind,
+        data: Vec<u8>,
+        nonce: u64,
+        gas_limit: u64,
+    ) -> Result<RlpEvmTransaction, SignError> {
+        self.sign_transaction(to, data, nonce, 0, 100000000000u128, gas_limit)
+    }
+
+    fn sign_transaction(
+        &self,
+        to: TransactionKind,
+        data: Vec<u8>,
+        nonce: u64,
+        value: u128,
+        max_fee_per_gas: u128,
+        gas_limit: u64,
     ) -> Result<RlpEvmTransaction, SignError> {
         let reth_tx = RethTxEip1559 {
             to,
@@ -66,7 +89,7 @@ impl TestSigner {
             nonce,
             value: U256::from(value),
             chain_id: DEFAULT_CHAIN_ID,
-            gas_limit: 1_000_000u64,
+            gas_limit,
             max_fee_per_gas,
             ..Default::default()
         };
@@ -0,0 +1,104 @@
+use core::fmt::{self, Debug, Display};
+
+/// Errors returned by the [`LightClient`](crate::LightClient).
+#[derive(Debug)]
+pub enum LightClientError<E> {
+    /// The header does not extend the tip of the followed chain.
+    HeaderNotConnected {
+        /// Height of the rejected header.
+        height: u64,
+    },
+    /// The header does not have valid proof of work.
+    InvalidWork {
+        /// Height of the rejected header.
+        height: u64,
+    },
+    /// The header does not commit to the difficulty required by the chain it extends.
+    UnexpectedDifficulty {
+        /// Height of the rejected header.
+        height: u64,
+    },
+    /// The fork does not have more chainwork than the followed chain.
+    ForkHasLessWork,
+    /// The proof failed to verify against the code commitment.
+    InvalidProof(E),
+    /// The proof or commitment refers to a DA block that is not in the followed chain.
+    UnknownSlot([u8; 32]),
+    /// The DA block referred by the proof or commitment is not buried deep enough.
+    NotEnoughConfirmations {
+        /// Confirmations of the DA block.
+        confirmations: u64,
+        /// Confirmations required by the client.
+        required: u64,
+    },
+    /// The proof does not start from the latest proven state root.
+    StateRootMismatch,
+    /// The proof commits to a DA block that is not after the latest proven one.
+    StaleProof {
+        /// Height of the DA block of the latest proven state.
+        latest_proven_height: u64,
+        /// Height of the DA block of the proof.
+        proof_height: u64,
+    },
+    /// The sequencer commitment's L1 range is invalid.
+    InvalidCommitmentRange,
+    /// The blob is not a valid DA blob.
+    InvalidBlob,
+}
+
+impl<E: Debug> Display for LightClientError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::HeaderNotConnected { height } => {
+                write!(f, "header at height {height} does not extend the chain tip")
+            }
+            Self::InvalidWork { height } => {
+                write!(f, "header at height {height} has invalid proof of work")
+            }
+            Self::UnexpectedDifficulty { height } => {
+                write!(f, "header at height {height} has an unexpected difficulty")
+            }
+            Self::ForkHasLessWork => {
+                f.write_str("fork does not have more chainwork than the followed chain")
+            }
+            Self::InvalidProof(e) => write!(f, "invalid proof: {e:?}"),
+            Self::UnknownSlot(hash) => {
+                write!(f, "DA block {} is not in the followed chain", hex(hash))
+            }
+            Self::NotEnoughConfirmations {
+                confirmations,
+                required,
+            } => write!(
+                f,
+                "DA block has {confirmations} confirmations, {required} are required"
+            ),
+            Self::StateRootMismatch => {
+                f.write_str("proof does not start from the latest proven state root")
+            }
+            Self::StaleProof {
+                latest_proven_height,
+                proof_height,
+            } => write!(
+                f,
+                "proof for DA height {proof_height} is not after latest proven DA height {latest_proven_height}"
+            ),
+            Self::InvalidCommitmentRange => f.write_str("invalid sequencer commitment L1 range"),
+            Self::InvalidBlob => f.write_str("invalid DA blob"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E: Debug> std::error::Error for LightClientError<E> {}
+
+fn hex(hash: &[u8; 32]) -> impl Display + '_ {
+    struct Hex<'a>(&'a [u8; 32]);
+
+    impl Display for Hex<'_> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            self.0.iter().try_for_each(|byte| write!(f, "{byte:02x}"))
+        }
+    }
+
+    Hex(hash)
+}
@@ -0,0 +1,190 @@
+use core::ops::Add;
+
+use sov_rollup_interface::da::BlockHeaderTrait;
+
+/// A DA block header whose proof of work can be checked against the chain it extends.
+pub trait SpvHeader: BlockHeaderTrait {
+    /// Consensus parameters the difficulty of headers is checked against.
+    type Params;
+    /// Work done to produce a header, summed into the chainwork of a chain.
+    type Work: Copy + Ord + Add<Output = Self::Work>;
+
+    /// Returns true if the header's hash satisfies the target it commits to.
+    fn has_valid_work(&self) -> bool;
+
+    /// Returns the work done to produce the header, derived from the target it commits to.
+    fn work(&self) -> Self::Work;
+
+    /// Returns true if the header commits to the target required by the difficulty adjustment
+    /// rules, given `ancestor` returning the header at a height of the chain it extends.
+    fn has_expected_target<'a>(
+        &self,
+        ancestor: impl Fn(u64) -> Option<&'a Self>,
+        params: &Self::Params,
+    ) -> bool
+    where
+        Self: 'a;
+}
+
+#[cfg(feature = "bitcoin")]
+mod bitcoin_header {
+    use bitcoin::{CompactTarget, Network, Target, Work};
+    use bitcoin_da::spec::header::HeaderWrapper;
+
+    use super::SpvHeader;
+
+    /// Number of blocks between two difficulty adjustments.
+    const RETARGET_INTERVAL: u64 = 2016;
+    /// Expected time between two blocks, in seconds.
+    const TARGET_SPACING: u64 = 10 * 60;
+    /// Expected time between two difficulty adjustments, in seconds.
+    const TARGET_TIMESPAN: u64 = RETARGET_INTERVAL * TARGET_SPACING;
+
+    /// Difficulty adjustment rules of a network, as in `GetNextWorkRequired` of Bitcoin Core.
+    struct DifficultyRules {
+        pow_limit: CompactTarget,
+        allow_min_difficulty_blocks: bool,
+        no_retargeting: bool,
+    }
+
+    impl DifficultyRules {
+        fn of(network: Network) -> Option<Self> {
+            let (pow_limit, allow_min_difficulty_blocks, no_retargeting) = match network {
+                Network::Bitcoin => (0x1d00ffff, false, false),
+                Network::Testnet => (0x1d00ffff, true, false),
+                Network::Signet => (0x1e0377ae, false, false),
+                Network::Regtest => (0x207fffff, true, true),
+                _ => return None,
+            };
+            Some(Self {
+                pow_limit: CompactTarget::from_consensus(pow_limit),
+                allow_min_difficulty_blocks,
+                no_retargeting,
+            })
+        }
+    }
+
+    impl SpvHeader for HeaderWrapper {
+        type Params = Network;
+        type Work = Work;
+
+        fn has_valid_work(&self) -> bool {
+            let header = self.header();
+            header.validate_pow(header.target()).is_ok()
+        }
+
+        fn work(&self) -> Work {
+            self.header().work()
+        }
+
+        fn has_expected_target<'a>(
+            &self,
+            ancestor: impl Fn(u64) -> Option<&'a Self>,
+            network: &Network,
+        ) -> bool
+        where
+            Self: 'a,
+        {
+            expected_bits(self, &ancestor, *network) == Some(self.header().bits)
+        }
+    }
+
+    /// Returns the bits the header must commit to, or None if they can't be computed from the
+    /// ancestors available.
+    fn expected_bits<'a>(
+        header: &HeaderWrapper,
+        ancestor: &impl Fn(u64) -> Option<&'a HeaderWrapper>,
+        network: Network,
+    ) -> Option<CompactTarget> {
+        let rules = DifficultyRules::of(network)?;
+        let prev = ancestor(header.height.checked_sub(1)?)?;
+        if rules.no_retargeting {
+            return Some(prev.header().bits);
+        }
+
+        if header.height % RETARGET_INTERVAL != 0 {
+            if !rules.allow_min_difficulty_blocks {
+                return Some(prev.header().bits);
+            }
+            // Blocks mined long enough after their parent may have the minimum difficulty,
+            // the others have the difficulty of the last block that did not.
+            if header.header().time as u64 > prev.header().time as u64 + 2 * TARGET_SPACING {
+                return Some(rules.pow_limit);
+            }
+            let mut last = prev;
+            while last.height % RETARGET_INTERVAL != 0 && last.header().bits == rules.pow_limit {
+                last = ancestor(last.height - 1)?;
+            }
+            return Some(last.header().bits);
+        }
+
+        let first = ancestor(header.height - RETARGET_INTERVAL)?;
+        let timespan = (prev.header().time as u64)
+            .saturating_sub(first.header().time as u64)
+            .clamp(TARGET_TIMESPAN / 4, TARGET_TIMESPAN * 4);
+        let target = mul_div(
+            Target::from_compact(prev.header().bits).to_le_bytes(),
+            timespan,
+            TARGET_TIMESPAN,
+        );
+        let pow_limit = Target::from_compact(rules.pow_limit);
+        let target = match target {
+            Some(target) if Target::from_le_bytes(target) <= pow_limit => {
+                Target::from_le_bytes(target)
+            }
+            _ => pow_limit,
+        };
+        Some(target.to_compact_lossy())
+    }
+
+    /// Computes `value * mul / div` on a little-endian 256-bit number, or None if the
+    /// result does not fit in 256 bits.
+    fn mul_div(value: [u8; 32], mul: u64, div: u64) -> Option<[u8; 32]> {
+        let mut limbs = [0u64; 5];
+        let mut carry = 0u128;
+        for (i, limb) in limbs.iter_mut().take(4).enumerate() {
+            let bytes = value[i * 8..(i + 1) * 8].try_into().unwrap();
+            let product = u64::from_le_bytes(bytes) as u128 * mul as u128 + carry;
+            *limb = product as u64;
+            carry = product >> 64;
+        }
+        limbs[4] = carry as u64;
+
+        let mut remainder = 0u128;
+        for limb in limbs.iter_mut().rev() {
+            let dividend = (remainder << 64) | *limb as u128;
+            *limb = (dividend / div as u128) as u64;
+            remainder = dividend % div as u128;
+        }
+        if limbs[4] != 0 {
+            return None;
+        }
+
+        let mut result = [0u8; 32];
+        for (i, limb) in limbs.iter().take(4).enumerate() {
+            result[i * 8..(i + 1) * 8].copy_from_slice(&limb.to_le_bytes());
+        }
+        Some(result)
+    }
+}
+
+#[cfg(feature = "mock")]
+impl SpvHeader for sov_mock_da::MockBlockHeader {
+    type Params = ();
+    type Work = u64;
+
+    fn has_valid_work(&self) -> bool {
+        true
+    }
+
+    fn work(&self) -> u64 {
+        1
+    }
+
+    fn has_expected_target<'a>(&self, _ancestor: impl Fn(u64) -> Option<&'a Self>, _: &()) -> bool
+    where
+        Self: 'a,
+    {
+        true
+    }
+}
@@ -0,0 +1,308 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(missing_docs)]
+#![doc = include_str!("../README.md")]
+
+extern crate alloc;
+
+mod error;
+mod header;
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use borsh::BorshDeserialize;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sov_rollup_interface::da::{BlockHeaderTrait, DaData, DaSpec, SequencerCommitment};
+use sov_rollup_interface::zk::Zkvm;
+
+pub use crate::error::LightClientError;
+pub use crate::header::SpvHeader;
+
+/// A state root proven to be the result of executing Citrea up to a DA block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProvenState<Root> {
+    /// Height of the DA block.
+    pub l1_height: u64,
+    /// Hash of the DA block.
+    pub l1_hash: [u8; 32],
+    /// L2 state root after the DA block.
+    pub state_root: Root,
+}
+
+/// Follows the DA layer's header chain from a trusted checkpoint and verifies Citrea's
+/// proofs against it, keeping track of the latest proven L2 state root.
+pub struct LightClient<Da: DaSpec, Vm: Zkvm, Root>
+where
+    Da::BlockHeader: SpvHeader,
+{
+    code_commitment: Vm::CodeCommitment,
+    params: <Da::BlockHeader as SpvHeader>::Params,
+    min_confirmations: u64,
+    /// Followed chain from the checkpoint to the tip, in order.
+    headers: Vec<Da::BlockHeader>,
+    /// Chainwork of the followed chain from the checkpoint up to each of its headers.
+    chainwork: Vec<<Da::BlockHeader as SpvHeader>::Work>,
+    /// Proven states in order, the first one being the checkpoint's state.
+    proven_states: Vec<ProvenState<Root>>,
+    latest_commitment: Option<SequencerCommitment>,
+}
+
+impl<Da, Vm, Root> LightClient<Da, Vm, Root>
+where
+    Da: DaSpec,
+    Da::BlockHeader: SpvHeader,
+    Vm: Zkvm,
+    Root: Serialize + DeserializeOwned + PartialEq + Clone,
+{
+    /// Creates a light client trusting `checkpoint` and the L2 `state_root` after it.
+    ///
+    /// Headers are checked against the difficulty adjustment rules of `params`, which need the
+    /// headers of the current difficulty period: the checkpoint should start one.
+    /// Proofs and commitments are only accepted once their DA block has at least
+    /// `min_confirmations` confirmations, the tip counting as one.
+    pub fn new(
+        code_commitment: Vm::CodeCommitment,
+        params: <Da::BlockHeader as SpvHeader>::Params,
+        checkpoint: Da::BlockHeader,
+        state_root: Root,
+        min_confirmations: u64,
+    ) -> Self {
+        let proven_state = ProvenState {
+            l1_height: checkpoint.height(),
+            l1_hash: checkpoint.hash().into(),
+            state_root,
+        };
+        Self {
+            code_commitment,
+            params,
+            min_confirmations,
+            chainwork: vec![checkpoint.work()],
+            headers: vec![checkpoint],
+            proven_states: vec![proven_state],
+            latest_commitment: None,
+        }
+    }
+
+    /// Returns the tip of the followed chain.
+    pub fn tip(&self) -> &Da::BlockHeader {
+        self.headers.last().expect("Checkpoint is never removed")
+    }
+
+    /// Returns the chainwork of the followed chain from the checkpoint to the tip.
+    pub fn chainwork(&self) -> <Da::BlockHeader as SpvHeader>::Work {
+        *self.chainwork.last().expect("Checkpoint is never removed")
+    }
+
+    /// Returns the latest proven state.
+    pub fn latest_proven_state(&self) -> &ProvenState<Root> {
+        self.proven_states
+            .last()
+            .expect("Checkpoint state is never removed")
+    }
+
+    /// Returns the latest sequencer commitment seen on the followed chain.
+    pub fn latest_commitment(&self) -> Option<&SequencerCommitment> {
+        self.latest_commitment.as_ref()
+    }
+
+    /// Returns the header of the followed chain with the given hash.
+    pub fn header_by_hash(&self, hash: &[u8; 32]) -> Option<&Da::BlockHeader> {
+        self.headers.iter().rev().find(|header| {
+            let header_hash: [u8; 32] = header.hash().into();
+            &header_hash == hash
+        })
+    }
+
+    /// Extends the followed chain with `header`.
+    pub fn add_header(
+        &mut self,
+        header: Da::BlockHeader,
+    ) -> Result<(), LightClientError<Vm::Error>> {
+        self.check_header(&header, self.tip(), |height| self.followed_header(height))?;
+        let chainwork = self.chainwork() + header.work();
+        self.headers.push(header);
+        self.chainwork.push(chainwork);
+        Ok(())
+    }
+
+    /// Switches the followed chain to `fork`, headers in order branching off the followed chain,
+    /// if it has more chainwork than the followed chain. Proven states and commitments of the
+    /// dropped blocks are forgotten.
+    pub fn reorg(&mut self, fork: Vec<Da::BlockHeader>) -> Result<(), LightClientError<Vm::Error>> {
+        let first = fork.first().ok_or(LightClientError::ForkHasLessWork)?;
+        let fork_point = first
+            .height()
+            .checked_sub(1)
+            .and_then(|height| self.followed_header(height))
+            .ok_or(LightClientError::HeaderNotConnected {
+                height: first.height(),
+            })?;
+        let fork_height = fork_point.height();
+
+        let mut chainwork = self.chainwork[self.index_of(fork_height)];
+        for (i, header) in fork.iter().enumerate() {
+            let prev = if i == 0 { fork_point } else { &fork[i - 1] };
+            self.check_header(header, prev, |height| {
+                if height <= fork_height {
+                    self.followed_header(height)
+                } else {
+                    fork.get((height - fork_height - 1) as usize)
+                }
+            })?;
+            chainwork = chainwork + header.work();
+        }
+        if chainwork <= self.chainwork() {
+            return Err(LightClientError::ForkHasLessWork);
+        }
+
+        self.rollback(fork_height);
+        for header in fork {
+            let chainwork = self.chainwork() + header.work();
+            self.headers.push(header);
+            self.chainwork.push(chainwork);
+        }
+        Ok(())
+    }
+
+    /// Verifies a state transition proof and, if it is valid, makes its final state root
+    /// the latest proven state.
+    ///
+    /// The proof must be valid for the code commitment, start from the latest proven state root,
+    /// and commit to a DA block of the followed chain with enough confirmations.
+    pub fn verify_proof(
+        &mut self,
+        proof: &[u8],
+    ) -> Result<&ProvenState<Root>, LightClientError<Vm::Error>> {
+        let transition = Vm::verify_and_extract_output::<Da, Root>(proof, &self.code_commitment)
+            .map_err(LightClientError::InvalidProof)?;
+
+        let l1_hash: [u8; 32] = transition.slot_hash.into();
+        let l1_height = self.confirmed_height(&l1_hash)?;
+
+        let latest = self.latest_proven_state();
+        if transition.initial_state_root != latest.state_root {
+            return Err(LightClientError::StateRootMismatch);
+        }
+        if l1_height <= latest.l1_height {
+            return Err(LightClientError::StaleProof {
+                latest_proven_height: latest.l1_height,
+                proof_height: l1_height,
+            });
+        }
+
+        self.proven_states.push(ProvenState {
+            l1_height,
+            l1_hash,
+            state_root: transition.final_state_root,
+        });
+        Ok(self.latest_proven_state())
+    }
+
+    /// Records a sequencer commitment whose L1 range is on the followed chain.
+    pub fn process_commitment(
+        &mut self,
+        commitment: SequencerCommitment,
+    ) -> Result<(), LightClientError<Vm::Error>> {
+        let start = self
+            .header_by_hash(&commitment.l1_start_block_hash)
+            .ok_or(LightClientError::UnknownSlot(
+                commitment.l1_start_block_hash,
+            ))?
+            .height();
+        let end = self.confirmed_height(&commitment.l1_end_block_hash)?;
+        if start > end {
+            return Err(LightClientError::InvalidCommitmentRange);
+        }
+        self.latest_commitment = Some(commitment);
+        Ok(())
+    }
+
+    /// Processes a blob extracted from the followed chain.
+    ///
+    /// Sequencer commitments are recorded, and proofs carrying the proof itself are verified
+    /// with [`verify_proof`](Self::verify_proof). Proofs only carrying their L2 range can't be
+    /// verified and are ignored, like governance actions, which are verified by the rollup.
+    pub fn process_blob(&mut self, blob: &[u8]) -> Result<(), LightClientError<Vm::Error>> {
+        match DaData::try_from_slice(blob).map_err(|_| LightClientError::InvalidBlob)? {
+            DaData::SequencerCommitment(commitment) => self.process_commitment(commitment),
+            DaData::VerifiableZKProof(proof) => self.verify_proof(&proof.proof).map(|_| ()),
+            DaData::ZKProof(_) | DaData::Governance(_) => Ok(()),
+        }
+    }
+
+    /// Checks that `header` extends `prev` with valid proof of work and the expected difficulty,
+    /// `ancestor` returning the headers of the chain it extends.
+    fn check_header<'a>(
+        &self,
+        header: &Da::BlockHeader,
+        prev: &Da::BlockHeader,
+        ancestor: impl Fn(u64) -> Option<&'a Da::BlockHeader>,
+    ) -> Result<(), LightClientError<Vm::Error>>
+    where
+        Da::BlockHeader: 'a,
+    {
+        let prev_hash: [u8; 32] = header.prev_hash().into();
+        let parent_hash: [u8; 32] = prev.hash().into();
+        if prev_hash != parent_hash || header.height() != prev.height() + 1 {
+            return Err(LightClientError::HeaderNotConnected {
+                height: header.height(),
+            });
+        }
+        if !header.has_valid_work() {
+            return Err(LightClientError::InvalidWork {
+                height: header.height(),
+            });
+        }
+        if !header.has_expected_target(ancestor, &self.params) {
+            return Err(LightClientError::UnexpectedDifficulty {
+                height: header.height(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Drops the headers above `height`, which must be on the followed chain. Proven states
+    /// and commitments of the dropped blocks are forgotten.
+    fn rollback(&mut self, height: u64) {
+        let len = self.index_of(height) + 1;
+        self.headers.truncate(len);
+        self.chainwork.truncate(len);
+        self.proven_states.retain(|state| state.l1_height <= height);
+        let commitment_dropped = self
+            .latest_commitment
+            .as_ref()
+            .is_some_and(|commitment| self.header_by_hash(&commitment.l1_end_block_hash).is_none());
+        if commitment_dropped {
+            self.latest_commitment = None;
+        }
+    }
+
+    /// Returns the header of the followed chain at `height`.
+    fn followed_header(&self, height: u64) -> Option<&Da::BlockHeader> {
+        let checkpoint = self.headers[0].height();
+        height
+            .checked_sub(checkpoint)
+            .and_then(|index| self.headers.get(index as usize))
+    }
+
+    fn index_of(&self, height: u64) -> usize {
+        (height - self.headers[0].height()) as usize
+    }
+
+    /// Returns the height of the DA block if it is on the followed chain with enough confirmations.
+    fn confirmed_height(&self, hash: &[u8; 32]) -> Result<u64, LightClientError<Vm::Error>> {
+        let height = self
+            .header_by_hash(hash)
+            .ok_or(LightClientError::UnknownSlot(*hash))?
+            .height();
+        let confirmations = self.tip().height() - height + 1;
+        if confirmations < self.min_confirmations {
+            return Err(LightClientError::NotEnoughConfirmations {
+                confirmations,
+                required: self.min_confirmations,
+            });
+        }
+        Ok(height)
+    }
+}
@@ -0,0 +1,69 @@
+use bitcoin::block::{Header, Version};
+use bitcoin::blockdata::constants::genesis_block;
+use bitcoin::hashes::Hash;
+use bitcoin::{CompactTarget, Network, TxMerkleNode, WitnessMerkleNode};
+use bitcoin_da::spec::header::HeaderWrapper;
+use bitcoin_da::spec::BitcoinSpec;
+use citrea_light_client::{LightClient, LightClientError};
+use sov_mock_da::MockValidityCond;
+use sov_mock_zkvm::{MockCodeCommitment, MockZkvm};
+
+type BitcoinLightClient = LightClient<BitcoinSpec, MockZkvm<MockValidityCond>, [u8; 32]>;
+
+fn wrap(header: Header, height: u64) -> HeaderWrapper {
+    HeaderWrapper::new(header, 1, height, WitnessMerkleNode::all_zeros())
+}
+
+fn mainnet_client() -> BitcoinLightClient {
+    let genesis = genesis_block(Network::Bitcoin).header;
+    BitcoinLightClient::new(
+        MockCodeCommitment([1; 32]),
+        Network::Bitcoin,
+        wrap(genesis, 0),
+        [0; 32],
+        1,
+    )
+}
+
+#[test]
+fn accepts_mainnet_headers() {
+    let mut client = mainnet_client();
+    let genesis_work = client.chainwork();
+
+    let block_1 = Header {
+        version: Version::ONE,
+        prev_blockhash: genesis_block(Network::Bitcoin).block_hash(),
+        merkle_root: "0e3e2357e806b6cdb1f70b54c3a3a17b6714ee1f0e68bebb44a74b1efd512098"
+            .parse::<TxMerkleNode>()
+            .unwrap(),
+        time: 1231469665,
+        bits: CompactTarget::from_consensus(0x1d00ffff),
+        nonce: 2573394689,
+    };
+    client.add_header(wrap(block_1, 1)).unwrap();
+    assert_eq!(client.chainwork(), genesis_work + block_1.work());
+}
+
+#[test]
+fn rejects_forged_low_difficulty_headers() {
+    let mut client = mainnet_client();
+
+    // Valid proof of work for the target it commits to, which is far below the required one.
+    let mut forged = Header {
+        version: Version::ONE,
+        prev_blockhash: genesis_block(Network::Bitcoin).block_hash(),
+        merkle_root: TxMerkleNode::all_zeros(),
+        time: 1231469665,
+        bits: CompactTarget::from_consensus(0x207fffff),
+        nonce: 0,
+    };
+    while forged.validate_pow(forged.target()).is_err() {
+        forged.nonce += 1;
+    }
+
+    assert!(matches!(
+        client.add_header(wrap(forged, 1)),
+        Err(LightClientError::UnexpectedDifficulty { height: 1 })
+    ));
+    assert_eq!(client.tip().height, 0);
+}
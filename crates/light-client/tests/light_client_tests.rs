@@ -0,0 +1,180 @@
+use borsh::BorshSerialize;
+use citrea_light_client::{LightClient, LightClientError};
+use sov_mock_da::{MockBlockHeader, MockDaSpec, MockHash, MockValidityCond};
+use sov_mock_zkvm::{MockCodeCommitment, MockProof, MockZkvm};
+use sov_rollup_interface::da::{
+    BatchProof, BlockHeaderTrait, DaData, SequencerCommitment, VerifiableBatchProof,
+};
+use sov_rollup_interface::zk::StateTransition;
+
+type TestLightClient = LightClient<MockDaSpec, MockZkvm<MockValidityCond>, [u8; 32]>;
+
+const CODE_COMMITMENT: MockCodeCommitment = MockCodeCommitment([1; 32]);
+
+fn light_client_with_headers(tip_height: u64) -> TestLightClient {
+    let mut client = TestLightClient::new(
+        CODE_COMMITMENT,
+        (),
+        MockBlockHeader::from_height(0),
+        [0; 32],
+        2,
+    );
+    for height in 1..=tip_height {
+        client
+            .add_header(MockBlockHeader::from_height(height))
+            .unwrap();
+    }
+    client
+}
+
+/// Returns headers from `start_height` to `tip_height` forking off `MockBlockHeader::from_height`.
+fn fork_headers(start_height: u64, tip_height: u64) -> Vec<MockBlockHeader> {
+    let fork_hash = |height: u64| MockHash([height as u8; 32]);
+    (start_height..=tip_height)
+        .map(|height| MockBlockHeader {
+            prev_hash: if height == start_height {
+                MockBlockHeader::from_height(height - 1).hash()
+            } else {
+                fork_hash(height - 1)
+            },
+            hash: fork_hash(height),
+            ..MockBlockHeader::from_height(height)
+        })
+        .collect()
+}
+
+fn proof(initial_state_root: [u8; 32], final_state_root: [u8; 32], l1_height: u64) -> Vec<u8> {
+    let transition = StateTransition::<MockDaSpec, [u8; 32]> {
+        initial_state_root,
+        final_state_root,
+        slot_hash: MockBlockHeader::from_height(l1_height).hash(),
+        validity_condition: MockValidityCond::default(),
+    };
+    let log = bincode::serialize(&transition).unwrap();
+    MockProof {
+        program_id: CODE_COMMITMENT,
+        is_valid: true,
+        log: &log,
+    }
+    .encode_to_vec()
+}
+
+#[test]
+fn rejects_disconnected_headers() {
+    let mut client = light_client_with_headers(2);
+
+    let result = client.add_header(MockBlockHeader::from_height(4));
+    assert!(matches!(
+        result,
+        Err(LightClientError::HeaderNotConnected { height: 4 })
+    ));
+    assert_eq!(client.tip().height(), 2);
+}
+
+#[test]
+fn verifies_proofs_in_order() {
+    let mut client = light_client_with_headers(4);
+
+    let proven = client.verify_proof(&proof([0; 32], [1; 32], 2)).unwrap();
+    assert_eq!(proven.l1_height, 2);
+    assert_eq!(proven.state_root, [1; 32]);
+
+    // Does not continue from the latest proven state root.
+    assert!(matches!(
+        client.verify_proof(&proof([0; 32], [2; 32], 3)),
+        Err(LightClientError::StateRootMismatch)
+    ));
+    // Proves a DA block that is not confirmed enough.
+    assert!(matches!(
+        client.verify_proof(&proof([1; 32], [2; 32], 4)),
+        Err(LightClientError::NotEnoughConfirmations {
+            confirmations: 1,
+            required: 2
+        })
+    ));
+    // Proves a DA block that is not in the followed chain.
+    assert!(matches!(
+        client.verify_proof(&proof([1; 32], [2; 32], 10)),
+        Err(LightClientError::UnknownSlot(_))
+    ));
+
+    let proven = client.verify_proof(&proof([1; 32], [2; 32], 3)).unwrap();
+    assert_eq!(proven.state_root, [2; 32]);
+}
+
+#[test]
+fn rejects_proofs_for_other_programs() {
+    let mut client = light_client_with_headers(4);
+
+    let mut proof = proof([0; 32], [1; 32], 2);
+    proof[0] = 0;
+    assert!(matches!(
+        client.verify_proof(&proof),
+        Err(LightClientError::InvalidProof(_))
+    ));
+    assert_eq!(client.latest_proven_state().state_root, [0; 32]);
+}
+
+#[test]
+fn verifies_proof_blobs() {
+    let mut client = light_client_with_headers(4);
+
+    let blob = |proof: Vec<u8>| {
+        DaData::VerifiableZKProof(VerifiableBatchProof {
+            batch_proof: BatchProof {
+                l2_start_height: 1,
+                l2_end_height: 10,
+            },
+            proof,
+            state_diff: vec![(b"key".to_vec(), Some(b"value".to_vec()))],
+        })
+        .try_to_vec()
+        .unwrap()
+    };
+    assert!(matches!(
+        client.process_blob(&blob(proof([1; 32], [2; 32], 2))),
+        Err(LightClientError::StateRootMismatch)
+    ));
+    client
+        .process_blob(&blob(proof([0; 32], [1; 32], 2)))
+        .unwrap();
+    assert_eq!(client.latest_proven_state().state_root, [1; 32]);
+}
+
+#[test]
+fn rejects_forks_with_less_work() {
+    let mut client = light_client_with_headers(4);
+
+    assert!(matches!(
+        client.reorg(fork_headers(3, 4)),
+        Err(LightClientError::ForkHasLessWork)
+    ));
+    assert_eq!(client.tip().height(), 4);
+    assert_eq!(client.chainwork(), 5);
+}
+
+#[test]
+fn reorg_forgets_reorged_state() {
+    let mut client = light_client_with_headers(4);
+    client.verify_proof(&proof([0; 32], [1; 32], 3)).unwrap();
+
+    let commitment = SequencerCommitment {
+        merkle_root: [5; 32],
+        l1_start_block_hash: MockBlockHeader::from_height(1).hash().into(),
+        l1_end_block_hash: MockBlockHeader::from_height(3).hash().into(),
+    };
+    client
+        .process_blob(
+            &DaData::SequencerCommitment(commitment.clone())
+                .try_to_vec()
+                .unwrap(),
+        )
+        .unwrap();
+    assert_eq!(client.latest_commitment(), Some(&commitment));
+
+    client.reorg(fork_headers(3, 5)).unwrap();
+    assert_eq!(client.tip().height(), 5);
+    assert_eq!(client.chainwork(), 6);
+    assert_eq!(client.latest_proven_state().state_root, [0; 32]);
+    assert_eq!(client.latest_commitment(), None);
+}
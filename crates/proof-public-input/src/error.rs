@@ -0,0 +1,39 @@
+use core::fmt::{self, Display};
+
+/// Errors returned when decoding a [`BatchProofPublicInput`](crate::BatchProofPublicInput).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PublicInputError {
+    /// The input is empty.
+    Empty,
+    /// The version byte is not a known version.
+    UnknownVersion(u8),
+    /// The input does not have the size of its version.
+    InvalidLength {
+        /// Version of the input.
+        version: u8,
+        /// Size of an input of this version.
+        expected: usize,
+        /// Size of the input.
+        actual: usize,
+    },
+}
+
+impl Display for PublicInputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("empty public input"),
+            Self::UnknownVersion(version) => write!(f, "unknown public input version {version}"),
+            Self::InvalidLength {
+                version,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "public input of version {version} must be {expected} bytes, got {actual}"
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PublicInputError {}
@@ -0,0 +1,105 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![deny(missing_docs)]
+#![doc = include_str!("../README.md")]
+
+extern crate alloc;
+
+mod error;
+
+use alloc::vec::Vec;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+pub use crate::error::PublicInputError;
+
+/// Version of [`BatchProofPublicInputV1`].
+pub const V1: u8 = 0;
+
+/// Size in bytes of an encoded [`BatchProofPublicInputV1`], version byte included.
+pub const V1_ENCODED_LEN: usize = 1 + 5 * 32;
+
+/// Public input committed by a batch proof.
+///
+/// Encoded as a version byte followed by the fields of the version, each field being
+/// a 32 byte array in declaration order. New versions only ever get appended, so an
+/// encoded input keeps its meaning across releases.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(tag = "version", rename_all = "camelCase")]
+pub enum BatchProofPublicInput {
+    /// First version of the public input.
+    V1(BatchProofPublicInputV1),
+}
+
+/// Public input of batch proofs, version [`V1`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchProofPublicInputV1 {
+    /// L2 state root before the first proven soft confirmation.
+    pub initial_state_root: [u8; 32],
+    /// L2 state root after the last proven soft confirmation.
+    pub final_state_root: [u8; 32],
+    /// Hash of the first DA block of the proven range.
+    pub da_start_slot_hash: [u8; 32],
+    /// Hash of the last DA block of the proven range.
+    pub da_end_slot_hash: [u8; 32],
+    /// Merkle root of the sequencer commitments processed by the proof.
+    pub sequencer_commitments_root: [u8; 32],
+}
+
+impl BatchProofPublicInput {
+    /// Returns the version of the public input.
+    pub fn version(&self) -> u8 {
+        match self {
+            Self::V1(_) => V1,
+        }
+    }
+
+    /// Encodes the public input as committed by the guest.
+    pub fn encode(&self) -> Vec<u8> {
+        self.try_to_vec()
+            .expect("Serialization to a vector never fails")
+    }
+
+    /// Decodes a public input committed by the guest.
+    ///
+    /// Fails on unknown versions and on inputs that are not exactly one encoded public input.
+    pub fn decode(bytes: &[u8]) -> Result<Self, PublicInputError> {
+        let (&version, fields) = bytes.split_first().ok_or(PublicInputError::Empty)?;
+        match version {
+            V1 => {
+                if bytes.len() != V1_ENCODED_LEN {
+                    return Err(PublicInputError::InvalidLength {
+                        version,
+                        expected: V1_ENCODED_LEN,
+                        actual: bytes.len(),
+                    });
+                }
+                let input = BatchProofPublicInputV1::try_from_slice(fields)
+                    .expect("Fixed size fields always decode");
+                Ok(Self::V1(input))
+            }
+            _ => Err(PublicInputError::UnknownVersion(version)),
+        }
+    }
+
+    /// Returns the L2 state root before the proven range.
+    pub fn initial_state_root(&self) -> &[u8; 32] {
+        match self {
+            Self::V1(input) => &input.initial_state_root,
+        }
+    }
+
+    /// Returns the L2 state root after the proven range.
+    pub fn final_state_root(&self) -> &[u8; 32] {
+        match self {
+            Self::V1(input) => &input.final_state_root,
+        }
+    }
+}
+
+impl From<BatchProofPublicInputV1> for BatchProofPublicInput {
+    fn from(input: BatchProofPublicInputV1) -> Self {
+        Self::V1(input)
+    }
+}
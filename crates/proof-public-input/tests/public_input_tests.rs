@@ -0,0 +1,64 @@
+use citrea_proof_public_input::{
+    BatchProofPublicInput, BatchProofPublicInputV1, PublicInputError, V1, V1_ENCODED_LEN,
+};
+
+fn public_input() -> BatchProofPublicInput {
+    BatchProofPublicInputV1 {
+        initial_state_root: [1; 32],
+        final_state_root: [2; 32],
+        da_start_slot_hash: [3; 32],
+        da_end_slot_hash: [4; 32],
+        sequencer_commitments_root: [5; 32],
+    }
+    .into()
+}
+
+#[test]
+fn test_v1_layout() {
+    let encoded = public_input().encode();
+
+    assert_eq!(encoded.len(), V1_ENCODED_LEN);
+    assert_eq!(encoded[0], V1);
+    for (i, field) in encoded[1..].chunks(32).enumerate() {
+        assert_eq!(field, [i as u8 + 1; 32]);
+    }
+}
+
+#[test]
+fn test_round_trip() {
+    let input = public_input();
+
+    let decoded = BatchProofPublicInput::decode(&input.encode()).unwrap();
+
+    assert_eq!(decoded, input);
+    assert_eq!(decoded.version(), V1);
+    assert_eq!(decoded.initial_state_root(), &[1; 32]);
+    assert_eq!(decoded.final_state_root(), &[2; 32]);
+}
+
+#[test]
+fn test_decode_rejects_malformed_inputs() {
+    assert_eq!(
+        BatchProofPublicInput::decode(&[]),
+        Err(PublicInputError::Empty)
+    );
+
+    let mut encoded = public_input().encode();
+    encoded[0] = 7;
+    assert_eq!(
+        BatchProofPublicInput::decode(&encoded),
+        Err(PublicInputError::UnknownVersion(7))
+    );
+
+    let mut encoded = public_input().encode();
+    encoded.push(0);
+    assert_eq!(
+        BatchProofPublicInput::decode(&encoded),
+        Err(PublicInputError::InvalidLength {
+            version: V1,
+            expected: V1_ENCODED_LEN,
+            actual: V1_ENCODED_LEN + 1,
+        })
+    );
+    assert!(BatchProofPublicInput::decode(&encoded[..V1_ENCODED_LEN - 1]).is_err());
+}
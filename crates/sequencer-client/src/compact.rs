@@ -0,0 +1,163 @@
+//! Compact relay of soft batches between full nodes.
+//!
+//! A compact soft batch replaces the EVM transaction bodies inside its sovereign transactions
+//! with their hashes. Downstream nodes already have most of these bodies, as their users'
+//! transactions are forwarded to the sequencer through them, and only fetch the missing ones.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use reth_primitives::{keccak256, B256};
+use serde::{Deserialize, Serialize};
+
+/// Number of forwarded transactions kept to rebuild compact soft batches.
+const FORWARDED_TX_CACHE_SIZE: usize = 10_000;
+
+/// A soft batch whose transactions are sent in compact form.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompactSoftBatch<B> {
+    /// The soft batch, without its transactions.
+    #[serde(flatten)]
+    pub soft_batch: B,
+    /// The transactions of the soft batch.
+    pub compact_txs: Vec<CompactTx>,
+}
+
+/// A transaction split into raw bytes and transaction bodies referenced by hash.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactTx {
+    /// The parts of the transaction, in order.
+    pub segments: Vec<TxSegment>,
+}
+
+/// Part of a [`CompactTx`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TxSegment {
+    /// Bytes sent as is.
+    Raw(#[serde(with = "hex::serde")] Vec<u8>),
+    /// Keccak hash of a transaction body.
+    Body(B256),
+}
+
+impl CompactTx {
+    /// Splits `tx` by replacing the given transaction bodies, in the order they appear in `tx`,
+    /// with their hashes. Bodies not longer than a hash are kept in place.
+    pub fn new(tx: &[u8], bodies: &[Vec<u8>]) -> Self {
+        let mut segments = vec![];
+        let mut cursor = 0;
+        for body in bodies.iter().filter(|body| body.len() > B256::len_bytes()) {
+            let Some(position) = tx[cursor..]
+                .windows(body.len())
+                .position(|window| window == body.as_slice())
+            else {
+                continue;
+            };
+            if position > 0 {
+                segments.push(TxSegment::Raw(tx[cursor..cursor + position].to_vec()));
+            }
+            segments.push(TxSegment::Body(keccak256(body)));
+            cursor += position + body.len();
+        }
+        if cursor < tx.len() {
+            segments.push(TxSegment::Raw(tx[cursor..].to_vec()));
+        }
+        Self { segments }
+    }
+
+    /// Hashes of the bodies referenced by the transaction.
+    pub fn body_hashes(&self) -> impl Iterator<Item = &B256> {
+        self.segments.iter().filter_map(|segment| match segment {
+            TxSegment::Body(hash) => Some(hash),
+            TxSegment::Raw(_) => None,
+        })
+    }
+
+    /// Rebuilds the transaction, returns `None` if a body is missing.
+    pub fn expand(&self, bodies: &HashMap<B256, Vec<u8>>) -> Option<Vec<u8>> {
+        let mut tx = vec![];
+        for segment in &self.segments {
+            match segment {
+                TxSegment::Raw(bytes) => tx.extend_from_slice(bytes),
+                TxSegment::Body(hash) => tx.extend_from_slice(bodies.get(hash)?),
+            }
+        }
+        Some(tx)
+    }
+}
+
+/// Bounded cache of the raw transactions forwarded to the sequencer, oldest evicted first.
+#[derive(Debug, Default)]
+pub(crate) struct ForwardedTxCache {
+    bodies: HashMap<B256, Vec<u8>>,
+    order: VecDeque<B256>,
+}
+
+impl ForwardedTxCache {
+    pub(crate) fn insert(&mut self, body: Vec<u8>) {
+        let hash = keccak256(&body);
+        if self.bodies.insert(hash, body).is_some() {
+            return;
+        }
+        self.order.push_back(hash);
+        if self.order.len() > FORWARDED_TX_CACHE_SIZE {
+            let oldest = self.order.pop_front().expect("Cache is not empty");
+            self.bodies.remove(&oldest);
+        }
+    }
+
+    /// Returns the cached bodies among `hashes` and the hashes of the missing ones.
+    pub(crate) fn lookup<'a>(
+        &self,
+        hashes: impl Iterator<Item = &'a B256>,
+    ) -> (HashMap<B256, Vec<u8>>, Vec<B256>) {
+        let mut found = HashMap::new();
+        let mut missing = HashSet::new();
+        for hash in hashes {
+            match self.bodies.get(hash) {
+                Some(body) => {
+                    found.insert(*hash, body.clone());
+                }
+                None => {
+                    missing.insert(*hash);
+                }
+            }
+        }
+        (found, missing.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compact_tx_roundtrip() {
+        let first = vec![1u8; 100];
+        let second = vec![2u8; 50];
+        let short = vec![3u8; 10];
+        let tx = [
+            vec![0u8; 8],
+            first.clone(),
+            vec![0u8; 4],
+            short.clone(),
+            second.clone(),
+            vec![9u8; 16],
+        ]
+        .concat();
+
+        let compact = CompactTx::new(&tx, &[first.clone(), short, second.clone()]);
+        assert_eq!(
+            compact.body_hashes().collect::<Vec<_>>(),
+            vec![&keccak256(&first), &keccak256(&second)]
+        );
+
+        let mut cache = ForwardedTxCache::default();
+        cache.insert(first.clone());
+        let (mut bodies, missing) = cache.lookup(compact.body_hashes());
+        assert_eq!(missing, vec![keccak256(&second)]);
+        assert_eq!(compact.expand(&bodies), None);
+
+        bodies.insert(keccak256(&second), second);
+        assert_eq!(compact.expand(&bodies), Some(tx));
+    }
+}
@@ -1,12 +1,35 @@
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
 use ethers::types::{Bytes, H256};
 use jsonrpsee::core::client::ClientT;
+use jsonrpsee::core::params::ArrayParams;
 use jsonrpsee::core::Error;
-use jsonrpsee::http_client::{HttpClient, HttpClientBuilder};
+use jsonrpsee::http_client::{HeaderMap, HeaderValue, HttpClient, HttpClientBuilder};
 use jsonrpsee::rpc_params;
-use reth_primitives::B256;
-use serde::Deserialize;
-use sov_rollup_interface::rpc::HexTx;
+use reth_primitives::{keccak256, Address, BlockNumberOrTag, B256, U64};
+use reth_rpc_types::txpool::{TxpoolContent, TxpoolInspect, TxpoolStatus};
+use serde::{Deserialize, Serialize};
+use sov_rollup_interface::rpc::{HexTx, SoftBatchResponse};
 use sov_rollup_interface::soft_confirmation::SignedSoftConfirmationBatch;
+use tracing::warn;
+
+mod compact;
+mod local;
+
+use compact::ForwardedTxCache;
+pub use compact::{CompactSoftBatch, CompactTx, TxSegment};
+pub use local::{
+    LocalMempool, MempoolTipsSource, PendingTransactionsSource, ReplacedTransaction,
+    ReplacedTransactionsSource, RpcCachesUsageSink,
+};
+
+/// Header identifying the full node in its requests to the sequencer, so that the sequencer
+/// can count the full nodes syncing from it.
+pub const FULL_NODE_ID_HEADER: &str = "x-citrea-full-node-id";
+
+/// How long the sequencer is considered connected after it last served a soft batch request.
+const SEQUENCER_CONNECTION_TIMEOUT: Duration = Duration::from_secs(60);
 
 /// Configuration for SequencerClient.
 #[derive(Debug, Clone)]
@@ -15,13 +38,112 @@ pub struct SequencerClient {
     pub rpc_url: String,
     /// Client object for soft confirmation
     pub client: HttpClient,
+    /// Whether to sync soft batches in compact form
+    compact_relay: bool,
+    /// Whether to serve the receipts computed by the sequencer before executing soft batches
+    stream_receipts: bool,
+    /// Whether to ask the sequencer for the blocks, receipts and transactions not synced yet
+    delegate_reads: bool,
+    /// Transactions forwarded to the sequencer, shared by the clones of the client
+    forwarded_txs: Arc<Mutex<ForwardedTxCache>>,
+    /// When the sequencer last served a soft batch request, shared by the clones of the client
+    last_soft_batch_response: Arc<Mutex<Option<Instant>>>,
 }
 
 impl SequencerClient {
     /// Creates the sequencer client
     pub fn new(rpc_url: String) -> Self {
-        let client = HttpClientBuilder::default().build(&rpc_url).unwrap();
-        Self { rpc_url, client }
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            FULL_NODE_ID_HEADER,
+            HeaderValue::from_str(full_node_id()).expect("Full node id is a valid header"),
+        );
+        let client = HttpClientBuilder::default()
+            .set_headers(headers)
+            .build(&rpc_url)
+            .unwrap();
+        Self {
+            rpc_url,
+            client,
+            compact_relay: false,
+            stream_receipts: false,
+            delegate_reads: false,
+            forwarded_txs: Default::default(),
+            last_soft_batch_response: Default::default(),
+        }
+    }
+
+    /// Returns true if the sequencer served a soft batch request recently
+    pub fn is_connected(&self) -> bool {
+        self.last_soft_batch_response
+            .lock()
+            .unwrap()
+            .is_some_and(|at| at.elapsed() < SEQUENCER_CONNECTION_TIMEOUT)
+    }
+
+    /// Syncs soft batches in compact form, rebuilding them from the forwarded transactions
+    /// and fetching only the missing transaction bodies
+    pub fn with_compact_relay(mut self, compact_relay: bool) -> Self {
+        self.compact_relay = compact_relay;
+        self
+    }
+
+    /// Streams the receipts computed by the sequencer for the soft batches not executed yet,
+    /// to serve them until the node executes the soft batches itself
+    pub fn with_receipt_streaming(mut self, stream_receipts: bool) -> Self {
+        self.stream_receipts = stream_receipts;
+        self
+    }
+
+    /// Returns true if the receipts computed by the sequencer are streamed
+    pub fn streams_receipts(&self) -> bool {
+        self.stream_receipts
+    }
+
+    /// Asks the sequencer for the blocks, receipts and transactions the node has not synced yet
+    pub fn with_read_delegation(mut self, delegate_reads: bool) -> Self {
+        self.delegate_reads = delegate_reads;
+        self
+    }
+
+    /// Returns true if reads of data not synced yet are delegated to the sequencer
+    pub fn delegates_reads(&self) -> bool {
+        self.delegate_reads
+    }
+
+    /// Calls a read method on the sequencer, returning its result as is
+    pub async fn delegated_read(
+        &self,
+        method: &str,
+        params: Vec<serde_json::Value>,
+    ) -> anyhow::Result<serde_json::Value> {
+        let mut array_params = ArrayParams::new();
+        for param in params {
+            array_params.insert(param)?;
+        }
+        Ok(self.client.request(method, array_params).await?)
+    }
+
+    /// Gets the number of the latest l2 block of the sequencer
+    pub async fn block_number(&self) -> anyhow::Result<u64> {
+        let number: U64 = self
+            .client
+            .request("eth_blockNumber", rpc_params![])
+            .await?;
+        Ok(number.to())
+    }
+
+    /// Gets the receipts of the l2 blocks in `from..=to` computed by the sequencer.
+    /// The sequencer may return fewer blocks than requested.
+    pub async fn get_block_receipts_range(
+        &self,
+        from: u64,
+        to: u64,
+    ) -> anyhow::Result<Vec<BlockReceipts>> {
+        Ok(self
+            .client
+            .request("citrea_getBlockReceiptsRange", rpc_params![from, to])
+            .await?)
     }
 
     /// Gets l2 block given l2 height
@@ -29,6 +151,22 @@ impl SequencerClient {
         &self,
         num: u64,
     ) -> anyhow::Result<Option<GetSoftBatchResponse>> {
+        let soft_batch = self.request_soft_batch(num).await?;
+        *self.last_soft_batch_response.lock().unwrap() = Some(Instant::now());
+        Ok(soft_batch)
+    }
+
+    async fn request_soft_batch(&self, num: u64) -> anyhow::Result<Option<GetSoftBatchResponse>> {
+        if self.compact_relay {
+            match self.get_compact_soft_batch(num).await {
+                Ok(soft_batch) => return Ok(soft_batch),
+                Err(e) => warn!(
+                    "Failed to get compact soft batch {}, getting the full batch: {:?}",
+                    num, e
+                ),
+            }
+        }
+
         let res: Result<Option<GetSoftBatchResponse>, jsonrpsee::core::Error> = self
             .client
             .request("ledger_getSoftBatchByNumber", rpc_params![num])
@@ -43,12 +181,54 @@ impl SequencerClient {
         }
     }
 
+    /// Gets l2 block given l2 height in compact form and rebuilds its transactions
+    async fn get_compact_soft_batch(
+        &self,
+        num: u64,
+    ) -> anyhow::Result<Option<GetSoftBatchResponse>> {
+        let compact: Option<CompactSoftBatch<GetSoftBatchResponse>> = self
+            .client
+            .request("citrea_getCompactSoftBatchByNumber", rpc_params![num])
+            .await?;
+        let Some(CompactSoftBatch {
+            mut soft_batch,
+            compact_txs,
+        }) = compact
+        else {
+            return Ok(None);
+        };
+
+        let (mut bodies, missing) = self
+            .forwarded_txs
+            .lock()
+            .unwrap()
+            .lookup(compact_txs.iter().flat_map(CompactTx::body_hashes));
+        if !missing.is_empty() {
+            let fetched: Vec<Bytes> = self
+                .client
+                .request("citrea_getSoftBatchTxBodies", rpc_params![num, &missing])
+                .await?;
+            for body in fetched {
+                bodies.insert(keccak256(&body), body.to_vec());
+            }
+        }
+
+        let txs = compact_txs
+            .iter()
+            .map(|tx| tx.expand(&bodies).map(HexTx::from))
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| anyhow::anyhow!("Missing transaction bodies of soft batch {}", num))?;
+        soft_batch.txs = Some(txs);
+        Ok(Some(soft_batch))
+    }
+
     /// Sends raw tx to sequencer
     pub async fn send_raw_tx(&self, tx: Bytes) -> anyhow::Result<H256> {
         let tx_hash: H256 = self
             .client
-            .request("eth_sendRawTransaction", rpc_params![tx])
+            .request("eth_sendRawTransaction", rpc_params![tx.clone()])
             .await?;
+        self.forwarded_txs.lock().unwrap().insert(tx.to_vec());
         Ok(tx_hash)
     }
 
@@ -66,9 +246,71 @@ impl SequencerClient {
             .await?;
         Ok(tx)
     }
+
+    /// Gets the nonce of the next transaction of `address`, counting its transactions
+    /// in the mempool of the sequencer
+    pub async fn get_pending_nonce(&self, address: Address) -> anyhow::Result<U64> {
+        let nonce: U64 = self
+            .client
+            .request(
+                "eth_getTransactionCount",
+                rpc_params![address, BlockNumberOrTag::Pending],
+            )
+            .await?;
+        Ok(nonce)
+    }
+
+    /// Gets the pending and queued transactions of the mempool of the sequencer
+    pub async fn txpool_content(&self) -> anyhow::Result<TxpoolContent> {
+        Ok(self.client.request("txpool_content", rpc_params![]).await?)
+    }
+
+    /// Gets the number of pending and queued transactions in the mempool of the sequencer
+    pub async fn txpool_status(&self) -> anyhow::Result<TxpoolStatus> {
+        Ok(self.client.request("txpool_status", rpc_params![]).await?)
+    }
+
+    /// Gets a summary of the pending and queued transactions of the mempool of the sequencer
+    pub async fn txpool_inspect(&self) -> anyhow::Result<TxpoolInspect> {
+        Ok(self.client.request("txpool_inspect", rpc_params![]).await?)
+    }
+
+    /// Pushes a soft batch to the full node or relay the client is connected to
+    pub async fn push_soft_batch(&self, pushed: &PushedSoftBatch) -> anyhow::Result<()> {
+        Ok(self
+            .client
+            .request("citrea_pushSoftBatch", rpc_params![pushed])
+            .await?)
+    }
 }
 
-#[derive(Deserialize, Debug, Clone)]
+/// A random id of the full node process, sent in [`FULL_NODE_ID_HEADER`] by all its clients.
+fn full_node_id() -> &'static str {
+    static FULL_NODE_ID: OnceLock<String> = OnceLock::new();
+    FULL_NODE_ID.get_or_init(|| {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let seed = [
+            nanos.to_be_bytes().as_slice(),
+            &std::process::id().to_be_bytes(),
+        ]
+        .concat();
+        hex::encode(&keccak256(seed)[..8])
+    })
+}
+
+/// Receipts of the transactions of an l2 block, as computed by the sequencer
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockReceipts {
+    pub number: u64,
+    pub hash: B256,
+    pub receipts: Vec<reth_rpc_types::TransactionReceipt>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GetSoftBatchResponse {
     #[serde(with = "hex::serde")]
     pub hash: [u8; 32],
@@ -91,6 +333,52 @@ pub struct GetSoftBatchResponse {
     pub timestamp: u64,
 }
 
+impl From<SoftBatchResponse> for GetSoftBatchResponse {
+    fn from(val: SoftBatchResponse) -> Self {
+        Self {
+            hash: val.hash,
+            da_slot_height: val.da_slot_height,
+            da_slot_hash: val.da_slot_hash,
+            da_slot_txs_commitment: val.da_slot_txs_commitment,
+            txs: val.txs,
+            pre_state_root: val.pre_state_root,
+            post_state_root: val.post_state_root,
+            soft_confirmation_signature: val.soft_confirmation_signature,
+            pub_key: val.pub_key,
+            l1_fee_rate: val.l1_fee_rate,
+            timestamp: val.timestamp,
+        }
+    }
+}
+
+/// A soft batch pushed by the sequencer to full nodes as soon as it is produced, either
+/// directly or through relays, so that they don't wait for their next poll.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PushedSoftBatch {
+    /// L2 height of the soft batch.
+    pub height: u64,
+    /// The soft batch.
+    pub soft_batch: GetSoftBatchResponse,
+    /// Signature of the sequencer over [`PushedSoftBatch::message`]. The soft confirmation
+    /// signature doesn't cover the height and the post state root, so relays can't alter them.
+    #[serde(with = "hex::serde")]
+    pub push_signature: Vec<u8>,
+}
+
+impl PushedSoftBatch {
+    /// Returns the message signed by the sequencer in `push_signature`: the height,
+    /// the hash and the post state root of the soft batch.
+    pub fn message(height: u64, soft_batch: &GetSoftBatchResponse) -> Vec<u8> {
+        [
+            height.to_be_bytes().as_slice(),
+            &soft_batch.hash,
+            &soft_batch.post_state_root,
+        ]
+        .concat()
+    }
+}
+
 impl From<GetSoftBatchResponse> for SignedSoftConfirmationBatch {
     fn from(val: GetSoftBatchResponse) -> Self {
         SignedSoftConfirmationBatch::new(
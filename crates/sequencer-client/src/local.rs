@@ -0,0 +1,50 @@
+//! Access to the mempool of the sequencer from the RPC of the sequencer node itself.
+//!
+//! Full nodes read the mempool of the sequencer through a [`SequencerClient`](crate::SequencerClient),
+//! the sequencer node is handed a [`LocalMempool`] instead.
+
+use std::sync::Arc;
+
+use reth_primitives::{Address, TxHash};
+use serde::{Deserialize, Serialize};
+use tokio::sync::{broadcast, mpsc};
+
+/// Returns the effective tips per gas of the executable transactions of the mempool,
+/// given the base fee of the next block.
+pub type MempoolTipsSource = Arc<dyn Fn(u64) -> Vec<u128> + Send + Sync>;
+
+/// Returns a new receiver of the hashes of the transactions becoming executable in the mempool.
+pub type PendingTransactionsSource = Arc<dyn Fn() -> mpsc::Receiver<TxHash> + Send + Sync>;
+
+/// Records the approximate memory used by the RPC caches of the node, in bytes.
+pub type RpcCachesUsageSink = Arc<dyn Fn(u64) + Send + Sync>;
+
+/// Returns a new receiver of the transactions replaced in the mempool.
+pub type ReplacedTransactionsSource =
+    Arc<dyn Fn() -> broadcast::Receiver<ReplacedTransaction> + Send + Sync>;
+
+/// A pending transaction replaced by a transaction of the same sender with the same nonce
+/// and a higher fee.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplacedTransaction {
+    /// Hash of the replaced transaction.
+    pub hash: TxHash,
+    /// Hash of the transaction replacing it.
+    pub replaced_by: TxHash,
+    /// Sender of both transactions.
+    pub from: Address,
+}
+
+/// The mempool of the sequencer, read by the RPC of the sequencer node.
+#[derive(Clone)]
+pub struct LocalMempool {
+    /// Tips offered by the executable transactions of the mempool.
+    pub tips: MempoolTipsSource,
+    /// Transactions becoming executable in the mempool.
+    pub pending_transactions: PendingTransactionsSource,
+    /// Transactions replaced in the mempool.
+    pub replaced_transactions: ReplacedTransactionsSource,
+    /// Memory budget of the sequencer, counting the RPC caches along with the mempool.
+    pub rpc_caches_usage: RpcCachesUsageSink,
+}
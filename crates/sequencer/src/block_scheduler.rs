@@ -0,0 +1,87 @@
+use std::time::{Duration, Instant};
+
+use crate::metrics::{SEQUENCER_BLOCK_TIME_SECONDS, SEQUENCER_MISSED_BLOCK_SLOTS};
+
+/// Schedules blocks on a fixed grid of deadlines, `interval` apart.
+///
+/// The next deadline is computed from the previous one rather than from when the block
+/// started or finished, so the time spent executing blocks and talking to the DA layer
+/// doesn't add up into drift: a block started late is followed by a shorter wait.
+/// When production falls more than a whole interval behind, the missed slots are skipped
+/// instead of producing a burst of blocks to catch up.
+#[derive(Debug)]
+pub(crate) struct BlockScheduler {
+    interval: Duration,
+    next_deadline: Instant,
+    last_block_start: Option<Instant>,
+}
+
+impl BlockScheduler {
+    /// Creates a scheduler whose first block is due at `now`
+    pub(crate) fn new(interval: Duration, now: Instant) -> Self {
+        Self {
+            interval,
+            next_deadline: now,
+            last_block_start: None,
+        }
+    }
+
+    /// Returns the time the next scheduled block is due at
+    pub(crate) fn next_deadline(&self) -> Instant {
+        self.next_deadline
+    }
+
+    /// Records the start of a block and schedules the next one.
+    /// Blocks started before their deadline, like the ones forced through RPC,
+    /// don't move the schedule.
+    pub(crate) fn block_started(&mut self, started: Instant) {
+        if let Some(last_block_start) = self.last_block_start {
+            SEQUENCER_BLOCK_TIME_SECONDS.observe((started - last_block_start).as_secs_f64());
+        }
+        self.last_block_start = Some(started);
+
+        if started < self.next_deadline {
+            return;
+        }
+        let missed_slots = self.advance(started);
+        if missed_slots > 0 {
+            SEQUENCER_MISSED_BLOCK_SLOTS.inc_by(missed_slots);
+        }
+    }
+
+    /// Moves the deadline past `started`, returning the number of skipped slots
+    fn advance(&mut self, started: Instant) -> u64 {
+        let lateness = started - self.next_deadline;
+        let missed_slots = (lateness.as_nanos() / self.interval.as_nanos().max(1)) as u64;
+        self.next_deadline += self.interval * (missed_slots as u32 + 1);
+        missed_slots
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compensates_for_late_blocks() {
+        let interval = Duration::from_secs(2);
+        let start = Instant::now();
+        let mut scheduler = BlockScheduler::new(interval, start);
+        assert_eq!(scheduler.next_deadline(), start);
+
+        scheduler.block_started(start);
+        assert_eq!(scheduler.next_deadline(), start + interval);
+
+        // a block started late keeps the grid, the next one waits less
+        scheduler.block_started(start + Duration::from_millis(2300));
+        assert_eq!(scheduler.next_deadline(), start + 2 * interval);
+
+        // forced blocks don't move the schedule
+        scheduler.block_started(start + Duration::from_millis(3000));
+        assert_eq!(scheduler.next_deadline(), start + 2 * interval);
+
+        // slots missed by more than an interval are skipped
+        scheduler.block_started(start + Duration::from_millis(8500));
+        assert_eq!(scheduler.next_deadline(), start + 5 * interval);
+    }
+}
@@ -108,6 +108,22 @@ pub fn get_commitment_info(
     })
 }
 
+/// Returns the hashes of the soft confirmations in `l2_height_range`, the leaves of a commitment.
+pub fn get_soft_confirmation_hashes(
+    ledger_db: &LedgerDB,
+    l2_height_range: &RangeInclusive<BatchNumber>,
+) -> Vec<[u8; 32]> {
+    // calculate exclusive range end
+    let range_end = BatchNumber(l2_height_range.end().0 + 1); // cannnot add u64 to BatchNumber directly
+
+    ledger_db
+        .get_soft_batch_range(&(*l2_height_range.start()..range_end))
+        .expect("Sequencer: Failed to get soft batch range")
+        .iter()
+        .map(|sb| sb.hash)
+        .collect()
+}
+
 pub fn get_commitment(
     commitment_info: CommitmentInfo,
     soft_confirmation_hashes: Vec<[u8; 32]>,
@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use citrea_evm::{Evm, QueryContext};
+use reth_primitives::{Address, TxHash, B256, U256, U64};
+use serde::{Deserialize, Serialize};
+use sov_modules_api::WorkingSet;
+
+/// The maximum number of storage slots checked by the conditions of a transaction,
+/// the limit of the other rollups serving `eth_sendRawTransactionConditional`.
+pub(crate) const MAX_KNOWN_ACCOUNTS_COST: usize = 1000;
+
+/// Gas charged to the sender of a conditional transaction for each storage slot its conditions
+/// check, the cost of a cold storage read.
+pub(crate) const KNOWN_SLOT_VALIDATION_GAS: u64 = 2100;
+
+/// Conditions of a transaction submitted through `eth_sendRawTransactionConditional`.
+/// The transaction is only included in a block at which all of them hold.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TransactionConditional {
+    /// Expected values of storage slots of accounts at the start of the block.
+    #[serde(default)]
+    pub known_accounts: HashMap<Address, KnownAccount>,
+    #[serde(default)]
+    pub block_number_min: Option<U64>,
+    #[serde(default)]
+    pub block_number_max: Option<U64>,
+    #[serde(default)]
+    pub timestamp_min: Option<U64>,
+    #[serde(default)]
+    pub timestamp_max: Option<U64>,
+}
+
+/// Expected storage of an account.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum KnownAccount {
+    /// Storage root of the account, not supported as accounts have no storage root.
+    StorageRoot(B256),
+    /// Values of storage slots by slot.
+    Slots(HashMap<B256, B256>),
+}
+
+impl TransactionConditional {
+    /// Checks that the conditions can be served, before checking whether they hold.
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        let mut cost = 0;
+        for known_account in self.known_accounts.values() {
+            match known_account {
+                KnownAccount::StorageRoot(_) => {
+                    return Err("storage root conditions are not supported".to_string())
+                }
+                KnownAccount::Slots(slots) => cost += slots.len(),
+            }
+        }
+        if cost > MAX_KNOWN_ACCOUNTS_COST {
+            return Err(format!(
+                "too many known account slots, at most {} are allowed",
+                MAX_KNOWN_ACCOUNTS_COST
+            ));
+        }
+        Ok(())
+    }
+
+    /// Gas the sequencer spends checking the conditions, which isn't paid for if they don't hold.
+    pub(crate) fn validation_gas(&self) -> u64 {
+        let slots: usize = self
+            .known_accounts
+            .values()
+            .map(|known_account| match known_account {
+                KnownAccount::StorageRoot(_) => 1,
+                KnownAccount::Slots(slots) => slots.len(),
+            })
+            .sum();
+        slots as u64 * KNOWN_SLOT_VALIDATION_GAS
+    }
+
+    /// Checks the conditions for a block at `block_number` and `timestamp` built on top of
+    /// the state of `working_set`, returning the first one that doesn't hold.
+    pub(crate) fn check<C: sov_modules_api::Context>(
+        &self,
+        evm: &Evm<C>,
+        working_set: &mut WorkingSet<C>,
+        block_number: u64,
+        timestamp: u64,
+    ) -> Result<(), String> {
+        let in_range = |value: u64, min: Option<U64>, max: Option<U64>| {
+            min.map_or(true, |min| value >= min.to::<u64>())
+                && max.map_or(true, |max| value <= max.to::<u64>())
+        };
+        if !in_range(block_number, self.block_number_min, self.block_number_max) {
+            return Err(format!("block number {} out of range", block_number));
+        }
+        if !in_range(timestamp, self.timestamp_min, self.timestamp_max) {
+            return Err(format!("timestamp {} out of range", timestamp));
+        }
+
+        for (address, known_account) in &self.known_accounts {
+            let KnownAccount::Slots(slots) = known_account else {
+                return Err("storage root conditions are not supported".to_string());
+            };
+            for (slot, expected) in slots {
+                // the latest state needs no node-scoped query context
+                let value = evm
+                    .get_storage_at(
+                        &QueryContext::default(),
+                        *address,
+                        U256::from_be_bytes(slot.0),
+                        None,
+                        working_set,
+                    )
+                    .map_err(|e| e.message().to_string())?;
+                if value != *expected {
+                    return Err(format!("storage slot {} of {} changed", slot, address));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Conditions of the transactions submitted through `eth_sendRawTransactionConditional`
+/// that are still in the mempool.
+#[derive(Default)]
+pub(crate) struct ConditionalTransactions {
+    inner: Mutex<HashMap<TxHash, TransactionConditional>>,
+}
+
+impl ConditionalTransactions {
+    pub(crate) fn insert(&self, hash: TxHash, conditional: TransactionConditional) {
+        self.inner.lock().unwrap().insert(hash, conditional);
+    }
+
+    pub(crate) fn get(&self, hash: &TxHash) -> Option<TransactionConditional> {
+        self.inner.lock().unwrap().get(hash).cloned()
+    }
+
+    /// Gas spent checking the conditions of a transaction, 0 if it has none.
+    pub(crate) fn validation_gas(&self, hash: &TxHash) -> u64 {
+        self.inner
+            .lock()
+            .unwrap()
+            .get(hash)
+            .map_or(0, TransactionConditional::validation_gas)
+    }
+
+    pub(crate) fn remove(&self, hash: &TxHash) {
+        self.inner.lock().unwrap().remove(hash);
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.inner.lock().unwrap().is_empty()
+    }
+
+    /// Forgets the conditions of the transactions for which `keep` is false,
+    /// e.g. the ones not in the mempool anymore.
+    pub(crate) fn retain(&self, mut keep: impl FnMut(&TxHash) -> bool) {
+        self.inner.lock().unwrap().retain(|hash, _| keep(hash));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_validates_conditionals() {
+        let conditional: TransactionConditional = serde_json::from_value(serde_json::json!({
+            "knownAccounts": {
+                "0x000000000000000000000000000000000000dead": {
+                    "0x0000000000000000000000000000000000000000000000000000000000000001":
+                        "0x0000000000000000000000000000000000000000000000000000000000000002"
+                }
+            },
+            "blockNumberMin": "0x10",
+            "timestampMax": "0x20"
+        }))
+        .unwrap();
+        assert_eq!(conditional.block_number_min, Some(U64::from(16)));
+        assert_eq!(conditional.block_number_max, None);
+        assert_eq!(conditional.timestamp_max, Some(U64::from(32)));
+        assert!(conditional.validate().is_ok());
+        assert_eq!(conditional.validation_gas(), KNOWN_SLOT_VALIDATION_GAS);
+
+        let storage_root: TransactionConditional = serde_json::from_value(serde_json::json!({
+            "knownAccounts": {
+                "0x000000000000000000000000000000000000dead":
+                    "0x56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421"
+            }
+        }))
+        .unwrap();
+        assert!(storage_root.validate().is_err());
+
+        let slots = (0..=MAX_KNOWN_ACCOUNTS_COST as u64)
+            .map(|slot| (B256::from(U256::from(slot)), B256::ZERO))
+            .collect();
+        let too_costly = TransactionConditional {
+            known_accounts: HashMap::from([(Address::ZERO, KnownAccount::Slots(slots))]),
+            ..Default::default()
+        };
+        assert!(too_costly.validate().is_err());
+    }
+}
@@ -1,5 +1,10 @@
+use std::path::PathBuf;
+
+use reth_primitives::{address, Address};
 use serde::Deserialize;
 
+use crate::misbehavior::ScriptedMisbehavior;
+
 /// Rollup Configuration
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct SequencerConfig {
@@ -7,6 +12,30 @@ pub struct SequencerConfig {
     pub min_soft_confirmations_per_commitment: u64,
     /// Sequencer specific mempool config
     pub mempool_conf: SequencerMempoolConfig,
+    /// Limits on gas the sequencer spends validating transactions that may not pay for it
+    #[serde(default)]
+    pub validation_gas_policy: ValidationGasPolicyConfig,
+    /// Max megabytes of memory used by the mempools, the block being built and the Ethereum
+    /// RPC caches. New transactions are rejected once it is reached. Unlimited if not set.
+    #[serde(default)]
+    pub memory_budget_mb: Option<u64>,
+    /// Admission rate limits of `eth_sendRawTransaction`
+    #[serde(default)]
+    pub rate_limits: RateLimitConfig,
+    /// Milliseconds between the L2 blocks produced by the sequencer on its own.
+    /// Blocks are only produced when requested through `eth_publishBatch` if not set.
+    #[serde(default)]
+    pub block_production_interval_ms: Option<u64>,
+    /// RPC URLs of the full nodes and relays the sequencer pushes its soft batches to through
+    /// `citrea_pushSoftBatch` as soon as they are produced. Full nodes keep polling the
+    /// sequencer for the soft batches that don't reach them.
+    #[serde(default)]
+    pub push_soft_batches_to: Vec<String>,
+    /// Soft confirmations to tamper with, to test how full nodes and provers reject them.
+    /// Only applied by builds with the `testing` feature, other builds refuse to start
+    /// the sequencer if it isn't empty.
+    #[serde(default)]
+    pub test_misbehaviors: Vec<ScriptedMisbehavior>,
 }
 
 /// Mempool Config for the sequencer
@@ -27,6 +56,20 @@ pub struct SequencerMempoolConfig {
     pub base_fee_tx_size: u64,
     /// Max number of executable transaction slots guaranteed per account
     pub max_account_slots: u64,
+    /// Seconds a transaction can stay in the pool before being evicted, disabled if 0 or not set
+    #[serde(default)]
+    pub transaction_ttl_secs: u64,
+    /// Directory where transactions are spilled when the pool is full, to be added back
+    /// once blocks free space. Transactions are rejected when the pool is full if not set.
+    #[serde(default)]
+    pub overflow_dir: Option<PathBuf>,
+    /// Max number of transactions spilled to the overflow directory
+    #[serde(default = "default_overflow_tx_limit")]
+    pub overflow_tx_limit: u64,
+}
+
+const fn default_overflow_tx_limit() -> u64 {
+    100000
 }
 
 impl Default for SequencerMempoolConfig {
@@ -39,6 +82,91 @@ impl Default for SequencerMempoolConfig {
             base_fee_tx_limit: 100000,
             base_fee_tx_size: 200,
             max_account_slots: 16,
+            transaction_ttl_secs: 0,
+            overflow_dir: None,
+            overflow_tx_limit: default_overflow_tx_limit(),
+        }
+    }
+}
+
+/// Admission rate limits protecting the public endpoint from transaction floods.
+/// A limit that is not set is disabled.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct RateLimitConfig {
+    /// Limit per source IP, checked by the RPC server before the calls are handled.
+    /// The source IP is read from the `X-Forwarded-For` header, see `trusted_proxy_hops`.
+    pub per_ip: Option<RateLimit>,
+    /// Number of trusted reverse proxies in front of the sequencer, each appending the address
+    /// it received the request from to `X-Forwarded-For`. The source IP is the entry appended
+    /// by the outermost one, the entries before it are set by the client and ignored.
+    /// Requests with fewer entries share a single bucket.
+    #[serde(default = "default_trusted_proxy_hops")]
+    pub trusted_proxy_hops: usize,
+    /// Limit per sender address, checked before transactions are added to the mempool
+    pub per_sender: Option<RateLimit>,
+}
+
+const fn default_trusted_proxy_hops() -> usize {
+    1
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            per_ip: None,
+            trusted_proxy_hops: default_trusted_proxy_hops(),
+            per_sender: None,
+        }
+    }
+}
+
+/// Token bucket limit, allowing `burst` transactions at once then `per_second` transactions per second.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct RateLimit {
+    /// Sustained transactions per second
+    pub per_second: u64,
+    /// Max transactions at once
+    pub burst: u64,
+}
+
+/// Policy limiting the validation gas a sender can make the sequencer spend per block.
+///
+/// Calls to entry points, like ERC-4337 `handleOps`, run user operation validation before
+/// anything is paid for, and the conditions of `eth_sendRawTransactionConditional` transactions
+/// are checked before they are included, so a sender can make the sequencer do this work for
+/// free by letting validation fail. The verification gas limits of the user operations are
+/// charged to their senders, and the storage reads of the conditions to the transaction senders.
+/// Transactions exceeding the cap of a sender stay in the mempool for the next blocks,
+/// along with the later transactions of their sender.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ValidationGasPolicyConfig {
+    /// Contracts validating user operations before executing them
+    #[serde(default = "default_entry_points")]
+    pub entry_points: Vec<Address>,
+    /// Max validation gas charged to a sender per block, 0 disables the cap.
+    /// Entry point calls that are not `handleOps` are charged their whole gas limit
+    #[serde(default = "default_max_validation_gas_per_sender")]
+    pub max_validation_gas_per_sender: u64,
+}
+
+fn default_entry_points() -> Vec<Address> {
+    vec![
+        // ERC-4337 v0.6 entry point
+        address!("5FF137D4b0FDCD49DcA30c7CF57E578a026d2789"),
+        // ERC-4337 v0.7 entry point
+        address!("0000000071727De22E5E9d8BAf0edAc6f37da032"),
+    ]
+}
+
+const fn default_max_validation_gas_per_sender() -> u64 {
+    5_000_000
+}
+
+impl Default for ValidationGasPolicyConfig {
+    fn default() -> Self {
+        Self {
+            entry_points: default_entry_points(),
+            max_validation_gas_per_sender: default_max_validation_gas_per_sender(),
         }
     }
 }
@@ -51,6 +179,7 @@ mod tests {
     use tempfile::NamedTempFile;
 
     use super::*;
+    use crate::misbehavior::Misbehavior;
 
     fn create_config_from(content: &str) -> NamedTempFile {
         let mut config_file = NamedTempFile::new().unwrap();
@@ -86,8 +215,158 @@ mod tests {
                 base_fee_tx_limit: 100000,
                 base_fee_tx_size: 200,
                 max_account_slots: 16,
+                transaction_ttl_secs: 0,
+                overflow_dir: None,
+                overflow_tx_limit: 100000,
             },
+            validation_gas_policy: ValidationGasPolicyConfig::default(),
+            memory_budget_mb: None,
+            rate_limits: RateLimitConfig::default(),
+            block_production_interval_ms: None,
+            push_soft_batches_to: vec![],
+            test_misbehaviors: vec![],
         };
         assert_eq!(config, expected);
     }
+
+    #[test]
+    fn test_mempool_config_transaction_ttl() {
+        let config = r#"
+            min_soft_confirmations_per_commitment = 123
+            [mempool_conf]
+            pending_tx_limit = 100000
+            pending_tx_size = 200
+            queue_tx_limit = 100000
+            queue_tx_size = 200
+            base_fee_tx_limit = 100000
+            base_fee_tx_size = 200
+            max_account_slots = 16
+            transaction_ttl_secs = 60
+        "#;
+
+        let config_file = create_config_from(config);
+
+        let config: SequencerConfig = from_toml_path(config_file.path()).unwrap();
+        assert_eq!(config.mempool_conf.transaction_ttl_secs, 60);
+    }
+
+    #[test]
+    fn test_memory_budget_config() {
+        let config = r#"
+            min_soft_confirmations_per_commitment = 123
+            memory_budget_mb = 4096
+            [mempool_conf]
+            pending_tx_limit = 100000
+            pending_tx_size = 200
+            queue_tx_limit = 100000
+            queue_tx_size = 200
+            base_fee_tx_limit = 100000
+            base_fee_tx_size = 200
+            max_account_slots = 16
+        "#;
+
+        let config_file = create_config_from(config);
+
+        let config: SequencerConfig = from_toml_path(config_file.path()).unwrap();
+        assert_eq!(config.memory_budget_mb, Some(4096));
+    }
+
+    #[test]
+    fn test_rate_limits_config() {
+        let config = r#"
+            min_soft_confirmations_per_commitment = 123
+            [mempool_conf]
+            pending_tx_limit = 100000
+            pending_tx_size = 200
+            queue_tx_limit = 100000
+            queue_tx_size = 200
+            base_fee_tx_limit = 100000
+            base_fee_tx_size = 200
+            max_account_slots = 16
+            [rate_limits]
+            per_sender = { per_second = 2, burst = 16 }
+        "#;
+
+        let config_file = create_config_from(config);
+
+        let config: SequencerConfig = from_toml_path(config_file.path()).unwrap();
+        assert_eq!(
+            config.rate_limits,
+            RateLimitConfig {
+                per_ip: None,
+                trusted_proxy_hops: 1,
+                per_sender: Some(RateLimit {
+                    per_second: 2,
+                    burst: 16,
+                }),
+            }
+        );
+    }
+
+    #[test]
+    fn test_misbehaviors_config() {
+        let config = r#"
+            min_soft_confirmations_per_commitment = 123
+            [mempool_conf]
+            pending_tx_limit = 100000
+            pending_tx_size = 200
+            queue_tx_limit = 100000
+            queue_tx_size = 200
+            base_fee_tx_limit = 100000
+            base_fee_tx_size = 200
+            max_account_slots = 16
+            [[test_misbehaviors]]
+            l2_height = 3
+            misbehavior = "invalid_state_root"
+            [[test_misbehaviors]]
+            l2_height = 5
+            misbehavior = "conflicting_batch"
+        "#;
+
+        let config_file = create_config_from(config);
+
+        let config: SequencerConfig = from_toml_path(config_file.path()).unwrap();
+        assert_eq!(
+            config.test_misbehaviors,
+            vec![
+                ScriptedMisbehavior {
+                    l2_height: 3,
+                    misbehavior: Misbehavior::InvalidStateRoot,
+                },
+                ScriptedMisbehavior {
+                    l2_height: 5,
+                    misbehavior: Misbehavior::ConflictingBatch,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validation_gas_policy_config() {
+        let config = r#"
+            min_soft_confirmations_per_commitment = 123
+            [mempool_conf]
+            pending_tx_limit = 100000
+            pending_tx_size = 200
+            queue_tx_limit = 100000
+            queue_tx_size = 200
+            base_fee_tx_limit = 100000
+            base_fee_tx_size = 200
+            max_account_slots = 16
+            [validation_gas_policy]
+            entry_points = ["0x0000000000000000000000000000000000000001"]
+            max_validation_gas_per_sender = 1000000
+        "#;
+
+        let config_file = create_config_from(config);
+
+        let config: SequencerConfig = from_toml_path(config_file.path()).unwrap();
+        assert_eq!(
+            config.validation_gas_policy,
+            ValidationGasPolicyConfig {
+                entry_points: vec![address!("0000000000000000000000000000000000000001")],
+                max_validation_gas_per_sender: 1_000_000,
+            }
+        );
+    }
 }
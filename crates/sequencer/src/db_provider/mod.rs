@@ -32,6 +32,15 @@ impl<C: sov_modules_api::Context> DbProvider<C> {
         self.evm.get_chain_config(&mut working_set)
     }
 
+    /// Returns the base fee of the next block.
+    pub fn next_block_base_fee(&self) -> u64 {
+        let mut working_set = WorkingSet::<C>::new(self.storage.clone());
+        let parent = self.evm.last_sealed_header(&mut working_set).unseal();
+        self.evm
+            .next_block_base_fee(&parent, &mut working_set)
+            .expect("Failed to get next block base fee")
+    }
+
     pub fn last_block_tx_hashes(&self) -> Vec<B256> {
         let mut working_set = WorkingSet::<C>::new(self.storage.clone());
         let rich_block = self
@@ -0,0 +1,209 @@
+use std::sync::Mutex;
+
+use citrea_evm::{
+    EncryptedBatch, EncryptedMempoolConfig, EncryptedTransaction, EpochKeyAnnouncement,
+    EpochKeyRelease,
+};
+use reth_primitives::B256;
+use secp256k1::PublicKey;
+
+/// Encrypted transactions and epoch key shares waiting to be included by the sequencer.
+///
+/// Users encrypt their transactions to the key of the current epoch, announced by the keypers
+/// before the epoch started, and the sequencer orders them during the epoch. Once the epoch ended
+/// the keypers release their shares of the epoch secret key, and the EVM module decrypts the
+/// transactions of the epoch in the block including the last share. The sequencer never holds
+/// a key it could decrypt the transactions it orders with.
+#[derive(Default)]
+pub(crate) struct EncryptedMempool {
+    inner: Mutex<EncryptedMempoolInner>,
+}
+
+#[derive(Default)]
+struct EncryptedMempoolInner {
+    /// Transactions submitted and not ordered yet, in submission order.
+    pending: Vec<EncryptedTransaction>,
+    /// Public key shares not included yet.
+    announcements: Vec<EpochKeyAnnouncement>,
+    /// Secret key shares not included yet.
+    releases: Vec<EpochKeyRelease>,
+}
+
+impl EncryptedMempool {
+    /// Adds a transaction encrypted to the key of the current epoch or a later one,
+    /// returns its hash.
+    pub(crate) fn add_transaction(
+        &self,
+        transaction: EncryptedTransaction,
+        current_epoch: u64,
+    ) -> anyhow::Result<B256> {
+        anyhow::ensure!(
+            !transaction.ciphertext.is_empty(),
+            "Empty encrypted transaction"
+        );
+        anyhow::ensure!(
+            transaction.epoch >= current_epoch,
+            "Epoch {} already ended",
+            transaction.epoch
+        );
+        PublicKey::from_slice(&transaction.ephemeral_key)?;
+
+        let hash = transaction.hash();
+        let mut inner = self.inner.lock().unwrap();
+        anyhow::ensure!(
+            !inner.pending.iter().any(|pending| pending.hash() == hash),
+            "Encrypted transaction {} already known",
+            hash
+        );
+        inner.pending.push(transaction);
+        Ok(hash)
+    }
+
+    /// Adds a keyper's public key share, verified by the EVM module when included.
+    pub(crate) fn add_announcement(&self, announcement: EpochKeyAnnouncement) {
+        let mut inner = self.inner.lock().unwrap();
+        if !inner.announcements.contains(&announcement) {
+            inner.announcements.push(announcement);
+        }
+    }
+
+    /// Adds a keyper's secret key share, verified by the EVM module when included.
+    pub(crate) fn add_release(&self, release: EpochKeyRelease) {
+        let mut inner = self.inner.lock().unwrap();
+        if !inner.releases.contains(&release) {
+            inner.releases.push(release);
+        }
+    }
+
+    /// Returns the encrypted mempool part of the next block, or None if there is nothing to do.
+    ///
+    /// `block_number` is the number of the next block, and `epoch_key_announced` whether every
+    /// keyper announced its share of the key of its epoch, without which no transaction
+    /// can be ordered.
+    pub(crate) fn next_batch(
+        &self,
+        config: &EncryptedMempoolConfig,
+        block_number: u64,
+        epoch_key_announced: bool,
+    ) -> Option<EncryptedBatch> {
+        let inner = self.inner.lock().unwrap();
+        let epoch = config.epoch_of(block_number);
+
+        let ordered = match epoch_key_announced {
+            true => inner
+                .pending
+                .iter()
+                .filter(|transaction| transaction.epoch == epoch)
+                .cloned()
+                .collect(),
+            false => vec![],
+        };
+        let batch = EncryptedBatch {
+            ordered,
+            announcements: inner.announcements.clone(),
+            releases: inner.releases.clone(),
+        };
+        (!batch.is_empty()).then_some(batch)
+    }
+
+    /// Approximate memory used by the encrypted transactions and key shares, in bytes.
+    pub(crate) fn size_in_bytes(&self) -> u64 {
+        let inner = self.inner.lock().unwrap();
+        let pending: usize = inner
+            .pending
+            .iter()
+            .map(|transaction| 40 + transaction.ephemeral_key.len() + transaction.ciphertext.len())
+            .sum();
+        let announcements: usize = inner
+            .announcements
+            .iter()
+            .map(|announcement| {
+                8 + announcement.public_key.len()
+                    + announcement.signature.len()
+                    + announcement.proof_of_possession.len()
+            })
+            .sum();
+        (pending + announcements + inner.releases.len() * 60) as u64
+    }
+
+    /// Removes the transactions and key shares of a batch included in block `block_number`,
+    /// and the transactions whose epoch ended before they were ordered.
+    pub(crate) fn remove_included(
+        &self,
+        batch: &EncryptedBatch,
+        config: &EncryptedMempoolConfig,
+        block_number: u64,
+    ) {
+        let mut inner = self.inner.lock().unwrap();
+        let next_epoch = config.epoch_of(block_number + 1);
+        inner.pending.retain(|transaction| {
+            !batch.ordered.contains(transaction) && transaction.epoch >= next_epoch
+        });
+        inner
+            .announcements
+            .retain(|announcement| !batch.announcements.contains(announcement));
+        inner
+            .releases
+            .retain(|release| !batch.releases.contains(release));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reth_primitives::Address;
+    use secp256k1::{SecretKey, SECP256K1};
+
+    use super::*;
+
+    fn transaction(epoch: u64, ciphertext: &[u8]) -> EncryptedTransaction {
+        EncryptedTransaction::encrypt(
+            epoch,
+            &PublicKey::from_secret_key(SECP256K1, &SecretKey::from_slice(&[1; 32]).unwrap()),
+            &SecretKey::from_slice(&[2; 32]).unwrap(),
+            ciphertext,
+        )
+    }
+
+    #[test]
+    fn next_batch_orders_transactions_in_their_epoch() {
+        let config = EncryptedMempoolConfig {
+            epoch_blocks: 10,
+            expiry: 10,
+            keypers: vec![Address::from([1; 20])],
+        };
+        let mempool = EncryptedMempool::default();
+        let first = transaction(1, b"first");
+        let second = transaction(2, b"second");
+        mempool.add_transaction(first.clone(), 1).unwrap();
+        mempool.add_transaction(second.clone(), 1).unwrap();
+        assert!(mempool.add_transaction(first.clone(), 1).is_err());
+        assert!(mempool.add_transaction(transaction(0, b"late"), 1).is_err());
+
+        // Nothing is ordered before every keyper announced the epoch key
+        assert_eq!(mempool.next_batch(&config, 15, false), None);
+
+        let batch = mempool.next_batch(&config, 15, true).unwrap();
+        assert_eq!(batch.ordered, vec![first.clone()]);
+        mempool.remove_included(&batch, &config, 15);
+        assert_eq!(mempool.next_batch(&config, 16, true), None);
+
+        let release = EpochKeyRelease {
+            epoch: 1,
+            keyper: Address::from([1; 20]),
+            secret_key: B256::from([3; 32]),
+        };
+        mempool.add_release(release.clone());
+        mempool.add_release(release.clone());
+        let batch = mempool.next_batch(&config, 20, true).unwrap();
+        assert_eq!(batch.ordered, vec![second]);
+        assert_eq!(batch.releases, vec![release]);
+
+        // Transactions whose epoch ended without being ordered are dropped
+        mempool
+            .add_transaction(transaction(2, b"missed"), 2)
+            .unwrap();
+        mempool.remove_included(&EncryptedBatch::default(), &config, 29);
+        assert!(mempool.inner.lock().unwrap().pending.is_empty());
+        assert!(!mempool.inner.lock().unwrap().releases.is_empty());
+    }
+}
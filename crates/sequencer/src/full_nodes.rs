@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use hyper::Request;
+use sequencer_client::FULL_NODE_ID_HEADER;
+use tower::{Layer, Service};
+
+/// How long a full node is counted as connected after its last request.
+const FULL_NODE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Number of full nodes tracked before new ones are ignored, so that forged ids can't grow
+/// the tracker without bound.
+const MAX_TRACKED_FULL_NODES: usize = 10_000;
+
+/// Full nodes syncing from the sequencer, identified by the id they send in
+/// [`FULL_NODE_ID_HEADER`]. Serves `net_peerCount`.
+#[derive(Debug, Default)]
+pub(crate) struct FullNodeTracker {
+    last_seen: Mutex<HashMap<String, Instant>>,
+}
+
+impl FullNodeTracker {
+    /// Records a request of the full node `id`.
+    pub(crate) fn record(&self, id: &str, now: Instant) {
+        let mut last_seen = self.last_seen.lock().unwrap();
+        if !last_seen.contains_key(id) && last_seen.len() >= MAX_TRACKED_FULL_NODES {
+            last_seen.retain(|_, seen| now.duration_since(*seen) < FULL_NODE_TIMEOUT);
+            if last_seen.len() >= MAX_TRACKED_FULL_NODES {
+                return;
+            }
+        }
+        last_seen.insert(id.to_string(), now);
+    }
+
+    /// Returns the number of full nodes that sent a request in the last minute.
+    pub(crate) fn connected(&self, now: Instant) -> usize {
+        let mut last_seen = self.last_seen.lock().unwrap();
+        last_seen.retain(|_, seen| now.duration_since(*seen) < FULL_NODE_TIMEOUT);
+        last_seen.len()
+    }
+}
+
+/// A [`Layer`] recording the full nodes sending requests to the sequencer.
+#[derive(Debug, Clone)]
+pub(crate) struct FullNodeTrackingLayer {
+    tracker: Arc<FullNodeTracker>,
+}
+
+impl FullNodeTrackingLayer {
+    pub(crate) fn new(tracker: Arc<FullNodeTracker>) -> Self {
+        Self { tracker }
+    }
+}
+
+impl<S> Layer<S> for FullNodeTrackingLayer {
+    type Service = FullNodeTracking<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        FullNodeTracking {
+            inner,
+            tracker: self.tracker.clone(),
+        }
+    }
+}
+
+/// The service created by [`FullNodeTrackingLayer`].
+#[derive(Debug, Clone)]
+pub(crate) struct FullNodeTracking<S> {
+    inner: S,
+    tracker: Arc<FullNodeTracker>,
+}
+
+impl<S, B> Service<Request<B>> for FullNodeTracking<S>
+where
+    S: Service<Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<B>) -> Self::Future {
+        if let Some(id) = request
+            .headers()
+            .get(FULL_NODE_ID_HEADER)
+            .and_then(|id| id.to_str().ok())
+        {
+            self.tracker.record(id, Instant::now());
+        }
+        self.inner.call(request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_recently_seen_full_nodes() {
+        let tracker = FullNodeTracker::default();
+        let start = Instant::now();
+
+        tracker.record("a", start);
+        tracker.record("b", start);
+        tracker.record("a", start + Duration::from_secs(30));
+        assert_eq!(tracker.connected(start + Duration::from_secs(30)), 2);
+
+        // b stopped syncing
+        assert_eq!(tracker.connected(start + Duration::from_secs(70)), 1);
+        assert_eq!(tracker.connected(start + Duration::from_secs(100)), 0);
+    }
+}
@@ -1,10 +1,24 @@
+mod block_scheduler;
 mod commitment_controller;
+mod conditional;
 mod config;
 mod db_provider;
+mod encrypted_mempool;
+mod full_nodes;
+mod memory_budget;
 mod mempool;
+mod mempool_overflow;
+mod metrics;
+mod misbehavior;
+mod rate_limiter;
 mod rpc;
 mod sequencer;
+mod soft_batch_builder;
+mod soft_batch_push;
+mod user_operation;
 mod utils;
 
 pub use config::SequencerConfig;
+pub use misbehavior::{Misbehavior, ScriptedMisbehavior};
 pub use sequencer::CitreaSequencer;
+pub use soft_batch_builder::{BuiltSoftBatch, SoftBatchBuilder};
@@ -0,0 +1,145 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::Serialize;
+
+use crate::encrypted_mempool::EncryptedMempool;
+use crate::mempool::CitreaMempool;
+
+/// Part of the sequencer's memory tracked by the [`MemoryBudget`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MemoryComponent {
+    /// Transactions in the mempool.
+    Mempool,
+    /// Encrypted transactions and revealed keys waiting for their block.
+    EncryptedMempool,
+    /// Transactions of the block being built.
+    PendingBlock,
+    /// Response, block and fee history caches of the Ethereum RPC of the sequencer node.
+    RpcCaches,
+}
+
+/// Memory used by the tracked parts of the sequencer, in bytes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct MemoryUsage {
+    pub mempool: u64,
+    pub encrypted_mempool: u64,
+    pub pending_block: u64,
+    pub rpc_caches: u64,
+    pub total: u64,
+    /// Cap on the total, if any.
+    pub budget: Option<u64>,
+}
+
+/// Tracks the memory used by the mempools, the block being built and the RPC caches against a
+/// configured cap, so that the sequencer rejects new transactions instead of running out of
+/// memory under spam.
+///
+/// Usage is reported by the owners of each component, the budget doesn't allocate anything itself.
+/// The RPC caches are measured after every soft batch, their usage lags behind by a block.
+#[derive(Debug, Default)]
+pub(crate) struct MemoryBudget {
+    max_bytes: Option<u64>,
+    mempool: AtomicU64,
+    encrypted_mempool: AtomicU64,
+    pending_block: AtomicU64,
+    rpc_caches: AtomicU64,
+}
+
+impl MemoryBudget {
+    /// Creates a budget of `max_memory_mb` megabytes, unlimited if not set.
+    pub(crate) fn new(max_memory_mb: Option<u64>) -> Self {
+        Self {
+            max_bytes: max_memory_mb.map(|megabytes| megabytes * 1024 * 1024),
+            ..Default::default()
+        }
+    }
+
+    fn counter(&self, component: MemoryComponent) -> &AtomicU64 {
+        match component {
+            MemoryComponent::Mempool => &self.mempool,
+            MemoryComponent::EncryptedMempool => &self.encrypted_mempool,
+            MemoryComponent::PendingBlock => &self.pending_block,
+            MemoryComponent::RpcCaches => &self.rpc_caches,
+        }
+    }
+
+    /// Records the current memory usage of a component.
+    pub(crate) fn set_usage(&self, component: MemoryComponent, bytes: u64) {
+        self.counter(component).store(bytes, Ordering::Relaxed);
+    }
+
+    /// Records the current memory usage of both mempools.
+    pub(crate) fn update_mempool_usage<C: sov_modules_api::Context>(
+        &self,
+        mempool: &CitreaMempool<C>,
+        encrypted_mempool: &EncryptedMempool,
+    ) {
+        self.set_usage(MemoryComponent::Mempool, mempool.size_in_bytes());
+        self.set_usage(
+            MemoryComponent::EncryptedMempool,
+            encrypted_mempool.size_in_bytes(),
+        );
+    }
+
+    /// Returns the last recorded memory usage.
+    pub(crate) fn usage(&self) -> MemoryUsage {
+        let mempool = self.mempool.load(Ordering::Relaxed);
+        let encrypted_mempool = self.encrypted_mempool.load(Ordering::Relaxed);
+        let pending_block = self.pending_block.load(Ordering::Relaxed);
+        let rpc_caches = self.rpc_caches.load(Ordering::Relaxed);
+        MemoryUsage {
+            mempool,
+            encrypted_mempool,
+            pending_block,
+            rpc_caches,
+            total: mempool
+                .saturating_add(encrypted_mempool)
+                .saturating_add(pending_block)
+                .saturating_add(rpc_caches),
+            budget: self.max_bytes,
+        }
+    }
+
+    /// Returns true if `bytes` more fit in the budget.
+    pub(crate) fn has_room_for(&self, bytes: u64) -> bool {
+        match self.max_bytes {
+            Some(max_bytes) => self.usage().total.saturating_add(bytes) <= max_bytes,
+            None => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn budget_rejects_past_the_cap() {
+        let budget = MemoryBudget::new(Some(1));
+        assert!(budget.has_room_for(1024 * 1024));
+
+        budget.set_usage(MemoryComponent::Mempool, 512 * 1024);
+        budget.set_usage(MemoryComponent::PendingBlock, 128 * 1024);
+        budget.set_usage(MemoryComponent::RpcCaches, 128 * 1024);
+        assert!(budget.has_room_for(256 * 1024));
+        assert!(!budget.has_room_for(256 * 1024 + 1));
+
+        budget.set_usage(MemoryComponent::PendingBlock, 0);
+        assert_eq!(
+            budget.usage(),
+            MemoryUsage {
+                mempool: 512 * 1024,
+                encrypted_mempool: 0,
+                pending_block: 0,
+                rpc_caches: 128 * 1024,
+                total: 640 * 1024,
+                budget: Some(1024 * 1024),
+            }
+        );
+
+        let unlimited = MemoryBudget::new(None);
+        unlimited.set_usage(MemoryComponent::Mempool, u64::MAX);
+        assert!(unlimited.has_room_for(u64::MAX));
+    }
+}
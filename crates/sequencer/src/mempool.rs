@@ -1,18 +1,35 @@
-use std::sync::Arc;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use citrea_evm::SYSTEM_SIGNER;
-use reth_primitives::{Chain, ChainSpecBuilder, Genesis, TxHash};
+use reth_primitives::{
+    Address, Chain, ChainSpecBuilder, Genesis, IntoRecoveredTransaction,
+    TransactionSignedEcRecovered, TxHash, U256, U64,
+};
+use reth_rpc_types::other::OtherFields;
+use reth_rpc_types::txpool::{TxpoolContent, TxpoolInspect, TxpoolInspectSummary, TxpoolStatus};
+use reth_rpc_types_compat::transaction::from_recovered;
 use reth_tasks::TokioTaskExecutor;
 use reth_transaction_pool::blobstore::NoopBlobStore;
-use reth_transaction_pool::error::PoolError;
+use reth_transaction_pool::error::{PoolError, PoolErrorKind};
 use reth_transaction_pool::{
-    BestTransactions, BestTransactionsAttributes, CoinbaseTipOrdering, EthPooledTransaction,
-    EthTransactionValidator, Pool, PoolConfig, PoolResult, SubPoolLimit, TransactionPool,
-    TransactionValidationTaskExecutor, ValidPoolTransaction,
+    AllPoolTransactions, BestTransactions, BestTransactionsAttributes, CoinbaseTipOrdering,
+    EthPooledTransaction, EthTransactionValidator, Pool, PoolConfig, PoolResult, PoolTransaction,
+    SubPoolLimit, TransactionPool, TransactionValidationTaskExecutor, ValidPoolTransaction,
 };
+use schnellru::{ByLength, LruMap};
+use sequencer_client::ReplacedTransaction;
+use serde::Serialize;
+use tokio::sync::broadcast;
+use tracing::{debug, warn};
 
-use crate::config::SequencerMempoolConfig;
+use crate::conditional::ConditionalTransactions;
+use crate::config::{SequencerMempoolConfig, ValidationGasPolicyConfig};
 pub use crate::db_provider::DbProvider;
+use crate::mempool_overflow::MempoolOverflow;
+use crate::user_operation::user_operations_validation_gas;
+use crate::utils::recover_raw_transaction;
 
 type CitreaMempoolImpl<C> = Pool<
     TransactionValidationTaskExecutor<EthTransactionValidator<DbProvider<C>, EthPooledTransaction>>,
@@ -22,7 +39,146 @@ type CitreaMempoolImpl<C> = Pool<
 
 type Transaction<C> = <CitreaMempoolImpl<C> as TransactionPool>::Transaction;
 
-pub(crate) struct CitreaMempool<C: sov_modules_api::Context>(CitreaMempoolImpl<C>);
+/// Number of replaced transactions remembered, to answer `eth_getTransactionByHash` for them.
+const REPLACED_TRANSACTIONS_CAPACITY: u32 = 10_000;
+
+/// Why a stale transaction was evicted from the pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum EvictionReason {
+    /// Max fee per gas is below the current base fee.
+    Underpriced,
+    /// Transaction is waiting for a missing nonce.
+    NonceGap,
+    /// Transaction was executable but not included before its TTL.
+    Expired,
+}
+
+/// Returns why a transaction that has been in the pool for `age` is evicted, None if it isn't
+/// stale yet. Queued transactions are either underpriced or waiting for a missing nonce.
+fn eviction_reason(
+    age: Duration,
+    ttl: Duration,
+    queued: bool,
+    max_fee_per_gas: u128,
+    base_fee: u64,
+) -> Option<EvictionReason> {
+    if age <= ttl {
+        return None;
+    }
+    Some(if !queued {
+        EvictionReason::Expired
+    } else if max_fee_per_gas < base_fee as u128 {
+        EvictionReason::Underpriced
+    } else {
+        EvictionReason::NonceGap
+    })
+}
+
+/// Number of stale transactions evicted from the pool since startup, by reason.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct EvictionStats {
+    pub underpriced: u64,
+    pub nonce_gap: u64,
+    pub expired: u64,
+}
+
+impl EvictionStats {
+    fn record(&mut self, reason: EvictionReason) {
+        match reason {
+            EvictionReason::Underpriced => self.underpriced += 1,
+            EvictionReason::NonceGap => self.nonce_gap += 1,
+            EvictionReason::Expired => self.expired += 1,
+        }
+    }
+}
+
+/// Validation gas charged per sender while building a block, see [`ValidationGasPolicyConfig`].
+///
+/// The validation of the user operations bundled in a call to an entry point is charged to the
+/// senders of the user operations, not to the bundler sending the call, and the conditions of
+/// a conditional transaction are charged to its sender.
+pub(crate) struct ValidationGasMeter<'a> {
+    policy: &'a ValidationGasPolicyConfig,
+    used: HashMap<Address, u64>,
+    /// Senders of the transactions deferred to the next blocks, whose later transactions
+    /// are deferred as well
+    deferred_senders: HashSet<Address>,
+}
+
+impl<'a> ValidationGasMeter<'a> {
+    pub(crate) fn new(policy: &'a ValidationGasPolicyConfig) -> Self {
+        Self {
+            policy,
+            used: HashMap::new(),
+            deferred_senders: HashSet::new(),
+        }
+    }
+
+    /// Charges the validation gas of a transaction of `sender` to `to` if it fits in the cap of
+    /// every sender it is charged to, `conditional_validation_gas` being the cost of checking its
+    /// conditions. Returns false if the transaction, and the later ones of `sender`,
+    /// must wait for the next blocks.
+    pub(crate) fn try_charge(
+        &mut self,
+        sender: Address,
+        to: Option<Address>,
+        input: &[u8],
+        gas_limit: u64,
+        conditional_validation_gas: u64,
+    ) -> bool {
+        if self.deferred_senders.contains(&sender) {
+            return false;
+        }
+        let max_validation_gas = self.policy.max_validation_gas_per_sender;
+        if max_validation_gas == 0 {
+            return true;
+        }
+
+        let mut charges: HashMap<Address, u64> = HashMap::new();
+        if conditional_validation_gas > 0 {
+            *charges.entry(sender).or_default() += conditional_validation_gas;
+        }
+        if to.is_some_and(|to| self.policy.entry_points.contains(&to)) {
+            match user_operations_validation_gas(input) {
+                Some(user_operations) => {
+                    for (user_operation_sender, validation_gas) in user_operations {
+                        let charge = charges.entry(user_operation_sender).or_default();
+                        *charge = charge.saturating_add(validation_gas);
+                    }
+                }
+                // Other entry point calls are charged their whole gas limit
+                None => *charges.entry(sender).or_default() += gas_limit,
+            }
+        }
+
+        let fits = charges.iter().all(|(charged, charge)| {
+            let used = self.used.get(charged).copied().unwrap_or_default();
+            used.saturating_add(*charge) <= max_validation_gas
+        });
+        if !fits {
+            self.deferred_senders.insert(sender);
+            return false;
+        }
+        for (charged, charge) in charges {
+            *self.used.entry(charged).or_default() += charge;
+        }
+        true
+    }
+}
+
+pub(crate) struct CitreaMempool<C: sov_modules_api::Context> {
+    pool: CitreaMempoolImpl<C>,
+    eviction_stats: Mutex<EvictionStats>,
+    /// Transactions replaced by a transaction with the same nonce, by hash, with the hash of
+    /// their replacement in the `replacedBy` field.
+    replaced_txs: Mutex<LruMap<TxHash, reth_rpc_types::Transaction, ByLength>>,
+    /// Sends the replaced transactions along with the hashes of their replacements.
+    replacements: broadcast::Sender<ReplacedTransaction>,
+    /// Transactions the pool had no room for, if enabled.
+    overflow: Option<MempoolOverflow>,
+    pending_tx_limit: usize,
+}
 
 impl<C: sov_modules_api::Context> CitreaMempool<C> {
     pub(crate) fn new(client: DbProvider<C>, mempool_conf: SequencerMempoolConfig) -> Self {
@@ -75,16 +231,28 @@ impl<C: sov_modules_api::Context> CitreaMempool<C> {
             ..pool_config
         };
 
-        Self(Pool::eth_pool(
-            TransactionValidationTaskExecutor::eth(
-                client,
-                Arc::new(chain_spec),
+        let overflow = mempool_conf.overflow_dir.as_ref().map(|dir| {
+            MempoolOverflow::open(dir, mempool_conf.overflow_tx_limit)
+                .expect("Mempool overflow directory should be accessible")
+        });
+
+        Self {
+            pool: Pool::eth_pool(
+                TransactionValidationTaskExecutor::eth(
+                    client,
+                    Arc::new(chain_spec),
+                    blob_store,
+                    TokioTaskExecutor::default(),
+                ),
                 blob_store,
-                TokioTaskExecutor::default(),
+                pool_config,
             ),
-            blob_store,
-            pool_config,
-        ))
+            eviction_stats: Mutex::new(EvictionStats::default()),
+            replaced_txs: Mutex::new(LruMap::new(ByLength::new(REPLACED_TRANSACTIONS_CAPACITY))),
+            replacements: broadcast::channel(REPLACED_TRANSACTIONS_CAPACITY as usize).0,
+            overflow,
+            pending_tx_limit: mempool_conf.pending_tx_limit as usize,
+        }
     }
 
     pub(crate) async fn add_external_transaction(
@@ -97,25 +265,438 @@ impl<C: sov_modules_api::Context> CitreaMempool<C> {
                 "system transactions from rpc are not allowed",
             ));
         }
-        self.0.add_external_transaction(transaction).await
+        if let Some(overflow) = &self.overflow {
+            if overflow.contains(transaction.hash()) {
+                return Ok(*transaction.hash());
+            }
+        }
+        // a transaction of the sender with the same nonce is replaced if the fee is bumped enough
+        let replaced = self
+            .pool
+            .get_transactions_by_sender(transaction.transaction().signer())
+            .into_iter()
+            .find(|tx| tx.nonce() == transaction.transaction().nonce());
+
+        let spillable = self.overflow.is_some().then(|| transaction.clone());
+        let hash = match self.pool.add_external_transaction(transaction).await {
+            Ok(hash) => hash,
+            // the pool is full and the transaction has the lowest priority
+            Err(e) if matches!(e.kind, PoolErrorKind::DiscardedOnInsert) => {
+                return match spillable {
+                    Some(transaction) if self.spill(&transaction) => Ok(e.hash),
+                    _ => Err(e),
+                };
+            }
+            Err(e) => return Err(e),
+        };
+        if let Some(replaced) = replaced {
+            if *replaced.hash() != hash && self.pool.get(replaced.hash()).is_none() {
+                self.record_replacement(&replaced, hash);
+            }
+        }
+        Ok(hash)
+    }
+
+    fn spill(&self, transaction: &EthPooledTransaction) -> bool {
+        let Some(overflow) = &self.overflow else {
+            return false;
+        };
+        overflow.spill(
+            *transaction.hash(),
+            transaction.priority_fee_or_price(),
+            &transaction.transaction().envelope_encoded(),
+        )
+    }
+
+    /// Adds spilled transactions back to the pool, highest priority first, while the pending
+    /// sub-pool has room for them. Spilled transactions that became invalid are dropped.
+    pub(crate) async fn promote_overflow(&self) {
+        let Some(overflow) = &self.overflow else {
+            return;
+        };
+        let room = self
+            .pending_tx_limit
+            .saturating_sub(self.pool.pool_size().pending);
+
+        let mut promoted = 0;
+        for _ in 0..room {
+            let Some((hash, encoded)) = overflow.pop_best() else {
+                break;
+            };
+            let transaction = match recover_raw_transaction(encoded.into()) {
+                Ok(recovered) => EthPooledTransaction::from_recovered_pooled_transaction(recovered),
+                Err(e) => {
+                    warn!("Dropping undecodable spilled transaction {}: {}", hash, e);
+                    continue;
+                }
+            };
+            match self
+                .pool
+                .add_external_transaction(transaction.clone())
+                .await
+            {
+                Ok(_) => promoted += 1,
+                Err(e) if matches!(e.kind, PoolErrorKind::DiscardedOnInsert) => {
+                    self.spill(&transaction);
+                    break;
+                }
+                Err(e) => debug!("Dropping spilled transaction {}: {}", hash, e),
+            }
+        }
+        if promoted > 0 {
+            debug!(
+                "Promoted {} spilled transactions to the mempool, {} left",
+                promoted,
+                overflow.len()
+            );
+        }
+    }
+
+    fn record_replacement(&self, replaced: &ValidPoolTransaction<Transaction<C>>, by: TxHash) {
+        let mut transaction = from_recovered(replaced.to_recovered_transaction());
+        transaction.other = OtherFields::new(
+            [("replacedBy".to_string(), serde_json::json!(by))]
+                .into_iter()
+                .collect(),
+        );
+        self.replaced_txs
+            .lock()
+            .unwrap()
+            .insert(*replaced.hash(), transaction);
+        // no receiver until a subscriber listens to the replacements
+        let _ = self.replacements.send(ReplacedTransaction {
+            hash: *replaced.hash(),
+            replaced_by: by,
+            from: replaced.sender(),
+        });
+    }
+
+    /// Returns a transaction that left the pool because it was replaced by a transaction with
+    /// the same nonce, with the hash of the replacement in its `replacedBy` field.
+    pub(crate) fn get_replaced(&self, hash: &TxHash) -> Option<reth_rpc_types::Transaction> {
+        self.replaced_txs.lock().unwrap().get(hash).cloned()
+    }
+
+    /// Returns a receiver of the replaced transactions along with the hashes of their replacements.
+    pub(crate) fn replaced_transactions_listener(
+        &self,
+    ) -> broadcast::Receiver<ReplacedTransaction> {
+        self.replacements.subscribe()
     }
 
     pub(crate) fn get(&self, hash: &TxHash) -> Option<Arc<ValidPoolTransaction<Transaction<C>>>> {
-        self.0.get(hash)
+        self.pool.get(hash)
+    }
+
+    /// Returns a receiver of the hashes of the transactions entering the pending pool.
+    pub(crate) fn pending_transactions_listener(&self) -> tokio::sync::mpsc::Receiver<TxHash> {
+        self.pool.pending_transactions_listener()
+    }
+
+    /// Returns the nonce following the transactions of `sender` in the pool, pending and queued,
+    /// with nonces consecutive to `state_nonce`. Transactions after a nonce gap are ignored.
+    pub(crate) fn next_nonce(&self, sender: Address, state_nonce: u64) -> u64 {
+        let mut nonces: Vec<u64> = self
+            .pool
+            .get_transactions_by_sender(sender)
+            .iter()
+            .map(|tx| tx.nonce())
+            .collect();
+        nonces.sort_unstable();
+        nonces.into_iter().fold(
+            state_nonce,
+            |next, nonce| {
+                if nonce == next {
+                    next + 1
+                } else {
+                    next
+                }
+            },
+        )
     }
 
     pub(crate) fn remove_transactions(
         &self,
         tx_hashes: Vec<TxHash>,
     ) -> Vec<Arc<ValidPoolTransaction<Transaction<C>>>> {
-        self.0.remove_transactions(tx_hashes)
+        self.pool.remove_transactions(tx_hashes)
     }
 
     pub(crate) fn best_transactions_with_attributes(
         &self,
         best_transactions_attributes: BestTransactionsAttributes,
     ) -> Box<dyn BestTransactions<Item = Arc<ValidPoolTransaction<Transaction<C>>>>> {
-        self.0
+        self.pool
             .best_transactions_with_attributes(best_transactions_attributes)
     }
+
+    /// Returns the transactions to include in the next block, in order.
+    /// These are the best transactions paying at least `base_fee`, skipping the ones
+    /// whose gas limit does not fit into the remaining block gas, and the ones exceeding the
+    /// validation gas cap of a sender along with the later transactions of their sender.
+    pub(crate) fn next_block_transactions(
+        &self,
+        base_fee: u64,
+        block_gas_limit: u64,
+        validation_gas_policy: &ValidationGasPolicyConfig,
+        conditionals: &ConditionalTransactions,
+    ) -> Vec<TransactionSignedEcRecovered> {
+        let mut cumulative_gas_used = 0;
+        let mut validation_gas = ValidationGasMeter::new(validation_gas_policy);
+        let mut best_txs =
+            self.best_transactions_with_attributes(BestTransactionsAttributes::base_fee(base_fee));
+
+        let mut txs = vec![];
+        while let Some(tx) = best_txs.next() {
+            // Don't include transactions that exceed the block gas limit
+            let tx_gas_limit = tx.transaction.gas_limit();
+            if cumulative_gas_used + tx_gas_limit > block_gas_limit {
+                continue;
+            }
+
+            if !validation_gas.try_charge(
+                tx.sender(),
+                tx.transaction.to(),
+                tx.transaction.input(),
+                tx_gas_limit,
+                conditionals.validation_gas(tx.hash()),
+            ) {
+                // Later transactions of the sender can't be included without this one
+                best_txs.mark_invalid(&tx);
+                continue;
+            }
+
+            cumulative_gas_used += tx_gas_limit;
+            txs.push(tx.to_recovered_transaction());
+        }
+        txs
+    }
+
+    /// Removes transactions that have been in the pool for longer than `ttl`.
+    /// Returns the hashes of the evicted transactions with the reason they were not included.
+    pub(crate) fn evict_stale_transactions(
+        &self,
+        ttl: Duration,
+        base_fee: u64,
+    ) -> Vec<(TxHash, EvictionReason)> {
+        let all_transactions = self.pool.all_transactions();
+        let pending = all_transactions.pending.iter().map(|tx| (tx, false));
+        let queued = all_transactions.queued.iter().map(|tx| (tx, true));
+
+        let evicted: Vec<(TxHash, EvictionReason)> = pending
+            .chain(queued)
+            .filter_map(|(tx, queued)| {
+                eviction_reason(
+                    tx.timestamp.elapsed(),
+                    ttl,
+                    queued,
+                    tx.transaction.max_fee_per_gas(),
+                    base_fee,
+                )
+                .map(|reason| (*tx.hash(), reason))
+            })
+            .collect();
+        if evicted.is_empty() {
+            return evicted;
+        }
+
+        self.pool
+            .remove_transactions(evicted.iter().map(|(hash, _)| *hash).collect());
+
+        let mut stats = self.eviction_stats.lock().unwrap();
+        for (_, reason) in &evicted {
+            stats.record(*reason);
+        }
+        evicted
+    }
+
+    pub(crate) fn eviction_stats(&self) -> EvictionStats {
+        self.eviction_stats.lock().unwrap().clone()
+    }
+
+    /// Returns the pending and queued transactions of the pool, grouped by sender and nonce.
+    pub(crate) fn content(&self) -> TxpoolContent {
+        let AllPoolTransactions { pending, queued } = self.pool.all_transactions();
+        let group = |txs: Vec<Arc<ValidPoolTransaction<Transaction<C>>>>| {
+            let mut grouped: BTreeMap<Address, BTreeMap<String, reth_rpc_types::Transaction>> =
+                BTreeMap::new();
+            for tx in txs {
+                grouped.entry(tx.sender()).or_default().insert(
+                    tx.nonce().to_string(),
+                    from_recovered(tx.to_recovered_transaction()),
+                );
+            }
+            grouped
+        };
+        TxpoolContent {
+            pending: group(pending),
+            queued: group(queued),
+        }
+    }
+
+    /// Returns the effective tips per gas of the executable transactions of the pool
+    /// at the given base fee, leaving out the ones whose fee cap is under it.
+    pub(crate) fn pending_tips(&self, base_fee: u64) -> Vec<u128> {
+        self.pool
+            .pending_transactions()
+            .iter()
+            .filter_map(|tx| tx.effective_tip_per_gas(base_fee))
+            .collect()
+    }
+
+    /// Returns the number of pending and queued transactions in the pool.
+    pub(crate) fn status(&self) -> TxpoolStatus {
+        let AllPoolTransactions { pending, queued } = self.pool.all_transactions();
+        TxpoolStatus {
+            pending: U64::from(pending.len()),
+            queued: U64::from(queued.len()),
+        }
+    }
+
+    /// Returns a summary of the pending and queued transactions of the pool,
+    /// grouped by sender and nonce.
+    pub(crate) fn inspect(&self) -> TxpoolInspect {
+        let AllPoolTransactions { pending, queued } = self.pool.all_transactions();
+        let group = |txs: Vec<Arc<ValidPoolTransaction<Transaction<C>>>>| {
+            let mut grouped: BTreeMap<Address, BTreeMap<String, TxpoolInspectSummary>> =
+                BTreeMap::new();
+            for tx in txs {
+                let transaction = &tx.transaction;
+                grouped.entry(tx.sender()).or_default().insert(
+                    tx.nonce().to_string(),
+                    TxpoolInspectSummary {
+                        to: transaction.to(),
+                        value: transaction.value(),
+                        gas: U256::from(transaction.gas_limit()),
+                        gas_price: U256::from(transaction.max_fee_per_gas()),
+                    },
+                );
+            }
+            grouped
+        };
+        TxpoolInspect {
+            pending: group(pending),
+            queued: group(queued),
+        }
+    }
+
+    /// Total size of the transactions in the pool, in bytes.
+    pub(crate) fn size_in_bytes(&self) -> u64 {
+        let size = self.pool.pool_size();
+        (size.pending_size + size.basefee_size + size.queued_size + size.blob_size) as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use reth_primitives::address;
+
+    use super::*;
+    use crate::conditional::KNOWN_SLOT_VALIDATION_GAS;
+    use crate::user_operation::encode_handle_ops_v06;
+
+    const ENTRY_POINT: Address = address!("5FF137D4b0FDCD49DcA30c7CF57E578a026d2789");
+
+    #[test]
+    fn stale_transactions_are_evicted_with_their_reason() {
+        let ttl = Duration::from_secs(60);
+        let stale = Duration::from_secs(61);
+
+        assert_eq!(eviction_reason(ttl, ttl, false, 10, 7), None);
+        assert_eq!(eviction_reason(ttl, ttl, true, 1, 7), None);
+        assert_eq!(
+            eviction_reason(stale, ttl, false, 1, 7),
+            Some(EvictionReason::Expired)
+        );
+        assert_eq!(
+            eviction_reason(stale, ttl, true, 1, 7),
+            Some(EvictionReason::Underpriced)
+        );
+        assert_eq!(
+            eviction_reason(stale, ttl, true, 7, 7),
+            Some(EvictionReason::NonceGap)
+        );
+
+        let mut stats = EvictionStats::default();
+        for reason in [
+            EvictionReason::Expired,
+            EvictionReason::Underpriced,
+            EvictionReason::Underpriced,
+            EvictionReason::NonceGap,
+        ] {
+            stats.record(reason);
+        }
+        assert_eq!(
+            stats,
+            EvictionStats {
+                underpriced: 2,
+                nonce_gap: 1,
+                expired: 1,
+            }
+        );
+    }
+
+    #[test]
+    fn validation_gas_cap_defers_later_transactions_of_capped_sender() {
+        let policy = ValidationGasPolicyConfig {
+            entry_points: vec![ENTRY_POINT],
+            max_validation_gas_per_sender: 1_000_000,
+        };
+        let mut meter = ValidationGasMeter::new(&policy);
+        let bundler = Address::from([1; 20]);
+        let other_bundler = Address::from([2; 20]);
+        let account = Address::from([3; 20]);
+        let other_account = Address::from([4; 20]);
+        let recipient = Address::from([5; 20]);
+
+        // The user operations are charged to their senders, not to the bundler
+        let bundle = encode_handle_ops_v06(&[(account, 600_000)]);
+        assert!(meter.try_charge(bundler, Some(ENTRY_POINT), &bundle, 10_000_000, 0));
+        let bundle = encode_handle_ops_v06(&[(other_account, 900_000)]);
+        assert!(meter.try_charge(bundler, Some(ENTRY_POINT), &bundle, 10_000_000, 0));
+
+        // A bundle exceeding the cap of one of its user operation senders is deferred,
+        // along with the later nonces of the bundler
+        let bundle = encode_handle_ops_v06(&[(account, 300_000), (other_account, 200_000)]);
+        assert!(!meter.try_charge(bundler, Some(ENTRY_POINT), &bundle, 10_000_000, 0));
+        assert!(!meter.try_charge(bundler, Some(recipient), &[], 21_000, 0));
+
+        // The deferred bundle charged nothing, another bundler can include what fits
+        let bundle = encode_handle_ops_v06(&[(account, 400_000)]);
+        assert!(meter.try_charge(other_bundler, Some(ENTRY_POINT), &bundle, 10_000_000, 0));
+        let bundle = encode_handle_ops_v06(&[(account, 1)]);
+        assert!(!meter.try_charge(other_bundler, Some(ENTRY_POINT), &bundle, 10_000_000, 0));
+        assert!(!meter.try_charge(other_bundler, Some(recipient), &[], 21_000, 0));
+
+        // The conditions of conditional transactions are charged to their sender
+        let sender = Address::from([6; 20]);
+        let conditional_validation_gas = 400 * KNOWN_SLOT_VALIDATION_GAS;
+        assert!(meter.try_charge(
+            sender,
+            Some(recipient),
+            &[],
+            21_000,
+            conditional_validation_gas
+        ));
+        assert!(!meter.try_charge(
+            sender,
+            Some(recipient),
+            &[],
+            21_000,
+            conditional_validation_gas
+        ));
+        assert!(!meter.try_charge(sender, Some(recipient), &[], 21_000, 0));
+
+        // Entry point calls that are not bundles are charged their gas limit to their sender
+        let sender = Address::from([7; 20]);
+        assert!(!meter.try_charge(sender, Some(ENTRY_POINT), &[0xde, 0xad], 2_000_000, 0));
+
+        let disabled = ValidationGasPolicyConfig {
+            max_validation_gas_per_sender: 0,
+            ..policy
+        };
+        let mut meter = ValidationGasMeter::new(&disabled);
+        let bundle = encode_handle_ops_v06(&[(account, 2_000_000)]);
+        assert!(meter.try_charge(bundler, Some(ENTRY_POINT), &bundle, 10_000_000, 0));
+    }
 }
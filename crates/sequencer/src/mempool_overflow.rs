@@ -0,0 +1,201 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::{fs, io};
+
+use reth_primitives::{hex, TxHash};
+use tracing::warn;
+
+/// Size of the priority prefix of a spilled transaction file.
+const PRIORITY_LEN: usize = 16;
+
+/// Disk-backed area holding the transactions the mempool had no room for, so that they are
+/// promoted back to the mempool once blocks free space instead of being rejected.
+///
+/// Each transaction is a file named after its hash, holding its priority as big endian
+/// followed by its encoded envelope. Only the priorities are kept in memory.
+#[derive(Debug)]
+pub(crate) struct MempoolOverflow {
+    dir: PathBuf,
+    max_txs: usize,
+    inner: Mutex<OverflowIndex>,
+}
+
+#[derive(Debug, Default)]
+struct OverflowIndex {
+    /// Spilled transactions, highest priority first, then oldest first.
+    by_priority: BTreeSet<(Reverse<u128>, u64, TxHash)>,
+    /// Hashes of the spilled transactions.
+    hashes: HashSet<TxHash>,
+    next_seq: u64,
+}
+
+impl OverflowIndex {
+    fn insert(&mut self, priority: u128, hash: TxHash) {
+        self.by_priority
+            .insert((Reverse(priority), self.next_seq, hash));
+        self.hashes.insert(hash);
+        self.next_seq += 1;
+    }
+
+    fn remove(&mut self, entry: &(Reverse<u128>, u64, TxHash)) {
+        self.by_priority.remove(entry);
+        self.hashes.remove(&entry.2);
+    }
+}
+
+impl MempoolOverflow {
+    /// Opens the overflow area in `dir`, keeping at most `max_txs` transactions.
+    /// Transactions spilled before a restart are picked up again.
+    pub(crate) fn open(dir: &Path, max_txs: u64) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+
+        let mut index = OverflowIndex::default();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            let hash = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.parse::<TxHash>().ok());
+            let priority = fs::read(&path).ok().and_then(|content| {
+                let priority = content.get(..PRIORITY_LEN)?.try_into().ok()?;
+                Some(u128::from_be_bytes(priority))
+            });
+            match (hash, priority) {
+                (Some(hash), Some(priority)) => index.insert(priority, hash),
+                _ => {
+                    warn!("Removing invalid mempool overflow file {}", path.display());
+                    let _ = fs::remove_file(&path);
+                }
+            }
+        }
+
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            max_txs: max_txs as usize,
+            inner: Mutex::new(index),
+        })
+    }
+
+    fn path(&self, hash: &TxHash) -> PathBuf {
+        self.dir.join(hex::encode_prefixed(hash))
+    }
+
+    /// Spills a transaction. When the area is full, the lowest priority transaction is dropped
+    /// to make room, unless it is the spilled one.
+    /// Returns false if the transaction was not spilled.
+    pub(crate) fn spill(&self, hash: TxHash, priority: u128, encoded: &[u8]) -> bool {
+        if self.max_txs == 0 {
+            return false;
+        }
+        let mut index = self.inner.lock().unwrap();
+        if index.hashes.contains(&hash) {
+            return true;
+        }
+        if index.by_priority.len() >= self.max_txs {
+            let lowest = *index.by_priority.last().expect("Area is full");
+            if lowest.0 .0 >= priority {
+                return false;
+            }
+            index.remove(&lowest);
+            let _ = fs::remove_file(self.path(&lowest.2));
+        }
+
+        let mut content = priority.to_be_bytes().to_vec();
+        content.extend_from_slice(encoded);
+        if let Err(e) = fs::write(self.path(&hash), content) {
+            warn!("Failed to spill transaction {} to disk: {}", hash, e);
+            return false;
+        }
+        index.insert(priority, hash);
+        true
+    }
+
+    /// Removes and returns the highest priority transaction with its encoded envelope.
+    pub(crate) fn pop_best(&self) -> Option<(TxHash, Vec<u8>)> {
+        let mut index = self.inner.lock().unwrap();
+        while let Some(best) = index.by_priority.first().copied() {
+            index.remove(&best);
+            let hash = best.2;
+            let path = self.path(&hash);
+            let content = fs::read(&path);
+            let _ = fs::remove_file(&path);
+            match content {
+                Ok(content) if content.len() > PRIORITY_LEN => {
+                    return Some((hash, content[PRIORITY_LEN..].to_vec()));
+                }
+                _ => warn!("Dropping unreadable spilled transaction {}", hash),
+            }
+        }
+        None
+    }
+
+    /// Returns true if the transaction is spilled.
+    pub(crate) fn contains(&self, hash: &TxHash) -> bool {
+        self.inner.lock().unwrap().hashes.contains(hash)
+    }
+
+    /// Number of spilled transactions.
+    pub(crate) fn len(&self) -> usize {
+        self.inner.lock().unwrap().by_priority.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(byte: u8) -> TxHash {
+        TxHash::repeat_byte(byte)
+    }
+
+    #[test]
+    fn pops_highest_priority_first() {
+        let dir = tempfile::tempdir().unwrap();
+        let overflow = MempoolOverflow::open(dir.path(), 10).unwrap();
+
+        assert!(overflow.spill(hash(1), 5, b"first"));
+        assert!(overflow.spill(hash(2), 7, b"second"));
+        assert!(overflow.spill(hash(3), 5, b"third"));
+        assert_eq!(overflow.len(), 3);
+        assert!(overflow.contains(&hash(3)));
+
+        assert_eq!(overflow.pop_best(), Some((hash(2), b"second".to_vec())));
+        assert_eq!(overflow.pop_best(), Some((hash(1), b"first".to_vec())));
+        assert_eq!(overflow.pop_best(), Some((hash(3), b"third".to_vec())));
+        assert_eq!(overflow.pop_best(), None);
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn drops_lowest_priority_when_full() {
+        let dir = tempfile::tempdir().unwrap();
+        let overflow = MempoolOverflow::open(dir.path(), 2).unwrap();
+
+        assert!(overflow.spill(hash(1), 5, b"first"));
+        assert!(overflow.spill(hash(2), 7, b"second"));
+        assert!(!overflow.spill(hash(3), 5, b"third"));
+        assert!(overflow.spill(hash(4), 6, b"fourth"));
+
+        assert!(!overflow.contains(&hash(1)));
+        assert_eq!(overflow.len(), 2);
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn reopens_spilled_transactions() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let overflow = MempoolOverflow::open(dir.path(), 10).unwrap();
+            assert!(overflow.spill(hash(1), 5, b"first"));
+            assert!(overflow.spill(hash(2), 7, b"second"));
+        }
+        fs::write(dir.path().join("garbage"), b"").unwrap();
+
+        let overflow = MempoolOverflow::open(dir.path(), 10).unwrap();
+        assert_eq!(overflow.len(), 2);
+        assert_eq!(overflow.pop_best(), Some((hash(2), b"second".to_vec())));
+        assert!(!dir.path().join("garbage").exists());
+    }
+}
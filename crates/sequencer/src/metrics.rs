@@ -0,0 +1,34 @@
+use once_cell::sync::Lazy;
+use prometheus::{
+    exponential_buckets, register_histogram, register_int_counter, Histogram, IntCounter,
+};
+
+pub static SEQUENCER_BLOCK_TIME_SECONDS: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        // metric name
+        "sequencer_block_time_seconds",
+        // metric description
+        "Time between the starts of consecutive L2 blocks in seconds",
+        exponential_buckets(/*start=*/ 0.05, /*factor=*/ 1.5, /*count=*/ 20).unwrap(),
+    )
+    .unwrap()
+});
+
+pub static SEQUENCER_BLOCK_PRODUCTION_SECONDS: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        // metric name
+        "sequencer_block_production_seconds",
+        // metric description
+        "Time spent producing an L2 block, including the DA calls, in seconds",
+        exponential_buckets(/*start=*/ 1e-3, /*factor=*/ 2.0, /*count=*/ 16).unwrap(),
+    )
+    .unwrap()
+});
+
+pub static SEQUENCER_MISSED_BLOCK_SLOTS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "sequencer_missed_block_slots",
+        "Number of scheduled block slots skipped because block production fell behind"
+    )
+    .unwrap()
+});
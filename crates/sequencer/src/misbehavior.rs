@@ -0,0 +1,108 @@
+//! Scripted sequencer misbehavior, to test how full nodes and provers reject invalid
+//! soft confirmations.
+//!
+//! The sequencer executes and persists its blocks honestly, then tampers with the soft
+//! confirmation it publishes at the scripted L2 heights. Its own state is left untouched,
+//! so the next blocks are built on top of the honest state.
+//!
+//! Skipping forced transactions can't be scripted yet, as the rollup has no forced
+//! transactions inclusion.
+//!
+//! Misbehaviors are only applied by sequencers built with the `testing` feature, other
+//! builds refuse to start with scripted misbehaviors.
+
+use serde::Deserialize;
+#[cfg(feature = "testing")]
+use sov_modules_api::{Context, UnsignedSoftConfirmationBatch};
+#[cfg(feature = "testing")]
+use sov_modules_stf_blueprint::StfBlueprintTrait;
+#[cfg(feature = "testing")]
+use sov_rollup_interface::da::DaSpec;
+#[cfg(feature = "testing")]
+use sov_rollup_interface::stf::SoftBatchReceipt;
+#[cfg(feature = "testing")]
+use sov_rollup_interface::zk::Zkvm;
+
+#[cfg(feature = "testing")]
+use crate::soft_batch_builder::SoftBatchBuilder;
+
+/// A way the sequencer can misbehave when publishing a soft confirmation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Misbehavior {
+    /// Publish a post state root the soft confirmation doesn't lead to.
+    InvalidStateRoot,
+    /// Publish a signature not made by the sequencer key.
+    InvalidSignature,
+    /// Publish a validly signed soft confirmation conflicting with the one the sequencer
+    /// executed, at the same height with a different timestamp.
+    ConflictingBatch,
+}
+
+/// A misbehavior of the sequencer at a given L2 height.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct ScriptedMisbehavior {
+    /// L2 height of the tampered soft confirmation.
+    pub l2_height: u64,
+    /// What to tamper with.
+    pub misbehavior: Misbehavior,
+}
+
+/// Tampers with the receipt of the soft confirmation at `l2_height` if it is scripted to.
+#[cfg(feature = "testing")]
+pub(crate) fn apply_misbehaviors<C, Da, Vm, Stf, B, T>(
+    misbehaviors: &[ScriptedMisbehavior],
+    l2_height: u64,
+    builder: &SoftBatchBuilder<C, Da, Vm, Stf>,
+    receipt: &mut SoftBatchReceipt<B, T, Da>,
+) where
+    C: Context,
+    Da: DaSpec,
+    Vm: Zkvm,
+    Stf: StfBlueprintTrait<C, Da, Vm>,
+    Stf::PreState: Clone,
+{
+    for scripted in misbehaviors
+        .iter()
+        .filter(|scripted| scripted.l2_height == l2_height)
+    {
+        tracing::warn!(
+            "Sequencer: misbehaving at l2 height {}: {:?}",
+            l2_height,
+            scripted.misbehavior
+        );
+        match scripted.misbehavior {
+            Misbehavior::InvalidStateRoot => {
+                receipt
+                    .post_state_root
+                    .iter_mut()
+                    .for_each(|byte| *byte = !*byte);
+            }
+            Misbehavior::InvalidSignature => {
+                receipt
+                    .soft_confirmation_signature
+                    .iter_mut()
+                    .for_each(|byte| *byte = !*byte);
+            }
+            Misbehavior::ConflictingBatch => {
+                let txs = receipt
+                    .tx_receipts
+                    .iter()
+                    .filter_map(|tx| tx.body_to_save.clone())
+                    .collect();
+                let conflicting = builder.sign(UnsignedSoftConfirmationBatch::new(
+                    receipt.da_slot_height,
+                    receipt.da_slot_hash.clone().into(),
+                    receipt.da_slot_txs_commitment.clone().into(),
+                    receipt.pre_state_root.clone(),
+                    txs,
+                    receipt.l1_fee_rate,
+                    receipt.timestamp + 1,
+                ));
+                receipt.batch_hash = conflicting.hash();
+                receipt.soft_confirmation_signature = conflicting.signature();
+                receipt.timestamp = conflicting.timestamp();
+            }
+        }
+    }
+}
@@ -0,0 +1,407 @@
+use std::fmt;
+use std::future::Future;
+use std::hash::Hash;
+use std::net::{IpAddr, Ipv4Addr};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use hyper::body::HttpBody;
+use hyper::header::{HeaderValue, CONTENT_TYPE};
+use hyper::{Body, Method, Request, Response, StatusCode};
+use schnellru::{ByLength, LruMap};
+use serde::Serialize;
+use tower::{Layer, Service};
+
+use crate::config::RateLimit;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// Error code of requests rejected by a rate limit, the "limit exceeded" code of EIP-1474.
+pub(crate) const RATE_LIMIT_EXCEEDED_CODE: i32 = -32005;
+
+/// Number of keys tracked, the least recently used bucket is dropped past it.
+const MAX_TRACKED_KEYS: u32 = 100_000;
+
+/// Requests larger than the default max request body size of the RPC server
+/// are rejected without being read further.
+const MAX_INSPECTED_BODY_SIZE: usize = 10 * 1024 * 1024;
+
+/// Method whose calls are limited per source IP.
+const LIMITED_METHOD: &str = "eth_sendRawTransaction";
+
+/// Key of the bucket shared by the requests that didn't go through the trusted proxies.
+const UNKNOWN_SOURCE_IP: IpAddr = IpAddr::V4(Ipv4Addr::UNSPECIFIED);
+
+/// Admissions and rejections of a [`RateLimiter`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RateLimiterStats {
+    pub accepted: u64,
+    pub rejected: u64,
+    /// Number of keys with a bucket that didn't refill yet.
+    pub tracked_keys: u64,
+}
+
+/// Stats of the admission rate limits of the sequencer, `None` for disabled limits.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RateLimitStats {
+    pub per_ip: Option<RateLimiterStats>,
+    pub per_sender: Option<RateLimiterStats>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+struct RateLimiterState<K> {
+    buckets: LruMap<K, Bucket, ByLength>,
+    accepted: u64,
+    rejected: u64,
+}
+
+/// Token bucket rate limiter, one bucket per key.
+///
+/// Each key can spend `burst` requests at once, and gets back `per_second` requests per second
+/// up to `burst`. Dropping the least recently used bucket past [`MAX_TRACKED_KEYS`] only resets
+/// a key that didn't send anything for the longest time.
+pub(crate) struct RateLimiter<K> {
+    per_second: f64,
+    burst: f64,
+    state: Mutex<RateLimiterState<K>>,
+}
+
+impl<K: Hash + Eq + Clone> RateLimiter<K> {
+    pub(crate) fn new(limit: &RateLimit) -> Self {
+        Self {
+            per_second: limit.per_second as f64,
+            burst: limit.burst.max(1) as f64,
+            state: Mutex::new(RateLimiterState {
+                buckets: LruMap::new(ByLength::new(MAX_TRACKED_KEYS)),
+                accepted: 0,
+                rejected: 0,
+            }),
+        }
+    }
+
+    /// Spends `count` requests of `key`, returns false if it doesn't have enough left.
+    pub(crate) fn check(&self, key: &K, count: u64) -> bool {
+        self.check_at(key, count, Instant::now())
+    }
+
+    fn check_at(&self, key: &K, count: u64, now: Instant) -> bool {
+        let mut state = self.state.lock().unwrap();
+
+        let tokens = state
+            .buckets
+            .get(key)
+            .map_or(self.burst, |bucket| self.refilled(bucket, now));
+        let allowed = tokens >= count as f64;
+        state.buckets.insert(
+            key.clone(),
+            Bucket {
+                tokens: if allowed {
+                    tokens - count as f64
+                } else {
+                    tokens
+                },
+                updated_at: now,
+            },
+        );
+        if allowed {
+            state.accepted += count;
+        } else {
+            state.rejected += count;
+        }
+        allowed
+    }
+
+    fn refilled(&self, bucket: &Bucket, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(bucket.updated_at);
+        (bucket.tokens + elapsed.as_secs_f64() * self.per_second).min(self.burst)
+    }
+
+    pub(crate) fn stats(&self) -> RateLimiterStats {
+        let state = self.state.lock().unwrap();
+        let now = Instant::now();
+        RateLimiterStats {
+            accepted: state.accepted,
+            rejected: state.rejected,
+            tracked_keys: state
+                .buckets
+                .iter()
+                .filter(|(_, bucket)| self.refilled(bucket, now) < self.burst)
+                .count() as u64,
+        }
+    }
+}
+
+impl<K> fmt::Debug for RateLimiter<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RateLimiter")
+            .field("per_second", &self.per_second)
+            .field("burst", &self.burst)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A [`Layer`] limiting the `eth_sendRawTransaction` calls per source IP.
+///
+/// The RPC server doesn't expose peer addresses to its middleware, so the source IP is the
+/// `X-Forwarded-For` entry appended by the outermost of the `trusted_proxy_hops` reverse proxies
+/// in front of the sequencer. The entries before it are set by the client and never trusted.
+/// Requests with fewer entries didn't go through the proxies and share a single bucket.
+#[derive(Debug, Clone)]
+pub(crate) struct IpRateLimitLayer {
+    limiter: Option<Arc<RateLimiter<IpAddr>>>,
+    trusted_proxy_hops: usize,
+}
+
+impl IpRateLimitLayer {
+    /// Creates a layer limiting calls with `limiter`, or passing them through if not set.
+    pub(crate) fn new(
+        limiter: Option<Arc<RateLimiter<IpAddr>>>,
+        trusted_proxy_hops: usize,
+    ) -> Self {
+        Self {
+            limiter,
+            trusted_proxy_hops,
+        }
+    }
+}
+
+impl<S> Layer<S> for IpRateLimitLayer {
+    type Service = IpRateLimit<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        IpRateLimit {
+            inner,
+            limiter: self.limiter.clone(),
+            trusted_proxy_hops: self.trusted_proxy_hops,
+        }
+    }
+}
+
+/// The service created by [`IpRateLimitLayer`].
+#[derive(Debug, Clone)]
+pub(crate) struct IpRateLimit<S> {
+    inner: S,
+    limiter: Option<Arc<RateLimiter<IpAddr>>>,
+    trusted_proxy_hops: usize,
+}
+
+impl<S> Service<Request<Body>> for IpRateLimit<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>, Error = BoxError> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        // The inner service was driven to readiness, so it is the one to be called
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let limiter = self.limiter.clone();
+        let trusted_proxy_hops = self.trusted_proxy_hops;
+
+        Box::pin(async move {
+            let Some(limiter) = limiter else {
+                return inner.call(request).await;
+            };
+            if request.method() != Method::POST {
+                return inner.call(request).await;
+            }
+            let ip = source_ip(&request, trusted_proxy_hops).unwrap_or(UNKNOWN_SOURCE_IP);
+
+            let (parts, mut body) = request.into_parts();
+            let mut bytes = vec![];
+            while let Some(chunk) = body.data().await {
+                bytes.extend_from_slice(&chunk?);
+                if bytes.len() > MAX_INSPECTED_BODY_SIZE {
+                    return Ok(error_response(
+                        StatusCode::PAYLOAD_TOO_LARGE,
+                        "request body too large",
+                    ));
+                }
+            }
+
+            let calls = count_limited_calls(&bytes);
+            if calls > 0 && !limiter.check(&ip, calls) {
+                tracing::debug!("Sequencer: rate limiting {} calls from {}", calls, ip);
+                return Ok(error_response(
+                    StatusCode::TOO_MANY_REQUESTS,
+                    "rate limit exceeded, try again later",
+                ));
+            }
+
+            inner
+                .call(Request::from_parts(parts, Body::from(bytes)))
+                .await
+        })
+    }
+}
+
+/// Returns the client IP appended to `X-Forwarded-For` by the outermost of the
+/// `trusted_proxy_hops` proxies, the one `trusted_proxy_hops` entries from the end.
+fn source_ip(request: &Request<Body>, trusted_proxy_hops: usize) -> Option<IpAddr> {
+    if trusted_proxy_hops == 0 {
+        return None;
+    }
+    let entries: Vec<&str> = request
+        .headers()
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .collect();
+    let index = entries.len().checked_sub(trusted_proxy_hops)?;
+    entries[index].trim().parse().ok()
+}
+
+/// Returns the number of calls to the limited method in a single or batch request.
+fn count_limited_calls(body: &[u8]) -> u64 {
+    let is_limited = |call: &serde_json::Value| {
+        call.get("method").and_then(|method| method.as_str()) == Some(LIMITED_METHOD)
+    };
+    match serde_json::from_slice::<serde_json::Value>(body) {
+        Ok(serde_json::Value::Array(calls)) => {
+            calls.iter().filter(|call| is_limited(call)).count() as u64
+        }
+        Ok(call) => is_limited(&call) as u64,
+        Err(_) => 0,
+    }
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response<Body> {
+    let body = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": null,
+        "error": { "code": RATE_LIMIT_EXCEEDED_CODE, "message": message },
+    });
+    let mut response = Response::new(Body::from(body.to_string()));
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn token_bucket_allows_bursts_and_refills() {
+        let limiter = RateLimiter::new(&RateLimit {
+            per_second: 2,
+            burst: 4,
+        });
+        let start = Instant::now();
+
+        assert!(limiter.check_at(&1u8, 3, start));
+        assert!(limiter.check_at(&1u8, 1, start));
+        assert!(!limiter.check_at(&1u8, 1, start));
+        // other keys have their own bucket
+        assert!(limiter.check_at(&2u8, 4, start));
+
+        // half a second gives one request back
+        let later = start + Duration::from_millis(500);
+        assert!(limiter.check_at(&1u8, 1, later));
+        assert!(!limiter.check_at(&1u8, 1, later));
+
+        // the bucket doesn't refill past the burst
+        let much_later = later + Duration::from_secs(60);
+        assert!(!limiter.check_at(&1u8, 5, much_later));
+        assert!(limiter.check_at(&1u8, 4, much_later));
+
+        let stats = limiter.stats();
+        assert_eq!(stats.accepted, 13);
+        assert_eq!(stats.rejected, 7);
+    }
+
+    #[test]
+    fn counts_limited_calls() {
+        assert_eq!(
+            count_limited_calls(
+                br#"{"jsonrpc":"2.0","id":1,"method":"eth_sendRawTransaction","params":["0x00"]}"#
+            ),
+            1
+        );
+        assert_eq!(
+            count_limited_calls(
+                br#"[{"jsonrpc":"2.0","id":1,"method":"eth_sendRawTransaction","params":["0x00"]},
+                    {"jsonrpc":"2.0","id":2,"method":"eth_blockNumber","params":[]},
+                    {"jsonrpc":"2.0","id":3,"method":"eth_sendRawTransaction","params":["0x01"]}]"#
+            ),
+            2
+        );
+        assert_eq!(
+            count_limited_calls(
+                br#"{"jsonrpc":"2.0","id":1,"method":"eth_blockNumber","params":[]}"#
+            ),
+            0
+        );
+        assert_eq!(count_limited_calls(b"not json"), 0);
+    }
+
+    #[test]
+    fn reads_source_ip_from_trusted_proxy_hop() {
+        let request = Request::builder()
+            .header("x-forwarded-for", "198.51.100.1, 203.0.113.7, 10.0.0.1")
+            .body(Body::empty())
+            .unwrap();
+        // the entries before the trusted hops are set by the client
+        assert_eq!(source_ip(&request, 1), Some("10.0.0.1".parse().unwrap()));
+        assert_eq!(source_ip(&request, 2), Some("203.0.113.7".parse().unwrap()));
+        assert_eq!(source_ip(&request, 4), None);
+        assert_eq!(source_ip(&request, 0), None);
+
+        // the proxy appends its own header to the one sent by the client
+        let request = Request::builder()
+            .header("x-forwarded-for", "198.51.100.1")
+            .header("x-forwarded-for", "2001:db8::1")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(source_ip(&request, 1), Some("2001:db8::1".parse().unwrap()));
+
+        // the proxy headers it doesn't set are not trusted
+        let request = Request::builder()
+            .header("x-real-ip", "2001:db8::1")
+            .body(Body::empty())
+            .unwrap();
+        assert_eq!(source_ip(&request, 1), None);
+    }
+
+    #[test]
+    fn drops_least_recently_used_buckets() {
+        let limiter = RateLimiter::new(&RateLimit {
+            per_second: 0,
+            burst: 1,
+        });
+        let start = Instant::now();
+
+        assert!(limiter.check_at(&0u32, 1, start));
+        for key in 1..MAX_TRACKED_KEYS {
+            assert!(limiter.check_at(&key, 1, start));
+        }
+        // the first key is still tracked, and becomes the most recently used one
+        assert!(!limiter.check_at(&0u32, 1, start));
+        assert!(limiter.check_at(&MAX_TRACKED_KEYS, 1, start));
+        assert_eq!(limiter.stats().tracked_keys, MAX_TRACKED_KEYS as u64);
+        assert!(!limiter.check_at(&0u32, 1, start));
+        // the least recently used key was dropped, and starts over with a full bucket
+        assert!(limiter.check_at(&1u32, 1, start));
+    }
+}
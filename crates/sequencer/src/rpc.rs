@@ -1,26 +1,75 @@
+use std::net::IpAddr;
 use std::sync::Arc;
+use std::time::Instant;
 
-use citrea_evm::Evm;
+use borsh::BorshSerialize;
+use citrea_evm::{
+    EncryptedMempoolConfig, EncryptedTransaction, EpochKeyAnnouncement, EpochKeyRelease,
+    EthApiError, Evm, PendingTransactionResult, QueryContext,
+};
 use futures::channel::mpsc::UnboundedSender;
+use jsonrpsee::types::error::INVALID_PARAMS_CODE;
 use jsonrpsee::types::ErrorObjectOwned;
 use jsonrpsee::RpcModule;
-use reth_primitives::{Bytes, FromRecoveredPooledTransaction, IntoRecoveredTransaction, B256};
+use reth_primitives::{
+    Address, BlockId, BlockNumberOrTag, Bytes, FromRecoveredPooledTransaction,
+    IntoRecoveredTransaction, TransactionSignedEcRecovered, B256, U256, U64,
+};
+use reth_rpc_types::txpool::{TxpoolContent, TxpoolInspect, TxpoolStatus};
+use reth_rpc_types::{TransactionInput, TransactionRequest};
 use reth_rpc_types_compat::transaction::from_recovered;
-use reth_transaction_pool::EthPooledTransaction;
+use reth_transaction_pool::{EthPooledTransaction, PoolTransaction};
+use serde::Serialize;
+use sov_db::ledger_db::LedgerDB;
 use sov_mock_da::{MockAddress, MockDaService};
 use sov_modules_api::utils::to_jsonrpsee_error_object;
 use sov_modules_api::WorkingSet;
-use tracing::info;
+use sov_rollup_interface::da::DaData;
+use sov_stf_runner::DiskWatchdog;
+use tracing::{debug, info};
 
-use crate::mempool::CitreaMempool;
+use crate::commitment_controller;
+use crate::conditional::{ConditionalTransactions, TransactionConditional};
+use crate::config::ValidationGasPolicyConfig;
+use crate::db_provider::DbProvider;
+use crate::encrypted_mempool::EncryptedMempool;
+use crate::full_nodes::FullNodeTracker;
+use crate::memory_budget::{MemoryBudget, MemoryUsage};
+use crate::mempool::{CitreaMempool, EvictionStats};
+use crate::rate_limiter::{RateLimitStats, RateLimiter, RATE_LIMIT_EXCEEDED_CODE};
 use crate::utils::recover_raw_transaction;
 
 const ETH_RPC_ERROR: &str = "ETH_RPC_ERROR";
 
+/// Error code of transactions rejected because the memory budget is exhausted,
+/// the "limit exceeded" code of EIP-1474.
+const MEMORY_BUDGET_EXCEEDED_CODE: i32 = -32005;
+
+/// Error code of conditional transactions whose conditions don't hold,
+/// the "transaction rejected" code of EIP-1474.
+const CONDITIONS_NOT_MET_CODE: i32 = -32003;
+
+/// State reads served by the sequencer instead of the EVM module, so that the `pending` tag
+/// reflects the transactions in the mempool.
+pub(crate) const PENDING_STATE_METHODS: [&str; 3] =
+    ["eth_getCode", "eth_getStorageAt", "eth_getTransactionCount"];
+
 pub(crate) struct RpcContext<C: sov_modules_api::Context> {
     pub mempool: Arc<CitreaMempool<C>>,
+    pub encrypted_mempool: Arc<EncryptedMempool>,
+    pub conditionals: Arc<ConditionalTransactions>,
+    pub full_nodes: Arc<FullNodeTracker>,
+    pub memory_budget: Arc<MemoryBudget>,
     pub l2_force_block_tx: UnboundedSender<()>,
     pub storage: C::Storage,
+    /// Node-scoped context of the EVM queries, the sequencer resolves no `safe` or `finalized` block.
+    pub query_context: QueryContext,
+    pub validation_gas_policy: ValidationGasPolicyConfig,
+    pub disk_watchdog: DiskWatchdog,
+    pub ip_rate_limiter: Option<Arc<RateLimiter<IpAddr>>>,
+    pub sender_rate_limiter: Option<Arc<RateLimiter<Address>>>,
+    pub ledger_db: LedgerDB,
+    pub min_soft_confirmations_per_commitment: u64,
 }
 
 pub(crate) fn create_rpc_module<C: sov_modules_api::Context>(
@@ -30,20 +79,44 @@ pub(crate) fn create_rpc_module<C: sov_modules_api::Context>(
     rpc.register_async_method("eth_sendRawTransaction", |parameters, ctx| async move {
         info!("Sequencer: eth_sendRawTransaction");
         let data: Bytes = parameters.one().unwrap();
+        send_raw_transaction(&ctx, data, None).await
+    })?;
+    rpc.register_async_method(
+        "eth_sendRawTransactionConditional",
+        |parameters, ctx| async move {
+            info!("Sequencer: eth_sendRawTransactionConditional");
+            let mut params = parameters.sequence();
+            let data: Bytes = params.next()?;
+            let conditional: TransactionConditional = params.next()?;
 
-        // Only check if the signature is valid for now
-        let recovered: reth_primitives::PooledTransactionsElementEcRecovered =
-            recover_raw_transaction(data.clone())?;
-
-        let pool_transaction = EthPooledTransaction::from_recovered_pooled_transaction(recovered);
+            conditional
+                .validate()
+                .map_err(|e| ErrorObjectOwned::owned(INVALID_PARAMS_CODE, e, None::<()>))?;
+            // reject the transactions whose conditions already don't hold
+            {
+                let evm = Evm::<C>::default();
+                let mut working_set = WorkingSet::<C>::new(ctx.storage.clone());
+                let block_number = evm.last_sealed_header(&mut working_set).number + 1;
+                let timestamp = chrono::Local::now().timestamp() as u64;
+                conditional
+                    .check(&evm, &mut working_set, block_number, timestamp)
+                    .map_err(|e| {
+                        ErrorObjectOwned::owned(
+                            CONDITIONS_NOT_MET_CODE,
+                            format!("conditions not met: {}", e),
+                            None::<()>,
+                        )
+                    })?;
+            }
 
-        // submit the transaction to the pool with an `External` origin
-        let hash: B256 = ctx
-            .mempool
-            .add_external_transaction(pool_transaction)
-            .await
-            .map_err(|e| to_jsonrpsee_error_object(e, ETH_RPC_ERROR))?;
-        Ok::<B256, ErrorObjectOwned>(hash)
+            send_raw_transaction(&ctx, data, Some(conditional)).await
+        },
+    )?;
+    rpc.register_async_method("net_peerCount", |_, ctx| async move {
+        info!("Sequencer: net_peerCount");
+        // the peers of the sequencer are the full nodes syncing from it
+        let connected = ctx.full_nodes.connected(Instant::now());
+        Ok::<U64, ErrorObjectOwned>(U64::from(connected))
     })?;
     rpc.register_async_method("eth_publishBatch", |_, ctx| async move {
         info!("Sequencer: eth_publishBatch");
@@ -74,18 +147,680 @@ pub(crate) fn create_rpc_module<C: sov_modules_api::Context>(
                 Ok::<Option<reth_rpc_types::Transaction>, ErrorObjectOwned>(Some(tx))
             }
             None => match mempool_only {
-                Ok(Some(true)) => Ok::<Option<reth_rpc_types::Transaction>, ErrorObjectOwned>(None),
+                Ok(Some(true)) => Ok::<Option<reth_rpc_types::Transaction>, ErrorObjectOwned>(
+                    ctx.mempool.get_replaced(&hash),
+                ),
                 _ => {
                     let evm = Evm::<C>::default();
                     let mut working_set = WorkingSet::<C>::new(ctx.storage.clone());
 
                     match evm.get_transaction_by_hash(hash, &mut working_set) {
-                        Ok(tx) => Ok::<Option<reth_rpc_types::Transaction>, ErrorObjectOwned>(tx),
+                        Ok(Some(tx)) => {
+                            Ok::<Option<reth_rpc_types::Transaction>, ErrorObjectOwned>(Some(tx))
+                        }
+                        // transactions replaced in the mempool are returned with their replacement
+                        Ok(None) => Ok(ctx.mempool.get_replaced(&hash)),
                         Err(e) => Err(to_jsonrpsee_error_object(e, ETH_RPC_ERROR)),
                     }
                 }
             },
         }
     })?;
+    rpc.register_async_method("eth_getCode", |parameters, ctx| async move {
+        let mut params = parameters.sequence();
+        let address: Address = params.next()?;
+        let block_number: Option<BlockId> = params.optional_next()?;
+        info!("Sequencer: eth_getCode({}, {:?})", address, block_number);
+
+        let evm = Evm::<C>::default();
+        let (mut working_set, block_number) = state_at(&ctx, &evm, block_number);
+        evm.get_code(&ctx.query_context, address, block_number, &mut working_set)
+            .map_err(|e| to_jsonrpsee_error_object(e, ETH_RPC_ERROR))
+    })?;
+    rpc.register_async_method("eth_getStorageAt", |parameters, ctx| async move {
+        let mut params = parameters.sequence();
+        let address: Address = params.next()?;
+        let index: U256 = params.next()?;
+        let block_number: Option<BlockId> = params.optional_next()?;
+        info!(
+            "Sequencer: eth_getStorageAt({}, {}, {:?})",
+            address, index, block_number
+        );
+
+        let evm = Evm::<C>::default();
+        let (mut working_set, block_number) = state_at(&ctx, &evm, block_number);
+        evm.get_storage_at(
+            &ctx.query_context,
+            address,
+            index,
+            block_number,
+            &mut working_set,
+        )
+        .map_err(|e| to_jsonrpsee_error_object(e, ETH_RPC_ERROR))
+    })?;
+    rpc.register_async_method("eth_getTransactionCount", |parameters, ctx| async move {
+        let mut params = parameters.sequence();
+        let address: Address = params.next()?;
+        let block_number: Option<BlockId> = params.optional_next()?;
+        info!(
+            "Sequencer: eth_getTransactionCount({}, {:?})",
+            address, block_number
+        );
+
+        let evm = Evm::<C>::default();
+        if block_number == Some(BlockId::Number(BlockNumberOrTag::Pending)) {
+            return Ok(U64::from(pending_nonce(&ctx, &evm, address)));
+        }
+        let mut working_set = WorkingSet::<C>::new(ctx.storage.clone());
+        evm.get_transaction_count(&ctx.query_context, address, block_number, &mut working_set)
+            .map_err(|e| to_jsonrpsee_error_object(e, ETH_RPC_ERROR))
+    })?;
+    rpc.register_async_method("citrea_dryRunBlock", |_, ctx| async move {
+        info!("Sequencer: citrea_dryRunBlock");
+        let evm = Evm::<C>::default();
+        let (_, block) = apply_next_block(&ctx, &evm);
+        Ok::<DryRunBlock, ErrorObjectOwned>(block)
+    })?;
+    rpc.register_async_method("citrea_checkTransaction", |parameters, ctx| async move {
+        info!("Sequencer: citrea_checkTransaction");
+        let data: Bytes = parameters.one()?;
+
+        let recovered = recover_raw_transaction(data)?;
+        let tx = EthPooledTransaction::from_recovered_pooled_transaction(recovered)
+            .to_recovered_transaction();
+
+        let evm = Evm::<C>::default();
+        Ok::<TransactionCheck, ErrorObjectOwned>(check_transaction(&ctx, &evm, tx))
+    })?;
+    rpc.register_async_method("citrea_previewCommitment", |parameters, ctx| async move {
+        let min_soft_confirmations_per_commitment: Option<u64> =
+            parameters.sequence().optional_next()?;
+        info!(
+            "Sequencer: citrea_previewCommitment({:?})",
+            min_soft_confirmations_per_commitment
+        );
+
+        preview_commitment(
+            &ctx,
+            min_soft_confirmations_per_commitment
+                .unwrap_or(ctx.min_soft_confirmations_per_commitment),
+        )
+        .map_err(|e| to_jsonrpsee_error_object(e, ETH_RPC_ERROR))
+    })?;
+    rpc.register_async_method("citrea_getEvictedTransactionStats", |_, ctx| async move {
+        info!("Sequencer: citrea_getEvictedTransactionStats");
+        Ok::<EvictionStats, ErrorObjectOwned>(ctx.mempool.eviction_stats())
+    })?;
+    rpc.register_async_method("txpool_content", |_, ctx| async move {
+        info!("Sequencer: txpool_content");
+        Ok::<TxpoolContent, ErrorObjectOwned>(ctx.mempool.content())
+    })?;
+    rpc.register_async_method("txpool_status", |_, ctx| async move {
+        info!("Sequencer: txpool_status");
+        Ok::<TxpoolStatus, ErrorObjectOwned>(ctx.mempool.status())
+    })?;
+    rpc.register_async_method("txpool_inspect", |_, ctx| async move {
+        info!("Sequencer: txpool_inspect");
+        Ok::<TxpoolInspect, ErrorObjectOwned>(ctx.mempool.inspect())
+    })?;
+    rpc.register_async_method("citrea_getMemoryUsage", |_, ctx| async move {
+        info!("Sequencer: citrea_getMemoryUsage");
+        ctx.memory_budget
+            .update_mempool_usage(&ctx.mempool, &ctx.encrypted_mempool);
+        Ok::<MemoryUsage, ErrorObjectOwned>(ctx.memory_budget.usage())
+    })?;
+    rpc.register_async_method("citrea_getRateLimitStats", |_, ctx| async move {
+        info!("Sequencer: citrea_getRateLimitStats");
+        Ok::<RateLimitStats, ErrorObjectOwned>(RateLimitStats {
+            per_ip: ctx.ip_rate_limiter.as_ref().map(|limiter| limiter.stats()),
+            per_sender: ctx
+                .sender_rate_limiter
+                .as_ref()
+                .map(|limiter| limiter.stats()),
+        })
+    })?;
+    rpc.register_async_method(
+        "citrea_sendEncryptedTransaction",
+        |parameters, ctx| async move {
+            info!("Sequencer: citrea_sendEncryptedTransaction");
+            let transaction: EncryptedTransaction = parameters.one()?;
+
+            if ctx.disk_watchdog.is_low_on_disk_space() {
+                return Err(to_jsonrpsee_error_object(
+                    "Sequencer is low on disk space, not accepting transactions",
+                    ETH_RPC_ERROR,
+                ));
+            }
+            check_memory_budget(
+                &ctx,
+                transaction.ephemeral_key.len() + transaction.ciphertext.len() + 32,
+            )?;
+
+            let (_, epoch) = encrypted_mempool_epoch(&ctx)?;
+            ctx.encrypted_mempool
+                .add_transaction(transaction, epoch)
+                .map_err(|e| to_jsonrpsee_error_object(e, ETH_RPC_ERROR))
+        },
+    )?;
+    rpc.register_async_method(
+        "citrea_getEncryptedMempoolEpochKey",
+        |parameters, ctx| async move {
+            let mut params = parameters.sequence();
+            let epoch: Option<U64> = params.optional_next()?;
+            info!("Sequencer: citrea_getEncryptedMempoolEpochKey({:?})", epoch);
+
+            let (_, current_epoch) = encrypted_mempool_epoch(&ctx)?;
+            let epoch = epoch.map(|epoch| epoch.to()).unwrap_or(current_epoch);
+            let mut working_set = WorkingSet::<C>::new(ctx.storage.clone());
+            Ok::<Option<Bytes>, ErrorObjectOwned>(
+                Evm::<C>::default().encrypted_mempool_epoch_key(epoch, &mut working_set),
+            )
+        },
+    )?;
+    rpc.register_async_method("citrea_announceEpochKey", |parameters, ctx| async move {
+        let announcement: EpochKeyAnnouncement = parameters.one()?;
+        info!("Sequencer: citrea_announceEpochKey({})", announcement.epoch);
+
+        let (config, epoch) = encrypted_mempool_epoch(&ctx)?;
+        let evm = Evm::<C>::default();
+        let mut working_set = WorkingSet::<C>::new(ctx.storage.clone());
+        let chain_id = evm
+            .chain_id(&mut working_set)?
+            .expect("EVM config must be set at genesis")
+            .to();
+        let Some(keyper) = announcement
+            .signer(chain_id)
+            .filter(|signer| config.keypers.contains(signer))
+        else {
+            return Err(to_jsonrpsee_error_object(
+                "Key announcement is not signed by a keyper",
+                ETH_RPC_ERROR,
+            ));
+        };
+        if announcement.epoch <= epoch {
+            return Err(to_jsonrpsee_error_object(
+                format!("Epoch {} already started", announcement.epoch),
+                ETH_RPC_ERROR,
+            ));
+        }
+        if !announcement.verify_proof_of_possession(chain_id, keyper) {
+            return Err(to_jsonrpsee_error_object(
+                "Invalid proof of possession of the public key share",
+                ETH_RPC_ERROR,
+            ));
+        }
+        if evm.is_epoch_key_announced(announcement.epoch, keyper, &mut working_set) {
+            return Err(to_jsonrpsee_error_object(
+                format!(
+                    "Keyper {} already announced its key of epoch {}",
+                    keyper, announcement.epoch
+                ),
+                ETH_RPC_ERROR,
+            ));
+        }
+
+        ctx.encrypted_mempool.add_announcement(announcement);
+        Ok::<(), ErrorObjectOwned>(())
+    })?;
+    rpc.register_async_method("citrea_releaseEpochKey", |parameters, ctx| async move {
+        let release: EpochKeyRelease = parameters.one()?;
+        info!(
+            "Sequencer: citrea_releaseEpochKey({}, {})",
+            release.epoch, release.keyper
+        );
+
+        let (config, epoch) = encrypted_mempool_epoch(&ctx)?;
+        if !config.keypers.contains(&release.keyper) {
+            return Err(to_jsonrpsee_error_object(
+                format!("{} is not a keyper", release.keyper),
+                ETH_RPC_ERROR,
+            ));
+        }
+        if release.epoch >= epoch {
+            return Err(to_jsonrpsee_error_object(
+                format!("Epoch {} did not end", release.epoch),
+                ETH_RPC_ERROR,
+            ));
+        }
+
+        ctx.encrypted_mempool.add_release(release);
+        Ok::<(), ErrorObjectOwned>(())
+    })?;
     Ok(rpc)
 }
+
+/// Returns the encrypted mempool config and the epoch of the next block,
+/// fails if the encrypted mempool is disabled.
+fn encrypted_mempool_epoch<C: sov_modules_api::Context>(
+    ctx: &RpcContext<C>,
+) -> Result<(EncryptedMempoolConfig, u64), ErrorObjectOwned> {
+    let evm = Evm::<C>::default();
+    let mut working_set = WorkingSet::<C>::new(ctx.storage.clone());
+    let Some(config) = evm.encrypted_mempool_config(&mut working_set) else {
+        return Err(to_jsonrpsee_error_object(
+            "Encrypted mempool is disabled",
+            ETH_RPC_ERROR,
+        ));
+    };
+    let block_number = evm.last_sealed_header(&mut working_set).number + 1;
+    let epoch = config.epoch_of(block_number);
+    Ok((config, epoch))
+}
+
+/// Submits a raw transaction to the mempool, with the conditions of
+/// `eth_sendRawTransactionConditional` if any.
+async fn send_raw_transaction<C: sov_modules_api::Context>(
+    ctx: &RpcContext<C>,
+    data: Bytes,
+    conditional: Option<TransactionConditional>,
+) -> Result<B256, ErrorObjectOwned> {
+    if ctx.disk_watchdog.is_low_on_disk_space() {
+        return Err(to_jsonrpsee_error_object(
+            "Sequencer is low on disk space, not accepting transactions",
+            ETH_RPC_ERROR,
+        ));
+    }
+    check_memory_budget(ctx, data.len())?;
+
+    // Only check if the signature is valid for now
+    let recovered: reth_primitives::PooledTransactionsElementEcRecovered =
+        recover_raw_transaction(data.clone())?;
+
+    if let Some(limiter) = &ctx.sender_rate_limiter {
+        if !limiter.check(&recovered.signer(), 1) {
+            debug!(
+                "Sequencer: rate limiting transactions of {}",
+                recovered.signer()
+            );
+            return Err(ErrorObjectOwned::owned(
+                RATE_LIMIT_EXCEEDED_CODE,
+                "sender rate limit exceeded, try again later",
+                None::<()>,
+            ));
+        }
+    }
+
+    let pool_transaction = EthPooledTransaction::from_recovered_pooled_transaction(recovered);
+
+    // transactions of frozen accounts would be dropped from the blocks
+    {
+        let evm = Evm::<C>::default();
+        let mut working_set = WorkingSet::<C>::new(ctx.storage.clone());
+        let frozen = evm.is_account_frozen(&pool_transaction.sender(), &mut working_set)
+            || pool_transaction
+                .to()
+                .is_some_and(|to| evm.is_account_frozen(&to, &mut working_set));
+        if frozen {
+            return Err(to_jsonrpsee_error_object(
+                "account is frozen by governance",
+                ETH_RPC_ERROR,
+            ));
+        }
+    }
+
+    // the conditions are registered first, so that the transaction isn't included
+    // in a block without checking them
+    let hash = *pool_transaction.hash();
+    if let Some(conditional) = conditional {
+        ctx.conditionals.insert(hash, conditional);
+    }
+
+    // submit the transaction to the pool with an `External` origin
+    // pool errors are mapped to the codes and messages of geth, e.g. "nonce too low",
+    // which tooling like hardhat, foundry and viem match on
+    let hash = ctx
+        .mempool
+        .add_external_transaction(pool_transaction)
+        .await
+        .map_err(|e| {
+            if ctx.mempool.get(&hash).is_none() {
+                ctx.conditionals.remove(&hash);
+            }
+            ErrorObjectOwned::from(EthApiError::from(e))
+        })?;
+
+    // the timeline of the transaction starts at its submission
+    if let Err(e) = ctx.ledger_db.put_tx_submission(hash.0) {
+        debug!("Failed to record the submission of {}: {:?}", hash, e);
+    }
+    Ok(hash)
+}
+
+/// Result of building a block from the current mempool without publishing it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct DryRunBlock {
+    /// Base fee of the block.
+    pub base_fee: u64,
+    /// Block gas limit.
+    pub gas_limit: u64,
+    /// Total gas used by the transactions.
+    pub gas_used: u64,
+    /// Total state diff size of the transactions.
+    pub diff_size: u64,
+    /// Transactions in the order they would be included, including the invalid ones.
+    pub transactions: Vec<PendingTransactionResult>,
+}
+
+/// Problem found by `citrea_checkTransaction` in a transaction that wasn't submitted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub(crate) enum TransactionWarning {
+    /// The transaction is already in the mempool.
+    AlreadyKnown,
+    /// The nonce was already used by the sender.
+    NonceTooLow { expected: u64, nonce: u64 },
+    /// Transactions of the sender with lower nonces are missing, the transaction
+    /// waits in the mempool until they are sent.
+    NonceGap { expected: u64, nonce: u64 },
+    /// The max fee per gas is below the base fee of the next block.
+    Underpriced {
+        base_fee: u64,
+        max_fee_per_gas: u128,
+    },
+    /// The gas limit doesn't fit in a block.
+    ExceedsBlockGasLimit {
+        block_gas_limit: u64,
+        gas_limit: u64,
+    },
+    /// The sender can't pay the gas limit at the max fee per gas plus the value.
+    InsufficientFunds { balance: U256, cost: U256 },
+    /// The transaction reverts or is invalid when executed on top of the pending state.
+    WillRevert { reason: String },
+}
+
+/// Result of `citrea_checkTransaction`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct TransactionCheck {
+    pub hash: B256,
+    pub from: Address,
+    pub nonce: u64,
+    /// Gas used when executed in the next block, if the nonce is the next one of the sender.
+    pub gas_used: Option<u64>,
+    /// Empty if the transaction would be accepted and executed successfully.
+    pub warnings: Vec<TransactionWarning>,
+}
+
+/// Runs the admission checks of the mempool against the pending state, then simulates the
+/// transaction on top of it, without submitting it.
+fn check_transaction<C: sov_modules_api::Context>(
+    ctx: &RpcContext<C>,
+    evm: &Evm<C>,
+    tx: TransactionSignedEcRecovered,
+) -> TransactionCheck {
+    let hash = tx.hash();
+    let from = tx.signer();
+    let mut warnings = vec![];
+
+    if ctx.mempool.get(&hash).is_some() {
+        warnings.push(TransactionWarning::AlreadyKnown);
+    }
+
+    let (mut working_set, block) = apply_next_block(ctx, evm);
+    let latest = Some(BlockNumberOrTag::Latest.into());
+
+    let expected = evm
+        .get_transaction_count(&ctx.query_context, from, latest, &mut working_set)
+        .map(|nonce| nonce.to::<u64>())
+        .unwrap_or_default();
+    if tx.nonce() < expected {
+        warnings.push(TransactionWarning::NonceTooLow {
+            expected,
+            nonce: tx.nonce(),
+        });
+    } else if tx.nonce() > expected {
+        warnings.push(TransactionWarning::NonceGap {
+            expected,
+            nonce: tx.nonce(),
+        });
+    }
+
+    if tx.max_fee_per_gas() < block.base_fee as u128 {
+        warnings.push(TransactionWarning::Underpriced {
+            base_fee: block.base_fee,
+            max_fee_per_gas: tx.max_fee_per_gas(),
+        });
+    }
+
+    if tx.gas_limit() > block.gas_limit {
+        warnings.push(TransactionWarning::ExceedsBlockGasLimit {
+            block_gas_limit: block.gas_limit,
+            gas_limit: tx.gas_limit(),
+        });
+    }
+
+    let balance = evm
+        .get_balance(&ctx.query_context, from, latest, &mut working_set)
+        .unwrap_or_default();
+    let cost =
+        U256::from(tx.gas_limit()) * U256::from(tx.max_fee_per_gas()) + U256::from(tx.value());
+    if balance < cost {
+        warnings.push(TransactionWarning::InsufficientFunds { balance, cost });
+    }
+
+    // The call ignores the nonce, so the outcome is known even with a nonce gap
+    let request = TransactionRequest {
+        from: Some(from),
+        to: tx.to(),
+        max_fee_per_gas: Some(U256::from(tx.max_fee_per_gas())),
+        max_priority_fee_per_gas: tx.max_priority_fee_per_gas().map(U256::from),
+        value: Some(U256::from(tx.value())),
+        gas: Some(U256::from(tx.gas_limit())),
+        input: TransactionInput {
+            data: None,
+            input: Some(tx.input().clone()),
+        },
+        chain_id: tx.chain_id().map(U64::from),
+        ..Default::default()
+    };
+    let mut reverted = false;
+    if let Err(e) = evm.get_call(
+        &ctx.query_context,
+        request,
+        latest,
+        None,
+        None,
+        &mut working_set,
+    ) {
+        reverted = true;
+        warnings.push(TransactionWarning::WillRevert {
+            reason: e.message().to_string(),
+        });
+    }
+
+    // Executing it the way the block would also charges the L1 fee
+    let mut gas_used = None;
+    if tx.nonce() == expected {
+        let result = evm
+            .apply_pending_transactions(&[tx.clone()], &mut working_set)
+            .remove(0);
+        gas_used = Some(result.gas_used);
+        if !result.success && !reverted {
+            warnings.push(TransactionWarning::WillRevert {
+                reason: result
+                    .error
+                    .unwrap_or_else(|| "execution reverted".to_string()),
+            });
+        }
+    }
+
+    TransactionCheck {
+        hash,
+        from,
+        nonce: tx.nonce(),
+        gas_used,
+        warnings,
+    }
+}
+
+/// The next sequencer commitment, as it would be sent once the sequencer sees a new L1 block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct CommitmentPreview {
+    /// First L2 height committed to.
+    pub l2_start_height: u64,
+    /// Last L2 height committed to.
+    pub l2_end_height: u64,
+    /// L1 height of the first soft confirmation.
+    pub l1_start_height: u64,
+    /// L1 height of the last soft confirmation.
+    pub l1_end_height: u64,
+    pub l1_start_block_hash: B256,
+    pub l1_end_block_hash: B256,
+    /// Merkle root of the soft confirmation hashes.
+    pub merkle_root: B256,
+    /// Whether the range has enough soft confirmations to be committed to.
+    pub ready: bool,
+    pub min_soft_confirmations_per_commitment: u64,
+    /// Size of the commitment blob sent to the DA layer, in bytes.
+    pub da_size: u64,
+    /// L1 fee rate of the latest soft confirmation.
+    pub l1_fee_rate: u64,
+    /// Fee of the blob at the L1 fee rate, the overhead of the DA transactions not included.
+    pub estimated_fee: u64,
+}
+
+/// Builds the commitment the sequencer would send if the L1 block of its latest soft confirmation
+/// were finalized now. Returns `None` if the soft confirmations since the last commitment are
+/// all on L1 blocks already committed to.
+fn preview_commitment<C: sov_modules_api::Context>(
+    ctx: &RpcContext<C>,
+    min_soft_confirmations_per_commitment: u64,
+) -> anyhow::Result<Option<CommitmentPreview>> {
+    let Some((_, head)) = ctx.ledger_db.get_head_soft_batch()? else {
+        return Ok(None);
+    };
+    let prev_l1_height = head.da_slot_height;
+    if let Some(last_commitment_l1_height) =
+        ctx.ledger_db.get_last_sequencer_commitment_l1_height()?
+    {
+        if last_commitment_l1_height.0 >= prev_l1_height {
+            return Ok(None);
+        }
+    }
+
+    // Built regardless of the minimum, so that short ranges can be previewed too
+    let Some(commitment_info) =
+        commitment_controller::get_commitment_info(&ctx.ledger_db, 0, prev_l1_height)
+    else {
+        return Ok(None);
+    };
+    let l2_height_range = commitment_info.l2_height_range.clone();
+    let l1_height_range = commitment_info.l1_height_range.clone();
+    let soft_confirmations = l2_height_range.end().0 - l2_height_range.start().0 + 1;
+
+    let soft_confirmation_hashes =
+        commitment_controller::get_soft_confirmation_hashes(&ctx.ledger_db, &l2_height_range);
+    let commitment =
+        commitment_controller::get_commitment(commitment_info, soft_confirmation_hashes);
+    let da_size = DaData::SequencerCommitment(commitment.clone())
+        .try_to_vec()?
+        .len() as u64;
+
+    Ok(Some(CommitmentPreview {
+        l2_start_height: l2_height_range.start().0,
+        l2_end_height: l2_height_range.end().0,
+        l1_start_height: l1_height_range.start().0,
+        l1_end_height: l1_height_range.end().0,
+        l1_start_block_hash: B256::from(commitment.l1_start_block_hash),
+        l1_end_block_hash: B256::from(commitment.l1_end_block_hash),
+        merkle_root: B256::from(commitment.merkle_root),
+        ready: soft_confirmations >= min_soft_confirmations_per_commitment,
+        min_soft_confirmations_per_commitment,
+        da_size,
+        l1_fee_rate: head.l1_fee_rate,
+        estimated_fee: da_size.saturating_mul(head.l1_fee_rate),
+    }))
+}
+
+/// Applies the mempool transactions that would be included in the next block
+/// on top of the latest state of the returned working set.
+fn apply_next_block<C: sov_modules_api::Context>(
+    ctx: &RpcContext<C>,
+    evm: &Evm<C>,
+) -> (WorkingSet<C>, DryRunBlock) {
+    let db_provider = DbProvider::new(ctx.storage.clone());
+    let cfg = db_provider.cfg();
+    let base_fee = db_provider.next_block_base_fee();
+    let txs = ctx.mempool.next_block_transactions(
+        base_fee,
+        cfg.block_gas_limit,
+        &ctx.validation_gas_policy,
+        &ctx.conditionals,
+    );
+
+    let mut working_set = WorkingSet::<C>::new(ctx.storage.clone());
+    let transactions = evm.apply_pending_transactions(&txs, &mut working_set);
+    let block = DryRunBlock {
+        base_fee,
+        gas_limit: cfg.block_gas_limit,
+        gas_used: transactions.iter().map(|tx| tx.gas_used).sum(),
+        diff_size: transactions.iter().map(|tx| tx.diff_size).sum(),
+        transactions,
+    };
+    (working_set, block)
+}
+
+/// Rejects a transaction of `size` bytes if it doesn't fit in the memory budget,
+/// so that spam is shed before it can exhaust the sequencer's memory.
+fn check_memory_budget<C: sov_modules_api::Context>(
+    ctx: &RpcContext<C>,
+    size: usize,
+) -> Result<(), ErrorObjectOwned> {
+    ctx.memory_budget
+        .update_mempool_usage(&ctx.mempool, &ctx.encrypted_mempool);
+    if ctx.memory_budget.has_room_for(size as u64) {
+        return Ok(());
+    }
+
+    debug!(
+        "Sequencer: rejecting transaction, memory budget exhausted: {:?}",
+        ctx.memory_budget.usage()
+    );
+    Err(ErrorObjectOwned::owned(
+        MEMORY_BUDGET_EXCEEDED_CODE,
+        "sequencer memory budget exceeded, try again later",
+        None::<()>,
+    ))
+}
+
+/// Returns the nonce of the next transaction of `sender`, after its transactions in the
+/// mempool with consecutive nonces, whether they fit in the next block or are queued.
+fn pending_nonce<C: sov_modules_api::Context>(
+    ctx: &RpcContext<C>,
+    evm: &Evm<C>,
+    sender: Address,
+) -> u64 {
+    let mut working_set = WorkingSet::<C>::new(ctx.storage.clone());
+    let state_nonce = evm
+        .get_transaction_count(
+            &ctx.query_context,
+            sender,
+            Some(BlockNumberOrTag::Latest.into()),
+            &mut working_set,
+        )
+        .map(|nonce| nonce.to::<u64>())
+        .unwrap_or_default();
+    ctx.mempool.next_nonce(sender, state_nonce)
+}
+
+/// Returns a working set to read the state at `block_number` from.
+/// For the `pending` tag, the mempool transactions that would be included in the next block
+/// are applied on top of the latest state, and the tag is replaced with `latest`.
+fn state_at<C: sov_modules_api::Context>(
+    ctx: &RpcContext<C>,
+    evm: &Evm<C>,
+    block_number: Option<BlockId>,
+) -> (WorkingSet<C>, Option<BlockId>) {
+    if block_number != Some(BlockId::Number(BlockNumberOrTag::Pending)) {
+        return (WorkingSet::<C>::new(ctx.storage.clone()), block_number);
+    }
+
+    let (working_set, _) = apply_next_block(ctx, evm);
+    (working_set, Some(BlockNumberOrTag::Latest.into()))
+}
@@ -1,43 +1,50 @@
 use std::cmp::Ordering;
-use std::marker::PhantomData;
-use std::net::SocketAddr;
+use std::collections::HashSet;
+use std::net::{IpAddr, SocketAddr};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 use std::vec;
 
 use borsh::ser::BorshSerialize;
-use citrea_evm::{CallMessage, RlpEvmTransaction};
-use citrea_stf::runtime::Runtime;
-use digest::Digest;
+use citrea_evm::{AbiRegistry, CallMessage, Evm, QueryContext, RlpEvmTransaction};
 use futures::channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender};
 use futures::StreamExt;
 use jsonrpsee::RpcModule;
-use reth_primitives::IntoRecoveredTransaction;
-use reth_provider::BlockReaderIdExt;
-use reth_transaction_pool::{BestTransactionsAttributes, PoolTransaction};
-use sov_accounts::Accounts;
-use sov_accounts::Response::{AccountEmpty, AccountExists};
-use sov_db::ledger_db::{LedgerDB, SlotCommit};
+use reth_primitives::{Address, TransactionSignedEcRecovered};
+use sequencer_client::{GetSoftBatchResponse, LocalMempool, PushedSoftBatch};
+use soft_confirmation_rule_enforcer::SoftConfirmationRuleEnforcer;
+use sov_db::ledger_db::LedgerDB;
 use sov_db::schema::types::{BatchNumber, SlotNumber};
-use sov_modules_api::hooks::HookSoftConfirmationInfo;
-use sov_modules_api::transaction::Transaction;
-use sov_modules_api::{
-    Context, EncodeCall, PrivateKey, SignedSoftConfirmationBatch, SlotData,
-    UnsignedSoftConfirmationBatch, WorkingSet,
-};
+use sov_modules_api::{Context, PrivateKey, SlotData, WorkingSet};
 use sov_modules_stf_blueprint::StfBlueprintTrait;
 use sov_rollup_interface::da::{BlockHeaderTrait, DaData, DaSpec};
+use sov_rollup_interface::rpc::SoftBatchResponse;
 use sov_rollup_interface::services::da::DaService;
-use sov_rollup_interface::stf::{SoftBatchReceipt, StateTransitionFunction};
+use sov_rollup_interface::stf::StateTransitionFunction;
 use sov_rollup_interface::storage::HierarchicalStorageManager;
 use sov_rollup_interface::zk::ZkvmHost;
-use sov_stf_runner::{InitVariant, RpcConfig, RunnerConfig};
+use sov_stf_runner::{
+    apply_rpc_allowlist, batch_request_config, BatchLimitLayer, DiskWatchdog, HttpCacheLayer,
+    InitVariant, RpcConfig, RunnerConfig,
+};
 use tracing::{debug, info, warn};
 
+use crate::block_scheduler::BlockScheduler;
 use crate::commitment_controller;
+use crate::conditional::ConditionalTransactions;
 use crate::config::SequencerConfig;
 use crate::db_provider::DbProvider;
+use crate::encrypted_mempool::EncryptedMempool;
+use crate::full_nodes::{FullNodeTracker, FullNodeTrackingLayer};
+use crate::memory_budget::{MemoryBudget, MemoryComponent};
 use crate::mempool::CitreaMempool;
-use crate::rpc::{create_rpc_module, RpcContext};
+use crate::metrics::SEQUENCER_BLOCK_PRODUCTION_SECONDS;
+#[cfg(feature = "testing")]
+use crate::misbehavior::apply_misbehaviors;
+use crate::rate_limiter::{IpRateLimitLayer, RateLimiter};
+use crate::rpc::{create_rpc_module, RpcContext, PENDING_STATE_METHODS};
+use crate::soft_batch_builder::SoftBatchBuilder;
+use crate::soft_batch_push::SoftBatchPusher;
 
 type StateRoot<ST, Vm, Da> = <ST as StateTransitionFunction<Vm, Da>>::StateRoot;
 
@@ -52,6 +59,12 @@ where
 {
     da_service: Da,
     mempool: Arc<CitreaMempool<C>>,
+    encrypted_mempool: Arc<EncryptedMempool>,
+    conditionals: Arc<ConditionalTransactions>,
+    full_nodes: Arc<FullNodeTracker>,
+    memory_budget: Arc<MemoryBudget>,
+    ip_rate_limiter: Option<Arc<RateLimiter<IpAddr>>>,
+    sender_rate_limiter: Option<Arc<RateLimiter<Address>>>,
     sov_tx_signer_priv_key: C::PrivateKey,
     l2_force_block_tx: UnboundedSender<()>,
     l2_force_block_rx: UnboundedReceiver<()>,
@@ -64,6 +77,9 @@ where
     state_root: StateRoot<Stf, Vm, Da::Spec>,
     sequencer_pub_key: Vec<u8>,
     rpc_config: RpcConfig,
+    query_context: QueryContext,
+    disk_watchdog: DiskWatchdog,
+    soft_batch_pusher: SoftBatchPusher,
 }
 
 impl<C, Da, Sm, Vm, Stf> CitreaSequencer<C, Da, Sm, Vm, Stf>
@@ -93,6 +109,10 @@ where
         ledger_db: LedgerDB,
         runner_config: RunnerConfig,
     ) -> Result<Self, anyhow::Error> {
+        anyhow::ensure!(
+            cfg!(feature = "testing") || config.test_misbehaviors.is_empty(),
+            "Sequencer: test_misbehaviors are only applied by builds with the testing feature",
+        );
         let (l2_force_block_tx, l2_force_block_rx) = unbounded();
 
         let prev_state_root = match init_variant {
@@ -119,9 +139,33 @@ where
 
         let pool = CitreaMempool::new(db_provider.clone(), config.mempool_conf.clone());
 
+        let rpc_config = runner_config.rpc_config;
+        let mut query_context = QueryContext::default();
+        if !rpc_config.archive {
+            query_context =
+                query_context.with_state_history_blocks(rpc_config.state_history_blocks);
+        }
+        if let Some(path) = &rpc_config.abi_registry_path {
+            query_context = query_context.with_abi_registry(Arc::new(AbiRegistry::from_dir(path)?));
+        }
+
         Ok(Self {
             da_service,
             mempool: Arc::new(pool),
+            encrypted_mempool: Arc::new(EncryptedMempool::default()),
+            conditionals: Arc::new(ConditionalTransactions::default()),
+            full_nodes: Arc::new(FullNodeTracker::default()),
+            memory_budget: Arc::new(MemoryBudget::new(config.memory_budget_mb)),
+            ip_rate_limiter: config
+                .rate_limits
+                .per_ip
+                .as_ref()
+                .map(|limit| Arc::new(RateLimiter::new(limit))),
+            sender_rate_limiter: config
+                .rate_limits
+                .per_sender
+                .as_ref()
+                .map(|limit| Arc::new(RateLimiter::new(limit))),
             sov_tx_signer_priv_key,
             l2_force_block_tx,
             l2_force_block_rx,
@@ -133,16 +177,47 @@ where
             storage_manager,
             state_root: prev_state_root,
             sequencer_pub_key,
-            rpc_config: runner_config.rpc_config,
+            rpc_config,
+            query_context,
+            disk_watchdog: DiskWatchdog::default(),
+            soft_batch_pusher: SoftBatchPusher::default(),
         })
     }
 
+    /// Returns the mempool as read by the RPC of the node, the effective tips of its
+    /// executable transactions being sampled by the gas price oracle and its pending and
+    /// replaced transactions feeding the filters and subscriptions. The RPC caches of the node
+    /// are counted in the memory budget of the sequencer.
+    pub fn local_mempool(&self) -> LocalMempool {
+        let tips_mempool = self.mempool.clone();
+        let pending_mempool = self.mempool.clone();
+        let replaced_mempool = self.mempool.clone();
+        let memory_budget = self.memory_budget.clone();
+        LocalMempool {
+            tips: Arc::new(move |base_fee| tips_mempool.pending_tips(base_fee)),
+            pending_transactions: Arc::new(move || pending_mempool.pending_transactions_listener()),
+            replaced_transactions: Arc::new(move || {
+                replaced_mempool.replaced_transactions_listener()
+            }),
+            rpc_caches_usage: Arc::new(move |bytes| {
+                memory_budget.set_usage(MemoryComponent::RpcCaches, bytes)
+            }),
+        }
+    }
+
+    /// Stops accepting transactions while the watchdog reports low disk space.
+    pub fn with_disk_watchdog(mut self, disk_watchdog: DiskWatchdog) -> Self {
+        self.disk_watchdog = disk_watchdog;
+        self
+    }
+
     pub async fn start_rpc_server(
         &self,
         channel: Option<tokio::sync::oneshot::Sender<SocketAddr>>,
         methods: RpcModule<()>,
     ) -> Result<(), anyhow::Error> {
         let methods = self.register_rpc_methods(methods)?;
+        let methods = apply_rpc_allowlist(methods, self.rpc_config.allowed_methods.as_deref())?;
 
         let listen_address = SocketAddr::new(
             self.rpc_config
@@ -153,10 +228,21 @@ where
         );
 
         let max_connections = self.rpc_config.max_connections;
+        let batch_config = batch_request_config(&self.rpc_config);
+        let middleware = tower::ServiceBuilder::new()
+            .layer(FullNodeTrackingLayer::new(self.full_nodes.clone()))
+            .layer(IpRateLimitLayer::new(
+                self.ip_rate_limiter.clone(),
+                self.config.rate_limits.trusted_proxy_hops,
+            ))
+            .layer(BatchLimitLayer::new(&self.rpc_config))
+            .layer(HttpCacheLayer::new(self.rpc_config.response_etags));
 
         let _handle = tokio::spawn(async move {
             let server = jsonrpsee::server::ServerBuilder::default()
                 .max_connections(max_connections)
+                .set_batch_request_config(batch_config)
+                .set_middleware(middleware)
                 .build([listen_address].as_ref())
                 .await
                 .unwrap();
@@ -197,24 +283,12 @@ where
             "Sequencer: L1 height mismatch, expected {da_height} (or {da_height}-1), got {l1_height}",
         );
 
-        let timestamp = chrono::Local::now().timestamp() as u64;
+        if !self.is_leader(da_height) {
+            debug!("Sequencer: not the leader of DA block {da_height}, skipping");
+            return Ok(());
+        }
 
-        let batch_info = HookSoftConfirmationInfo {
-            da_slot_height: da_block.header().height(),
-            da_slot_hash: da_block.header().hash().into(),
-            da_slot_txs_commitment: da_block.header().txs_commitment().into(),
-            pre_state_root: self.state_root.clone().as_ref().to_vec(),
-            pub_key: self.sov_tx_signer_priv_key.pub_key().try_to_vec().unwrap(),
-            l1_fee_rate,
-            timestamp,
-        };
-        let mut signed_batch: SignedSoftConfirmationBatch = batch_info.clone().into();
-        // initially create sc info and call begin soft confirmation hook with it
-        let call_txs = CallMessage { txs: rlp_txs };
-        let raw_message =
-            <Runtime<C, Da::Spec> as EncodeCall<citrea_evm::Evm<C>>>::encode_call(call_txs);
-        let signed_blob = self.make_blob(raw_message);
-        let txs = vec![signed_blob.clone()];
+        let timestamp = chrono::Local::now().timestamp() as u64;
 
         let prestate = self
             .storage_manager
@@ -226,149 +300,248 @@ where
             hex::encode(da_block.header().hash().into())
         );
 
-        let pub_key = signed_batch.pub_key().clone();
+        let evm = Evm::<C>::default();
+        let mut working_set = WorkingSet::<C>::new(self.storage.clone());
+        let block_number = evm.last_sealed_header(&mut working_set).number + 1;
+        let encrypted_mempool_config = evm.encrypted_mempool_config(&mut working_set);
+        let encrypted = encrypted_mempool_config.as_ref().and_then(|config| {
+            let epoch_key_announced = evm
+                .encrypted_mempool_epoch_key(config.epoch_of(block_number), &mut working_set)
+                .is_some();
+            self.encrypted_mempool
+                .next_batch(config, block_number, epoch_key_announced)
+        });
 
-        match self.stf.begin_soft_batch(
-            &pub_key,
+        // the governance actions posted to the DA block are applied by the STF from its blobs
+        let mut da_blobs = self.da_service.extract_relevant_blobs(&da_block);
+        let builder = SoftBatchBuilder::<C, Da::Spec, Vm, Stf>::new(
+            &self.stf,
+            &self.sov_tx_signer_priv_key,
+            &self.sequencer_pub_key,
+            &self.storage,
+        );
+        // the receipt is only tampered with by the misbehaviors of the testing builds
+        #[cfg_attr(not(feature = "testing"), allow(unused_mut))]
+        let mut built = match builder.build(
             &self.state_root,
-            prestate.clone(),
-            Default::default(),
+            prestate,
             da_block.header(),
-            &mut signed_batch,
+            &mut da_blobs,
+            l1_fee_rate,
+            timestamp,
+            CallMessage {
+                txs: rlp_txs,
+                encrypted: encrypted.clone(),
+            },
         ) {
-            (Ok(()), batch_workspace) => {
-                let (batch_workspace, tx_receipts) =
-                    self.stf.apply_soft_batch_txs(txs.clone(), batch_workspace);
-
-                // create the unsigned batch with the txs then sign th sc
-                let unsigned_batch = UnsignedSoftConfirmationBatch::new(
-                    da_block.header().height(),
-                    da_block.header().hash().into(),
-                    da_block.header().txs_commitment().into(),
-                    self.state_root.clone().as_ref().to_vec(),
-                    txs,
-                    l1_fee_rate,
-                    timestamp,
+            Ok(built) => built,
+            Err(err) => {
+                warn!(
+                    "Failed to apply soft confirmation hook: {:?} \n reverting batch workspace",
+                    err
                 );
+                return Ok(());
+            }
+        };
 
-                let mut signed_soft_batch = self.sign_soft_confirmation_batch(unsigned_batch);
+        if built.state_root.as_ref() == self.state_root.as_ref() {
+            debug!("Limiting number is reached for the current L1 block. State root is the same as before, skipping");
+            // TODO: Check if below is legit
+            self.storage_manager
+                .save_change_set_l2(l2_height, built.change_set)?;
 
-                let (batch_receipt, checkpoint) = self.stf.end_soft_batch(
-                    self.sequencer_pub_key.as_ref(),
-                    &mut signed_soft_batch,
-                    tx_receipts,
-                    batch_workspace,
-                );
+            tracing::debug!("Finalizing l2 height: {:?}", l2_height);
+            self.storage_manager.finalize_l2(l2_height)?;
+            return Ok(());
+        }
 
-                // Finalize soft confirmation
-                let slot_result = self.stf.finalize_soft_batch(
-                    batch_receipt,
-                    checkpoint,
-                    prestate,
-                    &mut signed_soft_batch,
-                );
+        info!("State root after applying slot: {:?}", built.state_root);
 
-                if slot_result.state_root.as_ref() == self.state_root.as_ref() {
-                    debug!("Limiting number is reached for the current L1 block. State root is the same as before, skipping");
-                    // TODO: Check if below is legit
-                    self.storage_manager
-                        .save_change_set_l2(l2_height, slot_result.change_set)?;
+        // TODO: this will only work for mock da
+        // when https://github.com/Sovereign-Labs/sovereign-sdk/issues/1218
+        // is merged, rpc will access up to date storage then we won't need to finalize rigth away.
+        // however we need much better DA + finalization logic here
+        self.storage_manager
+            .save_change_set_l2(l2_height, built.change_set)?;
 
-                    tracing::debug!("Finalizing l2 height: {:?}", l2_height);
-                    self.storage_manager.finalize_l2(l2_height)?;
-                    return Ok(());
-                }
+        tracing::debug!("Finalizing l2 height: {:?}", l2_height);
+        self.storage_manager.finalize_l2(l2_height)?;
 
-                info!(
-                    "State root after applying slot: {:?}",
-                    slot_result.state_root
-                );
+        self.state_root = built.state_root;
 
-                let mut data_to_commit = SlotCommit::new(da_block.clone());
-                for receipt in slot_result.batch_receipts {
-                    data_to_commit.add_batch(receipt);
-                }
+        #[cfg(feature = "testing")]
+        apply_misbehaviors(
+            &self.config.test_misbehaviors,
+            l2_height,
+            &builder,
+            &mut built.receipt,
+        );
+        self.ledger_db.commit_soft_batch(built.receipt, true)?;
+        if self.soft_batch_pusher.is_enabled() {
+            self.push_head_soft_batch()?;
+        }
 
-                // TODO: This will be a single receipt once we have apply_soft_batch.
-                let batch_receipt = data_to_commit.batch_receipts()[0].clone();
-
-                let next_state_root = slot_result.state_root;
-
-                let soft_batch_receipt = SoftBatchReceipt::<_, _, Da::Spec> {
-                    pre_state_root: self.state_root.as_ref().to_vec(),
-                    post_state_root: next_state_root.as_ref().to_vec(),
-                    phantom_data: PhantomData::<u64>,
-                    batch_hash: batch_receipt.batch_hash,
-                    da_slot_hash: da_block.header().hash(),
-                    da_slot_height: da_block.header().height(),
-                    da_slot_txs_commitment: da_block.header().txs_commitment(),
-                    tx_receipts: batch_receipt.tx_receipts,
-                    soft_confirmation_signature: signed_soft_batch.signature().to_vec(),
-                    pub_key: signed_soft_batch.pub_key().to_vec(),
-                    l1_fee_rate: signed_soft_batch.l1_fee_rate(),
-                    timestamp: signed_soft_batch.timestamp(),
-                };
+        self.mempool
+            .remove_transactions(self.db_provider.last_block_tx_hashes());
+        self.mempool.promote_overflow().await;
+        self.conditionals
+            .retain(|hash| self.mempool.get(hash).is_some());
+
+        if let Some(config) = encrypted_mempool_config {
+            self.encrypted_mempool.remove_included(
+                &encrypted.unwrap_or_default(),
+                &config,
+                block_number,
+            );
+        }
 
-                // TODO: this will only work for mock da
-                // when https://github.com/Sovereign-Labs/sovereign-sdk/issues/1218
-                // is merged, rpc will access up to date storage then we won't need to finalize rigth away.
-                // however we need much better DA + finalization logic here
-                self.storage_manager
-                    .save_change_set_l2(l2_height, slot_result.change_set)?;
+        // connect L1 and L2 height
+        self.ledger_db
+            .extend_l2_range_of_l1_slot(
+                SlotNumber(da_block.header().height()),
+                BatchNumber(l2_height),
+            )
+            .expect("Sequencer: Failed to set L1 L2 connection");
+
+        Ok(())
+    }
+
+    /// Pushes the soft batch just committed to the full nodes and relays, signing its height
+    /// and post state root, which the soft confirmation signature doesn't cover.
+    fn push_head_soft_batch(&self) -> anyhow::Result<()> {
+        let Some((height, soft_batch)) = self.ledger_db.get_head_soft_batch()? else {
+            return Ok(());
+        };
+        let soft_batch = GetSoftBatchResponse::from(SoftBatchResponse::try_from(soft_batch)?);
+        let push_signature = self
+            .sov_tx_signer_priv_key
+            .sign(&PushedSoftBatch::message(height.0, &soft_batch));
+
+        self.soft_batch_pusher.push(PushedSoftBatch {
+            height: height.0,
+            soft_batch,
+            push_signature: push_signature.try_to_vec()?,
+        });
+        Ok(())
+    }
 
-                tracing::debug!("Finalizing l2 height: {:?}", l2_height);
-                self.storage_manager.finalize_l2(l2_height)?;
+    /// Drops the transactions submitted with conditions that don't hold at the next block
+    /// from the mempool, along with the later transactions of their senders from the block.
+    fn drop_violated_conditionals(
+        &self,
+        txs: Vec<TransactionSignedEcRecovered>,
+    ) -> Vec<TransactionSignedEcRecovered> {
+        if self.conditionals.is_empty() {
+            return txs;
+        }
 
-                self.state_root = next_state_root;
+        let evm = Evm::<C>::default();
+        let mut working_set = WorkingSet::<C>::new(self.storage.clone());
+        let block_number = evm.last_sealed_header(&mut working_set).number + 1;
+        let timestamp = chrono::Local::now().timestamp() as u64;
 
-                self.ledger_db.commit_soft_batch(soft_batch_receipt, true)?;
+        let mut violated = vec![];
+        let mut blocked_senders = HashSet::new();
+        let txs = txs
+            .into_iter()
+            .filter(|tx| {
+                if blocked_senders.contains(&tx.signer()) {
+                    return false;
+                }
+                let Some(conditional) = self.conditionals.get(&tx.hash()) else {
+                    return true;
+                };
+                match conditional.check(&evm, &mut working_set, block_number, timestamp) {
+                    Ok(()) => true,
+                    Err(reason) => {
+                        debug!(
+                            "Sequencer: dropping conditional transaction {}: {}",
+                            tx.hash(),
+                            reason
+                        );
+                        violated.push(tx.hash());
+                        blocked_senders.insert(tx.signer());
+                        false
+                    }
+                }
+            })
+            .collect();
 
-                self.mempool
-                    .remove_transactions(self.db_provider.last_block_tx_hashes());
+        for hash in &violated {
+            self.conditionals.remove(hash);
+        }
+        self.mempool.remove_transactions(violated);
+        txs
+    }
 
-                // connect L1 and L2 height
-                self.ledger_db
-                    .extend_l2_range_of_l1_slot(
-                        SlotNumber(da_block.header().height()),
-                        BatchNumber(l2_height),
-                    )
-                    .expect("Sequencer: Failed to set L1 L2 connection");
-            }
-            (Err(err), batch_workspace) => {
-                warn!(
-                    "Failed to apply soft confirmation hook: {:?} \n reverting batch workspace",
-                    err
-                );
-                batch_workspace.revert();
-            }
+    /// Whether this sequencer may produce soft confirmations on the DA block at `da_height`.
+    /// Always true unless sequencers take turns, with the `multi-sequencer` feature.
+    /// The soft confirmations of the other sequencers are not synced back, so taking turns
+    /// is only meant for experiments on top of a shared state.
+    fn is_leader(&self, da_height: u64) -> bool {
+        if !cfg!(feature = "multi-sequencer") {
+            return true;
         }
-        Ok(())
+        let mut working_set = WorkingSet::<C>::new(self.storage.clone());
+        SoftConfirmationRuleEnforcer::<C, Da::Spec>::default()
+            .get_sequencer_leader(da_height, &mut working_set)
+            .expect("Sequencer leader query must succeed")
+            .map_or(true, |leader| leader == self.sequencer_pub_key)
     }
 
     pub async fn run(&mut self) -> Result<(), anyhow::Error> {
         // TODO: hotfix for mock da
         self.da_service.get_block_at(1).await.unwrap();
 
+        self.soft_batch_pusher = SoftBatchPusher::new(&self.config.push_soft_batches_to);
+
+        // Blocks are produced on schedule if an interval is set, and whenever forced through RPC
+        let mut scheduler = self.config.block_production_interval_ms.map(|interval_ms| {
+            BlockScheduler::new(Duration::from_millis(interval_ms), Instant::now())
+        });
+
         loop {
-            if (self.l2_force_block_rx.next().await).is_some() {
+            let produce_block = match scheduler.as_ref().map(BlockScheduler::next_deadline) {
+                Some(deadline) => tokio::select! {
+                    forced = self.l2_force_block_rx.next() => forced.is_some(),
+                    _ = tokio::time::sleep_until(deadline.into()) => true,
+                },
+                None => self.l2_force_block_rx.next().await.is_some(),
+            };
+
+            if produce_block {
+                let block_start = Instant::now();
+                if let Some(scheduler) = scheduler.as_mut() {
+                    scheduler.block_started(block_start);
+                }
+
                 // best txs with base fee
                 // get base fee from last blocks => header => next base fee() function
                 let cfg: citrea_evm::EvmChainConfig = self.db_provider.cfg();
+                let base_fee = self.db_provider.next_block_base_fee();
 
-                let latest_header = self
-                    .db_provider
-                    .latest_header()
-                    .expect("Failed to get latest header")
-                    .expect("Latest header must always exist")
-                    .unseal();
-
-                let base_fee = latest_header
-                    .next_block_base_fee(cfg.base_fee_params)
-                    .expect("Failed to get next block base fee");
+                let transaction_ttl_secs = self.config.mempool_conf.transaction_ttl_secs;
+                if transaction_ttl_secs > 0 {
+                    let evicted = self.mempool.evict_stale_transactions(
+                        Duration::from_secs(transaction_ttl_secs),
+                        base_fee,
+                    );
+                    if !evicted.is_empty() {
+                        debug!(
+                            "Sequencer: evicted {} stale transactions: {:?}",
+                            evicted.len(),
+                            evicted
+                        );
+                    }
+                }
 
-                let best_txs_with_base_fee = self.mempool.best_transactions_with_attributes(
-                    BestTransactionsAttributes::base_fee(base_fee),
+                let next_block_txs = self.mempool.next_block_transactions(
+                    base_fee,
+                    cfg.block_gas_limit,
+                    &self.config.validation_gas_policy,
+                    &self.conditionals,
                 );
+                let next_block_txs = self.drop_violated_conditionals(next_block_txs);
 
                 let mut prev_l1_height = self
                     .ledger_db
@@ -439,18 +612,11 @@ where
                     if commitment_info.is_some() {
                         debug!("Sequencer: enough soft confirmations to submit commitment");
                         let commitment_info = commitment_info.unwrap();
-                        let l2_range_to_submit = commitment_info.l2_height_range.clone();
-
-                        // calculate exclusive range end
-                        let range_end = BatchNumber(l2_range_to_submit.end().0 + 1); // cannnot add u64 to BatchNumber directly
-
-                        let soft_confirmation_hashes = self
-                            .ledger_db
-                            .get_soft_batch_range(&(*l2_range_to_submit.start()..range_end))
-                            .expect("Sequencer: Failed to get soft batch range")
-                            .iter()
-                            .map(|sb| sb.hash)
-                            .collect::<Vec<[u8; 32]>>();
+                        let soft_confirmation_hashes =
+                            commitment_controller::get_soft_confirmation_hashes(
+                                &self.ledger_db,
+                                &commitment_info.l2_height_range,
+                            );
 
                         let commitment = commitment_controller::get_commitment(
                             commitment_info.clone(),
@@ -479,25 +645,9 @@ where
                 }
 
                 // TODO: implement block builder instead of just including every transaction in order
-                let mut cumulative_gas_used = 0;
-                let rlp_txs: Vec<RlpEvmTransaction> = best_txs_with_base_fee
+                let rlp_txs: Vec<RlpEvmTransaction> = next_block_txs
                     .into_iter()
-                    .filter(|tx| {
-                        // Don't include transactions that exceed the block gas limit
-                        let tx_gas_limit = tx.transaction.gas_limit();
-                        let fits_into_block =
-                            cumulative_gas_used + tx_gas_limit <= cfg.block_gas_limit;
-                        if fits_into_block {
-                            cumulative_gas_used += tx_gas_limit
-                        }
-                        fits_into_block
-                    })
-                    .map(|tx| {
-                        tx.to_recovered_transaction()
-                            .into_signed()
-                            .envelope_encoded()
-                            .to_vec()
-                    })
+                    .map(|tx| tx.into_signed().envelope_encoded().to_vec())
                     .map(|rlp| RlpEvmTransaction { rlp })
                     .collect();
 
@@ -507,73 +657,43 @@ where
                     .await
                     .unwrap();
 
-                self.produce_l2_block(last_finalized_block, l1_fee_rate, rlp_txs)
-                    .await?;
+                self.memory_budget.set_usage(
+                    MemoryComponent::PendingBlock,
+                    rlp_txs.iter().map(|tx| tx.rlp.len() as u64).sum(),
+                );
+                let produced = self
+                    .produce_l2_block(last_finalized_block, l1_fee_rate, rlp_txs)
+                    .await;
+                self.memory_budget
+                    .set_usage(MemoryComponent::PendingBlock, 0);
+                self.memory_budget
+                    .update_mempool_usage(&self.mempool, &self.encrypted_mempool);
+                SEQUENCER_BLOCK_PRODUCTION_SECONDS.observe(block_start.elapsed().as_secs_f64());
+                produced?;
             }
         }
     }
 
-    /// Signs batch of messages with sovereign priv key turns them into a sov blob
-    /// Returns a single sovereign transaction made up of multiple ethereum transactions
-    fn make_blob(&mut self, raw_message: Vec<u8>) -> Vec<u8> {
-        // if a batch failed need to refetch nonce
-        // so sticking to fetching from state makes sense
-        let nonce = self.get_nonce();
-
-        // TODO: figure out what to do with sov-tx fields
-        // chain id gas tip and gas limit
-
-        Transaction::<C>::new_signed_tx(&self.sov_tx_signer_priv_key, raw_message, 0, nonce)
-            .try_to_vec()
-            .unwrap()
-    }
-
-    /// Signs necessary info and returns a BlockTemplate
-    fn sign_soft_confirmation_batch(
-        &mut self,
-        soft_confirmation: UnsignedSoftConfirmationBatch,
-    ) -> SignedSoftConfirmationBatch {
-        let raw = soft_confirmation.try_to_vec().unwrap();
-
-        let hash = <C as sov_modules_api::Spec>::Hasher::digest(raw.as_slice()).into();
-
-        let signature = self.sov_tx_signer_priv_key.sign(&raw);
-
-        SignedSoftConfirmationBatch::new(
-            hash,
-            soft_confirmation.da_slot_height(),
-            soft_confirmation.da_slot_hash(),
-            soft_confirmation.da_slot_txs_commitment(),
-            soft_confirmation.pre_state_root(),
-            soft_confirmation.l1_fee_rate(),
-            soft_confirmation.txs(),
-            signature.try_to_vec().unwrap(),
-            self.sov_tx_signer_priv_key.pub_key().try_to_vec().unwrap(),
-            soft_confirmation.timestamp(),
-        )
-    }
-
-    /// Fetches nonce from state
-    fn get_nonce(&self) -> u64 {
-        let accounts = Accounts::<C>::default();
-        let mut working_set = WorkingSet::<C>::new(self.storage.clone());
-
-        match accounts
-            .get_account(self.sov_tx_signer_priv_key.pub_key(), &mut working_set)
-            .expect("Sequencer: Failed to get sov-account")
-        {
-            AccountExists { addr: _, nonce } => nonce,
-            AccountEmpty => 0,
-        }
-    }
-
     /// Creates a shared RpcContext with all required data.
     fn create_rpc_context(&self) -> RpcContext<C> {
         let l2_force_block_tx = self.l2_force_block_tx.clone();
         RpcContext {
             mempool: self.mempool.clone(),
+            encrypted_mempool: self.encrypted_mempool.clone(),
+            conditionals: self.conditionals.clone(),
+            full_nodes: self.full_nodes.clone(),
+            memory_budget: self.memory_budget.clone(),
             l2_force_block_tx,
             storage: self.storage.clone(),
+            query_context: self.query_context.clone(),
+            validation_gas_policy: self.config.validation_gas_policy.clone(),
+            disk_watchdog: self.disk_watchdog.clone(),
+            ip_rate_limiter: self.ip_rate_limiter.clone(),
+            sender_rate_limiter: self.sender_rate_limiter.clone(),
+            ledger_db: self.ledger_db.clone(),
+            min_soft_confirmations_per_commitment: self
+                .config
+                .min_soft_confirmations_per_commitment,
         }
     }
 
@@ -584,6 +704,9 @@ where
     ) -> Result<jsonrpsee::RpcModule<()>, jsonrpsee::core::Error> {
         let rpc_context = self.create_rpc_context();
         let rpc = create_rpc_module(rpc_context)?;
+        for method in PENDING_STATE_METHODS {
+            rpc_methods.remove_method(method);
+        }
         rpc_methods.merge(rpc).unwrap();
         Ok(rpc_methods)
     }
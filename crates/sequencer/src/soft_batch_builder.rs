@@ -0,0 +1,252 @@
+//! Assembly of soft confirmations, shared by every sequencer implementation.
+//!
+//! A soft confirmation is built in four steps:
+//! 1. the sequencer selects and orders the EVM transactions of the block, e.g. from its mempool,
+//!    along with the encrypted transactions to order and the epoch key shares to publish if the
+//!    encrypted mempool is enabled;
+//! 2. [`SoftBatchBuilder::encode_transactions`] wraps them in a single sovereign transaction
+//!    signed with the sequencer key and the next account nonce;
+//! 3. [`SoftBatchBuilder::build`] executes the transaction through the STF hooks
+//!    on top of the given pre-state, on the given DA block, and signs the resulting header;
+//! 4. the sequencer persists the [`BuiltSoftBatch`]: the change set with its storage manager and
+//!    the receipt with its ledger, then publishes it.
+//!
+//! Steps 2 and 3 are consensus critical: full nodes and provers reject soft confirmations
+//! that are not encoded, executed and signed exactly this way. Sequencers with different
+//! transaction selection, e.g. auction based ordering, only replace step 1.
+
+use std::marker::PhantomData;
+
+use borsh::ser::BorshSerialize;
+use citrea_evm::CallMessage;
+use citrea_stf::runtime::Runtime;
+use digest::Digest;
+use sov_accounts::Accounts;
+use sov_accounts::Response::{AccountEmpty, AccountExists};
+use sov_modules_api::hooks::{ApplySoftConfirmationError, HookSoftConfirmationInfo};
+use sov_modules_api::transaction::Transaction;
+use sov_modules_api::{
+    Context, EncodeCall, PrivateKey, SignedSoftConfirmationBatch, UnsignedSoftConfirmationBatch,
+    WorkingSet,
+};
+use sov_modules_stf_blueprint::StfBlueprintTrait;
+use sov_rollup_interface::da::{BlockHeaderTrait, DaSpec};
+use sov_rollup_interface::stf::{SoftBatchReceipt, StateTransitionFunction};
+use sov_rollup_interface::zk::Zkvm;
+
+/// A soft confirmation executed and signed by [`SoftBatchBuilder::build`], not persisted yet.
+pub struct BuiltSoftBatch<Stf, Vm, Da>
+where
+    Vm: Zkvm,
+    Da: DaSpec,
+    Stf: StateTransitionFunction<Vm, Da>,
+{
+    /// The state root after the soft confirmation.
+    pub state_root: Stf::StateRoot,
+    /// The state changes of the soft confirmation, to be saved at its L2 height.
+    pub change_set: Stf::ChangeSet,
+    /// The signed soft confirmation and the receipts of its transactions,
+    /// to be committed to the ledger and served to full nodes.
+    pub receipt: SoftBatchReceipt<Stf::BatchReceiptContents, Stf::TxReceiptContents, Da>,
+}
+
+/// Builds soft confirmations on top of a state, see the [module documentation](self).
+pub struct SoftBatchBuilder<'a, C, Da, Vm, Stf>
+where
+    C: Context,
+    Da: DaSpec,
+    Vm: Zkvm,
+    Stf: StfBlueprintTrait<C, Da, Vm>,
+{
+    stf: &'a Stf,
+    signer: &'a C::PrivateKey,
+    sequencer_pub_key: &'a [u8],
+    storage: &'a C::Storage,
+    phantom: PhantomData<(Da, Vm)>,
+}
+
+impl<'a, C, Da, Vm, Stf> SoftBatchBuilder<'a, C, Da, Vm, Stf>
+where
+    C: Context,
+    Da: DaSpec,
+    Vm: Zkvm,
+    Stf: StfBlueprintTrait<C, Da, Vm>,
+    Stf::PreState: Clone,
+{
+    /// Creates a builder signing with `signer`, whose public key the rollup knows as
+    /// `sequencer_pub_key`. The nonce of the signer is read from `storage`.
+    pub fn new(
+        stf: &'a Stf,
+        signer: &'a C::PrivateKey,
+        sequencer_pub_key: &'a [u8],
+        storage: &'a C::Storage,
+    ) -> Self {
+        Self {
+            stf,
+            signer,
+            sequencer_pub_key,
+            storage,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Wraps the ordered EVM transactions of a block into a single sovereign transaction
+    /// signed by the sequencer.
+    pub fn encode_transactions(&self, call_message: CallMessage) -> Vec<u8> {
+        let raw_message =
+            <Runtime<C, Da> as EncodeCall<citrea_evm::Evm<C>>>::encode_call(call_message);
+
+        // if a batch failed need to refetch nonce
+        // so sticking to fetching from state makes sense
+        let nonce = self.nonce();
+
+        // TODO: figure out what to do with sov-tx fields
+        // chain id gas tip and gas limit
+
+        Transaction::<C>::new_signed_tx(self.signer, raw_message, 0, nonce)
+            .try_to_vec()
+            .unwrap()
+    }
+
+    /// Executes the EVM call message in a soft confirmation given for `da_block_header`,
+    /// whose relevant blobs are `da_blobs`, on top of `pre_state`, and signs it.
+    ///
+    /// Fails if the soft confirmation hooks reject the soft confirmation, for example
+    /// when the sequencer exceeded the number of soft confirmations allowed on the DA block.
+    #[allow(clippy::too_many_arguments)]
+    pub fn build(
+        &self,
+        pre_state_root: &Stf::StateRoot,
+        pre_state: Stf::PreState,
+        da_block_header: &Da::BlockHeader,
+        da_blobs: &mut [Da::BlobTransaction],
+        l1_fee_rate: u64,
+        timestamp: u64,
+        call_message: CallMessage,
+    ) -> Result<BuiltSoftBatch<Stf, Vm, Da>, ApplySoftConfirmationError> {
+        let batch_info = HookSoftConfirmationInfo {
+            da_slot_height: da_block_header.height(),
+            da_slot_hash: da_block_header.hash().into(),
+            da_slot_txs_commitment: da_block_header.txs_commitment().into(),
+            da_slot_prev_hash: da_block_header.prev_hash().into(),
+            pre_state_root: pre_state_root.as_ref().to_vec(),
+            pub_key: self.signer.pub_key().try_to_vec().unwrap(),
+            l1_fee_rate,
+            timestamp,
+            da_slot_governance: vec![],
+        };
+        // initially create sc info and call begin soft confirmation hook with it
+        let mut signed_batch: SignedSoftConfirmationBatch = batch_info.into();
+        let txs = vec![self.encode_transactions(call_message)];
+
+        let pub_key = signed_batch.pub_key().clone();
+
+        let (result, batch_workspace) = self.stf.begin_soft_batch(
+            &pub_key,
+            pre_state_root,
+            pre_state.clone(),
+            Default::default(),
+            da_block_header,
+            da_blobs,
+            &mut signed_batch,
+        );
+        if let Err(err) = result {
+            batch_workspace.revert();
+            return Err(err);
+        }
+
+        let (batch_workspace, tx_receipts) =
+            self.stf.apply_soft_batch_txs(txs.clone(), batch_workspace);
+
+        // create the unsigned batch with the txs then sign th sc
+        let unsigned_batch = UnsignedSoftConfirmationBatch::new(
+            da_block_header.height(),
+            da_block_header.hash().into(),
+            da_block_header.txs_commitment().into(),
+            pre_state_root.as_ref().to_vec(),
+            txs,
+            l1_fee_rate,
+            timestamp,
+        );
+
+        let mut signed_soft_batch = self.sign(unsigned_batch);
+
+        let (batch_receipt, checkpoint) = self.stf.end_soft_batch(
+            self.sequencer_pub_key,
+            &mut signed_soft_batch,
+            tx_receipts,
+            batch_workspace,
+        );
+
+        // Finalize soft confirmation
+        let mut slot_result = self.stf.finalize_soft_batch(
+            batch_receipt,
+            checkpoint,
+            pre_state,
+            &mut signed_soft_batch,
+        );
+
+        // TODO: This will be a single receipt once we have apply_soft_batch.
+        let batch_receipt = slot_result.batch_receipts.swap_remove(0);
+
+        let receipt = SoftBatchReceipt {
+            pre_state_root: pre_state_root.as_ref().to_vec(),
+            post_state_root: slot_result.state_root.as_ref().to_vec(),
+            phantom_data: PhantomData,
+            batch_hash: batch_receipt.batch_hash,
+            da_slot_hash: da_block_header.hash(),
+            da_slot_height: da_block_header.height(),
+            da_slot_txs_commitment: da_block_header.txs_commitment(),
+            tx_receipts: batch_receipt.tx_receipts,
+            soft_confirmation_signature: signed_soft_batch.signature().to_vec(),
+            pub_key: signed_soft_batch.pub_key().to_vec(),
+            l1_fee_rate: signed_soft_batch.l1_fee_rate(),
+            timestamp: signed_soft_batch.timestamp(),
+        };
+
+        Ok(BuiltSoftBatch {
+            state_root: slot_result.state_root,
+            change_set: slot_result.change_set,
+            receipt,
+        })
+    }
+
+    /// Signs the soft confirmation header and its transactions with the sequencer key.
+    pub fn sign(
+        &self,
+        soft_confirmation: UnsignedSoftConfirmationBatch,
+    ) -> SignedSoftConfirmationBatch {
+        let raw = soft_confirmation.try_to_vec().unwrap();
+
+        let hash = <C as sov_modules_api::Spec>::Hasher::digest(raw.as_slice()).into();
+
+        let signature = self.signer.sign(&raw);
+
+        SignedSoftConfirmationBatch::new(
+            hash,
+            soft_confirmation.da_slot_height(),
+            soft_confirmation.da_slot_hash(),
+            soft_confirmation.da_slot_txs_commitment(),
+            soft_confirmation.pre_state_root(),
+            soft_confirmation.l1_fee_rate(),
+            soft_confirmation.txs(),
+            signature.try_to_vec().unwrap(),
+            self.signer.pub_key().try_to_vec().unwrap(),
+            soft_confirmation.timestamp(),
+        )
+    }
+
+    /// Fetches the nonce of the sequencer account from state
+    fn nonce(&self) -> u64 {
+        let accounts = Accounts::<C>::default();
+        let mut working_set = WorkingSet::<C>::new(self.storage.clone());
+
+        match accounts
+            .get_account(self.signer.pub_key(), &mut working_set)
+            .expect("Sequencer: Failed to get sov-account")
+        {
+            AccountExists { addr: _, nonce } => nonce,
+            AccountEmpty => 0,
+        }
+    }
+}
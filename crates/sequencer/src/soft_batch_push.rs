@@ -0,0 +1,59 @@
+use sequencer_client::{PushedSoftBatch, SequencerClient};
+use tokio::sync::mpsc;
+use tracing::debug;
+
+/// Soft batches waiting to be pushed to a full node or relay. Pushes to a node that is slower
+/// than the sequencer are dropped, the node polls the sequencer for them.
+const PUSH_QUEUE_SIZE: usize = 16;
+
+/// Pushes the soft batches produced by the sequencer to the full nodes and relays listed in
+/// `push_soft_batches_to`, through `citrea_pushSoftBatch`.
+///
+/// Each node has its own queue and task, so that soft batches reach every node in order and
+/// a node that is down doesn't delay the others.
+#[derive(Debug, Default)]
+pub(crate) struct SoftBatchPusher {
+    queues: Vec<mpsc::Sender<PushedSoftBatch>>,
+}
+
+impl SoftBatchPusher {
+    /// Spawns a push task for each of the URLs.
+    pub(crate) fn new(urls: &[String]) -> Self {
+        let queues = urls
+            .iter()
+            .map(|url| {
+                let (tx, mut rx) = mpsc::channel::<PushedSoftBatch>(PUSH_QUEUE_SIZE);
+                let client = SequencerClient::new(url.clone());
+                tokio::spawn(async move {
+                    while let Some(pushed) = rx.recv().await {
+                        if let Err(e) = client.push_soft_batch(&pushed).await {
+                            debug!(
+                                "Failed to push soft batch {} to {}: {:?}",
+                                pushed.height, client.rpc_url, e
+                            );
+                        }
+                    }
+                });
+                tx
+            })
+            .collect();
+        Self { queues }
+    }
+
+    /// Whether soft batches are pushed to any node.
+    pub(crate) fn is_enabled(&self) -> bool {
+        !self.queues.is_empty()
+    }
+
+    /// Queues the soft batch to be pushed to every node.
+    pub(crate) fn push(&self, pushed: PushedSoftBatch) {
+        for queue in &self.queues {
+            if queue.try_send(pushed.clone()).is_err() {
+                debug!(
+                    "Dropping push of soft batch {}, the push queue is full",
+                    pushed.height
+                );
+            }
+        }
+    }
+}
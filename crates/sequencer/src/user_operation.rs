@@ -0,0 +1,199 @@
+use ethers::abi::{self, ParamType, Token};
+use reth_primitives::Address;
+
+/// ERC-4337 v0.6 `UserOperation`
+fn user_operation_v06() -> ParamType {
+    ParamType::Tuple(vec![
+        // sender
+        ParamType::Address,
+        // nonce
+        ParamType::Uint(256),
+        // initCode
+        ParamType::Bytes,
+        // callData
+        ParamType::Bytes,
+        // callGasLimit
+        ParamType::Uint(256),
+        // verificationGasLimit
+        ParamType::Uint(256),
+        // preVerificationGas
+        ParamType::Uint(256),
+        // maxFeePerGas
+        ParamType::Uint(256),
+        // maxPriorityFeePerGas
+        ParamType::Uint(256),
+        // paymasterAndData
+        ParamType::Bytes,
+        // signature
+        ParamType::Bytes,
+    ])
+}
+
+/// ERC-4337 v0.7 `PackedUserOperation`
+fn packed_user_operation_v07() -> ParamType {
+    ParamType::Tuple(vec![
+        // sender
+        ParamType::Address,
+        // nonce
+        ParamType::Uint(256),
+        // initCode
+        ParamType::Bytes,
+        // callData
+        ParamType::Bytes,
+        // accountGasLimits
+        ParamType::FixedBytes(32),
+        // preVerificationGas
+        ParamType::Uint(256),
+        // gasFees
+        ParamType::FixedBytes(32),
+        // paymasterAndData
+        ParamType::Bytes,
+        // signature
+        ParamType::Bytes,
+    ])
+}
+
+/// Parameters of `handleOps(ops, beneficiary)`
+fn handle_ops_params(user_operation: ParamType) -> Vec<ParamType> {
+    vec![
+        ParamType::Array(Box::new(user_operation)),
+        ParamType::Address,
+    ]
+}
+
+/// Returns the sender and the validation gas limit of each user operation of a `handleOps`
+/// call to an ERC-4337 v0.6 or v0.7 entry point, or None if `input` is not such a call.
+pub(crate) fn user_operations_validation_gas(input: &[u8]) -> Option<Vec<(Address, u64)>> {
+    let versions: [(ParamType, fn(&[Token]) -> Option<u64>); 2] = [
+        (user_operation_v06(), validation_gas_v06),
+        (packed_user_operation_v07(), validation_gas_v07),
+    ];
+    let selector = input.get(..4)?;
+    for (user_operation, validation_gas) in versions {
+        let params = handle_ops_params(user_operation);
+        if selector != abi::short_signature("handleOps", &params) {
+            continue;
+        }
+        let tokens = abi::decode(&params, &input[4..]).ok()?;
+        let Some(Token::Array(ops)) = tokens.into_iter().next() else {
+            return None;
+        };
+        return ops
+            .iter()
+            .map(|op| {
+                let Token::Tuple(fields) = op else {
+                    return None;
+                };
+                let sender = fields.first()?.clone().into_address()?;
+                Some((Address::from(sender.0), validation_gas(fields)?))
+            })
+            .collect();
+    }
+    None
+}
+
+/// In v0.6 `verificationGasLimit` covers the validation of both the account and the paymaster.
+fn validation_gas_v06(fields: &[Token]) -> Option<u64> {
+    let verification_gas_limit = fields.get(5)?.clone().into_uint()?;
+    Some(u64::try_from(verification_gas_limit).unwrap_or(u64::MAX))
+}
+
+/// In v0.7 the verification gas limit of the account is the high half of `accountGasLimits`,
+/// and the one of the paymaster follows the paymaster address in `paymasterAndData`.
+fn validation_gas_v07(fields: &[Token]) -> Option<u64> {
+    let account_gas_limits = fields.get(4)?.clone().into_fixed_bytes()?;
+    let paymaster_and_data = fields.get(7)?.clone().into_bytes()?;
+
+    let verification_gas_limit =
+        u128::from_be_bytes(account_gas_limits.get(..16)?.try_into().ok()?);
+    let paymaster_verification_gas_limit = match paymaster_and_data.get(20..36) {
+        Some(limit) => u128::from_be_bytes(limit.try_into().ok()?),
+        None => 0,
+    };
+    Some(
+        u64::try_from(verification_gas_limit.saturating_add(paymaster_verification_gas_limit))
+            .unwrap_or(u64::MAX),
+    )
+}
+
+/// Encodes a v0.6 `handleOps` call bundling a user operation of each sender with the given
+/// verification gas limit.
+#[cfg(test)]
+pub(crate) fn encode_handle_ops_v06(ops: &[(Address, u64)]) -> Vec<u8> {
+    let params = handle_ops_params(user_operation_v06());
+    let ops = ops
+        .iter()
+        .map(|(sender, verification_gas_limit)| {
+            Token::Tuple(vec![
+                Token::Address(sender.0 .0.into()),
+                Token::Uint(0u64.into()),
+                Token::Bytes(vec![]),
+                Token::Bytes(vec![]),
+                Token::Uint(100_000u64.into()),
+                Token::Uint((*verification_gas_limit).into()),
+                Token::Uint(21_000u64.into()),
+                Token::Uint(1u64.into()),
+                Token::Uint(1u64.into()),
+                Token::Bytes(vec![]),
+                Token::Bytes(vec![]),
+            ])
+        })
+        .collect();
+    let beneficiary = Token::Address(Default::default());
+    [
+        abi::short_signature("handleOps", &params).to_vec(),
+        abi::encode(&[Token::Array(ops), beneficiary]),
+    ]
+    .concat()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_validation_gas_of_user_operations() {
+        let first = Address::from([1; 20]);
+        let second = Address::from([2; 20]);
+        let input = encode_handle_ops_v06(&[(first, 150_000), (second, 70_000)]);
+        assert_eq!(
+            user_operations_validation_gas(&input),
+            Some(vec![(first, 150_000), (second, 70_000)])
+        );
+
+        let params = handle_ops_params(packed_user_operation_v07());
+        let account_gas_limits = [200_000u128.to_be_bytes(), 100_000u128.to_be_bytes()].concat();
+        let paymaster_and_data = [
+            [3; 20].to_vec(),
+            50_000u128.to_be_bytes().to_vec(),
+            10_000u128.to_be_bytes().to_vec(),
+        ]
+        .concat();
+        let op = Token::Tuple(vec![
+            Token::Address(first.0 .0.into()),
+            Token::Uint(0u64.into()),
+            Token::Bytes(vec![]),
+            Token::Bytes(vec![]),
+            Token::FixedBytes(account_gas_limits),
+            Token::Uint(21_000u64.into()),
+            Token::FixedBytes(vec![0; 32]),
+            Token::Bytes(paymaster_and_data),
+            Token::Bytes(vec![]),
+        ]);
+        let input = [
+            abi::short_signature("handleOps", &params).to_vec(),
+            abi::encode(&[Token::Array(vec![op]), Token::Address(Default::default())]),
+        ]
+        .concat();
+        assert_eq!(
+            user_operations_validation_gas(&input),
+            Some(vec![(first, 250_000)])
+        );
+
+        assert_eq!(
+            user_operations_validation_gas(&[0xde, 0xad, 0xbe, 0xef]),
+            None
+        );
+        assert_eq!(user_operations_validation_gas(&input[..40]), None);
+    }
+}
@@ -15,6 +15,11 @@ pub struct SoftConfirmationRuleEnforcerConfig<C: Context> {
     /// L1 fee rate change percentage
     /// Out of 100.
     pub(crate) l1_fee_rate_change_percentage: u64,
+    /// Public keys of the sequencers taking turns, serialized as hex.
+    /// The leader of a DA slot is the key at the slot height modulo the number of keys.
+    /// Experimental, only allowed with the `multi-sequencer` feature. Empty if not set.
+    #[serde(default, with = "hex_keys")]
+    pub(crate) sequencer_rotation: Vec<Vec<u8>>,
 }
 
 impl<C: Context, Da: DaSpec> SoftConfirmationRuleEnforcer<C, Da> {
@@ -28,6 +33,35 @@ impl<C: Context, Da: DaSpec> SoftConfirmationRuleEnforcer<C, Da> {
             .set(&config.limiting_number, working_set);
         self.l1_fee_rate_change_percentage
             .set(&config.l1_fee_rate_change_percentage, working_set);
+        if !config.sequencer_rotation.is_empty() {
+            anyhow::ensure!(
+                cfg!(feature = "multi-sequencer"),
+                "Sequencer rotation requires the multi-sequencer feature"
+            );
+            self.sequencer_rotation
+                .set(&config.sequencer_rotation, working_set);
+        }
         Ok(())
     }
 }
+
+mod hex_keys {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(
+        keys: &[Vec<u8>],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(keys.iter().map(hex::encode))
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Vec<u8>>, D::Error> {
+        let keys: Vec<String> = Vec::deserialize(deserializer)?;
+        keys.iter()
+            .map(|key| hex::decode(key.trim_start_matches("0x")).map_err(D::Error::custom))
+            .collect()
+    }
+}
@@ -115,13 +115,85 @@ where
         Ok(())
     }
 
+    /// Checks the DA hash continuity rule.
+    /// A soft confirmation must either reference the same DA block as the previous one,
+    /// or the DA block right after it, whose parent is the previous soft confirmation's DA block.
+    /// The sequencer produces soft confirmations for every DA block, so there are no skips.
+    /// This ensures the sequencer cannot reference blocks of a DA fork without full nodes noticing.
+    fn apply_da_hash_continuity_rule(
+        &self,
+        soft_batch: &mut HookSoftConfirmationInfo,
+        working_set: &mut WorkingSet<C>,
+    ) -> Result<(), ApplySoftConfirmationError> {
+        let height = soft_batch.da_slot_height;
+        let hash = soft_batch.da_slot_hash();
+
+        // the first soft confirmation after genesis can reference any DA block
+        if let Some((last_height, last_hash)) = self.last_da_slot.get(working_set) {
+            let is_continuous = match height.checked_sub(last_height) {
+                Some(0) => hash == last_hash,
+                Some(1) => soft_batch.da_slot_prev_hash() == last_hash,
+                _ => false,
+            };
+
+            if !is_continuous {
+                return Err(ApplySoftConfirmationError::DaSlotHashNotContinuous {
+                    height,
+                    hash,
+                    last_height,
+                    last_hash,
+                });
+            }
+
+            // same DA block, nothing to update
+            if height == last_height {
+                return Ok(());
+            }
+        }
+
+        self.last_da_slot.set(&(height, hash), working_set);
+
+        Ok(())
+    }
+
+    /// Checks the leader rule.
+    /// If sequencers take turns, a soft confirmation must be signed by the leader of its DA slot,
+    /// the sequencer at the slot height modulo the number of sequencers in the rotation.
+    /// This ensures a sequencer cannot produce soft confirmations out of its turn.
+    fn apply_leader_rule(
+        &self,
+        soft_batch: &mut HookSoftConfirmationInfo,
+        working_set: &mut WorkingSet<C>,
+    ) -> Result<(), ApplySoftConfirmationError> {
+        let da_slot_height = soft_batch.da_slot_height;
+        let leader_pub_key = self
+            .get_sequencer_leader(da_slot_height, working_set)
+            .expect("Sequencer leader query must succeed");
+
+        match leader_pub_key {
+            Some(leader_pub_key) if leader_pub_key != soft_batch.sequencer_pub_key() => {
+                Err(ApplySoftConfirmationError::UnexpectedSequencer {
+                    da_slot_height,
+                    sequencer_pub_key: soft_batch.sequencer_pub_key().to_vec(),
+                    leader_pub_key,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
     /// Logic executed at the beginning of the soft confirmation.
-    /// Checks two rules: block count rule and fee rate rule.
+    /// Checks five rules: leader rule, DA hash continuity rule, block count rule, fee rate rule
+    /// and timestamp rule.
     pub fn begin_soft_confirmation_hook(
         &self,
         soft_batch: &mut HookSoftConfirmationInfo,
         working_set: &mut WorkingSet<C>,
     ) -> Result<(), ApplySoftConfirmationError> {
+        self.apply_leader_rule(soft_batch, working_set)?;
+
+        self.apply_da_hash_continuity_rule(soft_batch, working_set)?;
+
         self.apply_block_count_rule(soft_batch, working_set)?;
 
         self.apply_fee_rate_rule(soft_batch, working_set)?;
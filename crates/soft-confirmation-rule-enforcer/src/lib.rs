@@ -37,6 +37,14 @@ pub struct SoftConfirmationRuleEnforcer<C: Context, Da: DaSpec> {
     /// Sequencer's block timestamp
     #[state]
     pub(crate) last_timestamp: StateValue<u64, BcsCodec>,
+    /// Height and hash of the DA block of the last soft confirmation.
+    /// Not set before the first soft confirmation.
+    #[state]
+    pub(crate) last_da_slot: StateValue<(u64, [u8; 32]), BcsCodec>,
+    /// Public keys of the sequencers taking turns, by DA slot height.
+    /// Not set if a single sequencer produces every soft confirmation.
+    #[state]
+    pub(crate) sequencer_rotation: StateValue<Vec<Vec<u8>>, BcsCodec>,
     /// Phantom state using the da type.
     /// This is used to make sure that the state is generic over the DA type.
     #[allow(dead_code)]
@@ -52,4 +52,19 @@ impl<C: Context, Da: DaSpec> SoftConfirmationRuleEnforcer<C, Da> {
     pub fn get_last_timestamp(&self, working_set: &mut WorkingSet<C>) -> RpcResult<u64> {
         Ok(self.last_timestamp.get(working_set).unwrap_or(0))
     }
+
+    #[rpc_method(name = "getSequencerLeader")]
+    /// Get the public key of the sequencer allowed to produce soft confirmations on the DA slot
+    /// at the given height. None if a single sequencer produces every soft confirmation.
+    pub fn get_sequencer_leader(
+        &self,
+        da_slot_height: u64,
+        working_set: &mut WorkingSet<C>,
+    ) -> RpcResult<Option<Vec<u8>>> {
+        Ok(self
+            .sequencer_rotation
+            .get(working_set)
+            .filter(|rotation| !rotation.is_empty())
+            .map(|rotation| rotation[(da_slot_height % rotation.len() as u64) as usize].clone()))
+    }
 }
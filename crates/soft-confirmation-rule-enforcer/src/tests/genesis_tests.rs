@@ -19,6 +19,7 @@ lazy_static! {
             )
             .unwrap(),
             l1_fee_rate_change_percentage: 10,
+            sequencer_rotation: vec![],
         };
 }
 
@@ -54,6 +55,37 @@ fn genesis_data() {
     );
 }
 
+#[test]
+fn genesis_sequencer_rotation() {
+    let config: SoftConfirmationRuleEnforcerConfig<C> = serde_json::from_str(
+        r#"{
+            "limiting_number": 10,
+            "authority": "sov1l6n2cku82yfqld30lanm2nfw43n2auc8clw7r5u5m6s7p8jrm4zqrr8r94",
+            "l1_fee_rate_change_percentage": 10,
+            "sequencer_rotation": ["0101", "0x0202"]
+        }"#,
+    )
+    .unwrap();
+    assert_eq!(config.sequencer_rotation, vec![vec![1, 1], vec![2, 2]]);
+
+    let tmpdir = tempfile::tempdir().unwrap();
+    let mut working_set = WorkingSet::new(new_orphan_storage(tmpdir.path()).unwrap());
+    let soft_confirmation_rule_enforcer = SoftConfirmationRuleEnforcer::<C, MockDaSpec>::default();
+    let res = soft_confirmation_rule_enforcer.genesis(&config, &mut working_set);
+
+    if cfg!(feature = "multi-sequencer") {
+        res.unwrap();
+        assert_eq!(
+            soft_confirmation_rule_enforcer
+                .sequencer_rotation
+                .get(&mut working_set),
+            Some(config.sequencer_rotation)
+        );
+    } else {
+        assert!(res.is_err());
+    }
+}
+
 pub(crate) fn get_soft_confirmation_rule_enforcer<Da: DaSpec>(
     config: &SoftConfirmationRuleEnforcerConfig<C>,
 ) -> (
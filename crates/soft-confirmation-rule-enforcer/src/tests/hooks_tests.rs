@@ -3,6 +3,7 @@ use std::str::FromStr;
 use anyhow::anyhow;
 use sov_mock_da::MockDaSpec;
 use sov_modules_api::default_context::DefaultContext;
+use sov_modules_api::hooks::HookSoftConfirmationInfo;
 use sov_modules_api::utils::generate_address;
 use sov_modules_api::{Context, Module, Spec, StateValueAccessor};
 use sov_rollup_interface::soft_confirmation::SignedSoftConfirmationBatch;
@@ -50,7 +51,7 @@ fn begin_soft_confirmation_hook_checks_limiting_number() {
     for i in 0..11 {
         if soft_confirmation_rule_enforcer
             .begin_soft_confirmation_hook(
-                &mut signed_soft_confirmation_batch.clone().into(),
+                &mut HookSoftConfirmationInfo::new(&signed_soft_confirmation_batch, [0; 32]),
                 &mut working_set,
             )
             .is_err()
@@ -81,7 +82,7 @@ fn begin_soft_confirmation_hook_checks_l1_fee_rate() {
 
     // call first with 100 fee rate to set last_l1_fee_rate
     let res = soft_confirmation_rule_enforcer.begin_soft_confirmation_hook(
-        &mut signed_soft_confirmation_batch.clone().into(),
+        &mut HookSoftConfirmationInfo::new(&signed_soft_confirmation_batch, [0; 32]),
         &mut working_set,
     );
 
@@ -92,7 +93,7 @@ fn begin_soft_confirmation_hook_checks_l1_fee_rate() {
     signed_soft_confirmation_batch.set_l1_fee_rate(111);
 
     let res = soft_confirmation_rule_enforcer.begin_soft_confirmation_hook(
-        &mut signed_soft_confirmation_batch.clone().into(),
+        &mut HookSoftConfirmationInfo::new(&signed_soft_confirmation_batch, [0; 32]),
         &mut working_set,
     );
 
@@ -117,7 +118,7 @@ fn begin_soft_confirmation_hook_checks_l1_fee_rate() {
     signed_soft_confirmation_batch.set_l1_fee_rate(110);
 
     let res = soft_confirmation_rule_enforcer.begin_soft_confirmation_hook(
-        &mut signed_soft_confirmation_batch.clone().into(),
+        &mut HookSoftConfirmationInfo::new(&signed_soft_confirmation_batch, [0; 32]),
         &mut working_set,
     );
 
@@ -127,7 +128,7 @@ fn begin_soft_confirmation_hook_checks_l1_fee_rate() {
     signed_soft_confirmation_batch.set_l1_fee_rate(122);
 
     let res = soft_confirmation_rule_enforcer.begin_soft_confirmation_hook(
-        &mut signed_soft_confirmation_batch.clone().into(),
+        &mut HookSoftConfirmationInfo::new(&signed_soft_confirmation_batch, [0; 32]),
         &mut working_set,
     );
 
@@ -136,7 +137,7 @@ fn begin_soft_confirmation_hook_checks_l1_fee_rate() {
     signed_soft_confirmation_batch.set_l1_fee_rate(121);
 
     let res = soft_confirmation_rule_enforcer.begin_soft_confirmation_hook(
-        &mut signed_soft_confirmation_batch.clone().into(),
+        &mut HookSoftConfirmationInfo::new(&signed_soft_confirmation_batch, [0; 32]),
         &mut working_set,
     );
 
@@ -146,25 +147,26 @@ fn begin_soft_confirmation_hook_checks_l1_fee_rate() {
     signed_soft_confirmation_batch.set_l1_fee_rate(109);
 
     let res = soft_confirmation_rule_enforcer.begin_soft_confirmation_hook(
-        &mut signed_soft_confirmation_batch.clone().into(),
+        &mut HookSoftConfirmationInfo::new(&signed_soft_confirmation_batch, [0; 32]),
         &mut working_set,
     );
     assert!(res.is_ok());
     signed_soft_confirmation_batch.set_l1_fee_rate(100);
     let res = soft_confirmation_rule_enforcer.begin_soft_confirmation_hook(
-        &mut signed_soft_confirmation_batch.clone().into(),
+        &mut HookSoftConfirmationInfo::new(&signed_soft_confirmation_batch, [0; 32]),
         &mut working_set,
     );
     assert!(res.is_ok());
 
     // change da root hash so it doesnt fail
+    signed_soft_confirmation_batch.set_da_slot_height(1);
     signed_soft_confirmation_batch.set_da_slot_hash([1; 32]);
 
     // now 89 should'nt pass but 90 should
     signed_soft_confirmation_batch.set_l1_fee_rate(89);
 
     let res = soft_confirmation_rule_enforcer.begin_soft_confirmation_hook(
-        &mut signed_soft_confirmation_batch.clone().into(),
+        &mut HookSoftConfirmationInfo::new(&signed_soft_confirmation_batch, [0; 32]),
         &mut working_set,
     );
 
@@ -188,7 +190,7 @@ fn begin_soft_confirmation_hook_checks_l1_fee_rate() {
     signed_soft_confirmation_batch.set_l1_fee_rate(90);
 
     let res = soft_confirmation_rule_enforcer.begin_soft_confirmation_hook(
-        &mut signed_soft_confirmation_batch.clone().into(),
+        &mut HookSoftConfirmationInfo::new(&signed_soft_confirmation_batch, [0; 32]),
         &mut working_set,
     );
 
@@ -199,7 +201,7 @@ fn begin_soft_confirmation_hook_checks_l1_fee_rate() {
     signed_soft_confirmation_batch.set_l1_fee_rate(89);
 
     let res = soft_confirmation_rule_enforcer.begin_soft_confirmation_hook(
-        &mut signed_soft_confirmation_batch.clone().into(),
+        &mut HookSoftConfirmationInfo::new(&signed_soft_confirmation_batch, [0; 32]),
         &mut working_set,
     );
 
@@ -228,7 +230,7 @@ fn begin_soft_confirmation_hook_checks_timestamp() {
 
     // call first with `original_timestamp`
     let res = soft_confirmation_rule_enforcer.begin_soft_confirmation_hook(
-        &mut signed_soft_confirmation_batch.clone().into(),
+        &mut HookSoftConfirmationInfo::new(&signed_soft_confirmation_batch, [0; 32]),
         &mut working_set,
     );
 
@@ -250,7 +252,7 @@ fn begin_soft_confirmation_hook_checks_timestamp() {
     );
 
     let res = soft_confirmation_rule_enforcer.begin_soft_confirmation_hook(
-        &mut signed_soft_confirmation_batch.clone().into(),
+        &mut HookSoftConfirmationInfo::new(&signed_soft_confirmation_batch, [0; 32]),
         &mut working_set,
     );
 
@@ -287,9 +289,153 @@ fn begin_soft_confirmation_hook_checks_timestamp() {
     );
 
     let res = soft_confirmation_rule_enforcer.begin_soft_confirmation_hook(
-        &mut signed_soft_confirmation_batch.clone().into(),
+        &mut HookSoftConfirmationInfo::new(&signed_soft_confirmation_batch, [0; 32]),
         &mut working_set,
     );
 
     assert!(res.is_ok());
 }
+
+#[test]
+fn begin_soft_confirmation_hook_checks_da_hash_continuity() {
+    let (soft_confirmation_rule_enforcer, mut working_set) =
+        get_soft_confirmation_rule_enforcer::<MockDaSpec>(&TEST_CONFIG);
+
+    let mut signed_soft_confirmation_batch = SignedSoftConfirmationBatch::new(
+        [0; 32],
+        5,
+        [5; 32],
+        [0; 32],
+        vec![],
+        100,
+        vec![],
+        vec![],
+        vec![],
+        1,
+    );
+
+    // first soft confirmation can be given for any DA block
+    let res = soft_confirmation_rule_enforcer.begin_soft_confirmation_hook(
+        &mut HookSoftConfirmationInfo::new(&signed_soft_confirmation_batch, [4; 32]),
+        &mut working_set,
+    );
+    assert!(res.is_ok());
+
+    // same height but a different hash should fail
+    signed_soft_confirmation_batch.set_da_slot_hash([55; 32]);
+    let res = soft_confirmation_rule_enforcer.begin_soft_confirmation_hook(
+        &mut HookSoftConfirmationInfo::new(&signed_soft_confirmation_batch, [4; 32]),
+        &mut working_set,
+    );
+    assert_eq!(
+        format!(
+            "{}",
+            anyhow!(
+                "DA block {} with hash {:?} does not continue the DA chain of the previous soft confirmation's DA block {} with hash {:?}",
+                5,
+                [55u8; 32],
+                5,
+                [5u8; 32]
+            )
+        ),
+        format!("{}", res.unwrap_err())
+    );
+
+    // next height on a fork should fail
+    signed_soft_confirmation_batch.set_da_slot_height(6);
+    signed_soft_confirmation_batch.set_da_slot_hash([6; 32]);
+    let res = soft_confirmation_rule_enforcer.begin_soft_confirmation_hook(
+        &mut HookSoftConfirmationInfo::new(&signed_soft_confirmation_batch, [55; 32]),
+        &mut working_set,
+    );
+    assert!(res.is_err());
+
+    // skipping a DA block should fail
+    signed_soft_confirmation_batch.set_da_slot_height(7);
+    signed_soft_confirmation_batch.set_da_slot_hash([7; 32]);
+    let res = soft_confirmation_rule_enforcer.begin_soft_confirmation_hook(
+        &mut HookSoftConfirmationInfo::new(&signed_soft_confirmation_batch, [6; 32]),
+        &mut working_set,
+    );
+    assert!(res.is_err());
+
+    // next height building on the last DA block should pass
+    signed_soft_confirmation_batch.set_da_slot_height(6);
+    signed_soft_confirmation_batch.set_da_slot_hash([6; 32]);
+    let res = soft_confirmation_rule_enforcer.begin_soft_confirmation_hook(
+        &mut HookSoftConfirmationInfo::new(&signed_soft_confirmation_batch, [5; 32]),
+        &mut working_set,
+    );
+    assert!(res.is_ok());
+    assert_eq!(
+        soft_confirmation_rule_enforcer
+            .last_da_slot
+            .get(&mut working_set)
+            .unwrap(),
+        (6, [6; 32])
+    );
+}
+
+#[test]
+fn begin_soft_confirmation_hook_checks_leader() {
+    let (soft_confirmation_rule_enforcer, mut working_set) =
+        get_soft_confirmation_rule_enforcer::<MockDaSpec>(&TEST_CONFIG);
+
+    // a single sequencer has no turns
+    assert_eq!(
+        soft_confirmation_rule_enforcer
+            .get_sequencer_leader(5, &mut working_set)
+            .unwrap(),
+        None
+    );
+
+    soft_confirmation_rule_enforcer
+        .sequencer_rotation
+        .set(&vec![vec![1; 33], vec![2; 33]], &mut working_set);
+
+    let soft_confirmation_by = |pub_key: Vec<u8>, da_slot_height: u64| {
+        SignedSoftConfirmationBatch::new(
+            [0; 32],
+            da_slot_height,
+            [da_slot_height as u8; 32],
+            [0; 32],
+            vec![],
+            100,
+            vec![],
+            vec![],
+            pub_key,
+            da_slot_height,
+        )
+    };
+
+    // the second sequencer leads odd DA slots
+    let res = soft_confirmation_rule_enforcer.begin_soft_confirmation_hook(
+        &mut HookSoftConfirmationInfo::new(&soft_confirmation_by(vec![1; 33], 5), [4; 32]),
+        &mut working_set,
+    );
+    assert_eq!(
+        format!(
+            "{}",
+            anyhow!(
+                "Soft confirmation on DA block {} signed by sequencer {:?}, expected the leader {:?}",
+                5,
+                vec![1u8; 33],
+                vec![2u8; 33]
+            )
+        ),
+        format!("{}", res.unwrap_err())
+    );
+
+    let res = soft_confirmation_rule_enforcer.begin_soft_confirmation_hook(
+        &mut HookSoftConfirmationInfo::new(&soft_confirmation_by(vec![2; 33], 5), [4; 32]),
+        &mut working_set,
+    );
+    assert!(res.is_ok());
+
+    // the first sequencer takes over on the next DA slot
+    let res = soft_confirmation_rule_enforcer.begin_soft_confirmation_hook(
+        &mut HookSoftConfirmationInfo::new(&soft_confirmation_by(vec![1; 33], 6), [5; 32]),
+        &mut working_set,
+    );
+    assert!(res.is_ok());
+}
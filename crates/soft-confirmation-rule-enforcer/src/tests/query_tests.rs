@@ -1,4 +1,5 @@
 use sov_mock_da::MockDaSpec;
+use sov_modules_api::hooks::HookSoftConfirmationInfo;
 use sov_modules_api::StateMapAccessor;
 use sov_rollup_interface::soft_confirmation::SignedSoftConfirmationBatch;
 
@@ -25,7 +26,7 @@ fn block_count_per_da_hash_must_be_correct() {
     for _ in 0..3 {
         soft_confirmation_rule_enforcer
             .begin_soft_confirmation_hook(
-                &mut signed_soft_confirmation_batch.clone().into(),
+                &mut HookSoftConfirmationInfo::new(&signed_soft_confirmation_batch, [0; 32]),
                 &mut working_set,
             )
             .unwrap();
@@ -39,12 +40,13 @@ fn block_count_per_da_hash_must_be_correct() {
         3
     );
 
+    signed_soft_confirmation_batch.set_da_slot_height(1);
     signed_soft_confirmation_batch.set_da_slot_hash([1; 32]);
 
     // call with a different da hash
     soft_confirmation_rule_enforcer
         .begin_soft_confirmation_hook(
-            &mut signed_soft_confirmation_batch.clone().into(),
+            &mut HookSoftConfirmationInfo::new(&signed_soft_confirmation_batch, [0; 32]),
             &mut working_set,
         )
         .unwrap();
@@ -85,7 +87,7 @@ fn get_max_l1_fee_rate_change_percentage_must_be_correct() {
 
     soft_confirmation_rule_enforcer
         .begin_soft_confirmation_hook(
-            &mut signed_soft_confirmation_batch.clone().into(),
+            &mut HookSoftConfirmationInfo::new(&signed_soft_confirmation_batch, [0; 32]),
             &mut working_set,
         )
         .unwrap();
@@ -125,7 +127,7 @@ fn get_last_l1_fee_rate_must_be_correct() {
     );
     soft_confirmation_rule_enforcer
         .begin_soft_confirmation_hook(
-            &mut signed_soft_confirmation_batch.clone().into(),
+            &mut HookSoftConfirmationInfo::new(&signed_soft_confirmation_batch, [0; 32]),
             &mut working_set,
         )
         .unwrap();
@@ -166,7 +168,7 @@ fn get_last_timestamp_must_be_correct() {
     );
     soft_confirmation_rule_enforcer
         .begin_soft_confirmation_hook(
-            &mut signed_soft_confirmation_batch.clone().into(),
+            &mut HookSoftConfirmationInfo::new(&signed_soft_confirmation_batch, [0; 32]),
             &mut working_set,
         )
         .unwrap();
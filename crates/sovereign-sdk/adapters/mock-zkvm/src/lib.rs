@@ -173,6 +173,10 @@ impl<ValidityCond: ValidityCondition> sov_rollup_interface::zk::ZkvmHost
         Ok(sov_rollup_interface::zk::Proof::PublicInput(data))
     }
 
+    fn serialized_hints(&self) -> Option<Vec<u8>> {
+        Some(self.committed_data.iter().flatten().copied().collect())
+    }
+
     fn extract_output<
         Da: sov_rollup_interface::da::DaSpec,
         Root: Serialize + serde::de::DeserializeOwned,
@@ -0,0 +1,304 @@
+//! Checkpointed proving, so that proving a large guest execution survives the memory limits
+//! of the host.
+//!
+//! The guest is executed once and its segments are written to a checkpoint directory. The
+//! segments are then proven one by one, each segment receipt being written next to them.
+//! Before each segment, a [`MemoryWatchdog`] checks the memory usage of the host. When it
+//! nears the limit, the remaining segments are proven by a fresh process running the
+//! continuation binary, which picks the checkpoint up where it was left and exits with
+//! [`INTERRUPTED_EXIT_CODE`] when it nears the limit in turn. Since a proving job always
+//! writes to the same directory, a job interrupted by a crash resumes from its checkpoint too.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::Context;
+use risc0_zkvm::sha::{Digestible, Impl, Sha256};
+use risc0_zkvm::{
+    get_prover_server, CompositeReceipt, ExecutorEnvBuilder, ExecutorImpl, InnerReceipt, Journal,
+    ProverOpts, Receipt, Segment, SegmentReceipt, SegmentRef, VerifierContext,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::host::GuestLogs;
+
+/// Exit code of the continuation binary when it stopped proving because the host
+/// neared its memory limit. The remaining segments are proven by another process.
+pub const INTERRUPTED_EXIT_CODE: i32 = 75;
+
+const SESSION_FILE: &str = "session.bincode";
+
+/// Checkpointed proving configuration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CheckpointConfig {
+    /// Directory the checkpoints of the proving jobs are written to.
+    pub dir: PathBuf,
+    /// Percentage of the host memory above which proving continues in a fresh process.
+    pub max_memory_usage_percent: u8,
+    /// Binary proving the remaining segments of a checkpoint, called with the checkpoint
+    /// directory and `--max-memory-usage-percent`.
+    pub continuation_bin: PathBuf,
+}
+
+/// Watches the memory usage of the host, as the highest of the system and cgroup usages.
+#[derive(Debug, Clone, Copy)]
+pub struct MemoryWatchdog {
+    max_memory_usage_percent: u8,
+}
+
+impl MemoryWatchdog {
+    /// Creates a watchdog firing above `max_memory_usage_percent` of the host memory.
+    pub fn new(max_memory_usage_percent: u8) -> Self {
+        Self {
+            max_memory_usage_percent,
+        }
+    }
+
+    /// Returns true if the memory usage of the host is above the limit.
+    /// The usage is unknown outside of Linux, where the watchdog never fires.
+    pub fn near_limit(&self) -> bool {
+        memory_usage_percent().is_some_and(|usage| usage >= self.max_memory_usage_percent)
+    }
+}
+
+fn memory_usage_percent() -> Option<u8> {
+    let system = fs::read_to_string("/proc/meminfo")
+        .ok()
+        .and_then(|meminfo| meminfo_usage_percent(&meminfo));
+    let cgroup = match (
+        fs::read_to_string("/sys/fs/cgroup/memory.current"),
+        fs::read_to_string("/sys/fs/cgroup/memory.max"),
+    ) {
+        (Ok(current), Ok(max)) => cgroup_usage_percent(&current, &max),
+        _ => None,
+    };
+    system.max(cgroup)
+}
+
+/// Usage of the system memory, from the content of `/proc/meminfo`.
+fn meminfo_usage_percent(meminfo: &str) -> Option<u8> {
+    let field = |name: &str| {
+        meminfo
+            .lines()
+            .find_map(|line| line.strip_prefix(name))
+            .and_then(|value| {
+                value
+                    .trim()
+                    .trim_end_matches("kB")
+                    .trim()
+                    .parse::<u64>()
+                    .ok()
+            })
+    };
+    let total = field("MemTotal:")?;
+    let available = field("MemAvailable:")?;
+    usage_percent(total.saturating_sub(available), total)
+}
+
+/// Usage of the memory of the cgroup, from `memory.current` and `memory.max`.
+/// `memory.max` is `max` when the cgroup has no limit.
+fn cgroup_usage_percent(current: &str, max: &str) -> Option<u8> {
+    let current = current.trim().parse::<u64>().ok()?;
+    let max = max.trim().parse::<u64>().ok()?;
+    usage_percent(current, max)
+}
+
+fn usage_percent(used: u64, total: u64) -> Option<u8> {
+    if total == 0 {
+        return None;
+    }
+    Some((used.saturating_mul(100) / total).min(100) as u8)
+}
+
+/// Outcome of the execution of the guest, kept in the checkpoint.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CheckpointedSession {
+    /// Journal committed by the guest.
+    pub journal: Journal,
+    /// Number of segments of the execution.
+    pub segments: usize,
+    /// Total cycle count of the execution, if known.
+    pub cycle_count: Option<u64>,
+    /// What the guest wrote to its stdout and stderr.
+    pub logs: String,
+}
+
+/// A segment written to the checkpoint directory, read back when it is proven.
+struct CheckpointSegmentRef {
+    path: PathBuf,
+}
+
+impl SegmentRef for CheckpointSegmentRef {
+    fn resolve(&self) -> anyhow::Result<Segment> {
+        Ok(bincode::deserialize(&fs::read(&self.path)?)?)
+    }
+}
+
+fn segment_path(dir: &Path, index: usize) -> PathBuf {
+    dir.join(format!("segment-{}.bincode", index))
+}
+
+fn receipt_path(dir: &Path, index: usize) -> PathBuf {
+    dir.join(format!("receipt-{}.bincode", index))
+}
+
+/// Returns the checkpoint directory of a proving job, named after its input.
+pub fn job_dir(root: &Path, input: &[u32]) -> PathBuf {
+    root.join(Impl::hash_words(input).to_string())
+}
+
+/// Returns the session of a checkpoint, if the guest was executed already.
+pub fn read_session(dir: &Path) -> anyhow::Result<Option<CheckpointedSession>> {
+    match fs::read(dir.join(SESSION_FILE)) {
+        Ok(data) => Ok(Some(bincode::deserialize(&data)?)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Executes the guest, writing its segments to the checkpoint directory.
+pub fn execute(
+    dir: &Path,
+    mut env: ExecutorEnvBuilder<'_>,
+    logs: &GuestLogs,
+    elf: &[u8],
+) -> anyhow::Result<CheckpointedSession> {
+    fs::create_dir_all(dir)?;
+    let mut executor = ExecutorImpl::from_elf(env.build()?, elf)?;
+    let mut index = 0;
+    let session = executor.run_with_callback(|segment| {
+        let path = segment_path(dir, index);
+        fs::write(&path, bincode::serialize(&segment)?)?;
+        index += 1;
+        Ok(Box::new(CheckpointSegmentRef { path }))
+    })?;
+
+    let checkpointed = CheckpointedSession {
+        journal: session.journal.clone(),
+        segments: session.segments.len(),
+        cycle_count: session.get_cycles().ok(),
+        logs: logs.take(),
+    };
+    // The session is written last, a checkpoint without it is executed again
+    fs::write(dir.join(SESSION_FILE), bincode::serialize(&checkpointed)?)?;
+    Ok(checkpointed)
+}
+
+/// Proves the segments of a checkpoint that are not proven yet.
+///
+/// Returns false if proving stopped because the host neared its memory limit. With
+/// `make_progress`, the first segment is proven regardless of the memory usage, so that
+/// a fresh process always moves the checkpoint forward.
+pub fn prove_segments(
+    dir: &Path,
+    watchdog: &MemoryWatchdog,
+    make_progress: bool,
+) -> anyhow::Result<bool> {
+    let session = read_session(dir)?
+        .with_context(|| format!("No executed session in checkpoint {}", dir.display()))?;
+    let prover = get_prover_server(&ProverOpts::default())?;
+    let ctx = VerifierContext::default();
+
+    let mut proven = 0;
+    for index in 0..session.segments {
+        let receipt_path = receipt_path(dir, index);
+        if receipt_path.exists() {
+            continue;
+        }
+        if watchdog.near_limit() && !(make_progress && proven == 0) {
+            return Ok(false);
+        }
+
+        let segment: Segment = bincode::deserialize(&fs::read(segment_path(dir, index))?)?;
+        let receipt = prover.prove_segment(&ctx, &segment)?;
+        // Written aside first so that an interrupted write is never taken for a receipt
+        let partial_path = receipt_path.with_extension("partial");
+        fs::write(&partial_path, bincode::serialize(&receipt)?)?;
+        fs::rename(&partial_path, &receipt_path)?;
+        proven += 1;
+    }
+    Ok(true)
+}
+
+/// Assembles the receipt of a checkpoint whose segments are all proven.
+pub fn assemble_receipt(dir: &Path, session: &CheckpointedSession) -> anyhow::Result<Receipt> {
+    let segments = (0..session.segments)
+        .map(|index| {
+            let data = fs::read(receipt_path(dir, index))?;
+            Ok(bincode::deserialize::<SegmentReceipt>(&data)?)
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let inner = InnerReceipt::Composite(CompositeReceipt {
+        segments,
+        assumptions: vec![],
+        journal_digest: Some(session.journal.digest()),
+    });
+    Ok(Receipt::new(inner, session.journal.bytes.clone()))
+}
+
+/// Proves a guest execution with checkpoints, continuing in fresh processes when the host
+/// nears its memory limit. The checkpoint is removed once the receipt is assembled.
+pub fn prove(
+    config: &CheckpointConfig,
+    input: &[u32],
+    env: ExecutorEnvBuilder<'_>,
+    logs: &GuestLogs,
+    elf: &[u8],
+) -> anyhow::Result<(Receipt, CheckpointedSession)> {
+    let dir = job_dir(&config.dir, input);
+    let session = match read_session(&dir)? {
+        Some(session) => session,
+        None => execute(&dir, env, logs, elf)?,
+    };
+
+    let watchdog = MemoryWatchdog::new(config.max_memory_usage_percent);
+    if !prove_segments(&dir, &watchdog, false)? {
+        continue_in_fresh_processes(config, &dir)?;
+    }
+
+    let receipt = assemble_receipt(&dir, &session)?;
+    fs::remove_dir_all(&dir)?;
+    Ok((receipt, session))
+}
+
+fn continue_in_fresh_processes(config: &CheckpointConfig, dir: &Path) -> anyhow::Result<()> {
+    loop {
+        let status = Command::new(&config.continuation_bin)
+            .arg(dir)
+            .arg("--max-memory-usage-percent")
+            .arg(config.max_memory_usage_percent.to_string())
+            .status()
+            .with_context(|| {
+                format!(
+                    "Failed to run continuation binary {}",
+                    config.continuation_bin.display()
+                )
+            })?;
+        match status.code() {
+            Some(0) => return Ok(()),
+            Some(INTERRUPTED_EXIT_CODE) => continue,
+            _ => anyhow::bail!(
+                "Proving checkpoint {} failed in continuation process: {}",
+                dir.display(),
+                status
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_memory_usage() {
+        let meminfo = "MemTotal:       16000000 kB\nMemFree:         1000000 kB\nMemAvailable:    4000000 kB\n";
+        assert_eq!(meminfo_usage_percent(meminfo), Some(75));
+        assert_eq!(meminfo_usage_percent("MemTotal: 100 kB\n"), None);
+
+        assert_eq!(cgroup_usage_percent("900\n", "1000\n"), Some(90));
+        assert_eq!(cgroup_usage_percent("900\n", "max\n"), None);
+    }
+}
@@ -1,19 +1,56 @@
 //! This module implements the [`ZkvmHost`] trait for the RISC0 VM.
 
-use risc0_zkvm::{ExecutorEnvBuilder, ExecutorImpl, InnerReceipt, Journal, Receipt, Session};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+use risc0_zkvm::recursion::identity_p254;
+use risc0_zkvm::{
+    get_prover_server, stark_to_snark, CompactReceipt, ExecutorEnvBuilder, ExecutorImpl,
+    InnerReceipt, Journal, ProverOpts, Receipt, Session,
+};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
-use sov_rollup_interface::zk::{Proof, Zkvm, ZkvmHost};
+use sov_rollup_interface::zk::{GuestExecutionDetails, Proof, Zkvm, ZkvmHost};
 
+use crate::checkpoint::{self, CheckpointConfig};
 use crate::guest::Risc0Guest;
 use crate::Risc0MethodId;
 
+/// Logs of the guest kept for a session, the rest is dropped.
+const MAX_GUEST_LOGS_SIZE: usize = 64 * 1024;
+
+/// Collects what the guest writes to its stdout and stderr, up to [`MAX_GUEST_LOGS_SIZE`].
+#[derive(Clone, Default)]
+pub struct GuestLogs(Arc<Mutex<Vec<u8>>>);
+
+impl GuestLogs {
+    /// Takes the logs collected so far.
+    pub fn take(&self) -> String {
+        let logs = std::mem::take(&mut *self.0.lock().unwrap());
+        String::from_utf8_lossy(&logs).into_owned()
+    }
+}
+
+impl Write for GuestLogs {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let mut logs = self.0.lock().unwrap();
+        let kept = buf.len().min(MAX_GUEST_LOGS_SIZE - logs.len());
+        logs.extend_from_slice(&buf[..kept]);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
 /// A [`Risc0Host`] stores a binary to execute in the Risc0 VM, and accumulates hints to be
 /// provided to its execution.
 #[derive(Clone)]
 pub struct Risc0Host<'a> {
     env: Vec<u32>,
     elf: &'a [u8],
+    checkpoint: Option<CheckpointConfig>,
 }
 
 #[cfg(not(feature = "bench"))]
@@ -43,26 +80,68 @@ impl<'a> Risc0Host<'a> {
         Self {
             env: Default::default(),
             elf,
+            checkpoint: None,
         }
     }
 
+    /// Proves with checkpoints, continuing in a fresh process when the host nears its
+    /// memory limit. See [`checkpoint`].
+    pub fn with_checkpoints(mut self, config: CheckpointConfig) -> Self {
+        self.checkpoint = Some(config);
+        self
+    }
+
+    fn executor_env(&self, logs: &GuestLogs) -> ExecutorEnvBuilder<'_> {
+        let mut env = add_benchmarking_callbacks(ExecutorEnvBuilder::default());
+        env.write_slice(&self.env);
+        env.stdout(logs.clone());
+        env.stderr(logs.clone());
+        env
+    }
+
+    fn run_session(&mut self, logs: &GuestLogs) -> anyhow::Result<Session> {
+        let env = self.executor_env(logs).build().unwrap();
+        let mut executor = ExecutorImpl::from_elf(env, self.elf)?;
+        executor.run()
+    }
+
     /// Run a computation in the zkVM without generating a receipt.
     /// This creates the "Session" trace without invoking the heavy cryptographic machinery.
     pub fn run_without_proving(&mut self) -> anyhow::Result<Session> {
-        let env = add_benchmarking_callbacks(ExecutorEnvBuilder::default())
-            .write_slice(&self.env)
-            .build()
-            .unwrap();
-        let mut executor = ExecutorImpl::from_elf(env, self.elf)?;
-        executor.run()
+        self.run_session(&GuestLogs::default())
     }
     /// Run a computation in the zkvm and generate a receipt.
     pub fn run(&mut self) -> anyhow::Result<Receipt> {
+        if let Some(config) = &self.checkpoint {
+            let logs = GuestLogs::default();
+            let (receipt, _) =
+                checkpoint::prove(config, &self.env, self.executor_env(&logs), &logs, self.elf)?;
+            return Ok(receipt);
+        }
         let session = self.run_without_proving()?;
         session.prove()
     }
 }
 
+/// Wraps a STARK receipt into a Groth16 receipt of a few hundred bytes.
+///
+/// The segment receipts are compressed into a single succinct receipt, which is then
+/// proven by the Groth16 prover. The Groth16 prover runs in docker.
+pub fn wrap_receipt(receipt: &Receipt) -> anyhow::Result<Receipt> {
+    let claim = receipt.get_claim()?;
+    let composite = receipt.inner.composite()?;
+
+    let prover = get_prover_server(&ProverOpts::default())?;
+    let succinct = prover.compress(composite)?;
+    let identity = identity_p254(&succinct)?;
+    let seal = stark_to_snark(&identity.get_seal_bytes())?.to_vec();
+
+    Ok(Receipt::new(
+        InnerReceipt::Compact(CompactReceipt { seal, claim }),
+        receipt.journal.bytes.clone(),
+    ))
+}
+
 impl<'a> ZkvmHost for Risc0Host<'a> {
     type Guest = Risc0Guest;
 
@@ -97,6 +176,57 @@ impl<'a> ZkvmHost for Risc0Host<'a> {
         }
     }
 
+    fn run_with_details(
+        &mut self,
+        with_proof: bool,
+    ) -> Result<(Proof, GuestExecutionDetails), anyhow::Error> {
+        let logs = GuestLogs::default();
+        if let (true, Some(config)) = (with_proof, &self.checkpoint) {
+            let (receipt, session) =
+                checkpoint::prove(config, &self.env, self.executor_env(&logs), &logs, self.elf)?;
+            let details = GuestExecutionDetails {
+                journal: bincode::serialize(&session.journal)?,
+                cycle_count: session.cycle_count,
+                logs: session.logs,
+            };
+            return Ok((Proof::Full(bincode::serialize(&receipt)?), details));
+        }
+        let session = self.run_session(&logs)?;
+        // The journal is serialized the same way in both modes, so that
+        // executions and proving sessions of the same block can be compared.
+        let details = GuestExecutionDetails {
+            journal: bincode::serialize(&session.journal)?,
+            cycle_count: session.get_cycles().ok(),
+            logs: logs.take(),
+        };
+        let proof = if with_proof {
+            let receipt = session.prove()?;
+            Proof::Full(bincode::serialize(&receipt)?)
+        } else {
+            Proof::PublicInput(details.journal.clone())
+        };
+        Ok((proof, details))
+    }
+
+    fn serialized_hints(&self) -> Option<Vec<u8>> {
+        // The guest reads the input as little endian words
+        Some(
+            self.env
+                .iter()
+                .flat_map(|word| word.to_le_bytes())
+                .collect(),
+        )
+    }
+
+    fn wrap_proof(&self, proof: Proof) -> Result<Proof, anyhow::Error> {
+        let Proof::Full(data) = proof else {
+            anyhow::bail!("Only full proofs can be wrapped");
+        };
+        let receipt: Receipt = bincode::deserialize(&data)?;
+        let wrapped = wrap_receipt(&receipt)?;
+        Ok(Proof::Full(bincode::serialize(&wrapped)?))
+    }
+
     fn extract_output<Da: sov_rollup_interface::da::DaSpec, Root: Serialize + DeserializeOwned>(
         proof: &Proof,
     ) -> Result<sov_rollup_interface::zk::StateTransition<Da, Root>, Self::Error> {
@@ -7,6 +7,8 @@ use risc0_zkvm::sha::Digest;
 use serde::{Deserialize, Serialize};
 use sov_rollup_interface::zk::Matches;
 
+#[cfg(feature = "native")]
+pub mod checkpoint;
 pub mod guest;
 #[cfg(feature = "native")]
 pub mod host;
@@ -19,6 +21,13 @@ pub mod metrics;
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Risc0MethodId([u32; 8]);
 
+impl Risc0MethodId {
+    /// Creates the method id of a binary from the image id computed when building it.
+    pub fn new(id: [u32; 8]) -> Self {
+        Self(id)
+    }
+}
+
 impl Matches<Self> for Risc0MethodId {
     fn matches(&self, other: &Self) -> bool {
         self.0 == other.0
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use sov_risc0_adapter::host::Risc0Host;
-use sov_rollup_interface::zk::{ZkvmGuest, ZkvmHost};
+use sov_rollup_interface::zk::{Proof, ZkvmGuest, ZkvmHost};
 
 #[derive(Serialize, Deserialize, Debug, PartialEq)]
 struct TestStruct {
@@ -32,3 +32,9 @@ fn test_hints_roundtrip() {
     received = guest.read_from_host();
     assert_eq!(hint_b, received);
 }
+
+#[test]
+fn test_wrap_proof_requires_full_proof() {
+    let host = Risc0Host::new(&[]);
+    assert!(host.wrap_proof(Proof::PublicInput(vec![1, 2, 3])).is_err());
+}
@@ -1,27 +1,41 @@
+use std::ops::RangeInclusive;
 use std::path::Path;
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 use serde::Serialize;
 use sov_rollup_interface::da::DaSpec;
+use sov_rollup_interface::rpc::{L1FeeRateSnapshot, SyncStatus};
 use sov_rollup_interface::services::da::SlotData;
 use sov_rollup_interface::stf::{BatchReceipt, Event, SoftBatchReceipt};
 use sov_schema_db::{Schema, SchemaBatch, SeekKeyEncoder, DB};
 
+use crate::metrics::SOFT_CONFIRMATION_LATENCY_SECONDS;
 use crate::rocks_db_config::gen_rocksdb_options;
 use crate::schema::tables::{
-    BatchByHash, BatchByNumber, EventByKey, EventByNumber, L2RangeByL1Height,
-    LastSequencerCommitmentSent, SlotByHash, SlotByNumber, SoftBatchByHash, SoftBatchByNumber,
-    SoftConfirmationStatus, TxByHash, TxByNumber, LEDGER_TABLES,
+    AddressIndexHead, BatchByHash, BatchByNumber, EventByKey, EventByNumber, L1FeeRateByL1Height,
+    L2RangeByL1Height, LastSequencerCommitmentSent, LightNodeHead, ProofDetailsBySlotHash,
+    ProvenRangeByL2End, QuarantinedProofBySlotHash, RelayedTxByHash, SequencerDaActivity,
+    SlotByHash, SlotByNumber, SoftBatchByHash, SoftBatchByNumber, SoftBatchCommittedAt,
+    SoftConfirmationStatus, StatusTimestampsByL1Height, TxByHash, TxByNumber, TxHashByAddress,
+    TxSubmittedAt, LEDGER_TABLES,
 };
 use crate::schema::types::{
-    split_tx_for_storage, BatchNumber, EventNumber, L2HeightRange, SlotNumber, StoredBatch,
-    StoredSlot, StoredSoftBatch, StoredTransaction, TxNumber,
+    split_tx_for_storage, BatchNumber, DbHash, EventNumber, L2HeightRange, SlotNumber,
+    SoftBatchTimeline, StoredBatch, StoredLightNodeHead, StoredSlot, StoredSoftBatch,
+    StoredTransaction, TxNumber,
 };
 
 mod rpc;
+mod status_cache;
+
+use status_cache::StatusCache;
 
 const LEDGER_DB_PATH_SUFFIX: &str = "ledger";
 
+/// Number of items read per iterator by range queries.
+const RANGE_QUERY_CHUNK_SIZE: usize = 64;
+
 #[derive(Clone, Debug)]
 /// A database which stores the ledger history (slots, transactions, events, etc).
 /// Ledger data is first ingested into an in-memory map before being fed to the state-transition function.
@@ -32,6 +46,10 @@ pub struct LedgerDB {
     db: Arc<DB>,
     next_item_numbers: Arc<Mutex<ItemNumbers>>,
     slot_subscriptions: tokio::sync::broadcast::Sender<u64>,
+    soft_batch_subscriptions: tokio::sync::broadcast::Sender<u64>,
+    status_cache: Arc<RwLock<StatusCache>>,
+    /// Sync progress recorded by the runner, not persisted.
+    sync_status: Arc<RwLock<Option<SyncStatus>>>,
 }
 
 /// A SlotNumber, BatchNumber, TxNumber, and EventNumber which are grouped together, typically representing
@@ -113,13 +131,41 @@ impl LedgerDB {
                 + 1,
         };
 
+        let status_cache = Self::load_status_cache(&inner)?;
+
         Ok(Self {
             db: Arc::new(inner),
             next_item_numbers: Arc::new(Mutex::new(next_item_numbers)),
             slot_subscriptions: tokio::sync::broadcast::channel(10).0,
+            soft_batch_subscriptions: tokio::sync::broadcast::channel(10).0,
+            status_cache: Arc::new(RwLock::new(status_cache)),
+            sync_status: Arc::new(RwLock::new(None)),
         })
     }
 
+    /// Caches the persisted soft confirmation statuses by L2 range.
+    fn load_status_cache(db: &DB) -> anyhow::Result<StatusCache> {
+        let mut cache = StatusCache::default();
+        let mut iter = db.iter::<SoftConfirmationStatus>()?;
+        iter.seek_to_first();
+        for item in iter {
+            let (l1_height, status) = item?.into_tuple();
+            if let Some((l2_start, l2_end)) = db.get::<L2RangeByL1Height>(&l1_height)? {
+                cache.insert(l2_start.0, l2_end.0, status);
+            }
+        }
+        Ok(cache)
+    }
+
+    /// Returns the total size of the ledger tables in bytes,
+    /// and records the size of each table in the storage metrics.
+    pub fn report_table_sizes(&self) -> anyhow::Result<u64> {
+        LEDGER_TABLES
+            .iter()
+            .map(|table| self.db.report_cf_size(table))
+            .sum()
+    }
+
     /// Get the next slot, block, transaction, and event numbers
     pub fn get_next_items_numbers(&self) -> ItemNumbers {
         self.next_item_numbers.lock().unwrap().clone()
@@ -127,7 +173,7 @@ impl LedgerDB {
 
     /// Gets all slots with numbers `range.start` to `range.end`. If `range.end` is outside
     /// the range of the database, the result will smaller than the requested range.
-    /// Note that this method blindly preallocates for the requested range, so it should not be exposed
+    /// Note that this method reads the whole range at once, so it should not be exposed
     /// directly via rpc.
     pub(crate) fn _get_slot_range(
         &self,
@@ -138,7 +184,7 @@ impl LedgerDB {
 
     /// Gets all batches with numbers `range.start` to `range.end`. If `range.end` is outside
     /// the range of the database, the result will smaller than the requested range.
-    /// Note that this method blindly preallocates for the requested range, so it should not be exposed
+    /// Note that this method reads the whole range at once, so it should not be exposed
     /// directly via rpc.
     pub(crate) fn get_batch_range(
         &self,
@@ -149,7 +195,7 @@ impl LedgerDB {
 
     /// Gets all soft confirmations with numbers `range.start` to `range.end`. If `range.end` is outside
     /// the range of the database, the result will smaller than the requested range.
-    /// Note that this method blindly preallocates for the requested range, so it should not be exposed
+    /// Note that this method reads the whole range at once, so it should not be exposed
     /// directly via rpc.
     pub fn get_soft_batch_range(
         &self,
@@ -160,7 +206,7 @@ impl LedgerDB {
 
     /// Gets all transactions with numbers `range.start` to `range.end`. If `range.end` is outside
     /// the range of the database, the result will smaller than the requested range.
-    /// Note that this method blindly preallocates for the requested range, so it should not be exposed
+    /// Note that this method reads the whole range at once, so it should not be exposed
     /// directly via rpc.
     pub(crate) fn get_tx_range(
         &self,
@@ -171,23 +217,64 @@ impl LedgerDB {
 
     /// Gets all data with identifier in `range.start` to `range.end`. If `range.end` is outside
     /// the range of the database, the result will smaller than the requested range.
-    /// Note that this method blindly preallocates for the requested range, so it should not be exposed
+    /// Note that this method reads the whole range at once, so it should not be exposed
     /// directly via rpc.
     fn get_data_range<T, K, V>(&self, range: &std::ops::Range<K>) -> Result<Vec<V>, anyhow::Error>
     where
         T: Schema<Key = K, Value = V>,
         K: Into<u64> + Copy + SeekKeyEncoder<T>,
     {
-        let mut raw_iter = self.db.iter()?;
-        let max_items = (range.end.into() - range.start.into()) as usize;
-        raw_iter.seek(&range.start)?;
-        let iter = raw_iter.take(max_items);
-        let mut out = Vec::with_capacity(max_items);
-        for res in iter {
-            let batch = res?.value;
-            out.push(batch)
+        Ok(self
+            .get_keyed_data_range::<T, K, V>(range)?
+            .into_iter()
+            .map(|(_, value)| value)
+            .collect())
+    }
+
+    /// Gets all data with identifier in `range.start` to `range.end`, with their identifiers.
+    ///
+    /// The range is read from a single snapshot, so it is consistent even if it is written to
+    /// or compacted meanwhile. It is read in chunks of [`RANGE_QUERY_CHUNK_SIZE`] items with a
+    /// new iterator per chunk, so that a long range doesn't pin memtables and SST files for its
+    /// whole duration, and the thread yields between chunks to let writers make progress.
+    fn get_keyed_data_range<T, K, V>(
+        &self,
+        range: &std::ops::Range<K>,
+    ) -> Result<Vec<(K, V)>, anyhow::Error>
+    where
+        T: Schema<Key = K, Value = V>,
+        K: Into<u64> + Copy + SeekKeyEncoder<T>,
+    {
+        let end: u64 = range.end.into();
+        let max_items = end.saturating_sub(range.start.into()) as usize;
+        let snapshot = self.db.read_snapshot();
+        let mut out = Vec::with_capacity(max_items.min(RANGE_QUERY_CHUNK_SIZE));
+
+        let mut seek_key = range.start;
+        // After the first chunk, the seek key is the last item read
+        let mut skip_seek_key = false;
+        loop {
+            let mut iter = snapshot.iter::<T>()?;
+            iter.seek(&seek_key)?;
+            let chunk = iter
+                .skip(skip_seek_key as usize)
+                .take(RANGE_QUERY_CHUNK_SIZE)
+                .map(|res| res.map(|item| item.into_tuple()))
+                .take_while(|res| res.as_ref().map_or(true, |(key, _)| (*key).into() < end))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let is_last_chunk = chunk.len() < RANGE_QUERY_CHUNK_SIZE;
+            if let Some((key, _)) = chunk.last() {
+                seek_key = *key;
+            }
+            out.extend(chunk);
+            if is_last_chunk {
+                return Ok(out);
+            }
+
+            skip_seek_key = true;
+            std::thread::yield_now();
         }
-        Ok(out)
     }
 
     fn put_slot(
@@ -297,6 +384,27 @@ impl LedgerDB {
             txs.push(tx_to_store);
         }
 
+        // Record the L1 fee rate observed on the DA slot
+        let l1_height = SlotNumber(batch_receipt.da_slot_height);
+        let l1_fee_rate = batch_receipt.l1_fee_rate;
+        let l1_fee_rate_snapshot = match self.db.get::<L1FeeRateByL1Height>(&l1_height)? {
+            Some(snapshot) => L1FeeRateSnapshot {
+                min_l1_fee_rate: snapshot.min_l1_fee_rate.min(l1_fee_rate),
+                max_l1_fee_rate: snapshot.max_l1_fee_rate.max(l1_fee_rate),
+                last_l1_fee_rate: l1_fee_rate,
+                soft_confirmation_count: snapshot.soft_confirmation_count + 1,
+                ..snapshot
+            },
+            None => L1FeeRateSnapshot {
+                l1_height: l1_height.0,
+                min_l1_fee_rate: l1_fee_rate,
+                max_l1_fee_rate: l1_fee_rate,
+                last_l1_fee_rate: l1_fee_rate,
+                soft_confirmation_count: 1,
+            },
+        };
+        schema_batch.put::<L1FeeRateByL1Height>(&l1_height, &l1_fee_rate_snapshot)?;
+
         // Insert batch
         let batch_to_store = StoredSoftBatch {
             da_slot_height: batch_receipt.da_slot_height,
@@ -317,13 +425,27 @@ impl LedgerDB {
             &BatchNumber(current_item_numbers.soft_batch_number),
             &mut schema_batch,
         )?;
+        schema_batch.put::<SoftBatchCommittedAt>(
+            &BatchNumber(current_item_numbers.soft_batch_number),
+            &unix_millis(),
+        )?;
         current_item_numbers.soft_batch_number += 1;
 
         self.db.write_schemas(schema_batch)?;
 
+        // Notify subscribers. This call returns an error IFF there are no subscribers, so we don't need to check the result
+        let _ = self
+            .soft_batch_subscriptions
+            .send(current_item_numbers.soft_batch_number - 1);
+
         Ok(())
     }
 
+    /// Returns a receiver of the numbers of the soft batches committed from now on.
+    pub fn subscribe_soft_batches(&self) -> tokio::sync::broadcast::Receiver<u64> {
+        self.soft_batch_subscriptions.subscribe()
+    }
+
     /// Commits a slot to the database by inserting its events, transactions, and batches before
     /// inserting the slot metadata.
     pub fn commit_slot<S: SlotData, B: Serialize, T: Serialize>(
@@ -458,9 +580,272 @@ impl LedgerDB {
             .unwrap();
         self.db.write_schemas(schema_batch)?;
 
+        self.record_status_timestamps(height, &status)?;
+        self.cache_soft_confirmation_status(height, status)
+    }
+
+    /// Marks the soft confirmations on the DA slots at `l1_heights` as finalized, once the
+    /// sequencer commitment covering them is verified.
+    pub fn put_verified_commitment(&self, l1_heights: RangeInclusive<u64>) -> anyhow::Result<()> {
+        let status = sov_rollup_interface::rpc::SoftConfirmationStatus::Finalized;
+        let mut schema_batch = SchemaBatch::new();
+        for l1_height in l1_heights.clone() {
+            schema_batch.put::<SoftConfirmationStatus>(&SlotNumber(l1_height), &status)?;
+        }
+        self.db.write_schemas(schema_batch)?;
+
+        for l1_height in l1_heights {
+            self.record_status_timestamps(SlotNumber(l1_height), &status)?;
+            self.cache_soft_confirmation_status(SlotNumber(l1_height), status.clone())?;
+        }
+        Ok(())
+    }
+
+    /// Records when the soft confirmations of the DA slot were finalized or proven, unless they
+    /// were before, and observes the latency of each stage from their commitment.
+    fn record_status_timestamps(
+        &self,
+        l1_height: SlotNumber,
+        status: &sov_rollup_interface::rpc::SoftConfirmationStatus,
+    ) -> anyhow::Result<()> {
+        use sov_rollup_interface::rpc::SoftConfirmationStatus::{Finalized, Proven};
+
+        let now = unix_millis();
+        let mut timestamps = self
+            .db
+            .get::<StatusTimestampsByL1Height>(&l1_height)?
+            .unwrap_or_default();
+        let mut reached = vec![];
+        if matches!(status, Finalized | Proven) && timestamps.finalized_at.is_none() {
+            timestamps.finalized_at = Some(now);
+            reached.push("finalized");
+        }
+        if *status == Proven && timestamps.proven_at.is_none() {
+            timestamps.proven_at = Some(now);
+            reached.push("proven");
+        }
+        if reached.is_empty() {
+            return Ok(());
+        }
+        self.db
+            .put::<StatusTimestampsByL1Height>(&l1_height, &timestamps)?;
+
+        if let Some((l2_start, l2_end)) = self.db.get::<L2RangeByL1Height>(&l1_height)? {
+            for l2_height in l2_start.0..=l2_end.0 {
+                let Some(committed_at) = self
+                    .db
+                    .get::<SoftBatchCommittedAt>(&BatchNumber(l2_height))?
+                else {
+                    continue;
+                };
+                let latency = now.saturating_sub(committed_at) as f64 / 1000.0;
+                for stage in &reached {
+                    SOFT_CONFIRMATION_LATENCY_SECONDS
+                        .with_label_values(&[stage])
+                        .observe(latency);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns when the soft batch was committed, finalized and proven on the node,
+    /// if it is committed.
+    pub fn get_soft_batch_timeline(
+        &self,
+        number: BatchNumber,
+    ) -> anyhow::Result<Option<SoftBatchTimeline>> {
+        let Some(soft_batch) = self.db.get::<SoftBatchByNumber>(&number)? else {
+            return Ok(None);
+        };
+        let timestamps = self
+            .db
+            .get::<StatusTimestampsByL1Height>(&SlotNumber(soft_batch.da_slot_height))?
+            .unwrap_or_default();
+        Ok(Some(SoftBatchTimeline {
+            da_slot_height: soft_batch.da_slot_height,
+            committed_at: self.db.get::<SoftBatchCommittedAt>(&number)?,
+            finalized_at: timestamps.finalized_at,
+            proven_at: timestamps.proven_at,
+        }))
+    }
+
+    /// Records that a transaction was submitted to the node now, by the hash of the EVM
+    /// transaction. Called by the RPC of the sequencer and of full nodes.
+    pub fn put_tx_submission(&self, hash: DbHash) -> anyhow::Result<()> {
+        self.db.put::<TxSubmittedAt>(&hash, &unix_millis())
+    }
+
+    /// Returns when a transaction was submitted to the node, in Unix milliseconds,
+    /// if it was submitted to this node.
+    pub fn get_tx_submission(&self, hash: DbHash) -> anyhow::Result<Option<u64>> {
+        self.db.get::<TxSubmittedAt>(&hash)
+    }
+
+    fn cache_soft_confirmation_status(
+        &self,
+        l1_height: SlotNumber,
+        status: sov_rollup_interface::rpc::SoftConfirmationStatus,
+    ) -> anyhow::Result<()> {
+        if let Some((l2_start, l2_end)) = self.db.get::<L2RangeByL1Height>(&l1_height)? {
+            self.status_cache
+                .write()
+                .unwrap()
+                .insert(l2_start.0, l2_end.0, status);
+        }
+        Ok(())
+    }
+
+    /// Saves the details of the prover's session for a DA slot
+    pub fn put_proof_details(
+        &self,
+        details: &sov_rollup_interface::rpc::ProofDetails,
+    ) -> Result<(), anyhow::Error> {
+        let mut schema_batch = SchemaBatch::new();
+
+        schema_batch
+            .put::<ProofDetailsBySlotHash>(&details.da_slot_hash, details)
+            .unwrap();
+        self.db.write_schemas(schema_batch)?;
+
+        Ok(())
+    }
+
+    /// Saves a proof that failed the self-check of the prover, instead of submitting it to DA.
+    /// Called by the prover.
+    pub fn put_quarantined_proof(
+        &self,
+        da_slot_hash: DbHash,
+        proof: Vec<u8>,
+    ) -> anyhow::Result<()> {
+        self.db
+            .put::<QuarantinedProofBySlotHash>(&da_slot_hash, &proof)
+    }
+
+    /// Returns the proof of the DA slot quarantined by the prover, if any.
+    pub fn get_quarantined_proof(&self, da_slot_hash: &DbHash) -> anyhow::Result<Option<Vec<u8>>> {
+        self.db.get::<QuarantinedProofBySlotHash>(da_slot_hash)
+    }
+
+    /// Saves the DA activity of the sequencer and the prover
+    /// Called by the full node.
+    pub fn put_sequencer_da_activity(
+        &self,
+        activity: &sov_rollup_interface::rpc::SequencerDaActivity,
+    ) -> Result<(), anyhow::Error> {
+        let mut schema_batch = SchemaBatch::new();
+
+        schema_batch
+            .put::<SequencerDaActivity>(&(), activity)
+            .unwrap();
+        self.db.write_schemas(schema_batch)?;
+
+        Ok(())
+    }
+
+    /// Saves how far the light node followed the proven state
+    /// Called by the light node.
+    pub fn put_light_node_head(&self, head: &StoredLightNodeHead) -> anyhow::Result<()> {
+        self.db.put::<LightNodeHead>(&(), head)
+    }
+
+    /// Returns how far the light node followed the proven state, if it started.
+    pub fn get_light_node_head(&self) -> anyhow::Result<Option<StoredLightNodeHead>> {
+        self.db.get::<LightNodeHead>(&())
+    }
+
+    /// Saves an L2 range covered by a ZK proof
+    /// Called by the full node.
+    pub fn put_proven_range(
+        &self,
+        range: &sov_rollup_interface::rpc::ProvenRange,
+    ) -> Result<(), anyhow::Error> {
+        let mut schema_batch = SchemaBatch::new();
+
+        schema_batch
+            .put::<ProvenRangeByL2End>(&BatchNumber(range.l2_end_height), range)
+            .unwrap();
+        self.db.write_schemas(schema_batch)?;
+
         Ok(())
     }
 
+    /// Journals a raw transaction relayed to the sequencer, by its hash.
+    /// Called by the full node.
+    pub fn put_relayed_tx(&self, hash: DbHash, raw_tx: Vec<u8>) -> anyhow::Result<()> {
+        self.db.put::<RelayedTxByHash>(&hash, &raw_tx)
+    }
+
+    /// Removes transactions from the journal of relayed transactions.
+    pub fn delete_relayed_txs(&self, hashes: &[DbHash]) -> anyhow::Result<()> {
+        let mut schema_batch = SchemaBatch::new();
+        for hash in hashes {
+            schema_batch.delete::<RelayedTxByHash>(hash)?;
+        }
+        self.db.write_schemas(schema_batch)
+    }
+
+    /// Returns the journaled relayed transactions, with their hashes.
+    pub fn get_relayed_txs(&self) -> anyhow::Result<Vec<(DbHash, Vec<u8>)>> {
+        let mut iter = self.db.iter::<RelayedTxByHash>()?;
+        iter.seek_to_first();
+        iter.map(|item| item.map(|item| item.into_tuple()))
+            .collect()
+    }
+
+    /// Indexes the EVM transactions of the L2 block `l2_height` by the addresses that sent or
+    /// received them, as `(address, index in the block, transaction hash)`, and records the block
+    /// as the last indexed one.
+    /// Called by the RPC of nodes indexing transactions by address.
+    pub fn put_address_txs(
+        &self,
+        l2_height: BatchNumber,
+        txs: &[([u8; 20], u32, DbHash)],
+    ) -> anyhow::Result<()> {
+        let mut schema_batch = SchemaBatch::new();
+        for (address, index, hash) in txs {
+            schema_batch.put::<TxHashByAddress>(&(*address, l2_height.0, *index), hash)?;
+        }
+        schema_batch.put::<AddressIndexHead>(&(), &l2_height)?;
+        self.db.write_schemas(schema_batch)
+    }
+
+    /// Returns the last L2 block indexed by [`LedgerDB::put_address_txs`], if any.
+    pub fn get_address_index_head(&self) -> anyhow::Result<Option<BatchNumber>> {
+        self.db.get::<AddressIndexHead>(&())
+    }
+
+    /// Returns up to `limit` indexed transactions of an address before the position `before`,
+    /// newest first, as `((L2 block number, index in the block), transaction hash)`.
+    /// Starts from the newest transaction if `before` is not set.
+    pub fn get_address_txs(
+        &self,
+        address: [u8; 20],
+        before: Option<(u64, u32)>,
+        limit: usize,
+    ) -> anyhow::Result<Vec<((u64, u32), DbHash)>> {
+        let last = match before {
+            None => (u64::MAX, u32::MAX),
+            Some((number, index)) => match (index.checked_sub(1), number.checked_sub(1)) {
+                (Some(index), _) => (number, index),
+                (None, Some(number)) => (number, u32::MAX),
+                (None, None) => return Ok(vec![]),
+            },
+        };
+
+        let mut iter = self.db.iter::<TxHashByAddress>()?.rev();
+        iter.seek_for_prev(&(address, last.0, last.1))?;
+        let mut txs = Vec::new();
+        for item in iter {
+            let ((tx_address, number, index), hash) = item?.into_tuple();
+            if tx_address != address || txs.len() == limit {
+                break;
+            }
+            txs.push(((number, index), hash));
+        }
+        Ok(txs)
+    }
+
     fn last_version_written<T: Schema<Key = U>, U: Into<u64>>(
         db: &DB,
         _schema: T,
@@ -499,6 +884,171 @@ impl LedgerDB {
         }
     }
 
+    /// Rolls the ledger back to the most recent soft batch which is fully written and accepted
+    /// by `is_consistent`, walking back from the head soft batch.
+    /// Used on startup to recover from a tail that was left half-written, e.g. after a power loss
+    /// between committing a soft batch to the ledger and finalizing its state.
+    /// Errors without removing anything if more than `max_depth` soft batches would be removed,
+    /// as such a tail is not left by a crash and needs to be looked at.
+    ///
+    /// Returns the number of removed soft batches.
+    pub fn recover_soft_batch_tail(
+        &self,
+        max_depth: u64,
+        mut is_consistent: impl FnMut(BatchNumber, &StoredSoftBatch) -> bool,
+    ) -> anyhow::Result<u64> {
+        let head = self.get_next_items_numbers().soft_batch_number - 1;
+
+        let mut last_consistent = head;
+        while last_consistent > 0 {
+            let number = BatchNumber(last_consistent);
+            if let Ok(batch) = self.get_fully_written_soft_batch(number) {
+                if is_consistent(number, &batch) {
+                    break;
+                }
+            }
+            if head - last_consistent >= max_depth {
+                anyhow::bail!(
+                    "None of the last {} soft batches up to {} is consistent, refusing to roll back further",
+                    max_depth,
+                    head
+                );
+            }
+            last_consistent -= 1;
+        }
+
+        if last_consistent < head {
+            self.rollback_soft_batches(BatchNumber(last_consistent))?;
+        }
+        Ok(head - last_consistent)
+    }
+
+    /// Returns the soft batch with the given number if it can be read, is indexed by its hash,
+    /// all of its transactions are stored and it continues from its parent's post state root.
+    fn get_fully_written_soft_batch(&self, number: BatchNumber) -> anyhow::Result<StoredSoftBatch> {
+        let batch = self
+            .db
+            .get::<SoftBatchByNumber>(&number)?
+            .ok_or_else(|| anyhow::anyhow!("Soft batch {} is missing", number.0))?;
+
+        if self.db.get::<SoftBatchByHash>(&batch.hash)? != Some(number) {
+            anyhow::bail!("Soft batch {} is not indexed by its hash", number.0);
+        }
+
+        if batch.tx_range.end.0 - batch.tx_range.start.0 != batch.txs.len() as u64 {
+            anyhow::bail!("Soft batch {} has a malformed transaction range", number.0);
+        }
+        for (tx_number, tx) in (batch.tx_range.start.0..batch.tx_range.end.0).zip(&batch.txs) {
+            let stored_tx = self.db.get::<TxByNumber>(&TxNumber(tx_number))?;
+            if stored_tx.map(|stored_tx| stored_tx.hash) != Some(tx.hash) {
+                anyhow::bail!(
+                    "Transaction {} of soft batch {} is missing",
+                    tx_number,
+                    number.0
+                );
+            }
+        }
+
+        if number.0 > 1 {
+            if let Some(parent) = self
+                .db
+                .get::<SoftBatchByNumber>(&BatchNumber(number.0 - 1))?
+            {
+                if parent.post_state_root != batch.pre_state_root {
+                    anyhow::bail!(
+                        "Soft batch {} does not continue from the state root of its parent",
+                        number.0
+                    );
+                }
+            }
+        }
+
+        Ok(batch)
+    }
+
+    /// Removes the soft batches after `last` together with their transactions and events,
+    /// and shrinks the L2 ranges of L1 slots accordingly.
+    /// The removed soft batches are committed again when the node re-syncs them.
+    pub fn rollback_soft_batches(&self, last: BatchNumber) -> anyhow::Result<()> {
+        let next_item_numbers = self.get_next_items_numbers();
+
+        let first_removed_tx = match self.db.get::<SoftBatchByNumber>(&last)? {
+            Some(batch) => batch.tx_range.end.0,
+            None if last.0 == 0 => 1,
+            None => anyhow::bail!("Cannot roll back to missing soft batch {}", last.0),
+        };
+        let first_removed_event = if first_removed_tx > 1 {
+            self.db
+                .get::<TxByNumber>(&TxNumber(first_removed_tx - 1))?
+                .map(|tx| tx.events.end.0)
+                .ok_or_else(|| anyhow::anyhow!("Transaction {} is missing", first_removed_tx - 1))?
+        } else {
+            1
+        };
+
+        let mut schema_batch = SchemaBatch::new();
+
+        for number in last.0 + 1..next_item_numbers.soft_batch_number {
+            // Entries that cannot be read are removed by number only
+            if let Ok(Some(batch)) = self.db.get::<SoftBatchByNumber>(&BatchNumber(number)) {
+                schema_batch.delete::<SoftBatchByHash>(&batch.hash)?;
+            }
+            schema_batch.delete::<SoftBatchByNumber>(&BatchNumber(number))?;
+            schema_batch.delete::<SoftBatchCommittedAt>(&BatchNumber(number))?;
+        }
+        for number in first_removed_tx..next_item_numbers.tx_number {
+            if let Ok(Some(tx)) = self.db.get::<TxByNumber>(&TxNumber(number)) {
+                schema_batch.delete::<TxByHash>(&tx.hash)?;
+                for event_number in tx.events.start.0..tx.events.end.0 {
+                    let event_number = EventNumber(event_number);
+                    if let Ok(Some(event)) = self.db.get::<EventByNumber>(&event_number) {
+                        schema_batch.delete::<EventByKey>(&(
+                            event.key().clone(),
+                            TxNumber(number),
+                            event_number,
+                        ))?;
+                    }
+                }
+            }
+            schema_batch.delete::<TxByNumber>(&TxNumber(number))?;
+        }
+        for number in first_removed_event..next_item_numbers.event_number {
+            schema_batch.delete::<EventByNumber>(&EventNumber(number))?;
+        }
+
+        let mut ranges = self.db.iter::<L2RangeByL1Height>()?.rev();
+        ranges.seek_to_last();
+        for item in ranges {
+            let (l1_height, (start, end)) = item?.into_tuple();
+            if end.0 <= last.0 {
+                break;
+            }
+            if start.0 > last.0 {
+                schema_batch.delete::<L2RangeByL1Height>(&l1_height)?;
+            } else {
+                schema_batch.put::<L2RangeByL1Height>(&l1_height, &(start, last))?;
+            }
+        }
+
+        // the removed blocks are indexed again when re-synced
+        if self
+            .db
+            .get::<AddressIndexHead>(&())?
+            .is_some_and(|head| head.0 > last.0)
+        {
+            schema_batch.put::<AddressIndexHead>(&(), &last)?;
+        }
+
+        self.db.write_schemas(schema_batch)?;
+
+        let mut next_item_numbers = self.next_item_numbers.lock().unwrap();
+        next_item_numbers.soft_batch_number = last.0 + 1;
+        next_item_numbers.tx_number = first_removed_tx;
+        next_item_numbers.event_number = first_removed_event;
+
+        Ok(())
+    }
+
     /// Get the most recent committed batch
     /// Returns L1 height, which means the corresponding L2 heights
     /// were committed.
@@ -507,6 +1057,28 @@ impl LedgerDB {
         self.db.get::<LastSequencerCommitmentSent>(&())
     }
 
+    /// Returns the last L2 height finalized by a sequencer commitment on DA, if any.
+    /// Proven L2 heights are finalized as well.
+    pub fn get_last_finalized_l2_height(&self) -> Option<u64> {
+        self.status_cache.read().unwrap().last_finalized()
+    }
+
+    /// Returns the last L2 height covered by a verified proof, if any.
+    pub fn get_last_proven_l2_height(&self) -> Option<u64> {
+        self.status_cache.read().unwrap().last_proven()
+    }
+
+    /// Records the sync progress of the node.
+    /// Called by the runner of full nodes and provers.
+    pub fn set_sync_status(&self, sync_status: SyncStatus) {
+        *self.sync_status.write().unwrap() = Some(sync_status);
+    }
+
+    /// Returns the sync progress of the node, if its runner recorded any.
+    pub fn get_sync_status(&self) -> Option<SyncStatus> {
+        *self.sync_status.read().unwrap()
+    }
+
     /// Get L2 height range for a given L1 height.
     /// This means L2 heights in that range were soft confirmations for L1 height.
     pub fn get_l2_range_by_l1_height(
@@ -516,3 +1088,355 @@ impl LedgerDB {
         self.db.get::<L2RangeByL1Height>(&l1_height)
     }
 }
+
+/// Returns the current Unix time in milliseconds.
+fn unix_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_millis() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::marker::PhantomData;
+
+    use sov_mock_da::{MockDaSpec, MockHash};
+    use sov_rollup_interface::rpc::LedgerRpcProvider;
+    use sov_rollup_interface::stf::TransactionReceipt;
+
+    use super::*;
+
+    fn soft_batch_receipt(number: u8) -> SoftBatchReceipt<(), (), MockDaSpec> {
+        SoftBatchReceipt {
+            da_slot_height: 1,
+            da_slot_hash: MockHash([0; 32]),
+            da_slot_txs_commitment: MockHash([0; 32]),
+            batch_hash: [number; 32],
+            tx_receipts: vec![TransactionReceipt {
+                tx_hash: [number; 32],
+                body_to_save: None,
+                events: vec![Event::new("key", "value")],
+                receipt: (),
+            }],
+            phantom_data: PhantomData,
+            pre_state_root: vec![number - 1],
+            post_state_root: vec![number],
+            soft_confirmation_signature: vec![],
+            pub_key: vec![],
+            l1_fee_rate: 0,
+            timestamp: 0,
+        }
+    }
+
+    fn ledger_with_soft_batches(path: &Path, count: u8) -> LedgerDB {
+        let db = LedgerDB::with_path(path).unwrap();
+        for number in 1..=count {
+            db.commit_soft_batch(soft_batch_receipt(number), false)
+                .unwrap();
+            db.extend_l2_range_of_l1_slot(SlotNumber(1), BatchNumber(number as u64))
+                .unwrap();
+        }
+        db
+    }
+
+    #[test]
+    fn soft_batch_subscription() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db = ledger_with_soft_batches(temp_dir.path(), 2);
+
+        let mut rx = db.subscribe_soft_batches();
+        db.commit_soft_batch(soft_batch_receipt(3), false).unwrap();
+
+        assert_eq!(rx.try_recv().unwrap(), 3);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn l1_fee_rate_snapshots() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db = LedgerDB::with_path(temp_dir.path()).unwrap();
+        for (number, (da_slot_height, l1_fee_rate)) in
+            [(1, 10), (1, 12), (1, 11), (3, 20)].into_iter().enumerate()
+        {
+            let receipt = SoftBatchReceipt {
+                da_slot_height,
+                l1_fee_rate,
+                ..soft_batch_receipt(number as u8 + 1)
+            };
+            db.commit_soft_batch(receipt, false).unwrap();
+        }
+
+        let history = db.get_l1_fee_history(0, 5).unwrap();
+        assert_eq!(
+            history,
+            vec![
+                L1FeeRateSnapshot {
+                    l1_height: 1,
+                    min_l1_fee_rate: 10,
+                    max_l1_fee_rate: 12,
+                    last_l1_fee_rate: 11,
+                    soft_confirmation_count: 3,
+                },
+                L1FeeRateSnapshot {
+                    l1_height: 3,
+                    min_l1_fee_rate: 20,
+                    max_l1_fee_rate: 20,
+                    last_l1_fee_rate: 20,
+                    soft_confirmation_count: 1,
+                },
+            ]
+        );
+        assert_eq!(db.get_l1_fee_history(2, 2).unwrap(), vec![]);
+        assert!(db.get_l1_fee_history(3, 1).is_err());
+    }
+
+    #[test]
+    fn soft_confirmation_status_survives_restart() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db = ledger_with_soft_batches(temp_dir.path(), 3);
+
+        assert_eq!(
+            db.get_soft_confirmation_status(2).unwrap(),
+            sov_rollup_interface::rpc::SoftConfirmationStatus::Trusted
+        );
+        db.put_verified_commitment(1..=1).unwrap();
+        assert_eq!(
+            db.get_soft_confirmation_status(2).unwrap(),
+            sov_rollup_interface::rpc::SoftConfirmationStatus::Finalized
+        );
+        db.put_soft_confirmation_status(
+            SlotNumber(1),
+            sov_rollup_interface::rpc::SoftConfirmationStatus::Proven,
+        )
+        .unwrap();
+        drop(db);
+
+        let db = LedgerDB::with_path(temp_dir.path()).unwrap();
+        assert_eq!(
+            db.status_cache.read().unwrap().get(3),
+            Some(sov_rollup_interface::rpc::SoftConfirmationStatus::Proven)
+        );
+        assert_eq!(
+            db.get_soft_confirmation_status(1).unwrap(),
+            sov_rollup_interface::rpc::SoftConfirmationStatus::Proven
+        );
+        assert!(db.get_soft_confirmation_status(4).is_err());
+    }
+
+    #[test]
+    fn soft_batch_heights_by_status() {
+        use sov_rollup_interface::rpc::SoftConfirmationStatus::{Finalized, Proven, Trusted};
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db = LedgerDB::with_path(temp_dir.path()).unwrap();
+        for (number, da_slot_height) in [(1, 1), (2, 1), (3, 2), (4, 3)] {
+            let receipt = SoftBatchReceipt {
+                da_slot_height,
+                ..soft_batch_receipt(number)
+            };
+            db.commit_soft_batch(receipt, false).unwrap();
+            db.extend_l2_range_of_l1_slot(SlotNumber(da_slot_height), BatchNumber(number as u64))
+                .unwrap();
+        }
+        db.put_verified_commitment(1..=2).unwrap();
+        db.put_soft_confirmation_status(SlotNumber(1), Proven)
+            .unwrap();
+
+        assert_eq!(
+            db.get_soft_batch_heights_by_status(Proven, 1, 10).unwrap(),
+            vec![1, 2]
+        );
+        assert_eq!(
+            db.get_soft_batch_heights_by_status(Finalized, 1, 10)
+                .unwrap(),
+            vec![3]
+        );
+        assert_eq!(
+            db.get_soft_batch_heights_by_status(Trusted, 1, 10).unwrap(),
+            vec![4]
+        );
+        assert_eq!(
+            db.get_soft_batch_heights_by_status(Trusted, 1, 3).unwrap(),
+            vec![]
+        );
+        assert!(db.get_soft_batch_heights_by_status(Trusted, 3, 1).is_err());
+        assert!(db
+            .get_soft_batch_heights_by_status(Trusted, 0, 1000)
+            .is_err());
+    }
+
+    #[test]
+    fn relayed_tx_journal_survives_restart() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        {
+            let db = LedgerDB::with_path(temp_dir.path()).unwrap();
+            db.put_relayed_tx([1; 32], vec![1]).unwrap();
+            db.put_relayed_tx([2; 32], vec![2]).unwrap();
+            db.put_relayed_tx([3; 32], vec![3]).unwrap();
+            db.delete_relayed_txs(&[[2; 32]]).unwrap();
+        }
+
+        let db = LedgerDB::with_path(temp_dir.path()).unwrap();
+        assert_eq!(
+            db.get_relayed_txs().unwrap(),
+            vec![([1; 32], vec![1]), ([3; 32], vec![3])]
+        );
+    }
+
+    #[test]
+    fn soft_batch_timelines() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db = ledger_with_soft_batches(temp_dir.path(), 2);
+
+        let timeline = db.get_soft_batch_timeline(BatchNumber(2)).unwrap().unwrap();
+        assert!(timeline.committed_at.is_some());
+        assert_eq!(timeline.finalized_at, None);
+        assert_eq!(timeline.proven_at, None);
+        assert!(db
+            .get_soft_batch_timeline(BatchNumber(3))
+            .unwrap()
+            .is_none());
+
+        db.put_verified_commitment(1..=1).unwrap();
+        let finalized = db.get_soft_batch_timeline(BatchNumber(2)).unwrap().unwrap();
+        assert!(finalized.finalized_at >= timeline.committed_at);
+        assert_eq!(finalized.proven_at, None);
+
+        db.put_soft_confirmation_status(
+            SlotNumber(1),
+            sov_rollup_interface::rpc::SoftConfirmationStatus::Proven,
+        )
+        .unwrap();
+        let proven = db.get_soft_batch_timeline(BatchNumber(1)).unwrap().unwrap();
+        assert_eq!(proven.finalized_at, finalized.finalized_at);
+        assert!(proven.proven_at >= proven.finalized_at);
+
+        assert_eq!(db.get_tx_submission([1; 32]).unwrap(), None);
+        db.put_tx_submission([1; 32]).unwrap();
+        assert!(db.get_tx_submission([1; 32]).unwrap().is_some());
+    }
+
+    #[test]
+    fn address_txs_are_paginated() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db = ledger_with_soft_batches(temp_dir.path(), 3);
+        let (alice, bob) = ([1; 20], [2; 20]);
+
+        db.put_address_txs(BatchNumber(1), &[(alice, 0, [10; 32]), (bob, 0, [10; 32])])
+            .unwrap();
+        db.put_address_txs(BatchNumber(2), &[]).unwrap();
+        db.put_address_txs(
+            BatchNumber(3),
+            &[(alice, 0, [30; 32]), (alice, 1, [31; 32])],
+        )
+        .unwrap();
+        assert_eq!(db.get_address_index_head().unwrap(), Some(BatchNumber(3)));
+
+        assert_eq!(
+            db.get_address_txs(alice, None, 2).unwrap(),
+            vec![((3, 1), [31; 32]), ((3, 0), [30; 32])]
+        );
+        assert_eq!(
+            db.get_address_txs(alice, Some((3, 0)), 2).unwrap(),
+            vec![((1, 0), [10; 32])]
+        );
+        assert_eq!(db.get_address_txs(alice, Some((1, 0)), 2).unwrap(), vec![]);
+        assert_eq!(
+            db.get_address_txs(bob, None, 10).unwrap(),
+            vec![((1, 0), [10; 32])]
+        );
+        assert_eq!(db.get_address_txs([3; 20], None, 10).unwrap(), vec![]);
+
+        db.rollback_soft_batches(BatchNumber(2)).unwrap();
+        assert_eq!(db.get_address_index_head().unwrap(), Some(BatchNumber(2)));
+    }
+
+    #[test]
+    fn get_soft_batch_range_reads_across_chunks() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let count = RANGE_QUERY_CHUNK_SIZE as u8 * 2 + 3;
+        let db = ledger_with_soft_batches(temp_dir.path(), count);
+
+        let batches = db
+            .get_soft_batch_range(&(BatchNumber(0)..BatchNumber(count as u64 + 10)))
+            .unwrap();
+        assert_eq!(batches.len(), count as usize);
+        for (batch, number) in batches.iter().zip(1..=count) {
+            assert_eq!(batch.hash, [number; 32]);
+        }
+
+        let batches = db
+            .get_soft_batch_range(&(BatchNumber(60)..BatchNumber(70)))
+            .unwrap();
+        assert_eq!(batches.len(), 10);
+        assert_eq!(batches[0].hash, [60; 32]);
+        assert_eq!(batches[9].hash, [69; 32]);
+
+        assert!(db
+            .get_soft_batch_range(&(BatchNumber(5)..BatchNumber(5)))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn recover_soft_batch_tail_rolls_back_to_consistent_batch() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db = ledger_with_soft_batches(temp_dir.path(), 3);
+
+        let removed = db
+            .recover_soft_batch_tail(10, |number, _| number.0 <= 1)
+            .unwrap();
+        assert_eq!(removed, 2);
+
+        let (head, _) = db.get_head_soft_batch().unwrap().unwrap();
+        assert_eq!(head, BatchNumber(1));
+        assert_eq!(db.db.get::<TxByHash>(&[2; 32]).unwrap(), None);
+        assert_eq!(db.db.get::<EventByNumber>(&EventNumber(2)).unwrap(), None);
+        assert_eq!(
+            db.get_l2_range_by_l1_height(SlotNumber(1)).unwrap(),
+            Some((BatchNumber(1), BatchNumber(1)))
+        );
+
+        // The removed soft batches can be committed again
+        db.commit_soft_batch(soft_batch_receipt(2), false).unwrap();
+        let (head, batch) = db.get_head_soft_batch().unwrap().unwrap();
+        assert_eq!(head, BatchNumber(2));
+        assert_eq!(batch.tx_range, TxNumber(2)..TxNumber(3));
+        assert_eq!(
+            db.db.get::<SoftBatchByHash>(&[2; 32]).unwrap(),
+            Some(BatchNumber(2))
+        );
+    }
+
+    #[test]
+    fn recover_soft_batch_tail_removes_partially_written_batch() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db = ledger_with_soft_batches(temp_dir.path(), 3);
+        db.db.delete::<TxByNumber>(&TxNumber(3)).unwrap();
+
+        assert_eq!(db.recover_soft_batch_tail(10, |_, _| true).unwrap(), 1);
+        assert_eq!(db.get_next_items_numbers().soft_batch_number, 3);
+
+        // Nothing to recover on a consistent ledger
+        assert_eq!(db.recover_soft_batch_tail(10, |_, _| true).unwrap(), 0);
+    }
+
+    #[test]
+    fn recover_soft_batch_tail_caps_rollback_depth() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db = ledger_with_soft_batches(temp_dir.path(), 3);
+
+        assert!(db
+            .recover_soft_batch_tail(1, |number, _| number.0 <= 1)
+            .is_err());
+        // Nothing is removed past the cap
+        let (head, _) = db.get_head_soft_batch().unwrap().unwrap();
+        assert_eq!(head, BatchNumber(3));
+
+        assert_eq!(
+            db.recover_soft_batch_tail(2, |number, _| number.0 <= 1)
+                .unwrap(),
+            2
+        );
+    }
+}
@@ -8,7 +8,8 @@ use sov_rollup_interface::stf::Event;
 use tokio::sync::broadcast::Receiver;
 
 use crate::schema::tables::{
-    BatchByHash, BatchByNumber, EventByNumber, SlotByHash, SlotByNumber, SoftBatchByHash,
+    BatchByHash, BatchByNumber, EventByNumber, L1FeeRateByL1Height, ProofDetailsBySlotHash,
+    ProvenRangeByL2End, SequencerDaActivity, SlotByHash, SlotByNumber, SoftBatchByHash,
     SoftBatchByNumber, SoftConfirmationStatus, TxByHash, TxByNumber,
 };
 use crate::schema::types::{
@@ -25,6 +26,12 @@ const MAX_SOFT_BATCHES_PER_REQUEST: u64 = 20;
 const MAX_TRANSACTIONS_PER_REQUEST: u64 = 100;
 /// The maximum number of events that can be requested in a single RPC range query
 const MAX_EVENTS_PER_REQUEST: u64 = 500;
+/// The maximum number of proven ranges that can be returned by a single RPC range query
+const MAX_PROVEN_RANGES_PER_REQUEST: usize = 100;
+/// The maximum number of DA slots whose L1 fee rates can be requested in a single RPC range query
+const MAX_L1_FEE_RATES_PER_REQUEST: u64 = 1000;
+/// The maximum number of L2 heights whose statuses can be filtered in a single RPC range query
+const MAX_SOFT_BATCH_STATUSES_PER_REQUEST: u64 = 1000;
 
 use super::LedgerDB;
 
@@ -321,8 +328,21 @@ impl LedgerRpcProvider for LedgerDB {
             "requested batch range too large. Max: {}",
             MAX_BATCHES_PER_REQUEST
         );
-        let ids: Vec<_> = (start..=end).map(SoftBatchIdentifier::Number).collect();
-        self.get_soft_batches(&ids)
+        // Read from a single snapshot, so that the range is consistent with concurrent writes
+        let mut stored = self
+            .get_keyed_data_range::<SoftBatchByNumber, _, _>(
+                &(BatchNumber(start)..BatchNumber(end.saturating_add(1))),
+            )?
+            .into_iter()
+            .peekable();
+        let mut out = Vec::with_capacity((end - start + 1) as usize);
+        for number in start..=end {
+            match stored.next_if(|(key, _)| key.0 == number) {
+                Some((_, batch)) => out.push(Some(batch.try_into()?)),
+                None => out.push(None),
+            }
+        }
+        Ok(out)
     }
 
     fn get_transactions_range<T: DeserializeOwned>(
@@ -345,6 +365,10 @@ impl LedgerRpcProvider for LedgerDB {
         &self,
         l2_height: u64,
     ) -> Result<sov_rollup_interface::rpc::SoftConfirmationStatus, anyhow::Error> {
+        if let Some(status) = self.status_cache.read().unwrap().get(l2_height) {
+            return Ok(status);
+        }
+
         let l2_soft_batch = match self.db.get::<SoftBatchByNumber>(&BatchNumber(l2_height)) {
             Ok(Some(batch)) => batch,
             _ => {
@@ -365,6 +389,136 @@ impl LedgerRpcProvider for LedgerDB {
         }
     }
 
+    fn get_soft_batch_heights_by_status(
+        &self,
+        status: sov_rollup_interface::rpc::SoftConfirmationStatus,
+        start: u64,
+        end: u64,
+    ) -> Result<Vec<u64>, anyhow::Error> {
+        anyhow::ensure!(start <= end, "start must be <= end");
+        anyhow::ensure!(
+            end - start < MAX_SOFT_BATCH_STATUSES_PER_REQUEST,
+            "requested L2 height range too large. Max: {}",
+            MAX_SOFT_BATCH_STATUSES_PER_REQUEST
+        );
+
+        let mut heights = Vec::new();
+        let mut uncached_heights = Vec::new();
+        {
+            let status_cache = self.status_cache.read().unwrap();
+            for l2_height in start..=end {
+                match status_cache.get(l2_height) {
+                    Some(cached) if cached == status => heights.push(l2_height),
+                    Some(_) => {}
+                    None => uncached_heights.push(l2_height),
+                }
+            }
+        }
+
+        // The soft batches of a DA slot share its status, so look each slot up once
+        let mut slot_statuses = std::collections::BTreeMap::new();
+        for l2_height in uncached_heights {
+            let Some(soft_batch) = self.db.get::<SoftBatchByNumber>(&BatchNumber(l2_height))?
+            else {
+                // soft batches are stored in order, the next ones are not processed yet either
+                break;
+            };
+            let l1_height = soft_batch.da_slot_height;
+            let slot_status = match slot_statuses.get(&l1_height) {
+                Some(slot_status) => slot_status.clone(),
+                None => {
+                    let slot_status = self
+                        .db
+                        .get::<SoftConfirmationStatus>(&SlotNumber(l1_height))?
+                        .unwrap_or(sov_rollup_interface::rpc::SoftConfirmationStatus::Trusted);
+                    slot_statuses.insert(l1_height, slot_status.clone());
+                    slot_status
+                }
+            };
+            if slot_status == status {
+                heights.push(l2_height);
+            }
+        }
+
+        heights.sort_unstable();
+        Ok(heights)
+    }
+
+    fn get_proof_details(
+        &self,
+        da_slot_hash: &[u8; 32],
+    ) -> Result<Option<sov_rollup_interface::rpc::ProofDetails>, anyhow::Error> {
+        self.db.get::<ProofDetailsBySlotHash>(da_slot_hash)
+    }
+
+    fn get_sequencer_da_activity(
+        &self,
+    ) -> Result<sov_rollup_interface::rpc::SequencerDaActivity, anyhow::Error> {
+        Ok(self.db.get::<SequencerDaActivity>(&())?.unwrap_or_default())
+    }
+
+    fn get_latest_proven_height(&self) -> Result<Option<u64>, anyhow::Error> {
+        let mut iter = self.db.iter::<ProvenRangeByL2End>()?;
+        iter.seek_to_last();
+
+        match iter.next() {
+            Some(Ok(item)) => Ok(Some(item.key.0)),
+            Some(Err(e)) => Err(e),
+            _ => Ok(None),
+        }
+    }
+
+    fn get_proof_coverage(
+        &self,
+        start: u64,
+        end: u64,
+    ) -> Result<sov_rollup_interface::rpc::ProofCoverage, anyhow::Error> {
+        anyhow::ensure!(start <= end, "start must be <= end");
+
+        let mut iter = self.db.iter::<ProvenRangeByL2End>()?;
+        iter.seek(&BatchNumber(start))?;
+        let mut ranges = Vec::new();
+        for item in iter {
+            let range = item?.value;
+            // Proofs cover consecutive ranges, so the next ones start after `end` too
+            if range.l2_start_height > end {
+                break;
+            }
+            anyhow::ensure!(
+                ranges.len() < MAX_PROVEN_RANGES_PER_REQUEST,
+                "requested too many proven ranges. Max: {}",
+                MAX_PROVEN_RANGES_PER_REQUEST
+            );
+            ranges.push(range);
+        }
+
+        Ok(sov_rollup_interface::rpc::ProofCoverage {
+            latest_proven_height: self.get_latest_proven_height()?,
+            ranges,
+        })
+    }
+
+    fn get_l1_fee_history(
+        &self,
+        start: u64,
+        end: u64,
+    ) -> Result<Vec<sov_rollup_interface::rpc::L1FeeRateSnapshot>, anyhow::Error> {
+        anyhow::ensure!(start <= end, "start must be <= end");
+        anyhow::ensure!(
+            end - start < MAX_L1_FEE_RATES_PER_REQUEST,
+            "requested L1 height range too large. Max: {}",
+            MAX_L1_FEE_RATES_PER_REQUEST
+        );
+
+        Ok(self
+            .get_keyed_data_range::<L1FeeRateByL1Height, _, _>(
+                &(SlotNumber(start)..SlotNumber(end.saturating_add(1))),
+            )?
+            .into_iter()
+            .map(|(_, snapshot)| snapshot)
+            .collect())
+    }
+
     fn subscribe_slots(&self) -> Result<Receiver<u64>, anyhow::Error> {
         Ok(self.slot_subscriptions.subscribe())
     }
@@ -534,7 +688,7 @@ impl LedgerDB {
 #[cfg(test)]
 mod tests {
     use sov_mock_da::{MockBlob, MockBlock};
-    use sov_rollup_interface::rpc::LedgerRpcProvider;
+    use sov_rollup_interface::rpc::{LedgerRpcProvider, ProvenRange};
 
     use crate::ledger_db::{LedgerDB, SlotCommit};
     #[test]
@@ -549,4 +703,36 @@ mod tests {
 
         assert_eq!(rx.blocking_recv().unwrap(), 1);
     }
+
+    #[test]
+    fn test_proof_coverage() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let db = LedgerDB::with_path(temp_dir.path()).unwrap();
+        assert_eq!(db.get_latest_proven_height().unwrap(), None);
+
+        let proven_range = |l2_start_height, l2_end_height| ProvenRange {
+            l2_start_height,
+            l2_end_height,
+            da_slot_height: l2_end_height,
+            da_slot_hash: [l2_end_height as u8; 32],
+            da_txid: [l2_start_height as u8; 32],
+        };
+        db.put_proven_range(&proven_range(1, 10)).unwrap();
+        db.put_proven_range(&proven_range(11, 25)).unwrap();
+        db.put_proven_range(&proven_range(26, 30)).unwrap();
+        assert_eq!(db.get_latest_proven_height().unwrap(), Some(30));
+
+        let coverage = db.get_proof_coverage(12, 26).unwrap();
+        assert_eq!(coverage.latest_proven_height, Some(30));
+        assert_eq!(
+            coverage.ranges,
+            vec![proven_range(11, 25), proven_range(26, 30)]
+        );
+        assert_eq!(
+            db.get_proof_coverage(1, 1).unwrap().ranges,
+            vec![proven_range(1, 10)]
+        );
+        assert!(db.get_proof_coverage(31, 40).unwrap().ranges.is_empty());
+        assert!(db.get_proof_coverage(2, 1).is_err());
+    }
 }
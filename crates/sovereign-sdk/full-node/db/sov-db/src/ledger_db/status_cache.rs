@@ -0,0 +1,97 @@
+use std::collections::BTreeMap;
+
+use sov_rollup_interface::rpc::SoftConfirmationStatus;
+
+/// Statuses of the soft confirmations finalized by a verified sequencer commitment or proven,
+/// by L2 range of their DA slot.
+///
+/// Lets `ledger_getSoftConfirmationStatus` answer without reading the soft batch from the
+/// database to find its DA slot. It is rebuilt from the persisted statuses when the ledger is
+/// opened. Soft confirmations not in the cache are trusted, or on a DA slot whose L2 range
+/// wasn't known when its status was set, and are looked up in the database.
+#[derive(Debug, Default)]
+pub(crate) struct StatusCache {
+    /// `(first L2 height, status)` of the DA slots, by last L2 height.
+    ranges: BTreeMap<u64, (u64, SoftConfirmationStatus)>,
+}
+
+impl StatusCache {
+    /// Sets the status of the soft confirmations at `l2_start..=l2_end`.
+    pub(crate) fn insert(&mut self, l2_start: u64, l2_end: u64, status: SoftConfirmationStatus) {
+        // drop the ranges the new one replaces, e.g. the range of the DA slot before it grew
+        let replaced: Vec<u64> = self
+            .ranges
+            .range(l2_start..)
+            .take_while(|(_, (start, _))| *start <= l2_end)
+            .map(|(end, _)| *end)
+            .collect();
+        for end in replaced {
+            self.ranges.remove(&end);
+        }
+        self.ranges.insert(l2_end, (l2_start, status));
+    }
+
+    /// Returns the status of the soft confirmation at `l2_height`, if cached.
+    pub(crate) fn get(&self, l2_height: u64) -> Option<SoftConfirmationStatus> {
+        let (_, (l2_start, status)) = self.ranges.range(l2_height..).next()?;
+        (*l2_start <= l2_height).then(|| status.clone())
+    }
+
+    /// Returns the last L2 height finalized by a sequencer commitment, proven ones included.
+    pub(crate) fn last_finalized(&self) -> Option<u64> {
+        self.last_where(|status| *status != SoftConfirmationStatus::Trusted)
+    }
+
+    /// Returns the last proven L2 height.
+    pub(crate) fn last_proven(&self) -> Option<u64> {
+        self.last_where(|status| *status == SoftConfirmationStatus::Proven)
+    }
+
+    fn last_where(&self, predicate: impl Fn(&SoftConfirmationStatus) -> bool) -> Option<u64> {
+        self.ranges
+            .iter()
+            .rev()
+            .find(|(_, (_, status))| predicate(status))
+            .map(|(l2_end, _)| *l2_end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn status_cache_lookups() {
+        let mut cache = StatusCache::default();
+        cache.insert(1, 3, SoftConfirmationStatus::Finalized);
+        cache.insert(6, 6, SoftConfirmationStatus::Finalized);
+
+        assert_eq!(cache.get(0), None);
+        assert_eq!(cache.get(2), Some(SoftConfirmationStatus::Finalized));
+        assert_eq!(cache.get(4), None);
+        assert_eq!(cache.get(6), Some(SoftConfirmationStatus::Finalized));
+        assert_eq!(cache.get(7), None);
+
+        // the range of a DA slot grew before it was proven
+        cache.insert(1, 4, SoftConfirmationStatus::Proven);
+        assert_eq!(cache.get(3), Some(SoftConfirmationStatus::Proven));
+        assert_eq!(cache.get(4), Some(SoftConfirmationStatus::Proven));
+        assert_eq!(cache.get(6), Some(SoftConfirmationStatus::Finalized));
+    }
+
+    #[test]
+    fn last_finalized_and_proven_heights() {
+        let mut cache = StatusCache::default();
+        assert_eq!(cache.last_finalized(), None);
+        assert_eq!(cache.last_proven(), None);
+
+        cache.insert(1, 3, SoftConfirmationStatus::Finalized);
+        cache.insert(4, 5, SoftConfirmationStatus::Finalized);
+        assert_eq!(cache.last_finalized(), Some(5));
+        assert_eq!(cache.last_proven(), None);
+
+        cache.insert(1, 3, SoftConfirmationStatus::Proven);
+        assert_eq!(cache.last_finalized(), Some(5));
+        assert_eq!(cache.last_proven(), Some(3));
+    }
+}
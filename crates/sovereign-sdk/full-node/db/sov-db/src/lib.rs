@@ -11,6 +11,7 @@
 /// This wrapper implements helper traits for writing blocks to the ledger, and for
 /// serving historical data via RPC
 pub mod ledger_db;
+mod metrics;
 /// Implements helpers for configuring RocksDB.
 pub mod rocks_db_config;
 /// Defines the tables used by the Sovereign SDK.
@@ -0,0 +1,15 @@
+use once_cell::sync::Lazy;
+use prometheus::{exponential_buckets, register_histogram_vec, HistogramVec};
+
+pub static SOFT_CONFIRMATION_LATENCY_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        // metric name
+        "soft_confirmation_latency_seconds",
+        // metric description
+        "Time from the commitment of soft confirmations by the node to their finalization on DA and their proof, in seconds",
+        // metric labels (dimensions)
+        &["stage"],
+        exponential_buckets(/*start=*/ 1.0, /*factor=*/ 2.0, /*count=*/ 18).unwrap(),
+    )
+    .unwrap()
+});
@@ -58,6 +58,18 @@ pub const LEDGER_TABLES: &[&str] = &[
     BatchByHash::table_name(),
     BatchByNumber::table_name(),
     SoftConfirmationStatus::table_name(),
+    ProofDetailsBySlotHash::table_name(),
+    QuarantinedProofBySlotHash::table_name(),
+    SequencerDaActivity::table_name(),
+    ProvenRangeByL2End::table_name(),
+    L1FeeRateByL1Height::table_name(),
+    RelayedTxByHash::table_name(),
+    TxHashByAddress::table_name(),
+    AddressIndexHead::table_name(),
+    TxSubmittedAt::table_name(),
+    SoftBatchCommittedAt::table_name(),
+    StatusTimestampsByL1Height::table_name(),
+    LightNodeHead::table_name(),
     TxByHash::table_name(),
     TxByNumber::table_name(),
     EventByKey::table_name(),
@@ -253,6 +265,70 @@ define_table_with_default_codec!(
     (SoftConfirmationStatus) SlotNumber => sov_rollup_interface::rpc::SoftConfirmationStatus
 );
 
+define_table_with_default_codec!(
+    /// Details of the prover's session for a DA slot
+    (ProofDetailsBySlotHash) DbHash => sov_rollup_interface::rpc::ProofDetails
+);
+
+define_table_with_default_codec!(
+    /// Proofs of the prover that failed the self-check before submission, kept for investigation
+    (QuarantinedProofBySlotHash) DbHash => Vec<u8>
+);
+
+define_table_with_seek_key_codec!(
+    /// Full node uses this table to store the DA activity of the sequencer and the prover
+    (SequencerDaActivity) () => sov_rollup_interface::rpc::SequencerDaActivity
+);
+
+define_table_with_seek_key_codec!(
+    /// Full node uses this table to store the L2 ranges covered by ZK proofs, by last L2 height
+    (ProvenRangeByL2End) BatchNumber => sov_rollup_interface::rpc::ProvenRange
+);
+
+define_table_with_seek_key_codec!(
+    /// The L1 fee rates of the soft confirmations on each DA slot
+    (L1FeeRateByL1Height) SlotNumber => sov_rollup_interface::rpc::L1FeeRateSnapshot
+);
+
+define_table_with_default_codec!(
+    /// Full node uses this table to journal the raw transactions it relayed to the sequencer,
+    /// until they are included in a soft batch
+    (RelayedTxByHash) DbHash => Vec<u8>
+);
+
+define_table_with_seek_key_codec!(
+    /// Optional index of the EVM transactions sent by or to an address,
+    /// by address, L2 block number and index in the block
+    (TxHashByAddress) ([u8; 20], u64, u32) => DbHash
+);
+
+define_table_with_seek_key_codec!(
+    /// The last L2 block indexed in [`TxHashByAddress`]
+    (AddressIndexHead) () => BatchNumber
+);
+
+define_table_with_default_codec!(
+    /// Unix time in milliseconds at which the transactions submitted to the node were received,
+    /// by the hash of the EVM transaction
+    (TxSubmittedAt) DbHash => u64
+);
+
+define_table_with_seek_key_codec!(
+    /// Unix time in milliseconds at which the node committed each soft batch
+    (SoftBatchCommittedAt) BatchNumber => u64
+);
+
+define_table_with_default_codec!(
+    /// Unix times in milliseconds at which the soft confirmations of each DA slot were
+    /// finalized and proven
+    (StatusTimestampsByL1Height) SlotNumber => super::types::StatusTimestamps
+);
+
+define_table_with_seek_key_codec!(
+    /// Light node uses this table to store how far it followed the proven state
+    (LightNodeHead) () => super::types::StoredLightNodeHead
+);
+
 define_table_with_default_codec!(
     /// A "secondary index" for batch data by hash
     (BatchByHash) DbHash => BatchNumber
@@ -102,6 +102,44 @@ pub struct StoredSoftBatch {
 /// (start, end) inclusive
 pub type L2HeightRange = (BatchNumber, BatchNumber);
 
+/// Unix times in milliseconds at which the node saw the soft confirmations of a DA slot
+/// finalized by a verified sequencer commitment and proven.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
+pub struct StatusTimestamps {
+    /// When the soft confirmations were finalized, also set when they were proven first.
+    pub finalized_at: Option<u64>,
+    /// When the soft confirmations were proven.
+    pub proven_at: Option<u64>,
+}
+
+/// How far a light node followed the state proven by the ZK proofs posted to DA.
+#[derive(Debug, Clone, PartialEq, Eq, BorshDeserialize, BorshSerialize)]
+pub struct StoredLightNodeHead {
+    /// Height of the next DA slot to scan.
+    pub next_l1_height: u64,
+    /// First L2 height not covered by the applied proofs.
+    pub next_l2_height: u64,
+    /// First L2 height not covered by the verified sequencer commitments.
+    pub next_commitment_l2_height: u64,
+    /// The proven state root at `next_l2_height`, serialized as JSON.
+    pub state_root: Vec<u8>,
+}
+
+/// Unix times in milliseconds at which a soft confirmation reached each stage of its
+/// lifecycle on the node. Stages the soft confirmation didn't reach yet are not set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SoftBatchTimeline {
+    /// Height of the DA slot of the soft confirmation.
+    pub da_slot_height: u64,
+    /// When the node committed the soft confirmation. Not set for the soft confirmations
+    /// committed by versions of the node not recording it.
+    pub committed_at: Option<u64>,
+    /// When the node saw the soft confirmation finalized by a verified sequencer commitment.
+    pub finalized_at: Option<u64>,
+    /// When the node saw the soft confirmation proven.
+    pub proven_at: Option<u64>,
+}
+
 impl TryFrom<StoredSoftBatch> for SoftBatchResponse {
     type Error = anyhow::Error;
     fn try_from(value: StoredSoftBatch) -> Result<Self, Self::Error> {
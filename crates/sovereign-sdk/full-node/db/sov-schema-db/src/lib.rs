@@ -28,12 +28,11 @@ use anyhow::format_err;
 use iterator::ScanDirection;
 pub use iterator::{RawDbReverseIterator, SchemaIterator, SeekKeyEncoder};
 use metrics::{
-    SCHEMADB_BATCH_COMMIT_BYTES, SCHEMADB_BATCH_COMMIT_LATENCY_SECONDS, SCHEMADB_DELETES,
-    SCHEMADB_GET_BYTES, SCHEMADB_GET_LATENCY_SECONDS, SCHEMADB_PUT_BYTES,
+    SCHEMADB_BATCH_COMMIT_BYTES, SCHEMADB_BATCH_COMMIT_LATENCY_SECONDS, SCHEMADB_CF_SIZE_BYTES,
+    SCHEMADB_DELETES, SCHEMADB_GET_BYTES, SCHEMADB_GET_LATENCY_SECONDS, SCHEMADB_PUT_BYTES,
 };
-pub use rocksdb;
 use rocksdb::ReadOptions;
-pub use rocksdb::DEFAULT_COLUMN_FAMILY_NAME;
+pub use rocksdb::{self, DEFAULT_COLUMN_FAMILY_NAME};
 use thiserror::Error;
 use tracing::info;
 
@@ -212,6 +211,14 @@ impl DB {
         self.iter_with_direction::<S>(opts, ScanDirection::Forward)
     }
 
+    /// Returns a [`ReadSnapshot`] of the current state of the DB.
+    pub fn read_snapshot(&self) -> ReadSnapshot<'_> {
+        ReadSnapshot {
+            db: self,
+            inner: self.inner.snapshot(),
+        }
+    }
+
     /// Writes a group of records wrapped in a [`SchemaBatch`].
     pub fn write_schemas(&self, batch: SchemaBatch) -> anyhow::Result<()> {
         let _timer = SCHEMADB_BATCH_COMMIT_LATENCY_SECONDS
@@ -282,6 +289,17 @@ impl DB {
             })
     }
 
+    /// Returns the size of a column family in bytes, its SST files and memtables,
+    /// and records it in the `schemadb_cf_size_bytes` metric.
+    pub fn report_cf_size(&self, cf_name: &str) -> anyhow::Result<u64> {
+        let size = self.get_property(cf_name, "rocksdb.total-sst-files-size")?
+            + self.get_property(cf_name, "rocksdb.size-all-mem-tables")?;
+        SCHEMADB_CF_SIZE_BYTES
+            .with_label_values(&[self.name, cf_name])
+            .set(size as i64);
+        Ok(size)
+    }
+
     /// Creates new physical DB checkpoint in directory specified by `path`.
     pub fn create_checkpoint<P: AsRef<Path>>(&self, path: P) -> anyhow::Result<()> {
         rocksdb::checkpoint::Checkpoint::new(&self.inner)?.create_checkpoint(path)?;
@@ -289,6 +307,51 @@ impl DB {
     }
 }
 
+/// A point-in-time view of a [`DB`]: reads through it don't observe the writes made after it was
+/// taken, and compactions keep the data it references until it is dropped.
+///
+/// Unlike an iterator, a snapshot doesn't pin memtables and SST files, so long scans can read
+/// through short-lived iterators created from the same snapshot.
+pub struct ReadSnapshot<'a> {
+    db: &'a DB,
+    inner: rocksdb::Snapshot<'a>,
+}
+
+impl<'a> ReadSnapshot<'a> {
+    /// Reads single record by key.
+    pub fn get<S: Schema>(
+        &self,
+        schema_key: &impl KeyCodec<S>,
+    ) -> anyhow::Result<Option<S::Value>> {
+        let k = schema_key.encode_key()?;
+        let cf_handle = self.db.get_cf_handle(S::COLUMN_FAMILY_NAME)?;
+
+        self.inner
+            .get_cf_opt(cf_handle, k, ReadOptions::default())?
+            .map(|raw_value| <S::Value as ValueCodec<S>>::decode_value(&raw_value))
+            .transpose()
+            .map_err(|err| err.into())
+    }
+
+    /// Returns a forward [`SchemaIterator`] on a certain schema.
+    pub fn iter<S: Schema>(&self) -> anyhow::Result<SchemaIterator<S>> {
+        let cf_handle = self.db.get_cf_handle(S::COLUMN_FAMILY_NAME)?;
+        Ok(SchemaIterator::new(
+            self.inner
+                .raw_iterator_cf_opt(cf_handle, ReadOptions::default()),
+            ScanDirection::Forward,
+        ))
+    }
+}
+
+impl<'a> std::fmt::Debug for ReadSnapshot<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReadSnapshot")
+            .field("db", &self.db.name)
+            .finish()
+    }
+}
+
 /// Readability alias for a key in the DB.
 pub type SchemaKey = Vec<u8>;
 /// Readability alias for a value in the DB.
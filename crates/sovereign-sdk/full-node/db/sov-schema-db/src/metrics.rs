@@ -3,8 +3,8 @@
 
 use once_cell::sync::Lazy;
 use prometheus::{
-    exponential_buckets, register_histogram_vec, register_int_counter_vec, HistogramVec,
-    IntCounterVec,
+    exponential_buckets, register_histogram_vec, register_int_counter_vec, register_int_gauge_vec,
+    HistogramVec, IntCounterVec, IntGaugeVec,
 };
 
 pub static SCHEMADB_ITER_LATENCY_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
@@ -110,3 +110,15 @@ pub static SCHEMADB_BATCH_PUT_LATENCY_SECONDS: Lazy<HistogramVec> = Lazy::new(||
     )
     .unwrap()
 });
+
+pub static SCHEMADB_CF_SIZE_BYTES: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        // metric name
+        "schemadb_cf_size_bytes",
+        // metric description
+        "Schemadb column family size in bytes, SST files and memtables",
+        // metric labels (dimensions)
+        &["db_name", "cf_name"]
+    )
+    .unwrap()
+});
@@ -315,6 +315,26 @@ fn test_report_size() {
     );
 }
 
+#[test]
+fn test_report_cf_size() {
+    let db = TestDB::new();
+
+    for i in 0..1000 {
+        db.put::<TestSchema1>(&TestField(i), &TestField(i)).unwrap();
+    }
+    // Not flushed yet, the data is in the memtable
+    assert!(db.report_cf_size("TestCF1").unwrap() > 0);
+
+    db.flush_cf("TestCF1").unwrap();
+    assert!(
+        db.get_property("TestCF1", "rocksdb.total-sst-files-size")
+            .unwrap()
+            > 0
+    );
+    assert!(db.report_cf_size("TestCF1").unwrap() > 0);
+    assert!(db.report_cf_size("UnknownCF").is_err());
+}
+
 #[test]
 fn test_checkpoint() {
     let tmpdir = tempfile::tempdir().unwrap();
@@ -345,3 +365,35 @@ fn test_checkpoint() {
         assert_eq!(db.get::<TestSchema1>(&TestField(1)).unwrap(), None);
     }
 }
+
+#[test]
+fn test_read_snapshot() {
+    let db = TestDB::new();
+    db.put::<TestSchema1>(&TestField(0), &TestField(0)).unwrap();
+    db.put::<TestSchema1>(&TestField(1), &TestField(1)).unwrap();
+
+    let snapshot = db.read_snapshot();
+    db.put::<TestSchema1>(&TestField(1), &TestField(10))
+        .unwrap();
+    db.put::<TestSchema1>(&TestField(2), &TestField(2)).unwrap();
+    db.flush_cf("TestCF1").unwrap();
+
+    assert_eq!(
+        snapshot.get::<TestSchema1>(&TestField(1)).unwrap(),
+        Some(TestField(1)),
+    );
+    assert_eq!(snapshot.get::<TestSchema1>(&TestField(2)).unwrap(), None);
+
+    let mut iter = snapshot.iter::<TestSchema1>().unwrap();
+    iter.seek_to_first();
+    let values = iter
+        .map(|res| res.map(|item| item.into_tuple()))
+        .collect::<Result<Vec<_>, anyhow::Error>>()
+        .unwrap();
+    assert_eq!(values, gen_expected_values(&[(0, 0), (1, 1)]));
+
+    assert_eq!(
+        db.get::<TestSchema1>(&TestField(1)).unwrap(),
+        Some(TestField(10)),
+    );
+}
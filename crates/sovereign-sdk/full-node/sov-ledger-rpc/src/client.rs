@@ -146,6 +146,15 @@ where
         soft_batch_receipt: u64,
     ) -> RpcResult<SoftConfirmationStatus>;
 
+    /// Gets the L2 heights in `start..=end` of the soft batches with the given status.
+    #[method(name = "getSoftBatchesByStatus")]
+    async fn get_soft_batches_by_status(
+        &self,
+        status: SoftConfirmationStatus,
+        start: u64,
+        end: u64,
+    ) -> RpcResult<Vec<u64>>;
+
     /// Subscription method to receive a notification each time a slot is
     /// processed.
     #[subscription(name = "subscribeSlots", item = u64)]
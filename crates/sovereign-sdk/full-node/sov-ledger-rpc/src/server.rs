@@ -6,7 +6,8 @@ use jsonrpsee::{RpcModule, SubscriptionMessage};
 use serde::de::DeserializeOwned;
 use sov_modules_api::utils::to_jsonrpsee_error_object;
 use sov_rollup_interface::rpc::{
-    BatchIdentifier, EventIdentifier, LedgerRpcProvider, QueryMode, SlotIdentifier, TxIdentifier,
+    BatchIdentifier, EventIdentifier, LedgerRpcProvider, QueryMode, SlotIdentifier,
+    SoftConfirmationStatus, TxIdentifier,
 };
 
 use crate::HexHash;
@@ -147,7 +148,8 @@ where
             .get_batches_range::<B, Tx>(args.0, args.1, args.2)
             .map_err(|e| to_jsonrpsee_error_object(e, LEDGER_RPC_ERROR))
     })?;
-    rpc.register_method("ledger_getSoftBatchRange", move |params, ledger| {
+    // Served on the blocking pool, so that reading a large range doesn't stall the runtime
+    rpc.register_blocking_method("ledger_getSoftBatchRange", move |params, ledger| {
         let args: (u64, u64) = params.parse()?;
         ledger
             .get_soft_batches_range(args.0, args.1)
@@ -165,6 +167,36 @@ where
             .get_soft_confirmation_status(args.0)
             .map_err(|e| to_jsonrpsee_error_object(e, LEDGER_RPC_ERROR))
     })?;
+    rpc.register_method("ledger_getSoftBatchesByStatus", move |params, ledger| {
+        let args: (SoftConfirmationStatus, u64, u64) = params.parse()?;
+        ledger
+            .get_soft_batch_heights_by_status(args.0, args.1, args.2)
+            .map_err(|e| to_jsonrpsee_error_object(e, LEDGER_RPC_ERROR))
+    })?;
+    rpc.register_method("citrea_getSequencerDaActivity", move |_, ledger| {
+        ledger
+            .get_sequencer_da_activity()
+            .map_err(|e| to_jsonrpsee_error_object(e, LEDGER_RPC_ERROR))
+    })?;
+
+    rpc.register_method("citrea_getLatestProvenHeight", move |_, ledger| {
+        ledger
+            .get_latest_proven_height()
+            .map_err(|e| to_jsonrpsee_error_object(e, LEDGER_RPC_ERROR))
+    })?;
+    rpc.register_method("citrea_getProofCoverage", move |params, ledger| {
+        let args: (u64, u64) = params.parse()?;
+        ledger
+            .get_proof_coverage(args.0, args.1)
+            .map_err(|e| to_jsonrpsee_error_object(e, LEDGER_RPC_ERROR))
+    })?;
+
+    rpc.register_method("citrea_getL1FeeHistory", move |params, ledger| {
+        let args: (u64, u64) = params.parse()?;
+        ledger
+            .get_l1_fee_history(args.0, args.1)
+            .map_err(|e| to_jsonrpsee_error_object(e, LEDGER_RPC_ERROR))
+    })?;
 
     rpc.register_subscription(
         "ledger_subscribeSlots",
@@ -205,6 +237,24 @@ where
     Ok(rpc)
 }
 
+/// Creates a new [`jsonrpsee::RpcModule`] exposing the JSON-RPC methods of the
+/// [`LedgerRpcProvider`] that only provers serve, about their proving sessions.
+pub fn prover_rpc_module<T>(ledger: T) -> anyhow::Result<RpcModule<T>>
+where
+    T: LedgerRpcProvider + Send + Sync + 'static,
+{
+    let mut rpc = RpcModule::new(ledger);
+
+    rpc.register_method("prover_getProofDetails", move |params, ledger| {
+        let args: QueryArgs<HexHash> = extract_query_args(params)?;
+        ledger
+            .get_proof_details(&args.0 .0)
+            .map_err(|e| to_jsonrpsee_error_object(e, LEDGER_RPC_ERROR))
+    })?;
+
+    Ok(rpc)
+}
+
 #[derive(serde::Deserialize)]
 struct RangeArgs(u64, u64, #[serde(default)] QueryMode);
 
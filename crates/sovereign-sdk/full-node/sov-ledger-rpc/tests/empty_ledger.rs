@@ -5,19 +5,26 @@ use jsonrpsee::core::client::{ClientT, SubscriptionClientT};
 use jsonrpsee::core::params::ArrayParams;
 use sov_db::ledger_db::LedgerDB;
 use sov_ledger_rpc::client::RpcClient;
-use sov_ledger_rpc::server::rpc_module;
+use sov_ledger_rpc::server::{prover_rpc_module, rpc_module};
 use sov_ledger_rpc::HexHash;
 use sov_modules_api::Event;
 use sov_rollup_interface::rpc::{
-    BatchResponse, EventIdentifier, QueryMode, SlotResponse, TxIdAndOffset, TxIdentifier,
-    TxResponse,
+    BatchResponse, EventIdentifier, L1FeeRateSnapshot, ProofCoverage, ProofDetails, QueryMode,
+    SequencerDaActivity, SlotResponse, TxIdAndOffset, TxIdentifier, TxResponse,
 };
 use tempfile::tempdir;
 
 async fn rpc_server() -> (jsonrpsee::server::ServerHandle, SocketAddr) {
+    rpc_server_of(false).await
+}
+
+async fn rpc_server_of(prover: bool) -> (jsonrpsee::server::ServerHandle, SocketAddr) {
     let dir = tempdir().unwrap();
     let db = LedgerDB::with_path(dir).unwrap();
-    let rpc_module = rpc_module::<LedgerDB, u32, u32>(db).unwrap();
+    let mut rpc_module = rpc_module::<LedgerDB, u32, u32>(db.clone()).unwrap();
+    if prover {
+        rpc_module.merge(prover_rpc_module(db).unwrap()).unwrap();
+    }
 
     let server = jsonrpsee::server::ServerBuilder::default()
         .build("127.0.0.1:0")
@@ -173,3 +180,73 @@ async fn get_events_patterns() {
         .await
         .unwrap();
 }
+
+#[tokio::test]
+async fn get_proof_details_of_unknown_slot() {
+    let (_server_handle, addr) = rpc_server_of(true).await;
+    let rpc_client = rpc_client(addr).await;
+
+    let details = rpc_client
+        .request::<Option<ProofDetails>, _>("prover_getProofDetails", vec![HexHash([0; 32])])
+        .await
+        .unwrap();
+    assert_eq!(details, None);
+}
+
+#[tokio::test]
+async fn proof_details_are_only_served_by_provers() {
+    let (_server_handle, addr) = rpc_server().await;
+    let rpc_client = rpc_client(addr).await;
+
+    rpc_client
+        .request::<Option<ProofDetails>, _>("prover_getProofDetails", vec![HexHash([0; 32])])
+        .await
+        .unwrap_err();
+}
+
+#[tokio::test]
+async fn get_sequencer_da_activity_before_scanning() {
+    let (_server_handle, addr) = rpc_server().await;
+    let rpc_client = rpc_client(addr).await;
+
+    let activity = rpc_client
+        .request::<SequencerDaActivity, _>("citrea_getSequencerDaActivity", ArrayParams::new())
+        .await
+        .unwrap();
+    assert_eq!(activity, SequencerDaActivity::default());
+}
+
+#[tokio::test]
+async fn get_proof_coverage_before_any_proof() {
+    let (_server_handle, addr) = rpc_server().await;
+    let rpc_client = rpc_client(addr).await;
+
+    let height = rpc_client
+        .request::<Option<u64>, _>("citrea_getLatestProvenHeight", ArrayParams::new())
+        .await
+        .unwrap();
+    assert_eq!(height, None);
+
+    let coverage = rpc_client
+        .request::<ProofCoverage, _>("citrea_getProofCoverage", vec![0, 100])
+        .await
+        .unwrap();
+    assert_eq!(coverage, ProofCoverage::default());
+}
+
+#[tokio::test]
+async fn get_l1_fee_history_before_any_soft_batch() {
+    let (_server_handle, addr) = rpc_server().await;
+    let rpc_client = rpc_client(addr).await;
+
+    let history = rpc_client
+        .request::<Vec<L1FeeRateSnapshot>, _>("citrea_getL1FeeHistory", vec![0, 100])
+        .await
+        .unwrap();
+    assert!(history.is_empty());
+
+    assert!(rpc_client
+        .request::<Vec<L1FeeRateSnapshot>, _>("citrea_getL1FeeHistory", vec![0, 1000])
+        .await
+        .is_err());
+}
@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use hyper::body::HttpBody;
+use hyper::header::{HeaderValue, CONTENT_TYPE};
+use hyper::{Body, Method, Request, Response, StatusCode};
+use jsonrpsee::server::BatchRequestConfig;
+use tower::{Layer, Service};
+
+use crate::RpcConfig;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// Error code of rejected batches, the one the RPC server uses for batches with too many requests.
+pub const TOO_BIG_BATCH_CODE: i32 = -32010;
+
+/// Requests larger than the default max request body size of the RPC server
+/// are rejected without being read further.
+const MAX_INSPECTED_BODY_SIZE: usize = 10 * 1024 * 1024;
+
+/// Weights of the methods that are expensive to serve, overridden by `method_weights` in the
+/// RPC config. Other methods weigh 1.
+pub const DEFAULT_METHOD_WEIGHTS: &[(&str, u64)] = &[
+    ("eth_call", 10),
+    ("eth_estimateGas", 10),
+    ("eth_createAccessList", 10),
+    ("eth_getLogs", 20),
+    ("eth_simulateV1", 50),
+    ("debug_traceCall", 50),
+    ("debug_traceTransaction", 50),
+    ("debug_traceBlockByHash", 100),
+    ("debug_traceBlockByNumber", 100),
+    ("trace_filter", 100),
+    ("citrea_getStorageHistory", 50),
+];
+
+/// Weights of the methods called in JSON-RPC batches.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MethodWeights {
+    weights: HashMap<String, u64>,
+}
+
+impl MethodWeights {
+    /// Creates weights from [`DEFAULT_METHOD_WEIGHTS`] with `overrides` applied.
+    pub fn new(overrides: &HashMap<String, u64>) -> Self {
+        let mut weights: HashMap<String, u64> = DEFAULT_METHOD_WEIGHTS
+            .iter()
+            .map(|(method, weight)| (method.to_string(), *weight))
+            .collect();
+        weights.extend(
+            overrides
+                .iter()
+                .map(|(method, weight)| (method.clone(), *weight)),
+        );
+        Self { weights }
+    }
+
+    /// Returns the weight of a method.
+    pub fn weight(&self, method: &str) -> u64 {
+        self.weights.get(method).copied().unwrap_or(1)
+    }
+
+    /// Returns the total weight of the calls of a batch request, or `None` if the body is not
+    /// a batch. Calls without a method weigh 1.
+    fn batch_weight(&self, body: &[u8]) -> Option<u64> {
+        let Ok(serde_json::Value::Array(calls)) = serde_json::from_slice(body) else {
+            return None;
+        };
+        Some(
+            calls
+                .iter()
+                .map(|call| {
+                    call.get("method")
+                        .and_then(|method| method.as_str())
+                        .map_or(1, |method| self.weight(method))
+                })
+                .fold(0u64, |total, weight| total.saturating_add(weight)),
+        )
+    }
+}
+
+/// Returns the batch request config of the RPC server, limiting the number of requests
+/// of a batch to `batch_requests_limit`.
+pub fn batch_request_config(rpc_config: &RpcConfig) -> BatchRequestConfig {
+    match rpc_config.batch_requests_limit {
+        0 => BatchRequestConfig::Disabled,
+        limit => BatchRequestConfig::Limit(limit),
+    }
+}
+
+/// A [`Layer`] rejecting HTTP batch requests whose calls weigh more than `batch_weight_limit`,
+/// so that a single batch of expensive calls can't starve the node.
+///
+/// The number of requests of a batch is limited by the RPC server itself, over HTTP and
+/// WebSocket, while weights are only checked for HTTP requests.
+#[derive(Debug, Clone)]
+pub struct BatchLimitLayer {
+    weights: Arc<MethodWeights>,
+    weight_limit: u64,
+}
+
+impl BatchLimitLayer {
+    /// Creates a layer with the batch weight limit and method weights of the RPC config.
+    pub fn new(rpc_config: &RpcConfig) -> Self {
+        Self {
+            weights: Arc::new(MethodWeights::new(&rpc_config.method_weights)),
+            weight_limit: rpc_config.batch_weight_limit,
+        }
+    }
+}
+
+impl<S> Layer<S> for BatchLimitLayer {
+    type Service = BatchLimit<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        BatchLimit {
+            inner,
+            weights: self.weights.clone(),
+            weight_limit: self.weight_limit,
+        }
+    }
+}
+
+/// The service created by [`BatchLimitLayer`].
+#[derive(Debug, Clone)]
+pub struct BatchLimit<S> {
+    inner: S,
+    weights: Arc<MethodWeights>,
+    weight_limit: u64,
+}
+
+impl<S> Service<Request<Body>> for BatchLimit<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>, Error = BoxError> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        // The inner service was driven to readiness, so it is the one to be called
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let weights = self.weights.clone();
+        let weight_limit = self.weight_limit;
+
+        Box::pin(async move {
+            if request.method() != Method::POST {
+                return inner.call(request).await;
+            }
+
+            let (parts, mut body) = request.into_parts();
+            let mut bytes = vec![];
+            while let Some(chunk) = body.data().await {
+                bytes.extend_from_slice(&chunk?);
+                if bytes.len() > MAX_INSPECTED_BODY_SIZE {
+                    return Ok(error_response(
+                        StatusCode::PAYLOAD_TOO_LARGE,
+                        "request body too large",
+                    ));
+                }
+            }
+
+            if let Some(weight) = weights.batch_weight(&bytes) {
+                if weight > weight_limit {
+                    tracing::debug!(
+                        "Rejecting batch request of weight {} over the limit {}",
+                        weight,
+                        weight_limit
+                    );
+                    return Ok(error_response(
+                        StatusCode::OK,
+                        &format!(
+                            "batch request weight {} exceeds the limit {}",
+                            weight, weight_limit
+                        ),
+                    ));
+                }
+            }
+
+            inner
+                .call(Request::from_parts(parts, Body::from(bytes)))
+                .await
+        })
+    }
+}
+
+fn error_response(status: StatusCode, message: &str) -> Response<Body> {
+    let body = serde_json::json!({
+        "jsonrpc": "2.0",
+        "id": null,
+        "error": { "code": TOO_BIG_BATCH_CODE, "message": message },
+    });
+    let mut response = Response::new(Body::from(body.to_string()));
+    *response.status_mut() = status;
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn weighs_batches() {
+        let weights = MethodWeights::new(&HashMap::from([
+            ("eth_call".to_string(), 5),
+            ("eth_getBalance".to_string(), 2),
+        ]));
+        assert_eq!(weights.weight("eth_call"), 5);
+        assert_eq!(weights.weight("eth_getBalance"), 2);
+        assert_eq!(weights.weight("debug_traceTransaction"), 50);
+        assert_eq!(weights.weight("eth_blockNumber"), 1);
+
+        assert_eq!(
+            weights.batch_weight(br#"{"jsonrpc":"2.0","id":1,"method":"eth_call","params":[]}"#),
+            None
+        );
+        assert_eq!(
+            weights.batch_weight(
+                br#"[{"jsonrpc":"2.0","id":1,"method":"eth_call","params":[]},
+                    {"jsonrpc":"2.0","id":2,"method":"eth_blockNumber","params":[]},
+                    {"jsonrpc":"2.0","id":3,"method":"debug_traceTransaction","params":[]},
+                    {"jsonrpc":"2.0","id":4}]"#
+            ),
+            Some(57)
+        );
+        assert_eq!(weights.batch_weight(b"[]"), Some(0));
+        assert_eq!(weights.batch_weight(b"not json"), None);
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::Read;
 use std::path::{Path, PathBuf};
@@ -25,6 +26,85 @@ pub struct RpcConfig {
     /// if not set defaults to 100.
     #[serde(default = "default_max_connections")]
     pub max_connections: u32,
+    /// Directory of contract ABIs (plain ABIs, compiler artifacts or Sourcify metadata files)
+    /// used to decode custom error reverts in RPC responses.
+    #[serde(default)]
+    pub abi_registry_path: Option<PathBuf>,
+    /// Directory storing the contract metadata and sources verified through `citrea_verifyContract`.
+    /// Contract verification is disabled if not set.
+    #[serde(default)]
+    pub contract_verification_path: Option<PathBuf>,
+    /// Whether responses about blocks addressed by number or hash get an `ETag`, so that
+    /// clients can revalidate them with `If-None-Match`. Responses are never marked cacheable
+    /// by shared HTTP caches. If not set defaults to true.
+    #[serde(default = "default_response_etags")]
+    pub response_etags: bool,
+    /// RPC namespaces (e.g. `eth`) and methods (e.g. `debug_traceTransaction`) served by the node.
+    /// Other methods are not registered. All methods are served if not set.
+    #[serde(default)]
+    pub allowed_methods: Option<Vec<String>>,
+    /// Gas price oracle serving `eth_gasPrice` and `eth_maxPriorityFeePerGas`.
+    /// Default settings are used if not set.
+    #[serde(default)]
+    pub gas_price_oracle: Option<GasPriceOracleRpcConfig>,
+    /// Serves the state at any past block if true. Otherwise only serves the state of the head
+    /// block and the `state_history_blocks` blocks before it. If not set defaults to true.
+    #[serde(default = "default_archive")]
+    pub archive: bool,
+    /// Number of blocks before the head whose state is served when `archive` is false.
+    /// If not set defaults to 128.
+    #[serde(default = "default_state_history_blocks")]
+    pub state_history_blocks: u64,
+    /// Maximum number of requests in a JSON-RPC batch, larger batches are rejected.
+    /// Batches are not served if set to 0. If not set defaults to 100.
+    #[serde(default = "default_batch_requests_limit")]
+    pub batch_requests_limit: u32,
+    /// Maximum total weight of the requests of a JSON-RPC batch sent over HTTP, heavier batches
+    /// are rejected. Each request weighs the weight of its method. If not set defaults to 1000.
+    #[serde(default = "default_batch_weight_limit")]
+    pub batch_weight_limit: u64,
+    /// Weights of methods in JSON-RPC batches, overriding the default weights of the expensive
+    /// methods like `eth_call`. Other methods weigh 1.
+    #[serde(default)]
+    pub method_weights: HashMap<String, u64>,
+    /// Indexes the transactions of every address in the ledger DB, serving
+    /// `citrea_getTransactionsByAddress`. Disabled if not set.
+    #[serde(default)]
+    pub index_transactions_by_address: bool,
+    /// Serves `citrea_pushSoftBatch` on full nodes, which sync the soft batches pushed by the
+    /// sequencer or a relay right away instead of at their next poll. Pushed soft batches must
+    /// be signed by the sequencer. Disabled if not set.
+    #[serde(default)]
+    pub accept_pushed_soft_batches: bool,
+}
+
+/// Gas price oracle configuration.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct GasPriceOracleRpcConfig {
+    /// Number of recent non-empty blocks sampled. If not set defaults to 20.
+    #[serde(default)]
+    pub blocks: Option<u32>,
+    /// Percentile of the sampled tips that is suggested. If not set defaults to 60.
+    #[serde(default)]
+    pub percentile: Option<u32>,
+    /// Tip in wei suggested while there is nothing to sample. If not set defaults to 1 gwei.
+    #[serde(default)]
+    pub default_tip: Option<u64>,
+    /// Highest tip in wei suggested. If not set defaults to 500 gwei.
+    #[serde(default)]
+    pub max_price: Option<u64>,
+    /// Tip in wei under which samples are ignored. If not set defaults to 2 wei.
+    #[serde(default)]
+    pub ignore_price: Option<u64>,
+    /// Also samples the tips of the executable transactions of the sequencer's mempool.
+    /// If not set defaults to true.
+    #[serde(default = "default_sample_mempool")]
+    pub sample_mempool: bool,
+}
+
+#[inline]
+const fn default_sample_mempool() -> bool {
+    true
 }
 
 #[inline]
@@ -32,11 +112,50 @@ const fn default_max_connections() -> u32 {
     100
 }
 
+#[inline]
+const fn default_response_etags() -> bool {
+    true
+}
+
+#[inline]
+const fn default_archive() -> bool {
+    true
+}
+
+#[inline]
+const fn default_state_history_blocks() -> u64 {
+    128
+}
+
+#[inline]
+const fn default_batch_requests_limit() -> u32 {
+    100
+}
+
+#[inline]
+const fn default_batch_weight_limit() -> u64 {
+    1000
+}
+
 /// Simple storage configuration
 #[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct StorageConfig {
     /// Path that can be utilized by concrete implementation
     pub path: PathBuf,
+    /// Free disk space in megabytes under which the sequencer stops accepting transactions
+    /// and full nodes pause syncing, before the databases run out of disk space.
+    /// The watchdog is disabled if not set.
+    #[serde(default)]
+    pub min_free_disk_space_mb: Option<u64>,
+    /// Number of seconds between measurements of the storage size and free disk space.
+    /// If not set defaults to 60.
+    #[serde(default = "default_storage_check_interval_secs")]
+    pub check_interval_secs: u64,
+}
+
+#[inline]
+const fn default_storage_check_interval_secs() -> u64 {
+    60
 }
 
 /// Sequencer RPC configuration.
@@ -44,13 +163,134 @@ pub struct StorageConfig {
 pub struct SequencerClientRpcConfig {
     /// RPC host url (with port, if applicable).
     pub url: String,
+    /// Sync soft batches in compact form, with the transactions forwarded through
+    /// this node sent as hashes. The node at `url` must be a Citrea node serving them.
+    #[serde(default)]
+    pub compact_relay: bool,
+    /// Serve the receipts and logs computed by the sequencer for the soft batches this node
+    /// has not executed yet, until it executes them. Only enable with a trusted sequencer.
+    #[serde(default)]
+    pub stream_receipts: bool,
+    /// Ask the sequencer for the blocks, receipts and block receipts this node has not
+    /// synced yet. Delegated responses are marked with `"source": "sequencer"`.
+    #[serde(default)]
+    pub delegate_unsynced_reads: bool,
 }
 
 /// Prover service configuration.
-#[derive(Debug, Clone, PartialEq, Deserialize, Copy)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct ProverServiceConfig {
     /// The "distance"  measured in the number of blocks between two consecutive aggregated proofs.
     pub aggregated_proof_block_jump: u64,
+    /// Wraps proofs into a SNARK that is cheap to verify on chain before submitting them to DA.
+    /// Wrapping is slower than proving alone. If not set defaults to false.
+    #[serde(default)]
+    pub wrap_proofs: bool,
+    /// Outsources proving to a proof market, proofs are generated locally if not set
+    /// or if the market fails.
+    #[serde(default)]
+    pub proof_market: Option<ProofMarketConfig>,
+    /// Checkpoints local proving and continues it in a fresh process when the host nears
+    /// its memory limit, instead of being OOM-killed.
+    #[serde(default)]
+    pub memory_watchdog: Option<MemoryWatchdogConfig>,
+}
+
+/// Prover memory watchdog configuration.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MemoryWatchdogConfig {
+    /// Directory the proving checkpoints are written to.
+    pub checkpoint_dir: PathBuf,
+    /// Percentage of the host memory above which proving continues in a fresh process.
+    #[serde(default = "default_max_memory_usage_percent")]
+    pub max_memory_usage_percent: u8,
+    /// Binary proving the remaining segments of a checkpoint. Defaults to the
+    /// `prove-checkpoint` binary next to the node binary.
+    #[serde(default)]
+    pub continuation_bin: Option<PathBuf>,
+}
+
+const fn default_max_memory_usage_percent() -> u8 {
+    85
+}
+
+/// Proof market configuration.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ProofMarketConfig {
+    /// Base url of the REST API of the market.
+    pub url: String,
+    /// Key sent in the `x-api-key` header, if the market requires one.
+    #[serde(default)]
+    pub api_key: Option<String>,
+    /// Id of the guest image uploaded to the market, as the market expects it.
+    pub image_id: String,
+    /// Seconds between two status requests of a proving job.
+    #[serde(default = "default_poll_interval_secs")]
+    pub poll_interval_secs: u64,
+    /// Seconds after which a proving job is given up and the proof is generated locally.
+    #[serde(default = "default_job_timeout_secs")]
+    pub job_timeout_secs: u64,
+}
+
+const fn default_poll_interval_secs() -> u64 {
+    10
+}
+
+const fn default_job_timeout_secs() -> u64 {
+    60 * 60
+}
+
+/// Telemetry configuration.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TelemetryConfig {
+    /// Url the reports are posted to.
+    pub endpoint: String,
+    /// Seconds between two reports.
+    #[serde(default = "default_telemetry_interval_secs")]
+    pub interval_secs: u64,
+}
+
+const fn default_telemetry_interval_secs() -> u64 {
+    5 * 60
+}
+
+/// Profiling endpoint configuration.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ProfilingConfig {
+    /// Host the profiling endpoint listens on. It should only be reachable by operators.
+    pub bind_host: String,
+    /// Port the profiling endpoint listens on.
+    pub bind_port: u16,
+    /// Token the requests must send in the `Authorization: Bearer` header, can't be empty.
+    #[serde(deserialize_with = "deserialize_auth_token")]
+    pub auth_token: String,
+    /// Maximum duration of a profile, in seconds.
+    #[serde(default = "default_max_profile_duration_secs")]
+    pub max_duration_secs: u64,
+}
+
+const fn default_max_profile_duration_secs() -> u64 {
+    60
+}
+
+fn deserialize_auth_token<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<String, D::Error> {
+    let token = String::deserialize(deserializer)?;
+    if token.is_empty() {
+        return Err(serde::de::Error::custom(
+            "profiling auth_token can't be empty",
+        ));
+    }
+    Ok(token)
+}
+
+/// Light node configuration.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct LightNodeConfig {
+    /// Commitment to the guest program the ZK proofs are verified against,
+    /// in the serde format of the code commitment of the zkVM.
+    pub code_commitment: serde_json::Value,
 }
 
 /// Rollup Configuration
@@ -80,6 +320,23 @@ pub struct RollupConfig<DaServiceConfig> {
     pub prover_service: ProverServiceConfig,
     /// Saves sequencer soft batches if set to true
     pub include_tx_body: bool,
+    /// Reports the sync state of the node to a telemetry endpoint.
+    /// Telemetry is opt-in, no reports are sent if not set.
+    #[serde(default)]
+    pub telemetry: Option<TelemetryConfig>,
+    /// Serves CPU profiles of the node on demand.
+    /// The profiling endpoint is disabled if not set.
+    #[serde(default)]
+    pub profiling: Option<ProfilingConfig>,
+    /// Runs the full node as a light node, which follows the state proven by the ZK proofs
+    /// posted to DA instead of executing the soft confirmations. Disabled if not set.
+    #[serde(default)]
+    pub light_node: Option<LightNodeConfig>,
+    /// Commitment to the guest program the ZK proofs posted to DA are verified against,
+    /// in the serde format of the code commitment of the zkVM. Only the L2 ranges of verified
+    /// proofs are recorded as proven, none are if not set.
+    #[serde(default)]
+    pub proof_code_commitment: Option<serde_json::Value>,
 }
 
 /// Reads toml file as a specific type.
@@ -123,16 +380,29 @@ mod tests {
             sender_address = "0000000000000000000000000000000000000000000000000000000000000000"
             [storage]
             path = "/tmp"
+            min_free_disk_space_mb = 1024
             [runner]
             start_height = 31337
             [runner.rpc_config]
             bind_host = "127.0.0.1"
             bind_port = 12345
             max_connections = 500
+            allowed_methods = ["eth", "ledger", "debug_traceTransaction"]
+            archive = false
+            batch_requests_limit = 50
+            index_transactions_by_address = true
+            [runner.rpc_config.method_weights]
+            eth_call = 20
+            [runner.rpc_config.gas_price_oracle]
+            percentile = 50
+            default_tip = 100
             [sequencer_client]
             url = "http://0.0.0.0:12346"
+            compact_relay = true
+            delegate_unsynced_reads = true
             [prover_service]
             aggregated_proof_block_jump = 22
+            wrap_proofs = true
         "#;
 
         let config_file = create_config_from(config);
@@ -147,6 +417,29 @@ mod tests {
                     bind_host: "127.0.0.1".to_string(),
                     bind_port: 12345,
                     max_connections: 500,
+                    abi_registry_path: None,
+                    contract_verification_path: None,
+                    response_etags: true,
+                    allowed_methods: Some(vec![
+                        "eth".to_string(),
+                        "ledger".to_string(),
+                        "debug_traceTransaction".to_string(),
+                    ]),
+                    gas_price_oracle: Some(GasPriceOracleRpcConfig {
+                        blocks: None,
+                        percentile: Some(50),
+                        default_tip: Some(100),
+                        max_price: None,
+                        ignore_price: None,
+                        sample_mempool: true,
+                    }),
+                    archive: false,
+                    state_history_blocks: 128,
+                    batch_requests_limit: 50,
+                    batch_weight_limit: 1000,
+                    method_weights: HashMap::from([("eth_call".to_string(), 20)]),
+                    index_transactions_by_address: true,
+                    accept_pushed_soft_batches: false,
                 },
             },
 
@@ -155,17 +448,180 @@ mod tests {
             },
             storage: StorageConfig {
                 path: PathBuf::from("/tmp"),
+                min_free_disk_space_mb: Some(1024),
+                check_interval_secs: 60,
             },
             sequencer_client: Some(SequencerClientRpcConfig {
                 url: "http://0.0.0.0:12346".to_owned(),
+                compact_relay: true,
+                stream_receipts: false,
+                delegate_unsynced_reads: true,
             }),
             prover_service: ProverServiceConfig {
                 aggregated_proof_block_jump: 22,
+                wrap_proofs: true,
+                proof_market: None,
+                memory_watchdog: None,
             },
             sequencer_da_pub_key: vec![119; 32],
             prover_da_pub_key: vec![],
             include_tx_body: true,
+            telemetry: None,
+            profiling: None,
+            light_node: None,
+            proof_code_commitment: None,
         };
         assert_eq!(config, expected);
     }
+
+    #[test]
+    fn test_telemetry_config() {
+        let config = r#"
+            sequencer_public_key = "0000000000000000000000000000000000000000000000000000000000000000"
+            include_tx_body = true
+            sequencer_da_pub_key = ""
+            prover_da_pub_key = ""
+            [da]
+            sender_address = "0000000000000000000000000000000000000000000000000000000000000000"
+            [storage]
+            path = "/tmp"
+            [runner]
+            start_height = 1
+            [runner.rpc_config]
+            bind_host = "127.0.0.1"
+            bind_port = 12345
+            [prover_service]
+            aggregated_proof_block_jump = 1
+            [telemetry]
+            endpoint = "https://telemetry.example.com/report"
+        "#;
+
+        let config_file = create_config_from(config);
+
+        let config: RollupConfig<sov_mock_da::MockDaConfig> =
+            from_toml_path(config_file.path()).unwrap();
+        assert_eq!(
+            config.telemetry,
+            Some(TelemetryConfig {
+                endpoint: "https://telemetry.example.com/report".to_string(),
+                interval_secs: 300,
+            })
+        );
+    }
+
+    #[test]
+    fn test_profiling_config() {
+        let config = r#"
+            sequencer_public_key = "0000000000000000000000000000000000000000000000000000000000000000"
+            include_tx_body = true
+            sequencer_da_pub_key = ""
+            prover_da_pub_key = ""
+            [da]
+            sender_address = "0000000000000000000000000000000000000000000000000000000000000000"
+            [storage]
+            path = "/tmp"
+            [runner]
+            start_height = 1
+            [runner.rpc_config]
+            bind_host = "127.0.0.1"
+            bind_port = 12345
+            [prover_service]
+            aggregated_proof_block_jump = 1
+            [profiling]
+            bind_host = "127.0.0.1"
+            bind_port = 6060
+            auth_token = "secret"
+        "#;
+
+        let config_file = create_config_from(config);
+
+        let config: RollupConfig<sov_mock_da::MockDaConfig> =
+            from_toml_path(config_file.path()).unwrap();
+        assert_eq!(
+            config.profiling,
+            Some(ProfilingConfig {
+                bind_host: "127.0.0.1".to_string(),
+                bind_port: 6060,
+                auth_token: "secret".to_string(),
+                max_duration_secs: 60,
+            })
+        );
+    }
+
+    #[test]
+    fn test_profiling_config_with_empty_token() {
+        let config = r#"
+            sequencer_public_key = "0000000000000000000000000000000000000000000000000000000000000000"
+            include_tx_body = true
+            sequencer_da_pub_key = ""
+            prover_da_pub_key = ""
+            [da]
+            sender_address = "0000000000000000000000000000000000000000000000000000000000000000"
+            [storage]
+            path = "/tmp"
+            [runner]
+            start_height = 1
+            [runner.rpc_config]
+            bind_host = "127.0.0.1"
+            bind_port = 12345
+            [prover_service]
+            aggregated_proof_block_jump = 1
+            [profiling]
+            bind_host = "127.0.0.1"
+            bind_port = 6060
+            auth_token = ""
+        "#;
+
+        let config_file = create_config_from(config);
+
+        let config: anyhow::Result<RollupConfig<sov_mock_da::MockDaConfig>> =
+            from_toml_path(config_file.path());
+        assert!(config.is_err());
+    }
+
+    #[test]
+    fn test_proof_market_config() {
+        let config = r#"
+            aggregated_proof_block_jump = 1
+            [proof_market]
+            url = "https://prover.example.com"
+            image_id = "0x1234"
+            poll_interval_secs = 5
+        "#;
+
+        let config_file = create_config_from(config);
+
+        let config: ProverServiceConfig = from_toml_path(config_file.path()).unwrap();
+        assert_eq!(
+            config.proof_market,
+            Some(ProofMarketConfig {
+                url: "https://prover.example.com".to_string(),
+                api_key: None,
+                image_id: "0x1234".to_string(),
+                poll_interval_secs: 5,
+                job_timeout_secs: 3600,
+            })
+        );
+    }
+
+    #[test]
+    fn test_memory_watchdog_config() {
+        let config = r#"
+            aggregated_proof_block_jump = 1
+            [memory_watchdog]
+            checkpoint_dir = "/tmp/checkpoints"
+        "#;
+
+        let config_file = create_config_from(config);
+
+        let config: ProverServiceConfig = from_toml_path(config_file.path()).unwrap();
+        assert_eq!(
+            config.memory_watchdog,
+            Some(MemoryWatchdogConfig {
+                checkpoint_dir: "/tmp/checkpoints".into(),
+                max_memory_usage_percent: 85,
+                continuation_bin: None,
+            })
+        );
+    }
 }
@@ -0,0 +1,237 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use hyper::header::{HeaderValue, CACHE_CONTROL, CONTENT_LENGTH, ETAG, IF_NONE_MATCH};
+use hyper::{Body, Method, Request, Response, StatusCode};
+use sha2::{Digest, Sha256};
+use tower::{Layer, Service};
+
+type BoxError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// RPC methods whose responses never change once they are non-null,
+/// as long as the requested block is addressed by number or hash.
+pub const IMMUTABLE_RPC_METHODS: &[&str] = &[
+    "eth_getBlockByHash",
+    "eth_getBlockByNumber",
+    "eth_getBlockReceipts",
+    "eth_getTransactionReceipt",
+];
+
+/// Block tags whose block changes over time.
+const MUTABLE_BLOCK_TAGS: &[&str] = &["latest", "pending", "safe", "finalized"];
+
+/// Requests larger than this are passed through without being inspected.
+const MAX_INSPECTED_BODY_SIZE: u64 = 64 * 1024;
+
+/// A [`Layer`] letting clients revalidate the responses to [`IMMUTABLE_RPC_METHODS`].
+///
+/// Such responses get an `ETag` computed over their result, and requests revalidating a
+/// response with `If-None-Match` are answered with `304 Not Modified`. Responses are marked
+/// `private, no-cache`: JSON-RPC requests are POSTs whose method is in the body, which shared
+/// HTTP caches don't key on, and blocks that are not finalized can still be rolled back, so
+/// clients must revalidate every response they keep.
+#[derive(Debug, Clone)]
+pub struct HttpCacheLayer {
+    enabled: bool,
+}
+
+impl HttpCacheLayer {
+    /// Creates a layer that lets clients revalidate immutable responses, if `enabled`.
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled }
+    }
+}
+
+impl<S> Layer<S> for HttpCacheLayer {
+    type Service = HttpCache<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        HttpCache {
+            inner,
+            enabled: self.enabled,
+        }
+    }
+}
+
+/// The service created by [`HttpCacheLayer`].
+#[derive(Debug, Clone)]
+pub struct HttpCache<S> {
+    inner: S,
+    enabled: bool,
+}
+
+impl<S> Service<Request<Body>> for HttpCache<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>, Error = BoxError> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        // The inner service was driven to readiness, so it is the one to be called
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let enabled = self.enabled;
+
+        Box::pin(async move {
+            if !enabled || !is_inspectable(&request) {
+                return inner.call(request).await;
+            }
+
+            let (parts, body) = request.into_parts();
+            let body = hyper::body::to_bytes(body).await?;
+            let cacheable = is_immutable_request(&body);
+            let if_none_match = parts.headers.get(IF_NONE_MATCH).cloned();
+
+            let response = inner
+                .call(Request::from_parts(parts, Body::from(body)))
+                .await?;
+            if !cacheable || response.status() != StatusCode::OK {
+                return Ok(response);
+            }
+
+            let (mut parts, body) = response.into_parts();
+            let body = hyper::body::to_bytes(body).await?;
+            let Some(etag) = result_etag(&body) else {
+                return Ok(Response::from_parts(parts, Body::from(body)));
+            };
+            let etag = HeaderValue::from_str(&etag)?;
+            parts
+                .headers
+                .insert(CACHE_CONTROL, HeaderValue::from_static("private, no-cache"));
+            parts.headers.insert(ETAG, etag.clone());
+
+            if if_none_match.as_ref() == Some(&etag) {
+                parts.status = StatusCode::NOT_MODIFIED;
+                parts.headers.remove(CONTENT_LENGTH);
+                return Ok(Response::from_parts(parts, Body::empty()));
+            }
+            Ok(Response::from_parts(parts, Body::from(body)))
+        })
+    }
+}
+
+fn is_inspectable(request: &Request<Body>) -> bool {
+    request.method() == Method::POST
+        && request
+            .headers()
+            .get(CONTENT_LENGTH)
+            .and_then(|length| length.to_str().ok()?.parse::<u64>().ok())
+            .is_some_and(|length| length <= MAX_INSPECTED_BODY_SIZE)
+}
+
+/// Returns true if the body is a single call to an immutable method
+/// that does not address a block by a mutable tag.
+fn is_immutable_request(body: &[u8]) -> bool {
+    let Ok(request) = serde_json::from_slice::<serde_json::Value>(body) else {
+        return false;
+    };
+    let Some(method) = request.get("method").and_then(|method| method.as_str()) else {
+        return false;
+    };
+    let Some(params) = request.get("params").and_then(|params| params.as_array()) else {
+        return false;
+    };
+
+    IMMUTABLE_RPC_METHODS.contains(&method)
+        && params.first().is_some_and(|block| !block.is_null())
+        && !params.iter().any(has_mutable_block_tag)
+}
+
+fn has_mutable_block_tag(param: &serde_json::Value) -> bool {
+    match param {
+        serde_json::Value::String(tag) => MUTABLE_BLOCK_TAGS.contains(&tag.as_str()),
+        serde_json::Value::Object(fields) => fields.values().any(has_mutable_block_tag),
+        _ => false,
+    }
+}
+
+/// Returns true if the body is a successful, non-null response.
+fn is_immutable_response(body: &[u8]) -> bool {
+    serde_json::from_slice::<serde_json::Value>(body)
+        .ok()
+        .and_then(|response| response.get("result").cloned())
+        .is_some_and(|result| !result.is_null())
+}
+
+/// Returns the `ETag` of a successful, non-null response. It only covers the result,
+/// so that the same response to requests with different ids has the same `ETag`.
+fn result_etag(body: &[u8]) -> Option<String> {
+    if !is_immutable_response(body) {
+        return None;
+    }
+    let response = serde_json::from_slice::<serde_json::Value>(body).ok()?;
+    let result = serde_json::to_vec(response.get("result")?).ok()?;
+    Some(format!(
+        "\"{}\"",
+        hex::encode(&Sha256::digest(result)[..16])
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn immutable_requests() {
+        assert!(is_immutable_request(
+            br#"{"jsonrpc":"2.0","id":1,"method":"eth_getBlockByNumber","params":["0x10",false]}"#
+        ));
+        assert!(is_immutable_request(
+            br#"{"jsonrpc":"2.0","id":1,"method":"eth_getBlockReceipts","params":[{"blockNumber":"0x10"}]}"#
+        ));
+        assert!(!is_immutable_request(
+            br#"{"jsonrpc":"2.0","id":1,"method":"eth_getBlockByNumber","params":["latest",false]}"#
+        ));
+        assert!(!is_immutable_request(
+            br#"{"jsonrpc":"2.0","id":1,"method":"eth_getBlockReceipts","params":[{"blockNumber":"pending"}]}"#
+        ));
+        assert!(!is_immutable_request(
+            br#"{"jsonrpc":"2.0","id":1,"method":"eth_getBlockByNumber","params":[]}"#
+        ));
+        assert!(!is_immutable_request(
+            br#"{"jsonrpc":"2.0","id":1,"method":"eth_getBalance","params":["0x0000000000000000000000000000000000000000","0x10"]}"#
+        ));
+        assert!(!is_immutable_request(
+            br#"[{"jsonrpc":"2.0","id":1,"method":"eth_getBlockByNumber","params":["0x10",false]}]"#
+        ));
+    }
+
+    #[test]
+    fn immutable_responses() {
+        assert!(is_immutable_response(
+            br#"{"jsonrpc":"2.0","id":1,"result":{"number":"0x10"}}"#
+        ));
+        assert!(!is_immutable_response(
+            br#"{"jsonrpc":"2.0","id":1,"result":null}"#
+        ));
+        assert!(!is_immutable_response(
+            br#"{"jsonrpc":"2.0","id":1,"error":{"code":-32000,"message":"unknown block"}}"#
+        ));
+    }
+
+    #[test]
+    fn etags_only_cover_the_result() {
+        let etag = result_etag(br#"{"jsonrpc":"2.0","id":1,"result":{"number":"0x10"}}"#);
+        assert!(etag.is_some());
+        assert_eq!(
+            etag,
+            result_etag(br#"{"jsonrpc":"2.0","id":"other","result":{"number":"0x10"}}"#)
+        );
+        assert_ne!(
+            etag,
+            result_etag(br#"{"jsonrpc":"2.0","id":1,"result":{"number":"0x11"}}"#)
+        );
+        assert_eq!(
+            result_etag(br#"{"jsonrpc":"2.0","id":1,"result":null}"#),
+            None
+        );
+    }
+}
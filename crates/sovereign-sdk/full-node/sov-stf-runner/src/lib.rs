@@ -1,14 +1,30 @@
 #![deny(missing_docs)]
 #![doc = include_str!("../README.md")]
 
+#[cfg(feature = "native")]
+mod batch_limit;
 #[cfg(feature = "native")]
 mod config;
+#[cfg(feature = "native")]
+mod http_cache;
+#[cfg(feature = "native")]
+mod metrics;
 #[cfg(feature = "mock")]
 /// Testing utilities.
 #[cfg(feature = "mock")]
 pub mod mock;
 #[cfg(feature = "native")]
+mod profiling;
+#[cfg(feature = "native")]
 mod prover_service;
+#[cfg(feature = "native")]
+mod rpc_allowlist;
+#[cfg(feature = "native")]
+mod soft_batch_push;
+#[cfg(feature = "native")]
+mod storage_monitor;
+#[cfg(feature = "native")]
+mod telemetry;
 
 #[cfg(feature = "native")]
 use std::path::Path;
@@ -16,18 +32,34 @@ use std::path::Path;
 #[cfg(feature = "native")]
 use anyhow::Context;
 #[cfg(feature = "native")]
+pub use batch_limit::{
+    batch_request_config, BatchLimit, BatchLimitLayer, MethodWeights, DEFAULT_METHOD_WEIGHTS,
+    TOO_BIG_BATCH_CODE,
+};
+#[cfg(feature = "native")]
 pub use config::RpcConfig;
 #[cfg(feature = "native")]
+pub use http_cache::{HttpCache, HttpCacheLayer, IMMUTABLE_RPC_METHODS};
+#[cfg(feature = "native")]
+pub use profiling::ProfilingServer;
+#[cfg(feature = "native")]
 pub use prover_service::*;
 #[cfg(feature = "native")]
+pub use rpc_allowlist::apply_rpc_allowlist;
+#[cfg(feature = "native")]
 mod runner;
 #[cfg(feature = "native")]
 pub use config::{
-    from_toml_path, ProverServiceConfig, RollupConfig, RunnerConfig, SequencerClientRpcConfig,
-    StorageConfig,
+    from_toml_path, GasPriceOracleRpcConfig, LightNodeConfig, MemoryWatchdogConfig,
+    ProfilingConfig, ProofMarketConfig, ProverServiceConfig, RollupConfig, RunnerConfig,
+    SequencerClientRpcConfig, StorageConfig, TelemetryConfig,
 };
 #[cfg(feature = "native")]
 pub use runner::*;
+#[cfg(feature = "native")]
+pub use storage_monitor::{DiskWatchdog, StorageMonitor};
+#[cfg(feature = "native")]
+pub use telemetry::{load_or_create_node_id, TelemetryReport, TelemetryReporter};
 
 /// Implements the `StateTransitionVerifier` type for checking the validity of a state transition
 pub mod verifier;
@@ -0,0 +1,41 @@
+use once_cell::sync::Lazy;
+use prometheus::{
+    register_int_counter, register_int_gauge, register_int_gauge_vec, IntCounter, IntGauge,
+    IntGaugeVec,
+};
+
+pub static STORAGE_DIRECTORY_SIZE_BYTES: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        // metric name
+        "storage_directory_size_bytes",
+        // metric description
+        "Size of the database directories of the node in bytes",
+        // metric labels (dimensions)
+        &["directory"]
+    )
+    .unwrap()
+});
+
+pub static STORAGE_FREE_DISK_SPACE_BYTES: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "storage_free_disk_space_bytes",
+        "Free disk space available to the node's storage in bytes"
+    )
+    .unwrap()
+});
+
+pub static STORAGE_LOW_DISK_SPACE: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "storage_low_disk_space",
+        "1 if free disk space is under the configured minimum and writes are paused, 0 otherwise"
+    )
+    .unwrap()
+});
+
+pub static PROVER_SELF_CHECK_FAILURES: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "prover_self_check_failures",
+        "Number of generated proofs that failed verification and were quarantined instead of submitted"
+    )
+    .unwrap()
+});
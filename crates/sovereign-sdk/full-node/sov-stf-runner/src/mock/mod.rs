@@ -1,6 +1,6 @@
 use std::marker::PhantomData;
 
-use sov_rollup_interface::da::DaSpec;
+use sov_rollup_interface::da::{DaSpec, StateDiff};
 use sov_rollup_interface::stf::{BatchReceipt, SlotResult, StateTransitionFunction};
 use sov_rollup_interface::zk::{ValidityCondition, Zkvm};
 
@@ -69,6 +69,7 @@ impl<Vm: Zkvm, Cond: ValidityCondition, Da: DaSpec> StateTransitionFunction<Vm,
         _witness: Self::Witness,
         _slot_header: &<Da as DaSpec>::BlockHeader,
         _validity_condition: &<Da as DaSpec>::ValidityCondition,
+        _slot_blobs: &mut [<Da as DaSpec>::BlobTransaction],
         _soft_batch: &mut sov_modules_api::SignedSoftConfirmationBatch,
     ) -> SlotResult<
         Self::StateRoot,
@@ -79,4 +80,12 @@ impl<Vm: Zkvm, Cond: ValidityCondition, Da: DaSpec> StateTransitionFunction<Vm,
     > {
         todo!()
     }
+
+    fn apply_state_diff(
+        &self,
+        _pre_state: Self::PreState,
+        _state_diff: StateDiff,
+    ) -> (Self::StateRoot, Self::ChangeSet) {
+        ([], ())
+    }
 }
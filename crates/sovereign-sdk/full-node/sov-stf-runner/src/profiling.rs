@@ -0,0 +1,253 @@
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Context;
+use hyper::header::{HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use pprof::protos::Message;
+use tracing::{info, warn};
+
+use crate::ProfilingConfig;
+
+/// Duration of a profile if the request doesn't set one, in seconds.
+const DEFAULT_PROFILE_DURATION_SECS: u64 = 30;
+
+/// Sampling frequency of the profiler, in Hz. Not a multiple of 10 so that the samples don't
+/// align with periodic work of the node.
+const SAMPLING_FREQUENCY: i32 = 99;
+
+/// Format of a CPU profile.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProfileFormat {
+    /// Protobuf profile, read by `go tool pprof`.
+    Pprof,
+    /// SVG flamegraph, viewed in a browser.
+    Flamegraph,
+}
+
+/// HTTP endpoint serving CPU profiles of the node on demand, so that operators can find hot spots
+/// in production without rebuilding the node.
+///
+/// - `GET /debug/pprof/profile?seconds=N` returns a protobuf profile of the next N seconds.
+/// - `GET /debug/pprof/flamegraph?seconds=N` returns the same profile as an SVG flamegraph.
+///
+/// Requests must send the configured token in the `Authorization: Bearer` header.
+/// A single profile is taken at a time.
+pub struct ProfilingServer {
+    config: ProfilingConfig,
+    profiling: AtomicBool,
+}
+
+impl ProfilingServer {
+    /// Creates the profiling endpoint.
+    pub fn new(config: ProfilingConfig) -> Self {
+        Self {
+            config,
+            profiling: AtomicBool::new(false),
+        }
+    }
+
+    /// Starts serving in the background, returns the address the endpoint listens on.
+    pub fn spawn(self) -> anyhow::Result<SocketAddr> {
+        let addr: SocketAddr = format!("{}:{}", self.config.bind_host, self.config.bind_port)
+            .parse()
+            .context("Invalid profiling endpoint address")?;
+        let server = Arc::new(self);
+        let make_service = make_service_fn(move |_| {
+            let server = server.clone();
+            async move {
+                Ok::<_, Infallible>(service_fn(move |request| {
+                    let server = server.clone();
+                    async move { Ok::<_, Infallible>(server.handle(request).await) }
+                }))
+            }
+        });
+
+        let server = Server::try_bind(&addr)
+            .with_context(|| format!("Failed to bind the profiling endpoint to {}", addr))?
+            .serve(make_service);
+        let addr = server.local_addr();
+        info!("Serving CPU profiles on http://{}/debug/pprof", addr);
+        tokio::spawn(async move {
+            if let Err(e) = server.await {
+                warn!("Profiling endpoint stopped: {:?}", e);
+            }
+        });
+        Ok(addr)
+    }
+
+    async fn handle(self: Arc<Self>, request: Request<Body>) -> Response<Body> {
+        if !self.is_authorized(&request) {
+            return text_response(StatusCode::UNAUTHORIZED, "invalid or missing token");
+        }
+        let format = match (request.method(), request.uri().path()) {
+            (&Method::GET, "/debug/pprof/profile") => ProfileFormat::Pprof,
+            (&Method::GET, "/debug/pprof/flamegraph") => ProfileFormat::Flamegraph,
+            _ => return text_response(StatusCode::NOT_FOUND, "not found"),
+        };
+        let seconds = match profile_duration(request.uri().query(), self.config.max_duration_secs) {
+            Ok(seconds) => seconds,
+            Err(e) => return text_response(StatusCode::BAD_REQUEST, &e.to_string()),
+        };
+
+        if self.profiling.swap(true, Ordering::SeqCst) {
+            return text_response(StatusCode::CONFLICT, "a profile is already being taken");
+        }
+        info!("Taking a {} seconds CPU profile, as {:?}", seconds, format);
+        // The profiler samples every thread of the process, the blocking thread only waits.
+        // It clears the flag itself, in case the request is dropped before the profile is done.
+        let server = self.clone();
+        let profile = tokio::task::spawn_blocking(move || {
+            let profile = take_profile(Duration::from_secs(seconds), format);
+            server.profiling.store(false, Ordering::SeqCst);
+            profile
+        })
+        .await;
+
+        match profile {
+            Ok(Ok(profile)) => {
+                let content_type = match format {
+                    ProfileFormat::Pprof => "application/octet-stream",
+                    ProfileFormat::Flamegraph => "image/svg+xml",
+                };
+                let mut response = Response::new(Body::from(profile));
+                response
+                    .headers_mut()
+                    .insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+                response
+            }
+            Ok(Err(e)) => {
+                warn!("Failed to take a CPU profile: {:?}", e);
+                text_response(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string())
+            }
+            Err(e) => {
+                warn!("Failed to take a CPU profile: {:?}", e);
+                text_response(StatusCode::INTERNAL_SERVER_ERROR, "profiling task failed")
+            }
+        }
+    }
+
+    fn is_authorized(&self, request: &Request<Body>) -> bool {
+        request
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|token| {
+                constant_time_eq(token.as_bytes(), self.config.auth_token.as_bytes())
+            })
+    }
+}
+
+/// Compares two byte strings in a time that only depends on their lengths,
+/// so that the token can't be guessed byte by byte from response times.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let diff = a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y));
+    std::hint::black_box(diff) == 0
+}
+
+/// Returns the duration of the profile requested by the `seconds` query parameter.
+fn profile_duration(query: Option<&str>, max_duration_secs: u64) -> anyhow::Result<u64> {
+    let seconds = query
+        .into_iter()
+        .flat_map(|query| query.split('&'))
+        .find_map(|param| param.strip_prefix("seconds="));
+    let seconds = match seconds {
+        Some(seconds) => seconds
+            .parse::<u64>()
+            .context("seconds must be a positive integer")?,
+        None => DEFAULT_PROFILE_DURATION_SECS.min(max_duration_secs),
+    };
+    anyhow::ensure!(
+        seconds > 0 && seconds <= max_duration_secs,
+        "seconds must be between 1 and {}",
+        max_duration_secs
+    );
+    Ok(seconds)
+}
+
+fn take_profile(duration: Duration, format: ProfileFormat) -> anyhow::Result<Vec<u8>> {
+    let guard = pprof::ProfilerGuardBuilder::default()
+        .frequency(SAMPLING_FREQUENCY)
+        .blocklist(&["libc", "libgcc", "pthread", "vdso"])
+        .build()?;
+    std::thread::sleep(duration);
+    let report = guard.report().build()?;
+
+    let mut body = Vec::new();
+    match format {
+        ProfileFormat::Pprof => report.pprof()?.encode(&mut body)?,
+        ProfileFormat::Flamegraph => report.flamegraph(&mut body)?,
+    }
+    Ok(body)
+}
+
+fn text_response(status: StatusCode, message: &str) -> Response<Body> {
+    let mut response = Response::new(Body::from(message.to_string()));
+    *response.status_mut() = status;
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> ProfilingConfig {
+        ProfilingConfig {
+            bind_host: "127.0.0.1".to_string(),
+            bind_port: 0,
+            auth_token: "secret".to_string(),
+            max_duration_secs: 60,
+        }
+    }
+
+    #[test]
+    fn duration_of_profile() {
+        assert_eq!(profile_duration(None, 60).unwrap(), 30);
+        assert_eq!(profile_duration(None, 10).unwrap(), 10);
+        assert_eq!(profile_duration(Some("seconds=5"), 60).unwrap(), 5);
+        assert_eq!(
+            profile_duration(Some("foo=bar&seconds=60"), 60).unwrap(),
+            60
+        );
+        assert!(profile_duration(Some("seconds=61"), 60).is_err());
+        assert!(profile_duration(Some("seconds=0"), 60).is_err());
+        assert!(profile_duration(Some("seconds=-1"), 60).is_err());
+    }
+
+    #[test]
+    fn token_comparison() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secret2"));
+        assert!(!constant_time_eq(b"", b"secret"));
+    }
+
+    #[tokio::test]
+    async fn requests_without_token_are_rejected() {
+        let addr = ProfilingServer::new(config()).spawn().unwrap();
+        let url = format!("http://{}/debug/pprof/profile?seconds=1", addr);
+        let client = reqwest::Client::new();
+
+        let response = client.get(&url).send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        let response = client.get(&url).bearer_auth("wrong").send().await.unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        let response = client
+            .get(format!("http://{}/debug/pprof/heap", addr))
+            .bearer_auth("secret")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+    }
+}
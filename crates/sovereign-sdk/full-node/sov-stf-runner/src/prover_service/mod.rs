@@ -1,7 +1,10 @@
 mod parallel;
+mod proof_market;
 use async_trait::async_trait;
 pub use parallel::ParallelProverService;
+pub use proof_market::{HttpProofMarket, ProofMarket, ProofMarketJobStatus};
 use serde::Serialize;
+use sov_db::ledger_db::LedgerDB;
 use sov_rollup_interface::da::DaSpec;
 use sov_rollup_interface::services::da::DaService;
 use sov_rollup_interface::zk::StateTransitionData;
@@ -73,6 +76,9 @@ pub trait ProverService {
     /// Data Availability service.
     type DaService: DaService;
 
+    /// Sets the ledger where the details of proving sessions are persisted.
+    fn set_ledger_db(&mut self, ledger_db: LedgerDB);
+
     /// Submit a witness for proving.
     async fn submit_witness(
         &self,
@@ -1,17 +1,20 @@
 mod prover;
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
-use prover::Prover;
+use prover::{ProofSelfCheck, Prover};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
+use sov_db::ledger_db::LedgerDB;
 use sov_rollup_interface::da::DaSpec;
 use sov_rollup_interface::services::da::DaService;
 use sov_rollup_interface::stf::StateTransitionFunction;
 use sov_rollup_interface::zk::{StateTransitionData, ZkvmHost};
 
+use super::proof_market::{OutsourcedProver, ProofMarket};
 use super::{ProverService, ProverServiceError};
-use crate::config::ProverServiceConfig;
+use crate::config::{ProofMarketConfig, ProverServiceConfig};
 use crate::verifier::StateTransitionVerifier;
 use crate::{
     ProofGenConfig, ProofProcessingStatus, ProofSubmissionStatus, RollupProverConfig,
@@ -32,6 +35,8 @@ where
 
     zk_storage: V::PreState,
     prover_state: Prover<StateRoot, Witness, Da>,
+    outsourced_prover: Option<Arc<OutsourcedProver>>,
+    self_check: Option<ProofSelfCheck<StateRoot, Da::Spec>>,
 }
 
 impl<StateRoot, Witness, Da, Vm, V> ParallelProverService<StateRoot, Witness, Da, Vm, V>
@@ -71,11 +76,48 @@ where
             prover_state: Prover::new(
                 num_threads,
                 prover_service_config.aggregated_proof_block_jump,
+                prover_service_config.wrap_proofs,
             ),
             zk_storage,
+            outsourced_prover: None,
+            self_check: None,
         }
     }
 
+    /// Outsources proving to a proof market, with the proofs checked against the code
+    /// commitment of the guest. Proofs are still generated locally if the market fails.
+    pub fn with_proof_market(
+        mut self,
+        market: Arc<dyn ProofMarket>,
+        config: &ProofMarketConfig,
+        code_commitment: Vm::CodeCommitment,
+    ) -> Self
+    where
+        Vm::CodeCommitment: Send + Sync + 'static,
+    {
+        self.outsourced_prover = Some(Arc::new(OutsourcedProver::new::<Vm>(
+            market,
+            code_commitment,
+            Duration::from_secs(config.poll_interval_secs),
+            Duration::from_secs(config.job_timeout_secs),
+        )));
+        self
+    }
+
+    /// Verifies the generated proofs against the code commitment of the guest, and checks that
+    /// they prove the state transition of their DA slot, before they are submitted to DA.
+    /// Proofs failing the check are quarantined in the ledger and never submitted.
+    pub fn with_self_check(mut self, code_commitment: Vm::CodeCommitment) -> Self
+    where
+        Vm::CodeCommitment: Send + Sync + 'static,
+    {
+        self.self_check = Some(Arc::new(move |proof| {
+            Vm::verify_and_extract_output::<Da::Spec, StateRoot>(proof, &code_commitment)
+                .map_err(|e| anyhow::anyhow!("{:?}", e))
+        }));
+        self
+    }
+
     /// Creates a new prover.
     pub fn new_with_default_workers(
         vm: Vm,
@@ -117,6 +159,10 @@ where
 
     type DaService = Da;
 
+    fn set_ledger_db(&mut self, ledger_db: LedgerDB) {
+        self.prover_state.set_ledger_db(ledger_db);
+    }
+
     async fn submit_witness(
         &self,
         state_transition_data: StateTransitionData<
@@ -140,6 +186,8 @@ where
             self.prover_config.clone(),
             vm,
             zk_storage,
+            self.outsourced_prover.clone(),
+            self.self_check.clone(),
         )
     }
 
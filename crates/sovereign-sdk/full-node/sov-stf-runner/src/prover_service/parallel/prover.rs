@@ -2,24 +2,37 @@ use std::collections::hash_map::Entry;
 use std::collections::HashMap;
 use std::ops::Deref;
 use std::sync::{Arc, RwLock};
+use std::time::Instant;
 
 use serde::de::DeserializeOwned;
 use serde::Serialize;
+use sov_db::ledger_db::LedgerDB;
 use sov_rollup_interface::da::{BlockHeaderTrait, DaSpec};
+use sov_rollup_interface::rpc::{ProofDetails, ProvingMode};
 use sov_rollup_interface::services::da::DaService;
 use sov_rollup_interface::stf::StateTransitionFunction;
-use sov_rollup_interface::zk::{Proof, StateTransitionData, ZkvmHost};
+use sov_rollup_interface::zk::{
+    GuestExecutionDetails, Proof, StateTransition, StateTransitionData, ZkvmHost,
+};
+use tokio::runtime::Handle;
 
 use super::ProverServiceError;
+use crate::metrics::PROVER_SELF_CHECK_FAILURES;
+use crate::prover_service::proof_market::OutsourcedProver;
 use crate::{
     ProofGenConfig, ProofProcessingStatus, ProofSubmissionStatus, WitnessSubmissionStatus,
 };
 
+/// Verifies a serialized proof against the code commitment of the guest,
+/// and returns the state transition it proves.
+pub(crate) type ProofSelfCheck<StateRoot, Da> =
+    Arc<dyn Fn(&[u8]) -> anyhow::Result<StateTransition<Da, StateRoot>> + Send + Sync>;
+
 enum ProverStatus<StateRoot, Witness, Da: DaSpec> {
     WitnessSubmitted(StateTransitionData<StateRoot, Witness, Da>),
     ProvingInProgress,
     #[allow(dead_code)]
-    Proved(Proof),
+    Proved(Proof, ProofDetails),
     Err(anyhow::Error),
 }
 
@@ -44,10 +57,12 @@ impl<StateRoot, Witness, Da: DaSpec> ProverState<StateRoot, Witness, Da> {
     fn set_to_proved(
         &mut self,
         hash: Da::SlotHash,
-        proof: Result<Proof, anyhow::Error>,
+        proof: Result<(Proof, ProofDetails), anyhow::Error>,
     ) -> Option<ProverStatus<StateRoot, Witness, Da>> {
         match proof {
-            Ok(p) => self.prover_status.insert(hash, ProverStatus::Proved(p)),
+            Ok((p, details)) => self
+                .prover_status
+                .insert(hash, ProverStatus::Proved(p, details)),
             Err(e) => self.prover_status.insert(hash, ProverStatus::Err(e)),
         }
     }
@@ -80,7 +95,9 @@ pub(crate) struct Prover<StateRoot, Witness, Da: DaService> {
     prover_state: Arc<RwLock<ProverState<StateRoot, Witness, Da::Spec>>>,
     num_threads: usize,
     pool: rayon::ThreadPool,
+    ledger_db: Option<LedgerDB>,
     _aggregated_proof_block_jump: u64,
+    wrap_proofs: bool,
 }
 
 impl<StateRoot, Witness, Da> Prover<StateRoot, Witness, Da>
@@ -89,7 +106,11 @@ where
     StateRoot: Serialize + DeserializeOwned + Clone + AsRef<[u8]> + Send + Sync + 'static,
     Witness: Serialize + DeserializeOwned + Send + Sync + 'static,
 {
-    pub(crate) fn new(num_threads: usize, _aggregated_proof_block_jump: u64) -> Self {
+    pub(crate) fn new(
+        num_threads: usize,
+        _aggregated_proof_block_jump: u64,
+        wrap_proofs: bool,
+    ) -> Self {
         Self {
             num_threads,
             pool: rayon::ThreadPoolBuilder::new()
@@ -101,10 +122,16 @@ where
                 prover_status: Default::default(),
                 pending_tasks_count: Default::default(),
             })),
+            ledger_db: None,
             _aggregated_proof_block_jump,
+            wrap_proofs,
         }
     }
 
+    pub(crate) fn set_ledger_db(&mut self, ledger_db: LedgerDB) {
+        self.ledger_db = Some(ledger_db);
+    }
+
     pub(crate) fn submit_witness(
         &self,
         state_transition_data: StateTransitionData<StateRoot, Witness, Da::Spec>,
@@ -130,6 +157,8 @@ where
         config: Arc<ProofGenConfig<V, Da, Vm>>,
         mut vm: Vm,
         zk_storage: V::PreState,
+        outsourced_prover: Option<Arc<OutsourcedProver>>,
+        self_check: Option<ProofSelfCheck<StateRoot, Da::Spec>>,
     ) -> Result<ProofProcessingStatus, ProverServiceError>
     where
         Vm: ZkvmHost + 'static,
@@ -150,16 +179,51 @@ where
                 // Initiate a new proving job only if the prover is not busy.
                 if start_prover {
                     prover_state.set_to_proving(block_header_hash.clone());
+                    let expected_transition = (
+                        state_transition_data.initial_state_root.clone(),
+                        state_transition_data.final_state_root.clone(),
+                    );
                     vm.add_hint(state_transition_data);
+                    let ledger_db = self.ledger_db.clone();
+                    let wrap_proofs = self.wrap_proofs;
+                    // Outsourced jobs are polled on the runtime of the node
+                    let outsourced = outsourced_prover.zip(Handle::try_current().ok());
 
                     self.pool.spawn(move || {
                         tracing::info_span!("guest_execution").in_scope(|| {
-                            let proof = make_proof(vm, config, zk_storage);
+                            let da_slot_hash = block_header_hash.clone().into();
+                            let mut proof = make_proof(
+                                vm,
+                                config,
+                                zk_storage,
+                                da_slot_hash,
+                                wrap_proofs,
+                                outsourced,
+                            );
+                            if let Some(self_check) = self_check {
+                                proof = self_check_proof(
+                                    proof,
+                                    &self_check,
+                                    expected_transition,
+                                    &block_header_hash,
+                                    ledger_db.as_ref(),
+                                );
+                            }
+                            if let Some(ledger_db) = ledger_db {
+                                let details = match &proof {
+                                    Ok((_, details)) => details,
+                                    Err((_, details)) => details,
+                                };
+                                if let Err(e) = ledger_db.put_proof_details(details) {
+                                    tracing::error!("Failed to save proof details: {:?}", e);
+                                }
+                            }
 
                             let mut prover_state =
                                 prover_state_clone.write().expect("Lock was poisoned");
 
-                            prover_state.set_to_proved(block_header_hash, proof);
+                            prover_state
+                                .set_to_proved(block_header_hash, proof.map_err(|(e, _)| e));
                             prover_state.dec_task_count();
                         })
                     });
@@ -174,7 +238,7 @@ where
                 block_header_hash
             )
             .into()),
-            ProverStatus::Proved(_) => Err(anyhow::anyhow!(
+            ProverStatus::Proved(..) => Err(anyhow::anyhow!(
                 "Witness for block_header_hash {:?}, submitted multiple times.",
                 block_header_hash,
             )
@@ -194,7 +258,7 @@ where
             Some(ProverStatus::ProvingInProgress) => {
                 Ok(ProofSubmissionStatus::ProofGenerationInProgress)
             }
-            Some(ProverStatus::Proved(_)) => {
+            Some(ProverStatus::Proved(..)) => {
                 prover_state.remove(&block_header_hash);
                 Ok(ProofSubmissionStatus::Success)
             }
@@ -211,24 +275,175 @@ where
     }
 }
 
+/// Runs the self-check on a full proof. A proof failing it is quarantined in the ledger
+/// and turned into an error, so that it is never submitted to DA.
+fn self_check_proof<StateRoot: AsRef<[u8]>, Da: DaSpec>(
+    proof: Result<(Proof, ProofDetails), (anyhow::Error, ProofDetails)>,
+    self_check: &ProofSelfCheck<StateRoot, Da>,
+    (initial_state_root, final_state_root): (StateRoot, StateRoot),
+    da_slot_hash: &Da::SlotHash,
+    ledger_db: Option<&LedgerDB>,
+) -> Result<(Proof, ProofDetails), (anyhow::Error, ProofDetails)> {
+    let (data, mut details) = match proof {
+        Ok((Proof::Full(data), details)) => (data, details),
+        proof => return proof,
+    };
+
+    match check_proof(
+        self_check,
+        &data,
+        &initial_state_root,
+        &final_state_root,
+        da_slot_hash,
+    ) {
+        Ok(()) => Ok((Proof::Full(data), details)),
+        Err(e) => {
+            PROVER_SELF_CHECK_FAILURES.inc();
+            tracing::error!(
+                "Proof of DA slot {:?} failed the self-check, quarantining it: {:?}",
+                da_slot_hash,
+                e
+            );
+            if let Some(ledger_db) = ledger_db {
+                if let Err(e) = ledger_db.put_quarantined_proof(details.da_slot_hash, data) {
+                    tracing::error!("Failed to quarantine proof: {:?}", e);
+                }
+            }
+            let e = e.context("Proof failed the self-check and was quarantined");
+            details.error = Some(format!("{:?}", e));
+            Err((e, details))
+        }
+    }
+}
+
+/// Checks that a proof verifies and proves the transition of the DA slot
+/// from `initial_state_root` to `final_state_root`.
+fn check_proof<StateRoot: AsRef<[u8]>, Da: DaSpec>(
+    self_check: &ProofSelfCheck<StateRoot, Da>,
+    proof: &[u8],
+    initial_state_root: &StateRoot,
+    final_state_root: &StateRoot,
+    da_slot_hash: &Da::SlotHash,
+) -> anyhow::Result<()> {
+    let transition = self_check(proof).map_err(|e| {
+        anyhow::anyhow!(
+            "Proof doesn't verify against the expected image id: {:?}",
+            e
+        )
+    })?;
+    anyhow::ensure!(
+        transition.initial_state_root.as_ref() == initial_state_root.as_ref(),
+        "Proof starts from state root 0x{} instead of 0x{}",
+        hex::encode(transition.initial_state_root.as_ref()),
+        hex::encode(initial_state_root.as_ref())
+    );
+    anyhow::ensure!(
+        transition.final_state_root.as_ref() == final_state_root.as_ref(),
+        "Proof ends at state root 0x{} instead of 0x{}",
+        hex::encode(transition.final_state_root.as_ref()),
+        hex::encode(final_state_root.as_ref())
+    );
+    anyhow::ensure!(
+        &transition.slot_hash == da_slot_hash,
+        "Proof is for DA slot {:?} instead of {:?}",
+        transition.slot_hash,
+        da_slot_hash
+    );
+    Ok(())
+}
+
 fn make_proof<V, Vm, Da>(
     mut vm: Vm,
     config: Arc<ProofGenConfig<V, Da, Vm>>,
     zk_storage: V::PreState,
-) -> Result<Proof, anyhow::Error>
+    da_slot_hash: [u8; 32],
+    wrap_proofs: bool,
+    outsourced: Option<(Arc<OutsourcedProver>, Handle)>,
+) -> Result<(Proof, ProofDetails), (anyhow::Error, ProofDetails)>
 where
     Da: DaService,
     Vm: ZkvmHost + 'static,
     V: StateTransitionFunction<Vm::Guest, Da::Spec> + Send + Sync + 'static,
     V::PreState: Send + Sync + 'static,
 {
-    match config.deref() {
-        ProofGenConfig::Skip => Ok(Proof::PublicInput(Vec::default())),
-        ProofGenConfig::Simulate(verifier) => verifier
-            .run_block(vm.simulate_with_hints(), zk_storage)
-            .map(|_| Proof::PublicInput(Vec::default()))
-            .map_err(|e| anyhow::anyhow!("Guest execution must succeed but failed with {:?}", e)),
-        ProofGenConfig::Execute => vm.run(false),
-        ProofGenConfig::Prover => vm.run(true),
+    let start = Instant::now();
+    let (mode, result) = match config.deref() {
+        ProofGenConfig::Skip => (
+            ProvingMode::Skip,
+            Ok((
+                Proof::PublicInput(Vec::default()),
+                GuestExecutionDetails::default(),
+            )),
+        ),
+        ProofGenConfig::Simulate(verifier) => (
+            ProvingMode::Simulate,
+            verifier
+                .run_block(vm.simulate_with_hints(), zk_storage)
+                .map(|_| {
+                    (
+                        Proof::PublicInput(Vec::default()),
+                        GuestExecutionDetails::default(),
+                    )
+                })
+                .map_err(|e| {
+                    anyhow::anyhow!("Guest execution must succeed but failed with {:?}", e)
+                }),
+        ),
+        ProofGenConfig::Execute => (ProvingMode::Execute, vm.run_with_details(false)),
+        ProofGenConfig::Prover => (
+            ProvingMode::Prove,
+            outsource(&vm, outsourced)
+                .map(Ok)
+                .unwrap_or_else(|| vm.run_with_details(true))
+                .and_then(|(proof, execution)| {
+                    if wrap_proofs {
+                        Ok((vm.wrap_proof(proof)?, execution))
+                    } else {
+                        Ok((proof, execution))
+                    }
+                }),
+        ),
+    };
+
+    let mut details = ProofDetails {
+        da_slot_hash,
+        mode,
+        journal: Vec::new(),
+        cycle_count: None,
+        logs: String::new(),
+        duration_ms: start.elapsed().as_millis() as u64,
+        error: None,
+    };
+    match result {
+        Ok((proof, execution)) => {
+            details.journal = execution.journal;
+            details.cycle_count = execution.cycle_count;
+            details.logs = execution.logs;
+            Ok((proof, details))
+        }
+        Err(e) => {
+            details.error = Some(e.to_string());
+            Err((e, details))
+        }
+    }
+}
+
+/// Proves on the proof market if one is set. Returns `None` if the proof must be generated
+/// locally, because there is no market or it failed.
+fn outsource<Vm: ZkvmHost>(
+    vm: &Vm,
+    outsourced: Option<(Arc<OutsourcedProver>, Handle)>,
+) -> Option<(Proof, GuestExecutionDetails)> {
+    let (prover, handle) = outsourced?;
+    let Some(input) = vm.serialized_hints() else {
+        tracing::warn!("The zkVM can't export its hints, proving locally");
+        return None;
+    };
+    match prover.prove(&handle, input) {
+        Ok(proof) => Some((proof, GuestExecutionDetails::default())),
+        Err(e) => {
+            tracing::warn!("Outsourced proving failed, proving locally: {:?}", e);
+            None
+        }
     }
 }
@@ -0,0 +1,205 @@
+//! Outsourced proving to external proof markets.
+//!
+//! A proving job sends the serialized hints of a block to the market, then polls the market
+//! until the proof is ready. The proof is only accepted if it verifies against the code
+//! commitment of the guest the node expects, so a faulty or malicious market can't make the
+//! prover publish an invalid proof. On any failure, the proof is generated locally.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sov_rollup_interface::zk::{Proof, Zkvm};
+use tokio::runtime::Handle;
+use tokio::time::Instant;
+
+use crate::config::ProofMarketConfig;
+
+/// Status of a proving job on a proof market.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProofMarketJobStatus {
+    /// The job is queued or being proven.
+    Running,
+    /// The job succeeded with the given serialized proof.
+    Succeeded(Vec<u8>),
+    /// The job failed with the given reason.
+    Failed(String),
+}
+
+/// An external service generating proofs, like Bonsai.
+#[async_trait]
+pub trait ProofMarket: Send + Sync {
+    /// Submits the serialized hints of a block for proving, returns the id of the job.
+    async fn submit(&self, input: Vec<u8>) -> anyhow::Result<String>;
+
+    /// Returns the status of a job.
+    async fn poll(&self, job_id: &str) -> anyhow::Result<ProofMarketJobStatus>;
+}
+
+#[derive(Serialize)]
+struct SubmitJobRequest<'a> {
+    image_id: &'a str,
+    input: String,
+}
+
+#[derive(Deserialize)]
+struct SubmitJobResponse {
+    job_id: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+enum JobState {
+    Running,
+    Succeeded,
+    Failed,
+}
+
+#[derive(Deserialize)]
+struct JobStatusResponse {
+    status: JobState,
+    #[serde(default)]
+    proof: Option<String>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+/// A proof market with a REST API:
+/// - `POST {url}/jobs` with `{"image_id", "input"}` returns `{"job_id"}`,
+/// - `GET {url}/jobs/{job_id}` returns `{"status", "proof", "error"}`,
+///   the status being one of `RUNNING`, `SUCCEEDED` and `FAILED`.
+///
+/// Inputs and proofs are hex encoded, proofs serialized the way the local prover serializes
+/// them. The configured api key is sent in the `x-api-key` header.
+pub struct HttpProofMarket {
+    client: reqwest::Client,
+    config: ProofMarketConfig,
+}
+
+impl HttpProofMarket {
+    /// Creates a client of the market at the configured url.
+    pub fn new(config: ProofMarketConfig) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            config,
+        }
+    }
+
+    fn request(&self, method: reqwest::Method, path: &str) -> reqwest::RequestBuilder {
+        let url = format!("{}/{}", self.config.url.trim_end_matches('/'), path);
+        let request = self.client.request(method, url);
+        match &self.config.api_key {
+            Some(api_key) => request.header("x-api-key", api_key),
+            None => request,
+        }
+    }
+}
+
+#[async_trait]
+impl ProofMarket for HttpProofMarket {
+    async fn submit(&self, input: Vec<u8>) -> anyhow::Result<String> {
+        let response: SubmitJobResponse = self
+            .request(reqwest::Method::POST, "jobs")
+            .json(&SubmitJobRequest {
+                image_id: &self.config.image_id,
+                input: hex::encode(input),
+            })
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(response.job_id)
+    }
+
+    async fn poll(&self, job_id: &str) -> anyhow::Result<ProofMarketJobStatus> {
+        let response: JobStatusResponse = self
+            .request(reqwest::Method::GET, &format!("jobs/{}", job_id))
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(match response.status {
+            JobState::Running => ProofMarketJobStatus::Running,
+            JobState::Succeeded => {
+                let proof = response
+                    .proof
+                    .ok_or_else(|| anyhow::anyhow!("Job {} succeeded without a proof", job_id))?;
+                ProofMarketJobStatus::Succeeded(hex::decode(proof.trim_start_matches("0x"))?)
+            }
+            JobState::Failed => ProofMarketJobStatus::Failed(response.error.unwrap_or_default()),
+        })
+    }
+}
+
+type ProofVerifier = Box<dyn Fn(&[u8]) -> anyhow::Result<()> + Send + Sync>;
+
+/// Proves blocks on a proof market, checking the proofs against the expected code commitment.
+pub(crate) struct OutsourcedProver {
+    market: Arc<dyn ProofMarket>,
+    verify_proof: ProofVerifier,
+    poll_interval: Duration,
+    job_timeout: Duration,
+}
+
+impl OutsourcedProver {
+    pub(crate) fn new<Vm>(
+        market: Arc<dyn ProofMarket>,
+        code_commitment: Vm::CodeCommitment,
+        poll_interval: Duration,
+        job_timeout: Duration,
+    ) -> Self
+    where
+        Vm: Zkvm,
+        Vm::CodeCommitment: Send + Sync + 'static,
+    {
+        Self {
+            market,
+            verify_proof: Box::new(move |proof| {
+                Vm::verify(proof, &code_commitment)
+                    .map(|_| ())
+                    .map_err(|e| anyhow::anyhow!("{:?}", e))
+            }),
+            poll_interval,
+            job_timeout,
+        }
+    }
+
+    /// Proves the block with the given hints, blocking the current thread until the market
+    /// returns a valid proof or fails.
+    pub(crate) fn prove(&self, handle: &Handle, input: Vec<u8>) -> anyhow::Result<Proof> {
+        handle.block_on(async {
+            let job_id = self.market.submit(input).await?;
+            tracing::info!("Submitted proving job {} to the proof market", job_id);
+
+            let deadline = Instant::now() + self.job_timeout;
+            loop {
+                match self.market.poll(&job_id).await? {
+                    ProofMarketJobStatus::Running => {
+                        anyhow::ensure!(
+                            Instant::now() < deadline,
+                            "Proving job {} timed out",
+                            job_id
+                        );
+                        tokio::time::sleep(self.poll_interval).await;
+                    }
+                    ProofMarketJobStatus::Succeeded(proof) => {
+                        (self.verify_proof)(&proof).map_err(|e| {
+                            anyhow::anyhow!(
+                                "Proof of job {} doesn't verify against the expected image id: {:?}",
+                                job_id,
+                                e
+                            )
+                        })?;
+                        return Ok(Proof::Full(proof));
+                    }
+                    ProofMarketJobStatus::Failed(reason) => {
+                        anyhow::bail!("Proving job {} failed: {}", job_id, reason)
+                    }
+                }
+            }
+        })
+    }
+}
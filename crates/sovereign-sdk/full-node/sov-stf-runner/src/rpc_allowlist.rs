@@ -0,0 +1,136 @@
+use std::collections::BTreeMap;
+
+use jsonrpsee::types::ErrorObjectOwned;
+use jsonrpsee::RpcModule;
+use tracing::warn;
+
+/// Version reported by `rpc_modules` for every namespace.
+const RPC_MODULE_VERSION: &str = "1.0";
+
+/// Removes the RPC methods not allowed by `allowed_methods` and registers `rpc_modules`,
+/// which returns the namespaces of the methods left, so that it reflects what the node serves.
+///
+/// Entries of `allowed_methods` are either namespaces, e.g. `eth`, or method names,
+/// e.g. `debug_traceTransaction`. All methods are kept if `allowed_methods` is not set.
+/// `rpc_modules` itself is always served.
+pub fn apply_rpc_allowlist(
+    mut methods: RpcModule<()>,
+    allowed_methods: Option<&[String]>,
+) -> Result<RpcModule<()>, jsonrpsee::core::Error> {
+    if let Some(allowed_methods) = allowed_methods {
+        for entry in allowed_methods {
+            if !methods
+                .method_names()
+                .any(|method| is_allowed(method, std::slice::from_ref(entry)))
+            {
+                warn!("RPC allowlist entry {} matches no RPC method", entry);
+            }
+        }
+
+        let disallowed: Vec<&'static str> = methods
+            .method_names()
+            .filter(|method| !is_allowed(method, allowed_methods))
+            .collect();
+        for method in disallowed {
+            methods.remove_method(method);
+        }
+    }
+
+    methods.remove_method("rpc_modules");
+    let mut modules: BTreeMap<String, String> = methods
+        .method_names()
+        .map(|method| {
+            (
+                namespace(method).to_string(),
+                RPC_MODULE_VERSION.to_string(),
+            )
+        })
+        .collect();
+    modules.insert("rpc".to_string(), RPC_MODULE_VERSION.to_string());
+    methods.register_method("rpc_modules", move |_, _| {
+        Ok::<_, ErrorObjectOwned>(modules.clone())
+    })?;
+
+    Ok(methods)
+}
+
+/// Returns the namespace of a method, the part of its name before the first underscore.
+fn namespace(method: &str) -> &str {
+    method
+        .split_once('_')
+        .map_or(method, |(namespace, _)| namespace)
+}
+
+fn is_allowed(method: &str, allowed_methods: &[String]) -> bool {
+    allowed_methods
+        .iter()
+        .any(|entry| entry == method || entry == namespace(method))
+}
+
+#[cfg(test)]
+mod tests {
+    use jsonrpsee::core::params::ArrayParams;
+
+    use super::*;
+
+    fn rpc_module() -> RpcModule<()> {
+        let mut methods = RpcModule::new(());
+        for method in [
+            "eth_blockNumber",
+            "eth_call",
+            "debug_traceTransaction",
+            "debug_traceCall",
+            "admin_addPeer",
+        ] {
+            methods
+                .register_method(method, |_, _| Ok::<_, ErrorObjectOwned>(()))
+                .unwrap();
+        }
+        methods
+    }
+
+    fn method_names(methods: &RpcModule<()>) -> Vec<&'static str> {
+        let mut names: Vec<_> = methods.method_names().collect();
+        names.sort();
+        names
+    }
+
+    #[tokio::test]
+    async fn allowlist_keeps_allowed_namespaces_and_methods() {
+        let allowed_methods = vec!["eth".to_string(), "debug_traceCall".to_string()];
+        let methods = apply_rpc_allowlist(rpc_module(), Some(&allowed_methods)).unwrap();
+
+        assert_eq!(
+            method_names(&methods),
+            vec![
+                "debug_traceCall",
+                "eth_blockNumber",
+                "eth_call",
+                "rpc_modules"
+            ]
+        );
+
+        let modules: BTreeMap<String, String> = methods
+            .call("rpc_modules", ArrayParams::new())
+            .await
+            .unwrap();
+        assert_eq!(
+            modules.keys().collect::<Vec<_>>(),
+            vec!["debug", "eth", "rpc"]
+        );
+    }
+
+    #[tokio::test]
+    async fn no_allowlist_keeps_all_methods() {
+        let methods = apply_rpc_allowlist(rpc_module(), None).unwrap();
+        assert_eq!(methods.method_names().count(), 6);
+
+        // Applying it again doesn't register rpc_modules twice
+        let methods = apply_rpc_allowlist(methods, Some(&["eth".to_string()])).unwrap();
+        let modules: BTreeMap<String, String> = methods
+            .call("rpc_modules", ArrayParams::new())
+            .await
+            .unwrap();
+        assert_eq!(modules.keys().collect::<Vec<_>>(), vec!["eth", "rpc"]);
+    }
+}
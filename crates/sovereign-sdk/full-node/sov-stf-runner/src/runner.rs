@@ -1,8 +1,9 @@
 use std::collections::VecDeque;
 use std::marker::PhantomData;
 use std::net::SocketAddr;
+use std::sync::Arc;
 
-use anyhow::bail;
+use anyhow::{bail, Context as _};
 use borsh::de::BorshDeserialize;
 use jsonrpsee::core::Error;
 use jsonrpsee::RpcModule;
@@ -10,13 +11,16 @@ use rs_merkle::algorithms::Sha256;
 use rs_merkle::MerkleTree;
 use sequencer_client::SequencerClient;
 use sov_db::ledger_db::{LedgerDB, SlotCommit};
-use sov_db::schema::types::{BatchNumber, SlotNumber, StoredSoftBatch};
+use sov_db::schema::types::{BatchNumber, SlotNumber, StoredLightNodeHead, StoredSoftBatch};
 use sov_modules_api::Context;
 use sov_modules_stf_blueprint::StfBlueprintTrait;
 use sov_rollup_interface::da::{
     BatchProof, BlobReaderTrait, BlockHeaderTrait, DaData, DaSpec, SequencerCommitment,
+    VerifiableBatchProof,
+};
+use sov_rollup_interface::rpc::{
+    DaActivityRecord, LedgerRpcProvider, ProvenRange, SoftConfirmationStatus, SyncStatus,
 };
-use sov_rollup_interface::rpc::SoftConfirmationStatus;
 use sov_rollup_interface::services::da::{DaService, SlotData};
 pub use sov_rollup_interface::stf::BatchReceipt;
 use sov_rollup_interface::stf::{SoftBatchReceipt, StateTransitionFunction};
@@ -26,8 +30,12 @@ use tokio::sync::oneshot;
 use tokio::time::{sleep, Duration, Instant};
 use tracing::{debug, error, info};
 
+use crate::soft_batch_push::{create_push_rpc_module, PushedSoftBatches};
 use crate::verifier::StateTransitionVerifier;
-use crate::{ProverService, RpcConfig, RunnerConfig};
+use crate::{
+    apply_rpc_allowlist, batch_request_config, BatchLimitLayer, DiskWatchdog, HttpCacheLayer,
+    LightNodeConfig, ProverService, RpcConfig, RunnerConfig,
+};
 
 type StateRoot<ST, Vm, Da> = <ST as StateTransitionFunction<Vm, Da>>::StateRoot;
 type GenesisParams<ST, Vm, Da> = <ST as StateTransitionFunction<Vm, Da>>::GenesisParams;
@@ -63,6 +71,10 @@ where
     prover_da_pub_key: Vec<u8>,
     phantom: std::marker::PhantomData<C>,
     include_tx_body: bool,
+    disk_watchdog: DiskWatchdog,
+    pushed_soft_batches: Option<Arc<PushedSoftBatches>>,
+    light_node: Option<LightNodeConfig>,
+    proof_code_commitment: Option<serde_json::Value>,
 }
 
 /// Represents the possible modes of execution for a zkVM program
@@ -126,6 +138,9 @@ where
         include_tx_body: bool,
     ) -> Result<Self, anyhow::Error> {
         let rpc_config = runner_config.rpc_config;
+        let pushed_soft_batches = rpc_config
+            .accept_pushed_soft_batches
+            .then(|| Arc::new(PushedSoftBatches::default()));
 
         let prev_state_root = match init_variant {
             InitVariant::Initialized(state_root) => {
@@ -167,15 +182,204 @@ where
             prover_da_pub_key,
             phantom: std::marker::PhantomData,
             include_tx_body,
+            disk_watchdog: DiskWatchdog::default(),
+            pushed_soft_batches,
+            light_node: None,
+            proof_code_commitment: None,
         })
     }
 
+    /// Pauses syncing while the watchdog reports low disk space.
+    pub fn with_disk_watchdog(mut self, disk_watchdog: DiskWatchdog) -> Self {
+        self.disk_watchdog = disk_watchdog;
+        self
+    }
+
+    /// Runs the full node as a light node, see [`run_light_node_process`](Self::run_light_node_process).
+    pub fn with_light_node(mut self, light_node: Option<LightNodeConfig>) -> Self {
+        self.light_node = light_node;
+        self
+    }
+
+    /// Records the L2 ranges of the ZK proofs posted to DA that verify against `proof_code_commitment`,
+    /// in the serde format of the code commitment of the zkVM. No ranges are recorded if not set.
+    pub fn with_proof_code_commitment(
+        mut self,
+        proof_code_commitment: Option<serde_json::Value>,
+    ) -> Self {
+        self.proof_code_commitment = proof_code_commitment;
+        self
+    }
+
+    /// True if the node runs as a light node.
+    pub fn is_light_node(&self) -> bool {
+        self.light_node.is_some()
+    }
+
+    fn proof_code_commitment(&self) -> anyhow::Result<Option<<Vm as Zkvm>::CodeCommitment>> {
+        self.proof_code_commitment
+            .clone()
+            .map(|code_commitment| {
+                serde_json::from_value(code_commitment)
+                    .context("Failed to parse the proof code commitment")
+            })
+            .transpose()
+    }
+
+    /// Returns the L2 ranges of the ZK proofs that verify against `code_commitment` and whose state
+    /// transition goes from the pre state root of the first soft confirmation they prove to the
+    /// post state root of the last one, as synced by the node.
+    ///
+    /// Proofs of soft confirmations the node hasn't synced yet are skipped, and so are all proofs
+    /// if there is no code commitment to verify them against.
+    fn verify_proofs(
+        &self,
+        zk_proofs: Vec<(VerifiableBatchProof, [u8; 32])>,
+        code_commitment: Option<&<Vm as Zkvm>::CodeCommitment>,
+    ) -> anyhow::Result<Vec<(BatchProof, [u8; 32])>> {
+        let Some(code_commitment) = code_commitment else {
+            tracing::warn!(
+                "Not recording {} proofs, no proof code commitment is configured",
+                zk_proofs.len()
+            );
+            return Ok(vec![]);
+        };
+
+        let soft_batch_at = |l2_height: u64| -> anyhow::Result<Option<StoredSoftBatch>> {
+            Ok(self
+                .ledger_db
+                .get_soft_batch_range(&(BatchNumber(l2_height)..BatchNumber(l2_height + 1)))?
+                .into_iter()
+                .next())
+        };
+
+        let mut verified_proofs = vec![];
+        for (proof, da_txid) in zk_proofs {
+            let BatchProof {
+                l2_start_height,
+                l2_end_height,
+            } = proof.batch_proof;
+            let transition = match Vm::verify_and_extract_output::<Da::Spec, Stf::StateRoot>(
+                &proof.proof,
+                code_commitment,
+            ) {
+                Ok(transition) => transition,
+                Err(e) => {
+                    tracing::warn!(
+                        "Invalid proof of L2 range {}..={}: {:?}",
+                        l2_start_height,
+                        l2_end_height,
+                        e
+                    );
+                    continue;
+                }
+            };
+
+            let (Some(first), Some(last)) = (
+                soft_batch_at(l2_start_height)?,
+                soft_batch_at(l2_end_height)?,
+            ) else {
+                tracing::warn!(
+                    "Not recording the proof of L2 range {}..={}, its soft confirmations are not synced",
+                    l2_start_height,
+                    l2_end_height
+                );
+                continue;
+            };
+            if transition.initial_state_root.as_ref() != first.pre_state_root.as_slice()
+                || transition.final_state_root.as_ref() != last.post_state_root.as_slice()
+            {
+                tracing::warn!(
+                    "Proof of L2 range {}..={} does not match the state roots of the node",
+                    l2_start_height,
+                    l2_end_height
+                );
+                continue;
+            }
+
+            verified_proofs.push((proof.batch_proof, da_txid));
+        }
+        Ok(verified_proofs)
+    }
+
+    /// Records the L2 ranges covered by the ZK proofs found in a DA block, and marks
+    /// the DA slots of the proven soft confirmations already synced as proven.
+    fn record_proofs(
+        &self,
+        zk_proofs: &[(BatchProof, [u8; 32])],
+        da_slot_height: u64,
+        da_slot_hash: [u8; 32],
+    ) -> anyhow::Result<()> {
+        for (batch_proof, da_txid) in zk_proofs {
+            if batch_proof.l2_start_height > batch_proof.l2_end_height {
+                tracing::warn!(
+                    "Found proof with invalid L2 range {}..={} in block 0x{}",
+                    batch_proof.l2_start_height,
+                    batch_proof.l2_end_height,
+                    hex::encode(da_slot_hash)
+                );
+                continue;
+            }
+
+            self.ledger_db.put_proven_range(&ProvenRange {
+                l2_start_height: batch_proof.l2_start_height,
+                l2_end_height: batch_proof.l2_end_height,
+                da_slot_height,
+                da_slot_hash,
+                da_txid: *da_txid,
+            })?;
+
+            let da_slot_of = |l2_height: u64| -> anyhow::Result<Option<u64>> {
+                let soft_batches = self
+                    .ledger_db
+                    .get_soft_batch_range(&(BatchNumber(l2_height)..BatchNumber(l2_height + 1)))?;
+                Ok(soft_batches
+                    .first()
+                    .map(|soft_batch| soft_batch.da_slot_height))
+            };
+            if let (Some(start), Some(end)) = (
+                da_slot_of(batch_proof.l2_start_height)?,
+                da_slot_of(batch_proof.l2_end_height)?,
+            ) {
+                for l1_height in start..=end {
+                    // Only DA slots whose soft confirmations are all proven
+                    let fully_proven = self
+                        .ledger_db
+                        .get_l2_range_by_l1_height(SlotNumber(l1_height))?
+                        .is_some_and(|(first, last)| {
+                            first.0 >= batch_proof.l2_start_height
+                                && last.0 <= batch_proof.l2_end_height
+                        });
+                    if fully_proven {
+                        self.ledger_db.put_soft_confirmation_status(
+                            SlotNumber(l1_height),
+                            SoftConfirmationStatus::Proven,
+                        )?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Starts a RPC server with provided rpc methods.
     pub async fn start_rpc_server(
         &self,
-        methods: RpcModule<()>,
+        mut methods: RpcModule<()>,
         channel: Option<oneshot::Sender<SocketAddr>>,
     ) {
+        if let Some(pushed_soft_batches) = &self.pushed_soft_batches {
+            let push_methods = create_push_rpc_module::<C>(
+                pushed_soft_batches.clone(),
+                self.ledger_db.clone(),
+                self.sequencer_pub_key.clone(),
+            )
+            .expect("Failed to create the soft batch push RPC module");
+            methods
+                .merge(push_methods)
+                .expect("Failed to register citrea_pushSoftBatch");
+        }
+
         let listen_address = SocketAddr::new(
             self.rpc_config
                 .bind_host
@@ -184,11 +388,20 @@ where
             self.rpc_config.bind_port,
         );
 
+        let methods = apply_rpc_allowlist(methods, self.rpc_config.allowed_methods.as_deref())
+            .expect("Failed to apply the RPC allowlist");
+
         let max_connections = self.rpc_config.max_connections;
+        let batch_config = batch_request_config(&self.rpc_config);
+        let middleware = tower::ServiceBuilder::new()
+            .layer(BatchLimitLayer::new(&self.rpc_config))
+            .layer(HttpCacheLayer::new(self.rpc_config.response_etags));
 
         let _handle = tokio::spawn(async move {
             let server = jsonrpsee::server::ServerBuilder::default()
                 .max_connections(max_connections)
+                .set_batch_request_config(batch_config)
+                .set_middleware(middleware)
                 .build([listen_address].as_ref())
                 .await
                 .unwrap();
@@ -216,6 +429,7 @@ where
         let Some(client) = &self.sequencer_client else {
             return Err(anyhow::anyhow!("Sequencer Client is not initialized"));
         };
+        let proof_code_commitment = self.proof_code_commitment()?;
 
         let mut seen_receipts: VecDeque<_> = VecDeque::new();
 
@@ -228,6 +442,11 @@ where
         let mut seen_block_headers: VecDeque<<Da::Spec as DaSpec>::BlockHeader> = VecDeque::new();
         // let mut seen_receipts: VecDeque<_> = VecDeque::new();
         let mut height = self.start_height;
+        let mut sync_status = SyncStatus {
+            starting_l2_height: height.saturating_sub(1),
+            current_l2_height: height.saturating_sub(1),
+            ..Default::default()
+        };
 
         info!("Prover trying to sync from height {}", height);
 
@@ -279,6 +498,11 @@ where
         let mut l1_height = soft_batch.da_slot_height;
 
         loop {
+            if self.disk_watchdog.is_low_on_disk_space() {
+                sleep(Duration::from_secs(RETRY_SLEEP)).await;
+                continue;
+            }
+
             let filtered_block = self.da_service.get_block_at(l1_height).await?;
 
             let (da_data, _da_errors): (Vec<_>, Vec<_>) = self
@@ -299,16 +523,73 @@ where
 
             // seperate DaData into sequencer commitments and proofs
             let mut sequencer_commitments = Vec::<SequencerCommitment>::new();
-            let mut zk_proofs = Vec::<BatchProof>::new();
+            let mut zk_proofs = Vec::<(VerifiableBatchProof, [u8; 32])>::new();
+
+            // a DA block is referred to by many soft confirmations, only record its activity once
+            let mut da_activity = self.ledger_db.get_sequencer_da_activity()?;
+            let record_da_activity = da_activity
+                .last_scanned_da_height
+                .map_or(true, |scanned| scanned < filtered_block.header().height());
+            let da_activity_record = DaActivityRecord {
+                da_slot_height: filtered_block.header().height(),
+                da_slot_hash: filtered_block.hash(),
+            };
+
+            self.da_service
+                .extract_relevant_blobs(&filtered_block)
+                .into_iter()
+                .for_each(|mut tx| {
+                    let data = DaData::try_from_slice(tx.full_data());
+
+                    if tx.sender().as_ref() == self.sequencer_da_pub_key.as_slice() {
+                        if let Ok(DaData::SequencerCommitment(seq_com)) = data {
+                            da_activity.commitment_count += 1;
+                            da_activity.last_commitment = Some(da_activity_record.clone());
+                            sequencer_commitments.push(seq_com);
+                        } else {
+                            da_activity.malformed_blob_count += 1;
+                            da_activity.last_malformed_blob = Some(da_activity_record.clone());
+                            tracing::warn!(
+                                "Found broken DA data in block 0x{}: {:?}",
+                                hex::encode(filtered_block.hash()),
+                                data
+                            );
+                        }
+                    } else if tx.sender().as_ref() == self.prover_da_pub_key.as_slice() {
+                        if let Ok(DaData::ZKProof(_) | DaData::VerifiableZKProof(_)) = &data {
+                            da_activity.proof_count += 1;
+                            da_activity.last_proof = Some(da_activity_record.clone());
+                            // Proofs without the proof itself can't be verified
+                            if let Ok(DaData::VerifiableZKProof(proof)) = data {
+                                zk_proofs.push((proof, tx.hash()));
+                            }
+                        } else {
+                            da_activity.malformed_blob_count += 1;
+                            da_activity.last_malformed_blob = Some(da_activity_record.clone());
+                            tracing::warn!(
+                                "Found broken DA data in block 0x{}: {:?}",
+                                hex::encode(filtered_block.hash()),
+                                data
+                            );
+                        }
+                    } else {
+                        // TODO: This is where force transactions will land - try to parse DA data force transaction
+                    }
+                });
 
-            da_data.into_iter().for_each(|da_data| match da_data {
-                Ok(DaData::SequencerCommitment(seq_com)) => sequencer_commitments.push(seq_com),
-                Ok(DaData::ZKProof(batch_proof)) => zk_proofs.push(batch_proof),
-                _ => {}
-            });
+            if record_da_activity {
+                da_activity.last_scanned_da_height = Some(da_activity_record.da_slot_height);
+                self.ledger_db.put_sequencer_da_activity(&da_activity)?;
+            }
 
             if !zk_proofs.is_empty() {
-                // TODO: Implement this
+                let verified_proofs =
+                    self.verify_proofs(zk_proofs, proof_code_commitment.as_ref())?;
+                self.record_proofs(
+                    &verified_proofs,
+                    filtered_block.header().height(),
+                    filtered_block.hash(),
+                )?;
             }
 
             // TODO here we can support multiple commitments but for now let's take the last one.
@@ -345,19 +626,11 @@ where
                             .unwrap();
 
                         if soft_batch.da_slot_height > end_l1_height {
-                            for i in start_l1_height..=end_l1_height {
-                                self.ledger_db
-                                    .put_soft_confirmation_status(
-                                        SlotNumber(i),
-                                        SoftConfirmationStatus::Finalized,
-                                    )
-                                    .unwrap_or_else(|_| {
-                                        panic!(
-                                "Failed to put soft confirmation status in the ledger db {}",
-                                i
-                            )
-                                    });
-                            }
+                            self.ledger_db
+                                .put_verified_commitment(start_l1_height..=end_l1_height)
+                                .expect(
+                                    "Failed to put soft confirmation statuses in the ledger db",
+                                );
                             l1_height += 1;
                             break;
                         }
@@ -387,6 +660,7 @@ where
                             Default::default(),
                             filtered_block.header(),
                             &filtered_block.validity_condition(),
+                            &mut self.da_service.extract_relevant_blobs(&filtered_block),
                             &mut soft_batch.clone().into(),
                         );
 
@@ -520,6 +794,8 @@ where
                         self.ledger_db.commit_slot(receipts)?;
                         self.storage_manager.finalize_l2(height)?;
 
+                        sync_status.current_l2_height = height;
+                        self.ledger_db.set_sync_status(sync_status);
                         height += 1;
                     }
                 }
@@ -531,9 +807,16 @@ where
                         .get_last_finalized_block_header()
                         .await?
                         .height();
+                    sync_status.current_l1_height = l1_height;
                     if l1_height < last_finalized_height {
+                        self.record_sync_status(client, &mut sync_status).await;
                         l1_height += 1;
                         sleep(Duration::from_millis(100)).await;
+                    } else {
+                        // the soft batches after the finalized DA head can't be proven yet
+                        sync_status.highest_l2_height = sync_status.current_l2_height;
+                        sync_status.highest_l1_height = last_finalized_height;
+                        self.ledger_db.set_sync_status(sync_status);
                     }
                 }
             }
@@ -545,10 +828,16 @@ where
         let Some(client) = &self.sequencer_client else {
             return Err(anyhow::anyhow!("Sequencer Client is not initialized"));
         };
+        let proof_code_commitment = self.proof_code_commitment()?;
 
         let mut seen_block_headers: VecDeque<<Da::Spec as DaSpec>::BlockHeader> = VecDeque::new();
         let mut seen_receipts: VecDeque<_> = VecDeque::new();
         let mut height = self.start_height;
+        let mut sync_status = SyncStatus {
+            starting_l2_height: height.saturating_sub(1),
+            current_l2_height: height.saturating_sub(1),
+            ..Default::default()
+        };
         info!("Starting to sync from height {}", height);
 
         let mut last_connection_error = Instant::now();
@@ -558,7 +847,22 @@ where
         let mut retry_index = 0;
 
         loop {
-            let soft_batch = client.get_soft_batch::<Da::Spec>(height).await;
+            if self.disk_watchdog.is_low_on_disk_space() {
+                sleep(Duration::from_secs(RETRY_SLEEP)).await;
+                continue;
+            }
+
+            let soft_batch = match self
+                .pushed_soft_batches
+                .as_ref()
+                .and_then(|pushed_soft_batches| pushed_soft_batches.take(height))
+            {
+                Some(soft_batch) => {
+                    debug!("Soft Batch: syncing pushed soft batch {}", height);
+                    Ok(Some(soft_batch))
+                }
+                None => client.get_soft_batch::<Da::Spec>(height).await,
+            };
 
             if soft_batch.is_err() {
                 let x = soft_batch.unwrap_err();
@@ -594,7 +898,8 @@ where
                         &mut retry_index,
                         "No soft batch published".to_string().as_str(),
                     );
-                    sleep(Duration::from_secs(RETRY_SLEEP)).await;
+                    self.record_sync_status(client, &mut sync_status).await;
+                    self.wait_for_soft_batch().await;
                     continue;
                 }
             };
@@ -605,6 +910,7 @@ where
                 .da_service
                 .get_block_at(soft_batch.da_slot_height)
                 .await?;
+            sync_status.current_l1_height = soft_batch.da_slot_height;
 
             // TODO: when legit blocks are implemented use below to
             // check for reorgs
@@ -633,7 +939,7 @@ where
             // TODO: Add support for multiple commitments in a single block
 
             let mut sequencer_commitments = Vec::<SequencerCommitment>::new();
-            let mut zk_proofs = Vec::<BatchProof>::new();
+            let mut zk_proofs = Vec::<(VerifiableBatchProof, [u8; 32])>::new();
 
             self.da_service
                 .extract_relevant_blobs(&filtered_block)
@@ -652,8 +958,11 @@ where
                             );
                         }
                     } else if tx.sender().as_ref() == self.prover_da_pub_key.as_slice() {
-                        if let Ok(DaData::ZKProof(batch_proof)) = data {
-                            zk_proofs.push(batch_proof);
+                        if let Ok(DaData::ZKProof(_) | DaData::VerifiableZKProof(_)) = &data {
+                            // Proofs without the proof itself can't be verified
+                            if let Ok(DaData::VerifiableZKProof(proof)) = data {
+                                zk_proofs.push((proof, tx.hash()));
+                            }
                         } else {
                             tracing::warn!(
                                 "Found broken DA data in block 0x{}: {:?}",
@@ -667,7 +976,13 @@ where
                 });
 
             if !zk_proofs.is_empty() {
-                // TODO: Implement this
+                let verified_proofs =
+                    self.verify_proofs(zk_proofs, proof_code_commitment.as_ref())?;
+                self.record_proofs(
+                    &verified_proofs,
+                    filtered_block.header().height(),
+                    filtered_block.hash(),
+                )?;
             }
 
             // TODO here we can support multiple commitments but for now let's take the last one.
@@ -728,19 +1043,9 @@ where
                     );
                 }
 
-                for i in start_l1_height..=end_l1_height {
-                    self.ledger_db
-                        .put_soft_confirmation_status(
-                            SlotNumber(i),
-                            SoftConfirmationStatus::Finalized,
-                        )
-                        .unwrap_or_else(|_| {
-                            panic!(
-                                "Failed to put soft confirmation status in the ledger db {}",
-                                i
-                            )
-                        });
-                }
+                self.ledger_db
+                    .put_verified_commitment(start_l1_height..=end_l1_height)
+                    .expect("Failed to put soft confirmation statuses in the ledger db");
             }
 
             info!(
@@ -762,6 +1067,7 @@ where
                 Default::default(),
                 filtered_block.header(),
                 &filtered_block.validity_condition(),
+                &mut self.da_service.extract_relevant_blobs(&filtered_block),
                 &mut soft_batch.clone().into(),
             );
 
@@ -895,10 +1201,314 @@ where
             self.ledger_db.commit_slot(receipts)?;
             self.storage_manager.finalize_l2(height)?;
 
+            sync_status.current_l2_height = height;
+            self.record_sync_status(client, &mut sync_status).await;
             height += 1;
         }
     }
 
+    /// Runs the node as a light node, which follows the state proven by the ZK proofs posted to DA
+    /// instead of executing the soft confirmations.
+    ///
+    /// Proofs are verified against the configured code commitment and must continue from the
+    /// state of the node, see [`apply_verifiable_proof`](Self::apply_verifiable_proof).
+    /// Sequencer commitments are checked against the hashes of the soft confirmations they
+    /// commit to, which are downloaded from the sequencer but not executed.
+    pub async fn run_light_node_process(&mut self) -> Result<(), anyhow::Error> {
+        let Some(light_node) = &self.light_node else {
+            return Err(anyhow::anyhow!("Light node is not configured"));
+        };
+        let code_commitment: <Vm as Zkvm>::CodeCommitment =
+            serde_json::from_value(light_node.code_commitment.clone())
+                .context("Failed to parse the code commitment of the light node")?;
+        let Some(client) = self.sequencer_client.clone() else {
+            return Err(anyhow::anyhow!("Sequencer Client is not initialized"));
+        };
+
+        let mut head = match self.ledger_db.get_light_node_head()? {
+            Some(head) => {
+                self.state_root = serde_json::from_slice(&head.state_root)?;
+                head
+            }
+            None => {
+                // Scanning starts at the DA slot of the first soft batch
+                let soft_batch = loop {
+                    match client.get_soft_batch::<Da::Spec>(self.start_height).await {
+                        Ok(Some(soft_batch)) => break soft_batch,
+                        Ok(None) => debug!("Soft Batch: no batch at height {}", self.start_height),
+                        Err(e) => debug!("Soft Batch: error during RPC call: {:?}", e),
+                    }
+                    sleep(Duration::from_secs(RETRY_SLEEP)).await;
+                };
+                StoredLightNodeHead {
+                    next_l1_height: soft_batch.da_slot_height,
+                    next_l2_height: self.start_height,
+                    next_commitment_l2_height: self.start_height,
+                    state_root: serde_json::to_vec(&self.state_root)?,
+                }
+            }
+        };
+        self.start_height = head.next_l2_height;
+        let mut sync_status = SyncStatus {
+            starting_l2_height: head.next_l2_height.saturating_sub(1),
+            current_l2_height: head.next_l2_height.saturating_sub(1),
+            ..Default::default()
+        };
+        info!(
+            "Light node following the proven state from height {}",
+            head.next_l2_height
+        );
+
+        loop {
+            if self.disk_watchdog.is_low_on_disk_space() {
+                sleep(Duration::from_secs(RETRY_SLEEP)).await;
+                continue;
+            }
+
+            let last_finalized_height = self
+                .da_service
+                .get_last_finalized_block_header()
+                .await?
+                .height();
+            if head.next_l1_height > last_finalized_height {
+                self.record_sync_status(&client, &mut sync_status).await;
+                sleep(Duration::from_secs(RETRY_SLEEP)).await;
+                continue;
+            }
+
+            let filtered_block = self.da_service.get_block_at(head.next_l1_height).await?;
+
+            let mut sequencer_commitments = Vec::<SequencerCommitment>::new();
+            let mut zk_proofs = Vec::<(VerifiableBatchProof, [u8; 32])>::new();
+            for mut tx in self.da_service.extract_relevant_blobs(&filtered_block) {
+                let data = DaData::try_from_slice(tx.full_data());
+                if tx.sender().as_ref() == self.sequencer_da_pub_key.as_slice() {
+                    if let Ok(DaData::SequencerCommitment(seq_com)) = data {
+                        sequencer_commitments.push(seq_com);
+                    }
+                } else if tx.sender().as_ref() == self.prover_da_pub_key.as_slice() {
+                    // Proofs without the proof itself and the state diff can't be followed
+                    if let Ok(DaData::VerifiableZKProof(proof)) = data {
+                        zk_proofs.push((proof, tx.hash()));
+                    }
+                }
+            }
+
+            for sequencer_commitment in &sequencer_commitments {
+                head.next_commitment_l2_height = self
+                    .verify_commitment(
+                        &client,
+                        sequencer_commitment,
+                        head.next_commitment_l2_height,
+                    )
+                    .await?;
+            }
+
+            let mut applied_proofs = Vec::<(BatchProof, [u8; 32])>::new();
+            for (proof, da_txid) in zk_proofs {
+                if self
+                    .apply_verifiable_proof(&proof, &code_commitment)
+                    .await?
+                {
+                    applied_proofs.push((proof.batch_proof, da_txid));
+                }
+            }
+            if !applied_proofs.is_empty() {
+                self.record_proofs(
+                    &applied_proofs,
+                    filtered_block.header().height(),
+                    filtered_block.hash(),
+                )?;
+            }
+
+            head.next_l1_height += 1;
+            head.next_l2_height = self.start_height;
+            head.state_root = serde_json::to_vec(&self.state_root)?;
+            self.ledger_db.put_light_node_head(&head)?;
+
+            sync_status.current_l2_height = head.next_l2_height.saturating_sub(1);
+            sync_status.current_l1_height = filtered_block.header().height();
+            self.record_sync_status(&client, &mut sync_status).await;
+        }
+    }
+
+    /// Verifies a ZK proof posted to DA with the state diff it proves and, if the proof continues
+    /// from the state of the node, applies the state diff instead of executing the soft
+    /// confirmations. Returns whether the proof was applied.
+    ///
+    /// Proofs that don't verify against `code_commitment`, don't start from the state of the node,
+    /// commit to a DA block unknown to the DA layer, or whose state diff doesn't give the proven
+    /// state root are skipped.
+    pub async fn apply_verifiable_proof(
+        &mut self,
+        proof: &VerifiableBatchProof,
+        code_commitment: &<Vm as Zkvm>::CodeCommitment,
+    ) -> anyhow::Result<bool> {
+        let BatchProof {
+            l2_start_height,
+            l2_end_height,
+        } = proof.batch_proof;
+        if l2_start_height != self.start_height || l2_end_height < l2_start_height {
+            tracing::warn!(
+                "Light node: skipping proof of L2 range {}..={}, the next proven height is {}",
+                l2_start_height,
+                l2_end_height,
+                self.start_height
+            );
+            return Ok(false);
+        }
+
+        let transition = match Vm::verify_and_extract_output::<Da::Spec, Stf::StateRoot>(
+            &proof.proof,
+            code_commitment,
+        ) {
+            Ok(transition) => transition,
+            Err(e) => {
+                tracing::warn!(
+                    "Light node: invalid proof of L2 range {}..={}: {:?}",
+                    l2_start_height,
+                    l2_end_height,
+                    e
+                );
+                return Ok(false);
+            }
+        };
+        if transition.initial_state_root.as_ref() != self.state_root.as_ref() {
+            tracing::warn!(
+                "Light node: proof of L2 range {}..={} does not start from the state root 0x{}",
+                l2_start_height,
+                l2_end_height,
+                hex::encode(self.state_root.as_ref())
+            );
+            return Ok(false);
+        }
+        if let Err(e) = self
+            .da_service
+            .get_block_by_hash(transition.slot_hash.into())
+            .await
+        {
+            tracing::warn!(
+                "Light node: proof of L2 range {}..={} commits to an unknown DA block: {:?}",
+                l2_start_height,
+                l2_end_height,
+                e
+            );
+            return Ok(false);
+        }
+
+        let pre_state = self
+            .storage_manager
+            .create_storage_on_l2_height(l2_end_height)?;
+        let (state_root, change_set) = self
+            .stf
+            .apply_state_diff(pre_state, proof.state_diff.clone());
+        if state_root.as_ref() != transition.final_state_root.as_ref() {
+            tracing::warn!(
+                "Light node: state diff of L2 range {}..={} does not give the proven state root",
+                l2_start_height,
+                l2_end_height
+            );
+            return Ok(false);
+        }
+
+        self.storage_manager
+            .save_change_set_l2(l2_end_height, change_set)?;
+        self.storage_manager.finalize_l2(l2_end_height)?;
+
+        self.state_root = state_root;
+        self.start_height = l2_end_height + 1;
+        info!(
+            "Light node: applied proof of L2 range {}..={}, new state root: 0x{}",
+            l2_start_height,
+            l2_end_height,
+            hex::encode(self.state_root.as_ref())
+        );
+        Ok(true)
+    }
+
+    /// Checks a sequencer commitment against the hashes of the soft confirmations it commits to,
+    /// downloaded from the sequencer from `l2_height` on, and marks its DA slots as finalized if
+    /// they match. Returns the first L2 height after the commitment.
+    async fn verify_commitment(
+        &self,
+        client: &SequencerClient,
+        sequencer_commitment: &SequencerCommitment,
+        mut l2_height: u64,
+    ) -> anyhow::Result<u64> {
+        let start_l1_height = self
+            .da_service
+            .get_block_by_hash(sequencer_commitment.l1_start_block_hash)
+            .await?
+            .header()
+            .height();
+        let end_l1_height = self
+            .da_service
+            .get_block_by_hash(sequencer_commitment.l1_end_block_hash)
+            .await?
+            .header()
+            .height();
+
+        let mut soft_batch_hashes = vec![];
+        while let Some(soft_batch) = client.get_soft_batch::<Da::Spec>(l2_height).await? {
+            if soft_batch.da_slot_height > end_l1_height {
+                break;
+            }
+            if soft_batch.da_slot_height >= start_l1_height {
+                soft_batch_hashes.push(soft_batch.hash);
+            }
+            l2_height += 1;
+        }
+
+        let soft_batches_tree = MerkleTree::<Sha256>::from_leaves(&soft_batch_hashes);
+        if soft_batches_tree.root() == Some(sequencer_commitment.merkle_root) {
+            self.ledger_db
+                .put_verified_commitment(start_l1_height..=end_l1_height)?;
+        } else {
+            tracing::warn!(
+                "Light node: merkle root mismatch of the sequencer commitment of DA blocks {}..={}",
+                start_l1_height,
+                end_l1_height
+            );
+        }
+        Ok(l2_height)
+    }
+
+    /// Waits before polling the sequencer for the next soft batch again, or until the next
+    /// soft batch is pushed.
+    async fn wait_for_soft_batch(&self) {
+        let retry = sleep(Duration::from_secs(RETRY_SLEEP));
+        match &self.pushed_soft_batches {
+            Some(pushed_soft_batches) => {
+                tokio::select! {
+                    _ = retry => {}
+                    _ = pushed_soft_batches.wait_for_push() => {}
+                }
+            }
+            None => retry.await,
+        }
+    }
+
+    /// Records the sync progress of the node in the ledger, reported by `eth_syncing`.
+    ///
+    /// The sequencer and DA heads are only requested again once the node reaches the recorded
+    /// L2 head, so that catching up doesn't cost requests per soft batch.
+    async fn record_sync_status(&self, client: &SequencerClient, sync_status: &mut SyncStatus) {
+        if sync_status.current_l2_height >= sync_status.highest_l2_height {
+            match (
+                client.block_number().await,
+                self.da_service.get_last_finalized_block_header().await,
+            ) {
+                (Ok(l2_head), Ok(l1_head)) => {
+                    sync_status.highest_l2_height = l2_head.max(sync_status.current_l2_height);
+                    sync_status.highest_l1_height = l1_head.height();
+                }
+                (Err(e), _) => debug!("Failed to get the sequencer head: {:?}", e),
+                (_, Err(e)) => debug!("Failed to get the finalized DA head: {:?}", e),
+            }
+        }
+        self.ledger_db.set_sync_status(*sync_status);
+    }
+
     /// Allows to read current state root
     pub fn get_state_root(&self) -> &Stf::StateRoot {
         &self.state_root
@@ -0,0 +1,193 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+use anyhow::anyhow;
+use borsh::BorshSerialize;
+use jsonrpsee::types::error::INVALID_PARAMS_CODE;
+use jsonrpsee::types::ErrorObjectOwned;
+use jsonrpsee::RpcModule;
+use sequencer_client::{GetSoftBatchResponse, PushedSoftBatch};
+use sov_db::ledger_db::LedgerDB;
+use sov_modules_api::{Context, Signature, UnsignedSoftConfirmationBatch};
+use tokio::sync::Notify;
+use tracing::debug;
+
+/// Pushed soft batches kept until the node syncs up to them. Only the soft batch following
+/// the head of the node is accepted, so the queue only grows when the node stalls.
+const MAX_PUSHED_SOFT_BATCHES: usize = 16;
+
+/// Soft batches pushed to the full node by the sequencer or a relay, by L2 height.
+///
+/// The node syncs a pushed soft batch as soon as it is pushed instead of waiting for its next
+/// poll of the sequencer, and keeps polling for the soft batches that weren't pushed.
+#[derive(Debug, Default)]
+pub(crate) struct PushedSoftBatches {
+    soft_batches: Mutex<BTreeMap<u64, GetSoftBatchResponse>>,
+    pushed: Notify,
+}
+
+impl PushedSoftBatches {
+    fn insert(&self, height: u64, soft_batch: GetSoftBatchResponse) {
+        {
+            let mut soft_batches = self.soft_batches.lock().unwrap();
+            if soft_batches.len() >= MAX_PUSHED_SOFT_BATCHES {
+                soft_batches.pop_first();
+            }
+            soft_batches.insert(height, soft_batch);
+        }
+        self.pushed.notify_one();
+    }
+
+    /// Takes the pushed soft batch at `height`, dropping the ones below it which were
+    /// synced by polling.
+    pub(crate) fn take(&self, height: u64) -> Option<GetSoftBatchResponse> {
+        let mut soft_batches = self.soft_batches.lock().unwrap();
+        *soft_batches = soft_batches.split_off(&height);
+        soft_batches.remove(&height)
+    }
+
+    /// Waits until a soft batch is pushed. Returns immediately if one was pushed since
+    /// the last wait.
+    pub(crate) async fn wait_for_push(&self) {
+        self.pushed.notified().await
+    }
+}
+
+struct PushRpcContext {
+    pushed_soft_batches: Arc<PushedSoftBatches>,
+    ledger_db: LedgerDB,
+    sequencer_pub_key: Vec<u8>,
+}
+
+/// Creates the RPC module serving `citrea_pushSoftBatch`, which queues the pushed soft batches
+/// once their signatures and their order are verified.
+pub(crate) fn create_push_rpc_module<C: Context>(
+    pushed_soft_batches: Arc<PushedSoftBatches>,
+    ledger_db: LedgerDB,
+    sequencer_pub_key: Vec<u8>,
+) -> Result<RpcModule<()>, jsonrpsee::core::Error> {
+    let mut rpc = RpcModule::new(PushRpcContext {
+        pushed_soft_batches,
+        ledger_db,
+        sequencer_pub_key,
+    });
+    rpc.register_method("citrea_pushSoftBatch", |parameters, ctx| {
+        let pushed: PushedSoftBatch = parameters.one()?;
+        debug!("Full node: citrea_pushSoftBatch({})", pushed.height);
+
+        verify_pushed_soft_batch::<C>(&pushed, &ctx.sequencer_pub_key)
+            .and_then(|()| check_order(&pushed, &ctx.ledger_db))
+            .map_err(|e| ErrorObjectOwned::owned(INVALID_PARAMS_CODE, e.to_string(), None::<()>))?;
+
+        ctx.pushed_soft_batches
+            .insert(pushed.height, pushed.soft_batch);
+        Ok::<(), ErrorObjectOwned>(())
+    })?;
+
+    let mut methods = RpcModule::new(());
+    methods.merge(rpc)?;
+    Ok(methods)
+}
+
+/// Verifies that the sequencer signed the soft batch and pushed it at its height. Soft batches
+/// with invalid signatures would otherwise halt the node when they are applied.
+fn verify_pushed_soft_batch<C: Context>(
+    pushed: &PushedSoftBatch,
+    sequencer_pub_key: &[u8],
+) -> anyhow::Result<()> {
+    let soft_batch = &pushed.soft_batch;
+    anyhow::ensure!(
+        soft_batch.pub_key == sequencer_pub_key,
+        "Soft batch is not signed by the sequencer"
+    );
+    let public_key = C::PublicKey::try_from(sequencer_pub_key)?;
+
+    let unsigned = UnsignedSoftConfirmationBatch::new(
+        soft_batch.da_slot_height,
+        soft_batch.da_slot_hash,
+        soft_batch.da_slot_txs_commitment,
+        soft_batch.pre_state_root.clone(),
+        soft_batch
+            .txs
+            .iter()
+            .flatten()
+            .map(|tx| tx.tx.clone())
+            .collect(),
+        soft_batch.l1_fee_rate,
+        soft_batch.timestamp,
+    );
+    C::Signature::try_from(soft_batch.soft_confirmation_signature.as_slice())?
+        .verify(&public_key, &unsigned.try_to_vec()?)
+        .map_err(|_| anyhow!("Invalid soft confirmation signature"))?;
+
+    C::Signature::try_from(pushed.push_signature.as_slice())?
+        .verify(
+            &public_key,
+            &PushedSoftBatch::message(pushed.height, soft_batch),
+        )
+        .map_err(|_| anyhow!("Invalid push signature"))?;
+
+    Ok(())
+}
+
+/// Checks that the pushed soft batch follows the head of the node.
+fn check_order(pushed: &PushedSoftBatch, ledger_db: &LedgerDB) -> anyhow::Result<()> {
+    let next_height = ledger_db.get_next_items_numbers().soft_batch_number;
+    anyhow::ensure!(
+        pushed.height == next_height,
+        "Soft batch {} doesn't follow the head of the node, expected soft batch {}",
+        pushed.height,
+        next_height
+    );
+
+    if let Some((_, head)) = ledger_db.get_head_soft_batch()? {
+        anyhow::ensure!(
+            head.post_state_root == pushed.soft_batch.pre_state_root,
+            "Soft batch {} doesn't build on the state root of the head of the node",
+            pushed.height
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn soft_batch(hash: u8) -> GetSoftBatchResponse {
+        GetSoftBatchResponse {
+            hash: [hash; 32],
+            da_slot_height: 1,
+            da_slot_hash: [0; 32],
+            da_slot_txs_commitment: [0; 32],
+            txs: None,
+            pre_state_root: vec![],
+            post_state_root: vec![],
+            soft_confirmation_signature: vec![],
+            pub_key: vec![],
+            l1_fee_rate: 0,
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn takes_pushed_soft_batches_in_order() {
+        let pushed = PushedSoftBatches::default();
+        pushed.insert(3, soft_batch(3));
+        pushed.insert(4, soft_batch(4));
+        pushed.insert(5, soft_batch(5));
+
+        assert!(pushed.take(2).is_none());
+        // soft batch 3 was synced by polling
+        assert_eq!(pushed.take(4).unwrap().hash, [4; 32]);
+        assert!(pushed.take(3).is_none());
+        assert_eq!(pushed.take(5).unwrap().hash, [5; 32]);
+        assert!(pushed.take(5).is_none());
+
+        for height in 0..MAX_PUSHED_SOFT_BATCHES as u64 + 1 {
+            pushed.insert(height, soft_batch(height as u8));
+        }
+        assert!(pushed.take(0).is_none());
+        assert!(pushed.take(1).is_some());
+    }
+}
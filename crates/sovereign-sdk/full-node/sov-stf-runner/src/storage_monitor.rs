@@ -0,0 +1,188 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use sov_db::ledger_db::LedgerDB;
+use tokio::time::{sleep, Duration};
+use tracing::{debug, error, info, warn};
+
+use crate::metrics::{
+    STORAGE_DIRECTORY_SIZE_BYTES, STORAGE_FREE_DISK_SPACE_BYTES, STORAGE_LOW_DISK_SPACE,
+};
+use crate::StorageConfig;
+
+/// Shared flag telling whether the node is low on disk space.
+/// Writers check it to stop gracefully instead of letting RocksDB fail on a full disk.
+///
+/// The default watchdog never reports low disk space.
+#[derive(Clone, Debug, Default)]
+pub struct DiskWatchdog {
+    low_disk_space: Arc<AtomicBool>,
+}
+
+impl DiskWatchdog {
+    /// Returns true if free disk space is under the configured minimum.
+    pub fn is_low_on_disk_space(&self) -> bool {
+        self.low_disk_space.load(Ordering::Relaxed)
+    }
+
+    fn set_low_on_disk_space(&self, low: bool) {
+        let was_low = self.low_disk_space.swap(low, Ordering::Relaxed);
+        if low && !was_low {
+            error!("Free disk space is under the configured minimum, pausing writes");
+        } else if !low && was_low {
+            info!("Free disk space is back over the configured minimum, resuming writes");
+        }
+        STORAGE_LOW_DISK_SPACE.set(low as i64);
+    }
+}
+
+/// Periodically measures the size of the node's databases and the free disk space,
+/// records them in the storage metrics and updates the [`DiskWatchdog`].
+#[derive(Clone)]
+pub struct StorageMonitor {
+    path: PathBuf,
+    min_free_disk_space: Option<u64>,
+    check_interval: Duration,
+    ledger_db: LedgerDB,
+    watchdog: DiskWatchdog,
+}
+
+impl StorageMonitor {
+    /// Creates a monitor of the storage configured by `config`.
+    pub fn new(config: &StorageConfig, ledger_db: LedgerDB) -> Self {
+        Self {
+            path: config.path.clone(),
+            min_free_disk_space: config
+                .min_free_disk_space_mb
+                .map(|megabytes| megabytes * 1024 * 1024),
+            check_interval: Duration::from_secs(config.check_interval_secs.max(1)),
+            ledger_db,
+            watchdog: DiskWatchdog::default(),
+        }
+    }
+
+    /// Returns the watchdog updated by the monitor.
+    pub fn watchdog(&self) -> DiskWatchdog {
+        self.watchdog.clone()
+    }
+
+    /// Takes a first measurement, then keeps measuring in the background.
+    /// Returns the watchdog updated by the monitor.
+    pub fn spawn(self) -> DiskWatchdog {
+        self.check();
+        let watchdog = self.watchdog();
+        tokio::spawn(async move {
+            loop {
+                sleep(self.check_interval).await;
+                let monitor = self.clone();
+                if let Err(e) = tokio::task::spawn_blocking(move || monitor.check()).await {
+                    error!("Storage monitor: measurement failed: {:?}", e);
+                }
+            }
+        });
+        watchdog
+    }
+
+    /// Measures the storage and updates the watchdog.
+    pub fn check(&self) {
+        if let Ok(entries) = std::fs::read_dir(&self.path) {
+            for entry in entries.flatten() {
+                if entry.file_type().is_ok_and(|file_type| file_type.is_dir()) {
+                    let size = directory_size(&entry.path());
+                    STORAGE_DIRECTORY_SIZE_BYTES
+                        .with_label_values(&[&entry.file_name().to_string_lossy()])
+                        .set(size as i64);
+                }
+            }
+        }
+
+        if let Err(e) = self.ledger_db.report_table_sizes() {
+            warn!("Storage monitor: failed to measure ledger tables: {:?}", e);
+        }
+
+        let free_disk_space = match fs2::available_space(&self.path) {
+            Ok(free_disk_space) => free_disk_space,
+            Err(e) => {
+                warn!(
+                    "Storage monitor: failed to read free disk space of {}: {:?}",
+                    self.path.display(),
+                    e
+                );
+                return;
+            }
+        };
+        STORAGE_FREE_DISK_SPACE_BYTES.set(free_disk_space as i64);
+        debug!(
+            "Storage monitor: {} bytes of free disk space",
+            free_disk_space
+        );
+
+        if let Some(min_free_disk_space) = self.min_free_disk_space {
+            self.watchdog
+                .set_low_on_disk_space(free_disk_space < min_free_disk_space);
+        }
+    }
+}
+
+/// Returns the total size of the files in a directory and its subdirectories.
+/// Files removed while walking the directory, e.g. by RocksDB compactions, are skipped.
+fn directory_size(path: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => directory_size(&entry.path()),
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn storage_config(path: &Path, min_free_disk_space_mb: Option<u64>) -> StorageConfig {
+        StorageConfig {
+            path: path.to_path_buf(),
+            min_free_disk_space_mb,
+            check_interval_secs: 60,
+        }
+    }
+
+    #[test]
+    fn directory_size_includes_subdirectories() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a"), [0u8; 10]).unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        std::fs::write(dir.path().join("sub").join("b"), [0u8; 5]).unwrap();
+
+        assert_eq!(directory_size(dir.path()), 15);
+        assert_eq!(directory_size(&dir.path().join("missing")), 0);
+    }
+
+    #[test]
+    fn watchdog_reports_low_disk_space() {
+        let dir = tempfile::tempdir().unwrap();
+        let ledger_db = LedgerDB::with_path(dir.path()).unwrap();
+
+        let monitor = StorageMonitor::new(&storage_config(dir.path(), None), ledger_db.clone());
+        monitor.check();
+        assert!(!monitor.watchdog().is_low_on_disk_space());
+
+        // No disk has this much free space
+        let monitor = StorageMonitor::new(
+            &storage_config(dir.path(), Some(u64::MAX / (1024 * 1024))),
+            ledger_db.clone(),
+        );
+        monitor.check();
+        assert!(monitor.watchdog().is_low_on_disk_space());
+
+        let monitor = StorageMonitor::new(&storage_config(dir.path(), Some(0)), ledger_db);
+        monitor.check();
+        assert!(!monitor.watchdog().is_low_on_disk_space());
+    }
+}
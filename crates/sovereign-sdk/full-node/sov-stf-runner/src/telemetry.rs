@@ -0,0 +1,201 @@
+use std::io::ErrorKind;
+use std::path::Path;
+
+use anyhow::Context;
+use serde::Serialize;
+use sov_db::ledger_db::LedgerDB;
+use tokio::time::{sleep, Duration, Instant};
+use tracing::{debug, info, warn};
+
+use crate::metrics::STORAGE_FREE_DISK_SPACE_BYTES;
+use crate::TelemetryConfig;
+
+/// File storing the node id, in the storage directory of the node.
+const NODE_ID_FILE: &str = "node_id";
+
+/// Returns the id of the node storing its data at `path`, generated and persisted on the
+/// first start so that it is stable across restarts.
+pub fn load_or_create_node_id(path: &Path) -> anyhow::Result<String> {
+    let file = path.join(NODE_ID_FILE);
+    match std::fs::read_to_string(&file) {
+        Ok(node_id) if !node_id.trim().is_empty() => return Ok(node_id.trim().to_string()),
+        Ok(_) => {}
+        Err(e) if e.kind() == ErrorKind::NotFound => {}
+        Err(e) => {
+            return Err(e)
+                .with_context(|| format!("Failed to read node id from {}", file.display()))
+        }
+    }
+
+    let node_id = hex::encode(rand::random::<[u8; 16]>());
+    std::fs::create_dir_all(path)?;
+    std::fs::write(&file, &node_id)
+        .with_context(|| format!("Failed to write node id to {}", file.display()))?;
+    Ok(node_id)
+}
+
+/// Report periodically sent to the telemetry endpoint.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TelemetryReport {
+    /// Stable id of the node, see [`load_or_create_node_id`].
+    pub node_id: String,
+    /// Version of the node software.
+    pub version: String,
+    /// `sequencer`, `full-node` or `prover`.
+    pub mode: String,
+    /// Height of the latest soft batch.
+    pub l2_height: u64,
+    /// L1 height of the latest soft batch.
+    pub l1_height: u64,
+    /// Soft batches produced or synced per minute since the previous report.
+    pub l2_blocks_per_minute: f64,
+    /// Seconds since the node started.
+    pub uptime_secs: u64,
+    /// Free disk space available to the node's storage, in gigabytes.
+    pub free_disk_space_gb: u64,
+}
+
+/// Sends a [`TelemetryReport`] to the configured endpoint at every interval.
+///
+/// Reports only contain the sync state of the node and coarse performance stats, no addresses,
+/// transactions or keys. Failures to report are logged and never affect the node.
+pub struct TelemetryReporter {
+    client: reqwest::Client,
+    config: TelemetryConfig,
+    node_id: String,
+    mode: &'static str,
+    ledger_db: LedgerDB,
+    started_at: Instant,
+}
+
+impl TelemetryReporter {
+    /// Creates a reporter for a node running in `mode`.
+    pub fn new(
+        config: TelemetryConfig,
+        node_id: String,
+        mode: &'static str,
+        ledger_db: LedgerDB,
+    ) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            config,
+            node_id,
+            mode,
+            ledger_db,
+            started_at: Instant::now(),
+        }
+    }
+
+    /// Keeps reporting in the background.
+    pub fn spawn(self) {
+        info!(
+            "Sending telemetry of node {} to {} every {} seconds",
+            self.node_id, self.config.endpoint, self.config.interval_secs
+        );
+        let interval = Duration::from_secs(self.config.interval_secs.max(1));
+        tokio::spawn(async move {
+            let mut previous = (self.started_at, self.l2_height());
+            loop {
+                sleep(interval).await;
+                let report = match self.report(previous) {
+                    Ok(report) => report,
+                    Err(e) => {
+                        warn!("Telemetry: failed to build report: {:?}", e);
+                        continue;
+                    }
+                };
+                previous = (Instant::now(), report.l2_height);
+
+                match self.send(&report).await {
+                    Ok(()) => debug!("Telemetry: sent {:?}", report),
+                    Err(e) => debug!("Telemetry: failed to send report: {:?}", e),
+                }
+            }
+        });
+    }
+
+    fn l2_height(&self) -> u64 {
+        self.ledger_db
+            .get_head_soft_batch()
+            .ok()
+            .flatten()
+            .map_or(0, |(number, _)| number.0)
+    }
+
+    /// Builds a report, with the sync rate since `previous`, the time and L2 height
+    /// of the previous report.
+    fn report(&self, previous: (Instant, u64)) -> anyhow::Result<TelemetryReport> {
+        let (l2_height, l1_height) = match self.ledger_db.get_head_soft_batch()? {
+            Some((number, soft_batch)) => (number.0, soft_batch.da_slot_height),
+            None => (0, 0),
+        };
+        let (previous_time, previous_l2_height) = previous;
+        let minutes = previous_time.elapsed().as_secs_f64() / 60.0;
+        let l2_blocks_per_minute = match minutes > 0.0 {
+            true => l2_height.saturating_sub(previous_l2_height) as f64 / minutes,
+            false => 0.0,
+        };
+
+        Ok(TelemetryReport {
+            node_id: self.node_id.clone(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            mode: self.mode.to_string(),
+            l2_height,
+            l1_height,
+            l2_blocks_per_minute,
+            uptime_secs: self.started_at.elapsed().as_secs(),
+            free_disk_space_gb: STORAGE_FREE_DISK_SPACE_BYTES.get().max(0) as u64
+                / (1024 * 1024 * 1024),
+        })
+    }
+
+    async fn send(&self, report: &TelemetryReport) -> anyhow::Result<()> {
+        self.client
+            .post(&self.config.endpoint)
+            .timeout(Duration::from_secs(10))
+            .json(report)
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn node_id_is_persisted() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("storage");
+
+        let node_id = load_or_create_node_id(&path).unwrap();
+        assert_eq!(node_id.len(), 32);
+        assert_eq!(load_or_create_node_id(&path).unwrap(), node_id);
+
+        let other_dir = tempfile::tempdir().unwrap();
+        assert_ne!(load_or_create_node_id(other_dir.path()).unwrap(), node_id);
+    }
+
+    #[test]
+    fn report_of_empty_ledger() {
+        let dir = tempfile::tempdir().unwrap();
+        let reporter = TelemetryReporter::new(
+            TelemetryConfig {
+                endpoint: "http://localhost:1".to_string(),
+                interval_secs: 60,
+            },
+            "node".to_string(),
+            "full-node",
+            LedgerDB::with_path(dir.path()).unwrap(),
+        );
+
+        let report = reporter.report((Instant::now(), 0)).unwrap();
+        assert_eq!(report.node_id, "node");
+        assert_eq!(report.mode, "full-node");
+        assert_eq!(report.l2_height, 0);
+        assert_eq!(report.l2_blocks_per_minute, 0.0);
+    }
+}
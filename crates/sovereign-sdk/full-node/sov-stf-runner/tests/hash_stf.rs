@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use sha2::Digest;
 use sov_mock_da::{
     MockAddress, MockBlob, MockBlock, MockBlockHeader, MockDaSpec, MockValidityCond,
@@ -6,10 +8,10 @@ use sov_mock_zkvm::MockZkvm;
 use sov_modules_api::Context;
 use sov_modules_stf_blueprint::StfBlueprintTrait;
 use sov_prover_storage_manager::{new_orphan_storage, SnapshotManager};
-use sov_rollup_interface::da::{BlobReaderTrait, BlockHeaderTrait, DaSpec};
+use sov_rollup_interface::da::{BlobReaderTrait, BlockHeaderTrait, DaSpec, StateDiff};
 use sov_rollup_interface::stf::{SlotResult, StateTransitionFunction};
 use sov_rollup_interface::zk::{ValidityCondition, Zkvm};
-use sov_state::storage::{NativeStorage, StorageKey, StorageValue};
+use sov_state::storage::{CacheKey, NativeStorage, StorageKey, StorageValue};
 use sov_state::{
     ArrayWitness, DefaultStorageSpec, OrderedReadsAndWrites, Prefix, ProverStorage, Storage,
 };
@@ -75,6 +77,7 @@ impl<C: Context, Da: DaSpec, Vm: Zkvm, Cond: ValidityCondition> StfBlueprintTrai
         _pre_state: Self::PreState,
         _witness: <<C as sov_modules_api::Spec>::Storage as Storage>::Witness,
         _slot_header: &<Da as DaSpec>::BlockHeader,
+        _slot_blobs: &mut [<Da as DaSpec>::BlobTransaction],
         _soft_batch: &mut sov_modules_api::SignedSoftConfirmationBatch,
     ) -> (
         Result<(), sov_modules_api::hooks::ApplySoftConfirmationError>,
@@ -209,6 +212,7 @@ impl<Vm: Zkvm, Cond: ValidityCondition, Da: DaSpec> StateTransitionFunction<Vm,
         _witness: Self::Witness,
         _slot_header: &<Da as DaSpec>::BlockHeader,
         _validity_condition: &<Da as DaSpec>::ValidityCondition,
+        _slot_blobs: &mut [<Da as DaSpec>::BlobTransaction],
         _soft_batch: &mut sov_modules_api::SignedSoftConfirmationBatch,
     ) -> SlotResult<
         Self::StateRoot,
@@ -219,6 +223,33 @@ impl<Vm: Zkvm, Cond: ValidityCondition, Da: DaSpec> StateTransitionFunction<Vm,
     > {
         todo!()
     }
+
+    fn apply_state_diff(
+        &self,
+        storage: Self::PreState,
+        state_diff: StateDiff,
+    ) -> (Self::StateRoot, Self::ChangeSet) {
+        let ordered_reads_writes = OrderedReadsAndWrites {
+            ordered_reads: Vec::default(),
+            ordered_writes: state_diff
+                .into_iter()
+                .map(|(key, value)| {
+                    (
+                        CacheKey { key: Arc::new(key) },
+                        value.map(|value| StorageValue::from(value).into_cache_value()),
+                    )
+                })
+                .collect(),
+        };
+
+        let (jmt_root_hash, state_update) = storage
+            .compute_state_update(ordered_reads_writes, &ArrayWitness::default())
+            .unwrap();
+
+        storage.commit(&state_update, &OrderedReadsAndWrites::default());
+
+        (jmt_root_hash.0, storage)
+    }
 }
 
 #[test]
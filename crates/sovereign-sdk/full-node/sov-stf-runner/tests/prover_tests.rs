@@ -1,13 +1,18 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use async_trait::async_trait;
 use sov_mock_da::{
     MockBlockHeader, MockDaService, MockDaSpec, MockDaVerifier, MockHash, MockValidityCond,
 };
-use sov_mock_zkvm::MockZkvm;
+use sov_mock_zkvm::{MockCodeCommitment, MockProof, MockZkvm};
 use sov_rollup_interface::da::Time;
-use sov_rollup_interface::zk::StateTransitionData;
+use sov_rollup_interface::zk::{StateTransition, StateTransitionData};
 use sov_stf_runner::mock::MockStf;
 use sov_stf_runner::{
-    ParallelProverService, ProofProcessingStatus, ProofSubmissionStatus, ProverService,
-    ProverServiceConfig, ProverServiceError, RollupProverConfig, WitnessSubmissionStatus,
+    ParallelProverService, ProofMarket, ProofMarketConfig, ProofMarketJobStatus,
+    ProofProcessingStatus, ProofSubmissionStatus, ProverService, ProverServiceConfig,
+    ProverServiceError, RollupProverConfig, WitnessSubmissionStatus,
 };
 
 #[tokio::test]
@@ -162,6 +167,151 @@ async fn test_generate_multiple_proofs_for_the_same_witness() -> Result<(), anyh
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_outsourced_proving() -> Result<(), anyhow::Error> {
+    let market = Arc::new(MockProofMarket::proving_for(MockCodeCommitment([1; 32])));
+    let TestProver { prover_service, .. } = make_new_outsourcing_prover(market.clone());
+
+    let header_hash = MockHash::from([0; 32]);
+    prover_service
+        .submit_witness(make_transition_data(header_hash))
+        .await;
+    prover_service.prove(header_hash).await?;
+
+    // The local prover is never unblocked, the proof comes from the market.
+    assert_proof_submitted(header_hash, &prover_service).await;
+    assert_eq!(market.submitted_jobs.load(Ordering::SeqCst), 1);
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_outsourced_proving_falls_back_to_local_prover() -> Result<(), anyhow::Error> {
+    // The market proves another guest than the expected one.
+    let market = Arc::new(MockProofMarket::proving_for(MockCodeCommitment([2; 32])));
+    let TestProver {
+        prover_service, vm, ..
+    } = make_new_outsourcing_prover(market.clone());
+
+    let header_hash = MockHash::from([0; 32]);
+    prover_service
+        .submit_witness(make_transition_data(header_hash))
+        .await;
+    prover_service.prove(header_hash).await?;
+
+    tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+    assert_eq!(market.submitted_jobs.load(Ordering::SeqCst), 1);
+    assert_eq!(
+        ProofSubmissionStatus::ProofGenerationInProgress,
+        prover_service.send_proof_to_da(header_hash).await?
+    );
+
+    vm.make_proof();
+    assert_proof_submitted(header_hash, &prover_service).await;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_self_check_accepts_proof_of_the_slot() -> Result<(), anyhow::Error> {
+    let header_hash = MockHash::from([0; 32]);
+    let market = Arc::new(
+        MockProofMarket::proving_for(MockCodeCommitment([1; 32]))
+            .with_output(transition_output(header_hash)),
+    );
+    let TestProver { prover_service, .. } = make_new_outsourcing_prover(market);
+    let prover_service = prover_service.with_self_check(MockCodeCommitment([1; 32]));
+
+    prover_service
+        .submit_witness(make_transition_data(header_hash))
+        .await;
+    prover_service.prove(header_hash).await?;
+
+    assert_proof_submitted(header_hash, &prover_service).await;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_self_check_quarantines_proof_of_another_slot() -> Result<(), anyhow::Error> {
+    let header_hash = MockHash::from([0; 32]);
+    let market = Arc::new(
+        MockProofMarket::proving_for(MockCodeCommitment([1; 32]))
+            .with_output(transition_output(MockHash::from([9; 32]))),
+    );
+    let TestProver { prover_service, .. } = make_new_outsourcing_prover(market);
+    let prover_service = prover_service.with_self_check(MockCodeCommitment([1; 32]));
+
+    prover_service
+        .submit_witness(make_transition_data(header_hash))
+        .await;
+    prover_service.prove(header_hash).await?;
+
+    for _ in 0..50 {
+        match prover_service.send_proof_to_da(header_hash).await {
+            Ok(ProofSubmissionStatus::ProofGenerationInProgress) => {
+                tokio::time::sleep(tokio::time::Duration::from_millis(100)).await
+            }
+            Ok(ProofSubmissionStatus::Success) => panic!("Mismatched proof was submitted"),
+            Err(e) => {
+                assert!(e.to_string().contains("quarantined"), "{}", e);
+                return Ok(());
+            }
+        }
+    }
+    panic!("Proof for {} was not generated", header_hash);
+}
+
+struct MockProofMarket {
+    program_id: MockCodeCommitment,
+    output: Vec<u8>,
+    submitted_jobs: AtomicUsize,
+}
+
+impl MockProofMarket {
+    fn proving_for(program_id: MockCodeCommitment) -> Self {
+        Self {
+            program_id,
+            output: vec![],
+            submitted_jobs: AtomicUsize::new(0),
+        }
+    }
+
+    fn with_output(mut self, output: Vec<u8>) -> Self {
+        self.output = output;
+        self
+    }
+}
+
+/// Public output of a proof of the empty state transition of the slot `slot_hash`.
+fn transition_output(slot_hash: MockHash) -> Vec<u8> {
+    bincode::serialize(&StateTransition::<MockDaSpec, [u8; 0]> {
+        initial_state_root: [],
+        final_state_root: [],
+        slot_hash,
+        validity_condition: MockValidityCond::default(),
+    })
+    .unwrap()
+}
+
+#[async_trait]
+impl ProofMarket for MockProofMarket {
+    async fn submit(&self, input: Vec<u8>) -> anyhow::Result<String> {
+        assert!(!input.is_empty());
+        let job = self.submitted_jobs.fetch_add(1, Ordering::SeqCst);
+        Ok(job.to_string())
+    }
+
+    async fn poll(&self, _job_id: &str) -> anyhow::Result<ProofMarketJobStatus> {
+        let proof = MockProof {
+            program_id: self.program_id.clone(),
+            is_valid: true,
+            log: &self.output,
+        };
+        Ok(ProofMarketJobStatus::Succeeded(proof.encode_to_vec()))
+    }
+}
+
 struct TestProver {
     prover_service: ParallelProverService<
         [u8; 0],
@@ -210,6 +360,9 @@ fn make_new_prover() -> TestProver {
             num_threads,
             ProverServiceConfig {
                 aggregated_proof_block_jump: 1,
+                wrap_proofs: false,
+                proof_market: None,
+                memory_watchdog: None,
             },
         ),
         vm,
@@ -217,6 +370,58 @@ fn make_new_prover() -> TestProver {
     }
 }
 
+fn make_new_outsourcing_prover(market: Arc<MockProofMarket>) -> TestProver {
+    let num_threads = num_cpus::get();
+    let vm = MockZkvm::new(MockValidityCond::default());
+    let market_config = ProofMarketConfig {
+        url: "http://localhost".to_string(),
+        api_key: None,
+        image_id: "mock".to_string(),
+        poll_interval_secs: 1,
+        job_timeout_secs: 10,
+    };
+
+    TestProver {
+        prover_service: ParallelProverService::new(
+            vm.clone(),
+            MockStf::<MockValidityCond>::default(),
+            MockDaVerifier::default(),
+            RollupProverConfig::Prove,
+            (),
+            num_threads,
+            ProverServiceConfig {
+                aggregated_proof_block_jump: 1,
+                wrap_proofs: false,
+                proof_market: Some(market_config.clone()),
+                memory_watchdog: None,
+            },
+        )
+        .with_proof_market(market, &market_config, MockCodeCommitment([1; 32])),
+        vm,
+        num_worker_threads: num_threads,
+    }
+}
+
+async fn assert_proof_submitted(
+    header_hash: MockHash,
+    prover_service: &ParallelProverService<
+        [u8; 0],
+        Vec<u8>,
+        MockDaService,
+        MockZkvm<MockValidityCond>,
+        MockStf<MockValidityCond>,
+    >,
+) {
+    for _ in 0..50 {
+        let status = prover_service.send_proof_to_da(header_hash).await;
+        if let Ok(ProofSubmissionStatus::Success) = status {
+            return;
+        }
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await
+    }
+    panic!("Proof for {} was not generated", header_hash);
+}
+
 fn make_transition_data(
     header_hash: MockHash,
 ) -> StateTransitionData<[u8; 0], Vec<u8>, MockDaSpec> {
@@ -2,9 +2,13 @@ use sov_db::ledger_db::LedgerDB;
 use sov_mock_da::{
     MockAddress, MockDaConfig, MockDaService, MockDaSpec, MockDaVerifier, MockValidityCond,
 };
-use sov_mock_zkvm::MockZkvm;
-use sov_prover_storage_manager::ProverStorageManager;
+use sov_mock_zkvm::{MockCodeCommitment, MockProof, MockZkvm};
+use sov_prover_storage_manager::{new_orphan_storage, ProverStorageManager};
+use sov_rollup_interface::da::{BatchProof, BlockHeaderTrait, StateDiff, VerifiableBatchProof};
+use sov_rollup_interface::services::da::{DaService, SlotData};
+use sov_rollup_interface::stf::StateTransitionFunction;
 use sov_rollup_interface::storage::HierarchicalStorageManager;
+use sov_rollup_interface::zk::StateTransition;
 use sov_state::{ArrayWitness, DefaultStorageSpec};
 use sov_stf_runner::{
     InitVariant, ParallelProverService, ProverServiceConfig, RollupConfig, RollupProverConfig,
@@ -28,19 +32,106 @@ async fn init_and_restart() {
     let init_variant: MockInitVariant = InitVariant::Genesis(genesis_params);
 
     let state_root_after_genesis = {
-        let runner = initialize_runner(tmpdir.path(), init_variant);
+        let runner = initialize_runner(
+            tmpdir.path(),
+            init_variant,
+            MockDaService::new(MockAddress::new([11u8; 32])),
+        );
         *runner.get_state_root()
     };
 
     let init_variant_2: MockInitVariant = InitVariant::Initialized(state_root_after_genesis);
 
-    let runner_2 = initialize_runner(tmpdir.path(), init_variant_2);
+    let runner_2 = initialize_runner(
+        tmpdir.path(),
+        init_variant_2,
+        MockDaService::new(MockAddress::new([11u8; 32])),
+    );
 
     let state_root_2 = *runner_2.get_state_root();
 
     assert_eq!(state_root_after_genesis, state_root_2);
 }
 
+#[tokio::test]
+async fn light_node_applies_proven_state_diffs() {
+    let tmpdir = tempfile::tempdir().unwrap();
+    let genesis_params = vec![1, 2, 3, 4, 5];
+    let da_service = MockDaService::new(MockAddress::new([11u8; 32]));
+    da_service.send_transaction(&[1, 1, 1]).await.unwrap();
+    let slot_hash = da_service.get_block_at(1).await.unwrap().header().hash();
+
+    let mut runner = initialize_runner(
+        tmpdir.path(),
+        InitVariant::Genesis(genesis_params.clone()),
+        da_service,
+    );
+    let initial_state_root = *runner.get_state_root();
+
+    let state_diff: StateDiff = vec![(vec![1, 2], Some(vec![3, 4])), (vec![5], None)];
+    let final_state_root = {
+        let other_tmpdir = tempfile::tempdir().unwrap();
+        let stf = HashStf::<MockValidityCond>::new();
+        let storage = new_orphan_storage(other_tmpdir.path()).unwrap();
+        let (_, storage) = <HashStf<MockValidityCond> as StateTransitionFunction<
+            MockZkvm<MockValidityCond>,
+            MockDaSpec,
+        >>::init_chain(&stf, storage, genesis_params);
+        <HashStf<MockValidityCond> as StateTransitionFunction<
+            MockZkvm<MockValidityCond>,
+            MockDaSpec,
+        >>::apply_state_diff(&stf, storage, state_diff.clone())
+        .0
+    };
+    assert_ne!(initial_state_root, final_state_root);
+
+    let code_commitment = MockCodeCommitment([1u8; 32]);
+    let verifiable_proof = |l2_start_height, state_diff: StateDiff| {
+        let transition = StateTransition::<MockDaSpec, [u8; 32]> {
+            initial_state_root,
+            final_state_root,
+            slot_hash,
+            validity_condition: MockValidityCond::default(),
+        };
+        let log = bincode::serialize(&transition).unwrap();
+        VerifiableBatchProof {
+            batch_proof: BatchProof {
+                l2_start_height,
+                l2_end_height: l2_start_height + 1,
+            },
+            proof: MockProof {
+                program_id: code_commitment.clone(),
+                is_valid: true,
+                log: &log,
+            }
+            .encode_to_vec(),
+            state_diff,
+        }
+    };
+
+    // The proof does not start at the next L2 height of the node.
+    let applied = runner
+        .apply_verifiable_proof(&verifiable_proof(2, state_diff.clone()), &code_commitment)
+        .await
+        .unwrap();
+    assert!(!applied);
+
+    // The state diff does not give the proven state root.
+    let applied = runner
+        .apply_verifiable_proof(&verifiable_proof(1, vec![]), &code_commitment)
+        .await
+        .unwrap();
+    assert!(!applied);
+    assert_eq!(initial_state_root, *runner.get_state_root());
+
+    let applied = runner
+        .apply_verifiable_proof(&verifiable_proof(1, state_diff), &code_commitment)
+        .await
+        .unwrap();
+    assert!(applied);
+    assert_eq!(final_state_root, *runner.get_state_root());
+}
+
 type MockProverService = ParallelProverService<
     [u8; 32],
     ArrayWitness,
@@ -51,6 +142,7 @@ type MockProverService = ParallelProverService<
 fn initialize_runner(
     path: &std::path::Path,
     init_variant: MockInitVariant,
+    da_service: MockDaService,
 ) -> StateTransitionRunner<
     HashStf<MockValidityCond>,
     StorageManager,
@@ -64,6 +156,8 @@ fn initialize_runner(
         sequencer_public_key: vec![0u8; 32],
         storage: StorageConfig {
             path: path.to_path_buf(),
+            min_free_disk_space_mb: None,
+            check_interval_secs: 60,
         },
         runner: RunnerConfig {
             start_height: 1,
@@ -71,6 +165,18 @@ fn initialize_runner(
                 bind_host: "127.0.0.1".to_string(),
                 bind_port: 0,
                 max_connections: 100,
+                abi_registry_path: None,
+                contract_verification_path: None,
+                response_etags: false,
+                allowed_methods: None,
+                gas_price_oracle: None,
+                archive: true,
+                state_history_blocks: 128,
+                batch_requests_limit: 100,
+                batch_weight_limit: 1000,
+                method_weights: Default::default(),
+                index_transactions_by_address: false,
+                accept_pushed_soft_batches: false,
             },
         },
         da: MockDaConfig {
@@ -78,15 +184,20 @@ fn initialize_runner(
         },
         prover_service: ProverServiceConfig {
             aggregated_proof_block_jump: 1,
+            wrap_proofs: false,
+            proof_market: None,
+            memory_watchdog: None,
         },
         sequencer_client: None,
         sequencer_da_pub_key: vec![],
         prover_da_pub_key: vec![],
         include_tx_body: true,
+        telemetry: None,
+        profiling: None,
+        light_node: None,
+        proof_code_commitment: None,
     };
 
-    let da_service = MockDaService::new(address);
-
     let ledger_db = LedgerDB::with_path(path).unwrap();
 
     let stf = HashStf::<MockValidityCond>::new();
@@ -109,7 +220,7 @@ fn initialize_runner(
         // Should be ZkStorage, but we don't need it for this test
         storage_manager.create_finalized_storage().unwrap(),
         1,
-        rollup_config.prover_service,
+        rollup_config.prover_service.clone(),
     );
 
     StateTransitionRunner::new(
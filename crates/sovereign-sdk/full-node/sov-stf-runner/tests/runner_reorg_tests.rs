@@ -120,6 +120,8 @@ async fn runner_execution(
         sequencer_public_key: vec![0u8; 32],
         storage: StorageConfig {
             path: path.to_path_buf(),
+            min_free_disk_space_mb: None,
+            check_interval_secs: 60,
         },
         runner: RunnerConfig {
             start_height: 1,
@@ -127,6 +129,18 @@ async fn runner_execution(
                 bind_host: "127.0.0.1".to_string(),
                 bind_port: 0,
                 max_connections: 1024,
+                abi_registry_path: None,
+                contract_verification_path: None,
+                response_etags: false,
+                allowed_methods: None,
+                gas_price_oracle: None,
+                archive: true,
+                state_history_blocks: 128,
+                batch_requests_limit: 100,
+                batch_weight_limit: 1000,
+                method_weights: Default::default(),
+                index_transactions_by_address: false,
+                accept_pushed_soft_batches: false,
             },
         },
         da: MockDaConfig {
@@ -134,11 +148,18 @@ async fn runner_execution(
         },
         prover_service: ProverServiceConfig {
             aggregated_proof_block_jump: 1,
+            wrap_proofs: false,
+            proof_market: None,
+            memory_watchdog: None,
         },
         sequencer_client: None,
         sequencer_da_pub_key: vec![],
         prover_da_pub_key: vec![],
         include_tx_body: true,
+        telemetry: None,
+        profiling: None,
+        light_node: None,
+        proof_code_commitment: None,
     };
 
     let ledger_db = LedgerDB::with_path(path).unwrap();
@@ -162,7 +183,7 @@ async fn runner_execution(
         // Should be ZkStorage, but we don't need it for this test
         storage_manager.create_finalized_storage().unwrap(),
         1,
-        rollup_config.prover_service,
+        rollup_config.prover_service.clone(),
     );
 
     let mut runner: StateTransitionRunner<_, _, _, _, _, DefaultContext> =
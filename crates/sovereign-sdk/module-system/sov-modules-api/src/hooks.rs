@@ -40,6 +40,30 @@ pub enum ApplySoftConfirmationError {
         prev
     )]
     CurrentTimestampIsNotGreaterThanPrev { current: u64, prev: u64 },
+    #[error(
+        "DA block {} with hash {:?} does not continue the DA chain of the previous soft confirmation's DA block {} with hash {:?}",
+        height,
+        hash,
+        last_height,
+        last_hash
+    )]
+    DaSlotHashNotContinuous {
+        height: u64,
+        hash: [u8; 32],
+        last_height: u64,
+        last_hash: [u8; 32],
+    },
+    #[error(
+        "Soft confirmation on DA block {} signed by sequencer {:?}, expected the leader {:?}",
+        da_slot_height,
+        sequencer_pub_key,
+        leader_pub_key
+    )]
+    UnexpectedSequencer {
+        da_slot_height: u64,
+        sequencer_pub_key: Vec<u8>,
+        leader_pub_key: Vec<u8>,
+    },
 }
 
 /// Hooks that execute within the `StateTransitionFunction::apply_blob` function for each processed transaction.
@@ -121,6 +145,8 @@ pub struct HookSoftConfirmationInfo {
     pub da_slot_hash: [u8; 32],
     /// DA block transactions commitment
     pub da_slot_txs_commitment: [u8; 32],
+    /// Hash of the DA block's parent
+    pub da_slot_prev_hash: [u8; 32],
     /// Previous batch's pre state root
     pub pre_state_root: Vec<u8>,
     /// Public key of signer
@@ -129,20 +155,36 @@ pub struct HookSoftConfirmationInfo {
     pub l1_fee_rate: u64,
     /// Timestamp
     pub timestamp: u64,
+    /// Governance actions posted to the DA block, taken from its blobs
+    /// and not part of the soft confirmation
+    pub da_slot_governance: Vec<Vec<u8>>,
 }
 
-impl From<SignedSoftConfirmationBatch> for HookSoftConfirmationInfo {
-    fn from(signed_soft_confirmation_batch: SignedSoftConfirmationBatch) -> Self {
+impl HookSoftConfirmationInfo {
+    /// Creates the hook info of a soft confirmation given for the DA block whose parent is `da_slot_prev_hash`.
+    /// The parent hash is not part of the soft confirmation, it is taken from the DA block header.
+    pub fn new(
+        signed_soft_confirmation_batch: &SignedSoftConfirmationBatch,
+        da_slot_prev_hash: [u8; 32],
+    ) -> Self {
         HookSoftConfirmationInfo {
             da_slot_height: signed_soft_confirmation_batch.da_slot_height(),
             da_slot_hash: signed_soft_confirmation_batch.da_slot_hash(),
             da_slot_txs_commitment: signed_soft_confirmation_batch.da_slot_txs_commitment(),
+            da_slot_prev_hash,
             pre_state_root: signed_soft_confirmation_batch.pre_state_root(),
             pub_key: signed_soft_confirmation_batch.sequencer_pub_key().to_vec(),
             l1_fee_rate: signed_soft_confirmation_batch.l1_fee_rate(),
             timestamp: signed_soft_confirmation_batch.timestamp(),
+            da_slot_governance: vec![],
         }
     }
+
+    /// Sets the governance actions posted to the DA block.
+    pub fn with_da_slot_governance(mut self, da_slot_governance: Vec<Vec<u8>>) -> Self {
+        self.da_slot_governance = da_slot_governance;
+        self
+    }
 }
 
 impl From<HookSoftConfirmationInfo> for SignedSoftConfirmationBatch {
@@ -173,6 +215,11 @@ impl HookSoftConfirmationInfo {
         self.da_slot_txs_commitment
     }
 
+    /// Hash of the DA block's parent
+    pub fn da_slot_prev_hash(&self) -> [u8; 32] {
+        self.da_slot_prev_hash
+    }
+
     /// Previous batch's pre state root
     pub fn pre_state_root(&self) -> Vec<u8> {
         self.pre_state_root.clone()
@@ -459,6 +459,30 @@ impl<C: Context> WorkingSet<C> {
         // First inner is `RevertableWriter` and second inner is actually a `Storage` instance
         self.delta.inner.inner.get_with_proof(key)
     }
+
+    /// Fetches given value at the requested version of the state and provides a proof of its
+    /// presence/absence against the root hash of that version.
+    pub fn get_with_proof_at_version(
+        &mut self,
+        key: StorageKey,
+        version: Version,
+    ) -> anyhow::Result<StorageProof<<C::Storage as Storage>::Proof>>
+    where
+        C::Storage: NativeStorage,
+    {
+        self.delta
+            .inner
+            .inner
+            .get_with_proof_at_version(key, version)
+    }
+
+    /// Returns the root hash of the state at the requested version.
+    pub fn get_root_hash(&self, version: Version) -> anyhow::Result<<C::Storage as Storage>::Root>
+    where
+        C::Storage: NativeStorage,
+    {
+        self.delta.inner.inner.get_root_hash(version)
+    }
 }
 
 impl<C: Context> StateReaderAndWriter for WorkingSet<C> {
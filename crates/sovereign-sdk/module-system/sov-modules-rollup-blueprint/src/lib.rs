@@ -9,7 +9,7 @@ use async_trait::async_trait;
 use citrea_sequencer::{CitreaSequencer, SequencerConfig};
 use const_rollup_config::TEST_PRIVATE_KEY;
 pub use runtime_rpc::*;
-use sequencer_client::SequencerClient;
+use sequencer_client::{LocalMempool, SequencerClient};
 use sov_db::ledger_db::LedgerDB;
 use sov_modules_api::runtime::capabilities::{Kernel, KernelSlotHooks};
 use sov_modules_api::{Context, DaSpec, Spec};
@@ -20,11 +20,17 @@ use sov_rollup_interface::zk::ZkvmHost;
 use sov_state::storage::NativeStorage;
 use sov_state::Storage;
 use sov_stf_runner::{
-    InitVariant, ProverService, RollupConfig, RollupProverConfig, StateTransitionRunner,
+    load_or_create_node_id, InitVariant, ProfilingServer, ProverService, RollupConfig,
+    RollupProverConfig, RpcConfig, StateTransitionRunner, StorageMonitor, TelemetryReporter,
 };
 use tokio::sync::oneshot;
+use tracing::{info, warn};
 pub use wallet::*;
 
+/// Max number of soft batches rolled back at the tail of the ledger on startup.
+/// A crash only leaves the few soft batches whose state wasn't finalized yet behind.
+const MAX_SOFT_BATCH_TAIL_ROLLBACK: u64 = 100;
+
 /// This trait defines how to crate all the necessary dependencies required by a rollup.
 #[async_trait]
 pub trait RollupBlueprint: Sized + Send + Sync {
@@ -71,12 +77,15 @@ pub trait RollupBlueprint: Sized + Send + Sync {
     fn new() -> Self;
 
     /// Creates RPC methods for the rollup.
+    /// Full nodes get a client of the sequencer, the sequencer node gets its own mempool.
     fn create_rpc_methods(
         &self,
         storage: &<Self::NativeContext as Spec>::Storage,
         ledger_db: &LedgerDB,
         da_service: &Self::DaService,
         sequencer_client: Option<SequencerClient>,
+        local_mempool: Option<LocalMempool>,
+        rpc_config: &RpcConfig,
     ) -> Result<jsonrpsee::RpcModule<()>, anyhow::Error>;
 
     /// Creates GenesisConfig from genesis files.
@@ -148,11 +157,38 @@ pub trait RollupBlueprint: Sized + Send + Sync {
     {
         let da_service = self.create_da_service(&rollup_config).await;
 
+        self.create_new_sequencer_with_da_service(
+            da_service,
+            runtime_genesis_paths,
+            kernel_genesis_config,
+            rollup_config,
+            sequencer_config,
+        )
+    }
+
+    /// Creates a new sequencer using the given DA service instead of creating one from the config.
+    fn create_new_sequencer_with_da_service(
+        &self,
+        da_service: Self::DaService,
+        runtime_genesis_paths: &<Self::NativeRuntime as RuntimeTrait<
+            Self::NativeContext,
+            Self::DaSpec,
+        >>::GenesisPaths,
+        kernel_genesis_config: <Self::NativeKernel as Kernel<Self::NativeContext, Self::DaSpec>>::GenesisConfig,
+        rollup_config: RollupConfig<Self::DaConfig>,
+        sequencer_config: SequencerConfig,
+    ) -> Result<Sequencer<Self>, anyhow::Error>
+    where
+        <Self::NativeContext as Spec>::Storage: NativeStorage,
+    {
         // TODO: Double check what kind of storage needed here.
         // Maybe whole "prev_root" can be initialized inside runner
         // Getting block here, so prover_service doesn't have to be `Send`
 
         let ledger_db = self.create_ledger_db(&rollup_config);
+        let disk_watchdog = StorageMonitor::new(&rollup_config.storage, ledger_db.clone()).spawn();
+        start_telemetry(&rollup_config, "sequencer", &ledger_db)?;
+        start_profiling(&rollup_config)?;
         let genesis_config = self.create_genesis_config(
             runtime_genesis_paths,
             kernel_genesis_config,
@@ -167,10 +203,6 @@ pub trait RollupBlueprint: Sized + Send + Sync {
             .map(|(number, _)| prover_storage.get_root_hash(number.0 + 1))
             .transpose()?;
 
-        // TODO(https://github.com/Sovereign-Labs/sovereign-sdk/issues/1218)
-        let rpc_methods =
-            self.create_rpc_methods(&prover_storage, &ledger_db, &da_service, None)?;
-
         let native_stf = StfBlueprint::new();
 
         let genesis_root = prover_storage.get_root_hash(1);
@@ -183,23 +215,36 @@ pub trait RollupBlueprint: Sized + Send + Sync {
             },
         };
 
+        let rpc_config = rollup_config.runner.rpc_config.clone();
+
         let seq =
             CitreaSequencer::new(
-                da_service,
+                da_service.clone(),
                 <<<Self as RollupBlueprint>::NativeContext as Spec>::PrivateKey as TryFrom<
                     &[u8],
                 >>::try_from(hex::decode(TEST_PRIVATE_KEY).unwrap().as_slice())
                 .unwrap(),
-                prover_storage,
+                prover_storage.clone(),
                 sequencer_config,
                 native_stf,
                 storage_manager,
                 init_variant,
                 rollup_config.sequencer_public_key,
-                ledger_db,
+                ledger_db.clone(),
                 rollup_config.runner,
             )
-            .unwrap();
+            .unwrap()
+            .with_disk_watchdog(disk_watchdog);
+
+        // TODO(https://github.com/Sovereign-Labs/sovereign-sdk/issues/1218)
+        let rpc_methods = self.create_rpc_methods(
+            &prover_storage,
+            &ledger_db,
+            &da_service,
+            None,
+            Some(seq.local_mempool()),
+            &rpc_config,
+        )?;
 
         Ok(Sequencer {
             runner: seq,
@@ -236,7 +281,50 @@ pub trait RollupBlueprint: Sized + Send + Sync {
             false => None,
         };
 
+        self.create_new_rollup_with_services(
+            da_service,
+            prover_service,
+            runtime_genesis_paths,
+            kernel_genesis_config,
+            rollup_config,
+        )
+    }
+
+    /// Creates a new rollup using the given DA and prover services instead of creating them from the config.
+    /// The node runs in prover mode if a prover service is given.
+    fn create_new_rollup_with_services(
+        &self,
+        da_service: Self::DaService,
+        mut prover_service: Option<Self::ProverService>,
+        runtime_genesis_paths: &<Self::NativeRuntime as RuntimeTrait<
+            Self::NativeContext,
+            Self::DaSpec,
+        >>::GenesisPaths,
+        kernel_genesis_config: <Self::NativeKernel as Kernel<Self::NativeContext, Self::DaSpec>>::GenesisConfig,
+        rollup_config: RollupConfig<Self::DaConfig>,
+    ) -> Result<Rollup<Self>, anyhow::Error>
+    where
+        <Self::NativeContext as Spec>::Storage: NativeStorage,
+    {
+        let is_prover = prover_service.is_some();
+        if is_prover && rollup_config.light_node.is_some() {
+            anyhow::bail!("A prover can't run as a light node");
+        }
+
         let ledger_db = self.create_ledger_db(&rollup_config);
+        if let Some(prover_service) = prover_service.as_mut() {
+            prover_service.set_ledger_db(ledger_db.clone());
+        }
+        let disk_watchdog = StorageMonitor::new(&rollup_config.storage, ledger_db.clone()).spawn();
+        let mode = if is_prover {
+            "prover"
+        } else if rollup_config.light_node.is_some() {
+            "light-node"
+        } else {
+            "full-node"
+        };
+        start_telemetry(&rollup_config, mode, &ledger_db)?;
+        start_profiling(&rollup_config)?;
         let genesis_config = self.create_genesis_config(
             runtime_genesis_paths,
             kernel_genesis_config,
@@ -246,23 +334,51 @@ pub trait RollupBlueprint: Sized + Send + Sync {
         let mut storage_manager = self.create_storage_manager(&rollup_config)?;
         let prover_storage = storage_manager.create_finalized_storage()?;
 
+        // Soft batches are committed to the ledger before their state is finalized,
+        // so the ledger's tail may be ahead of the state after a crash.
+        // Roll it back to the last soft batch whose state is on disk and re-sync the rest.
+        let removed_soft_batches = ledger_db.recover_soft_batch_tail(
+            MAX_SOFT_BATCH_TAIL_ROLLBACK,
+            |number, soft_batch| {
+                prover_storage
+                    .get_root_hash(number.0 + 1)
+                    .is_ok_and(|root| root.as_ref() == soft_batch.post_state_root.as_slice())
+            },
+        )?;
+        if removed_soft_batches > 0 {
+            warn!(
+                "Rolled back {} inconsistent soft batches at the tail of the ledger, they will be synced again",
+                removed_soft_batches
+            );
+        }
+
         let prev_root = ledger_db
             .get_head_soft_batch()?
             .map(|(number, _)| prover_storage.get_root_hash(number.0 + 1))
             .transpose()?;
 
         // if node does not have a sequencer client, then it is a sequencer
-        let sequencer_client = rollup_config
-            .sequencer_client
-            .map(|s| SequencerClient::new(s.url));
+        let sequencer_client = rollup_config.sequencer_client.map(|s| {
+            SequencerClient::new(s.url)
+                .with_compact_relay(s.compact_relay)
+                .with_receipt_streaming(s.stream_receipts)
+                .with_read_delegation(s.delegate_unsynced_reads)
+        });
 
         // TODO(https://github.com/Sovereign-Labs/sovereign-sdk/issues/1218)
-        let rpc_methods = self.create_rpc_methods(
+        let mut rpc_methods = self.create_rpc_methods(
             &prover_storage,
             &ledger_db,
             &da_service,
             sequencer_client.clone(),
+            None,
+            &rollup_config.runner.rpc_config,
         )?;
+        if is_prover {
+            rpc_methods.merge(sov_ledger_rpc::server::prover_rpc_module(
+                ledger_db.clone(),
+            )?)?;
+        }
 
         let native_stf = StfBlueprint::new();
 
@@ -289,7 +405,10 @@ pub trait RollupBlueprint: Sized + Send + Sync {
             rollup_config.sequencer_da_pub_key,
             rollup_config.prover_da_pub_key,
             rollup_config.include_tx_body,
-        )?;
+        )?
+        .with_disk_watchdog(disk_watchdog)
+        .with_light_node(rollup_config.light_node)
+        .with_proof_code_commitment(rollup_config.proof_code_commitment);
 
         Ok(Rollup {
             runner,
@@ -299,6 +418,28 @@ pub trait RollupBlueprint: Sized + Send + Sync {
     }
 }
 
+/// Loads the id of the node, and starts reporting telemetry if the node opted in.
+fn start_telemetry<DaConfig>(
+    rollup_config: &RollupConfig<DaConfig>,
+    mode: &'static str,
+    ledger_db: &LedgerDB,
+) -> Result<(), anyhow::Error> {
+    let node_id = load_or_create_node_id(&rollup_config.storage.path)?;
+    info!("Node id: {}", node_id);
+    if let Some(telemetry) = &rollup_config.telemetry {
+        TelemetryReporter::new(telemetry.clone(), node_id, mode, ledger_db.clone()).spawn();
+    }
+    Ok(())
+}
+
+/// Starts the profiling endpoint if the node enabled it.
+fn start_profiling<DaConfig>(rollup_config: &RollupConfig<DaConfig>) -> Result<(), anyhow::Error> {
+    if let Some(profiling) = &rollup_config.profiling {
+        ProfilingServer::new(profiling.clone()).spawn()?;
+    }
+    Ok(())
+}
+
 /// Sequencer stf runner
 pub struct Sequencer<S: RollupBlueprint> {
     /// The State Transition Runner of Sequencer.
@@ -373,6 +514,8 @@ impl<S: RollupBlueprint> Rollup<S> {
         runner.start_rpc_server(self.rpc_methods, channel).await;
         if self.is_prover {
             runner.run_prover_process().await?;
+        } else if runner.is_light_node() {
+            runner.run_light_node_process().await?;
         } else {
             runner.run_in_process().await?;
         }
@@ -9,7 +9,7 @@ mod tx_verifier;
 use std::marker::PhantomData;
 
 pub use batch::Batch;
-use borsh::BorshSerialize;
+use borsh::{BorshDeserialize, BorshSerialize};
 use sov_modules_api::da::BlockHeaderTrait;
 use sov_modules_api::hooks::{
     ApplyBlobHooks, ApplySoftConfirmationError, ApplySoftConfirmationHooks, FinalizeHook,
@@ -20,11 +20,13 @@ use sov_modules_api::{
     BasicAddress, BlobReaderTrait, Context, DaSpec, DispatchCall, Genesis, Signature, Spec,
     StateCheckpoint, UnsignedSoftConfirmationBatch, WorkingSet, Zkvm,
 };
+use sov_rollup_interface::da::{DaData, StateDiff};
+use sov_rollup_interface::maybestd::RefCount;
 use sov_rollup_interface::soft_confirmation::SignedSoftConfirmationBatch;
 pub use sov_rollup_interface::stf::{BatchReceipt, TransactionReceipt};
 use sov_rollup_interface::stf::{SlotResult, StateTransitionFunction};
-use sov_state::storage::KernelWorkingSet;
-use sov_state::Storage;
+use sov_state::storage::{CacheKey, KernelWorkingSet, StorageValue};
+use sov_state::{OrderedReadsAndWrites, Storage};
 #[cfg(all(target_os = "zkvm", feature = "bench"))]
 use sov_zk_cycle_macros::cycle_tracker;
 pub use stf_blueprint::StfBlueprint;
@@ -130,7 +132,7 @@ pub enum SlashingReason {
 pub trait StfBlueprintTrait<C: Context, Da: DaSpec, Vm: Zkvm>:
     StateTransitionFunction<Vm, Da>
 {
-    /// Begin a soft batch
+    /// Begin a soft batch, `slot_blobs` are the relevant blobs of the DA block at `slot_header`
     fn begin_soft_batch(
         &self,
         sequencer_public_key: &[u8],
@@ -138,6 +140,7 @@ pub trait StfBlueprintTrait<C: Context, Da: DaSpec, Vm: Zkvm>:
         pre_state: Self::PreState,
         witness: <<C as Spec>::Storage as Storage>::Witness,
         slot_header: &<Da as DaSpec>::BlockHeader,
+        slot_blobs: &mut [<Da as DaSpec>::BlobTransaction],
         soft_batch: &mut SignedSoftConfirmationBatch,
     ) -> (Result<(), ApplySoftConfirmationError>, WorkingSet<C>);
 
@@ -188,16 +191,20 @@ where
         pre_state: <C>::Storage,
         witness: <<C as Spec>::Storage as Storage>::Witness,
         slot_header: &<Da as DaSpec>::BlockHeader,
+        slot_blobs: &mut [<Da as DaSpec>::BlobTransaction],
         soft_batch: &mut SignedSoftConfirmationBatch,
     ) -> (Result<(), ApplySoftConfirmationError>, WorkingSet<C>) {
         debug!("Applying soft batch in STF Blueprint");
 
         // check if soft confirmation is coming from our sequencer
-        assert_eq!(
-            soft_batch.sequencer_pub_key(),
-            sequencer_public_key,
-            "Sequencer public key must match"
-        );
+        // if sequencers take turns, the rule enforcer checks it comes from the leader instead
+        if !cfg!(feature = "multi-sequencer") {
+            assert_eq!(
+                soft_batch.sequencer_pub_key(),
+                sequencer_public_key,
+                "Sequencer public key must match"
+            );
+        }
 
         // then verify da hashes match
         assert_eq!(
@@ -220,9 +227,25 @@ where
             "pre state roots must match"
         );
 
+        // governance actions posted to the DA block are applied whether or not the sequencer
+        // includes them. In the zkVM the blobs are checked against the transactions commitment
+        // of the DA block by the DA verifier, nodes take them from their DA service.
+        let da_slot_governance = slot_blobs
+            .iter_mut()
+            .filter_map(|blob| match DaData::try_from_slice(blob.full_data()) {
+                Ok(DaData::Governance(action)) => Some(action),
+                _ => None,
+            })
+            .collect();
+
         let checkpoint = StateCheckpoint::with_witness(pre_state, witness);
 
-        self.begin_soft_confirmation_inner(checkpoint, soft_batch)
+        self.begin_soft_confirmation_inner(
+            checkpoint,
+            slot_header.prev_hash().into(),
+            da_slot_governance,
+            soft_batch,
+        )
     }
 
     fn apply_soft_batch_txs(
@@ -241,8 +264,12 @@ where
         batch_workspace: WorkingSet<C>,
     ) -> (BatchReceipt<(), TxEffect>, StateCheckpoint<C>) {
         // verify signature
+        let signer = match cfg!(feature = "multi-sequencer") {
+            true => soft_batch.sequencer_pub_key(),
+            false => sequencer_public_key,
+        };
         assert!(
-            verify_soft_batch_signature::<C>(soft_batch, sequencer_public_key).is_ok(),
+            verify_soft_batch_signature::<C>(soft_batch, signer).is_ok(),
             "Signature verification must succeed"
         );
 
@@ -530,6 +557,7 @@ where
         witness: Self::Witness,
         slot_header: &<Da as DaSpec>::BlockHeader,
         _validity_condition: &<Da as DaSpec>::ValidityCondition,
+        slot_blobs: &mut [<Da as DaSpec>::BlobTransaction],
         soft_batch: &mut SignedSoftConfirmationBatch,
     ) -> SlotResult<
         Self::StateRoot,
@@ -544,6 +572,7 @@ where
             pre_state.clone(),
             witness,
             slot_header,
+            slot_blobs,
             soft_batch,
         ) {
             (Ok(()), batch_workspace) => {
@@ -574,6 +603,37 @@ where
             }
         }
     }
+
+    fn apply_state_diff(
+        &self,
+        pre_state: Self::PreState,
+        state_diff: StateDiff,
+    ) -> (Self::StateRoot, Self::ChangeSet) {
+        let ordered_writes = state_diff
+            .into_iter()
+            .map(|(key, value)| {
+                (
+                    CacheKey {
+                        key: RefCount::new(key),
+                    },
+                    value.map(|value| StorageValue::from(value).into_cache_value()),
+                )
+            })
+            .collect();
+        let state_accesses = OrderedReadsAndWrites {
+            ordered_reads: vec![],
+            ordered_writes,
+        };
+
+        let (root_hash, state_update) = pre_state
+            .compute_state_update(state_accesses, &Default::default())
+            .expect("Storage update must succeed");
+
+        // Accessory state is not part of the diff
+        pre_state.commit(&state_update, &OrderedReadsAndWrites::default());
+
+        (root_hash, pre_state)
+    }
 }
 
 fn verify_soft_batch_signature<C: Context>(
@@ -196,6 +196,8 @@ where
     pub fn begin_soft_confirmation_inner(
         &self,
         checkpoint: StateCheckpoint<C>,
+        da_slot_prev_hash: [u8; 32],
+        da_slot_governance: Vec<Vec<u8>>,
         soft_batch: &mut SignedSoftConfirmationBatch,
     ) -> (Result<(), ApplySoftConfirmationError>, WorkingSet<C>) {
         debug!(
@@ -208,7 +210,8 @@ where
 
         // ApplySoftConfirmationHook: begin
         if let Err(e) = self.runtime.begin_soft_confirmation_hook(
-            &mut HookSoftConfirmationInfo::from(soft_batch.clone()),
+            &mut HookSoftConfirmationInfo::new(soft_batch, da_slot_prev_hash)
+                .with_da_slot_governance(da_slot_governance),
             &mut batch_workspace,
         ) {
             error!(
@@ -264,9 +267,16 @@ where
     pub(crate) fn _apply_soft_confirmation_inner(
         &self,
         checkpoint: StateCheckpoint<C>,
+        da_slot_prev_hash: [u8; 32],
+        da_slot_governance: Vec<Vec<u8>>,
         soft_batch: &mut SignedSoftConfirmationBatch,
     ) -> (ApplySoftConfirmationResult, StateCheckpoint<C>) {
-        match self.begin_soft_confirmation_inner(checkpoint, soft_batch) {
+        match self.begin_soft_confirmation_inner(
+            checkpoint,
+            da_slot_prev_hash,
+            da_slot_governance,
+            soft_batch,
+        ) {
             (Ok(()), batch_workspace) => {
                 // TODO: wait for txs here, apply_sov_txs can be called multiple times
                 let (batch_workspace, tx_receipts) =
@@ -219,18 +219,23 @@ impl<S: MerkleProofSpec, Q: QueryManager> Storage for ProverStorage<S, Q> {
 
 impl<S: MerkleProofSpec, Q: QueryManager> NativeStorage for ProverStorage<S, Q> {
     fn get_with_proof(&self, key: StorageKey) -> StorageProof<Self::Proof> {
+        self.get_with_proof_at_version(key, self.db.get_next_version() - 1)
+            .unwrap()
+    }
+
+    fn get_with_proof_at_version(
+        &self,
+        key: StorageKey,
+        version: Version,
+    ) -> anyhow::Result<StorageProof<Self::Proof>> {
         let merkle = JellyfishMerkleTree::<StateDB<Q>, S::Hasher>::new(&self.db);
-        let (val_opt, proof) = merkle
-            .get_with_proof(
-                KeyHash::with::<S::Hasher>(key.as_ref()),
-                self.db.get_next_version() - 1,
-            )
-            .unwrap();
-        StorageProof {
+        let (val_opt, proof) =
+            merkle.get_with_proof(KeyHash::with::<S::Hasher>(key.as_ref()), version)?;
+        Ok(StorageProof {
             key,
             value: val_opt.map(StorageValue::from),
             proof,
-        }
+        })
     }
 
     fn get_root_hash(&self, version: Version) -> anyhow::Result<jmt::RootHash> {
@@ -7,6 +7,7 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
+use crate::maybestd::string::String;
 use crate::maybestd::vec::Vec;
 #[cfg(feature = "native")]
 use crate::stf::Event;
@@ -268,6 +269,147 @@ pub enum SoftConfirmationStatus {
     Proven,
 }
 
+/// The mode the prover ran the guest in.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ProvingMode {
+    /// Proving was skipped.
+    Skip,
+    /// The guest logic was run natively, without the zkVM.
+    Simulate,
+    /// The guest was executed in the zkVM without creating a proof.
+    Execute,
+    /// The guest was executed in the zkVM and proven.
+    Prove,
+}
+
+/// Details of a proving session for a DA slot, persisted by the prover so that
+/// executions of the same slot in different modes can be compared.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProofDetails {
+    /// The hash of the proven DA slot.
+    #[serde(with = "utils::rpc_hex")]
+    pub da_slot_hash: [u8; 32],
+    /// The mode the guest was run in.
+    pub mode: ProvingMode,
+    /// The serialized journal committed by the guest. Empty if the zkVM was not run.
+    #[serde(with = "utils::rpc_hex")]
+    pub journal: Vec<u8>,
+    /// Number of cycles of the guest execution, if reported by the zkVM.
+    pub cycle_count: Option<u64>,
+    /// What the guest wrote to its stdout and stderr, truncated to 64 KiB.
+    /// Empty if the zkVM was not run.
+    pub logs: String,
+    /// Wall clock duration of the session in milliseconds.
+    pub duration_ms: u64,
+    /// The error the session failed with, if any.
+    pub error: Option<String>,
+}
+
+/// A blob posted to the DA layer, identified by the DA slot it landed in.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DaActivityRecord {
+    /// The height of the DA slot.
+    pub da_slot_height: u64,
+    /// The hash of the DA slot.
+    #[serde(with = "utils::rpc_hex")]
+    pub da_slot_hash: [u8; 32],
+}
+
+/// Blobs posted to the DA layer by the sequencer and the prover, as observed by a full node
+/// scanning the DA blocks its soft confirmations refer to. Lets third parties monitor
+/// whether commitments and proofs keep landing on the DA layer.
+#[derive(
+    Debug, Default, PartialEq, Eq, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct SequencerDaActivity {
+    /// The height of the last DA slot scanned, if any.
+    pub last_scanned_da_height: Option<u64>,
+    /// Number of sequencer commitments found.
+    pub commitment_count: u64,
+    /// The last sequencer commitment found.
+    pub last_commitment: Option<DaActivityRecord>,
+    /// Number of ZK proofs found.
+    pub proof_count: u64,
+    /// The last ZK proof found.
+    pub last_proof: Option<DaActivityRecord>,
+    /// Number of blobs from the sequencer or the prover that could not be decoded.
+    pub malformed_blob_count: u64,
+    /// The last blob from the sequencer or the prover that could not be decoded.
+    pub last_malformed_blob: Option<DaActivityRecord>,
+}
+
+/// A range of L2 heights covered by a ZK proof found on the DA layer.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProvenRange {
+    /// The first L2 height covered by the proof.
+    pub l2_start_height: u64,
+    /// The last L2 height covered by the proof.
+    pub l2_end_height: u64,
+    /// The height of the DA slot the proof landed in.
+    pub da_slot_height: u64,
+    /// The hash of the DA slot the proof landed in.
+    #[serde(with = "utils::rpc_hex")]
+    pub da_slot_hash: [u8; 32],
+    /// The hash of the DA transaction carrying the proof.
+    #[serde(with = "utils::rpc_hex")]
+    pub da_txid: [u8; 32],
+}
+
+/// The proven ranges of L2 heights intersecting a queried range, ordered by L2 height.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProofCoverage {
+    /// The highest L2 height covered by a proof, if any.
+    pub latest_proven_height: Option<u64>,
+    /// The proven ranges intersecting the queried range.
+    pub ranges: Vec<ProvenRange>,
+}
+
+/// The L1 fee rates of the soft confirmations on a DA slot, as observed by the node.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1FeeRateSnapshot {
+    /// The height of the DA slot.
+    pub l1_height: u64,
+    /// The lowest L1 fee rate of the soft confirmations on the slot.
+    pub min_l1_fee_rate: u64,
+    /// The highest L1 fee rate of the soft confirmations on the slot.
+    pub max_l1_fee_rate: u64,
+    /// The L1 fee rate of the last soft confirmation on the slot.
+    pub last_l1_fee_rate: u64,
+    /// Number of soft confirmations on the slot.
+    pub soft_confirmation_count: u64,
+}
+
+/// Sync progress of a full node or prover, as recorded by its runner.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncStatus {
+    /// The L2 height the node started syncing from.
+    pub starting_l2_height: u64,
+    /// The last L2 height the node processed.
+    pub current_l2_height: u64,
+    /// The L2 height the node is syncing to. The sequencer head on full nodes. On provers,
+    /// the current height once the finalized DA head is scanned, since they can't go further.
+    pub highest_l2_height: u64,
+    /// The DA height of the last L2 block on full nodes, the last scanned one on provers.
+    pub current_l1_height: u64,
+    /// The last finalized DA height.
+    pub highest_l1_height: u64,
+}
+
+impl SyncStatus {
+    /// Returns true if the node is behind the height it is syncing to.
+    pub fn is_syncing(&self) -> bool {
+        self.current_l2_height < self.highest_l2_height
+    }
+}
+
 /// A LedgerRpcProvider provides a way to query the ledger for information about slots, batches, transactions, and events.
 #[cfg(feature = "native")]
 pub trait LedgerRpcProvider {
@@ -416,6 +558,38 @@ pub trait LedgerRpcProvider {
         soft_batch_receipt: u64,
     ) -> Result<SoftConfirmationStatus, anyhow::Error>;
 
+    /// Takes a range of L2 heights `start..=end` and returns the heights of the soft batches
+    /// with the given soft confirmation status, in order.
+    fn get_soft_batch_heights_by_status(
+        &self,
+        status: SoftConfirmationStatus,
+        start: u64,
+        end: u64,
+    ) -> Result<Vec<u64>, anyhow::Error>;
+
+    /// Get the details of the proving session for the DA slot with the given hash.
+    fn get_proof_details(
+        &self,
+        da_slot_hash: &[u8; 32],
+    ) -> Result<Option<ProofDetails>, anyhow::Error>;
+
+    /// Get the DA activity of the sequencer and the prover observed by the node.
+    fn get_sequencer_da_activity(&self) -> Result<SequencerDaActivity, anyhow::Error>;
+
+    /// Get the highest L2 height covered by a ZK proof verified by the node, if any.
+    fn get_latest_proven_height(&self) -> Result<Option<u64>, anyhow::Error>;
+
+    /// Get the ranges of L2 heights proven by verified ZK proofs intersecting `start..=end`.
+    fn get_proof_coverage(&self, start: u64, end: u64) -> Result<ProofCoverage, anyhow::Error>;
+
+    /// Get the L1 fee rates observed on the DA slots at heights `start..=end`, ordered by height.
+    /// Slots without soft confirmations are skipped.
+    fn get_l1_fee_history(
+        &self,
+        start: u64,
+        end: u64,
+    ) -> Result<Vec<L1FeeRateSnapshot>, anyhow::Error>;
+
     /// Get a notification each time a slot is processed
     fn subscribe_slots(&self) -> Result<tokio::sync::broadcast::Receiver<u64>, anyhow::Error>;
 }
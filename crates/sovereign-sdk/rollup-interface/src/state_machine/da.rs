@@ -24,7 +24,29 @@ pub struct SequencerCommitment {
 /// ZK proof of execution of a set of soft confirmations
 #[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, BorshDeserialize, BorshSerialize)]
 pub struct BatchProof {
-    // TODO - implement this
+    /// The first L2 height covered by the proof
+    pub l2_start_height: u64,
+    /// The last L2 height covered by the proof
+    pub l2_end_height: u64,
+    // TODO - add the proof itself
+}
+
+/// Writes to the state made by a range of soft confirmations, the last write of each key only.
+/// A `None` value deletes the key.
+pub type StateDiff = Vec<(Vec<u8>, Option<Vec<u8>>)>;
+
+/// ZK proof of execution of a set of soft confirmations, with the proof itself and
+/// the state diff it proves, so that nodes can follow the proven state without executing
+/// the soft confirmations
+#[derive(Debug, Clone, Serialize, Deserialize, Eq, PartialEq, BorshDeserialize, BorshSerialize)]
+pub struct VerifiableBatchProof {
+    /// The L2 range covered by the proof
+    pub batch_proof: BatchProof,
+    /// The serialized proof, whose output is the state transition of the range
+    pub proof: Vec<u8>,
+    /// The writes to the state made by the range. Applying them to the initial state root
+    /// of the proof must give its final state root.
+    pub state_diff: StateDiff,
 }
 
 /// Data written to DA can only be one of these two types
@@ -35,6 +57,11 @@ pub enum DaData {
     SequencerCommitment(SequencerCommitment),
     /// Or a zk proof and state diff
     ZKProof(BatchProof),
+    /// Or a governance action of the rollup, opaque to the DA layer.
+    /// Applied by the rollup from the DA block, which verifies it.
+    Governance(Vec<u8>),
+    /// Or a zk proof with the proof itself and the state diff it proves
+    VerifiableZKProof(VerifiableBatchProof),
 }
 
 /// A specification for the types used by a DA layer.
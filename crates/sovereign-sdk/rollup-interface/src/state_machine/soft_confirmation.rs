@@ -181,6 +181,11 @@ impl SignedSoftConfirmationBatch {
         self.da_slot_hash = da_slot_hash;
     }
 
+    /// Sets da slot height
+    pub fn set_da_slot_height(&mut self, da_slot_height: u64) {
+        self.da_slot_height = da_slot_height;
+    }
+
     /// Sequencer block timestamp
     pub fn timestamp(&self) -> u64 {
         self.timestamp
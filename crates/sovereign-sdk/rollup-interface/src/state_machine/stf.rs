@@ -10,7 +10,7 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 
-use crate::da::DaSpec;
+use crate::da::{DaSpec, StateDiff};
 use crate::maybestd::vec::Vec;
 use crate::soft_confirmation::SignedSoftConfirmationBatch;
 use crate::zk::{ValidityCondition, Zkvm};
@@ -206,6 +206,9 @@ pub trait StateTransitionFunction<Vm: Zkvm, Da: DaSpec> {
     /// The concrete blob type is defined by the DA layer implementation,
     /// which is why we use a generic here instead of an associated type.
     ///
+    /// `slot_blobs` are the relevant blobs of the DA block the soft confirmation is given for,
+    /// which carry the data the rollup takes from DA regardless of the sequencer, like governance actions.
+    ///
     /// Commits state changes to the database
     #[allow(clippy::type_complexity)]
     #[allow(clippy::too_many_arguments)]
@@ -217,6 +220,7 @@ pub trait StateTransitionFunction<Vm: Zkvm, Da: DaSpec> {
         witness: Self::Witness,
         slot_header: &Da::BlockHeader,
         validity_condition: &Da::ValidityCondition,
+        slot_blobs: &mut [Da::BlobTransaction],
         soft_batch: &mut SignedSoftConfirmationBatch,
     ) -> SlotResult<
         Self::StateRoot,
@@ -225,6 +229,18 @@ pub trait StateTransitionFunction<Vm: Zkvm, Da: DaSpec> {
         Self::TxReceiptContents,
         Self::Witness,
     >;
+
+    /// Applies the writes of a range of soft confirmations to the state without executing them,
+    /// and returns the resulting state root and changeset.
+    /// Used by light nodes following the state proven by ZK proofs, which check the returned root
+    /// against the final state root of the proof.
+    ///
+    /// Commits state changes to the database
+    fn apply_state_diff(
+        &self,
+        pre_state: Self::PreState,
+        state_diff: StateDiff,
+    ) -> (Self::StateRoot, Self::ChangeSet);
 }
 
 /// A key-value pair representing a change to the rollup state
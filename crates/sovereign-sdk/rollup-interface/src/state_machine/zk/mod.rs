@@ -6,6 +6,7 @@
 //! For a detailed example showing how to implement these traits, see the
 //! [risc0 adapter](https://github.com/Sovereign-Labs/sovereign-sdk/tree/main/adapters/risc0)
 //! maintained by the Sovereign Labs team.
+use alloc::string::String;
 use alloc::vec::Vec;
 use core::fmt::Debug;
 
@@ -25,6 +26,17 @@ pub enum Proof {
     Full(Vec<u8>),
 }
 
+/// Details of a guest execution, reported by [`ZkvmHost::run_with_details`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GuestExecutionDetails {
+    /// The serialized journal (public output) committed by the guest.
+    pub journal: Vec<u8>,
+    /// Number of cycles the execution took, if the zkVM reports it.
+    pub cycle_count: Option<u64>,
+    /// What the guest wrote to its stdout and stderr, truncated by the zkVM host.
+    pub logs: String,
+}
+
 /// A trait implemented by the prover ("host") of a zkVM program.
 pub trait ZkvmHost: Zkvm + Clone {
     /// The associated guest type
@@ -45,6 +57,46 @@ pub trait ZkvmHost: Zkvm + Clone {
     /// with some mild performance overhead and is not as easy to debug as [`simulate_with_hints`](ZkvmHost::simulate_with_hints).
     fn run(&mut self, with_proof: bool) -> Result<Proof, anyhow::Error>;
 
+    /// Same as [`run`](ZkvmHost::run), but also returns the journal, the cycle count
+    /// and the logs of the execution.
+    ///
+    /// The default implementation reports the public input as the journal, no cycle count
+    /// and no logs.
+    fn run_with_details(
+        &mut self,
+        with_proof: bool,
+    ) -> Result<(Proof, GuestExecutionDetails), anyhow::Error> {
+        let proof = self.run(with_proof)?;
+        let journal = match &proof {
+            Proof::PublicInput(data) => data.clone(),
+            Proof::Full(_) => Vec::new(),
+        };
+        Ok((
+            proof,
+            GuestExecutionDetails {
+                journal,
+                cycle_count: None,
+                logs: String::new(),
+            },
+        ))
+    }
+
+    /// Wraps a full proof into a SNARK which is small and cheap to verify on chain,
+    /// before it is submitted to the DA layer.
+    ///
+    /// The default implementation returns the proof unchanged, for zkVMs without a SNARK wrapper.
+    fn wrap_proof(&self, proof: Proof) -> Result<Proof, anyhow::Error> {
+        Ok(proof)
+    }
+
+    /// Returns the hints given with [`add_hint`](ZkvmHost::add_hint), serialized the way the
+    /// guest reads them, to run the guest on another machine, e.g. an outsourced prover.
+    ///
+    /// The default implementation returns `None`, for zkVMs that can't export their hints.
+    fn serialized_hints(&self) -> Option<Vec<u8>> {
+        None
+    }
+
     /// Extracts public input form the proof.
     fn extract_output<Da: DaSpec, Root: Serialize + DeserializeOwned>(
         proof: &Proof,